@@ -0,0 +1,11 @@
+mod crc;
+mod wire;
+
+pub use crc::*;
+pub use wire::*;
+
+pub mod block;
+#[cfg(feature = "encoder")]
+pub mod encoder;
+pub mod frame;
+pub mod mulaw;
@@ -0,0 +1,255 @@
+/*!
+
+## Parameter block
+
+This module implements the container for storing the set of parameters
+in flash memory or EEPROM as a fixed-size byte image.
+
+The parameters is serialized using [`Wire`] format (usually as a tuple of values)
+and protected by CRC16. The image also contains the version of parameters layout,
+so the stored parameters of old version can be converted by migration function
+when the firmware was updated.
+
+The layout of image:
+
+| Offset | Size | Field                                     |
+|--------|------|-------------------------------------------|
+| 0      | 2    | Signature _0x5042_ (little-endian)        |
+| 2      | 2    | Version (little-endian)                   |
+| 4      | 2    | Payload length _N_ (little-endian)        |
+| 6      | _N_  | Payload                                   |
+| 6 + _N_| 2    | CRC16 of header and payload               |
+
+The rest of storage slot is filled by _0xFF_ (like erased flash).
+
+*/
+
+use super::{Crc16, Wire};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The signature of parameter block
+pub const SIGNATURE: u16 = 0x5042;
+
+/// The size of block header (signature, version and length)
+pub const HEADER_SIZE: usize = 6;
+
+/// The size of block checksum
+pub const CRC_SIZE: usize = 2;
+
+/// Parameter block errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Buffer too small to hold image
+    Overflow,
+    /// Signature mismatch (storage is blank or corrupted)
+    Signature,
+    /// Checksum mismatch
+    Checksum,
+    /// Payload length mismatch
+    Length,
+    /// Unsupported version
+    Version,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Error::Overflow => "Buffer overflow",
+            Error::Signature => "Signature mismatch",
+            Error::Checksum => "Checksum mismatch",
+            Error::Length => "Payload length mismatch",
+            Error::Version => "Unsupported version",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/**
+Migration function
+
+Converts the payload of old version into actual parameters.
+Returns `None` when the version isn't supported.
+*/
+pub type Migrate<T> = fn(version: u16, payload: &[u8]) -> Option<T>;
+
+/**
+Parameter block
+
+- `T` - parameters type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct ParamBlock<T> {
+    /// Actual version
+    version: u16,
+    /// Migration function
+    migrate: Migrate<T>,
+}
+
+impl<T> ParamBlock<T>
+where
+    T: Wire,
+{
+    /// The size of image in bytes
+    pub const SIZE: usize = HEADER_SIZE + T::SIZE + CRC_SIZE;
+
+    /**
+    Create parameter block
+
+    - `version`: The actual version of parameters layout
+     */
+    pub const fn new(version: u16) -> Self {
+        Self {
+            version,
+            migrate: |_, _| None,
+        }
+    }
+
+    /// Set migration function
+    pub const fn with_migrate(mut self, migrate: Migrate<T>) -> Self {
+        self.migrate = migrate;
+        self
+    }
+
+    /// Actual version
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /**
+    Store parameters into the image
+
+    The whole buffer will be written. Returns the size of image without padding.
+     */
+    pub fn store(&self, value: &T, buf: &mut [u8]) -> Result<usize, Error> {
+        if T::SIZE > u16::MAX as usize {
+            return Err(Error::Length);
+        }
+
+        if buf.len() < Self::SIZE {
+            return Err(Error::Overflow);
+        }
+
+        SIGNATURE.write_to(buf);
+        self.version.write_to(&mut buf[2..]);
+        (T::SIZE as u16).write_to(&mut buf[4..]);
+        value.write_to(&mut buf[HEADER_SIZE..]);
+
+        let crc = Crc16::checksum(&buf[..HEADER_SIZE + T::SIZE]);
+        crc.write_to(&mut buf[HEADER_SIZE + T::SIZE..]);
+
+        for byte in &mut buf[Self::SIZE..] {
+            *byte = 0xff;
+        }
+
+        Ok(Self::SIZE)
+    }
+
+    /**
+    Load parameters from the image
+
+    The migration function will be called when the version of image doesn't match.
+     */
+    pub fn load(&self, buf: &[u8]) -> Result<T, Error> {
+        if buf.len() < HEADER_SIZE + CRC_SIZE {
+            return Err(Error::Overflow);
+        }
+
+        if u16::read_from(buf) != SIGNATURE {
+            return Err(Error::Signature);
+        }
+
+        let version = u16::read_from(&buf[2..]);
+        let length = u16::read_from(&buf[4..]) as usize;
+
+        if buf.len() < HEADER_SIZE + length + CRC_SIZE {
+            return Err(Error::Length);
+        }
+
+        let data = &buf[..HEADER_SIZE + length];
+        if Crc16::checksum(data) != u16::read_from(&buf[HEADER_SIZE + length..]) {
+            return Err(Error::Checksum);
+        }
+
+        let payload = &data[HEADER_SIZE..];
+
+        if version != self.version {
+            return (self.migrate)(version, payload).ok_or(Error::Version);
+        }
+
+        if length != T::SIZE {
+            return Err(Error::Length);
+        }
+
+        Ok(T::read_from(payload))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Cast;
+    use typenum::{N8, P16};
+    use ufix::bin::Fix;
+
+    type G = Fix<P16, N8>;
+
+    /// Version 1: (gain)
+    type V1 = (G,);
+
+    /// Version 2: (gain, limit)
+    type V2 = (G, u16);
+
+    #[test]
+    fn block_roundtrip() {
+        let block = ParamBlock::<V2>::new(2);
+        let mut buf = [0u8; 16];
+
+        assert_eq!(ParamBlock::<V2>::SIZE, 12);
+        assert_eq!(block.store(&(G::cast(1.5), 100), &mut buf), Ok(12));
+        assert_eq!(buf[..6], [0x42, 0x50, 2, 0, 4, 0]);
+        assert_eq!(buf[12..], [0xff; 4]);
+
+        assert_eq!(block.load(&buf), Ok((G::cast(1.5), 100)));
+
+        // Corrupted image
+        buf[7] ^= 0x10;
+        assert_eq!(block.load(&buf), Err(Error::Checksum));
+
+        // Blank storage
+        assert_eq!(block.load(&[0xff; 16]), Err(Error::Signature));
+
+        // Small storage
+        assert_eq!(
+            block.store(&(G::cast(1.5), 100), &mut buf[..8]),
+            Err(Error::Overflow)
+        );
+    }
+
+    #[test]
+    fn block_migrate() {
+        let old = ParamBlock::<V1>::new(1);
+        let mut buf = [0u8; 16];
+        old.store(&(G::cast(2.0),), &mut buf).unwrap();
+
+        let block = ParamBlock::<V2>::new(2);
+        assert_eq!(block.load(&buf), Err(Error::Version));
+
+        let block = block.with_migrate(|version, payload| match version {
+            1 => {
+                let (gain,) = V1::read_from(payload);
+                Some((gain, 100))
+            }
+            _ => None,
+        });
+        assert_eq!(block.load(&buf), Ok((G::cast(2.0), 100)));
+
+        // Future version
+        ParamBlock::<V1>::new(3)
+            .store(&(G::cast(2.0),), &mut buf)
+            .unwrap();
+        assert_eq!(block.load(&buf), Err(Error::Version));
+    }
+}
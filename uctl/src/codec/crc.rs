@@ -0,0 +1,87 @@
+/*!
+
+## CRC16
+
+This module implements streaming **CRC-16/CCITT-FALSE** checksum
+(polynomial _0x1021_, initial value _0xFFFF_, no reflection and final xor).
+
+The checksum is evaluated bitwise without lookup table to save the flash memory.
+
+See also [Cyclic redundancy check](https://en.wikipedia.org/wiki/Cyclic_redundancy_check).
+
+*/
+
+/// The generator polynomial
+const POLY: u16 = 0x1021;
+
+/// The initial value
+const INIT: u16 = 0xffff;
+
+/// Streaming CRC16 checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc16(u16);
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc16 {
+    /// Create new checksum
+    pub fn new() -> Self {
+        Self(INIT)
+    }
+
+    /// Update checksum using single byte
+    pub fn push(&mut self, byte: u8) {
+        let mut crc = self.0 ^ ((byte as u16) << 8);
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ POLY
+            } else {
+                crc << 1
+            };
+        }
+        self.0 = crc;
+    }
+
+    /// Update checksum using bytes
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.push(*byte);
+        }
+    }
+
+    /// Get the checksum value
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+
+    /// Evaluate checksum of bytes
+    pub fn checksum(data: &[u8]) -> u16 {
+        let mut crc = Self::new();
+        crc.update(data);
+        crc.value()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn crc16_check() {
+        assert_eq!(Crc16::checksum(b"123456789"), 0x29b1);
+        assert_eq!(Crc16::checksum(b""), 0xffff);
+    }
+
+    #[test]
+    fn crc16_streaming() {
+        let mut crc = Crc16::new();
+        crc.update(b"1234");
+        crc.push(b'5');
+        crc.update(b"6789");
+        assert_eq!(crc.value(), 0x29b1);
+    }
+}
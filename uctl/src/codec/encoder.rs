@@ -0,0 +1,471 @@
+/*!
+
+## Absolute encoder frames
+
+This module implements validation of frames of serial protocols of absolute encoders
+(like [BiSS](https://en.wikipedia.org/wiki/BiSS_interface), SSI, Tamagawa and etc.).
+
+The frame is received MSB first and packed into the word of 64 bits (right-aligned)
+with the following layout:
+
+| Field    | Size       | Description                                 |
+|----------|------------|---------------------------------------------|
+| Start    | _S_        | Start bits which should match the pattern   |
+| Position | _B_        | Single-turn position                        |
+| Status   | _F_        | Status flags (errors, warnings and etc.)    |
+| CRC      | _C_        | Checksum of position and status (optional)  |
+| Stop     | _P_        | Stop bits which should match the pattern    |
+
+The checksum [`Crc`] is evaluated bitwise with configurable width, polynomial,
+initial value and output inversion, so it suits for the bit streams as well as for bytes.
+
+The byte-oriented Tamagawa frames is decoded using [`tamagawa`].
+
+The decoded position can be converted to the [angle](crate::Cyc) or
+passed to the [absolute encoder decoder](crate::absenc) to count turns.
+
+*/
+
+use crate::{Cast, Cyc};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use typenum::Integer;
+use ufix::{bin, Digits, Exponent, Mantissa, Radix};
+
+/// Frame validation errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Start bits mismatch
+    Start,
+    /// Stop bits mismatch
+    Stop,
+    /// Frame length mismatch
+    Length,
+    /// Checksum mismatch
+    Checksum,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Error::Start => "Start bits mismatch",
+            Error::Stop => "Stop bits mismatch",
+            Error::Length => "Frame length mismatch",
+            Error::Checksum => "Checksum mismatch",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/**
+Bitwise CRC
+
+The checksum isn't reflected. The polynomial is given without the highest term.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc {
+    /// The width in bits (1..=32)
+    width: u32,
+    /// The generator polynomial
+    poly: u32,
+    /// The initial value
+    init: u32,
+    /// The final xor value
+    xorout: u32,
+}
+
+/// The CRC6 of BiSS-C (polynomial _x<sup>6</sup> + x + 1_, inverted output)
+pub const BISS_CRC6: Crc = Crc::new(6, 0x03, 0x00, 0x3f);
+
+/// The CRC8 of Tamagawa (polynomial _x<sup>8</sup> + 1_)
+pub const TAMAGAWA_CRC8: Crc = Crc::new(8, 0x01, 0x00, 0x00);
+
+impl Crc {
+    /**
+    Init CRC
+
+    - `width`: The width of checksum in bits (1..=32)
+    - `poly`: The generator polynomial without the highest term
+    - `init`: The initial value
+    - `xorout`: The value to xor with result
+     */
+    pub const fn new(width: u32, poly: u32, init: u32, xorout: u32) -> Self {
+        assert!(width > 0 && width <= 32);
+        Self {
+            width,
+            poly,
+            init,
+            xorout,
+        }
+    }
+
+    /// The width in bits
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn mask(&self) -> u32 {
+        u32::MAX >> (32 - self.width)
+    }
+
+    fn push(&self, mut crc: u32, data: u64, bits: u32) -> u32 {
+        let top = self.width - 1;
+
+        for index in (0..bits).rev() {
+            let bit = ((data >> index) & 1) as u32;
+            let feed = ((crc >> top) & 1) ^ bit;
+
+            crc = (crc << 1) & self.mask();
+            if feed != 0 {
+                crc ^= self.poly;
+            }
+        }
+        crc
+    }
+
+    /// Evaluate checksum of bits (MSB first, up to 64 bits)
+    pub fn checksum_bits(&self, data: u64, bits: u32) -> u32 {
+        (self.push(self.init, data, bits) ^ self.xorout) & self.mask()
+    }
+
+    /// Evaluate checksum of bytes
+    pub fn checksum(&self, data: &[u8]) -> u32 {
+        let crc = data
+            .iter()
+            .fold(self.init, |crc, byte| self.push(crc, *byte as u64, 8));
+        (crc ^ self.xorout) & self.mask()
+    }
+}
+
+/// Decoded position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Single-turn position in counts
+    pub position: u32,
+    /// Status flags
+    pub status: u32,
+}
+
+/**
+Frame layout
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    /// The start bits and its number
+    start: (u32, u32),
+    /// The position bits
+    bits: u32,
+    /// The status bits
+    status: u32,
+    /// The checksum (when used)
+    crc: Option<Crc>,
+    /// The stop bits and its number
+    stop: (u32, u32),
+}
+
+impl Layout {
+    /**
+    Init frame layout
+
+    - `bits`: The resolution of position in bits (1..=32)
+
+    The frame has no checksum by default.
+     */
+    pub fn new(bits: u32) -> Self {
+        let layout = Self {
+            start: (0, 0),
+            bits,
+            status: 0,
+            crc: None,
+            stop: (0, 0),
+        };
+        layout.check();
+        layout
+    }
+
+    /**
+    Set checksum
+
+    - `crc`: The checksum which follows the status
+     */
+    pub fn with_crc(mut self, crc: Crc) -> Self {
+        self.crc = Some(crc);
+        self.check();
+        self
+    }
+
+    /**
+    Set start bits
+
+    - `pattern`: The expected start bits
+    - `len`: The number of start bits
+     */
+    pub fn with_start(mut self, pattern: u32, len: u32) -> Self {
+        self.start = (pattern, len);
+        self.check();
+        self
+    }
+
+    /**
+    Set status bits
+
+    - `len`: The number of status bits which follows the position
+     */
+    pub fn with_status(mut self, len: u32) -> Self {
+        self.status = len;
+        self.check();
+        self
+    }
+
+    /**
+    Set stop bits
+
+    - `pattern`: The expected stop bits
+    - `len`: The number of stop bits
+     */
+    pub fn with_stop(mut self, pattern: u32, len: u32) -> Self {
+        self.stop = (pattern, len);
+        self.check();
+        self
+    }
+
+    fn check(&self) {
+        assert!(self.bits > 0 && self.bits <= 32);
+        assert!(self.status <= 32 && self.start.1 <= 32 && self.stop.1 <= 32);
+        if let Some(crc) = &self.crc {
+            assert!(crc.width > 0 && crc.width <= 32);
+        }
+        assert!(self.size() <= 64);
+    }
+
+    /// The width of checksum in bits
+    fn crc_width(&self) -> u32 {
+        self.crc.as_ref().map_or(0, Crc::width)
+    }
+
+    /// The length of frame in bits
+    pub fn size(&self) -> u32 {
+        self.start.1 + self.bits + self.status + self.crc_width() + self.stop.1
+    }
+
+    /// The resolution of position in bits
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /**
+    Validate frame and extract position
+
+    - `frame`: The received bits of frame (right-aligned, the earliest bit is the most significant)
+    - `len`: The number of received bits
+     */
+    pub fn decode(&self, frame: u64, len: u32) -> Result<Sample, Error> {
+        if len != self.size() {
+            return Err(Error::Length);
+        }
+
+        let field = |offset: u32, bits: u32| {
+            if bits == 0 {
+                0
+            } else {
+                ((frame >> offset) & (u64::MAX >> (64 - bits))) as u32
+            }
+        };
+
+        let mut offset = 0;
+
+        if field(offset, self.stop.1) != self.stop.0 {
+            return Err(Error::Stop);
+        }
+        offset += self.stop.1;
+
+        if let Some(crc) = &self.crc {
+            let checksum = field(offset, crc.width);
+            offset += crc.width;
+
+            let payload = self.bits + self.status;
+            if crc.checksum_bits(frame >> offset, payload) != checksum {
+                return Err(Error::Checksum);
+            }
+        }
+
+        let status = field(offset, self.status);
+        offset += self.status;
+
+        let position = field(offset, self.bits);
+        offset += self.bits;
+
+        if field(offset, self.start.1) != self.start.0 {
+            return Err(Error::Start);
+        }
+
+        Ok(Sample { position, status })
+    }
+
+    /**
+    Convert position to single-turn angle
+
+    The output type is `Cyc<bin::Fix<B, E>>` in range _0..1_.
+     */
+    pub fn angle<B, E>(&self, position: u32) -> Cyc<bin::Fix<B, E>>
+    where
+        B: Digits,
+        E: Exponent + Integer,
+        typenum::P2: Radix<B>,
+        Mantissa<typenum::P2, B>: Cast<i64>,
+    {
+        let value = position as i64;
+        let shift = -E::I32 - self.bits as i32;
+
+        Cyc(bin::Fix::new(Mantissa::<typenum::P2, B>::cast(
+            if shift >= 0 {
+                value << shift
+            } else {
+                value >> -shift
+            },
+        )))
+    }
+}
+
+/**
+Validate Tamagawa frame of absolute position request (data ID 0)
+
+The frame consists of control field, status field, three bytes of position (LSB first)
+and [CRC8](TAMAGAWA_CRC8) of preceding bytes.
+*/
+pub fn tamagawa(frame: &[u8]) -> Result<Sample, Error> {
+    if frame.len() != 6 {
+        return Err(Error::Length);
+    }
+
+    if TAMAGAWA_CRC8.checksum(&frame[..5]) != frame[5] as u32 {
+        return Err(Error::Checksum);
+    }
+
+    Ok(Sample {
+        position: u32::from_le_bytes([frame[2], frame[3], frame[4], 0]),
+        status: frame[1] as u32,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+
+    type T = bin::Fix<P32, N16>;
+
+    /// Pack BiSS-C frame: ack (0), start (1), cds (0), position, error and warning (active low), crc6
+    fn biss(position: u32, status: u32) -> u64 {
+        let payload = ((position as u64) << 2) | status as u64;
+        let crc = BISS_CRC6.checksum_bits(payload, 14);
+        (0b010 << 20) | (payload << 6) | crc as u64
+    }
+
+    #[test]
+    fn crc_check() {
+        // CRC-8/SMBUS
+        assert_eq!(Crc::new(8, 0x07, 0, 0).checksum(b"123456789"), 0xf4);
+        // CRC-16/CCITT-FALSE
+        assert_eq!(
+            Crc::new(16, 0x1021, 0xffff, 0).checksum(b"123456789"),
+            0x29b1
+        );
+        // CRC-6/G-704 is reflected, so checking the non-reflected CRC-6/CDMA2000-A instead
+        assert_eq!(Crc::new(6, 0x27, 0x3f, 0).checksum(b"123456789"), 0x0d);
+        // the bitwise and the bytewise checksums is same
+        assert_eq!(
+            BISS_CRC6.checksum_bits(0x3132, 16),
+            BISS_CRC6.checksum(b"12")
+        );
+    }
+
+    #[test]
+    fn biss_frame() {
+        let layout = Layout::new(12)
+            .with_crc(BISS_CRC6)
+            .with_start(0b010, 3)
+            .with_status(2);
+
+        assert_eq!(layout.size(), 23);
+
+        let frame = biss(0x400, 0b11);
+        let sample = layout.decode(frame, 23).unwrap();
+        assert_eq!(
+            sample,
+            Sample {
+                position: 0x400,
+                status: 0b11
+            }
+        );
+        assert_eq!(
+            layout.angle::<P32, N16>(sample.position),
+            Cyc(T::cast(0.25))
+        );
+
+        assert_eq!(layout.decode(frame, 22), Err(Error::Length));
+        // corrupted position bit
+        assert_eq!(layout.decode(frame ^ (1 << 10), 23), Err(Error::Checksum));
+        // corrupted crc bit
+        assert_eq!(layout.decode(frame ^ 1, 23), Err(Error::Checksum));
+        // missing start bit
+        assert_eq!(layout.decode(frame ^ (1 << 21), 23), Err(Error::Start));
+    }
+
+    #[test]
+    fn ssi_frame() {
+        // position, crc and stop bit (1)
+        let layout = Layout::new(10)
+            .with_crc(Crc::new(4, 0x03, 0, 0))
+            .with_stop(1, 1);
+        let crc = Crc::new(4, 0x03, 0, 0).checksum_bits(0x300, 10);
+        let frame = (0x300 << 5) | ((crc as u64) << 1) | 1;
+
+        assert_eq!(
+            layout.decode(frame, 15).map(|sample| sample.position),
+            Ok(0x300)
+        );
+        assert_eq!(layout.decode(frame ^ 1, 15), Err(Error::Stop));
+    }
+
+    #[test]
+    fn plain_frame() {
+        // SSI without checksum
+        let layout = Layout::new(13).with_status(1);
+
+        assert_eq!(layout.size(), 14);
+        assert_eq!(
+            layout.decode((0x1234 << 1) | 1, 14),
+            Ok(Sample {
+                position: 0x1234,
+                status: 1
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn crc_zero_width() {
+        Crc::new(0, 0, 0, 0);
+    }
+
+    #[test]
+    fn tamagawa_frame() {
+        let mut frame = [0x02, 0x00, 0x56, 0x34, 0x12, 0x00];
+        frame[5] = TAMAGAWA_CRC8.checksum(&frame[..5]) as u8;
+
+        assert_eq!(
+            tamagawa(&frame),
+            Ok(Sample {
+                position: 0x12_3456,
+                status: 0
+            })
+        );
+
+        frame[3] ^= 0x10;
+        assert_eq!(tamagawa(&frame), Err(Error::Checksum));
+        assert_eq!(tamagawa(&frame[..5]), Err(Error::Length));
+    }
+}
@@ -0,0 +1,304 @@
+/*!
+
+## Parameter frames
+
+This module implements tiny host-target protocol for uploading parameters,
+which can be used for live tuning of regulators over UART or similar byte stream link.
+
+Each parameter (or a group of parameters) is identified by numeric ID
+and serialized using [`Wire`] format into the frame:
+
+| Offset | Size | Field                            |
+|--------|------|----------------------------------|
+| 0      | 1    | Sync byte _0xA5_                 |
+| 1      | 2    | ID (little-endian)               |
+| 3      | 1    | Payload length _N_               |
+| 4      | _N_  | Payload                          |
+| 4 + _N_| 2    | CRC16 of ID, length and payload  |
+
+The frames is written using [`write`](fn@write) on the host side.
+The target feeds received bytes one by one to the [`Parser`] which emits the [`Frame`]
+when it was received completely and checksum matches.
+Then the target updates corresponding parameters using [`Frame::update`].
+
+The parser doesn't require allocations, it uses internal buffer of fixed size.
+When the error was detected, the parser skips bytes until the next sync byte.
+
+*/
+
+use super::{Crc16, Wire};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use generic_array::{ArrayLength, GenericArray};
+
+/// The sync byte which starts each frame
+pub const SYNC: u8 = 0xa5;
+
+/// The size of frame header (sync, ID and length)
+pub const HEADER_SIZE: usize = 4;
+
+/// The size of frame checksum
+pub const CRC_SIZE: usize = 2;
+
+/// The maximum size of payload
+pub const MAX_PAYLOAD: usize = 255;
+
+/// Frame processing errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Buffer too small to hold frame
+    Overflow,
+    /// Payload length mismatch
+    Length,
+    /// Checksum mismatch
+    Checksum,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Error::Overflow => "Buffer overflow",
+            Error::Length => "Payload length mismatch",
+            Error::Checksum => "Checksum mismatch",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/**
+Write the frame with value into the buffer
+
+Returns the size of frame in bytes.
+ */
+pub fn write<T>(id: u16, value: &T, buf: &mut [u8]) -> Result<usize, Error>
+where
+    T: Wire,
+{
+    if T::SIZE > MAX_PAYLOAD {
+        return Err(Error::Length);
+    }
+
+    let size = HEADER_SIZE + T::SIZE + CRC_SIZE;
+    if buf.len() < size {
+        return Err(Error::Overflow);
+    }
+
+    buf[0] = SYNC;
+    id.write_to(&mut buf[1..]);
+    buf[3] = T::SIZE as u8;
+    value.write_to(&mut buf[HEADER_SIZE..]);
+
+    let crc = Crc16::checksum(&buf[1..HEADER_SIZE + T::SIZE]);
+    crc.write_to(&mut buf[HEADER_SIZE + T::SIZE..]);
+
+    Ok(size)
+}
+
+/// Received frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// Parameter ID
+    pub id: u16,
+    /// Serialized value
+    pub payload: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Read value from the payload
+    pub fn read<T>(&self) -> Result<T, Error>
+    where
+        T: Wire,
+    {
+        if self.payload.len() != T::SIZE {
+            return Err(Error::Length);
+        }
+        Ok(T::read_from(self.payload))
+    }
+
+    /**
+    Update value when frame ID matches
+
+    Returns `true` when the value was updated.
+     */
+    pub fn update<T>(&self, id: u16, value: &mut T) -> Result<bool, Error>
+    where
+        T: Wire,
+    {
+        if self.id != id {
+            return Ok(false);
+        }
+        *value = self.read()?;
+        Ok(true)
+    }
+}
+
+/**
+Incremental frame parser
+
+- `N` - the size of internal buffer (should hold the ID, length, largest payload and checksum)
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Parser<N>
+where
+    N: ArrayLength<u8>,
+{
+    /// Received bytes except sync
+    buffer: GenericArray<u8, N>,
+    /// The number of received bytes
+    length: usize,
+    /// Sync byte received
+    synced: bool,
+}
+
+impl<N> Parser<N>
+where
+    N: ArrayLength<u8>,
+{
+    /// Create new parser
+    pub fn new() -> Self {
+        Self {
+            buffer: GenericArray::default(),
+            length: 0,
+            synced: false,
+        }
+    }
+
+    /// Reset parser state
+    pub fn reset(&mut self) {
+        self.length = 0;
+        self.synced = false;
+    }
+
+    /**
+    Feed received byte to the parser
+
+    Returns the frame when it was received completely.
+     */
+    pub fn push(&mut self, byte: u8) -> Result<Option<Frame<'_>>, Error> {
+        if !self.synced {
+            self.synced = byte == SYNC;
+            return Ok(None);
+        }
+
+        if self.length >= N::USIZE {
+            self.reset();
+            return Err(Error::Overflow);
+        }
+
+        self.buffer[self.length] = byte;
+        self.length += 1;
+
+        let header = HEADER_SIZE - 1;
+        if self.length < header {
+            return Ok(None);
+        }
+
+        let payload = self.buffer[header - 1] as usize;
+        let size = header + payload + CRC_SIZE;
+        if self.length < size {
+            return Ok(None);
+        }
+
+        self.reset();
+
+        let data = &self.buffer[..header + payload];
+        let crc = u16::read_from(&self.buffer[header + payload..]);
+        if Crc16::checksum(data) != crc {
+            return Err(Error::Checksum);
+        }
+
+        Ok(Some(Frame {
+            id: u16::read_from(data),
+            payload: &data[header..],
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Cast;
+    use typenum::{N8, P16, U16, U8};
+    use ufix::bin::Fix;
+
+    type T = Fix<P16, N8>;
+
+    #[test]
+    fn frame_roundtrip() {
+        let mut buf = [0u8; 16];
+        let size = write(0x0102, &(T::cast(1.5), 10u8), &mut buf).unwrap();
+        assert_eq!(size, 9);
+        assert_eq!(buf[..4], [SYNC, 0x02, 0x01, 3]);
+
+        let mut gain = T::cast(0.0);
+        let mut limit = 0u8;
+        let mut pair = (T::cast(0.0), 0u8);
+
+        let mut parser = Parser::<U16>::new();
+        let mut updated = 0;
+        for byte in &buf[..size] {
+            if let Some(frame) = parser.push(*byte).unwrap() {
+                assert_eq!(frame.update(0x0001, &mut limit), Ok(false));
+                assert_eq!(frame.update(0x0102, &mut gain), Err(Error::Length));
+                assert_eq!(frame.update(0x0102, &mut pair), Ok(true));
+                updated += 1;
+            }
+        }
+
+        assert_eq!(updated, 1);
+        assert_eq!(pair, (T::cast(1.5), 10));
+        assert_eq!(gain, T::cast(0.0));
+        assert_eq!(limit, 0);
+    }
+
+    #[test]
+    fn frame_resync() {
+        let mut buf = [0u8; 16];
+        let size = write(7, &-3i16, &mut buf).unwrap();
+
+        let mut parser = Parser::<U8>::new();
+
+        // Garbage before frame
+        assert_eq!(parser.push(0x00), Ok(None));
+        assert_eq!(parser.push(0xff), Ok(None));
+
+        // Corrupted frame
+        let mut bad = buf;
+        bad[4] ^= 0x01;
+        let mut result = Ok(None);
+        for byte in &bad[..size] {
+            result = parser.push(*byte).map(|frame| frame.map(|frame| frame.id));
+        }
+        assert_eq!(result, Err(Error::Checksum));
+
+        // Valid frame
+        let mut value = 0i16;
+        for byte in &buf[..size] {
+            if let Some(frame) = parser.push(*byte).unwrap() {
+                assert_eq!(frame.update(7, &mut value), Ok(true));
+            }
+        }
+        assert_eq!(value, -3);
+    }
+
+    #[test]
+    fn frame_overflow() {
+        let mut buf = [0u8; 8];
+        assert_eq!(write(1, &(0u32, 0u32), &mut buf), Err(Error::Overflow));
+
+        let mut buf = [0u8; 16];
+        let size = write(1, &(0u32, 0u32), &mut buf).unwrap();
+
+        let mut parser = Parser::<U8>::new();
+        let mut result = Ok(None);
+        for byte in &buf[..size] {
+            result = parser.push(*byte).map(|frame| frame.map(|frame| frame.id));
+            if result.is_err() {
+                break;
+            }
+        }
+        assert_eq!(result, Err(Error::Overflow));
+    }
+}
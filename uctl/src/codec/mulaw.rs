@@ -0,0 +1,257 @@
+/*!
+
+## μ-law compression
+
+This module implements **μ-law** style logarithmic compression of samples into 8-bit codes
+which is useful for streaming of signals over low-bandwidth telemetry links.
+
+The encoding follows the [G.711](https://en.wikipedia.org/wiki/G.711) μ-law algorithm:
+the code consists of sign bit, 3-bit segment (exponent) and 4-bit position (mantissa) in segment.
+The relative error of decoded values is about 3% over the whole dynamic range of 14 bits.
+
+The implementation uses only integer shifts, compares and additions,
+so it is suitable for FPU-less hardware.
+
+The input samples is scaled to 16-bit full scale before encoding,
+so for fixed-point values the full range of mantissa is used regardless of exponent.
+
+*/
+
+use crate::{Cast, Transducer};
+use core::marker::PhantomData;
+use typenum::P2;
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/// The bias which is added to magnitude before encoding
+const BIAS: i32 = 0x84;
+
+/// The maximum magnitude which can be encoded
+const CLIP: i32 = 32635;
+
+/**
+μ-law companding
+
+The values is treated as a fractions of full scale range of type.
+*/
+pub trait MuLaw: Sized {
+    /// Compress value into 8-bit code
+    fn mulaw_encode(self) -> u8;
+
+    /// Expand 8-bit code into value
+    fn mulaw_decode(code: u8) -> Self;
+}
+
+impl MuLaw for i16 {
+    fn mulaw_encode(self) -> u8 {
+        let mut value = self as i32;
+
+        let sign = if value < 0 {
+            value = -value;
+            0x80
+        } else {
+            0x00
+        };
+
+        if value > CLIP {
+            value = CLIP;
+        }
+        value += BIAS;
+
+        let mut exponent = 7;
+        while exponent > 0 && value & (0x4000 >> (7 - exponent)) == 0 {
+            exponent -= 1;
+        }
+
+        let mantissa = (value >> (exponent + 3)) & 0x0f;
+
+        !(sign | (exponent << 4) as u8 | mantissa as u8)
+    }
+
+    fn mulaw_decode(code: u8) -> Self {
+        let code = !code;
+
+        let exponent = (code >> 4) & 0x07;
+        let mantissa = (code & 0x0f) as i32;
+
+        let value = (((mantissa << 3) + BIAS) << exponent) - BIAS;
+
+        (if code & 0x80 != 0 { -value } else { value }) as i16
+    }
+}
+
+impl<B, E> MuLaw for Fix<P2, B, E>
+where
+    P2: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<P2, B>: Cast<i64>,
+    i64: Cast<Mantissa<P2, B>>,
+{
+    fn mulaw_encode(self) -> u8 {
+        let bits = i64::cast(self.bits);
+
+        let value = if B::I32 > 16 {
+            bits >> (B::I32 - 16)
+        } else {
+            bits << (16 - B::I32)
+        };
+
+        (value as i16).mulaw_encode()
+    }
+
+    fn mulaw_decode(code: u8) -> Self {
+        let value = i16::mulaw_decode(code) as i64;
+
+        let bits = if B::I32 > 16 {
+            value << (B::I32 - 16)
+        } else {
+            value >> (16 - B::I32)
+        };
+
+        Self::new(Mantissa::<P2, B>::cast(bits))
+    }
+}
+
+/**
+Compress the slice of values into codes
+
+Only the minimum of lengths of slices will be processed.
+Returns the number of encoded values.
+ */
+pub fn encode<T>(values: &[T], codes: &mut [u8]) -> usize
+where
+    T: Copy + MuLaw,
+{
+    codes
+        .iter_mut()
+        .zip(values.iter())
+        .fold(0, |count, (code, value)| {
+            *code = value.mulaw_encode();
+            count + 1
+        })
+}
+
+/**
+Expand the slice of codes into values
+
+Only the minimum of lengths of slices will be processed.
+Returns the number of decoded values.
+ */
+pub fn decode<T>(codes: &[u8], values: &mut [T]) -> usize
+where
+    T: MuLaw,
+{
+    values
+        .iter_mut()
+        .zip(codes.iter())
+        .fold(0, |count, (value, code)| {
+            *value = T::mulaw_decode(*code);
+            count + 1
+        })
+}
+
+/**
+μ-law encoder
+
+- `T` - input value type
+*/
+#[derive(Debug)]
+pub struct Encoder<T>(PhantomData<T>);
+
+impl<T> Transducer for Encoder<T>
+where
+    T: MuLaw,
+{
+    type Input = T;
+    type Output = u8;
+    type Param = ();
+    type State = ();
+
+    fn apply(_param: &Self::Param, _state: &mut Self::State, value: Self::Input) -> Self::Output {
+        value.mulaw_encode()
+    }
+}
+
+/**
+μ-law decoder
+
+- `T` - output value type
+*/
+#[derive(Debug)]
+pub struct Decoder<T>(PhantomData<T>);
+
+impl<T> Transducer for Decoder<T>
+where
+    T: MuLaw,
+{
+    type Input = u8;
+    type Output = T;
+    type Param = ();
+    type State = ();
+
+    fn apply(_param: &Self::Param, _state: &mut Self::State, code: Self::Input) -> Self::Output {
+        T::mulaw_decode(code)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N12, N4, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn mulaw_i16() {
+        assert_eq!(0i16.mulaw_encode(), 0xff);
+        assert_eq!(i16::mulaw_decode(0xff), 0);
+
+        assert_eq!(1000i16.mulaw_encode(), 0xce);
+        assert_eq!(i16::mulaw_decode(0xce), 988);
+
+        assert_eq!((-1000i16).mulaw_encode(), 0x4e);
+        assert_eq!(i16::mulaw_decode(0x4e), -988);
+
+        assert_eq!(i16::MAX.mulaw_encode(), 0x80);
+        assert_eq!(i16::mulaw_decode(0x80), 32124);
+
+        assert_eq!(i16::MIN.mulaw_encode(), 0x00);
+        assert_eq!(i16::mulaw_decode(0x00), -32124);
+    }
+
+    #[test]
+    fn mulaw_monotonic() {
+        // The code 0x7f is negative zero
+        let mut last = i16::MIN;
+        for code in (0x00..=0x7e).chain((0x80..=0xff).rev()) {
+            let value = i16::mulaw_decode(code);
+            assert!(value >= last);
+            assert_eq!(value.mulaw_encode(), code);
+            last = value;
+        }
+    }
+
+    #[test]
+    fn mulaw_fix() {
+        type T = Fix<P16, N12>;
+        type W = Fix<P32, N4>;
+
+        assert_eq!(T::cast(1.0).mulaw_encode(), 0xaf);
+        assert_eq!(T::mulaw_decode(0xaf), T::new(4092));
+
+        assert_eq!(W::new(1 << 24).mulaw_encode(), 0xe7);
+        assert_eq!(W::mulaw_decode(0xe7), W::new(260 << 16));
+    }
+
+    #[test]
+    fn mulaw_slice() {
+        let values = [0i16, 1000, -1000, 20000];
+        let mut codes = [0u8; 4];
+        let mut decoded = [0i16; 3];
+
+        assert_eq!(encode(&values, &mut codes), 4);
+        assert_eq!(codes, [0xff, 0xce, 0x4e, 0x8c]);
+
+        assert_eq!(decode(&codes, &mut decoded), 3);
+        assert_eq!(decoded, [0, 988, -988]);
+    }
+}
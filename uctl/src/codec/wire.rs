@@ -0,0 +1,360 @@
+/*!
+
+## Wire format
+
+This module implements the simple binary serialization of values
+which is used to transfer parameters between host and target.
+
+The values is stored using little-endian byte order without any padding.
+The binary fixed-point values is stored as its mantissa using the minimum number of bytes
+which is enough to hold the digits, so the host should know the exponent.
+The tuples is stored as a sequence of its fields.
+The optional values is stored as a presence flag followed by the value (zeroed when absent),
+so the size is fixed.
+
+The parameters of the core transducers (like PID, EMA, scaler and clamper) is serializable too,
+so it can be adjusted from host at runtime.
+
+*/
+
+use crate::Cast;
+use core::ops::{RangeFrom, RangeFull, RangeInclusive, RangeToInclusive};
+use typenum::{P2, U2};
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/**
+The binary serializable value
+*/
+pub trait Wire: Sized {
+    /// The size of serialized value in bytes
+    const SIZE: usize;
+
+    /// Write value into the buffer
+    ///
+    /// The buffer length should be at least [`Self::SIZE`].
+    fn write_to(&self, buf: &mut [u8]);
+
+    /// Read value from the buffer
+    ///
+    /// The buffer length should be at least [`Self::SIZE`].
+    fn read_from(buf: &[u8]) -> Self;
+}
+
+macro_rules! wire_impl {
+    ($($type: ty),*) => {
+        $(
+            impl Wire for $type {
+                const SIZE: usize = core::mem::size_of::<$type>();
+
+                fn write_to(&self, buf: &mut [u8]) {
+                    buf[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_from(buf: &[u8]) -> Self {
+                    let mut bytes = [0u8; core::mem::size_of::<$type>()];
+                    bytes.copy_from_slice(&buf[..Self::SIZE]);
+                    <$type>::from_le_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+wire_impl!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+#[cfg(feature = "i128")]
+wire_impl!(u128, i128);
+
+impl Wire for bool {
+    const SIZE: usize = 1;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        buf[0] = *self as u8;
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        buf[0] != 0
+    }
+}
+
+/// The flag of presence is stored before value (the absent value is zeroed)
+impl<T> Wire for Option<T>
+where
+    T: Wire,
+{
+    const SIZE: usize = 1 + T::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        self.is_some().write_to(buf);
+        match self {
+            Some(value) => value.write_to(&mut buf[1..]),
+            None => buf[1..Self::SIZE].iter_mut().for_each(|byte| *byte = 0),
+        }
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        if bool::read_from(buf) {
+            Some(T::read_from(&buf[1..]))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Wire for RangeInclusive<T>
+where
+    T: Wire,
+{
+    const SIZE: usize = 2 * T::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        self.start().write_to(buf);
+        self.end().write_to(&mut buf[T::SIZE..]);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        T::read_from(buf)..=T::read_from(&buf[T::SIZE..])
+    }
+}
+
+impl<T> Wire for RangeFrom<T>
+where
+    T: Wire,
+{
+    const SIZE: usize = T::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        self.start.write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        T::read_from(buf)..
+    }
+}
+
+impl<T> Wire for RangeToInclusive<T>
+where
+    T: Wire,
+{
+    const SIZE: usize = T::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        self.end.write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        ..=T::read_from(buf)
+    }
+}
+
+impl Wire for RangeFull {
+    const SIZE: usize = 0;
+
+    fn write_to(&self, _buf: &mut [u8]) {}
+
+    fn read_from(_buf: &[u8]) -> Self {
+        ..
+    }
+}
+
+macro_rules! wire_fix {
+    ($radix: ident, $type: ty) => {
+        impl<B, E> Wire for Fix<$radix, B, E>
+        where
+            $radix: Radix<B>,
+            B: Digits,
+            E: Exponent,
+            Mantissa<$radix, B>: Copy + Cast<$type>,
+            $type: Cast<Mantissa<$radix, B>>,
+        {
+            const SIZE: usize = (B::I32 as usize).div_ceil(8);
+
+            fn write_to(&self, buf: &mut [u8]) {
+                let bytes = <$type>::cast(self.bits).to_le_bytes();
+                buf[..Self::SIZE].copy_from_slice(&bytes[..Self::SIZE]);
+            }
+
+            fn read_from(buf: &[u8]) -> Self {
+                let mut bytes = [0u8; core::mem::size_of::<$type>()];
+                bytes[..Self::SIZE].copy_from_slice(&buf[..Self::SIZE]);
+                // extend sign (when signed)
+                let shift = (bytes.len() - Self::SIZE) * 8;
+                let bits = <$type>::from_le_bytes(bytes) << shift >> shift;
+                Self::new(Mantissa::<$radix, B>::cast(bits))
+            }
+        }
+    };
+}
+
+wire_fix!(P2, i64);
+wire_fix!(U2, u64);
+
+macro_rules! wire_tuple {
+    ($($type: ident => $field: tt),+) => {
+        impl<$($type),+> Wire for ($($type,)+)
+        where
+            $($type: Wire),+
+        {
+            const SIZE: usize = 0 $(+ $type::SIZE)+;
+
+            fn write_to(&self, buf: &mut [u8]) {
+                let mut offset = 0;
+                $(
+                    self.$field.write_to(&mut buf[offset..]);
+                    offset += $type::SIZE;
+                )+
+                let _ = offset;
+            }
+
+            fn read_from(buf: &[u8]) -> Self {
+                let mut offset = 0;
+                ($({
+                    let value = $type::read_from(&buf[offset..]);
+                    offset += $type::SIZE;
+                    let _ = offset;
+                    value
+                },)+)
+            }
+        }
+    };
+}
+
+wire_tuple!(A => 0);
+wire_tuple!(A => 0, B => 1);
+wire_tuple!(A => 0, B => 1, C => 2);
+wire_tuple!(A => 0, B => 1, C => 2, D => 3);
+wire_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+wire_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+wire_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+wire_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N8, P12, P16, P24};
+    use ufix::bin::{Fix, UFix};
+
+    #[test]
+    fn wire_primitive() {
+        let mut buf = [0u8; 4];
+
+        0x1234u16.write_to(&mut buf);
+        assert_eq!(buf[..2], [0x34, 0x12]);
+        assert_eq!(u16::read_from(&buf), 0x1234);
+
+        (-2i32).write_to(&mut buf);
+        assert_eq!(buf, [0xfe, 0xff, 0xff, 0xff]);
+        assert_eq!(i32::read_from(&buf), -2);
+
+        1.5f32.write_to(&mut buf);
+        assert_eq!(f32::read_from(&buf), 1.5);
+    }
+
+    #[test]
+    fn wire_fix() {
+        type T = Fix<P16, N8>;
+        let mut buf = [0u8; 2];
+
+        assert_eq!(T::SIZE, 2);
+        T::cast(1.5).write_to(&mut buf);
+        assert_eq!(buf, [0x80, 0x01]);
+        assert_eq!(T::read_from(&buf), T::cast(1.5));
+
+        type S = Fix<P24, N8>;
+        let mut buf = [0u8; 3];
+
+        assert_eq!(S::SIZE, 3);
+        S::cast(-1.5).write_to(&mut buf);
+        assert_eq!(buf, [0x80, 0xfe, 0xff]);
+        assert_eq!(S::read_from(&buf), S::cast(-1.5));
+
+        type U = UFix<P12, N8>;
+
+        assert_eq!(U::SIZE, 2);
+        U::cast(15.5).write_to(&mut buf);
+        assert_eq!(buf[..2], [0x80, 0x0f]);
+        assert_eq!(U::read_from(&buf), U::cast(15.5));
+    }
+
+    #[test]
+    fn wire_option() {
+        type T = Option<i16>;
+        let mut buf = [0xaau8; 3];
+
+        assert_eq!(T::SIZE, 3);
+        Some(-2i16).write_to(&mut buf);
+        assert_eq!(buf, [0x01, 0xfe, 0xff]);
+        assert_eq!(T::read_from(&buf), Some(-2));
+
+        None::<i16>.write_to(&mut buf);
+        assert_eq!(buf, [0x00, 0x00, 0x00]);
+        assert_eq!(T::read_from(&buf), None);
+
+        let mut buf = [0u8; 4];
+        (-1i16..=1).write_to(&mut buf);
+        assert_eq!(buf, [0xff, 0xff, 0x01, 0x00]);
+        assert_eq!(RangeInclusive::<i16>::read_from(&buf), -1..=1);
+    }
+
+    #[test]
+    fn wire_params() {
+        use crate::{
+            clamper::{ClampParam, SaturationParam},
+            ema, pid, scaler,
+        };
+
+        fn round_trip<T: Wire>(value: T, buf: &mut [u8]) {
+            value.write_to(buf);
+            let copy = T::read_from(buf);
+            let mut other = [0u8; 64];
+            copy.write_to(&mut other);
+            assert_eq!(buf[..T::SIZE], other[..T::SIZE]);
+        }
+
+        let mut buf = [0u8; 64];
+
+        type P = pid::Param<f32, Fix<P16, N8>>;
+        assert_eq!(P::SIZE, 4 * 4 + 2 * 3);
+        round_trip(
+            P::new(1.0, 0.5, 0.1, 10.0, 0.01).with_limits(-1.0, 1.0),
+            &mut buf,
+        );
+        assert_eq!(buf[16..22], [0x01, 0x00, 0xff, 0x01, 0x00, 0x01]);
+
+        type I = pid::IParam<f32, Fix<P16, N8>>;
+        assert_eq!(I::SIZE, 4 + 2 * 3);
+        round_trip(I::new(0.5, 0.01).with_limits(-1.0, 1.0), &mut buf);
+        assert_eq!(buf[4..10], [0x01, 0x00, 0xff, 0x01, 0x00, 0x01]);
+
+        type E = ema::Param<Fix<P16, N8>>;
+        assert_eq!(E::SIZE, 2);
+        round_trip(E::from_alpha(Fix::cast(0.25)), &mut buf);
+        assert_eq!(buf[..2], [0x40, 0x00]);
+
+        type S = scaler::Param<f32, i16>;
+        assert_eq!(S::SIZE, 6);
+        round_trip(S::from_factor(2.0, -1), &mut buf);
+
+        type C = ClampParam<i16>;
+        assert_eq!(C::SIZE, 6);
+        C::from_max(100).write_to(&mut buf);
+        assert_eq!(C::read_from(&buf), C::from_max(100));
+
+        type A = SaturationParam<C>;
+        assert_eq!(A::SIZE, 10);
+        A::new(C::new(-5, 5), 100).write_to(&mut buf);
+        let param = A::read_from(&buf);
+        assert_eq!(param.range, C::new(-5, 5));
+        assert_eq!(param.window(), 100);
+    }
+
+    #[test]
+    fn wire_tuple() {
+        type T = (u8, i16, bool);
+        let mut buf = [0u8; 4];
+
+        assert_eq!(T::SIZE, 4);
+        (7u8, -1i16, true).write_to(&mut buf);
+        assert_eq!(buf, [0x07, 0xff, 0xff, 0x01]);
+        assert_eq!(T::read_from(&buf), (7, -1, true));
+    }
+}
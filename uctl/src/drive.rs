@@ -0,0 +1,7 @@
+pub mod cogging;
+pub mod dcbus;
+pub mod inertia;
+pub mod mtpa;
+pub mod pll;
+pub mod vf;
+pub mod weakening;
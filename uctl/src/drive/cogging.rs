@@ -0,0 +1,302 @@
+/*!
+
+## Cogging torque compensation
+
+This module implements learning and compensation of **cogging torque**
+(and other position-dependent torque disturbances) of permanent magnet motors.
+
+The compensation is a [periodic lookup table](crate::lut::Periodic) of _N_ corrections
+uniformly distributed over one cycle of angle (mechanical or electrical).
+The corrections between nodes is interpolated linearly, the last node is interpolated with the first one:
+
+_y = c<sub>i</sub> + f (c<sub>i+1</sub> - c<sub>i</sub>)_, where _i + f = θ N_
+
+The table is learned while the drive runs slowly with constant speed under closed-loop control.
+In this case the torque command of speed regulator contains the cogging torque.
+The [`Learner`] updates two nodes around the angle by LMS rule:
+
+_e = u - y_
+
+_c<sub>i</sub> += μ (1 - f) e, c<sub>i+1</sub> += μ f e_
+
+After learning the mean value (the load torque) should be removed from table
+by [`State::remove_mean`]. Then the [`Compensator`] provides the feed-forward torque
+which should be added to the output of speed regulator.
+
+The table can be stored and loaded or changed at runtime through [`State::table_mut`].
+
+*/
+
+use crate::{
+    lut::{Periodic, PeriodicParam},
+    snapshot_fields, Cast, Cyc, ResetState, Transducer,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Cogging learning parameters
+
+- `K` - learning rate type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K> {
+    /// The learning rate _μ_
+    rate: K,
+}
+
+impl<K> Param<K> {
+    /**
+    Init cogging learning parameters
+
+    - `rate`: The learning rate _μ_ (0..1)
+     */
+    pub fn new<V>(rate: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            rate: K::cast(f64::cast(rate)),
+        }
+    }
+}
+
+/**
+Cogging compensation state
+
+- `T` - torque type
+- `N` - the number of nodes
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The corrections table
+    table: PeriodicParam<T, N>,
+}
+
+impl<T, N> State<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The corrections table
+    pub fn table(&self) -> &PeriodicParam<T, N> {
+        &self.table
+    }
+
+    /// The mutable corrections table
+    pub fn table_mut(&mut self) -> &mut PeriodicParam<T, N> {
+        &mut self.table
+    }
+
+    /// Remove the mean value from table
+    pub fn remove_mean(&mut self)
+    where
+        T: Copy + Cast<f64>,
+        f64: Cast<T>,
+    {
+        let values = self.table.values_mut();
+        let mean = values.iter().map(|value| f64::cast(*value)).sum::<f64>() / N::USIZE as f64;
+
+        for value in values.iter_mut() {
+            *value = T::cast(f64::cast(*value) - mean);
+        }
+    }
+}
+
+/// The preset value is a corrections table (for example stored in non-volatile memory)
+impl<T, N> ResetState for State<T, N>
+where
+    T: Default,
+    N: ArrayLength<T>,
+{
+    type Value = GenericArray<T, N>;
+
+    fn reset(&mut self) {
+        for value in self.table.values_mut() {
+            *value = T::default();
+        }
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.table = value.into();
+    }
+}
+
+snapshot_fields! {
+    State<T, N> where [N: ArrayLength<T>] {
+        table: PeriodicParam<T, N>,
+    }
+}
+
+/**
+Cogging table learner
+
+- `A` - angle type
+- `T` - torque type
+- `K` - learning rate type
+- `N` - the number of nodes
+
+The input is an angle and a torque command. The output is an actual correction.
+*/
+#[derive(Debug)]
+pub struct Learner<A, T, K, N>(PhantomData<(A, T, K, N)>);
+
+impl<A, T, K, N> Transducer for Learner<A, T, K, N>
+where
+    A: Copy
+        + Cast<f64>
+        + Cast<u32>
+        + Mul<A>
+        + Sub<A>
+        + Cast<Prod<A, A>>
+        + Cast<Diff<A, A>>
+        + Mul<T>,
+    u32: Cast<A>,
+    T: Copy
+        + Add<T>
+        + Sub<T>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>
+        + Cast<Prod<A, T>>
+        + Cast<Prod<K, T>>,
+    K: Copy + Mul<A> + Cast<Prod<K, A>> + Mul<T>,
+    N: ArrayLength<T>,
+{
+    type Input = (Cyc<A>, T);
+    type Output = T;
+    type Param = Param<K>;
+    type State = State<T, N>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (angle, torque): Self::Input,
+    ) -> Self::Output {
+        let (this, next, fraction) = PeriodicParam::<T, N>::locate(*angle);
+        let value = state.table.interpolate((this, next, fraction));
+
+        // e = u - y
+        let error = T::cast(torque - value);
+        let weights = [
+            K::cast(param.rate * A::cast(A::cast(1.0) - fraction)),
+            K::cast(param.rate * fraction),
+        ];
+
+        let values = state.table.values_mut();
+        for (index, weight) in [this, next].iter().zip(weights.iter()) {
+            let node = &mut values[*index];
+            *node = T::cast(*node + T::cast(*weight * error));
+        }
+
+        value
+    }
+}
+
+/**
+Cogging torque compensator
+
+- `A` - angle type
+- `T` - torque type
+- `N` - the number of nodes
+
+The input is an angle. The output is a feed-forward torque.
+*/
+#[derive(Debug)]
+pub struct Compensator<A, T, N>(PhantomData<(A, T, N)>);
+
+impl<A, T, N> Transducer for Compensator<A, T, N>
+where
+    Periodic<A, T, N>:
+        Transducer<Input = Cyc<A>, Output = T, Param = PeriodicParam<T, N>, State = ()>,
+    N: ArrayLength<T>,
+{
+    type Input = Cyc<A>;
+    type Output = T;
+    type Param = ();
+    type State = State<T, N>;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, angle: Self::Input) -> Self::Output {
+        Periodic::<A, T, N>::apply(&state.table, &mut (), angle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32, U48};
+    use ufix::bin::Fix;
+
+    /// The load torque with cogging of 6 periods per cycle
+    fn torque(angle: f64) -> f64 {
+        0.5 + 0.2 * (angle * 6.0 * core::f64::consts::TAU).sin()
+    }
+
+    fn learn<A, T, K>(param: &Param<K>, state: &mut State<T, U48>)
+    where
+        Learner<A, T, K, U48>:
+            Transducer<Input = (Cyc<A>, T), Output = T, Param = Param<K>, State = State<T, U48>>,
+        A: Cast<f64>,
+        T: Cast<f64>,
+    {
+        for step in 0..40000 {
+            let angle = (step as f64 * 0.001) % 1.0;
+            Learner::<A, T, K, U48>::apply(
+                param,
+                state,
+                (Cyc(A::cast(angle)), T::cast(torque(angle))),
+            );
+        }
+    }
+
+    #[test]
+    fn cogging_f32() {
+        type C = Compensator<f32, f32, U48>;
+        let param = Param::<f32>::new(0.05);
+        let mut state = State::<f32, U48>::default();
+
+        learn::<f32, f32, f32>(&param, &mut state);
+        state.remove_mean();
+
+        for step in 0..100 {
+            let angle = step as f64 * 0.01;
+            let error = C::apply(&(), &mut state, Cyc(angle as f32)) as f64 - (torque(angle) - 0.5);
+            assert!(error.abs() < 0.02, "angle: {} error: {}", angle, error);
+        }
+
+        state.reset();
+        assert_eq!(C::apply(&(), &mut state, Cyc(0.3)), 0.0);
+
+        // the table is written at runtime
+        state.table_mut().set(12, 0.5);
+        assert_eq!(C::apply(&(), &mut state, Cyc(0.25)), 0.5);
+        assert_eq!(state.table().values()[12], 0.5);
+    }
+
+    #[test]
+    fn cogging_fix() {
+        type A = Fix<P32, N24>;
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+        type C = Compensator<A, T, U48>;
+
+        let param = Param::<K>::new(0.05);
+        let mut state = State::<T, U48>::default();
+
+        learn::<A, T, K>(&param, &mut state);
+        state.remove_mean();
+
+        for step in 0..100 {
+            let angle = step as f64 * 0.01;
+            let error =
+                f64::cast(C::apply(&(), &mut state, Cyc(A::cast(angle)))) - (torque(angle) - 0.5);
+            assert!(error.abs() < 0.02, "angle: {} error: {}", angle, error);
+        }
+    }
+}
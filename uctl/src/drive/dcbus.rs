@@ -0,0 +1,177 @@
+/*!
+
+## DC-bus voltage compensation
+
+This module implements compensation of DC-bus voltage variations (ripple of rectified AC,
+sag under load and etc.) for voltage source inverters.
+
+The output voltage of inverter is proportional to the DC-bus voltage, so the voltage commands
+(or modulation indexes) evaluated for nominal voltage _Vn_ should be scaled by the ratio
+of nominal voltage to the measured voltage _Vdc_:
+
+_y = u Vn / Vdc_
+
+The ratio is limited to the range _[kmin, kmax]_ to prevent the excessive gain when the bus voltage
+collapses and the division by zero.
+
+*/
+
+use crate::{Cast, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Div, Mul},
+};
+use typenum::{Prod, Quot};
+
+/**
+DC-bus compensation parameters
+
+- `V` - voltage type
+- `K` - ratio type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<V, K> {
+    /// The nominal voltage _Vn_
+    nominal: V,
+    /// The voltage below which the maximum ratio is used (_Vn / kmax_)
+    low: V,
+    /// The voltage above which the minimum ratio is used (_Vn / kmin_)
+    high: V,
+    /// The minimum ratio _kmin_
+    min: K,
+    /// The maximum ratio _kmax_
+    max: K,
+}
+
+impl<V, K> Param<V, K> {
+    /**
+    Init DC-bus compensation parameters
+
+    - `nominal`: The nominal DC-bus voltage _Vn_
+    - `min`: The minimum ratio _kmin_ (usually less than 1)
+    - `max`: The maximum ratio _kmax_ (usually about 1.5..2)
+     */
+    pub fn new<T>(nominal: T, min: T, max: T) -> Self
+    where
+        V: Cast<f64>,
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let nominal = f64::cast(nominal);
+        let min = f64::cast(min);
+        let max = f64::cast(max);
+
+        assert!(min > 0.0 && min <= max);
+
+        Self {
+            nominal: V::cast(nominal),
+            low: V::cast(nominal / max),
+            high: V::cast(nominal / min),
+            min: K::cast(min),
+            max: K::cast(max),
+        }
+    }
+}
+
+/**
+DC-bus voltage compensator
+
+- `V` - voltage type
+- `U` - voltage command type
+- `K` - ratio type
+
+The input is a voltage command and a measured DC-bus voltage. The output is a compensated command.
+*/
+#[derive(Debug)]
+pub struct Compensator<V, U, K>(PhantomData<(V, U, K)>);
+
+impl<V, U, K> Compensator<V, U, K> {
+    /// Evaluate compensation ratio
+    pub fn ratio(param: &Param<V, K>, dc_bus: V) -> K
+    where
+        V: Copy + PartialOrd,
+        K: Copy + Mul<V> + Cast<Quot<Prod<K, V>, V>>,
+        Prod<K, V>: Cast<V> + Div<V>,
+    {
+        if dc_bus <= param.low {
+            param.max
+        } else if dc_bus >= param.high {
+            param.min
+        } else {
+            K::cast(Prod::<K, V>::cast(param.nominal) / dc_bus)
+        }
+    }
+}
+
+impl<V, U, K> Transducer for Compensator<V, U, K>
+where
+    V: Copy + PartialOrd,
+    U: Copy + Cast<Prod<U, K>> + Mul<K>,
+    K: Copy + Mul<V> + Cast<Quot<Prod<K, V>, V>>,
+    Prod<K, V>: Cast<V> + Div<V>,
+{
+    type Input = (U, V);
+    type Output = U;
+    type Param = Param<V, K>;
+    type State = ();
+
+    fn apply(
+        param: &Self::Param,
+        _state: &mut Self::State,
+        (command, dc_bus): Self::Input,
+    ) -> Self::Output {
+        U::cast(command * Self::ratio(param, dc_bus))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn dcbus_f32() {
+        type C = Compensator<f32, f32, f32>;
+        // 310 V nominal, ratio 0.5..2
+        let param = Param::<f32, f32>::new(310.0, 0.5, 2.0);
+
+        assert_eq!(C::apply(&param, &mut (), (100.0, 310.0)), 100.0);
+        assert_eq!(C::apply(&param, &mut (), (100.0, 248.0)), 125.0);
+        assert_eq!(C::apply(&param, &mut (), (-100.0, 400.0)), -77.5);
+
+        // limits
+        assert_eq!(C::apply(&param, &mut (), (100.0, 100.0)), 200.0);
+        assert_eq!(C::apply(&param, &mut (), (100.0, 0.0)), 200.0);
+        assert_eq!(C::apply(&param, &mut (), (100.0, 1000.0)), 50.0);
+    }
+
+    #[test]
+    fn dcbus_fix() {
+        type V = Fix<P16, N8>;
+        type U = Fix<P16, N8>;
+        type K = Fix<P16, N8>;
+        type C = Compensator<V, U, K>;
+
+        let param = Param::<V, K>::new(48.0, 0.5, 2.0);
+
+        assert_eq!(
+            C::apply(&param, &mut (), (U::cast(10.0), V::cast(48.0))),
+            U::cast(10.0)
+        );
+        assert_eq!(
+            C::apply(&param, &mut (), (U::cast(10.0), V::cast(32.0))),
+            U::cast(15.0)
+        );
+        assert_eq!(
+            C::apply(&param, &mut (), (U::cast(10.0), V::cast(0.0))),
+            U::cast(20.0)
+        );
+
+        type W = Fix<P32, N16>;
+        assert_eq!(
+            Compensator::<V, W, K>::apply(&param, &mut (), (W::cast(1.0), V::cast(64.0))),
+            W::cast(0.75)
+        );
+    }
+}
@@ -0,0 +1,309 @@
+/*!
+
+## Inertia and friction identification
+
+This module implements identification of mechanical parameters of drive:
+the moment of **inertia** _J_ and the coefficient of **viscous friction** _B_.
+
+The identifier applies the square wave torque profile (_±τ_ with specified half period)
+and measures the speed response. The mechanical model of drive
+
+_J ω' + B ω = τ_
+
+in discrete form is linear in parameters:
+
+_Δω = a τ + b ω_, where _a = P / J_, _b = -P B / J_
+
+The parameters _a_ and _b_ is estimated by least squares over all collected samples.
+The sums of normal equations is accumulated at each step, so the memory requirements
+doesn't depend on duration of identification:
+
+_a Στ² + b Στω = ΣτΔω_
+
+_a Στω + b Σω² = ΣωΔω_
+
+When the profile is complete the estimate is evaluated once using [`State::estimate`]:
+
+_J = P / a, B = -b / a_
+
+The sums accumulator type should hold the sums of squares of torques and speeds over whole profile.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Identification parameters
+
+- `T` - torque type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The positive and negative torques
+    torque: [T; 2],
+    /// The number of steps in half period of profile
+    half: u32,
+    /// The total number of steps of profile
+    steps: u32,
+    /// The sampling period
+    period: f64,
+}
+
+impl<T> Param<T> {
+    /**
+    Init identification parameters
+
+    - `torque`: The amplitude of torque
+    - `switch`: The half period of torque profile (s)
+    - `cycles`: The number of periods of torque profile
+    - `period`: The sampling period (s)
+
+    The half period should be comparable with mechanical time constant _J / B_.
+     */
+    pub fn new<V>(torque: V, switch: V, cycles: u32, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let torque = f64::cast(torque);
+        let period = f64::cast(period);
+        let half = (f64::cast(switch) / period + 0.5) as u32;
+
+        assert!(half > 0 && cycles > 0);
+
+        Self {
+            torque: [T::cast(torque), T::cast(-torque)],
+            half,
+            steps: 2 * half * cycles,
+            period,
+        }
+    }
+
+    /// The total number of steps of profile
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+}
+
+/**
+Identified parameters
+
+- `J` - inertia type
+- `B` - friction type
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate<J, B> {
+    /// The moment of inertia
+    pub inertia: J,
+    /// The coefficient of viscous friction
+    pub friction: B,
+}
+
+/**
+Identification state
+
+- `T` - torque type
+- `W` - speed type
+- `S` - sums accumulator type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T, W, S> {
+    /// The number of passed steps
+    step: u32,
+    /// The last applied torque
+    torque: T,
+    /// The last measured speed
+    speed: W,
+    /// The sums Στ², Στω, Σω², ΣτΔω, ΣωΔω
+    sums: [S; 5],
+}
+
+impl<T, W, S> State<T, W, S> {
+    /// The profile is complete
+    pub fn is_done(&self, param: &Param<T>) -> bool {
+        self.step > param.steps
+    }
+
+    /**
+    Evaluate the identified parameters
+
+    Returns `None` when the profile isn't complete or the data is degenerate.
+     */
+    pub fn estimate<J, B>(&self, param: &Param<T>) -> Option<Estimate<J, B>>
+    where
+        S: Copy,
+        f64: Cast<S>,
+        J: Cast<f64>,
+        B: Cast<f64>,
+    {
+        if !self.is_done(param) {
+            return None;
+        }
+
+        let [tt, tw, ww, td, wd] = self.sums.map(f64::cast);
+
+        // solve 2x2 normal equations
+        let det = tt * ww - tw * tw;
+        if det <= 0.0 {
+            return None;
+        }
+
+        let a = (td * ww - wd * tw) / det;
+        let b = (tt * wd - tw * td) / det;
+
+        if a <= 0.0 {
+            return None;
+        }
+
+        Some(Estimate {
+            inertia: J::cast(param.period / a),
+            friction: B::cast(-b / a),
+        })
+    }
+}
+
+/// The preset of identification is same as reset
+impl<T, W, S> ResetState for State<T, W, S>
+where
+    T: Default,
+    W: Default,
+    S: Default,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T, W, S> {
+        step: u32,
+        torque: T,
+        speed: W,
+        sums: [S; 5],
+    }
+}
+
+/**
+Inertia and friction identifier
+
+- `T` - torque type
+- `W` - speed type
+- `S` - sums accumulator type
+
+The input is a measured speed. The output is a torque command or `None` when the profile is complete.
+*/
+#[derive(Debug)]
+pub struct Identifier<T, W, S>(PhantomData<(T, W, S)>);
+
+impl<T, W, S> Transducer for Identifier<T, W, S>
+where
+    T: Copy + Mul<T> + Mul<W>,
+    W: Copy + Sub<W> + Mul<W> + Cast<Diff<W, W>>,
+    S: Copy + Add<S> + Cast<Sum<S, S>> + Cast<Prod<T, T>> + Cast<Prod<T, W>> + Cast<Prod<W, W>>,
+{
+    type Input = W;
+    type Output = Option<T>;
+    type Param = Param<T>;
+    type State = State<T, W, S>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, speed: Self::Input) -> Self::Output {
+        if state.is_done(param) {
+            return None;
+        }
+
+        if state.step > 0 {
+            // the response to the last applied torque
+            let torque = state.torque;
+            let last = state.speed;
+            let delta = W::cast(speed - last);
+
+            let terms = [
+                S::cast(torque * torque),
+                S::cast(torque * last),
+                S::cast(last * last),
+                S::cast(torque * delta),
+                S::cast(last * delta),
+            ];
+
+            for (sum, term) in state.sums.iter_mut().zip(terms.iter()) {
+                *sum = S::cast(*sum + *term);
+            }
+        }
+
+        state.speed = speed;
+        state.step += 1;
+
+        if state.is_done(param) {
+            return None;
+        }
+
+        state.torque = param.torque[(((state.step - 1) / param.half) % 2) as usize];
+
+        Some(state.torque)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32, P48};
+    use ufix::bin::Fix;
+
+    /// Simulate the drive with inertia 0.01 and friction 0.05
+    fn identify<T, W, S>(param: &Param<T>, state: &mut State<T, W, S>) -> Estimate<f64, f64>
+    where
+        Identifier<T, W, S>:
+            Transducer<Input = W, Output = Option<T>, Param = Param<T>, State = State<T, W, S>>,
+        T: Cast<f64>,
+        W: Cast<f64>,
+        S: Copy,
+        f64: Cast<T> + Cast<S>,
+    {
+        let (inertia, friction, period) = (0.01, 0.05, 1e-3);
+        let mut speed = 0.0;
+
+        while let Some(torque) = Identifier::<T, W, S>::apply(param, state, W::cast(speed)) {
+            speed += period * (f64::cast(torque) - friction * speed) / inertia;
+        }
+
+        state.estimate(param).unwrap()
+    }
+
+    #[test]
+    fn inertia_f64() {
+        let param = Param::<f64>::new(0.1, 0.2, 3, 1e-3);
+        let mut state = State::<f64, f64, f64>::default();
+
+        assert_eq!(param.steps(), 1200);
+        assert_eq!(state.estimate::<f64, f64>(&param), None);
+
+        let estimate = identify(&param, &mut state);
+        assert!((estimate.inertia - 0.01).abs() < 1e-6);
+        assert!((estimate.friction - 0.05).abs() < 1e-6);
+    }
+
+    #[test]
+    fn inertia_fix() {
+        type T = Fix<P32, N16>;
+        type W = Fix<P32, N16>;
+        type S = Fix<P48, N24>;
+
+        let param = Param::<T>::new(0.1, 0.2, 3, 1e-3);
+        let mut state = State::<T, W, S>::default();
+
+        let estimate = identify(&param, &mut state);
+        assert!((estimate.inertia - 0.01).abs() < 2e-4);
+        assert!((estimate.friction - 0.05).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,230 @@
+/*!
+
+## Maximum torque per ampere
+
+This module implements **MTPA** current references generator for interior permanent magnet
+synchronous motors, which gives the torque command with minimal magnitude of stator current.
+
+The torque of motor with _p_ pole pairs, flux linkage _ψ_ and inductances _Ld_, _Lq_ is:
+
+_T = 1.5 p (ψ iq + (Ld - Lq) id iq)_
+
+The MTPA trajectory for current magnitude _I_ (with _ΔL = Lq - Ld_) is:
+
+_id = (ψ - √(ψ² + 8 ΔL² I²)) / (4 ΔL)_
+
+_iq = √(I² - id²)_
+
+The exact solution for the torque command requires solving of quartic equation,
+so instead the trajectory is sampled at initialization into _N_ points uniformly distributed
+over the current range and evaluated using [lookup tables](crate::lut) _id(|T|)_ and _iq(|T|)_
+which requires only single multiplication per component.
+
+For surface mounted motors (_Ld = Lq_) the trajectory degenerates to _id = 0_.
+
+The negative torque commands is handled by symmetry: the _iq_ changes the sign,
+the _id_ stays negative.
+
+*/
+
+use crate::{
+    lut::{Param as LutParam, Table},
+    Cast, Sqrt, Transducer,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Sum};
+
+/**
+MTPA parameters
+
+- `T` - torque type
+- `C` - current type
+- `K` - slopes type
+- `N` - the number of points of trajectory
+*/
+#[derive(Debug, Clone)]
+pub struct Param<T, C, K, N>
+where
+    N: ArrayLength<T> + ArrayLength<C> + ArrayLength<K>,
+{
+    /// The _d_-axis current table
+    id: LutParam<T, C, K, N>,
+    /// The _q_-axis current table
+    iq: LutParam<T, C, K, N>,
+}
+
+impl<T, C, K, N> Param<T, C, K, N>
+where
+    N: ArrayLength<T> + ArrayLength<C> + ArrayLength<K> + ArrayLength<(f64, f64)>,
+{
+    /**
+    Init MTPA parameters
+
+    - `poles`: The number of pole pairs _p_
+    - `flux`: The permanent magnet flux linkage _ψ_ (Wb)
+    - `ld`: The _d_-axis inductance (H)
+    - `lq`: The _q_-axis inductance (H)
+    - `current`: The maximum magnitude of current (A)
+     */
+    pub fn new<V>(poles: V, flux: V, ld: V, lq: V, current: V) -> Self
+    where
+        T: Cast<f64>,
+        C: Cast<f64>,
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let poles = f64::cast(poles);
+        let flux = f64::cast(flux);
+        let delta = f64::cast(lq) - f64::cast(ld);
+        let current = f64::cast(current);
+
+        let trajectory = GenericArray::<(f64, f64), N>::generate(|i| {
+            let i = current * i as f64 / (N::USIZE - 1) as f64;
+            let id = if delta.abs() > f64::EPSILON {
+                (flux - Sqrt::sqrt(flux * flux + 8.0 * delta * delta * i * i)) / (4.0 * delta)
+            } else {
+                0.0
+            };
+            (id, Sqrt::sqrt((i * i - id * id).max(0.0)))
+        });
+
+        let torque = |(id, iq): (f64, f64)| 1.5 * poles * (flux * iq - delta * id * iq);
+
+        let table = |select: fn((f64, f64)) -> f64| {
+            let points = GenericArray::<(f64, f64), N>::generate(|i| {
+                (torque(trajectory[i]), select(trajectory[i]))
+            });
+            LutParam::new::<f64>(points.as_slice())
+        };
+
+        Self {
+            id: table(|(id, _)| id),
+            iq: table(|(_, iq)| iq),
+        }
+    }
+}
+
+/**
+MTPA current references generator
+
+- `T` - torque type
+- `C` - current type
+- `K` - slopes type
+- `N` - the number of points of trajectory
+
+The input is a torque command. The output is _d_-axis and _q_-axis current references.
+The torque command out of range is clamped to the maximum current.
+*/
+#[derive(Debug)]
+pub struct Mtpa<T, C, K, N>(PhantomData<(T, C, K, N)>);
+
+impl<T, C, K, N> Transducer for Mtpa<T, C, K, N>
+where
+    T: Copy + PartialOrd + Cast<f64> + Sub<T> + Cast<Diff<T, T>>,
+    C: Copy
+        + Cast<f64>
+        + Add<C>
+        + Sub<C>
+        + Cast<Diff<C, C>>
+        + Cast<Prod<K, Diff<T, T>>>
+        + Cast<Sum<C, C>>,
+    K: Copy + Mul<Diff<T, T>>,
+    N: ArrayLength<T> + ArrayLength<C> + ArrayLength<K>,
+{
+    type Input = T;
+    type Output = (C, C);
+    type Param = Param<T, C, K, N>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, torque: Self::Input) -> Self::Output {
+        let zero = T::cast(0.0);
+        let magnitude = if torque < zero {
+            T::cast(zero - torque)
+        } else {
+            torque
+        };
+
+        let id = Table::<T, C, K, N>::apply(&param.id, &mut (), magnitude);
+        let iq = Table::<T, C, K, N>::apply(&param.iq, &mut (), magnitude);
+
+        (
+            id,
+            if torque < zero {
+                C::cast(C::cast(0.0) - iq)
+            } else {
+                iq
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P24, P32, U17};
+    use ufix::bin::Fix;
+
+    // 4 pole pairs, 0.01 Wb, Ld = 0.2 mH, Lq = 0.5 mH, 20 A
+    const MOTOR: (f64, f64, f64, f64, f64) = (4.0, 0.01, 0.0002, 0.0005, 20.0);
+
+    fn torque((id, iq): (f64, f64)) -> f64 {
+        1.5 * MOTOR.0 * (MOTOR.1 * iq + (MOTOR.2 - MOTOR.3) * id * iq)
+    }
+
+    #[test]
+    fn mtpa_f64() {
+        type M = Mtpa<f64, f64, f64, U17>;
+        let param = Param::<f64, f64, f64, U17>::new(MOTOR.0, MOTOR.1, MOTOR.2, MOTOR.3, MOTOR.4);
+
+        assert_eq!(M::apply(&param, &mut (), 0.0), (0.0, 0.0));
+        // the maximum torque is limited by current
+        let (id, iq) = M::apply(&param, &mut (), 2.0);
+        assert!(((id * id + iq * iq).sqrt() - 20.0).abs() < 1e-9);
+
+        for &command in &[0.2, 0.5, 1.0, 1.3] {
+            let (id, iq) = M::apply(&param, &mut (), command);
+            assert!(id < 0.0);
+            // the torque is produced
+            assert!((torque((id, iq)) - command).abs() < 0.01 * command);
+
+            // the current is minimal: the same torque can't be produced with less current
+            let current = (id * id + iq * iq).sqrt();
+            let other = (id * 0.8, iq + 0.5);
+            assert!(
+                torque(other) < command || (other.0 * other.0 + other.1 * other.1).sqrt() > current
+            );
+
+            // the negative torque
+            assert_eq!(M::apply(&param, &mut (), -command), (id, -iq));
+        }
+    }
+
+    #[test]
+    fn mtpa_surface() {
+        type M = Mtpa<f64, f64, f64, U17>;
+        let param = Param::<f64, f64, f64, U17>::new(4.0, 0.01, 0.0003, 0.0003, 20.0);
+
+        let (id, iq) = M::apply(&param, &mut (), 0.6);
+        assert_eq!(id, 0.0);
+        assert!((iq - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mtpa_fix() {
+        type T = Fix<P24, N16>;
+        type C = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+        type M = Mtpa<T, C, K, U17>;
+
+        let param = Param::<T, C, K, U17>::new(MOTOR.0, MOTOR.1, MOTOR.2, MOTOR.3, MOTOR.4);
+
+        let (id, iq) = M::apply(&param, &mut (), T::cast(1.0));
+        let (id, iq) = (f64::cast(id), f64::cast(iq));
+        assert!(id < 0.0);
+        assert!((torque((id, iq)) - 1.0).abs() < 0.01);
+    }
+}
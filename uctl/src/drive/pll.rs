@@ -0,0 +1,291 @@
+/*!
+
+## Angle tracking observer
+
+This module implements **angle tracking loop** (also known as PLL observer)
+which estimates the angle and the speed from measurements of angle
+(from encoders, resolvers, Hall sensors or sensorless estimators).
+
+Unlike the finite differencing the tracking loop doesn't amplify the quantization
+and measurement noise, and it follows the ramps of angle without steady-state error
+(type-2 loop).
+
+The angles is given in cycles in range [0, 1). The error of tracking is wrapped
+to range [-0.5, 0.5), so the wraps of measured angle doesn't disturb the loop:
+
+_e = wrap(θm - θ)_
+
+The error is corrected by [proportional-integral regulator](crate::pid::Pi)
+which output is a speed (in cycles per second) and which integral part is the speed estimate:
+
+_ω = ω + Ki P e_
+
+_θ = wrap(θ + P (ω + Kp e))_
+
+The regulator gains is tuned by natural frequency _ωn_ and damping factor _ζ_:
+
+_Kp = 2 ζ ωn, Ki = ωn²_
+
+The speed estimate can be limited using [`Param::with_limits`].
+
+*/
+
+use crate::{
+    pid::{Pi, PiParam, PiState},
+    snapshot_fields, Cast, Cyc, ResetState, Transducer,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Tracking loop parameters
+
+- `K` - coefficients type
+- `W` - speed type
+- `D` - sampling period type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K, W, D> {
+    /// The speed regulator
+    pi: PiParam<K, W>,
+    /// The sampling period _P_
+    period: D,
+}
+
+impl<K, W, D> Param<K, W, D> {
+    /**
+    Init tracking loop parameters
+
+    - `bandwidth`: The natural frequency of loop _ωn_ (rad/s)
+    - `damping`: The damping factor _ζ_ (usually about 0.7..1)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<T>(bandwidth: T, damping: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        D: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let omega = f64::cast(bandwidth);
+        let period = f64::cast(period);
+
+        Self {
+            pi: PiParam::new::<f64>(2.0 * f64::cast(damping) * omega, omega * omega, period),
+            period: D::cast(period),
+        }
+    }
+
+    /**
+    Set speed limits
+
+    - `min`: The minimum speed (cycles per second)
+    - `max`: The maximum speed (cycles per second)
+     */
+    pub fn with_limits<T>(mut self, min: T, max: T) -> Self
+    where
+        W: Cast<f64>,
+        f64: Cast<T>,
+    {
+        self.pi = self.pi.with_limits(min, max);
+        self
+    }
+}
+
+/**
+Tracking loop state
+
+- `A` - angle type
+- `W` - speed type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<A, W> {
+    /// Estimated angle
+    angle: A,
+    /// Speed regulator which integral part is the estimated speed
+    pi: PiState<W>,
+}
+
+/// The preset value is an initial angle
+impl<A, W> ResetState for State<A, W>
+where
+    A: Default,
+    W: Default,
+{
+    type Value = A;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.angle = value;
+        self.pi.reset();
+    }
+}
+
+snapshot_fields! {
+    State<A, W> {
+        angle: A,
+        pi: PiState<W>,
+    }
+}
+
+/**
+Tracking loop estimate
+
+- `A` - angle type
+- `W` - speed type
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate<A, W> {
+    /// Estimated angle
+    pub angle: Cyc<A>,
+    /// Estimated speed (cycles per second)
+    pub speed: W,
+}
+
+/**
+Angle tracking observer
+
+- `A` - angle type
+- `W` - speed type
+- `K` - coefficients type
+- `D` - sampling period type
+
+The input is a measured angle.
+*/
+#[derive(Debug)]
+pub struct Observer<A, W, K, D>(PhantomData<(A, W, K, D)>);
+
+impl<A, W, K, D> Transducer for Observer<A, W, K, D>
+where
+    A: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<A>
+        + Sub<A>
+        + Cast<Sum<A, A>>
+        + Cast<Diff<A, A>>
+        + Cast<Prod<W, D>>,
+    W: Copy + Cast<f64> + Cast<A> + Mul<D>,
+    D: Copy,
+    Pi<W, K>: Transducer<Input = (W, W), Output = W, Param = PiParam<K, W>, State = PiState<W>>,
+{
+    type Input = Cyc<A>;
+    type Output = Estimate<A, W>;
+    type Param = Param<K, W, D>;
+    type State = State<A, W>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, angle: Self::Input) -> Self::Output {
+        let one = A::cast(1.0);
+        let half = A::cast(0.5);
+
+        // e = wrap(θm - θ)
+        let error = A::cast(*angle - state.angle);
+        let error = if error >= half {
+            A::cast(error - one)
+        } else if error < A::cast(-0.5) {
+            A::cast(error + one)
+        } else {
+            error
+        };
+
+        // ω = ω + Ki P e, θ = θ + P (ω + Kp e)
+        let speed = Pi::<W, K>::apply(&param.pi, &mut state.pi, (W::cast(error), W::cast(0.0)));
+        let angle = A::cast(state.angle + A::cast(speed * param.period));
+        state.angle = if angle >= one {
+            A::cast(angle - one)
+        } else if angle < A::cast(0.0) {
+            A::cast(angle + one)
+        } else {
+            angle
+        };
+
+        Estimate {
+            angle: Cyc(state.angle),
+            speed: state.pi.integral(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, N28, P32};
+    use ufix::bin::Fix;
+
+    fn track<A, W, K, D>(
+        param: &Param<K, W, D>,
+        state: &mut State<A, W>,
+        speed: f64,
+        period: f64,
+        steps: usize,
+    ) -> Estimate<A, W>
+    where
+        Observer<A, W, K, D>: Transducer<
+            Input = Cyc<A>,
+            Output = Estimate<A, W>,
+            Param = Param<K, W, D>,
+            State = State<A, W>,
+        >,
+        A: Cast<f64>,
+    {
+        let mut angle = 0.0;
+        let mut estimate = None;
+        for i in 0..steps {
+            // the measurement with quantization noise
+            let noise = if i % 3 == 0 { 0.002 } else { -0.001 };
+            let measured = (angle + noise + 1.0f64) % 1.0;
+            estimate = Some(Observer::<A, W, K, D>::apply(
+                param,
+                state,
+                Cyc(A::cast(measured)),
+            ));
+            angle = (angle + speed * period) % 1.0;
+        }
+        estimate.unwrap()
+    }
+
+    #[test]
+    fn pll_f64() {
+        let param = Param::<f64, f64, f64>::new(200.0, 0.8, 1e-4);
+        let mut state = State::<f64, f64>::default();
+
+        // 25 revolutions per second
+        let estimate = track(&param, &mut state, 25.0, 1e-4, 2000);
+        assert!((estimate.speed - 25.0).abs() < 0.1);
+        // the estimate is advanced to the next step
+        assert!(!(0.002..=0.998).contains(&*estimate.angle));
+
+        // reverse rotation
+        state.reset();
+        let estimate = track(&param, &mut state, -10.0, 1e-4, 2000);
+        assert!((estimate.speed + 10.0).abs() < 0.1);
+        assert!(*estimate.angle >= 0.0 && *estimate.angle < 1.0);
+
+        // the speed estimate is limited
+        let param = param.with_limits(-20.0, 20.0);
+        state.reset();
+        let estimate = track(&param, &mut state, 25.0, 1e-4, 2000);
+        assert!(estimate.speed <= 20.0);
+    }
+
+    #[test]
+    fn pll_fix() {
+        type A = Fix<P32, N24>;
+        type W = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+        type D = Fix<P32, N28>;
+
+        let param = Param::<K, W, D>::new(200.0, 0.8, 1e-4);
+        let mut state = State::<A, W>::default();
+
+        let estimate = track(&param, &mut state, 25.0, 1e-4, 2000);
+        assert!((f64::cast(estimate.speed) - 25.0).abs() < 0.1);
+        let angle = f64::cast(*estimate.angle);
+        assert!(!(0.002..=0.998).contains(&angle));
+    }
+}
@@ -0,0 +1,382 @@
+/*!
+
+## V/f startup ramp
+
+This module implements open-loop **volts-per-hertz** ramp generator
+which is used to spin up sensorless motors before switching to closed-loop control.
+
+The generator accelerates the electrical frequency toward the target (passed as input)
+with limited acceleration and evaluates the voltage magnitude proportionally to frequency:
+
+_V = V0 + K * |f|_
+
+where _V0_ is a boost voltage which compensates the stator resistance at low speed.
+The voltage is limited by maximum value.
+
+The electrical angle is advanced at each step by _Δθ = f * P_ (in cycles, where _P_ is a sampling period)
+and wrapped to range [0, 1).
+
+The acceleration profile can be linear (constant acceleration) or S-shaped
+when the jerk is limited (see [`Param::with_jerk`]). The S-shaped profile reduces
+the mechanical stress and the risk of loss of synchronism at the start and end of ramp.
+
+*/
+
+use crate::{snapshot_fields, Cast, Cyc, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+V/f ramp parameters
+
+- `F` - frequency type (also used for acceleration and jerk per step)
+- `K` - V/f ratio type
+- `V` - voltage type
+- `D` - sampling period type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<F, K, V, D> {
+    /// The maximum change of frequency per step
+    accel: F,
+    /// The maximum change of acceleration per step (zero when not limited)
+    jerk: F,
+    /// The V/f ratio
+    ratio: K,
+    /// The boost voltage
+    boost: V,
+    /// The maximum voltage
+    limit: V,
+    /// The sampling period
+    period: D,
+}
+
+impl<F, K, V, D> Param<F, K, V, D> {
+    /**
+    Init V/f ramp parameters
+
+    - `accel`: The acceleration (Hz/s)
+    - `ratio`: The V/f ratio (V/Hz)
+    - `boost`: The boost voltage at zero frequency (V)
+    - `limit`: The maximum voltage (V)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<T>(accel: T, ratio: K, boost: V, limit: V, period: T) -> Self
+    where
+        F: Cast<f64>,
+        D: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let period = f64::cast(period);
+        Self {
+            accel: F::cast(f64::cast(accel) * period),
+            jerk: F::cast(0.0),
+            ratio,
+            boost,
+            limit,
+            period: D::cast(period),
+        }
+    }
+
+    /**
+    Use S-shaped acceleration profile
+
+    - `jerk`: The maximum rate of acceleration change (Hz/s²)
+    - `period`: The sampling period (s)
+     */
+    pub fn with_jerk<T>(self, jerk: T, period: T) -> Self
+    where
+        F: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let period = f64::cast(period);
+        Self {
+            jerk: F::cast(f64::cast(jerk) * period * period),
+            ..self
+        }
+    }
+}
+
+/**
+V/f ramp state
+
+- `F` - frequency type
+- `A` - angle type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<F, A> {
+    /// Actual frequency
+    frequency: F,
+    /// Actual acceleration per step
+    accel: F,
+    /// Actual angle
+    angle: A,
+}
+
+impl<F, A> State<F, A> {
+    /// Actual frequency
+    pub fn frequency(&self) -> F
+    where
+        F: Copy,
+    {
+        self.frequency
+    }
+}
+
+/// The preset value is a frequency
+impl<F, A> ResetState for State<F, A>
+where
+    F: Default,
+    A: Default,
+{
+    type Value = F;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.frequency = value;
+        self.accel = F::default();
+    }
+}
+
+snapshot_fields! {
+    State<F, A> {
+        frequency: F,
+        accel: F,
+        angle: A,
+    }
+}
+
+/**
+V/f ramp output
+
+- `V` - voltage type
+- `F` - frequency type
+- `A` - angle type
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drive<V, F, A> {
+    /// Voltage magnitude
+    pub voltage: V,
+    /// Electrical frequency
+    pub frequency: F,
+    /// Electrical angle
+    pub angle: Cyc<A>,
+    /// Electrical angle advance per step
+    pub advance: Cyc<A>,
+}
+
+/**
+V/f ramp generator
+
+- `F` - frequency type
+- `K` - V/f ratio type
+- `V` - voltage type
+- `D` - sampling period type
+- `A` - angle type
+
+The input is a target frequency.
+*/
+#[derive(Debug)]
+pub struct Ramp<F, K, V, D, A>(PhantomData<(F, K, V, D, A)>);
+
+impl<F, K, V, D, A> Transducer for Ramp<F, K, V, D, A>
+where
+    F: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<F>
+        + Sub<F>
+        + Cast<Sum<F, F>>
+        + Cast<Diff<F, F>>
+        + Mul<F>
+        + Mul<D>,
+    Prod<F, F>: PartialOrd,
+    K: Copy + Mul<F>,
+    V: Copy + PartialOrd + Add<V> + Cast<Prod<K, F>> + Cast<Sum<V, V>>,
+    D: Copy,
+    A: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<A>
+        + Sub<A>
+        + Cast<Sum<A, A>>
+        + Cast<Diff<A, A>>
+        + Cast<Prod<F, D>>,
+{
+    type Input = F;
+    type Output = Drive<V, F, A>;
+    type Param = Param<F, K, V, D>;
+    type State = State<F, A>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, target: Self::Input) -> Self::Output {
+        let zero = F::cast(0.0);
+        let frequency = state.frequency;
+
+        let remain = if target > frequency {
+            F::cast(target - frequency)
+        } else {
+            F::cast(frequency - target)
+        };
+
+        // acceleration profile
+        state.accel = if param.jerk > zero {
+            let accel = state.accel;
+            // braking distance: a² / 2j
+            let jerk2 = F::cast(param.jerk + param.jerk);
+            if accel * accel >= jerk2 * remain {
+                let next = F::cast(accel - param.jerk);
+                if next > param.jerk {
+                    next
+                } else {
+                    param.jerk
+                }
+            } else {
+                let next = F::cast(accel + param.jerk);
+                if next < param.accel {
+                    next
+                } else {
+                    param.accel
+                }
+            }
+        } else {
+            param.accel
+        };
+
+        let step = if remain < state.accel {
+            remain
+        } else {
+            state.accel
+        };
+
+        state.frequency = if target > frequency {
+            F::cast(frequency + step)
+        } else {
+            F::cast(frequency - step)
+        };
+
+        let frequency = state.frequency;
+        let magnitude = if frequency < zero {
+            F::cast(zero - frequency)
+        } else {
+            frequency
+        };
+
+        // V = V0 + K * |f|
+        let voltage = V::cast(param.boost + V::cast(param.ratio * magnitude));
+        let voltage = if voltage > param.limit {
+            param.limit
+        } else {
+            voltage
+        };
+
+        // θ = θ + f * P
+        let advance = A::cast(frequency * param.period);
+        let one = A::cast(1.0);
+        let angle = A::cast(state.angle + advance);
+        state.angle = if angle >= one {
+            A::cast(angle - one)
+        } else if angle < A::cast(0.0) {
+            A::cast(angle + one)
+        } else {
+            angle
+        };
+
+        Drive {
+            voltage,
+            frequency,
+            angle: Cyc(state.angle),
+            advance: Cyc(advance),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, N8, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn vf_linear_f32() {
+        // 100 Hz/s with 10 ms period, 0.5 V/Hz, 1 V boost, 24 V max
+        let param = Param::<f32, f32, f32, f32>::new(100.0, 0.5, 1.0, 24.0, 0.01);
+        let mut state = State::default();
+        type R = Ramp<f32, f32, f32, f32, f32>;
+
+        let out = R::apply(&param, &mut state, 50.0);
+        assert_eq!(out.frequency, 1.0);
+        assert_eq!(out.voltage, 1.5);
+        assert_eq!(out.advance, Cyc(0.01));
+
+        for _ in 0..48 {
+            R::apply(&param, &mut state, 50.0);
+        }
+
+        let out = R::apply(&param, &mut state, 50.0);
+        assert_eq!(out.frequency, 50.0);
+        assert_eq!(out.voltage, 24.0);
+        assert_eq!(out.advance, Cyc(0.5));
+
+        // Target is reached
+        let out = R::apply(&param, &mut state, 50.0);
+        assert_eq!(out.frequency, 50.0);
+
+        // Reverse
+        let out = R::apply(&param, &mut state, -50.0);
+        assert_eq!(out.frequency, 49.0);
+    }
+
+    #[test]
+    fn vf_angle_wrap() {
+        let param = Param::<f32, f32, f32, f32>::new(1000.0, 0.5, 0.0, 24.0, 0.1);
+        let mut state = State::default();
+        type R = Ramp<f32, f32, f32, f32, f32>;
+
+        assert_eq!(R::apply(&param, &mut state, 3.0).angle, Cyc(0.3));
+        assert_eq!(R::apply(&param, &mut state, 3.0).angle, Cyc(0.6));
+        assert_eq!(R::apply(&param, &mut state, 3.0).angle, Cyc(0.90000004));
+        assert_eq!(R::apply(&param, &mut state, 3.0).angle, Cyc(0.20000005));
+    }
+
+    #[test]
+    fn vf_scurve_fix() {
+        type F = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+        type V = Fix<P32, N8>;
+        type D = Fix<P32, N24>;
+        type A = Fix<P32, N24>;
+
+        // 100 Hz/s, 1000 Hz/s², 10 ms period
+        let param =
+            Param::<F, K, V, D>::new(100.0, K::cast(0.5), V::cast(1.0), V::cast(24.0), 0.01)
+                .with_jerk(1000.0, 0.01);
+        let mut state = State::default();
+        type R = Ramp<F, K, V, D, A>;
+
+        // Acceleration grows smoothly (the jerk is truncated to 6553)
+        assert_eq!(
+            R::apply(&param, &mut state, F::cast(10.0)).frequency,
+            F::cast(0.1)
+        );
+        assert_eq!(
+            R::apply(&param, &mut state, F::cast(10.0)).frequency,
+            F::new(19659)
+        );
+        assert_eq!(
+            R::apply(&param, &mut state, F::cast(10.0)).frequency,
+            F::new(39318)
+        );
+
+        let mut out = R::apply(&param, &mut state, F::cast(10.0));
+        for _ in 0..30 {
+            out = R::apply(&param, &mut state, F::cast(10.0));
+        }
+        assert_eq!(out.frequency, F::cast(10.0));
+        assert_eq!(out.voltage, V::cast(6.0));
+    }
+}
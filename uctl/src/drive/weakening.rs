@@ -0,0 +1,215 @@
+/*!
+
+## Field weakening
+
+This module implements **field weakening** regulator for permanent magnet synchronous motors
+which extends the speed range above the base speed.
+
+When the back-EMF approaches the DC-bus voltage, the current regulators lose the voltage margin
+and can't track the references anymore. The field weakening regulator injects negative
+_d_-axis current which reduces the flux linkage and so the back-EMF.
+
+The available voltage is evaluated from the DC-bus voltage using the modulation limit _m_
+(_1 / √3_ for space vector modulation, _1 / 2_ for sine modulation, usually reduced a bit
+to keep the margin for current regulation):
+
+_e = m Vdc - |Vdq|_
+
+The margin is regulated by [proportional-integral regulator](crate::pid::Pi)
+which output is [clamped](crate::clamper::Clamper) to range _[-Imax, 0]_
+(the integral part is clamped too to prevent windup):
+
+_s = clamp(s + Ki P e)_
+
+_Id = clamp(s + Kp e)_
+
+While the margin is positive the output stays zero, so the regulator doesn't affect
+the operation below the base speed.
+
+*/
+
+use crate::{
+    clamper::{ClampParam, Clamper},
+    pid::{Pi, PiParam, PiState},
+    snapshot_fields, Cast, ResetState, Transducer,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Mul, Sub},
+};
+use typenum::{Diff, Prod};
+
+/**
+Field weakening parameters
+
+- `K` - coefficients type
+- `C` - current type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K, C> {
+    /// The modulation limit _m_
+    ratio: K,
+    /// The margin regulator
+    pi: PiParam<K, C>,
+    /// The range of current _[-Imax, 0]_
+    limit: ClampParam<C>,
+}
+
+impl<K, C> Param<K, C> {
+    /**
+    Init field weakening parameters
+
+    - `ratio`: The modulation limit _m_
+    - `kp`: The proportional gain (A/V)
+    - `ki`: The integral gain (A/V/s)
+    - `current`: The maximum magnitude of _d_-axis current (A)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<T>(ratio: T, kp: T, ki: T, current: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        C: Cast<f64>,
+        f64: Cast<T>,
+    {
+        Self {
+            ratio: K::cast(f64::cast(ratio)),
+            pi: PiParam::new(kp, ki, period),
+            limit: ClampParam::new(C::cast(-f64::cast(current)), C::cast(0.0)),
+        }
+    }
+}
+
+/**
+Field weakening state
+
+- `C` - current type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<C> {
+    /// The margin regulator
+    pi: PiState<C>,
+}
+
+/// The preset value is an integral part of regulator
+impl<C> ResetState for State<C>
+where
+    C: Default,
+{
+    type Value = C;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.pi.preset(value);
+    }
+}
+
+snapshot_fields! {
+    State<C> {
+        pi: PiState<C>,
+    }
+}
+
+/**
+Field weakening regulator
+
+- `V` - voltage type
+- `C` - current type
+- `K` - coefficients type
+
+The input is a magnitude of voltage command _|Vdq|_ and a DC-bus voltage _Vdc_.
+The output is a _d_-axis current reference.
+*/
+#[derive(Debug)]
+pub struct Regulator<V, C, K>(PhantomData<(V, C, K)>);
+
+impl<V, C, K> Transducer for Regulator<V, C, K>
+where
+    V: Copy + Sub<V> + Cast<Prod<K, V>> + Cast<Diff<V, V>>,
+    C: Copy + PartialOrd + Default + Cast<f64> + Cast<V>,
+    K: Copy + Mul<V>,
+    Pi<C, K>: Transducer<Input = (C, C), Output = C, Param = PiParam<K, C>, State = PiState<C>>,
+{
+    type Input = (V, V);
+    type Output = C;
+    type Param = Param<K, C>;
+    type State = State<C>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (voltage, dc_bus): Self::Input,
+    ) -> Self::Output {
+        type L<C> = Clamper<ClampParam<C>, C>;
+
+        // e = m Vdc - |Vdq|
+        let margin = V::cast(V::cast(param.ratio * dc_bus) - voltage);
+
+        let current = Pi::<C, K>::apply(&param.pi, &mut state.pi, (C::cast(margin), C::cast(0.0)));
+
+        // s = clamp(s + Ki P e)
+        let integral = L::apply(&param.limit, &mut (), state.pi.integral());
+        state.pi.preset(integral);
+
+        // Id = clamp(s + Kp e)
+        L::apply(&param.limit, &mut (), current)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn weakening_f32() {
+        type R = Regulator<f32, f32, f32>;
+        // m = 0.5, Kp = 0.1 A/V, Ki = 100 A/V/s, 10 A max, 1 ms period
+        let param = Param::<f32, f32>::new(0.5, 0.1, 100.0, 10.0, 0.001);
+        let mut state = State::default();
+
+        // below base speed
+        assert_eq!(R::apply(&param, &mut state, (20.0, 48.0)), 0.0);
+        assert_eq!(R::apply(&param, &mut state, (23.0, 48.0)), 0.0);
+
+        // voltage exceeds available by 2 V
+        assert_eq!(R::apply(&param, &mut state, (26.0, 48.0)), -0.4);
+        assert_eq!(R::apply(&param, &mut state, (26.0, 48.0)), -0.6);
+
+        // saturation
+        for _ in 0..100 {
+            R::apply(&param, &mut state, (26.0, 48.0));
+        }
+        assert_eq!(R::apply(&param, &mut state, (26.0, 48.0)), -10.0);
+
+        // the integral part is limited, so the output recovers quickly
+        assert_eq!(R::apply(&param, &mut state, (22.0, 48.0)), -9.6);
+    }
+
+    #[test]
+    fn weakening_fix() {
+        type V = Fix<P32, N16>;
+        type C = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+        type R = Regulator<V, C, K>;
+
+        let param = Param::<K, C>::new(0.5, 0.25, 250.0, 10.0, 0.001);
+        let mut state = State::default();
+
+        assert_eq!(
+            R::apply(&param, &mut state, (V::cast(20.0), V::cast(48.0))),
+            C::cast(0.0)
+        );
+        assert_eq!(
+            R::apply(&param, &mut state, (V::cast(26.0), V::cast(48.0))),
+            C::cast(-1.0)
+        );
+        assert_eq!(
+            R::apply(&param, &mut state, (V::cast(26.0), V::cast(48.0))),
+            C::cast(-1.5)
+        );
+    }
+}
@@ -1,3 +1,16 @@
+pub mod allpass;
+pub mod cic;
+pub mod comb;
 pub mod ema;
+pub mod farrow;
 pub mod fir;
+pub mod halfband;
+pub mod hampel;
+pub mod hilbert;
 pub mod lqe;
+pub mod median;
+pub mod oversample;
+pub mod sdm;
+pub mod slope;
+pub mod tf;
+pub mod tustin;
@@ -0,0 +1,400 @@
+/*!
+
+## All-pass filter
+
+This module implements first and second order **all-pass** filter sections.
+
+All-pass filter passes all frequencies with unity gain but shifts the phase.
+The phase shift grows with frequency, so the chain of sections can be used
+to create the pair of signals with phase difference close to 90° in the range of frequencies.
+
+The first order section shifts the phase from 0° to -180°:
+
+_H(z) = (c + z^-1) / (1 + c z^-1)_
+
+_y = c (x - y\[-1\]) + x\[-1\]_
+
+The second order section shifts the phase from 0° to -360°:
+
+_H(z) = (c2 + c1 z^-1 + z^-2) / (1 + c1 z^-1 + c2 z^-2)_
+
+_y = c2 (x - y\[-2\]) + c1 (x\[-1\] - y\[-1\]) + x\[-2\]_
+
+The coefficients can be found from s-domain specification using [bilinear transform](crate::tustin).
+
+See also [All-pass filter](https://en.wikipedia.org/wiki/All-pass_filter).
+
+*/
+
+use crate::{snapshot_fields, tustin, Cast, Core, CycleCost, OpCost, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+First order all-pass parameters
+
+- `C` - coefficient type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param1<C> {
+    /// The coefficient _c_
+    c: C,
+}
+
+impl<C> Param1<C> {
+    /**
+    Init first order all-pass parameters
+
+    - `c`: The coefficient _c_ (should be in range -1 .. 1)
+     */
+    pub fn new<T>(c: T) -> Self
+    where
+        C: Cast<f64>,
+        f64: Cast<T>,
+    {
+        Self {
+            c: C::cast(f64::cast(c)),
+        }
+    }
+
+    /**
+    Init first order all-pass parameters from time constant
+
+    The s-domain transfer function is _H(s) = (1 - T s) / (1 + T s)_,
+    so the phase shift is -90° at frequency _1 / (2 π T)_.
+
+    - `time`: The time constant _T_
+    - `period`: The sampling period
+     */
+    pub fn from_time<T>(time: T, period: T) -> Self
+    where
+        C: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let time = f64::cast(time);
+        let (_, a) = tustin::first([-time, 1.0], [time, 1.0], f64::cast(period));
+
+        Self { c: C::cast(a[1]) }
+    }
+}
+
+/**
+First order all-pass state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State1<T> {
+    /// Previous input _x\[-1\]_
+    x1: T,
+    /// Previous output _y\[-1\]_
+    y1: T,
+}
+
+impl<T> State1<T> {
+    /// Initialize first order all-pass state by zeros
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+}
+
+/// The preset of all-pass state sets the steady state for constant input
+impl<T> ResetState for State1<T>
+where
+    T: Copy + Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.x1 = value;
+        self.y1 = value;
+    }
+}
+
+snapshot_fields! {
+    State1<T> {
+        x1: T,
+        y1: T,
+    }
+}
+
+/**
+First order all-pass filter section
+
+- `T` - values type
+- `C` - coefficient type
+*/
+#[derive(Debug)]
+pub struct AllPass1<T, C>(PhantomData<(T, C)>);
+
+impl<T, C> Transducer for AllPass1<T, C>
+where
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<C, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    C: Copy + Mul<T>,
+{
+    type Input = T;
+    type Output = T;
+    type Param = Param1<C>;
+    type State = State1<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        // y = c (x - y[-1]) + x[-1]
+        let output = T::cast(T::cast(param.c * T::cast(value - state.y1)) + state.x1);
+
+        state.x1 = value;
+        state.y1 = output;
+
+        output
+    }
+}
+
+/**
+Second order all-pass parameters
+
+- `C` - coefficients type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param2<C> {
+    /// The coefficient _c1_
+    c1: C,
+    /// The coefficient _c2_
+    c2: C,
+}
+
+impl<C> Param2<C> {
+    /**
+    Init second order all-pass parameters
+
+    - `c1`: The coefficient _c1_
+    - `c2`: The coefficient _c2_ (should be in range -1 .. 1)
+     */
+    pub fn new<T>(c1: T, c2: T) -> Self
+    where
+        C: Cast<f64>,
+        f64: Cast<T>,
+    {
+        Self {
+            c1: C::cast(f64::cast(c1)),
+            c2: C::cast(f64::cast(c2)),
+        }
+    }
+
+    /**
+    Init second order all-pass parameters from natural frequency and damping
+
+    The s-domain transfer function is _H(s) = (s² - 2 ζ ω s + ω²) / (s² + 2 ζ ω s + ω²)_,
+    so the phase shift is -180° at frequency _ω_.
+
+    - `omega`: The natural frequency _ω_ (rad/s)
+    - `zeta`: The damping ratio _ζ_
+    - `period`: The sampling period
+     */
+    pub fn from_frequency<T>(omega: T, zeta: T, period: T) -> Self
+    where
+        C: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let omega = f64::cast(omega);
+        let zeta = f64::cast(zeta);
+        let omega2 = omega * omega;
+
+        let (_, a) = tustin::second(
+            [1.0, -2.0 * zeta * omega, omega2],
+            [1.0, 2.0 * zeta * omega, omega2],
+            f64::cast(period),
+        );
+
+        Self {
+            c1: C::cast(a[1]),
+            c2: C::cast(a[2]),
+        }
+    }
+}
+
+/**
+Second order all-pass state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State2<T> {
+    /// Previous inputs _x\[-1\], x\[-2\]_
+    x: [T; 2],
+    /// Previous outputs _y\[-1\], y\[-2\]_
+    y: [T; 2],
+}
+
+impl<T> State2<T> {
+    /// Initialize second order all-pass state by zeros
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+}
+
+/// The preset of all-pass state sets the steady state for constant input
+impl<T> ResetState for State2<T>
+where
+    T: Copy + Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.x = [value; 2];
+        self.y = [value; 2];
+    }
+}
+
+snapshot_fields! {
+    State2<T> {
+        x: [T; 2],
+        y: [T; 2],
+    }
+}
+
+/**
+Second order all-pass filter section
+
+- `T` - values type
+- `C` - coefficients type
+*/
+#[derive(Debug)]
+pub struct AllPass2<T, C>(PhantomData<(T, C)>);
+
+impl<T, C> Transducer for AllPass2<T, C>
+where
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<C, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    C: Copy + Mul<T>,
+{
+    type Input = T;
+    type Output = T;
+    type Param = Param2<C>;
+    type State = State2<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let [x1, x2] = state.x;
+        let [y1, y2] = state.y;
+
+        // y = c2 (x - y[-2]) + c1 (x[-1] - y[-1]) + x[-2]
+        let output = T::cast(
+            T::cast(T::cast(param.c2 * T::cast(value - y2)) + T::cast(param.c1 * T::cast(x1 - y1)))
+                + x2,
+        );
+
+        state.x = [value, x1];
+        state.y = [output, y1];
+
+        output
+    }
+}
+
+impl<T, C> CycleCost for AllPass1<T, C>
+where
+    T: OpCost,
+    C: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        C::mul(core) + 2 * T::add(core)
+    }
+}
+
+impl<T, C> CycleCost for AllPass2<T, C>
+where
+    T: OpCost,
+    C: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        2 * C::mul(core) + 4 * T::add(core)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn allpass1_f32() {
+        let param = Param1::<f32>::new(0.5);
+        let mut state = State1::new();
+        type A = AllPass1<f32, f32>;
+
+        assert_eq!(A::apply(&param, &mut state, 1.0), 0.5);
+        assert_eq!(A::apply(&param, &mut state, 0.0), 0.75);
+        assert_eq!(A::apply(&param, &mut state, 0.0), -0.375);
+        assert_eq!(A::apply(&param, &mut state, 0.0), 0.1875);
+
+        // unity gain at DC
+        state.preset(2.0);
+        assert_eq!(A::apply(&param, &mut state, 2.0), 2.0);
+
+        let param = Param1::<f32>::from_time(0.5, 3.0);
+        state.reset();
+        assert_eq!(A::apply(&param, &mut state, 1.0), 0.5);
+    }
+
+    #[test]
+    fn allpass1_fix() {
+        type T = Fix<P32, N16>;
+        type C = Fix<P32, N16>;
+
+        let param = Param1::<C>::new(-0.5);
+        let mut state = State1::new();
+        type A = AllPass1<T, C>;
+
+        assert_eq!(A::apply(&param, &mut state, T::cast(1.0)), T::cast(-0.5));
+        assert_eq!(A::apply(&param, &mut state, T::cast(0.0)), T::cast(0.75));
+        assert_eq!(A::apply(&param, &mut state, T::cast(0.0)), T::cast(0.375));
+    }
+
+    #[test]
+    fn allpass2_f32() {
+        let param = Param2::<f32>::new(0.5, 0.25);
+        let mut state = State2::new();
+        type A = AllPass2<f32, f32>;
+
+        assert_eq!(A::apply(&param, &mut state, 1.0), 0.25);
+        assert_eq!(A::apply(&param, &mut state, 0.0), 0.375);
+        assert_eq!(A::apply(&param, &mut state, 0.0), 0.75);
+
+        // unity gain at DC
+        state.preset(2.0);
+        assert_eq!(A::apply(&param, &mut state, 2.0), 2.0);
+
+        let param = Param2::<f32>::from_frequency(4.0, 0.25, 1.0);
+        state.reset();
+        assert_eq!(A::apply(&param, &mut state, 1.0), 0.6666667);
+        assert_eq!(A::apply(&param, &mut state, 0.0), 0.3333333);
+    }
+
+    #[test]
+    fn allpass2_fix() {
+        type T = Fix<P32, N16>;
+        type C = Fix<P32, N16>;
+
+        let param = Param2::<C>::new(0.5, 0.25);
+        let mut state = State2::new();
+        type A = AllPass2<T, C>;
+
+        assert_eq!(A::apply(&param, &mut state, T::cast(1.0)), T::cast(0.25));
+        assert_eq!(A::apply(&param, &mut state, T::cast(0.0)), T::cast(0.375));
+        assert_eq!(A::apply(&param, &mut state, T::cast(0.0)), T::cast(0.75));
+    }
+}
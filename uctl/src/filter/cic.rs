@@ -0,0 +1,203 @@
+/*!
+
+## CIC decimator
+
+This module implements **Cascaded Integrator-Comb** (CIC) decimation filter.
+
+CIC filter consists of _N_ integrator stages running at input rate followed by decimation by _R_
+and _N_ comb stages running at output rate. The filter does not require multiplications at all,
+so it is a good choice for the first stage of multi-stage decimator.
+
+Integrator: _y\[n\] = y\[n - 1\] + x\[n\]_
+
+Comb: _y\[n\] = x\[n\] - x\[n - 1\]_
+
+The DC gain of filter is _R<sup>N</sup>_, so the value type should have at least
+_N * log2(R)_ extra bits to hold the output.
+The integrators may overflow but it is not a problem because the modular arithmetic is used,
+the output will be correct when the type is wide enough to hold the output value.
+
+See also [Cascaded integrator–comb filter](https://en.wikipedia.org/wiki/Cascaded_integrator%E2%80%93comb_filter).
+
+*/
+
+use crate::{snapshot_fields, ResetState, Transducer, WrappingOps};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+
+/**
+CIC filter parameters
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// Decimation ratio
+    ratio: u32,
+}
+
+impl Param {
+    /**
+    Init CIC filter parameters
+
+    - `ratio`: The decimation ratio _R_ (1..)
+     */
+    pub fn new(ratio: u32) -> Self {
+        Self { ratio }
+    }
+
+    /// Decimation ratio
+    pub fn ratio(&self) -> u32 {
+        self.ratio
+    }
+}
+
+/**
+CIC filter state
+
+- `T` - values type
+- `N` - filter order
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// Integrators outputs
+    integ: GenericArray<T, N>,
+    /// Combs previous inputs
+    comb: GenericArray<T, N>,
+    /// Decimation phase
+    phase: u32,
+}
+
+/// The preset of decimator is same as reset
+impl<T, N> ResetState for State<T, N>
+where
+    T: Default,
+    N: ArrayLength<T>,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T, N> where [N: ArrayLength<T>] {
+        integ: GenericArray<T, N>,
+        comb: GenericArray<T, N>,
+        phase: u32,
+    }
+}
+
+/**
+CIC decimator
+
+- `T` - values type
+- `N` - filter order
+*/
+#[derive(Debug)]
+pub struct Decimator<T, N>(PhantomData<(T, N)>);
+
+impl<T, N> Transducer for Decimator<T, N>
+where
+    T: Copy + WrappingOps,
+    N: ArrayLength<T>,
+{
+    type Input = T;
+    type Output = Option<T>;
+    type Param = Param;
+    type State = State<T, N>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let value = state.integ.iter_mut().fold(value, |value, integ| {
+            *integ = integ.wrapping_add(value);
+            *integ
+        });
+
+        state.phase += 1;
+        if state.phase < param.ratio {
+            return None;
+        }
+        state.phase = 0;
+
+        Some(state.comb.iter_mut().fold(value, |value, comb| {
+            let result = value.wrapping_sub(*comb);
+            *comb = value;
+            result
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Cast;
+    use typenum::{N8, P16, U2};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn cic_i16_n2_r4() {
+        let param = Param::new(4);
+        let mut state = State::<i16, U2>::default();
+        type D = Decimator<i16, U2>;
+
+        let mut output = [0; 4];
+        let mut n = 0;
+        for _ in 0..16 {
+            if let Some(value) = D::apply(&param, &mut state, 1) {
+                output[n] = value;
+                n += 1;
+            }
+        }
+
+        // DC gain is R^N = 16
+        assert_eq!(output, [10, 16, 16, 16]);
+    }
+
+    #[test]
+    fn cic_wrapping() {
+        let param = Param::new(8);
+        let mut state = State::<i8, U2>::default();
+        type D = Decimator<i8, U2>;
+
+        // DC gain is R^N = 64 so the output fits to i8
+        // but integrators overflow many times before
+        let mut out = None;
+        for _ in 0..64 {
+            if let Some(value) = D::apply(&param, &mut state, 1) {
+                out = Some(value);
+            }
+        }
+        assert_eq!(out, Some(64));
+    }
+
+    #[test]
+    fn cic_fix() {
+        type T = Fix<P16, N8>;
+
+        let param = Param::new(2);
+        let mut state = State::<T, U2>::default();
+        type D = Decimator<T, U2>;
+
+        assert_eq!(D::apply(&param, &mut state, T::cast(0.5)), None);
+        assert_eq!(
+            D::apply(&param, &mut state, T::cast(0.5)),
+            Some(T::cast(1.5))
+        );
+        assert_eq!(D::apply(&param, &mut state, T::cast(0.5)), None);
+        assert_eq!(
+            D::apply(&param, &mut state, T::cast(0.5)),
+            Some(T::cast(2.0))
+        );
+        assert_eq!(D::apply(&param, &mut state, T::cast(0.5)), None);
+        assert_eq!(
+            D::apply(&param, &mut state, T::cast(0.5)),
+            Some(T::cast(2.0))
+        );
+    }
+}
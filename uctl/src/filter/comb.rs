@@ -0,0 +1,235 @@
+/*!
+
+## Comb filter
+
+This module implements feedforward and feedback **comb** filters.
+
+The comb filter adds the delayed signal to itself, so the magnitude response
+has the regularly spaced notches or peaks at the harmonics of frequency _1 / (D P)_,
+where _D_ is a delay in samples and _P_ is a sampling period.
+
+The feedforward comb filter:
+
+_y = x + g x\[-D\]_
+
+The feedback comb filter:
+
+_y = x + g y\[-D\]_
+
+The feedforward filter with gain _-1_ cancels any periodic signal with period _D_
+(including constant component), so it can be used to reject the periodic disturbance
+with known period.
+The feedback filter with gain close to _1_ has high gain at the harmonics of period _D_,
+so it can be used as internal model of periodic signal in repetitive control.
+The feedback filter is stable only when _|g| < 1_.
+
+The delay _D_ is a length of delay line which is used as filter state.
+
+See also [Comb filter](https://en.wikipedia.org/wiki/Comb_filter).
+
+*/
+
+use crate::{Cast, Core, CycleCost, DelayLine, OpCost, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+use typenum::{Prod, Sum};
+
+/**
+Comb filter parameters
+
+- `G` - gain type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<G> {
+    /// The gain of delayed signal
+    gain: G,
+}
+
+impl<G> Param<G> {
+    /**
+    Init comb filter parameters
+
+    - `gain`: The gain of delayed signal _g_
+     */
+    pub fn new<T>(gain: T) -> Self
+    where
+        G: Cast<f64>,
+        f64: Cast<T>,
+    {
+        Self {
+            gain: G::cast(f64::cast(gain)),
+        }
+    }
+}
+
+/// Comb filter state
+///
+/// - `L` - delay line type
+///
+/// The delay of filter is a length of delay line.
+pub type State<L> = L;
+
+/**
+Feedforward comb filter
+
+- `O` - output values type
+- `G` - gain type
+- `L` - delay line type (of input values)
+*/
+#[derive(Debug)]
+pub struct FeedForward<O, G, L>(PhantomData<(O, G, L)>);
+
+impl<O, G, L> Transducer for FeedForward<O, G, L>
+where
+    G: Copy + Mul<L::Value>,
+    O: Cast<L::Value> + Cast<Prod<G, L::Value>> + Add<O> + Cast<Sum<O, O>>,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    type Input = L::Value;
+    type Output = O;
+    type Param = Param<G>;
+    type State = State<L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let output = match state.oldest() {
+            // y = x + g x[-D]
+            Some(delayed) if state.is_full() => {
+                O::cast(O::cast(value) + O::cast(param.gain * delayed))
+            }
+            _ => O::cast(value),
+        };
+
+        state.push(value);
+        output
+    }
+}
+
+/**
+Feedback comb filter
+
+- `G` - gain type
+- `L` - delay line type (of output values)
+*/
+#[derive(Debug)]
+pub struct FeedBack<G, L>(PhantomData<(G, L)>);
+
+impl<G, L> Transducer for FeedBack<G, L>
+where
+    G: Copy + Mul<L::Value>,
+    L: DelayLine,
+    L::Value: Add<L::Value> + Cast<Prod<G, L::Value>> + Cast<Sum<L::Value, L::Value>>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    type Input = L::Value;
+    type Output = L::Value;
+    type Param = Param<G>;
+    type State = State<L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let output = match state.oldest() {
+            // y = x + g y[-D]
+            Some(delayed) if state.is_full() => {
+                L::Value::cast(value + L::Value::cast(param.gain * delayed))
+            }
+            _ => value,
+        };
+
+        state.push(output);
+        output
+    }
+}
+
+impl<O, G, L> CycleCost for FeedForward<O, G, L>
+where
+    G: OpCost,
+    O: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        G::mul(core) + O::add(core)
+    }
+}
+
+impl<G, L> CycleCost for FeedBack<G, L>
+where
+    G: OpCost,
+    L: DelayLine,
+    L::Value: OpCost,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    fn cycles(core: Core) -> u32 {
+        G::mul(core) + L::Value::add(core)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pfdl::Store as DL;
+    use typenum::{N16, P32, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn comb_feedforward_f32() {
+        let param = Param::<f32>::new(0.5);
+        let mut state = DL::<f32, U3>::from(0.0);
+        type C = FeedForward<f32, f32, DL<f32, U3>>;
+
+        assert_eq!(C::apply(&param, &mut state, 1.0), 1.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.5);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+    }
+
+    #[test]
+    fn comb_feedforward_reject() {
+        let param = Param::<f32>::new(-1.0);
+        let mut state = DL::<f32, U3>::from(0.0);
+        type C = FeedForward<f32, f32, DL<f32, U3>>;
+
+        // the periodic disturbance is cancelled after the first period
+        let disturbance = [0.5, 2.0, -1.0];
+        for value in &disturbance {
+            assert_eq!(C::apply(&param, &mut state, *value), *value);
+        }
+        for value in disturbance.iter().cycle().take(6) {
+            assert_eq!(C::apply(&param, &mut state, *value), 0.0);
+        }
+    }
+
+    #[test]
+    fn comb_feedback_f32() {
+        let param = Param::<f32>::new(0.5);
+        let mut state = DL::<f32, U3>::from(0.0);
+        type C = FeedBack<f32, DL<f32, U3>>;
+
+        assert_eq!(C::apply(&param, &mut state, 1.0), 1.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.5);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.0);
+        assert_eq!(C::apply(&param, &mut state, 0.0), 0.25);
+    }
+
+    #[test]
+    fn comb_feedback_fix() {
+        type T = Fix<P32, N16>;
+        type G = Fix<P32, N16>;
+
+        let param = Param::<G>::new(-0.5);
+        let mut state = DL::<T, U3>::from(T::cast(0.0));
+        type C = FeedBack<G, DL<T, U3>>;
+
+        assert_eq!(C::apply(&param, &mut state, T::cast(2.0)), T::cast(2.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(0.0)), T::cast(0.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(0.0)), T::cast(0.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(1.0)), T::cast(0.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(0.0)), T::cast(0.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(0.0)), T::cast(0.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(0.0)), T::cast(0.0));
+    }
+}
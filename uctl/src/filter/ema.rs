@@ -15,11 +15,18 @@ There are different ways of definition a filter parameters, such as:
 3. Through smoothing time
 4. As an 1st-order transmission behavior
 
+The variable-period filter [`VarFilter`] takes the sampling period together with each value
+and evaluates the α factor at each step as for 1st-order transmission behavior,
+which is useful for the event-driven or sporadic sampling.
+
 See also [Exponential moving average](https://en.wikipedia.org/wiki/Moving_average#Exponential_moving_average).
 
 */
 
-use crate::{Cast, Transducer};
+use crate::{
+    Bounds, Cast, Core, CycleCost, Interpolate, NoiseBudget, OpCost, RangeOverflow, ResetState,
+    Snapshot, Transducer, ValueBounds, ValueResolution, WarmStart, Wire, WorstCase,
+};
 use core::{
     marker::PhantomData,
     ops::{Add, Div, Mul, Sub},
@@ -159,6 +166,35 @@ impl<A> Param<A> {
     }
 }
 
+/// Only the alpha factor is transferred
+impl<A> Wire for Param<A>
+where
+    A: Copy + Cast<f64> + Sub<A> + Cast<Diff<A, A>> + Wire,
+{
+    const SIZE: usize = A::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        self.alpha.write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        Self::from_alpha(A::read_from(buf))
+    }
+}
+
+impl<A, K> Interpolate<K> for Param<A>
+where
+    A: Interpolate<K>,
+    K: Copy,
+{
+    fn interpolate(&self, to: &Self, factor: K) -> Self {
+        Self {
+            alpha: self.alpha.interpolate(&to.alpha, factor),
+            one_sub_alpha: self.one_sub_alpha.interpolate(&to.one_sub_alpha, factor),
+        }
+    }
+}
+
 /**
 EMA filter state
 
@@ -179,6 +215,44 @@ impl<O> State<O> {
     pub fn new(value: O) -> Self {
         Self { last_value: value }
     }
+
+    /// The last output value
+    pub fn value(&self) -> O
+    where
+        O: Copy,
+    {
+        self.last_value
+    }
+}
+
+impl<O> ResetState for State<O>
+where
+    O: Default,
+{
+    type Value = O;
+
+    fn reset(&mut self) {
+        self.last_value = O::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.last_value = value;
+    }
+}
+
+impl<O> Snapshot for State<O>
+where
+    O: Snapshot,
+{
+    const SIZE: usize = O::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        self.last_value.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        self.last_value.restore(buffer)
+    }
 }
 
 /**
@@ -209,11 +283,146 @@ where
     }
 }
 
+impl<A, I, O> WarmStart for Filter<A, I, O>
+where
+    I: Copy,
+    O: Cast<I>,
+{
+    type Input = I;
+    type State = State<O>;
+
+    fn warm_start(state: &mut Self::State, value: &Self::Input) {
+        state.last_value = O::cast(*value);
+    }
+}
+
+impl<A, I, O> CycleCost for Filter<A, I, O>
+where
+    A: OpCost,
+    O: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        2 * A::mul(core) + O::add(core)
+    }
+}
+
+/**
+Worst-case output range of EMA filter
+
+- `input`: The range of input values
+
+The output is a weighted average of input values and initial zero state.
+*/
+pub const fn bounds(input: Bounds) -> Bounds {
+    input.hull(Bounds::point(0.0))
+}
+
+impl<A, I, O> WorstCase for Filter<A, I, O>
+where
+    Self: Transducer<Output = O>,
+    O: ValueBounds,
+{
+    fn output_bounds(_param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        bounds(input).check::<O>()
+    }
+}
+
+/// Both products is rounded and the rounding noise is accumulated by the feedback
+impl<A, I, O> NoiseBudget for Filter<A, I, O>
+where
+    Self: Transducer<Param = Param<A>, Output = O>,
+    A: Copy,
+    O: ValueResolution,
+    f64: Cast<A>,
+{
+    fn output_noise(param: &Self::Param, input: f64) -> f64 {
+        let alpha = f64::cast(param.alpha);
+        let pole = f64::cast(param.one_sub_alpha);
+        // the power gain of feedback 1 / (1 - (1 - α)²)
+        let feedback = 1.0 / (1.0 - pole * pole);
+        (alpha * alpha * input + 2.0 * O::NOISE) * feedback
+    }
+}
+
+/**
+Variable-period EMA filter parameters
+
+- `A` - filter weights type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct VarParam<A> {
+    /// The time constant _T_
+    time: A,
+}
+
+impl<A> VarParam<A> {
+    /**
+    Init variable-period EMA parameters
+
+    - `time`: The time constant _T_ (s)
+     */
+    pub fn new<V>(time: V) -> Self
+    where
+        A: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            time: A::cast(f64::cast(time)),
+        }
+    }
+}
+
+/**
+Variable-period EMA filter
+
+- `A` - filter weights type
+- `I` - filter input value type
+- `O` - filter output value type
+
+The input is a tuple of value and the period since previous step.
+
+_α = P / (T + P)_
+ */
+#[derive(Debug)]
+pub struct VarFilter<A, I, O>(PhantomData<(A, I, O)>);
+
+impl<A, I, O> Transducer for VarFilter<A, I, O>
+where
+    O: Copy + Add<O> + Cast<Prod<A, I>> + Cast<Prod<A, O>> + Cast<Sum<O, O>>,
+    A: Copy
+        + Cast<f64>
+        + Add<A>
+        + Sub<A>
+        + Mul<A>
+        + Mul<I>
+        + Mul<O>
+        + Cast<Sum<A, A>>
+        + Cast<Diff<A, A>>
+        + Cast<Prod<A, A>>
+        + Cast<Quot<Prod<A, A>, A>>,
+    Prod<A, A>: Cast<A> + Div<A>,
+{
+    type Input = (I, A);
+    type Output = O;
+    type Param = VarParam<A>;
+    type State = State<O>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (value, period): Self::Input,
+    ) -> Self::Output {
+        let alpha = A::cast(Prod::<A, A>::cast(period) / A::cast(param.time + period));
+
+        Filter::<A, I, O>::apply(&Param::from_alpha(alpha), state, value)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use typenum::*;
-    use ufix::bin::Fix;
+    use ufix::{assert_fix_eq, bin::Fix};
 
     #[test]
     fn from_n_float() {
@@ -237,19 +446,13 @@ mod test {
 
         let param = Param::<A>::from_steps(Fix::<P31, N18>::cast(2.0));
 
-        assert_eq!(param.alpha, Fix::cast(0.6666667));
-        assert_eq!(param.one_sub_alpha, Fix::cast(0.333336));
+        assert_fix_eq!(param.alpha, 2.0 / 3.0);
+        assert_fix_eq!(param.one_sub_alpha, 1.0 / 3.0);
 
         let mut state = State::<V>::new(Fix::cast(0.0));
 
-        assert_eq!(
-            Filter::apply(&param, &mut state, V::cast(1.0)),
-            V::cast(0.6666667)
-        );
-        assert_eq!(
-            Filter::apply(&param, &mut state, V::cast(1.0)),
-            V::cast(0.8888889)
-        );
+        assert_fix_eq!(Filter::apply(&param, &mut state, V::cast(1.0)), 2.0 / 3.0);
+        assert_fix_eq!(Filter::apply(&param, &mut state, V::cast(1.0)), 8.0 / 9.0);
     }
 
     #[test]
@@ -259,18 +462,41 @@ mod test {
 
         let param = Param::<A>::from_steps(Fix::<P16, N11>::cast(2.0));
 
-        assert_eq!(param.alpha, Fix::cast(0.6666667));
-        assert_eq!(param.one_sub_alpha, Fix::cast(0.333336));
+        assert_fix_eq!(param.alpha, 2.0 / 3.0);
+        assert_fix_eq!(param.one_sub_alpha, 1.0 / 3.0);
 
         let mut state = State::<V>::new(Fix::cast(0.0));
 
+        assert_fix_eq!(Filter::apply(&param, &mut state, V::cast(1.0)), 2.0 / 3.0);
+        assert_fix_eq!(Filter::apply(&param, &mut state, V::cast(1.0)), 8.0 / 9.0);
+    }
+
+    #[test]
+    fn variable_period() {
+        type F = VarFilter<f32, f32, f32>;
+        let param = VarParam::<f32>::new(1.0);
+        let mut state = State::<f32>::new(0.0);
+
+        assert_eq!(F::apply(&param, &mut state, (1.0, 1.0)), 0.5);
+        assert_eq!(F::apply(&param, &mut state, (1.0, 3.0)), 0.875);
+        assert_eq!(F::apply(&param, &mut state, (0.0, 0.25)), 0.7);
+    }
+
+    #[test]
+    fn variable_period_fix() {
+        type A = Fix<P32, N24>;
+        type V = Fix<P32, N16>;
+        type F = VarFilter<A, V, V>;
+        let param = VarParam::<A>::new(1.0);
+        let mut state = State::<V>::new(V::cast(0.0));
+
         assert_eq!(
-            Filter::apply(&param, &mut state, V::cast(1.0)),
-            V::cast(0.6666667)
+            F::apply(&param, &mut state, (V::cast(1.0), A::cast(1.0))),
+            V::cast(0.5)
         );
         assert_eq!(
-            Filter::apply(&param, &mut state, V::cast(1.0)),
-            V::cast(0.8888889)
+            F::apply(&param, &mut state, (V::cast(1.0), A::cast(3.0))),
+            V::cast(0.875)
         );
     }
 }
@@ -0,0 +1,361 @@
+/*!
+
+## Farrow resampler
+
+This module implements fractional resampler based on **Farrow structure** with cubic Lagrange interpolation.
+
+The resampler converts the signal between two sample rates which is not an integer multiples of each other.
+For example, the 1 kHz control loop can consume the 833 Hz sensor data.
+
+The resampler is applied at output rate. The input is an optional value which should be
+the new input sample when it arrived since previous output tick.
+Resampler keeps four last input samples and interpolates between two middle of them
+using the fractional position _μ_ in range \[0, 1).
+The phase is advanced by ratio of input to output rate at each tick
+and decreased by one when the input sample is consumed, so the residual phase is kept between samples.
+
+The position _μ_ is the phase clamped to \[0, 1), so the polynomial is never extrapolated.
+When the input sample arrives early the output is held at the first middle sample
+and when it is late the output is held at the second middle sample.
+The phase itself isn't clamped (except of the stalled input), so the jitter of input samples
+doesn't break the phase continuity: the output returns to the regular position
+as soon as the samples arrives in time.
+
+The Farrow structure evaluates polynomial coefficients from input samples using constant matrix
+and then calculates output using Horner's method:
+
+_y(μ) = ((c3 * μ + c2) * μ + c1) * μ + c0_
+
+The resampler introduces a delay of two input samples.
+
+Because only one input sample per output tick can be consumed, the input rate should not exceed the output rate.
+To reduce the sample rate use decimators before resampler.
+
+See also [Farrow filter](https://en.wikipedia.org/wiki/Farrow_filter).
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer, WarmStart};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/// Cubic Lagrange interpolation matrix
+const LAGRANGE: [[f64; 4]; 4] = [
+    [0.0, 1.0, 0.0, 0.0],
+    [-1.0 / 3.0, -0.5, 1.0, -1.0 / 6.0],
+    [0.5, -1.0, 0.5, 0.0],
+    [-1.0 / 6.0, 0.5, -0.5, 1.0 / 6.0],
+];
+
+/**
+Resampler parameters
+
+- `F` - fractional position type
+- `K` - interpolation matrix coefficients type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<F, K> {
+    /// The ratio of input rate to output rate
+    step: F,
+    /// Farrow matrix
+    matrix: [[K; 4]; 4],
+}
+
+impl<F, K> Param<F, K> {
+    /**
+    Init resampler parameters
+
+    - `step`: The ratio of input sampling rate to output sampling rate (0..1]
+     */
+    pub fn new<S>(step: S) -> Self
+    where
+        F: Cast<S>,
+        K: Copy + Cast<f64>,
+    {
+        let mut matrix = [[K::cast(0.0); 4]; 4];
+
+        for (row, coeffs) in matrix.iter_mut().zip(LAGRANGE.iter()) {
+            for (k, c) in row.iter_mut().zip(coeffs.iter()) {
+                *k = K::cast(*c);
+            }
+        }
+
+        Self {
+            step: F::cast(step),
+            matrix,
+        }
+    }
+}
+
+/**
+Resampler state
+
+- `I` - input value type
+- `F` - fractional position type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<I, F> {
+    /// Last input samples (oldest first)
+    history: [I; 4],
+    /// Fractional phase between two middle samples
+    phase: F,
+}
+
+impl<I, F> State<I, F> {
+    /**
+    Initialize resampler state
+
+    - `value`: The initial value
+     */
+    pub fn new(value: I) -> Self
+    where
+        I: Copy,
+        F: Cast<f64>,
+    {
+        Self {
+            history: [value; 4],
+            // ready to consume the first sample
+            phase: F::cast(1.0),
+        }
+    }
+}
+
+impl<I, F> ResetState for State<I, F>
+where
+    I: Copy + Default,
+    F: Cast<f64>,
+{
+    type Value = I;
+
+    fn reset(&mut self) {
+        *self = Self::new(I::default());
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        *self = Self::new(value);
+    }
+}
+
+snapshot_fields! {
+    State<I, F> {
+        history: [I; 4],
+        phase: F,
+    }
+}
+
+/**
+Farrow resampler
+
+- `I` - input value type
+- `O` - output value type
+- `F` - fractional position type
+- `K` - interpolation matrix coefficients type
+- `C` - polynomial coefficients type
+*/
+#[derive(Debug)]
+pub struct Resampler<I, O, F, K, C>(PhantomData<(I, O, F, K, C)>);
+
+impl<I, O, F, K, C> Transducer for Resampler<I, O, F, K, C>
+where
+    I: Copy,
+    O: Cast<C>,
+    F: Copy + PartialOrd + Cast<f64> + Add<F> + Sub<F> + Cast<Sum<F, F>> + Cast<Diff<F, F>>,
+    K: Copy + Mul<I>,
+    C: Copy + Cast<f64> + Cast<Prod<K, I>> + Add<C> + Cast<Sum<C, C>> + Mul<F> + Cast<Prod<C, F>>,
+{
+    type Input = Option<I>;
+    type Output = O;
+    type Param = Param<F, K>;
+    type State = State<I, F>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let zero = F::cast(0.0);
+        let one = F::cast(1.0);
+        let newest = F::cast(2.0);
+
+        if let Some(value) = value {
+            state.history.rotate_left(1);
+            state.history[3] = value;
+
+            // keep the residual phase
+            state.phase = F::cast(state.phase - one);
+        }
+
+        let mut coeffs = [C::cast(0.0); 4];
+
+        for (c, row) in coeffs.iter_mut().zip(param.matrix.iter()) {
+            *c = row
+                .iter()
+                .zip(state.history.iter())
+                .fold(C::cast(0.0), |accum, (k, x)| {
+                    C::cast(accum + C::cast(*k * *x))
+                });
+        }
+
+        let mu = if state.phase < zero {
+            // the sample arrived early
+            zero
+        } else if state.phase < one {
+            state.phase
+        } else {
+            // the sample is late, y(1) is the second middle sample itself
+            one
+        };
+
+        // y = ((c3 * μ + c2) * μ + c1) * μ + c0
+        let result = coeffs[..3]
+            .iter()
+            .rev()
+            .fold(coeffs[3], |accum, c| C::cast(C::cast(accum * mu) + *c));
+
+        state.phase = F::cast(state.phase + param.step);
+        if state.phase > newest {
+            // the input is stalled
+            state.phase = newest;
+        }
+
+        O::cast(result)
+    }
+}
+
+impl<I, O, F, K, C> WarmStart for Resampler<I, O, F, K, C>
+where
+    I: Copy,
+    F: Cast<f64>,
+{
+    type Input = Option<I>;
+    type State = State<I, F>;
+
+    fn warm_start(state: &mut Self::State, value: &Self::Input) {
+        if let Some(value) = value {
+            *state = State::new(*value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N12, N20, N8, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn farrow_f32_ramp() {
+        let param = Param::<f32, f32>::new(0.5);
+        let mut state = State::<f32, f32>::new(0.0);
+        type R = Resampler<f32, f32, f32, f32, f32>;
+
+        // The ramp is interpolated exactly with two samples delay
+        let mut outputs = [0.0; 12];
+        for (n, out) in outputs.iter_mut().enumerate() {
+            let input = if n % 2 == 0 {
+                Some((n / 2) as f32 + 2.0)
+            } else {
+                None
+            };
+            *out = R::apply(&param, &mut state, input);
+        }
+
+        assert_eq!(&outputs[8..], &[4.0, 4.5, 5.0, 5.5]);
+    }
+
+    #[test]
+    fn farrow_f32_jitter() {
+        let param = Param::<f32, f32>::new(0.5);
+        let mut state = State::<f32, f32>::new(0.0);
+        type R = Resampler<f32, f32, f32, f32, f32>;
+
+        for n in 0..8 {
+            let input = if n % 2 == 0 {
+                Some((n / 2) as f32 + 1.0)
+            } else {
+                None
+            };
+            R::apply(&param, &mut state, input);
+        }
+
+        // The late sample doesn't shift the phase
+        assert_eq!(R::apply(&param, &mut state, None), 3.0);
+        assert_eq!(R::apply(&param, &mut state, Some(5.0)), 3.5);
+        assert_eq!(R::apply(&param, &mut state, Some(6.0)), 4.0);
+        assert_eq!(R::apply(&param, &mut state, None), 4.5);
+        // The position is held at the second middle sample when input is stalled
+        assert_eq!(R::apply(&param, &mut state, None), 5.0);
+        assert_eq!(R::apply(&param, &mut state, None), 5.0);
+        assert_eq!(R::apply(&param, &mut state, None), 5.0);
+        assert_eq!(R::apply(&param, &mut state, Some(7.0)), 6.0);
+        // The early sample holds the position at the first middle sample
+        assert_eq!(R::apply(&param, &mut state, Some(8.0)), 6.5);
+        assert_eq!(R::apply(&param, &mut state, Some(9.0)), 7.0);
+        assert_eq!(R::apply(&param, &mut state, Some(10.0)), 8.0);
+        assert_eq!(R::apply(&param, &mut state, None), 8.0);
+        assert_eq!(R::apply(&param, &mut state, Some(11.0)), 9.0);
+        // The phase returns to the regular position
+        assert_eq!(R::apply(&param, &mut state, None), 9.0);
+        assert_eq!(R::apply(&param, &mut state, None), 9.5);
+        assert_eq!(R::apply(&param, &mut state, Some(12.0)), 10.0);
+    }
+
+    #[test]
+    fn farrow_f64_uniform() {
+        let param = Param::<f64, f64>::new(0.833);
+        let mut state = State::<f64, f64>::new(0.0);
+        type R = Resampler<f64, f64, f64, f64, f64>;
+
+        // 833 Hz ramp to 1 kHz
+        let mut input = 0;
+        let mut prev = 0.0;
+        for tick in 0..40 {
+            let value = if input * 1000 <= tick * 833 {
+                input += 1;
+                Some(input as f64)
+            } else {
+                None
+            };
+            let out = R::apply(&param, &mut state, value);
+            if tick > 4 {
+                assert!((out - prev - 0.833).abs() < 1e-9);
+            }
+            prev = out;
+        }
+    }
+
+    #[test]
+    fn farrow_fix() {
+        type I = Fix<P16, N8>;
+        type O = Fix<P16, N8>;
+        type F = Fix<P16, N12>;
+        type K = Fix<P16, N12>;
+        type C = Fix<P32, N20>;
+
+        // 833 Hz to 1 kHz
+        let param = Param::<F, K>::new(0.833);
+        let mut state = State::<I, F>::new(I::cast(0.0));
+        type R = Resampler<I, O, F, K, C>;
+
+        let mut input = 0;
+        let mut prev = O::cast(0.0);
+
+        for tick in 0..24 {
+            let value = if input * 1000 <= tick * 833 {
+                input += 1;
+                Some(I::cast(input))
+            } else {
+                None
+            };
+            let out = R::apply(&param, &mut state, value);
+            if tick > 4 {
+                let step = f64::cast(O::cast(out - prev));
+                assert!((step - 0.833).abs() < 0.01);
+            }
+            prev = out;
+        }
+
+        assert_eq!(input, 20);
+        // The output is delayed by two input samples
+        assert!((f64::cast(prev) - (23.0 * 0.833 - 1.0)).abs() < 0.01);
+    }
+}
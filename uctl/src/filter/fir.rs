@@ -10,7 +10,10 @@ See also [Finite impulse response](https://en.wikipedia.org/wiki/Finite_impulse_
 
 */
 
-use crate::{Cast, DelayLine, Transducer};
+use crate::{
+    Bounds, Cast, Core, CycleCost, DelayLine, NoiseBudget, OpCost, RangeOverflow, ResetState,
+    Transducer, ValueBounds, ValueResolution, WarmStart, WorstCase,
+};
 use core::{
     marker::PhantomData,
     ops::{Add, Mul},
@@ -67,6 +70,89 @@ where
     }
 }
 
+impl<O, B, L> WarmStart for Filter<O, B, L>
+where
+    L: DelayLine + ResetState<Value = <L as DelayLine>::Value>,
+    for<'a> &'a L: IntoIterator<Item = <L as DelayLine>::Value>,
+{
+    type Input = <L as DelayLine>::Value;
+    type State = State<L>;
+
+    fn warm_start(state: &mut Self::State, value: &Self::Input) {
+        state.preset(*value);
+    }
+}
+
+impl<O, B, L> CycleCost for Filter<O, B, L>
+where
+    B: OpCost,
+    O: OpCost,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    fn cycles(core: Core) -> u32 {
+        let order = L::Length::to_u32();
+        (order + 1) * B::mul(core) + order * O::add(core)
+    }
+}
+
+/**
+Worst-case output range of FIR filter
+
+- `input`: The range of input values
+- `coefficients`: The coefficients of filter
+
+The delay line is initially filled by zeros.
+*/
+pub const fn bounds(input: Bounds, coefficients: &[f64]) -> Bounds {
+    let input = input.hull(Bounds::point(0.0));
+    let mut sum = Bounds::point(0.0);
+    let mut index = 0;
+    while index < coefficients.len() {
+        sum = sum.add(input.scale(coefficients[index]));
+        index += 1;
+    }
+    sum
+}
+
+impl<O, B, L> WorstCase for Filter<O, B, L>
+where
+    Self: Transducer<Param = Param<B, L::Length>, Output = O>,
+    B: Copy,
+    O: ValueBounds,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+    L::Length: Add<B1>,
+    Add1<L::Length>: ArrayLength<B> + ArrayLength<f64>,
+    f64: Cast<B>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        let coefficients: GenericArray<f64, Add1<L::Length>> =
+            param.iter().map(|b| f64::cast(*b)).collect();
+        bounds(input, &coefficients).check::<O>()
+    }
+}
+
+/// Each product is rounded
+impl<O, B, L> NoiseBudget for Filter<O, B, L>
+where
+    Self: Transducer<Param = Param<B, L::Length>, Output = O>,
+    B: Copy,
+    O: ValueResolution,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+    L::Length: Add<B1>,
+    Add1<L::Length>: ArrayLength<B>,
+    f64: Cast<B>,
+{
+    fn output_noise(param: &Self::Param, input: f64) -> f64 {
+        param.iter().fold(0.0, |noise, b| {
+            let b = f64::cast(*b);
+            noise + b * b * input + O::NOISE
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
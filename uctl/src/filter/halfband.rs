@@ -0,0 +1,241 @@
+/*!
+
+## Half-band decimator
+
+This module implements **half-band** FIR filter with decimation by two using polyphase evaluation.
+
+Half-band filter is a FIR filter which cutoff frequency is a quarter of sampling rate.
+The impulse response of such filter has each second coefficient equal to zero except the center one
+which is usually equal to ½.
+
+The polyphase structure splits the input stream to two phases:
+
+- the odd samples is convolved with non-zero coefficients
+- the even samples is simply delayed to be multiplied by center coefficient
+
+The output is evaluated only once per two input samples, so the decimator requires
+about four times less multiplications than naive FIR filter of same order followed by decimation.
+
+The filter of length _4M - 1_ has _2M_ non-zero coefficients (except center) and requires
+the odd phase delay line of length _2M_ and the even phase delay line of length _M_.
+
+Usually several half-band decimators is chained to build efficient multi-stage decimator.
+
+See also [Half-band filter](https://en.wikipedia.org/wiki/Half-band_filter).
+
+*/
+
+use crate::{snapshot_fields, Cast, DelayLine, ResetState, Transducer, WarmStart};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Prod, Sum};
+
+/**
+Half-band filter parameters
+
+- `B` - filter weights type
+- `N` - the number of non-zero coefficients except center
+*/
+#[derive(Debug, Clone)]
+pub struct Param<B, N>
+where
+    N: ArrayLength<B>,
+{
+    /// Non-zero coefficients _h\[0\], h\[2\], .. h\[4M - 2\]_
+    taps: GenericArray<B, N>,
+    /// Center coefficient _h\[2M - 1\]_
+    center: B,
+}
+
+impl<B, N> Param<B, N>
+where
+    N: ArrayLength<B>,
+{
+    /**
+    Init half-band filter parameters
+
+    - `taps`: The non-zero coefficients of impulse response _h\[0\], h\[2\], .. h\[4M - 2\]_
+    - `center`: The center coefficient _h\[2M - 1\]_ (usually ½)
+     */
+    pub fn new(taps: GenericArray<B, N>, center: B) -> Self {
+        Self { taps, center }
+    }
+}
+
+/**
+Half-band filter state
+
+- `L` - odd phase delay line type
+- `D` - even phase delay line type
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<L, D> {
+    /// The odd samples
+    odd: L,
+    /// The delayed even samples
+    even: D,
+}
+
+impl<L, D> State<L, D> {
+    /**
+    Initialize filter state
+
+    - `odd`: The odd phase delay line (length _2M_)
+    - `even`: The even phase delay line (length _M_)
+     */
+    pub fn new(odd: L, even: D) -> Self {
+        Self { odd, even }
+    }
+}
+
+impl<L, D> ResetState for State<L, D>
+where
+    L: ResetState,
+    D: ResetState<Value = L::Value>,
+    L::Value: Copy,
+{
+    type Value = L::Value;
+
+    fn reset(&mut self) {
+        self.odd.reset();
+        self.even.reset();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.odd.preset(value);
+        self.even.preset(value);
+    }
+}
+
+snapshot_fields! {
+    State<L, D> {
+        odd: L,
+        even: D,
+    }
+}
+
+/**
+Half-band decimator
+
+- `O` - output values type
+- `B` - filter weights type
+- `L` - odd phase delay line type
+- `D` - even phase delay line type
+
+The input is a pair of consecutive samples (even, odd).
+*/
+#[derive(Debug)]
+pub struct Decimator<O, B, L, D>(PhantomData<(O, B, L, D)>);
+
+impl<O, B, L, D> Transducer for Decimator<O, B, L, D>
+where
+    B: Copy + Mul<L::Value>,
+    O: Cast<Prod<B, L::Value>> + Add<O> + Cast<Sum<O, O>>,
+    L: DelayLine,
+    D: DelayLine<Value = L::Value>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+    for<'a> &'a D: IntoIterator<Item = L::Value>,
+    L::Length: ArrayLength<B>,
+{
+    type Input = (L::Value, L::Value);
+    type Output = O;
+    type Param = Param<B, L::Length>;
+    type State = State<L, D>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (even, odd): Self::Input,
+    ) -> Self::Output {
+        state.odd.push(odd);
+        state.even.push(even);
+
+        let center = state.even.iter().last().unwrap_or(even);
+
+        param
+            .taps
+            .iter()
+            .zip(state.odd.iter())
+            .fold(O::cast(param.center * center), |accum, (b, x)| {
+                O::cast(accum + O::cast(*b * x))
+            })
+    }
+}
+
+impl<O, B, L, D> WarmStart for Decimator<O, B, L, D>
+where
+    L: DelayLine + ResetState<Value = <L as DelayLine>::Value>,
+    D: ResetState<Value = <L as DelayLine>::Value>,
+    for<'a> &'a L: IntoIterator<Item = <L as DelayLine>::Value>,
+{
+    type Input = (<L as DelayLine>::Value, <L as DelayLine>::Value);
+    type State = State<L, D>;
+
+    fn warm_start(state: &mut Self::State, (even, odd): &Self::Input) {
+        state.odd.preset(*odd);
+        state.even.preset(*even);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pfdl::Store as DL;
+    use typenum::{N14, N15, N8, P16, P32, U1, U2, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn halfband_f32_m1() {
+        // h = [0.25, 0.5, 0.25]
+        let param = Param::<f32, U2>::new([0.25, 0.25].into(), 0.5);
+        let mut state = State::new(DL::<f32, U2>::from(0.0), DL::<f32, U1>::from(0.0));
+        type D = Decimator<f32, f32, DL<f32, U2>, DL<f32, U1>>;
+
+        assert_eq!(D::apply(&param, &mut state, (1.0, 0.0)), 0.5);
+        assert_eq!(D::apply(&param, &mut state, (0.0, 0.0)), 0.0);
+
+        assert_eq!(D::apply(&param, &mut state, (0.0, 1.0)), 0.25);
+        assert_eq!(D::apply(&param, &mut state, (0.0, 0.0)), 0.25);
+        assert_eq!(D::apply(&param, &mut state, (0.0, 0.0)), 0.0);
+
+        assert_eq!(D::apply(&param, &mut state, (1.0, 1.0)), 0.75);
+        assert_eq!(D::apply(&param, &mut state, (1.0, 1.0)), 1.0);
+    }
+
+    #[test]
+    fn halfband_fix_m2() {
+        type I = Fix<P16, N14>;
+        type B = Fix<P16, N15>;
+        type O = Fix<P32, N8>;
+
+        // h = [-0.03125, 0, 0.28125, 0.5, 0.28125, 0, -0.03125]
+        let param = Param::<B, U4>::new(
+            [-0.03125, 0.28125, 0.28125, -0.03125]
+                .iter()
+                .map(|b| B::cast(*b))
+                .collect(),
+            B::cast(0.5),
+        );
+        let mut state = State::new(
+            DL::<I, U4>::from(I::cast(0.0)),
+            DL::<I, U2>::from(I::cast(0.0)),
+        );
+        type D = Decimator<O, B, DL<I, U4>, DL<I, U2>>;
+
+        // DC gain is unity
+        let mut out = O::cast(0.0);
+        for _ in 0..4 {
+            out = D::apply(&param, &mut state, (I::cast(1.0), I::cast(1.0)));
+        }
+        assert_eq!(out, O::cast(1.0));
+
+        // Nyquist frequency is suppressed
+        for _ in 0..4 {
+            out = D::apply(&param, &mut state, (I::cast(1.0), I::cast(-1.0)));
+        }
+        assert_eq!(out, O::cast(0.0));
+    }
+}
@@ -0,0 +1,162 @@
+/*!
+
+## Hampel filter
+
+This module implements **Hampel** filter which is a robust outlier detector and remover.
+
+The filter evaluates the median _m_ of last _N_ samples and the median absolute deviation (MAD)
+from it. When the deviation of actual sample exceeds the threshold, the sample is treated as outlier
+and replaced by median, otherwise the sample passes unchanged:
+
+_MAD = median(|x\[i\] - m|)_
+
+_y = m, when |x - m| > k 1.4826 MAD_
+
+_y = x, otherwise_
+
+The factor _1.4826_ makes the MAD an estimation of standard deviation for normally distributed noise,
+so the _k_ is a threshold in sigmas (usually 3).
+
+Unlike the [median filter](crate::median) it doesn't distort the signal when outliers is absent.
+The filter is causal, so the actual (newest) sample is checked.
+
+*/
+
+use crate::{median, Cast, DelayLine, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod};
+
+/// The ratio of standard deviation and MAD for normal distribution
+const MAD_SCALE: f64 = 1.4826;
+
+/**
+Hampel filter parameters
+
+- `K` - threshold type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K> {
+    /// The threshold scaled to MAD
+    threshold: K,
+}
+
+impl<K> Param<K> {
+    /**
+    Init Hampel filter parameters
+
+    - `sigmas`: The threshold in standard deviations (usually 3)
+     */
+    pub fn new<V>(sigmas: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            threshold: K::cast(f64::cast(sigmas) * MAD_SCALE),
+        }
+    }
+}
+
+/**
+Hampel filter state
+
+- `L` - delay line type
+*/
+pub type State<L> = L;
+
+/**
+Hampel filter
+
+- `K` - threshold type
+- `L` - delay line type
+*/
+#[derive(Debug)]
+pub struct Filter<K, L>(PhantomData<(K, L)>);
+
+impl<K, L> Transducer for Filter<K, L>
+where
+    K: Copy + Mul<L::Value>,
+    L: DelayLine,
+    L::Value: PartialOrd + Default + Sub<L::Value> + Cast<Diff<L::Value, L::Value>>,
+    L::Value: Cast<Prod<K, L::Value>>,
+    L::Length: ArrayLength<L::Value>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    type Input = L::Value;
+    type Output = L::Value;
+    type Param = Param<K>;
+    type State = State<L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.push(value);
+
+        let mut buffer = GenericArray::<L::Value, L::Length>::default();
+        let median = median::median(state, &mut buffer);
+
+        let count = buffer
+            .iter_mut()
+            .zip(state.iter())
+            .map(|(slot, value)| *slot = deviation(value, median))
+            .count();
+        let mad = median::select(&mut buffer[..count], count.saturating_sub(1) / 2);
+
+        if deviation(value, median) > L::Value::cast(param.threshold * mad) {
+            median
+        } else {
+            value
+        }
+    }
+}
+
+fn deviation<T>(value: T, median: T) -> T
+where
+    T: PartialOrd + Sub<T> + Cast<Diff<T, T>>,
+{
+    if value > median {
+        T::cast(value - median)
+    } else {
+        T::cast(median - value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pfdl::Store as DL;
+    use typenum::{N16, P32, U5};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn hampel_f32() {
+        type F = Filter<f32, DL<f32, U5>>;
+        let param = Param::<f32>::new(3.0);
+        let mut state = DL::from(0.0);
+
+        // fill the window
+        for value in &[1.0, 1.2, 0.9, 1.1, 1.0] {
+            F::apply(&param, &mut state, *value);
+        }
+        // the outlier is replaced by median
+        assert_eq!(F::apply(&param, &mut state, 8.0), 1.1);
+        // the noise passes unchanged
+        assert_eq!(F::apply(&param, &mut state, 1.05), 1.05);
+    }
+
+    #[test]
+    fn hampel_fix() {
+        type T = Fix<P32, N16>;
+        type F = Filter<T, DL<T, U5>>;
+        let param = Param::<T>::new(3.0);
+        let mut state = DL::from(T::cast(0.0));
+
+        for value in &[2.0, 2.5, 1.5, 2.0, 2.0] {
+            F::apply(&param, &mut state, T::cast(*value));
+        }
+        assert_eq!(F::apply(&param, &mut state, T::cast(-20.0)), T::cast(2.0));
+        assert_eq!(F::apply(&param, &mut state, T::cast(3.0)), T::cast(3.0));
+    }
+}
@@ -0,0 +1,121 @@
+/*!
+
+## Hilbert transformer
+
+This module implements designer of **Hilbert transformer** as [FIR filter](crate::fir).
+
+Hilbert transformer shifts the phase of all frequencies by -90°, so the input signal
+and the transformed signal forms the quadrature pair which is required to evaluate
+instant amplitude (envelope), phase or reactive power of signal.
+
+The ideal impulse response is truncated to filter order _N_ and windowed
+by Welch window to reduce the ripple of magnitude response:
+
+_h\[n\] = (1 - cos(π d)) / (π d) * (1 - (d / (N/2 + 1))²)_, where _d = n - N/2_
+
+The filter with even order has each second coefficient equal to zero
+and delays the signal by _N/2_ samples, so the in-phase signal should be delayed
+by the same number of samples (for example using [delay line](crate::DelayLine)).
+The filter with odd order delays the signal by fractional number of samples.
+
+The transformer has a band-pass response, so the magnitude drops near zero and Nyquist frequencies.
+The higher order gives more wide pass band.
+
+See also [Hilbert transform](https://en.wikipedia.org/wiki/Hilbert_transform).
+
+*/
+
+use crate::{fir, Cast};
+use core::{f64::consts::PI, ops::Add};
+use generic_array::ArrayLength;
+use typenum::{Add1, Unsigned, B1};
+
+/**
+Design Hilbert transformer
+
+- `B` - filter weights type
+- `N` - filter order
+
+Returns the parameters of FIR filter.
+ */
+pub fn design<B, N>() -> fir::Param<B, N>
+where
+    B: Cast<f64>,
+    N: Unsigned + Add<B1>,
+    Add1<N>: ArrayLength<B>,
+{
+    let order = N::to_usize();
+    let center = order as f64 * 0.5;
+    let width = center + 1.0;
+
+    (0..=order)
+        .map(|n| {
+            // the value of (1 - cos(π d))
+            let gain = if order % 2 == 1 {
+                1.0
+            } else if (n + order / 2) % 2 == 1 {
+                2.0
+            } else {
+                0.0
+            };
+
+            let d = n as f64 - center;
+            let window = 1.0 - (d / width) * (d / width);
+
+            B::cast(if gain == 0.0 {
+                0.0
+            } else {
+                gain / (PI * d) * window
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pfdl::Store as DL, Transducer};
+    use typenum::{U3, U6};
+
+    #[test]
+    fn hilbert_even() {
+        let param = design::<f64, U6>();
+
+        assert_eq!(param[0], -2.0 / (PI * 3.0) * (7.0 / 16.0));
+        assert_eq!(param[1], 0.0);
+        assert_eq!(param[2], -2.0 / PI * (15.0 / 16.0));
+        assert_eq!(param[3], 0.0);
+        assert_eq!(param[4], 2.0 / PI * (15.0 / 16.0));
+        assert_eq!(param[5], 0.0);
+        assert_eq!(param[6], 2.0 / (PI * 3.0) * (7.0 / 16.0));
+    }
+
+    #[test]
+    fn hilbert_odd() {
+        let param = design::<f32, U3>();
+
+        assert_eq!(param[0], -param[3]);
+        assert_eq!(param[1], -param[2]);
+        assert!(param[0] < 0.0 && param[1] < 0.0);
+    }
+
+    #[test]
+    fn hilbert_quadrature() {
+        let param = design::<f32, U6>();
+        let mut state = DL::<f32, U6>::from(0.0);
+        type F = fir::Filter<f32, f32, DL<f32, U6>>;
+
+        // the input is a sine of quarter of sampling rate: 0, 1, 0, -1, ..
+        // the output is a negated cosine delayed by 3 samples
+        let mut output = [0.0; 12];
+        for (n, output) in output.iter_mut().enumerate() {
+            let input = [0.0, 1.0, 0.0, -1.0][n % 4];
+            *output = F::apply(&param, &mut state, input);
+        }
+
+        assert_eq!(output[8], 0.0);
+        assert_eq!(output[9], -output[11]);
+        assert!(output[9] > 0.99 && output[9] < 1.01);
+        assert_eq!(output[10], 0.0);
+    }
+}
@@ -25,7 +25,10 @@ See also [Kalman filter](https://en.wikipedia.org/wiki/Kalman_filter) article.
 
  */
 
-use crate::{Cast, Transducer};
+use crate::{
+    snapshot_fields, Bounds, Cast, Core, CycleCost, OpCost, RangeOverflow, ResetState, Transducer,
+    ValueBounds, WarmStart, WorstCase,
+};
 use core::{
     marker::PhantomData,
     ops::{Add, Div, Mul, Sub},
@@ -97,6 +100,30 @@ pub struct State<O, P> {
     p: P,
 }
 
+impl<O, P> ResetState for State<O, P>
+where
+    O: Default,
+    P: Default,
+{
+    type Value = O;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.x = value;
+        self.p = P::default();
+    }
+}
+
+snapshot_fields! {
+    State<O, P> {
+        x: O,
+        p: P,
+    }
+}
+
 /**
 LQE filter
 
@@ -161,6 +188,117 @@ where
     }
 }
 
+impl<F, N, F2, I, O, P, K> CycleCost for Filter<F, N, F2, I, O, P, K>
+where
+    F: OpCost,
+    O: OpCost,
+    P: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        // 7 mul, 5 add/sub and div of gain
+        7 * F::mul(core) + F::add(core) + 2 * P::add(core) + 2 * O::add(core) + P::div(core)
+    }
+}
+
+/// The maximum number of steps to converge the gain
+const MAX_STEPS: usize = 1 << 16;
+
+/**
+Worst-case output range of LQE filter
+
+- `input`: The range of input values
+- `f`: The factor of actual value to previous actual value
+- `h`: The factor of measured value to actual value
+- `q`: The measurement noise
+- `r`: The environment noise
+
+The gain doesn't depend on input values, so it is evaluated step-by-step from the initial
+(default) state together with the range of state value until the gain converges.
+The remaining steps is bounded by the fixed point of range.
+The output is unbounded when the gain doesn't converge or the state value isn't damped.
+*/
+pub const fn bounds(input: Bounds, f: f64, h: f64, q: f64, r: f64) -> Bounds {
+    let unbounded = Bounds::new(f64::NEG_INFINITY, f64::INFINITY);
+    // X0 = F * X
+    let predicted = input.scale(f);
+
+    let mut state = Bounds::point(0.0);
+    let mut range = state;
+    let mut p = 0.0;
+    let mut gain = f64::NAN;
+    let mut step = 0;
+
+    loop {
+        if step == MAX_STEPS {
+            return unbounded;
+        }
+
+        let p0 = f * f * p + q;
+        let k = h * p0 / (h * h * p0 + r);
+        p = (1.0 - k * h) * p0;
+
+        // X = (1 - K * H) * X0 + K * X
+        let forced = predicted.scale(1.0 - k * h);
+        state = forced.add(state.scale(k));
+        range = range.hull(state);
+
+        if (k - gain).abs() <= 1e-12 * k.abs() {
+            if k.abs() >= 1.0 {
+                return unbounded;
+            }
+            // the next value of oscillating range and the fixed point X = A + K * X
+            let next = forced.add(state.scale(k));
+            let fixed = if k >= 0.0 {
+                forced.scale(1.0 / (1.0 - k))
+            } else {
+                let norm = 1.0 / (1.0 - k * k);
+                Bounds::new(
+                    (forced.min + k * forced.max) * norm,
+                    (forced.max + k * forced.min) * norm,
+                )
+            };
+            return range.hull(next).hull(fixed);
+        }
+
+        gain = k;
+        step += 1;
+    }
+}
+
+/// The internal values isn't verified
+impl<F, N, F2, I, O, P, K> WorstCase for Filter<F, N, F2, I, O, P, K>
+where
+    Self: Transducer<Param = Param<F, N, F2>, Output = O>,
+    F: Copy,
+    N: Copy,
+    O: ValueBounds,
+    f64: Cast<F> + Cast<N>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        bounds(
+            input,
+            f64::cast(param.f),
+            f64::cast(param.h),
+            f64::cast(param.q),
+            f64::cast(param.r),
+        )
+        .check::<O>()
+    }
+}
+
+impl<F, N, F2, I, O, P, K> WarmStart for Filter<F, N, F2, I, O, P, K>
+where
+    I: Copy,
+    O: Cast<I>,
+{
+    type Input = I;
+    type State = State<O, P>;
+
+    fn warm_start(state: &mut Self::State, value: &Self::Input) {
+        state.x = O::cast(*value);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,143 @@
+/*!
+
+## Median filter
+
+This module implements sliding **median** filter which suppresses impulse noise
+(spikes, dropouts) without smoothing the edges of signal.
+
+The output is a median of last _N_ samples which is stored in delay line.
+For even _N_ the lower of two middle values is used.
+
+The values is selected using insertion sort of copy of delay line,
+so the filter is intended for short windows (usually 3..15 samples).
+
+*/
+
+use crate::{DelayLine, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+
+/**
+Select the value by rank
+
+- `values`: The values to select from (will be sorted in-place)
+- `rank`: The index of value in sorted order
+
+The values which cannot be compared (like NaN) keeps their relative order.
+*/
+pub fn select<T>(values: &mut [T], rank: usize) -> T
+where
+    T: Copy + PartialOrd,
+{
+    for i in 1..values.len() {
+        let mut j = i;
+        while j > 0 && values[j] < values[j - 1] {
+            values.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+    values[rank]
+}
+
+/**
+Get the median of delay line contents
+
+- `line`: The delay line
+- `buffer`: The buffer to copy values to
+*/
+pub fn median<L>(line: &L, buffer: &mut GenericArray<L::Value, L::Length>) -> L::Value
+where
+    L: DelayLine,
+    L::Value: PartialOrd,
+    L::Length: ArrayLength<L::Value>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    let count = buffer
+        .iter_mut()
+        .zip(line.iter())
+        .map(|(slot, value)| *slot = value)
+        .count();
+
+    select(&mut buffer[..count], count.saturating_sub(1) / 2)
+}
+
+/**
+Median filter state
+
+- `L` - delay line type
+*/
+pub type State<L> = L;
+
+/**
+Median filter
+
+- `L` - delay line type
+*/
+#[derive(Debug)]
+pub struct Filter<L>(PhantomData<L>);
+
+impl<L> Transducer for Filter<L>
+where
+    L: DelayLine,
+    L::Value: PartialOrd + Default,
+    L::Length: ArrayLength<L::Value>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    type Input = L::Value;
+    type Output = L::Value;
+    type Param = ();
+    type State = State<L>;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.push(value);
+        median(state, &mut GenericArray::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pfdl::Store as DL, Cast};
+    use typenum::{N16, P32, U3, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn select_rank() {
+        let mut values = [3, 1, 4, 1, 5];
+        assert_eq!(select(&mut values, 2), 3);
+        assert_eq!(values, [1, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn median_f32() {
+        type F = Filter<DL<f32, U3>>;
+        let mut state = DL::from(0.0);
+
+        assert_eq!(F::apply(&(), &mut state, 1.0), 0.0);
+        assert_eq!(F::apply(&(), &mut state, 1.0), 1.0);
+        // the spike is removed
+        assert_eq!(F::apply(&(), &mut state, 10.0), 1.0);
+        assert_eq!(F::apply(&(), &mut state, 1.0), 1.0);
+        assert_eq!(F::apply(&(), &mut state, 1.0), 1.0);
+        // the edge is kept
+        assert_eq!(F::apply(&(), &mut state, 5.0), 1.0);
+        assert_eq!(F::apply(&(), &mut state, 5.0), 5.0);
+
+        type G = Filter<DL<f32, U4>>;
+        let mut state = DL::from(0.0);
+        G::apply(&(), &mut state, 4.0);
+        G::apply(&(), &mut state, 3.0);
+        assert_eq!(G::apply(&(), &mut state, 2.0), 2.0);
+    }
+
+    #[test]
+    fn median_fix() {
+        type T = Fix<P32, N16>;
+        type F = Filter<DL<T, U3>>;
+        let mut state = DL::from(T::cast(0.5));
+
+        assert_eq!(F::apply(&(), &mut state, T::cast(-7.0)), T::cast(0.5));
+        assert_eq!(F::apply(&(), &mut state, T::cast(1.5)), T::cast(0.5));
+        assert_eq!(F::apply(&(), &mut state, T::cast(2.5)), T::cast(1.5));
+    }
+}
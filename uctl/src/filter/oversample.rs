@@ -0,0 +1,164 @@
+/*!
+
+## Oversampling decimator
+
+This module implements the well known technique of increasing the resolution of ADC
+by oversampling and decimation.
+
+The _4<sup>K</sup>_ consecutive samples is summed and the sum is shifted right by _K_ bits,
+so the output has _K_ extra bits of resolution:
+
+_y = (Σ x) >> K_
+
+The gained resolution is expressed in types: when the input type has _B_ digits and exponent _E_,
+the output type has _B + K_ digits and exponent _E - K_. So the value of output is a mean of samples
+but with _K_ more fractional digits.
+
+Note that the extra resolution is effective only when the signal has enough noise (dither)
+of at least one least significant digit of input.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+use typenum::{Diff, NonZero, PInt, Sum, Unsigned};
+use ufix::{bin, Digits, Exponent, Mantissa, Radix};
+
+/**
+Oversampling decimator state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The sum of mantissas of samples
+    sum: i64,
+    /// The number of summed samples
+    count: u32,
+}
+
+/// The preset of decimator is same as reset
+impl ResetState for State {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State {
+        sum: i64,
+        count: u32,
+    }
+}
+
+/**
+Oversampling decimator
+
+- `B` - input digits
+- `E` - input exponent
+- `K` - extra bits of resolution (the decimation ratio is _4<sup>K</sup>_), the positive unsigned
+
+The input type is `bin::Fix<B, E>`, the output type is `bin::Fix<B + K, E - K>`.
+The output is emitted on each _4<sup>K</sup>_ input sample.
+*/
+#[derive(Debug)]
+pub struct Decimator<B, E, K>(PhantomData<(B, E, K)>);
+
+impl<B, E, K> Decimator<B, E, K>
+where
+    K: Unsigned + NonZero,
+{
+    /// The decimation ratio
+    pub fn ratio() -> u32 {
+        1 << (2 * K::U32)
+    }
+}
+
+impl<B, E, K> Transducer for Decimator<B, E, K>
+where
+    B: Digits + Add<PInt<K>>,
+    E: Exponent + Sub<PInt<K>>,
+    K: Unsigned + NonZero,
+    Sum<B, PInt<K>>: Digits,
+    Diff<E, PInt<K>>: Exponent,
+    typenum::P2: Radix<B> + Radix<Sum<B, PInt<K>>>,
+    i64: Cast<Mantissa<typenum::P2, B>>,
+    Mantissa<typenum::P2, Sum<B, PInt<K>>>: Cast<i64>,
+{
+    type Input = bin::Fix<B, E>;
+    type Output = Option<bin::Fix<Sum<B, PInt<K>>, Diff<E, PInt<K>>>>;
+    type Param = ();
+    type State = State;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.sum += i64::cast(value.bits);
+        state.count += 1;
+
+        if state.count < Self::ratio() {
+            return None;
+        }
+
+        let sum = state.sum;
+        state.reset();
+
+        Some(bin::Fix::new(
+            Mantissa::<typenum::P2, Sum<B, PInt<K>>>::cast(sum >> K::U32),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N2, N3, P12, P13, P14, U1, U2, Z0};
+
+    #[test]
+    fn oversample_12bit() {
+        type I = bin::Fix<P12, Z0>;
+        type O = bin::Fix<P14, N2>;
+        type D = Decimator<P12, Z0, U2>;
+
+        assert_eq!(D::ratio(), 16);
+
+        let mut state = State::default();
+
+        // the dithered signal between 100 and 101
+        for i in 0..15 {
+            assert_eq!(D::apply(&(), &mut state, I::cast(100 + i % 2)), None);
+        }
+        assert_eq!(
+            D::apply(&(), &mut state, I::cast(101)),
+            Some(O::cast(100.5))
+        );
+
+        for _ in 0..15 {
+            D::apply(&(), &mut state, I::cast(7));
+        }
+        assert_eq!(D::apply(&(), &mut state, I::cast(8)), Some(O::cast(7.0)));
+    }
+
+    #[test]
+    fn oversample_fraction() {
+        type I = bin::Fix<P12, N2>;
+        type O = bin::Fix<P13, N3>;
+        type D = Decimator<P12, N2, U1>;
+
+        let mut state = State::default();
+
+        D::apply(&(), &mut state, I::cast(0.25));
+        D::apply(&(), &mut state, I::cast(0.25));
+        D::apply(&(), &mut state, I::cast(0.0));
+        // the mean has more fractional digits than input
+        assert_eq!(
+            D::apply(&(), &mut state, I::cast(0.0)),
+            Some(O::cast(0.125))
+        );
+    }
+}
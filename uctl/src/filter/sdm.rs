@@ -0,0 +1,177 @@
+/*!
+
+## Sigma-delta bitstream decimator
+
+This module implements the front end for 1-bit sigma-delta modulators
+(like isolated current and voltage sensors AMC1306, AD7403 and etc.).
+
+The bitstream is captured by peripheral (SPI, SDFM and etc.) into words of 32 bits
+(the earliest bit is the most significant). The bits is unpacked and passed to the
+third order [CIC decimator](crate::cic) (also known as sinc3 filter) with ratio _R_.
+
+The output of decimator is normalized to the range _-1..1_, where _-1_ corresponds to the stream
+of zeros and _1_ corresponds to the stream of ones:
+
+_y = 2 s / R<sup>3</sup> - 1_
+
+The ratio should be a power of two, so the normalization requires only bit shifts.
+The output type should have enough fractional digits to hold the _3 log2(R)_ bits of result.
+
+The filter settles after three output samples.
+
+*/
+
+use crate::{cic, snapshot_fields, Cast, ResetState, Transducer};
+use core::marker::PhantomData;
+use typenum::{Integer, U3};
+use ufix::{bin, Digits, Exponent, Mantissa, Radix};
+
+/**
+Sigma-delta decimator parameters
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// The CIC decimator parameters
+    cic: cic::Param,
+    /// The full scale of decimator output (R^3)
+    scale: i64,
+}
+
+impl Param {
+    /**
+    Init sigma-delta decimator parameters
+
+    - `ratio`: The decimation (oversampling) ratio _R_ (power of two up to 1024)
+     */
+    pub fn new(ratio: u32) -> Self {
+        assert!(ratio.is_power_of_two() && ratio <= 1024);
+
+        Self {
+            cic: cic::Param::new(ratio),
+            scale: (ratio as i64).pow(3),
+        }
+    }
+}
+
+/**
+Sigma-delta decimator state
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    /// The CIC decimator state
+    cic: cic::State<i32, U3>,
+}
+
+impl ResetState for State {
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.cic.reset();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State {
+        cic: cic::State<i32, U3>,
+    }
+}
+
+/**
+Sigma-delta decimator
+
+- `B` - output digits
+- `E` - output exponent
+
+The input is a word of 32 bits of stream. The output type is `bin::Fix<B, E>`.
+When the ratio is less than 32 only the last decimated sample of word is emitted.
+*/
+#[derive(Debug)]
+pub struct Sinc3<B, E>(PhantomData<(B, E)>);
+
+impl<B, E> Transducer for Sinc3<B, E>
+where
+    B: Digits,
+    E: Exponent + Integer,
+    typenum::P2: Radix<B>,
+    Mantissa<typenum::P2, B>: Cast<i64>,
+{
+    type Input = u32;
+    type Output = Option<bin::Fix<B, E>>;
+    type Param = Param;
+    type State = State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, bits: Self::Input) -> Self::Output {
+        let mut result = None;
+
+        for index in (0..32).rev() {
+            let bit = ((bits >> index) & 1) as i32;
+
+            if let Some(value) = cic::Decimator::<i32, U3>::apply(&param.cic, &mut state.cic, bit) {
+                result = Some(value);
+            }
+        }
+
+        result.map(|value| {
+            // the mantissa of (2 s - R^3) / R^3 with exponent E
+            let value = 2 * value as i64 - param.scale;
+            let shift = -E::I32 - param.scale.trailing_zeros() as i32;
+
+            bin::Fix::new(Mantissa::<typenum::P2, B>::cast(if shift >= 0 {
+                value << shift
+            } else {
+                value >> -shift
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+
+    type T = bin::Fix<P32, N16>;
+    type S = Sinc3<P32, N16>;
+
+    fn settle(param: &Param, state: &mut State, bits: u32) -> Option<T> {
+        for _ in 0..3 {
+            S::apply(param, state, bits);
+        }
+        S::apply(param, state, bits)
+    }
+
+    #[test]
+    fn sinc3_full_scale() {
+        let param = Param::new(32);
+        let mut state = State::default();
+
+        assert_eq!(settle(&param, &mut state, 0xffff_ffff), Some(T::cast(1.0)));
+        assert_eq!(settle(&param, &mut state, 0x0000_0000), Some(T::cast(-1.0)));
+
+        state.reset();
+        assert_eq!(settle(&param, &mut state, 0xaaaa_aaaa), Some(T::cast(0.0)));
+        // three ones per four bits
+        assert_eq!(settle(&param, &mut state, 0xeeee_eeee), Some(T::cast(0.5)));
+    }
+
+    #[test]
+    fn sinc3_ratio() {
+        let param = Param::new(64);
+        let mut state = State::default();
+
+        // the output emitted once per two words
+        let outputs = (0..8)
+            .filter_map(|_| S::apply(&param, &mut state, 0xeeee_eeee))
+            .count();
+        assert_eq!(outputs, 4);
+        assert_eq!(S::apply(&param, &mut state, 0xeeee_eeee), None);
+        assert_eq!(
+            S::apply(&param, &mut state, 0xeeee_eeee),
+            Some(T::cast(0.5))
+        );
+    }
+}
@@ -0,0 +1,164 @@
+/*!
+
+## Robust slope estimator
+
+This module implements **Theil–Sen** estimator of slope (rate of change) of signal
+over the last _N_ samples.
+
+The slope is a median of slopes of all pairs of samples in window:
+
+_s = median((x\[i\] - x\[j\]) / ((j - i) P))_, where _i < j_
+
+Unlike the finite differences the estimation is robust to outliers (up to ~29% of samples)
+and much less sensitive to noise, so it can be used for rate-of-change alarms and etc.
+
+The window has _N (N - 1) / 2_ pairs, so the estimator is intended for small windows (4..8 samples).
+The slopes is selected using the [median](crate::median) machinery.
+
+*/
+
+use crate::{median, Cast, DelayLine, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Mul, Sub},
+};
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Unsigned};
+
+/**
+Slope estimator parameters
+
+- `K` - coefficients type
+- `N` - window length
+*/
+#[derive(Debug, Clone)]
+pub struct Param<K, N>
+where
+    N: ArrayLength<K>,
+{
+    /// The reciprocals of time spans between samples
+    recip: GenericArray<K, N>,
+}
+
+impl<K, N> Param<K, N>
+where
+    N: ArrayLength<K> + Unsigned,
+{
+    /**
+    Init slope estimator parameters
+
+    - `period`: The sampling period
+     */
+    pub fn new<T>(period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let period = f64::cast(period);
+
+        Self {
+            recip: GenericArray::generate(|span| {
+                K::cast(if span > 0 {
+                    1.0 / (span as f64 * period)
+                } else {
+                    0.0
+                })
+            }),
+        }
+    }
+}
+
+/**
+Slope estimator state
+
+- `L` - delay line type
+*/
+pub type State<L> = L;
+
+/**
+Theil–Sen slope estimator
+
+- `K` - coefficients type
+- `L` - delay line type
+
+The output is a slope per unit of time.
+*/
+#[derive(Debug)]
+pub struct Filter<K, L>(PhantomData<(K, L)>);
+
+impl<K, L> Transducer for Filter<K, L>
+where
+    K: Copy + Mul<Diff<L::Value, L::Value>>,
+    L: DelayLine,
+    L::Value: PartialOrd + Default + Sub<L::Value> + Cast<Prod<K, Diff<L::Value, L::Value>>>,
+    L::Length: ArrayLength<K> + Mul<L::Length>,
+    Prod<L::Length, L::Length>: ArrayLength<L::Value>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    type Input = L::Value;
+    type Output = L::Value;
+    type Param = Param<K, L::Length>;
+    type State = State<L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.push(value);
+
+        let mut slopes = GenericArray::<L::Value, Prod<L::Length, L::Length>>::default();
+        let mut count = 0;
+
+        // the delay line gives the newest values first
+        for (i, newer) in state.iter().enumerate() {
+            for (j, older) in state.iter().enumerate().skip(i + 1) {
+                slopes[count] = L::Value::cast(param.recip[j - i] * (newer - older));
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            median::select(&mut slopes[..count], (count - 1) / 2)
+        } else {
+            L::Value::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pfdl::Store as DL, ResetState};
+    use typenum::{N8, P16, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn slope_f32() {
+        type F = Filter<f32, DL<f32, U4>>;
+        let param = Param::<f32, U4>::new(0.5);
+        let mut state = DL::from(0.0);
+
+        for value in &[1.0, 2.0, 3.0] {
+            F::apply(&param, &mut state, *value);
+        }
+        assert_eq!(F::apply(&param, &mut state, 4.0), 2.0);
+
+        // the outlier doesn't affect the slope
+        state.preset(0.0);
+        for value in &[1.0, 2.0, 30.0] {
+            F::apply(&param, &mut state, *value);
+        }
+        assert_eq!(F::apply(&param, &mut state, 4.0), 2.0);
+    }
+
+    #[test]
+    fn slope_fix() {
+        type T = Fix<P16, N8>;
+        type F = Filter<T, DL<T, U4>>;
+        let param = Param::<T, U4>::new(1.0);
+        let mut state = DL::from(T::cast(0.0));
+
+        for value in &[1.0, 2.0, 3.0] {
+            F::apply(&param, &mut state, T::cast(*value));
+        }
+        assert_eq!(F::apply(&param, &mut state, T::cast(4.0)), T::cast(1.0));
+        assert_eq!(F::apply(&param, &mut state, T::cast(20.0)), T::cast(1.0));
+    }
+}
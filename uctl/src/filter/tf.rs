@@ -0,0 +1,347 @@
+/*!
+
+## Transfer function
+
+This module implements discrete **transfer function** of arbitrary order
+given by z-domain numerator and denominator coefficients:
+
+_H(z) = (b0 + b1 z^-1 + ... + bN z^-N) / (1 + a1 z^-1 + ... + aN z^-N)_
+
+The difference equation is evaluated in **direct form II transposed**
+which requires only _N_ state values and has good numerical properties:
+
+_y = b0 x + s1_
+
+_s\[i\] = b\[i\] x - a\[i\] y + s\[i+1\]_
+
+The coefficients can be taken directly from filter designs made in MATLAB or SciPy
+(for example the output of `scipy.signal.butter`).
+When the numerator and denominator has different lengths the shorter one is padded by zeros.
+
+See also [Digital filter](https://en.wikipedia.org/wiki/Digital_filter#Direct_form_II).
+
+*/
+
+use crate::{
+    snapshot_fields, Bounds, Cast, Core, CycleCost, OpCost, RangeOverflow, ResetState, Transducer,
+    ValueBounds, WorstCase,
+};
+use core::{
+    iter::{once, repeat_with},
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Add1, Diff, Prod, Sum, Unsigned, B1};
+
+/**
+Transfer function parameters
+
+- `B` - coefficients type
+- `N` - order of transfer function
+*/
+#[derive(Debug, Clone)]
+pub struct Param<B, N>
+where
+    N: ArrayLength<B> + Add<B1>,
+    Add1<N>: ArrayLength<B>,
+{
+    /// Numerator coefficients _b0 .. bN_
+    b: GenericArray<B, Add1<N>>,
+    /// Denominator coefficients _a1 .. aN_
+    a: GenericArray<B, N>,
+}
+
+impl<B, N> Param<B, N>
+where
+    N: ArrayLength<B> + Add<B1>,
+    Add1<N>: ArrayLength<B>,
+{
+    /**
+    Init transfer function parameters from normalized coefficients
+
+    - `b`: The numerator coefficients _b0 .. bN_
+    - `a`: The denominator coefficients _a1 .. aN_ (the _a0_ is assumed to be 1)
+     */
+    pub fn from_arrays(b: GenericArray<B, Add1<N>>, a: GenericArray<B, N>) -> Self {
+        Self { b, a }
+    }
+
+    /**
+    Init transfer function parameters from arbitrary coefficients
+
+    - `b`: The numerator coefficients _b0, b1, .._
+    - `a`: The denominator coefficients _a0, a1, .._
+
+    The coefficients will be normalized by _a0_ and padded by zeros up to order _N_.
+    The extra coefficients will be ignored.
+     */
+    pub fn new<T>(b: &[T], a: &[T]) -> Self
+    where
+        T: Copy,
+        B: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let a0 = a.first().map(|a0| f64::cast(*a0)).unwrap_or(1.0);
+        let coeff = |value: &T| B::cast(f64::cast(*value) / a0);
+
+        Self {
+            b: b.iter()
+                .map(coeff)
+                .chain(repeat_with(|| B::cast(0.0)))
+                .take(Add1::<N>::to_usize())
+                .collect(),
+            a: a.iter()
+                .skip(1)
+                .map(coeff)
+                .chain(repeat_with(|| B::cast(0.0)))
+                .take(N::to_usize())
+                .collect(),
+        }
+    }
+}
+
+/**
+Transfer function state
+
+- `S` - state values type
+- `N` - order of transfer function
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<S, N>
+where
+    N: ArrayLength<S>,
+{
+    /// State values _s1 .. sN_
+    s: GenericArray<S, N>,
+}
+
+impl<S, N> State<S, N>
+where
+    N: ArrayLength<S>,
+{
+    /// Initialize transfer function state by zeros
+    pub fn new() -> Self
+    where
+        S: Default,
+    {
+        Self::default()
+    }
+}
+
+/// The preset of transfer function is same as reset
+impl<S, N> ResetState for State<S, N>
+where
+    S: Default,
+    N: ArrayLength<S>,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<S, N> where [N: ArrayLength<S>] {
+        s: GenericArray<S, N>,
+    }
+}
+
+/**
+Transfer function
+
+- `I` - input value type
+- `O` - output value type
+- `B` - coefficients type
+- `S` - state values type
+- `N` - order of transfer function
+*/
+#[derive(Debug)]
+pub struct TransferFunction<I, O, B, S, N>(PhantomData<(I, O, B, S, N)>);
+
+impl<I, O, B, S, N> Transducer for TransferFunction<I, O, B, S, N>
+where
+    I: Copy,
+    O: Copy + Cast<S>,
+    B: Copy + Mul<I> + Mul<O>,
+    S: Copy
+        + Default
+        + Add<S>
+        + Sub<S>
+        + Cast<Prod<B, I>>
+        + Cast<Prod<B, O>>
+        + Cast<Sum<S, S>>
+        + Cast<Diff<S, S>>,
+    N: ArrayLength<B> + ArrayLength<S> + Add<B1>,
+    Add1<N>: ArrayLength<B>,
+{
+    type Input = I;
+    type Output = O;
+    type Param = Param<B, N>;
+    type State = State<S, N>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        // y = b0 x + s1
+        let first = state.s.first().copied().unwrap_or_default();
+        let output = O::cast(S::cast(S::cast(param.b[0] * value) + first));
+
+        // s[i] = b[i] x - a[i] y + s[i+1]
+        let next = state.s.iter().skip(1).copied().chain(once(S::default()));
+        let states: GenericArray<S, N> = param
+            .b
+            .iter()
+            .skip(1)
+            .zip(param.a.iter())
+            .zip(next)
+            .map(|((b, a), s)| S::cast(S::cast(S::cast(*b * value) - S::cast(*a * output)) + s))
+            .collect();
+        state.s = states;
+
+        output
+    }
+}
+
+impl<I, O, B, S, N> CycleCost for TransferFunction<I, O, B, S, N>
+where
+    B: OpCost,
+    S: OpCost,
+    N: Unsigned,
+{
+    fn cycles(core: Core) -> u32 {
+        let order = N::to_u32();
+        (2 * order + 1) * B::mul(core) + 2 * order * S::add(core)
+    }
+}
+
+/// The maximum number of steps of impulse response
+const MAX_STEPS: usize = 1 << 16;
+
+/**
+Worst-case output range of transfer function
+
+- `input`: The range of input values
+- `b`: The numerator coefficients _b0 .. bN_
+- `a`: The denominator coefficients _a1 .. aN_
+
+The output is a sum of input values weighted by impulse response and initial zero state.
+The impulse response is summed until the state decays below 10<sup>-12</sup>
+of the sum of absolute response, so the output is unbounded for unstable functions.
+*/
+pub const fn bounds<const N: usize>(input: Bounds, b: &[f64], a: &[f64; N]) -> Bounds {
+    impulse_bounds(input, b, a, &mut [0.0; N])
+}
+
+/// The worst-case output range using given storage for state
+const fn impulse_bounds(input: Bounds, b: &[f64], a: &[f64], s: &mut [f64]) -> Bounds {
+    assert!(b.len() == a.len() + 1 && s.len() == a.len());
+
+    let input = input.hull(Bounds::point(0.0));
+    let mut sum = Bounds::point(0.0);
+    let mut norm = 0.0;
+    let mut step = 0;
+
+    while step < MAX_STEPS {
+        let x = if step == 0 { 1.0 } else { 0.0 };
+
+        // y = b0 x + s1
+        let y = b[0] * x + if s.is_empty() { 0.0 } else { s[0] };
+        sum = sum.add(input.scale(y));
+        norm += y.abs();
+
+        // s[i] = b[i] x - a[i] y + s[i+1]
+        let mut rest = 0.0;
+        let mut i = 0;
+        while i < s.len() {
+            let next = if i + 1 < s.len() { s[i + 1] } else { 0.0 };
+            s[i] = b[i + 1] * x - a[i] * y + next;
+            rest += s[i].abs();
+            i += 1;
+        }
+
+        if rest <= 1e-12 * norm {
+            return sum;
+        }
+        step += 1;
+    }
+
+    Bounds::new(f64::NEG_INFINITY, f64::INFINITY)
+}
+
+/// The state values isn't verified
+impl<I, O, B, S, N> WorstCase for TransferFunction<I, O, B, S, N>
+where
+    Self: Transducer<Param = Param<B, N>, Output = O>,
+    B: Copy,
+    O: ValueBounds,
+    N: ArrayLength<B> + ArrayLength<f64> + Add<B1>,
+    Add1<N>: ArrayLength<B> + ArrayLength<f64>,
+    f64: Cast<B>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        let b: GenericArray<f64, Add1<N>> = param.b.iter().map(|b| f64::cast(*b)).collect();
+        let a: GenericArray<f64, N> = param.a.iter().map(|a| f64::cast(*a)).collect();
+        let mut s = GenericArray::<f64, N>::default();
+        impulse_bounds(input, &b, &a, &mut s).check::<O>()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use generic_array::arr;
+    use typenum::{N16, N24, P32, U1, U2};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn tf_first_order_f32() {
+        // y = 0.5 x + 0.5 y[-1]
+        let param = Param::<f32, U1>::new(&[0.5], &[1.0, -0.5]);
+        let mut state = State::new();
+        type F = TransferFunction<f32, f32, f32, f32, U1>;
+
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.5);
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.75);
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.875);
+        assert_eq!(F::apply(&param, &mut state, 0.0), 0.4375);
+    }
+
+    #[test]
+    fn tf_second_order_f32() {
+        // 2 y = x + 2 x[-1] + x[-2] + y[-1] - 0.5 y[-2]
+        let param = Param::<f32, U2>::new(&[1.0, 2.0, 1.0], &[2.0, -1.0, 0.5]);
+        let mut state = State::new();
+        type F = TransferFunction<f32, f32, f32, f32, U2>;
+
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.5);
+        assert_eq!(F::apply(&param, &mut state, 0.0), 1.25);
+        assert_eq!(F::apply(&param, &mut state, 0.0), 1.0);
+        assert_eq!(F::apply(&param, &mut state, 0.0), 0.1875);
+
+        state.reset();
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.5);
+    }
+
+    #[test]
+    fn tf_second_order_fix() {
+        type T = Fix<P32, N16>;
+        type B = Fix<P32, N24>;
+        type S = Fix<P32, N16>;
+
+        let param = Param::<B, U2>::from_arrays(
+            arr![B; B::cast(0.5), B::cast(1.0), B::cast(0.5)],
+            arr![B; B::cast(-0.5), B::cast(0.25)],
+        );
+        let mut state = State::new();
+        type F = TransferFunction<T, T, B, S, U2>;
+
+        assert_eq!(F::apply(&param, &mut state, T::cast(1.0)), T::cast(0.5));
+        assert_eq!(F::apply(&param, &mut state, T::cast(0.0)), T::cast(1.25));
+        assert_eq!(F::apply(&param, &mut state, T::cast(0.0)), T::cast(1.0));
+        assert_eq!(F::apply(&param, &mut state, T::cast(0.0)), T::cast(0.1875));
+    }
+}
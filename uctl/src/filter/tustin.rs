@@ -0,0 +1,234 @@
+/*!
+
+## Bilinear discretization
+
+This module implements helpers which converts continuous-time (s-domain) transfer functions
+of first and second order into discrete-time (z-domain) coefficients
+using **bilinear transform** (also known as Tustin's method):
+
+_s = 2/P * (1 - z^-1) / (1 + z^-1)_
+
+where _P_ is a sampling period.
+
+The resulting coefficients is normalized (_a0 = 1_) and can be used to initialize
+the parameters of [transfer function](crate::tf) using [`Param::new`](crate::tf::Param::new).
+All helpers is `const fn`, so the coefficients can be evaluated at compile time.
+
+The bilinear transform maps the frequencies non-linearly, so the characteristic frequencies
+should be much less than the Nyquist frequency to get the close response.
+
+See also [Bilinear transform](https://en.wikipedia.org/wiki/Bilinear_transform).
+
+*/
+
+/// First order coefficients (numerator, denominator)
+pub type First = ([f64; 2], [f64; 2]);
+
+/// Second order coefficients (numerator, denominator)
+pub type Second = ([f64; 3], [f64; 3]);
+
+/// Third order coefficients (numerator, denominator)
+pub type Third = ([f64; 4], [f64; 4]);
+
+/**
+Discretize first order transfer function
+
+_H(s) = (b0 s + b1) / (a0 s + a1)_
+
+- `b`: The s-domain numerator coefficients
+- `a`: The s-domain denominator coefficients
+- `period`: The sampling period
+ */
+pub const fn first(b: [f64; 2], a: [f64; 2], period: f64) -> First {
+    let c = 2.0 / period;
+
+    let n0 = b[0] * c + b[1];
+    let n1 = b[1] - b[0] * c;
+    let d0 = a[0] * c + a[1];
+    let d1 = a[1] - a[0] * c;
+
+    ([n0 / d0, n1 / d0], [1.0, d1 / d0])
+}
+
+/**
+Discretize second order transfer function
+
+_H(s) = (b0 s² + b1 s + b2) / (a0 s² + a1 s + a2)_
+
+- `b`: The s-domain numerator coefficients
+- `a`: The s-domain denominator coefficients
+- `period`: The sampling period
+ */
+pub const fn second(b: [f64; 3], a: [f64; 3], period: f64) -> Second {
+    let c = 2.0 / period;
+    let c2 = c * c;
+
+    let n0 = b[0] * c2 + b[1] * c + b[2];
+    let n1 = 2.0 * (b[2] - b[0] * c2);
+    let n2 = b[0] * c2 - b[1] * c + b[2];
+    let d0 = a[0] * c2 + a[1] * c + a[2];
+    let d1 = 2.0 * (a[2] - a[0] * c2);
+    let d2 = a[0] * c2 - a[1] * c + a[2];
+
+    ([n0 / d0, n1 / d0, n2 / d0], [1.0, d1 / d0, d2 / d0])
+}
+
+/**
+Discretize third order transfer function
+
+_H(s) = (b0 s³ + b1 s² + b2 s + b3) / (a0 s³ + a1 s² + a2 s + a3)_
+
+- `b`: The s-domain numerator coefficients
+- `a`: The s-domain denominator coefficients
+- `period`: The sampling period
+ */
+pub const fn third(b: [f64; 4], a: [f64; 4], period: f64) -> Third {
+    let c = 2.0 / period;
+    let c2 = c * c;
+    let c3 = c2 * c;
+
+    // s^k (1 + z^-1)^3 = c^k (1 - z^-1)^k (1 + z^-1)^(3-k)
+    let n0 = b[0] * c3 + b[1] * c2 + b[2] * c + b[3];
+    let n1 = -3.0 * b[0] * c3 - b[1] * c2 + b[2] * c + 3.0 * b[3];
+    let n2 = 3.0 * b[0] * c3 - b[1] * c2 - b[2] * c + 3.0 * b[3];
+    let n3 = -b[0] * c3 + b[1] * c2 - b[2] * c + b[3];
+    let d0 = a[0] * c3 + a[1] * c2 + a[2] * c + a[3];
+    let d1 = -3.0 * a[0] * c3 - a[1] * c2 + a[2] * c + 3.0 * a[3];
+    let d2 = 3.0 * a[0] * c3 - a[1] * c2 - a[2] * c + 3.0 * a[3];
+    let d3 = -a[0] * c3 + a[1] * c2 - a[2] * c + a[3];
+
+    (
+        [n0 / d0, n1 / d0, n2 / d0, n3 / d0],
+        [1.0, d1 / d0, d2 / d0, d3 / d0],
+    )
+}
+
+/**
+Discretize first order low-pass (PT1) element
+
+_H(s) = K / (T s + 1)_
+
+- `gain`: The gain _K_
+- `time`: The time constant _T_
+- `period`: The sampling period
+ */
+pub const fn lowpass1(gain: f64, time: f64, period: f64) -> First {
+    first([0.0, gain], [time, 1.0], period)
+}
+
+/**
+Discretize lead-lag element
+
+_H(s) = K (T1 s + 1) / (T2 s + 1)_
+
+- `gain`: The gain _K_
+- `lead`: The lead time constant _T1_
+- `lag`: The lag time constant _T2_
+- `period`: The sampling period
+ */
+pub const fn lead_lag(gain: f64, lead: f64, lag: f64, period: f64) -> First {
+    first([gain * lead, gain], [lag, 1.0], period)
+}
+
+/**
+Discretize second order low-pass (PT2) element
+
+_H(s) = K ω² / (s² + 2 ζ ω s + ω²)_
+
+- `gain`: The gain _K_
+- `omega`: The natural frequency _ω_ (rad/s)
+- `zeta`: The damping ratio _ζ_
+- `period`: The sampling period
+ */
+pub const fn lowpass2(gain: f64, omega: f64, zeta: f64, period: f64) -> Second {
+    let omega2 = omega * omega;
+    second(
+        [0.0, 0.0, gain * omega2],
+        [1.0, 2.0 * zeta * omega, omega2],
+        period,
+    )
+}
+
+/**
+Discretize type III compensator
+
+_H(s) = ωi (1 + s / ωz1) (1 + s / ωz2) / (s (1 + s / ωp1) (1 + s / ωp2))_
+
+The compensator is usually used in voltage mode control of switching converters:
+the zeros is placed near the resonance of output LC filter, the poles is placed
+near the zero of output capacitor ESR and above the crossover frequency.
+
+- `gain`: The integrator gain _ωi_ (rad/s)
+- `zeros`: The zeros _ωz1, ωz2_ (rad/s)
+- `poles`: The poles _ωp1, ωp2_ (rad/s)
+- `period`: The sampling period
+ */
+pub const fn type3(gain: f64, zeros: [f64; 2], poles: [f64; 2], period: f64) -> Third {
+    let (z1, z2) = (1.0 / zeros[0], 1.0 / zeros[1]);
+    let (p1, p2) = (1.0 / poles[0], 1.0 / poles[1]);
+
+    third(
+        [0.0, gain * z1 * z2, gain * (z1 + z2), gain],
+        [p1 * p2, p1 + p2, 1.0, 0.0],
+        period,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{tf, Transducer};
+    use typenum::{U1, U2, U3};
+
+    #[test]
+    fn tustin_first() {
+        const PT1: First = lowpass1(1.0, 1.0, 2.0);
+        assert_eq!(PT1, ([0.5, 0.5], [1.0, 0.0]));
+
+        let param = tf::Param::<f32, U1>::new(&PT1.0, &PT1.1);
+        let mut state = tf::State::new();
+        type F = tf::TransferFunction<f32, f32, f32, f32, U1>;
+
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.5);
+        assert_eq!(F::apply(&param, &mut state, 1.0), 1.0);
+
+        let (b, a) = lead_lag(2.0, 3.0, 1.0, 2.0);
+        assert_eq!((b, a), ([4.0, -2.0], [1.0, 0.0]));
+        // static gain
+        assert_eq!((b[0] + b[1]) / (a[0] + a[1]), 2.0);
+    }
+
+    #[test]
+    fn tustin_second() {
+        const PT2: Second = lowpass2(1.0, 1.0, 1.0, 2.0);
+        assert_eq!(PT2, ([0.25, 0.5, 0.25], [1.0, 0.0, 0.0]));
+
+        let param = tf::Param::<f32, U2>::new(&PT2.0, &PT2.1);
+        let mut state = tf::State::new();
+        type F = tf::TransferFunction<f32, f32, f32, f32, U2>;
+
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.25);
+        assert_eq!(F::apply(&param, &mut state, 1.0), 0.75);
+        assert_eq!(F::apply(&param, &mut state, 1.0), 1.0);
+    }
+
+    #[test]
+    fn tustin_third() {
+        // the integrator with two lead-lag elements which cancels each other
+        const T3: Third = type3(1.0, [2.0, 4.0], [2.0, 4.0], 1.0);
+
+        let param = tf::Param::<f64, U3>::new(&T3.0, &T3.1);
+        let mut state = tf::State::new();
+        type F = tf::TransferFunction<f64, f64, f64, f64, U3>;
+
+        // the step response of discrete integrator
+        for step in 0..5 {
+            let output = F::apply(&param, &mut state, 1.0);
+            assert!((output - (step as f64 + 0.5)).abs() < 1e-12);
+        }
+
+        // the unity gain
+        let (b, a) = third([1.0, 3.0, 3.0, 1.0], [1.0, 3.0, 3.0, 1.0], 0.5);
+        assert_eq!(b, a);
+    }
+}
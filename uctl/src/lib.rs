@@ -26,24 +26,53 @@ You can select this types to get desired precision and fit to required range.
 
 In any case you should create specific tests to be sure in correctness of operation.
 
+### Host-side usage
+
+The `std` feature enables some conveniences for using on host side,
+such as signal recorders, comparison with `f64`-based reference models, tracing of transducers chains
+and exporting recorded signals to CSV or NDJSON files.
+
+### Range checking
+
+The `checked` feature enables the runtime checking of outputs of [`Checked`] transducers
+against the declared bounds during development.
+
+### SIMD
+
+The `simd` feature enables vectorized [block kernels](Batch) using portable SIMD.
+This feature requires nightly compiler.
+
  */
 
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 #![forbid(unsafe_code)]
 #![forbid(missing_docs)]
 
+mod codec;
 mod consts;
+mod drive;
 mod filter;
+mod math;
+mod measure;
+mod protect;
 mod regulator;
 mod transform;
 mod trigonometry;
+mod tuning;
 mod types;
 mod utils;
 
+pub use codec::*;
 pub use consts::*;
+pub use drive::*;
 pub use filter::*;
+pub use math::*;
+pub use measure::*;
+pub use protect::*;
 pub use regulator::*;
 pub use transform::*;
 pub use trigonometry::*;
+pub use tuning::*;
 pub use types::*;
 pub use utils::*;
@@ -0,0 +1,11 @@
+mod atan;
+mod batch;
+mod sincos;
+mod sqrt;
+mod wrapping;
+
+pub use atan::*;
+pub use batch::*;
+pub use sincos::*;
+pub use sqrt::*;
+pub use wrapping::*;
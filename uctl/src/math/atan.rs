@@ -0,0 +1,95 @@
+use crate::{Cast, Sqrt};
+use core::f64::consts::{FRAC_PI_2, PI};
+
+/// Arctangent
+///
+/// This is intended mainly for evaluating phases of frequency responses (stability margins and etc.)
+/// on targets without standard library, so it isn't optimized for speed.
+pub trait Atan: Sized {
+    /// Get arctangent in radians
+    fn atan(self) -> Self;
+
+    /// Get arctangent of `self / x` in radians in range -π..π
+    fn atan2(self, x: Self) -> Self;
+}
+
+impl Atan for f64 {
+    fn atan(self) -> Self {
+        if self > 1.0 {
+            return FRAC_PI_2 - Atan::atan(1.0 / self);
+        }
+        if self < -1.0 {
+            return -FRAC_PI_2 - Atan::atan(1.0 / self);
+        }
+
+        // Halve the argument twice to speed up the convergence of series
+        // atan(x) = 2 atan(x / (1 + √(1 + x²)))
+        let x = self / (1.0 + Sqrt::sqrt(1.0 + self * self));
+        let x = x / (1.0 + Sqrt::sqrt(1.0 + x * x));
+
+        // Evaluate Taylor series
+        let x2 = x * x;
+        let mut sum = 0.0;
+        for n in (0..12).rev() {
+            sum = 1.0 / (2 * n + 1) as f64 - x2 * sum;
+        }
+
+        4.0 * x * sum
+    }
+
+    fn atan2(self, x: Self) -> Self {
+        if x > 0.0 {
+            Atan::atan(self / x)
+        } else if x < 0.0 {
+            Atan::atan(self / x) + if self < 0.0 { -PI } else { PI }
+        } else if self < 0.0 {
+            -FRAC_PI_2
+        } else {
+            FRAC_PI_2
+        }
+    }
+}
+
+impl Atan for f32 {
+    fn atan(self) -> Self {
+        f32::cast(Atan::atan(f64::cast(self)))
+    }
+
+    fn atan2(self, x: Self) -> Self {
+        f32::cast(Atan::atan2(f64::cast(self), f64::cast(x)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn atan_f64() {
+        let sqrt_3 = Sqrt::sqrt(3.0f64);
+        for (x, y) in [
+            (0.0, 0.0),
+            (1.0, PI / 4.0),
+            (sqrt_3, PI / 3.0),
+            (1.0 / sqrt_3, PI / 6.0),
+        ] {
+            assert!((Atan::atan(x) - y).abs() < 1e-12, "{}", x);
+            assert!((Atan::atan(-x) + y).abs() < 1e-12, "{}", x);
+        }
+
+        for i in -40..=40 {
+            let x = i as f64 * 0.3;
+            assert!((Atan::atan(x) - x.atan()).abs() < 1e-14);
+        }
+
+        assert!((Atan::atan2(1.0, -1.0) - 0.75 * PI).abs() < 1e-12);
+        assert!((Atan::atan2(-1.0, -1.0) + 0.75 * PI).abs() < 1e-12);
+        assert_eq!(Atan::atan2(-1.0, 0.0), -FRAC_PI_2);
+    }
+
+    #[test]
+    fn atan_f32() {
+        assert_eq!(Atan::atan(1.0f32), PI as f32 / 4.0);
+        assert_eq!(Atan::atan2(-1.0f32, -1.0), -0.75 * PI as f32);
+    }
+}
@@ -0,0 +1,460 @@
+/*!
+
+## Block kernels
+
+This module implements the kernels which processes the slices of values at once,
+which is useful for block-based processing (like FIR filtering of buffered samples
+or field-oriented control of several motors).
+
+The three-phase values is given as separate slices of components
+(structure of arrays), so the kernels can be vectorized.
+
+The `simd` feature enables the implementation using portable SIMD (requires nightly compiler)
+for floating-point values. The scalar code is used for fixed-point values, for remaining values
+which doesn't fill the vector and for all values when the feature is disabled.
+
+The implementation is selected by the feature only, nothing is detected at compile time or at runtime.
+With the feature enabled the vectors is used on any target, so on targets without SIMD unit
+the compiler lowers them to scalar operations. Enable the feature only for targets which has SIMD unit.
+
+The fixed-point kernels rounds the products to the type of values,
+so the values type should have enough fractional digits.
+
+*/
+
+use crate::Cast;
+use core::ops::{Add, Mul, Sub};
+use typenum::{Diff, Prod, Sum};
+use ufix::{Digits, Exponent, Fix, Radix};
+
+/// 1/3
+const FRAC_1_3: f64 = 1.0 / 3.0;
+
+/// 1/√3
+const FRAC_1_SQRT_3: f64 = 0.5773502691896258;
+
+/**
+Block kernels for batches of values
+
+The lengths of slices should be equal.
+*/
+pub trait Batch: Sized {
+    /**
+    Dot product (like FIR filter output)
+
+    _y = Σ a<sub>i</sub> b<sub>i</sub>_
+     */
+    fn dot(a: &[Self], b: &[Self]) -> Self;
+
+    /**
+    Scale and offset values in place
+
+    _x<sub>i</sub> = x<sub>i</sub> factor + offset_
+     */
+    fn scale_offset(values: &mut [Self], factor: Self, offset: Self);
+
+    /**
+    Clarke transformation of values
+
+    See also [`AlphaBeta`](crate::ab::AlphaBeta).
+     */
+    fn clarke(a: &[Self], b: &[Self], c: &[Self], alpha: &mut [Self], beta: &mut [Self]);
+
+    /**
+    Park transformation of values
+
+    See also [`Dq`](crate::dqz::Dq).
+     */
+    fn park(
+        alpha: &[Self],
+        beta: &[Self],
+        sin: &[Self],
+        cos: &[Self],
+        d: &mut [Self],
+        q: &mut [Self],
+    );
+}
+
+fn scalar_dot<T>(init: T, a: &[T], b: &[T]) -> T
+where
+    T: Copy + Add<Output = T> + Mul<Output = T>,
+{
+    a.iter().zip(b).fold(init, |sum, (a, b)| sum + *a * *b)
+}
+
+fn scalar_scale_offset<T>(values: &mut [T], factor: T, offset: T)
+where
+    T: Copy + Add<Output = T> + Mul<Output = T>,
+{
+    for value in values {
+        *value = *value * factor + offset;
+    }
+}
+
+fn scalar_clarke<T>(
+    (frac_1_3, frac_1_sqrt_3): (T, T),
+    a: &[T],
+    b: &[T],
+    c: &[T],
+    alpha: &mut [T],
+    beta: &mut [T],
+) where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    for i in 0..a.len() {
+        alpha[i] = (a[i] + a[i] - b[i] - c[i]) * frac_1_3;
+        beta[i] = (b[i] - c[i]) * frac_1_sqrt_3;
+    }
+}
+
+fn scalar_park<T>(alpha: &[T], beta: &[T], sin: &[T], cos: &[T], d: &mut [T], q: &mut [T])
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    for i in 0..alpha.len() {
+        d[i] = alpha[i] * cos[i] + beta[i] * sin[i];
+        q[i] = beta[i] * cos[i] - alpha[i] * sin[i];
+    }
+}
+
+macro_rules! batch_float {
+    ($type: ty, $lanes: expr) => {
+        #[cfg(not(feature = "simd"))]
+        impl Batch for $type {
+            fn dot(a: &[Self], b: &[Self]) -> Self {
+                assert_eq!(a.len(), b.len());
+                scalar_dot(0.0, a, b)
+            }
+
+            fn scale_offset(values: &mut [Self], factor: Self, offset: Self) {
+                scalar_scale_offset(values, factor, offset);
+            }
+
+            fn clarke(a: &[Self], b: &[Self], c: &[Self], alpha: &mut [Self], beta: &mut [Self]) {
+                let len = a.len();
+                assert!(
+                    b.len() == len && c.len() == len && alpha.len() == len && beta.len() == len
+                );
+                scalar_clarke(
+                    (FRAC_1_3 as $type, FRAC_1_SQRT_3 as $type),
+                    a,
+                    b,
+                    c,
+                    alpha,
+                    beta,
+                );
+            }
+
+            fn park(
+                alpha: &[Self],
+                beta: &[Self],
+                sin: &[Self],
+                cos: &[Self],
+                d: &mut [Self],
+                q: &mut [Self],
+            ) {
+                let len = alpha.len();
+                assert!(
+                    beta.len() == len
+                        && sin.len() == len
+                        && cos.len() == len
+                        && d.len() == len
+                        && q.len() == len
+                );
+                scalar_park(alpha, beta, sin, cos, d, q);
+            }
+        }
+
+        #[cfg(feature = "simd")]
+        impl Batch for $type {
+            fn dot(a: &[Self], b: &[Self]) -> Self {
+                use core::simd::{num::SimdFloat, Simd};
+                type V = Simd<$type, $lanes>;
+
+                assert_eq!(a.len(), b.len());
+                let (a, a_rest) = a.as_chunks::<$lanes>();
+                let (b, b_rest) = b.as_chunks::<$lanes>();
+
+                let sum = a.iter().zip(b).fold(V::splat(0.0), |sum, (a, b)| {
+                    sum + V::from_array(*a) * V::from_array(*b)
+                });
+
+                scalar_dot(sum.reduce_sum(), a_rest, b_rest)
+            }
+
+            fn scale_offset(values: &mut [Self], factor: Self, offset: Self) {
+                use core::simd::Simd;
+                type V = Simd<$type, $lanes>;
+
+                let (chunks, rest) = values.as_chunks_mut::<$lanes>();
+                let (factor_v, offset_v) = (V::splat(factor), V::splat(offset));
+
+                for chunk in chunks {
+                    *chunk = (V::from_array(*chunk) * factor_v + offset_v).to_array();
+                }
+
+                scalar_scale_offset(rest, factor, offset);
+            }
+
+            fn clarke(a: &[Self], b: &[Self], c: &[Self], alpha: &mut [Self], beta: &mut [Self]) {
+                use core::simd::Simd;
+                type V = Simd<$type, $lanes>;
+
+                let len = a.len();
+                assert!(
+                    b.len() == len && c.len() == len && alpha.len() == len && beta.len() == len
+                );
+                let split = len - len % $lanes;
+                let consts = (FRAC_1_3 as $type, FRAC_1_SQRT_3 as $type);
+                let (frac_1_3, frac_1_sqrt_3) = (V::splat(consts.0), V::splat(consts.1));
+
+                for i in (0..split).step_by($lanes) {
+                    let a = V::from_slice(&a[i..]);
+                    let b = V::from_slice(&b[i..]);
+                    let c = V::from_slice(&c[i..]);
+                    ((a + a - b - c) * frac_1_3).copy_to_slice(&mut alpha[i..]);
+                    ((b - c) * frac_1_sqrt_3).copy_to_slice(&mut beta[i..]);
+                }
+
+                scalar_clarke(
+                    consts,
+                    &a[split..],
+                    &b[split..],
+                    &c[split..],
+                    &mut alpha[split..],
+                    &mut beta[split..],
+                );
+            }
+
+            fn park(
+                alpha: &[Self],
+                beta: &[Self],
+                sin: &[Self],
+                cos: &[Self],
+                d: &mut [Self],
+                q: &mut [Self],
+            ) {
+                use core::simd::Simd;
+                type V = Simd<$type, $lanes>;
+
+                let len = alpha.len();
+                assert!(
+                    beta.len() == len
+                        && sin.len() == len
+                        && cos.len() == len
+                        && d.len() == len
+                        && q.len() == len
+                );
+                let split = len - len % $lanes;
+
+                for i in (0..split).step_by($lanes) {
+                    let alpha = V::from_slice(&alpha[i..]);
+                    let beta = V::from_slice(&beta[i..]);
+                    let sin = V::from_slice(&sin[i..]);
+                    let cos = V::from_slice(&cos[i..]);
+                    (alpha * cos + beta * sin).copy_to_slice(&mut d[i..]);
+                    (beta * cos - alpha * sin).copy_to_slice(&mut q[i..]);
+                }
+
+                scalar_park(
+                    &alpha[split..],
+                    &beta[split..],
+                    &sin[split..],
+                    &cos[split..],
+                    &mut d[split..],
+                    &mut q[split..],
+                );
+            }
+        }
+    };
+}
+
+batch_float!(f32, 8);
+batch_float!(f64, 4);
+
+impl<R, B, E> Batch for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Self: Copy
+        + Cast<f64>
+        + Add<Self>
+        + Cast<Sum<Self, Self>>
+        + Sub<Self>
+        + Cast<Diff<Self, Self>>
+        + Mul<Self>
+        + Cast<Prod<Self, Self>>,
+{
+    fn dot(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len());
+        a.iter().zip(b).fold(Self::cast(0.0), |sum, (a, b)| {
+            Self::cast(sum + Self::cast(*a * *b))
+        })
+    }
+
+    fn scale_offset(values: &mut [Self], factor: Self, offset: Self) {
+        for value in values {
+            *value = Self::cast(Self::cast(*value * factor) + offset);
+        }
+    }
+
+    fn clarke(a: &[Self], b: &[Self], c: &[Self], alpha: &mut [Self], beta: &mut [Self]) {
+        let len = a.len();
+        assert!(b.len() == len && c.len() == len && alpha.len() == len && beta.len() == len);
+        let (frac_1_3, frac_1_sqrt_3) = (Self::cast(FRAC_1_3), Self::cast(FRAC_1_SQRT_3));
+
+        for i in 0..len {
+            let a2 = Self::cast(a[i] + a[i]);
+            let bc = Self::cast(b[i] + c[i]);
+            alpha[i] = Self::cast(Self::cast(a2 - bc) * frac_1_3);
+            beta[i] = Self::cast(Self::cast(b[i] - c[i]) * frac_1_sqrt_3);
+        }
+    }
+
+    fn park(
+        alpha: &[Self],
+        beta: &[Self],
+        sin: &[Self],
+        cos: &[Self],
+        d: &mut [Self],
+        q: &mut [Self],
+    ) {
+        let len = alpha.len();
+        assert!(
+            beta.len() == len
+                && sin.len() == len
+                && cos.len() == len
+                && d.len() == len
+                && q.len() == len
+        );
+
+        for i in 0..len {
+            let (ac, bs) = (alpha[i] * cos[i], beta[i] * sin[i]);
+            let (bc, as_) = (beta[i] * cos[i], alpha[i] * sin[i]);
+            d[i] = Self::cast(Self::cast(ac) + Self::cast(bs));
+            q[i] = Self::cast(Self::cast(bc) - Self::cast(as_));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ab::{Abc, AlphaBeta},
+        dqz::Dq,
+        SinCos,
+    };
+    use typenum::{N16, P32};
+
+    const LEN: usize = 19;
+
+    fn values(offset: f32) -> [f32; LEN] {
+        let mut values = [0.0; LEN];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = (i as f32 * 0.7 + offset).sin();
+        }
+        values
+    }
+
+    #[test]
+    fn batch_dot() {
+        let (a, b) = (values(0.0), values(1.0));
+
+        // all lengths to check the remaining values
+        for len in 0..LEN {
+            let expected = a[..len]
+                .iter()
+                .zip(&b[..len])
+                .fold(0.0, |sum, (a, b)| sum + a * b);
+            let result = f32::dot(&a[..len], &b[..len]);
+            assert!((result - expected).abs() < 1e-5, "{} {}", result, expected);
+        }
+
+        let a = [1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(f64::dot(&a, &a), 91.0);
+    }
+
+    #[test]
+    fn batch_scale_offset() {
+        let mut x = values(0.0);
+        f32::scale_offset(&mut x, 2.0, -1.0);
+
+        for (x, y) in values(0.0).iter().zip(x.iter()) {
+            assert_eq!(*y, x * 2.0 - 1.0);
+        }
+    }
+
+    #[test]
+    fn batch_clarke_park() {
+        let (a, b, c) = (values(0.0), values(1.0), values(2.0));
+        let mut alpha = [0.0; LEN];
+        let mut beta = [0.0; LEN];
+        f32::clarke(&a, &b, &c, &mut alpha, &mut beta);
+
+        let mut sin = [0.0; LEN];
+        let mut cos = [0.0; LEN];
+        for i in 0..LEN {
+            let (s, c) = SinCos::sin_cos(i as f32 * 0.3);
+            sin[i] = s;
+            cos[i] = c;
+        }
+
+        let mut d = [0.0; LEN];
+        let mut q = [0.0; LEN];
+        f32::park(&alpha, &beta, &sin, &cos, &mut d, &mut q);
+
+        for i in 0..LEN {
+            let ab = AlphaBeta::from(Abc::new(a[i], b[i], c[i]));
+            assert!((ab.alpha - alpha[i]).abs() < 1e-6);
+            assert!((ab.beta - beta[i]).abs() < 1e-6);
+
+            let dq: Dq<f32> = ab.to_dq((sin[i], cos[i]));
+            assert!((dq.d - d[i]).abs() < 1e-6);
+            assert!((dq.q - q[i]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn batch_fix() {
+        type F = ufix::bin::Fix<P32, N16>;
+
+        let cast = |values: [f32; LEN]| {
+            let mut result = [F::cast(0.0); LEN];
+            for (y, x) in result.iter_mut().zip(values.iter()) {
+                *y = F::cast(*x);
+            }
+            result
+        };
+        let near = |x: F, y: f32, tol: f64| (f64::cast(x) - y as f64).abs() < tol;
+
+        let (a, b, c) = (values(0.0), values(1.0), values(2.0));
+        let (af, bf, cf) = (cast(a), cast(b), cast(c));
+
+        assert!(near(F::dot(&af, &bf), f32::dot(&a, &b), 1e-3));
+
+        let mut x = af;
+        F::scale_offset(&mut x, F::cast(2.0), F::cast(-1.0));
+        for (x, y) in x.iter().zip(a.iter()) {
+            assert!(near(*x, y * 2.0 - 1.0, 1e-4));
+        }
+
+        let (mut alpha, mut beta) = ([0.0; LEN], [0.0; LEN]);
+        f32::clarke(&a, &b, &c, &mut alpha, &mut beta);
+        let (mut alpha_f, mut beta_f) = ([F::cast(0.0); LEN], [F::cast(0.0); LEN]);
+        F::clarke(&af, &bf, &cf, &mut alpha_f, &mut beta_f);
+
+        let (mut d, mut q) = ([0.0; LEN], [0.0; LEN]);
+        f32::park(&alpha, &beta, &b, &c, &mut d, &mut q);
+        let (mut d_f, mut q_f) = ([F::cast(0.0); LEN], [F::cast(0.0); LEN]);
+        F::park(&alpha_f, &beta_f, &bf, &cf, &mut d_f, &mut q_f);
+
+        for i in 0..LEN {
+            assert!(near(alpha_f[i], alpha[i], 1e-4));
+            assert!(near(beta_f[i], beta[i], 1e-4));
+            assert!(near(d_f[i], d[i], 1e-4));
+            assert!(near(q_f[i], q[i], 1e-4));
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::Cast;
+use core::f64::consts::{FRAC_PI_2, PI};
+
+/// Sine and cosine
+///
+/// This is intended mainly for evaluating coefficients (twiddle factors, windows and etc.)
+/// on targets without standard library, so it isn't optimized for speed.
+pub trait SinCos: Sized {
+    /// Get sine and cosine of angle in radians
+    fn sin_cos(self) -> (Self, Self);
+}
+
+impl SinCos for f64 {
+    fn sin_cos(self) -> (Self, Self) {
+        // Reduce the argument to [-π, π]
+        let turns = self / (2.0 * PI);
+        let turns = (if turns < 0.0 {
+            turns - 0.5
+        } else {
+            turns + 0.5
+        }) as i64;
+        let x = self - turns as f64 * (2.0 * PI);
+
+        // Reduce the argument to [-π/2, π/2] using symmetry around ±π/2
+        let (x, sign) = if x > FRAC_PI_2 {
+            (PI - x, -1.0)
+        } else if x < -FRAC_PI_2 {
+            (-PI - x, -1.0)
+        } else {
+            (x, 1.0)
+        };
+
+        // Evaluate Taylor series
+        let x2 = x * x;
+        let mut sin = 0.0;
+        let mut cos = 0.0;
+
+        for n in (1..=11).rev() {
+            sin = 1.0 - x2 / ((2 * n * (2 * n + 1)) as f64) * sin;
+            cos = 1.0 - x2 / ((2 * n * (2 * n - 1)) as f64) * cos;
+        }
+
+        (x * sin, sign * cos)
+    }
+}
+
+impl SinCos for f32 {
+    fn sin_cos(self) -> (Self, Self) {
+        let (sin, cos) = SinCos::sin_cos(f64::cast(self));
+        (f32::cast(sin), f32::cast(cos))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sin_cos_f64() {
+        assert_eq!(SinCos::sin_cos(0.0f64), (0.0, 1.0));
+
+        for i in -40..=40 {
+            let x = i as f64 * 0.3;
+            let (sin, cos) = SinCos::sin_cos(x);
+            assert!((sin - x.sin()).abs() < 1e-14);
+            assert!((cos - x.cos()).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn sin_cos_f32() {
+        assert_eq!(SinCos::sin_cos(PI as f32 / 6.0), (0.5, 0.8660254));
+        assert_eq!(SinCos::sin_cos(-PI as f32), (8.742278e-8, -1.0));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::Cast;
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/// Square root
+///
+/// Negative values gives zero for fixed-point types and NaN for floating-point types.
+pub trait Sqrt {
+    /// Get square root of value
+    fn sqrt(self) -> Self;
+}
+
+macro_rules! sqrt_float {
+    ($type: ty, $magic: expr, $steps: expr) => {
+        impl Sqrt for $type {
+            fn sqrt(self) -> Self {
+                if self > 0.0 {
+                    // Initial approximation by halving the exponent
+                    let mut y = <$type>::from_bits((self.to_bits() >> 1) + $magic);
+                    // Refine using Newton's method
+                    for _ in 0..$steps {
+                        y = 0.5 * (y + self / y);
+                    }
+                    y
+                } else if self == 0.0 {
+                    0.0
+                } else {
+                    <$type>::NAN
+                }
+            }
+        }
+    };
+}
+
+sqrt_float!(f64, 0x1ff7_a3be_a91d_9b1b, 5);
+
+impl Sqrt for f32 {
+    fn sqrt(self) -> Self {
+        // Evaluate using double precision to get correctly rounded result
+        f32::cast(f64::cast(self).sqrt())
+    }
+}
+
+/// Integer square root using digit-by-digit method
+fn isqrt(mut n: u64) -> u64 {
+    let mut res = 0;
+    let mut bit = 1 << 62;
+
+    while bit > n {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if n >= res + bit {
+            n -= res + bit;
+            res = (res >> 1) + bit;
+        } else {
+            res >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    res
+}
+
+/// Fixed-point square root
+///
+/// The result has same type as argument.
+/// Internally it operates with 64-bit integers so the digits of mantissa plus absolute value of exponent should fit to 64 bits.
+impl<R, B, E> Sqrt for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Copy + PartialOrd + Cast<u64>,
+    u64: Cast<Mantissa<R, B>>,
+{
+    fn sqrt(self) -> Self {
+        let zero = Mantissa::<R, B>::cast(0u64);
+
+        if self.bits <= zero {
+            return Self::new(zero);
+        }
+
+        // radix^|exp|
+        let ratio = R::U64.pow(E::I32.unsigned_abs());
+        let bits = u64::cast(self.bits);
+
+        // sqrt(m * R^E) / R^E
+        Self::new(Mantissa::<R, B>::cast(if E::I32 < 0 {
+            isqrt(bits * ratio)
+        } else {
+            isqrt(bits * ratio) / ratio
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, P4, P9};
+
+    #[test]
+    fn sqrt_f32() {
+        assert_eq!(Sqrt::sqrt(0.0f32), 0.0);
+        assert_eq!(Sqrt::sqrt(1.0f32), 1.0);
+        assert_eq!(Sqrt::sqrt(4.0f32), 2.0);
+        assert_eq!(Sqrt::sqrt(3.0f32), 1.7320508);
+        assert_eq!(Sqrt::sqrt(1e-6f32), 0.001);
+        assert!(Sqrt::sqrt(-1.0f32).is_nan());
+    }
+
+    #[test]
+    fn sqrt_f64() {
+        assert_eq!(Sqrt::sqrt(9.0f64), 3.0);
+        assert_eq!(Sqrt::sqrt(5.0f64), 2.23606797749979);
+        assert_eq!(Sqrt::sqrt(12345.678f64), 111.11110655555547);
+    }
+
+    #[test]
+    fn sqrt_fix_bin() {
+        type F = ufix::bin::Fix<P32, N16>;
+
+        assert_eq!(Sqrt::sqrt(F::cast(0.0)), F::cast(0.0));
+        assert_eq!(Sqrt::sqrt(F::cast(-4.0)), F::cast(0.0));
+        assert_eq!(Sqrt::sqrt(F::cast(4.0)), F::cast(2.0));
+        assert_eq!(Sqrt::sqrt(F::cast(2.0)), F::new(92681));
+        assert_eq!(Sqrt::sqrt(F::cast(0.25)), F::cast(0.5));
+    }
+
+    #[test]
+    fn sqrt_fix_dec() {
+        type F = ufix::si::Milli<P9>;
+
+        assert_eq!(Sqrt::sqrt(F::new(2_000)), F::new(1_414));
+        assert_eq!(Sqrt::sqrt(F::new(144_000)), F::new(12_000));
+
+        type K = ufix::si::Kilo<P4>;
+
+        assert_eq!(Sqrt::sqrt(K::new(4_000)), K::new(2));
+    }
+}
@@ -0,0 +1,49 @@
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/// Wrapping (modular) arithmetic
+///
+/// Some algorithms (like CIC filters) relies on two's complement overflow behavior.
+pub trait WrappingOps {
+    /// Wrapping addition
+    fn wrapping_add(self, other: Self) -> Self;
+    /// Wrapping subtraction
+    fn wrapping_sub(self, other: Self) -> Self;
+}
+
+macro_rules! wrapping_impl {
+    ($($type: ty),*) => {
+        $(
+            impl WrappingOps for $type {
+                #[inline]
+                fn wrapping_add(self, other: Self) -> Self {
+                    <$type>::wrapping_add(self, other)
+                }
+
+                #[inline]
+                fn wrapping_sub(self, other: Self) -> Self {
+                    <$type>::wrapping_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+wrapping_impl!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+#[cfg(feature = "i128")]
+wrapping_impl!(u128, i128);
+
+impl<R, B, E> WrappingOps for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: WrappingOps,
+{
+    fn wrapping_add(self, other: Self) -> Self {
+        Self::new(self.bits.wrapping_add(other.bits))
+    }
+
+    fn wrapping_sub(self, other: Self) -> Self {
+        Self::new(self.bits.wrapping_sub(other.bits))
+    }
+}
@@ -0,0 +1,21 @@
+pub mod absenc;
+pub mod fft;
+pub mod health;
+pub mod histogram;
+pub mod period;
+pub mod power;
+pub mod pwm;
+pub mod rms;
+pub mod stats;
+pub mod stiction;
+pub mod telemetry;
+pub mod totalizer;
+pub mod window;
+pub mod xcorr;
+
+#[cfg(feature = "psychrometrics")]
+pub mod psychro;
+#[cfg(feature = "rtd")]
+pub mod rtd;
+#[cfg(feature = "thermocouple")]
+pub mod thermocouple;
@@ -0,0 +1,266 @@
+/*!
+
+## Absolute encoder decoding
+
+This module implements decoding of raw readings of absolute position sensors
+(optical and magnetic encoders, resolvers-to-digital converters and etc.) into [angle](crate::Cyc).
+
+The decoding includes:
+
+- extraction of position bits from raw word (_shift_ and _bits_),
+- conversion from [Gray code](https://en.wikipedia.org/wiki/Gray_code) when required,
+- compensation of mounting offset,
+- counting of turns by detecting wraps of single-turn position.
+
+The output is a multi-turn angle in cycles, so the integer part is a number of turns
+and the fractional part is a single-turn position.
+The output type should have at least _bits_ fractional digits to keep the full resolution.
+
+The position shouldn't change more than half of turn between readings to count turns properly.
+
+*/
+
+use crate::{snapshot_fields, Cast, Cyc, ResetState, Transducer};
+use core::marker::PhantomData;
+use typenum::Integer;
+use ufix::{bin, Digits, Exponent, Mantissa, Radix};
+
+/// Convert binary to Gray code
+pub fn gray_encode(value: u32) -> u32 {
+    value ^ (value >> 1)
+}
+
+/// Convert Gray code to binary
+pub fn gray_decode(mut code: u32) -> u32 {
+    let mut shift = 1;
+    while shift < 32 {
+        code ^= code >> shift;
+        shift <<= 1;
+    }
+    code
+}
+
+/**
+Absolute encoder parameters
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// The position of least significant bit in raw word
+    shift: u32,
+    /// The resolution in bits
+    bits: u32,
+    /// The position is Gray coded
+    gray: bool,
+    /// The zero position offset in counts
+    offset: u32,
+}
+
+impl Param {
+    /**
+    Init absolute encoder parameters
+
+    - `bits`: The resolution in bits (1..=24)
+    - `shift`: The position of least significant bit of position in raw word
+     */
+    pub fn new(bits: u32, shift: u32) -> Self {
+        assert!(bits > 0 && bits <= 24 && bits + shift <= 32);
+
+        Self {
+            shift,
+            bits,
+            gray: false,
+            offset: 0,
+        }
+    }
+
+    /// Use Gray coded position
+    pub fn with_gray(mut self) -> Self {
+        self.gray = true;
+        self
+    }
+
+    /**
+    Set zero position
+
+    - `offset`: The position in counts which corresponds to zero angle
+     */
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = offset & self.mask();
+        self
+    }
+
+    /// The number of counts per turn
+    pub fn counts(&self) -> u32 {
+        1 << self.bits
+    }
+
+    fn mask(&self) -> u32 {
+        self.counts() - 1
+    }
+
+    /// Extract single-turn position in counts from raw word
+    pub fn position(&self, raw: u32) -> u32 {
+        let code = (raw >> self.shift) & self.mask();
+        let value = if self.gray { gray_decode(code) } else { code };
+        value.wrapping_sub(self.offset) & self.mask()
+    }
+}
+
+/**
+Absolute encoder state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The number of turns
+    turns: i32,
+    /// The last single-turn position
+    last: Option<u32>,
+}
+
+impl State {
+    /// The number of turns
+    pub fn turns(&self) -> i32 {
+        self.turns
+    }
+}
+
+/// The preset of state sets the number of turns
+impl ResetState for State {
+    type Value = i32;
+
+    fn reset(&mut self) {
+        self.preset(0);
+    }
+
+    fn preset(&mut self, turns: Self::Value) {
+        self.turns = turns;
+        self.last = None;
+    }
+}
+
+snapshot_fields! {
+    State {
+        turns: i32,
+        last: Option<u32>,
+    }
+}
+
+/**
+Absolute encoder decoder
+
+- `B` - output digits
+- `E` - output exponent
+
+The input is a raw word. The output is a multi-turn angle `Cyc<bin::Fix<B, E>>`.
+*/
+#[derive(Debug)]
+pub struct Decoder<B, E>(PhantomData<(B, E)>);
+
+impl<B, E> Transducer for Decoder<B, E>
+where
+    B: Digits,
+    E: Exponent + Integer,
+    typenum::P2: Radix<B>,
+    Mantissa<typenum::P2, B>: Cast<i64>,
+{
+    type Input = u32;
+    type Output = Cyc<bin::Fix<B, E>>;
+    type Param = Param;
+    type State = State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, raw: Self::Input) -> Self::Output {
+        let position = param.position(raw);
+
+        if let Some(last) = state.last {
+            let half = param.counts() / 2;
+
+            if last > position && last - position > half {
+                state.turns += 1;
+            } else if position > last && position - last > half {
+                state.turns -= 1;
+            }
+        }
+        state.last = Some(position);
+
+        // the mantissa of (turns + position / 2^bits) with exponent E
+        let value = ((state.turns as i64) << param.bits) + position as i64;
+        let shift = -E::I32 - param.bits as i32;
+
+        Cyc(bin::Fix::new(Mantissa::<typenum::P2, B>::cast(
+            if shift >= 0 {
+                value << shift
+            } else {
+                value >> -shift
+            },
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+
+    type T = bin::Fix<P32, N16>;
+    type D = Decoder<P32, N16>;
+
+    #[test]
+    fn gray_code() {
+        for value in 0..1024 {
+            assert_eq!(gray_decode(gray_encode(value)), value);
+            // the adjacent codes differ by single bit
+            assert_eq!(
+                (gray_encode(value) ^ gray_encode(value + 1)).count_ones(),
+                1
+            );
+        }
+        assert_eq!(gray_decode(0b1101), 0b1001);
+        assert_eq!(gray_decode(0x8000_0000), 0xffff_ffff);
+    }
+
+    #[test]
+    fn single_turn() {
+        // 12 bits position in bits 4..16 of raw word
+        let param = Param::new(12, 4);
+        let mut state = State::default();
+
+        assert_eq!(D::apply(&param, &mut state, 0x4000_0000), Cyc(T::cast(0.0)));
+        assert_eq!(
+            D::apply(&param, &mut state, 0x0000_4000),
+            Cyc(T::cast(0.25))
+        );
+        assert_eq!(
+            D::apply(&param, &mut state, 0x0000_c00f),
+            Cyc(T::cast(0.75))
+        );
+
+        let param = Param::new(12, 4).with_gray().with_offset(0x400);
+        state.reset();
+        assert_eq!(
+            D::apply(&param, &mut state, gray_encode(0xc00) << 4),
+            Cyc(T::cast(0.5))
+        );
+    }
+
+    #[test]
+    fn multi_turn() {
+        let param = Param::new(8, 0);
+        let mut state = State::default();
+
+        assert_eq!(D::apply(&param, &mut state, 0xc0), Cyc(T::cast(0.75)));
+        assert_eq!(D::apply(&param, &mut state, 0x20), Cyc(T::cast(1.125)));
+        assert_eq!(D::apply(&param, &mut state, 0x80), Cyc(T::cast(1.5)));
+        assert_eq!(state.turns(), 1);
+
+        // backward
+        assert_eq!(D::apply(&param, &mut state, 0x10), Cyc(T::cast(1.0625)));
+        assert_eq!(D::apply(&param, &mut state, 0xf0), Cyc(T::cast(0.9375)));
+        assert_eq!(D::apply(&param, &mut state, 0x80), Cyc(T::cast(0.5)));
+        assert_eq!(D::apply(&param, &mut state, 0x00), Cyc(T::cast(0.0)));
+        assert_eq!(D::apply(&param, &mut state, 0xc0), Cyc(T::cast(-0.25)));
+        assert_eq!(state.turns(), -1);
+
+        state.preset(10);
+        assert_eq!(D::apply(&param, &mut state, 0x40), Cyc(T::cast(10.25)));
+    }
+}
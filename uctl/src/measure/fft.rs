@@ -0,0 +1,234 @@
+/*!
+
+## Fast Fourier transform
+
+This module implements in-place radix-2 decimation-in-time **FFT** which is intended
+for on-target spectrum diagnostics (vibration analysis, motor current signature analysis and etc.).
+
+The transform operates on a block of _N_ complex samples which is represented by two buffers
+(real and imaginary parts). The length _N_ must be a power of two.
+
+_X\[k\] = Σ x\[n\] e<sup>-2πjkn/N</sup>_
+
+### Block floating point
+
+The magnitude of spectrum grows up to _N_ times, so fixed-point buffers may easily overflow.
+To avoid it the block floating point scaling is used: before each stage of butterflies the whole
+block is halved when any of values exceeds the safe limit. The number of halvings is returned
+as the common exponent of block, so the actual spectrum is _X\[k\] 2<sup>s</sup>_.
+
+This keeps the best available precision for weak signals and never overflows for strong ones.
+
+*/
+
+use crate::{Cast, SinCos};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+use typenum::{Diff, PowerOfTwo, Prod, Quot, Sum, Unsigned, U2};
+
+/**
+FFT parameters
+
+- `W` - twiddle factors type
+- `T` - values type
+- `N` - the length of transform
+*/
+#[derive(Debug, Clone)]
+pub struct Param<W, T, N>
+where
+    N: Div<U2>,
+    Quot<N, U2>: ArrayLength<W>,
+{
+    /// The cosines of twiddle factors
+    cos: GenericArray<W, Quot<N, U2>>,
+    /// The negated sines of twiddle factors
+    sin: GenericArray<W, Quot<N, U2>>,
+    /// The one half
+    half: W,
+    /// The maximum magnitude of values which is safe for butterflies
+    limit: T,
+    _phantom: PhantomData<N>,
+}
+
+impl<W, T, N> Param<W, T, N>
+where
+    N: Unsigned + PowerOfTwo + Div<U2>,
+    Quot<N, U2>: ArrayLength<W>,
+{
+    /**
+    Init FFT parameters
+
+    - `range`: The maximum absolute value which can be represented by values type
+
+    The butterfly may increase the magnitude of values up to _1 + √2_ times,
+    so the block is scaled when magnitude exceeds the _range / (1 + √2)_.
+     */
+    pub fn new<V>(range: V) -> Self
+    where
+        W: Cast<f64>,
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let angle = 2.0 * core::f64::consts::PI / N::USIZE as f64;
+
+        Self {
+            cos: GenericArray::generate(|k| W::cast(SinCos::sin_cos(k as f64 * angle).1)),
+            sin: GenericArray::generate(|k| W::cast(-SinCos::sin_cos(k as f64 * angle).0)),
+            half: W::cast(0.5),
+            limit: T::cast(f64::cast(range) / (1.0 + core::f64::consts::SQRT_2)),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/**
+Evaluate in-place FFT
+
+- `param`: The FFT parameters
+- `re`: The real parts of samples
+- `im`: The imaginary parts of samples (zeros for real signal)
+
+The buffers is replaced by spectrum in natural order.
+Returns the exponent of block (the number of halvings of values).
+*/
+pub fn transform<W, T, N>(
+    param: &Param<W, T, N>,
+    re: &mut GenericArray<T, N>,
+    im: &mut GenericArray<T, N>,
+) -> u32
+where
+    W: Copy + Mul<T>,
+    T: Copy
+        + PartialOrd
+        + Neg<Output = T>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<W, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    N: Unsigned + ArrayLength<T> + Div<U2>,
+    Quot<N, U2>: ArrayLength<W>,
+{
+    let n = N::USIZE;
+    if n < 2 {
+        return 0;
+    }
+
+    // Reorder samples in bit-reversed order
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut exponent = 0;
+    let mut size = 2;
+
+    while size <= n {
+        if exceeds(re, param.limit) || exceeds(im, param.limit) {
+            for x in re.iter_mut().chain(im.iter_mut()) {
+                *x = T::cast(param.half * *x);
+            }
+            exponent += 1;
+        }
+
+        let half = size / 2;
+        let step = n / size;
+
+        for start in (0..n).step_by(size) {
+            for k in 0..half {
+                let (wr, wi) = (param.cos[k * step], param.sin[k * step]);
+                let (i, j) = (start + k, start + k + half);
+
+                // t = w b
+                let tr = T::cast(T::cast(wr * re[j]) - T::cast(wi * im[j]));
+                let ti = T::cast(T::cast(wr * im[j]) + T::cast(wi * re[j]));
+
+                re[j] = T::cast(re[i] - tr);
+                im[j] = T::cast(im[i] - ti);
+                re[i] = T::cast(re[i] + tr);
+                im[i] = T::cast(im[i] + ti);
+            }
+        }
+
+        size *= 2;
+    }
+
+    exponent
+}
+
+fn exceeds<T>(values: &[T], limit: T) -> bool
+where
+    T: Copy + PartialOrd + Neg<Output = T>,
+{
+    values.iter().any(|&x| x > limit || x < -limit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N30, P32, U8};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn fft_f32() {
+        let param = Param::<f32, f32, U8>::new(f32::MAX);
+
+        // impulse gives flat spectrum
+        let mut re = GenericArray::from([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let mut im = GenericArray::default();
+
+        assert_eq!(transform(&param, &mut re, &mut im), 0);
+        assert_eq!(re, GenericArray::from([1.0; 8]));
+        assert_eq!(im, GenericArray::from([0.0; 8]));
+
+        // the constant gives the single component of zero frequency
+        let mut re = GenericArray::from([0.5; 8]);
+        let mut im = GenericArray::default();
+
+        transform(&param, &mut re, &mut im);
+        assert_eq!(
+            re,
+            GenericArray::from([4.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])
+        );
+
+        // the sine of quarter of sampling rate
+        let mut re = GenericArray::from([0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0]);
+        let mut im = GenericArray::default();
+
+        transform(&param, &mut re, &mut im);
+        assert_eq!(re, GenericArray::from([0.0; 8]));
+        assert_eq!(
+            im,
+            GenericArray::from([0.0, 0.0, -4.0, 0.0, 0.0, 0.0, 4.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn fft_fix() {
+        type W = Fix<P32, N30>;
+        type T = Fix<P32, N16>;
+
+        let param = Param::<W, T, U8>::new(32768.0);
+
+        let mut re = GenericArray::from([T::cast(1.0); 8]);
+        let mut im = GenericArray::from([T::cast(0.0); 8]);
+
+        assert_eq!(transform(&param, &mut re, &mut im), 0);
+        assert_eq!(re[0], T::cast(8.0));
+
+        // the block is scaled to avoid overflow: 8 * 10000 > 32768
+        let mut re = GenericArray::from([T::cast(10000.0); 8]);
+        let mut im = GenericArray::from([T::cast(0.0); 8]);
+
+        assert_eq!(transform(&param, &mut re, &mut im), 2);
+        assert_eq!(re[0], T::cast(20000.0));
+        assert_eq!(re[1], T::cast(0.0));
+    }
+}
@@ -0,0 +1,318 @@
+/*!
+
+## Loop health monitor
+
+This module implements the control performance monitor which watches the loop
+and flags the loops which needs retuning.
+
+The monitor watches the error _e = r - y_ of loop and detects two kinds of poor performance:
+
+- the sustained oscillation, when the error crosses zero too often during each window of observation
+  (the too aggressive tuning)
+- the sluggish response, when the error keeps the same sign too long
+  (the too conservative tuning)
+
+The error within the deadband is treated as zero and the crossing is counted only when
+the error goes from one side of deadband to another, so the noise doesn't give false crossings.
+
+The output of regulator is watched too: when the output is saturated the long-lasting error
+is caused by the lack of actuator power rather than tuning, so the sluggish response isn't flagged.
+
+The oscillation is flagged at the end of window and holds during the next window.
+The sluggish response is flagged immediately when the error keeps the sign longer than given time.
+The setpoint changes gives the long-lasting error naturally, so this time should be longer
+than expected settling time of loop.
+
+*/
+
+use crate::{snapshot_fields, steps, Cast, ResetState, Transducer};
+use core::{marker::PhantomData, ops::Sub};
+use typenum::Diff;
+
+/// The health of loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Health {
+    /// The loop performs well
+    #[default]
+    Normal,
+    /// The loop oscillates
+    Oscillating,
+    /// The loop responds too slow
+    Sluggish,
+}
+
+/**
+Loop health monitor parameters
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The upper bound of deadband
+    high: T,
+    /// The lower bound of deadband
+    low: T,
+    /// The window of observation in steps
+    window: u32,
+    /// The number of zero crossings per window which indicates oscillation
+    crossings: u32,
+    /// The time of error with the same sign which indicates sluggish response in steps
+    sluggish: u32,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<T> Param<T> {
+    /**
+    Init loop health monitor parameters
+
+    - `deadband`: The deadband of error (usually several times more than noise)
+    - `window`: The window of observation (s)
+    - `crossings`: The number of zero crossings of error per window which indicates oscillation
+    - `sluggish`: The time of error with the same sign which indicates sluggish response (s)
+    - `period`: The sampling period (s)
+
+    The output limits isn't set by default.
+     */
+    pub fn new<V>(deadband: V, window: V, crossings: u32, sluggish: V, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let period = f64::cast(period);
+        let deadband = f64::cast(deadband);
+
+        assert!(crossings > 0 && deadband >= 0.0);
+
+        Self {
+            high: T::cast(deadband),
+            low: T::cast(-deadband),
+            window: steps(f64::cast(window), period),
+            crossings,
+            sluggish: steps(f64::cast(sluggish), period),
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output of regulator
+    - `max`: The maximum output of regulator
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        self.min = Some(T::cast(f64::cast(min)));
+        self.max = Some(T::cast(f64::cast(max)));
+        self
+    }
+}
+
+/**
+Loop health monitor state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The side of error outside the deadband (-1, 0 or 1)
+    side: i8,
+    /// The number of zero crossings in the current window
+    counter: u32,
+    /// The number of zero crossings in the last window
+    crossings: u32,
+    /// The number of steps in the current window
+    elapsed: u32,
+    /// The number of steps while the error keeps the side
+    same_side: u32,
+}
+
+impl State {
+    /// The number of zero crossings of error in the last window
+    pub fn crossings(&self) -> u32 {
+        self.crossings
+    }
+}
+
+impl ResetState for State {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State {
+        side: i8,
+        counter: u32,
+        crossings: u32,
+        elapsed: u32,
+        same_side: u32,
+    }
+}
+
+/**
+Loop health monitor
+
+- `T` - values type
+
+The input is a tuple of setpoint, measurement and output of regulator.
+The output is a health of loop.
+*/
+#[derive(Debug)]
+pub struct Monitor<T>(PhantomData<T>);
+
+impl<T> Transducer for Monitor<T>
+where
+    T: Copy + PartialOrd + Sub<T> + Cast<Diff<T, T>>,
+{
+    type Input = (T, T, T);
+    type Output = Health;
+    type Param = Param<T>;
+    type State = State;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (setpoint, measurement, output): Self::Input,
+    ) -> Self::Output {
+        let error = T::cast(setpoint - measurement);
+
+        let side = if error > param.high {
+            1
+        } else if error < param.low {
+            -1
+        } else {
+            0
+        };
+
+        if side != 0 && side != state.side {
+            if state.side != 0 {
+                state.counter += 1;
+            }
+            state.side = side;
+            state.same_side = 0;
+        }
+
+        let saturated = matches!(param.max, Some(max) if output >= max)
+            || matches!(param.min, Some(min) if output <= min);
+
+        if side != 0 && !saturated {
+            state.same_side = state.same_side.saturating_add(1);
+        } else {
+            state.same_side = 0;
+        }
+
+        state.elapsed += 1;
+        if state.elapsed >= param.window {
+            state.crossings = state.counter;
+            state.counter = 0;
+            state.elapsed = 0;
+        }
+
+        if state.crossings >= param.crossings {
+            Health::Oscillating
+        } else if state.same_side > param.sluggish {
+            Health::Sluggish
+        } else {
+            Health::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn health_oscillating() {
+        type M = Monitor<f32>;
+        // 10 s window with 4 crossings and 20 s sluggish time
+        let param = Param::<f32>::new(0.1, 10.0, 4, 20.0, 1.0);
+        let mut state = State::default();
+
+        // the oscillation with 4 s period gives 5 crossings per window
+        let mut health = [Health::Normal; 30];
+        for (step, health) in health.iter_mut().enumerate() {
+            let y = if step % 4 < 2 { 1.0 } else { -1.0 };
+            *health = M::apply(&param, &mut state, (0.0, y, 0.0));
+        }
+
+        assert_eq!(health[8], Health::Normal);
+        assert_eq!(health[9], Health::Oscillating);
+        assert_eq!(health[29], Health::Oscillating);
+        assert_eq!(state.crossings(), 5);
+
+        // the noise within deadband doesn't give crossings
+        for step in 0..20 {
+            let y = if step % 2 == 0 { 0.05 } else { -0.05 };
+            M::apply(&param, &mut state, (0.0, y, 0.0));
+        }
+        assert_eq!(state.crossings(), 0);
+        assert_eq!(
+            M::apply(&param, &mut state, (0.0, 0.0, 0.0)),
+            Health::Normal
+        );
+    }
+
+    #[test]
+    fn health_sluggish() {
+        type M = Monitor<f32>;
+        let param = Param::<f32>::new(0.1, 10.0, 4, 5.0, 1.0).with_limits(0.0, 100.0);
+        let mut state = State::default();
+
+        let mut health = [Health::Normal; 8];
+        for health in health.iter_mut() {
+            *health = M::apply(&param, &mut state, (50.0, 40.0, 60.0));
+        }
+        assert_eq!(health[4], Health::Normal);
+        assert_eq!(health[5], Health::Sluggish);
+
+        // the saturated output isn't a tuning problem
+        assert_eq!(
+            M::apply(&param, &mut state, (50.0, 40.0, 100.0)),
+            Health::Normal
+        );
+        // the error within deadband
+        assert_eq!(
+            M::apply(&param, &mut state, (50.0, 49.95, 60.0)),
+            Health::Normal
+        );
+    }
+
+    #[test]
+    fn health_fix() {
+        type T = Fix<P32, N16>;
+        type M = Monitor<T>;
+        let param = Param::<T>::new(0.5, 4.0, 2, 3.0, 1.0);
+        let mut state = State::default();
+
+        let input = |r: f64, y: f64| (T::cast(r), T::cast(y), T::cast(0.0));
+
+        for _ in 0..4 {
+            M::apply(&param, &mut state, input(1.0, 0.0));
+        }
+        // the crossings is counted during the second window
+        assert_eq!(
+            M::apply(&param, &mut state, input(1.0, 2.0)),
+            Health::Normal
+        );
+        M::apply(&param, &mut state, input(1.0, 0.0));
+        M::apply(&param, &mut state, input(1.0, 2.0));
+        assert_eq!(
+            M::apply(&param, &mut state, input(1.0, 0.0)),
+            Health::Oscillating
+        );
+    }
+}
@@ -0,0 +1,247 @@
+/*!
+
+## Histogram
+
+This module implements histogram accumulator with fixed bins for long-term monitoring
+of signal health (distribution of temperatures, loads, errors and etc.).
+
+The bins is defined by _N_ sorted edges which split the range of values to _N + 1_ bins:
+
+- bin _0_ counts values below the first edge,
+- bin _i_ counts values in range _\[e<sub>i-1</sub>, e<sub>i</sub>)_,
+- bin _N_ counts values starting from the last edge.
+
+The counters saturates instead of overflow, so the histogram can be collected indefinitely.
+The histograms collected separately (for example by different channels or devices) can be merged.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{marker::PhantomData, ops::Add};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Add1, B1};
+
+/**
+Histogram parameters
+
+- `T` - values type
+- `N` - the number of edges
+*/
+#[derive(Debug, Clone)]
+pub struct Param<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The edges of bins
+    edges: GenericArray<T, N>,
+}
+
+impl<T, N> Param<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /**
+    Init histogram parameters
+
+    - `edges`: The edges of bins in ascending order
+     */
+    pub fn new(edges: GenericArray<T, N>) -> Self
+    where
+        T: PartialOrd,
+    {
+        debug_assert!(edges.windows(2).all(|pair| pair[0] < pair[1]));
+        Self { edges }
+    }
+
+    /// The edges of bins
+    pub fn edges(&self) -> &[T] {
+        &self.edges
+    }
+
+    /// Get the index of bin for value
+    pub fn bin(&self, value: &T) -> usize
+    where
+        T: PartialOrd,
+    {
+        self.edges.iter().take_while(|edge| *edge <= value).count()
+    }
+}
+
+/**
+Histogram state
+
+- `N` - the number of edges
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<N>
+where
+    N: Add<B1>,
+    Add1<N>: ArrayLength<u32>,
+{
+    /// The counters of bins
+    counts: GenericArray<u32, Add1<N>>,
+}
+
+impl<N> State<N>
+where
+    N: Add<B1>,
+    Add1<N>: ArrayLength<u32>,
+{
+    /// The counters of bins
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// The total number of values
+    pub fn total(&self) -> u32 {
+        self.counts
+            .iter()
+            .fold(0, |total, &count| total.saturating_add(count))
+    }
+
+    /// Add the counts of other histogram
+    pub fn merge(&mut self, other: &Self) {
+        for (count, &other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count = count.saturating_add(other);
+        }
+    }
+
+    /**
+    Get the percentile
+
+    - `param`: The histogram parameters
+    - `fraction`: The fraction of values (0..1)
+
+    Returns the upper edge of bin which contains the percentile.
+    Returns `None` when histogram is empty or the percentile falls into the last bin.
+     */
+    pub fn percentile<T, V>(&self, param: &Param<T, N>, fraction: V) -> Option<T>
+    where
+        T: Copy,
+        N: ArrayLength<T>,
+        f64: Cast<V>,
+    {
+        let target = f64::cast(fraction) * self.total() as f64;
+        let mut accum = 0u32;
+
+        for (index, &count) in self.counts.iter().enumerate() {
+            accum = accum.saturating_add(count);
+            if accum > 0 && accum as f64 >= target {
+                return param.edges.get(index).copied();
+            }
+        }
+
+        None
+    }
+}
+
+impl<N> ResetState for State<N>
+where
+    N: Add<B1>,
+    Add1<N>: ArrayLength<u32>,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        for count in self.counts.iter_mut() {
+            *count = 0;
+        }
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<N> where [N: Add<B1>, Add1<N>: ArrayLength<u32>] {
+        counts: GenericArray<u32, Add1<N>>,
+    }
+}
+
+/**
+Histogram accumulator
+
+- `T` - values type
+- `N` - the number of edges
+
+The output is an index of bin which counts the input value.
+*/
+#[derive(Debug)]
+pub struct Histogram<T, N>(PhantomData<(T, N)>);
+
+impl<T, N> Transducer for Histogram<T, N>
+where
+    T: PartialOrd,
+    N: ArrayLength<T> + Add<B1>,
+    Add1<N>: ArrayLength<u32>,
+{
+    type Input = T;
+    type Output = usize;
+    type Param = Param<T, N>;
+    type State = State<N>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let bin = param.bin(&value);
+        state.counts[bin] = state.counts[bin].saturating_add(1);
+        bin
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn histogram_f32() {
+        type H = Histogram<f32, U3>;
+
+        let param = Param::new(GenericArray::from([0.0, 1.0, 2.0]));
+        let mut state = State::default();
+
+        assert_eq!(H::apply(&param, &mut state, -0.5), 0);
+        assert_eq!(H::apply(&param, &mut state, 0.0), 1);
+        assert_eq!(H::apply(&param, &mut state, 0.5), 1);
+        assert_eq!(H::apply(&param, &mut state, 1.5), 2);
+        assert_eq!(H::apply(&param, &mut state, 2.0), 3);
+
+        assert_eq!(state.counts(), &[1, 2, 1, 1]);
+        assert_eq!(state.total(), 5);
+
+        assert_eq!(state.percentile(&param, 0.0), Some(0.0));
+        assert_eq!(state.percentile(&param, 0.5), Some(1.0));
+        assert_eq!(state.percentile(&param, 0.8), Some(2.0));
+        assert_eq!(state.percentile(&param, 1.0), None);
+
+        let other = state.clone();
+        state.merge(&other);
+        assert_eq!(state.counts(), &[2, 4, 2, 2]);
+
+        state.reset();
+        assert_eq!(state.total(), 0);
+        assert_eq!(state.percentile(&param, 0.5), None);
+    }
+
+    #[test]
+    fn histogram_fix() {
+        type T = Fix<P32, N16>;
+        type H = Histogram<T, U3>;
+
+        let param = Param::new(GenericArray::from([
+            T::cast(20.0),
+            T::cast(40.0),
+            T::cast(60.0),
+        ]));
+        let mut state = State::default();
+
+        for value in &[25.0, 30.0, 35.0, 45.0, 70.0] {
+            H::apply(&param, &mut state, T::cast(*value));
+        }
+
+        assert_eq!(state.counts(), &[0, 3, 1, 1]);
+        assert_eq!(state.percentile(&param, 0.5), Some(T::cast(40.0)));
+        assert_eq!(state.percentile(&param, 0.8), Some(T::cast(60.0)));
+    }
+}
@@ -0,0 +1,331 @@
+/*!
+
+## Loop period meter
+
+This module implements the meter of actual period of control loop.
+
+The period of loop on the busy MCUs isn't exactly constant because of interrupts latency,
+preemption by other tasks and etc. The period-dependent blocks which uses the nominal period
+(like integrators) accumulates the bias in that case.
+
+The meter takes the timestamps from the free-running timer (like cycle counter)
+at each execution of loop and outputs the actual elapsed time since previous execution:
+
+_dt = (t - t<sub>-1</sub>) / f<sub>clk</sub>_
+
+The difference of timestamps is evaluated using wrapping arithmetic,
+so the overflow of timer is handled transparently.
+
+The actual period is clamped to the tolerance range around nominal period,
+so the stalls of loop (like debugger breaks) doesn't cause huge steps.
+The periods out of range is counted as overruns (or underruns).
+Also the minimum and maximum periods is tracked to estimate the jitter of loop.
+
+The measured period can be fed into the variable-period blocks
+(like [`VarPid`](crate::pid::VarPid) or [`VarFilter`](crate::ema::VarFilter)).
+
+The time is converted using integer arithmetic on mantissa of fixed-point output.
+The products of ticks and scale should fit into 64 bits.
+The floating-point output is a product of ticks and the period of tick.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{fmt::Debug, marker::PhantomData};
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/// The fractional bits of scale
+const SCALE_BITS: u32 = 32;
+
+/**
+The period type which can be converted from timer ticks
+*/
+pub trait TickPeriod: Sized {
+    /// The scale of ticks
+    type Scale: Debug + Clone + Copy;
+
+    /// Evaluate the scale from the frequency of timer clock (Hz)
+    fn scale(clock: f64) -> Self::Scale;
+
+    /// Convert the number of ticks to period
+    fn from_ticks(ticks: u32, scale: Self::Scale) -> Self;
+}
+
+/// The scale is the mantissas of period per tick (with `SCALE_BITS` fractional bits)
+impl<R, B, E> TickPeriod for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Cast<u64>,
+    f64: Cast<Fix<R, B, E>>,
+{
+    type Scale = u64;
+
+    fn scale(clock: f64) -> Self::Scale {
+        // the value of least significant digit
+        let lsb = f64::cast(Self::new(Mantissa::<R, B>::cast(1u64)));
+        ((1u64 << SCALE_BITS) as f64 / (clock * lsb)) as u64
+    }
+
+    fn from_ticks(ticks: u32, scale: Self::Scale) -> Self {
+        Self::new(Mantissa::<R, B>::cast((ticks as u64 * scale) >> SCALE_BITS))
+    }
+}
+
+macro_rules! tick_period_float {
+    ($($type: ty),*) => {
+        $(
+            /// The scale is the period of tick
+            impl TickPeriod for $type {
+                type Scale = $type;
+
+                fn scale(clock: f64) -> Self::Scale {
+                    (1.0 / clock) as $type
+                }
+
+                fn from_ticks(ticks: u32, scale: Self::Scale) -> Self {
+                    ticks as $type * scale
+                }
+            }
+        )*
+    };
+}
+
+tick_period_float!(f32, f64);
+
+/**
+Loop period meter parameters
+
+- `T` - period type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T>
+where
+    T: TickPeriod,
+{
+    /// The scale of ticks
+    scale: T::Scale,
+    /// The nominal period in ticks
+    nominal: u32,
+    /// The minimum period in ticks
+    min_period: u32,
+    /// The maximum period in ticks
+    max_period: u32,
+}
+
+impl<T> Param<T>
+where
+    T: TickPeriod,
+{
+    /**
+    Init loop period meter parameters
+
+    - `clock`: The frequency of timer clock (Hz)
+    - `period`: The nominal period of loop (s)
+    - `tolerance`: The maximum relative deviation of period (0..1)
+     */
+    pub fn new<V>(clock: V, period: V, tolerance: V) -> Self
+    where
+        f64: Cast<V>,
+    {
+        let clock = f64::cast(clock);
+        let nominal = f64::cast(period) * clock;
+        let tolerance = f64::cast(tolerance);
+
+        assert!(tolerance > 0.0 && tolerance < 1.0);
+
+        Self {
+            scale: T::scale(clock),
+            nominal: (nominal + 0.5) as u32,
+            min_period: (nominal * (1.0 - tolerance)) as u32,
+            max_period: (nominal * (1.0 + tolerance)) as u32,
+        }
+    }
+}
+
+/**
+Loop period meter state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The last timestamp
+    last: Option<u32>,
+    /// The minimum measured period in ticks
+    min_period: u32,
+    /// The maximum measured period in ticks
+    max_period: u32,
+    /// The number of periods out of tolerance range
+    overruns: u32,
+}
+
+impl State {
+    /// The minimum measured period in ticks
+    pub fn min_period(&self) -> u32 {
+        self.min_period
+    }
+
+    /// The maximum measured period in ticks
+    pub fn max_period(&self) -> u32 {
+        self.max_period
+    }
+
+    /// The peak-to-peak jitter of period in ticks
+    pub fn jitter(&self) -> u32 {
+        self.max_period.saturating_sub(self.min_period)
+    }
+
+    /// The number of periods out of tolerance range
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Reset the statistics (the last timestamp is kept)
+    pub fn reset_stats(&mut self) {
+        *self = Self {
+            last: self.last,
+            ..Self::default()
+        };
+    }
+}
+
+impl ResetState for State {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State {
+        last: Option<u32>,
+        min_period: u32,
+        max_period: u32,
+        overruns: u32,
+    }
+}
+
+/**
+Loop period meter
+
+- `T` - period type
+
+The input is a timestamp in timer ticks. The output is an actual period.
+The nominal period is output on the first step after reset.
+*/
+#[derive(Debug)]
+pub struct Meter<T>(PhantomData<T>);
+
+impl<T> Transducer for Meter<T>
+where
+    T: TickPeriod,
+{
+    type Input = u32;
+    type Output = T;
+    type Param = Param<T>;
+    type State = State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, time: Self::Input) -> Self::Output {
+        let period = match state.last {
+            Some(last) => {
+                let period = time.wrapping_sub(last);
+
+                if state.min_period == 0 || period < state.min_period {
+                    state.min_period = period;
+                }
+                if period > state.max_period {
+                    state.max_period = period;
+                }
+
+                if period < param.min_period || period > param.max_period {
+                    state.overruns = state.overruns.saturating_add(1);
+                }
+
+                period.max(param.min_period).min(param.max_period)
+            }
+            None => param.nominal,
+        };
+
+        state.last = Some(time);
+
+        T::from_ticks(period, param.scale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N28, P32};
+    use ufix::bin;
+
+    type T = bin::Fix<P32, N28>;
+    type M = Meter<T>;
+
+    #[test]
+    fn period_jitter() {
+        // 1 MHz timer, 1 ms loop
+        let param = Param::<T>::new(1e6, 1e-3, 0.5);
+        let mut state = State::default();
+
+        let times = [100, 1100, 2050, 3100, 4100];
+        let mut periods = [0.0; 5];
+        for (period, time) in periods.iter_mut().zip(times.iter()) {
+            *period = f64::cast(M::apply(&param, &mut state, *time));
+        }
+
+        let expected = [1e-3, 1e-3, 0.95e-3, 1.05e-3, 1e-3];
+        for (period, expected) in periods.iter().zip(expected.iter()) {
+            assert!((period - expected).abs() < 1e-8, "{} {}", period, expected);
+        }
+
+        assert_eq!(state.min_period(), 950);
+        assert_eq!(state.max_period(), 1050);
+        assert_eq!(state.jitter(), 100);
+        assert_eq!(state.overruns(), 0);
+    }
+
+    #[test]
+    fn period_overrun() {
+        let param = Param::<T>::new(1e6, 1e-3, 0.5);
+        let mut state = State::default();
+
+        // the timer wraps around
+        M::apply(&param, &mut state, u32::MAX - 499);
+        let period = f64::cast(M::apply(&param, &mut state, 500));
+        assert!((period - 1e-3).abs() < 1e-8, "{}", period);
+
+        // the loop was stalled
+        let period = f64::cast(M::apply(&param, &mut state, 100_500));
+        assert!((period - 1.5e-3).abs() < 1e-8, "{}", period);
+        assert_eq!(state.overruns(), 1);
+        assert_eq!(state.max_period(), 100_000);
+
+        state.reset_stats();
+        assert_eq!(state.overruns(), 0);
+        let period = f64::cast(M::apply(&param, &mut state, 101_500));
+        assert!((period - 1e-3).abs() < 1e-8, "{}", period);
+    }
+
+    #[test]
+    fn period_float() {
+        let param = Param::<f32>::new(1e6, 1e-3, 0.5);
+        let mut state = State::default();
+
+        assert_eq!(Meter::<f32>::apply(&param, &mut state, 100), 1e-3);
+        let period = Meter::<f32>::apply(&param, &mut state, 1150);
+        assert!((period - 1.05e-3).abs() < 1e-9, "{}", period);
+
+        let param = Param::<f64>::new(1e6, 1e-3, 0.5);
+        let mut state = State::default();
+
+        Meter::<f64>::apply(&param, &mut state, u32::MAX - 499);
+        let period = Meter::<f64>::apply(&param, &mut state, 2_500);
+        assert!((period - 1.5e-3).abs() < 1e-12, "{}", period);
+        assert_eq!(state.overruns(), 1);
+    }
+}
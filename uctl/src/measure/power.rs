@@ -0,0 +1,320 @@
+/*!
+
+## Power metering
+
+This module implements measurement of **active** and **reactive** power and **power factor**
+from simultaneous samples of voltage and current.
+
+The measurement consists of two stages which can be chained together:
+
+1. [`Products`] evaluates instantaneous active _p = v * i_ and reactive _q = v' * i_ products,
+   where _v'_ is the voltage delayed by quarter of period (90° shift).
+2. [`Meter`] averages the products over the window of whole period and evaluates
+   apparent power _S = √(P² + Q²)_ and power factor _PF = P / S_.
+
+The quadrature delay line length should be equal to quarter of period of signal
+and the averaging delay lines should cover whole period in samples.
+
+See also [AC power](https://en.wikipedia.org/wiki/AC_power).
+
+*/
+
+use crate::{snapshot_fields, Cast, DelayLine, ResetState, Sqrt, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{AddAssign, Div, Mul},
+};
+use typenum::{Prod, Quot, Unsigned};
+
+/**
+Instantaneous power products
+
+- `V` - voltage value type
+- `I` - current value type
+- `P` - power value type
+- `L` - voltage delay line type
+
+The state is a delay line which length is quarter of period.
+*/
+#[derive(Debug)]
+pub struct Products<V, I, P, L>(PhantomData<(V, I, P, L)>);
+
+impl<V, I, P, L> Transducer for Products<V, I, P, L>
+where
+    V: Copy + Mul<I>,
+    I: Copy,
+    P: Cast<Prod<V, I>>,
+    L: DelayLine<Value = V>,
+    for<'a> &'a L: IntoIterator<Item = V>,
+{
+    type Input = (V, I);
+    type Output = (P, P);
+    type Param = ();
+    type State = L;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, (v, i): Self::Input) -> Self::Output {
+        // The oldest value in line is delayed by quarter of period
+        let v90 = state.iter().last().unwrap_or(v);
+        state.push(v);
+        (P::cast(v * i), P::cast(v90 * i))
+    }
+}
+
+/**
+Power meter parameters
+
+- `F` - scale factor type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<F> {
+    /// The reciprocal of window length
+    scale: F,
+}
+
+impl<F> Param<F> {
+    /**
+    Init power meter parameters
+
+    - `N` - the window length (the number of samples in period)
+     */
+    pub fn new<N>() -> Self
+    where
+        N: Unsigned,
+        F: Cast<f64>,
+    {
+        Self {
+            scale: F::cast(1.0 / f64::cast(N::U32)),
+        }
+    }
+}
+
+/**
+Power meter state
+
+- `L` - products delay line type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<L> {
+    /// Active power products
+    active: L,
+    /// Reactive power products
+    reactive: L,
+}
+
+impl<L> State<L> {
+    /**
+    Initialize power meter state
+
+    - `line`: The initial delay line
+     */
+    pub fn new(line: L) -> Self
+    where
+        L: Clone,
+    {
+        Self {
+            active: line.clone(),
+            reactive: line,
+        }
+    }
+}
+
+impl<L> ResetState for State<L>
+where
+    L: ResetState,
+    L::Value: Copy,
+{
+    type Value = L::Value;
+
+    fn reset(&mut self) {
+        self.active.reset();
+        self.reactive.reset();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.active.preset(value);
+        self.reactive.preset(value);
+    }
+}
+
+snapshot_fields! {
+    State<L> {
+        active: L,
+        reactive: L,
+    }
+}
+
+/**
+Power measurement results
+
+- `P` - power value type
+- `K` - power factor type
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Power<P, K> {
+    /// Active power
+    pub active: P,
+    /// Reactive power
+    pub reactive: P,
+    /// Apparent power
+    pub apparent: P,
+    /// Power factor
+    pub factor: K,
+}
+
+/**
+Power meter
+
+- `P` - power value type
+- `A` - sum accumulator type
+- `F` - scale factor type
+- `S` - square of power type
+- `D` - power factor dividend type
+- `K` - power factor type
+- `L` - products delay line type
+*/
+#[derive(Debug)]
+pub struct Meter<P, A, F, S, D, K, L>(PhantomData<(P, A, F, S, D, K, L)>);
+
+impl<P, A, F, S, D, K, L> Meter<P, A, F, S, D, K, L>
+where
+    P: Cast<Prod<F, A>>,
+    A: Default + AddAssign<P>,
+    F: Copy + Mul<A>,
+    L: DelayLine<Value = P>,
+    for<'a> &'a L: IntoIterator<Item = P>,
+{
+    fn mean(param: &Param<F>, line: &L) -> P {
+        let sum = line.iter().fold(A::default(), |mut sum, x| {
+            sum += x;
+            sum
+        });
+        P::cast(param.scale * sum)
+    }
+}
+
+impl<P, A, F, S, D, K, L> Transducer for Meter<P, A, F, S, D, K, L>
+where
+    P: Copy + Default + PartialEq + Mul<P> + Cast<Prod<F, A>> + Cast<S>,
+    A: Default + AddAssign<P>,
+    F: Copy + Mul<A>,
+    S: Sqrt + Cast<Prod<P, P>> + AddAssign<Prod<P, P>>,
+    D: Cast<P> + Div<P>,
+    K: Cast<f64> + Cast<Quot<D, P>>,
+    L: DelayLine<Value = P>,
+    for<'a> &'a L: IntoIterator<Item = P>,
+{
+    type Input = (P, P);
+    type Output = Power<P, K>;
+    type Param = Param<F>;
+    type State = State<L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, (p, q): Self::Input) -> Self::Output {
+        state.active.push(p);
+        state.reactive.push(q);
+
+        let active = Self::mean(param, &state.active);
+        let reactive = Self::mean(param, &state.reactive);
+
+        // S = √(P² + Q²)
+        let mut apparent = S::cast(active * active);
+        apparent += reactive * reactive;
+        let apparent = P::cast(apparent.sqrt());
+
+        // PF = P / S
+        let factor = if apparent == P::default() {
+            K::cast(0.0)
+        } else {
+            K::cast(D::cast(active) / apparent)
+        };
+
+        Power {
+            active,
+            reactive,
+            apparent,
+            factor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pfdl::Store as DL, Cast};
+    use core::f64::consts::FRAC_1_SQRT_2;
+    use typenum::{N16, N24, N32, N8, P16, P32, P40, P48, U2, U8};
+    use ufix::bin::Fix;
+
+    /// One period of sine wave in 8 samples
+    const SINE: [f64; 8] = [
+        0.0,
+        FRAC_1_SQRT_2,
+        1.0,
+        FRAC_1_SQRT_2,
+        0.0,
+        -FRAC_1_SQRT_2,
+        -1.0,
+        -FRAC_1_SQRT_2,
+    ];
+
+    #[test]
+    fn power_f32() {
+        type M = (
+            Products<f32, f32, f32, DL<f32, U2>>,
+            Meter<f32, f32, f32, f32, f32, f32, DL<f32, U8>>,
+        );
+
+        let param = ((), Param::<f32>::new::<U8>());
+        let mut state = (DL::from(0.0), State::new(DL::from(0.0)));
+
+        // Current lags voltage by 45 degree
+        let mut power = None;
+        for _ in 0..2 {
+            for (n, s) in SINE.iter().enumerate() {
+                let v = 10.0 * *s as f32;
+                let i = 2.0 * SINE[(n + 7) % 8] as f32;
+                power = Some(M::apply(&param, &mut state, (v, i)));
+            }
+        }
+        let power = power.unwrap();
+
+        assert_eq!(power.active, 7.071068);
+        assert_eq!(power.reactive, 7.071068);
+        assert_eq!(power.apparent, 10.0);
+        assert_eq!(power.factor, FRAC_1_SQRT_2 as f32);
+    }
+
+    #[test]
+    fn power_fix() {
+        type V = Fix<P16, N8>;
+        type I = Fix<P16, N8>;
+        type P = Fix<P32, N16>;
+        type A = Fix<P40, N16>;
+        type F = Fix<P16, N24>;
+        type S = Fix<P48, N16>;
+        type D = Fix<P48, N32>;
+        type K = Fix<P16, N16>;
+        type M = (
+            Products<V, I, P, DL<V, U2>>,
+            Meter<P, A, F, S, D, K, DL<P, U8>>,
+        );
+
+        let param = ((), Param::<F>::new::<U8>());
+        let mut state = (DL::from(V::cast(0.0)), State::new(DL::from(P::cast(0.0))));
+
+        // Current and voltage is in phase
+        let mut power = None;
+        for _ in 0..2 {
+            for s in &SINE {
+                let v = V::cast(10.0 * s);
+                let i = I::cast(2.0 * s);
+                power = Some(M::apply(&param, &mut state, (v, i)));
+            }
+        }
+        let power = power.unwrap();
+
+        assert_eq!(power.active, P::new(655_290));
+        assert_eq!(power.reactive, P::cast(0.0));
+        assert_eq!(power.apparent, P::new(655_289));
+        assert_eq!(power.factor, K::cast(1.0));
+    }
+}
@@ -0,0 +1,303 @@
+/*!
+
+## Psychrometrics
+
+This module provides the conversions of relative humidity and temperature of air
+to the dew point and the absolute humidity for HVAC control loops.
+
+The saturation vapor pressure is given by Magnus formula (with coefficients of Sonntag 1990):
+
+_E(t) = 6.112 exp(b t / (c + t))_ hPa, where _b = 17.62_, _c = 243.12 °C_
+
+The dew point for relative humidity _φ_ is:
+
+_γ = ln(φ) + b t / (c + t)_
+
+_t<sub>d</sub> = c γ / (b - γ)_
+
+The absolute humidity (density of water vapor) is:
+
+_ρ = φ 216.7 E(t) / (273.15 + t)_ g/m³
+
+All non-linear functions is approximated by polynomials which is evaluated using [polynomial](crate::poly),
+so the conversions is suitable for fixed-point types.
+The functions of temperature is given for scaled temperature _x = t / 100_ to keep
+the coefficients in reasonable range.
+
+The temperatures is in degrees of Celsius, the relative humidity is in range _0..1_.
+The approximations is valid for temperatures -20..60 °C and relative humidity 0.1..1.
+
+*/
+
+use crate::{poly, Cast, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+use typenum::{Prod, Sum, U5, U6, U7};
+
+/// The Magnus coefficient _b_
+pub const MAGNUS_B: f64 = 17.62;
+
+/// The Magnus coefficient _c_ (°C)
+pub const MAGNUS_C: f64 = 243.12;
+
+/// The temperature term of Magnus formula _b t / (c + t)_ by scaled temperature _t / 100_ (error ±0.0001)
+pub const MAGNUS_TEMPERATURE: [f64; 5] = [
+    4.984077955e-5,
+    7.248516041,
+    -2.98742069,
+    1.201616371,
+    -0.3517706091,
+];
+
+/// The humidity term of Magnus formula _ln(φ)_ by relative humidity 0.1..1 (error ±0.0055)
+pub const MAGNUS_HUMIDITY: [f64; 7] = [
+    -3.601273019,
+    17.80525636,
+    -58.56153309,
+    123.2488845,
+    -150.0875531,
+    96.44274069,
+    -25.24853132,
+];
+
+/// The dew point (°C) by Magnus term _γ_ in range -4..3.5 (error ±0.001 °C)
+pub const DEW_POINT: [f64; 6] = [
+    6.706825091e-4,
+    13.79765041,
+    0.7822117564,
+    4.453398281e-2,
+    2.692245619e-3,
+    1.422883704e-4,
+];
+
+/// The saturation vapor density (g/m³) by scaled temperature _t / 100_ (error ±0.0004 g/m³)
+pub const SATURATION_DENSITY: [f64; 7] = [
+    4.848699436,
+    33.37258724,
+    100.7064246,
+    171.5375646,
+    176.9038695,
+    111.3327328,
+    6.12101669,
+];
+
+/**
+Psychrometric conversions parameters
+
+- `A` - coefficients (and intermediate values) type
+*/
+#[derive(Debug, Clone)]
+pub struct Param<A> {
+    /// The temperature scale (1/100)
+    scale: A,
+    /// The temperature term of Magnus formula
+    temperature: poly::Param<A, U5>,
+    /// The humidity term of Magnus formula
+    humidity: poly::Param<A, U7>,
+    /// The dew point polynomial
+    dew_point: poly::Param<A, U6>,
+    /// The saturation vapor density polynomial
+    density: poly::Param<A, U7>,
+}
+
+impl<A> Default for Param<A>
+where
+    A: Cast<f64>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> Param<A> {
+    /// Init psychrometric conversions parameters
+    pub fn new() -> Self
+    where
+        A: Cast<f64>,
+    {
+        Self {
+            scale: A::cast(0.01),
+            temperature: poly::Param::new(&MAGNUS_TEMPERATURE),
+            humidity: poly::Param::new(&MAGNUS_HUMIDITY),
+            dew_point: poly::Param::new(&DEW_POINT),
+            density: poly::Param::new(&SATURATION_DENSITY),
+        }
+    }
+}
+
+/**
+Dew point
+
+- `T` - temperature type
+- `H` - relative humidity type
+- `A` - coefficients type
+
+The input is a temperature (°C) and a relative humidity (0..1). The output is a dew point (°C).
+*/
+#[derive(Debug)]
+pub struct DewPoint<T, H, A>(PhantomData<(T, H, A)>);
+
+impl<T, H, A> Transducer for DewPoint<T, H, A>
+where
+    T: Copy + Cast<A>,
+    H: Copy,
+    A: Copy
+        + Default
+        + Mul<A>
+        + Mul<H>
+        + Mul<T>
+        + Add<A>
+        + Cast<Prod<A, A>>
+        + Cast<Prod<A, H>>
+        + Cast<Prod<A, T>>
+        + Cast<Sum<A, A>>,
+{
+    type Input = (T, H);
+    type Output = T;
+    type Param = Param<A>;
+    type State = ();
+
+    fn apply(
+        param: &Self::Param,
+        _state: &mut Self::State,
+        (temperature, humidity): Self::Input,
+    ) -> Self::Output {
+        let x = A::cast(param.scale * temperature);
+
+        // γ = ln(φ) + b t / (c + t)
+        let gamma = A::cast(
+            poly::Polynomial::<A, A, U5>::apply(&param.temperature, &mut (), x)
+                + poly::Polynomial::<H, A, U7>::apply(&param.humidity, &mut (), humidity),
+        );
+
+        T::cast(poly::Polynomial::<A, A, U6>::apply(
+            &param.dew_point,
+            &mut (),
+            gamma,
+        ))
+    }
+}
+
+/**
+Absolute humidity
+
+- `T` - temperature type
+- `H` - relative humidity type
+- `A` - coefficients (and output) type
+
+The input is a temperature (°C) and a relative humidity (0..1). The output is an absolute humidity (g/m³).
+*/
+#[derive(Debug)]
+pub struct AbsoluteHumidity<T, H, A>(PhantomData<(T, H, A)>);
+
+impl<T, H, A> Transducer for AbsoluteHumidity<T, H, A>
+where
+    T: Copy,
+    H: Copy,
+    A: Copy
+        + Default
+        + Mul<A>
+        + Mul<H>
+        + Mul<T>
+        + Add<A>
+        + Cast<Prod<A, A>>
+        + Cast<Prod<A, H>>
+        + Cast<Prod<A, T>>
+        + Cast<Sum<A, A>>,
+{
+    type Input = (T, H);
+    type Output = A;
+    type Param = Param<A>;
+    type State = ();
+
+    fn apply(
+        param: &Self::Param,
+        _state: &mut Self::State,
+        (temperature, humidity): Self::Input,
+    ) -> Self::Output {
+        let x = A::cast(param.scale * temperature);
+        let density = poly::Polynomial::<A, A, U7>::apply(&param.density, &mut (), x);
+
+        A::cast(density * humidity)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N20, P32};
+    use ufix::bin::Fix;
+
+    fn saturation(t: f64) -> f64 {
+        6.112 * (MAGNUS_B * t / (MAGNUS_C + t)).exp()
+    }
+
+    fn dew_point(t: f64, h: f64) -> f64 {
+        let gamma = h.ln() + MAGNUS_B * t / (MAGNUS_C + t);
+        MAGNUS_C * gamma / (MAGNUS_B - gamma)
+    }
+
+    fn absolute(t: f64, h: f64) -> f64 {
+        h * 216.7 * saturation(t) / (273.15 + t)
+    }
+
+    const POINTS: [(f64, f64); 8] = [
+        (-20.0, 0.5),
+        (0.0, 0.1),
+        (0.0, 1.0),
+        (20.0, 0.5),
+        (25.0, 0.3),
+        (25.0, 0.9),
+        (40.0, 0.2),
+        (60.0, 0.95),
+    ];
+
+    #[test]
+    fn psychro_f64() {
+        let param = Param::<f64>::new();
+
+        for &(t, h) in &POINTS {
+            let dew = DewPoint::<f64, f64, f64>::apply(&param, &mut (), (t, h));
+            assert!((dew - dew_point(t, h)).abs() < 0.12, "{} {}", t, h);
+
+            let density = AbsoluteHumidity::<f64, f64, f64>::apply(&param, &mut (), (t, h));
+            assert!((density - absolute(t, h)).abs() < 0.001, "{} {}", t, h);
+        }
+
+        // the dew point of saturated air is equal to temperature
+        let dew = DewPoint::<f64, f64, f64>::apply(&param, &mut (), (20.0, 1.0));
+        assert!((dew - 20.0).abs() < 0.12);
+    }
+
+    #[test]
+    fn psychro_fix() {
+        type T = Fix<P32, N16>;
+        type H = Fix<P32, N16>;
+        type A = Fix<P32, N20>;
+
+        let param = Param::<A>::new();
+
+        for &(t, h) in &POINTS {
+            let dew = f64::cast(DewPoint::<T, H, A>::apply(
+                &param,
+                &mut (),
+                (T::cast(t), H::cast(h)),
+            ));
+            assert!((dew - dew_point(t, h)).abs() < 0.15, "{} {}", t, h);
+
+            let density = f64::cast(AbsoluteHumidity::<T, H, A>::apply(
+                &param,
+                &mut (),
+                (T::cast(t), H::cast(h)),
+            ));
+            assert!(
+                (density / absolute(t, h) - 1.0).abs() < 0.001,
+                "{} {}",
+                t,
+                h
+            );
+        }
+    }
+}
@@ -0,0 +1,259 @@
+/*!
+
+## PWM input decoder
+
+This module implements the decoder of PWM signals which is measured using capture timer
+(RC-servo commands, PWM-output sensors and etc.).
+
+The input is a pair of _period_ and _width_ of pulse in timer ticks.
+The outputs is the duty cycle and the frequency of signal:
+
+_D = width / period_
+
+_f = f<sub>clk</sub> / period_
+
+The division is evaluated using integer arithmetic on mantissas of fixed-point outputs,
+so the results is exact up to the least significant digit.
+The products of ticks and mantissas should fit into 64 bits.
+
+### Glitch filtering
+
+The captures with period out of expected range or with width which exceeds the period
+is treated as glitches. The glitches is counted and ignored, so the last valid result is kept.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::marker::PhantomData;
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/**
+PWM decoder parameters
+
+- `D` - duty cycle type
+- `F` - frequency type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<D, F> {
+    /// The mantissa of one (full duty)
+    one: u64,
+    /// The mantissa of timer clock frequency
+    clock: u64,
+    /// The minimum period in ticks
+    min_period: u32,
+    /// The maximum period in ticks
+    max_period: u32,
+    _phantom: PhantomData<(D, F)>,
+}
+
+impl<Rd, Bd, Ed, Rf, Bf, Ef> Param<Fix<Rd, Bd, Ed>, Fix<Rf, Bf, Ef>>
+where
+    Rd: Radix<Bd>,
+    Bd: Digits,
+    Ed: Exponent,
+    Mantissa<Rd, Bd>: Cast<u64>,
+    f64: Cast<Fix<Rd, Bd, Ed>>,
+    Rf: Radix<Bf>,
+    Bf: Digits,
+    Ef: Exponent,
+    Mantissa<Rf, Bf>: Cast<u64>,
+    f64: Cast<Fix<Rf, Bf, Ef>>,
+{
+    /**
+    Init PWM decoder parameters
+
+    - `clock`: The frequency of timer clock (Hz)
+    - `min_freq`: The minimum expected frequency of signal (Hz) (0..max_freq)
+    - `max_freq`: The maximum expected frequency of signal (Hz) (min_freq..=clock)
+
+    The period of signal should be at least one tick of timer.
+     */
+    pub fn new<V>(clock: V, min_freq: V, max_freq: V) -> Self
+    where
+        f64: Cast<V>,
+    {
+        let clock = f64::cast(clock);
+        let min_freq = f64::cast(min_freq);
+        let max_freq = f64::cast(max_freq);
+
+        assert!(0.0 < min_freq && min_freq < max_freq);
+        assert!(max_freq <= clock);
+
+        Self {
+            one: (1.0 / lsb::<Rd, Bd, Ed>()) as u64,
+            clock: (clock / lsb::<Rf, Bf, Ef>()) as u64,
+            min_period: (clock / max_freq) as u32,
+            max_period: (clock / min_freq) as u32,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// The value of least significant digit
+fn lsb<R, B, E>() -> f64
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Cast<u64>,
+    f64: Cast<Fix<R, B, E>>,
+{
+    f64::cast(Fix::<R, B, E>::new(Mantissa::<R, B>::cast(1u64)))
+}
+
+/**
+PWM decoder state
+
+- `D` - duty cycle type
+- `F` - frequency type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<D, F> {
+    /// The last valid duty cycle
+    duty: D,
+    /// The last valid frequency
+    frequency: F,
+    /// The number of rejected captures
+    glitches: u32,
+}
+
+impl<D, F> State<D, F> {
+    /// The number of rejected captures
+    pub fn glitches(&self) -> u32 {
+        self.glitches
+    }
+}
+
+impl<D, F> ResetState for State<D, F>
+where
+    D: Default,
+    F: Default,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<D, F> {
+        duty: D,
+        frequency: F,
+        glitches: u32,
+    }
+}
+
+/**
+PWM decoder
+
+- `D` - duty cycle type
+- `F` - frequency type
+
+The input is a tuple of period and width of pulse in timer ticks.
+The output is a tuple of duty cycle and frequency.
+*/
+#[derive(Debug)]
+pub struct Decoder<D, F>(PhantomData<(D, F)>);
+
+impl<Rd, Bd, Ed, Rf, Bf, Ef> Transducer for Decoder<Fix<Rd, Bd, Ed>, Fix<Rf, Bf, Ef>>
+where
+    Rd: Radix<Bd>,
+    Bd: Digits,
+    Ed: Exponent,
+    Mantissa<Rd, Bd>: Cast<u64>,
+    Rf: Radix<Bf>,
+    Bf: Digits,
+    Ef: Exponent,
+    Mantissa<Rf, Bf>: Cast<u64>,
+    Fix<Rd, Bd, Ed>: Copy,
+    Fix<Rf, Bf, Ef>: Copy,
+{
+    type Input = (u32, u32);
+    type Output = (Fix<Rd, Bd, Ed>, Fix<Rf, Bf, Ef>);
+    type Param = Param<Fix<Rd, Bd, Ed>, Fix<Rf, Bf, Ef>>;
+    type State = State<Fix<Rd, Bd, Ed>, Fix<Rf, Bf, Ef>>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (period, width): Self::Input,
+    ) -> Self::Output {
+        if period < param.min_period || period > param.max_period || width > period {
+            state.glitches = state.glitches.saturating_add(1);
+        } else {
+            let period = period as u64;
+
+            state.duty = Fix::new(Mantissa::<Rd, Bd>::cast(width as u64 * param.one / period));
+            state.frequency = Fix::new(Mantissa::<Rf, Bf>::cast(param.clock / period));
+        }
+
+        (state.duty, state.frequency)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin;
+
+    type D = bin::Fix<P16, N16>;
+    type F = bin::Fix<P32, N8>;
+    type P = Decoder<D, F>;
+
+    #[test]
+    fn pwm_decode() {
+        // 1 MHz timer, RC-servo signal 50 Hz
+        let param = Param::<D, F>::new(1e6, 40.0, 60.0);
+        let mut state = State::default();
+
+        assert_eq!(
+            P::apply(&param, &mut state, (20000, 1500)),
+            (D::cast(0.075), F::cast(50.0))
+        );
+        assert_eq!(
+            P::apply(&param, &mut state, (20000, 5000)),
+            (D::cast(0.25), F::cast(50.0))
+        );
+        assert_eq!(
+            P::apply(&param, &mut state, (18000, 0)),
+            (D::cast(0.0), F::new(14222))
+        );
+        assert_eq!(state.glitches(), 0);
+    }
+
+    #[test]
+    fn pwm_glitches() {
+        let param = Param::<D, F>::new(1e6, 40.0, 60.0);
+        let mut state = State::default();
+
+        P::apply(&param, &mut state, (20000, 10000));
+
+        // too short period
+        assert_eq!(
+            P::apply(&param, &mut state, (300, 100)),
+            (D::cast(0.5), F::cast(50.0))
+        );
+        // width exceeds period
+        assert_eq!(
+            P::apply(&param, &mut state, (20000, 20001)),
+            (D::cast(0.5), F::cast(50.0))
+        );
+        assert_eq!(state.glitches(), 2);
+
+        state.reset();
+        assert_eq!(state.glitches(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pwm_freq_above_clock() {
+        // the period would be less than one tick
+        Param::<D, F>::new(1e3, 40.0, 2e3);
+    }
+}
@@ -0,0 +1,286 @@
+/*!
+
+## Windowed RMS
+
+This module implements **Root Mean Square** measurement over a sliding window of last N samples.
+
+The input samples is squared and stored to the delay line, the running sum of squares is updated
+by adding of the new square and subtracting of the square which leaves the window,
+then the mean of squares is evaluated and the square root of it gives the result.
+
+_rms = √(Σx² / N)_
+
+This block is usually used to measure RMS of AC current or voltage in power applications.
+In that case the window should cover the integer number of periods of signal.
+
+The window length is the length of the delay line, so the delay line should be full
+(like the pre-filled one) to get the correct mean.
+The running sum is exact for fixed-point types, but accumulates the rounding errors for floats,
+so the float state should be [reset](ResetState) from time to time in long runs.
+
+*/
+
+use crate::{snapshot_fields, Cast, DelayLine, ResetState, Sqrt, Transducer, WarmStart};
+use core::{
+    marker::PhantomData,
+    ops::{AddAssign, Mul, SubAssign},
+};
+use typenum::{Prod, Unsigned};
+
+/**
+RMS parameters
+
+- `F` - scale factor type
+- `L` - delay line type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<F, L> {
+    /// The reciprocal of window length
+    scale: F,
+    _phantom: PhantomData<L>,
+}
+
+impl<F, L> Param<F, L>
+where
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    /// Init RMS parameters using the length of delay line as the window length
+    pub fn new() -> Self
+    where
+        F: Cast<f64>,
+    {
+        Self {
+            scale: F::cast(1.0 / f64::cast(L::Length::U32)),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, L> Default for Param<F, L>
+where
+    F: Cast<f64>,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+RMS state
+
+- `Q` - sum of squares accumulator type
+- `L` - delay line type
+
+The delay line holds the squares of input values.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct State<Q, L> {
+    /// The squares of input values
+    line: L,
+    /// The running sum of squares
+    sum: Q,
+}
+
+impl<Q, L> State<Q, L>
+where
+    Q: Default + AddAssign<L::Value>,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    /**
+    Initialize RMS state
+
+    - `line`: The delay line with squares of input values
+     */
+    pub fn new(line: L) -> Self {
+        let sum = Self::sum_of(&line);
+        Self { line, sum }
+    }
+
+    /// The delay line with squares of input values
+    pub fn line(&self) -> &L {
+        &self.line
+    }
+
+    fn sum_of(line: &L) -> Q {
+        line.iter().fold(Q::default(), |mut sum, x| {
+            sum += x;
+            sum
+        })
+    }
+
+    fn update_sum(&mut self) {
+        self.sum = Self::sum_of(&self.line);
+    }
+}
+
+impl<Q, L> From<L> for State<Q, L>
+where
+    Q: Default + AddAssign<L::Value>,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    fn from(line: L) -> Self {
+        Self::new(line)
+    }
+}
+
+impl<Q, L> ResetState for State<Q, L>
+where
+    Q: Default + AddAssign<<L as DelayLine>::Value>,
+    L: DelayLine + ResetState<Value = <L as DelayLine>::Value>,
+    for<'a> &'a L: IntoIterator<Item = <L as DelayLine>::Value>,
+{
+    type Value = <L as DelayLine>::Value;
+
+    fn reset(&mut self) {
+        self.line.reset();
+        self.update_sum();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.line.preset(value);
+        self.update_sum();
+    }
+}
+
+snapshot_fields! {
+    State<Q, L> {
+        line: L,
+        sum: Q,
+    }
+}
+
+/**
+Windowed RMS filter
+
+- `I` - input value type
+- `O` - output value type
+- `F` - scale factor type
+- `Q` - sum of squares accumulator type
+- `L` - delay line type
+*/
+#[derive(Debug)]
+pub struct Filter<I, O, F, Q, L>(PhantomData<(I, O, F, Q, L)>);
+
+impl<I, O, F, Q, L> Transducer for Filter<I, O, F, Q, L>
+where
+    I: Copy + Mul<I>,
+    O: Cast<L::Value>,
+    F: Copy + Mul<Q>,
+    Q: Copy + Default + AddAssign<L::Value> + SubAssign<L::Value>,
+    L: DelayLine,
+    L::Value: Sqrt + Cast<Prod<I, I>> + Cast<Prod<F, Q>>,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    type Input = I;
+    type Output = O;
+    type Param = Param<F, L>;
+    type State = State<Q, L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let square = L::Value::cast(value * value);
+
+        // the oldest square leaves the window when line is full
+        if state.line.is_full() {
+            if let Some(oldest) = state.line.oldest() {
+                state.sum -= oldest;
+            }
+        }
+        state.line.push(square);
+        state.sum += square;
+
+        O::cast(L::Value::cast(param.scale * state.sum).sqrt())
+    }
+}
+
+impl<I, O, F, Q, L> WarmStart for Filter<I, O, F, Q, L>
+where
+    I: Copy + Mul<I>,
+    Q: Default + AddAssign<<L as DelayLine>::Value>,
+    L: DelayLine + ResetState<Value = <L as DelayLine>::Value>,
+    <L as DelayLine>::Value: Cast<Prod<I, I>>,
+    for<'a> &'a L: IntoIterator<Item = <L as DelayLine>::Value>,
+{
+    type Input = I;
+    type State = State<Q, L>;
+
+    fn warm_start(state: &mut Self::State, value: &Self::Input) {
+        state.preset(<L as DelayLine>::Value::cast(*value * *value));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pfdl::Store as DL, sqrt2};
+    use typenum::{N16, N24, N8, P16, P32, P48, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn rms_f32() {
+        let param = Param::<f32, DL<f32, U4>>::new();
+        let mut state = State::<f32, _>::new(DL::<f32, U4>::from(0.0));
+        type R = Filter<f32, f32, f32, f32, DL<f32, U4>>;
+
+        assert_eq!(R::apply(&param, &mut state, 2.0), 1.0);
+        assert_eq!(R::apply(&param, &mut state, -2.0), sqrt2::<f32>());
+        assert_eq!(R::apply(&param, &mut state, 2.0), 1.7320508);
+        assert_eq!(R::apply(&param, &mut state, -2.0), 2.0);
+        assert_eq!(R::apply(&param, &mut state, 2.0), 2.0);
+        assert_eq!(R::apply(&param, &mut state, 0.0), 1.7320508);
+    }
+
+    #[test]
+    fn rms_fix() {
+        type F = Fix<P16, N24>;
+        type I = Fix<P16, N8>;
+        type S = Fix<P32, N16>;
+        type Q = Fix<P48, N16>;
+
+        let param = Param::<F, DL<S, U4>>::new();
+        let mut state = State::<Q, _>::new(DL::<S, U4>::from(S::cast(0.0)));
+        type R = Filter<I, I, F, Q, DL<S, U4>>;
+
+        assert_eq!(R::apply(&param, &mut state, I::cast(3.0)), I::cast(1.5));
+        assert_eq!(R::apply(&param, &mut state, I::cast(-3.0)), I::cast(2.1211));
+        assert_eq!(R::apply(&param, &mut state, I::cast(3.0)), I::cast(2.5977));
+        assert_eq!(R::apply(&param, &mut state, I::cast(-3.0)), I::cast(3.0));
+    }
+
+    #[test]
+    fn rms_window() {
+        type F = Fix<P16, N24>;
+        type I = Fix<P16, N8>;
+        type S = Fix<P32, N16>;
+        type Q = Fix<P48, N16>;
+
+        let param = Param::<F, DL<S, U4>>::new();
+        let mut state = State::<Q, _>::new(DL::<S, U4>::from(S::cast(0.0)));
+        type R = Filter<I, I, F, Q, DL<S, U4>>;
+
+        for _ in 0..100 {
+            R::apply(&param, &mut state, I::cast(1.5));
+        }
+        assert_eq!(R::apply(&param, &mut state, I::cast(1.5)), I::cast(1.5));
+        assert_eq!(
+            R::apply(&param, &mut state, I::cast(0.0)),
+            I::cast(1.299_038_1)
+        );
+        assert_eq!(
+            R::apply(&param, &mut state, I::cast(0.0)),
+            I::cast(1.060_66)
+        );
+        assert_eq!(R::apply(&param, &mut state, I::cast(0.0)), I::cast(0.75));
+        assert_eq!(R::apply(&param, &mut state, I::cast(0.0)), I::cast(0.0));
+
+        R::warm_start(&mut state, &I::cast(2.0));
+        assert_eq!(R::apply(&param, &mut state, I::cast(2.0)), I::cast(2.0));
+
+        state.reset();
+        assert_eq!(R::apply(&param, &mut state, I::cast(2.0)), I::cast(1.0));
+    }
+}
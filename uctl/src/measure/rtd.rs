@@ -0,0 +1,117 @@
+/*!
+
+## RTD tables
+
+This module provides the coefficients for linearization of platinum resistance
+thermometers (PT100, PT1000 and etc.) according to IEC 60751.
+
+The resistance of sensor is given by Callendar–Van Dusen equation:
+
+_R = R<sub>0</sub> (1 + A t + B t<sup>2</sup>)_, when _t ≥ 0_
+
+_R = R<sub>0</sub> (1 + A t + B t<sup>2</sup> + C (t - 100) t<sup>3</sup>)_, when _t < 0_
+
+The inverse conversion is done using polynomials of relative deviation of resistance
+_x = R / R<sub>0</sub> - 1_, so the same coefficients is suitable for sensors with any _R<sub>0</sub>_.
+Usually _x_ is obtained from ratiometric measurement using [scaler](crate::scaler).
+
+The temperatures is in degrees of Celsius.
+
+*/
+
+use crate::{poly, Cast};
+use typenum::U6;
+
+/// The Callendar–Van Dusen coefficient _A_
+pub const CVD_A: f64 = 3.9083e-3;
+
+/// The Callendar–Van Dusen coefficient _B_
+pub const CVD_B: f64 = -5.775e-7;
+
+/// The Callendar–Van Dusen coefficient _C_
+pub const CVD_C: f64 = -4.183e-12;
+
+/// Platinum RTD inverse polynomial coefficients for range -200..0 °C (error ±0.0001 °C)
+pub const PT_INVERSE_NEGATIVE: [f64; 6] = [
+    5.797284649e-5,
+    255.8688468,
+    9.713686526,
+    -0.8559170461,
+    4.803785729,
+    1.524513542,
+];
+
+/// Platinum RTD inverse polynomial coefficients for range 0..850 °C (error ±0.003 °C)
+pub const PT_INVERSE: [f64; 6] = [
+    -2.20797155e-3,
+    255.8961567,
+    9.575027072,
+    0.8565404855,
+    -2.115432362e-3,
+    2.433346308e-2,
+];
+
+/// Platinum RTD relative deviation of resistance to temperature (°C) conversion for range 0..850 °C
+pub fn pt<A>() -> poly::Param<A, U6>
+where
+    A: Cast<f64>,
+{
+    poly::Param::new(&PT_INVERSE)
+}
+
+/// Platinum RTD relative deviation of resistance to temperature (°C) conversion for range -200..0 °C
+pub fn pt_negative<A>() -> poly::Param<A, U6>
+where
+    A: Cast<f64>,
+{
+    poly::Param::new(&PT_INVERSE_NEGATIVE)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Transducer;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    type P = poly::Polynomial<f64, f64, U6>;
+
+    fn deviation(t: f64) -> f64 {
+        CVD_A * t
+            + CVD_B * t * t
+            + if t < 0.0 {
+                CVD_C * (t - 100.0) * t * t * t
+            } else {
+                0.0
+            }
+    }
+
+    #[test]
+    fn pt_f64() {
+        let param = pt::<f64>();
+
+        for t in &[0.0, 100.0, 250.0, 600.0, 850.0] {
+            assert!((P::apply(&param, &mut (), deviation(*t)) - t).abs() < 0.003);
+        }
+
+        // PT100 at 100 °C
+        assert!((P::apply(&param, &mut (), 138.5055 / 100.0 - 1.0) - 100.0).abs() < 0.003);
+
+        let param = pt_negative::<f64>();
+
+        for t in &[-200.0, -100.0, -50.0, 0.0] {
+            assert!((P::apply(&param, &mut (), deviation(*t)) - t).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn pt_fix() {
+        type T = Fix<P32, N16>;
+        type F = poly::Polynomial<T, T, U6>;
+
+        let param = pt::<T>();
+        let temperature = f64::cast(F::apply(&param, &mut (), T::cast(deviation(300.0))));
+
+        assert!((temperature - 300.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,269 @@
+/*!
+
+## Statistics summary
+
+This module implements accumulator which collects running **min**, **max**, **mean** and **RMS** values of signal.
+
+The statistics is collected over a fixed number of samples (the reporting period).
+When the period is complete the summary is emitted and accumulator is reset to start collecting again.
+
+Sums and sums of squares is collected using wide accumulators which types should be selected to hold
+the whole period without overflow.
+
+The mean value is calculated as _mean = Σx * (1 / N)_.
+
+The RMS value is calculated as _rms = √(Σx² * (1 / N))_.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Sqrt, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{AddAssign, Mul},
+};
+use typenum::Prod;
+
+/**
+Statistics parameters
+
+- `F` - scale factor type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<F> {
+    /// The number of samples in period
+    period: u32,
+    /// The reciprocal of period
+    scale: F,
+}
+
+impl<F> Param<F> {
+    /**
+    Init statistics parameters
+
+    - `period`: The number of samples to collect before emitting summary (1..)
+     */
+    pub fn new(period: u32) -> Self
+    where
+        F: Cast<f64>,
+    {
+        Self {
+            period,
+            scale: F::cast(1.0 / f64::cast(period)),
+        }
+    }
+
+    /// The number of samples in period
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+}
+
+/**
+Statistics summary
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary<T> {
+    /// Minimum value
+    pub min: T,
+    /// Maximum value
+    pub max: T,
+    /// Mean value
+    pub mean: T,
+    /// Root mean square value
+    pub rms: T,
+}
+
+/**
+Statistics state
+
+- `T` - values type
+- `A` - sum accumulator type
+- `Q` - sum of squares accumulator type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T, A, Q> {
+    /// Minimum value
+    min: T,
+    /// Maximum value
+    max: T,
+    /// Sum of values
+    sum: A,
+    /// Sum of squares
+    sum_sq: Q,
+    /// The number of collected values
+    count: u32,
+}
+
+impl<T, A, Q> State<T, A, Q> {
+    /// The number of values collected in current period
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Minimum value in current period
+    pub fn min(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        if self.count > 0 {
+            Some(self.min)
+        } else {
+            None
+        }
+    }
+
+    /// Maximum value in current period
+    pub fn max(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        if self.count > 0 {
+            Some(self.max)
+        } else {
+            None
+        }
+    }
+}
+
+/// The preset of statistics is same as reset
+impl<T, A, Q> ResetState for State<T, A, Q>
+where
+    T: Default,
+    A: Default,
+    Q: Default,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T, A, Q> {
+        min: T,
+        max: T,
+        sum: A,
+        sum_sq: Q,
+        count: u32,
+    }
+}
+
+/**
+Statistics accumulator
+
+- `F` - scale factor type
+- `T` - values type
+- `A` - sum accumulator type
+- `Q` - sum of squares accumulator type
+*/
+#[derive(Debug)]
+pub struct Stats<F, T, A, Q>(PhantomData<(F, T, A, Q)>);
+
+impl<F, T, A, Q> Transducer for Stats<F, T, A, Q>
+where
+    F: Copy + Mul<A> + Mul<Q>,
+    T: Copy + Default + PartialOrd + Mul<T> + Cast<Prod<F, A>> + Cast<Prod<T, T>>,
+    A: Copy + Default + AddAssign<T>,
+    Q: Copy + Default + AddAssign<Prod<T, T>>,
+    Prod<T, T>: Sqrt + Cast<Prod<F, Q>>,
+{
+    type Input = T;
+    type Output = Option<Summary<T>>;
+    type Param = Param<F>;
+    type State = State<T, A, Q>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        if state.count == 0 {
+            state.min = value;
+            state.max = value;
+        } else if value < state.min {
+            state.min = value;
+        } else if value > state.max {
+            state.max = value;
+        }
+
+        state.sum += value;
+        state.sum_sq += value * value;
+        state.count += 1;
+
+        if state.count < param.period {
+            return None;
+        }
+
+        let summary = Summary {
+            min: state.min,
+            max: state.max,
+            // mean = Σx * (1 / N)
+            mean: T::cast(param.scale * state.sum),
+            // rms = √(Σx² * (1 / N))
+            rms: T::cast(Prod::<T, T>::cast(param.scale * state.sum_sq).sqrt()),
+        };
+
+        *state = State::default();
+
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::*;
+    use ufix::bin::Fix;
+
+    #[test]
+    fn stats_f32() {
+        let param = Param::<f32>::new(4);
+        let mut state = State::<f32, f32, f32>::default();
+        type S = Stats<f32, f32, f32, f32>;
+
+        assert_eq!(S::apply(&param, &mut state, 1.0), None);
+        assert_eq!(S::apply(&param, &mut state, -1.0), None);
+        assert_eq!(state.min(), Some(-1.0));
+        assert_eq!(state.max(), Some(1.0));
+        assert_eq!(S::apply(&param, &mut state, 3.0), None);
+        assert_eq!(
+            S::apply(&param, &mut state, -3.0),
+            Some(Summary {
+                min: -3.0,
+                max: 3.0,
+                mean: 0.0,
+                rms: 2.236068,
+            })
+        );
+        assert_eq!(state.count(), 0);
+        assert_eq!(state.min(), None);
+    }
+
+    #[test]
+    fn stats_fix() {
+        type F = Fix<P16, N24>;
+        type T = Fix<P16, N8>;
+        type A = Fix<P32, N8>;
+        type Q = Fix<P48, N16>;
+
+        let param = Param::<F>::new(4);
+        let mut state = State::<T, A, Q>::default();
+        type S = Stats<F, T, A, Q>;
+
+        assert_eq!(S::apply(&param, &mut state, T::cast(2.0)), None);
+        assert_eq!(S::apply(&param, &mut state, T::cast(4.0)), None);
+        assert_eq!(S::apply(&param, &mut state, T::cast(-1.5)), None);
+        assert_eq!(
+            S::apply(&param, &mut state, T::cast(0.5)),
+            Some(Summary {
+                min: T::cast(-1.5),
+                max: T::cast(4.0),
+                mean: T::cast(1.25),
+                rms: T::cast(2.3711),
+            })
+        );
+    }
+}
@@ -0,0 +1,343 @@
+/*!
+
+## Stiction detection
+
+This module implements the detector of actuator stiction (static friction) in loops
+with valves and other actuators which can stick.
+
+The sticking actuator doesn't move until the control output overcomes the friction,
+then it jumps, so the loop oscillates with typical pattern: the integral part of regulator
+gives the sawtooth-like control output while the actuator moves like a square wave
+(and the measurement of self-regulating process follows it).
+In integrating processes (like level loops) the pattern is inverted:
+the measurement becomes a sawtooth and the output becomes a square wave.
+
+The detection uses the cross-correlation of control output _u_ and measurement _y_:
+
+_r(τ) = Σ (u<sub>k</sub> - ū) (y<sub>k+τ</sub> - ȳ) / (N σ<sub>u</sub> σ<sub>y</sub>)_
+
+When the oscillation is caused by aggressive tuning or by external disturbance
+the output and measurement is in phase or antiphase, so the cross-correlation function
+is even (the extremum is at zero lag).
+When the oscillation is caused by stiction the output and measurement is shifted
+by quarter of period, so the cross-correlation function is odd (zero at zero lag).
+
+The confidence score is a share of energy of odd part of cross-correlation function
+over lags _-L..L_:
+
+_c = Σ (r(τ) - r(-τ))² / (Σ (r(τ) - r(-τ))² + Σ (r(τ) + r(-τ))²)_
+
+The score is near 1 for stiction and near 0 for other causes.
+The maximum lag should be about half of period of oscillation.
+The score is meaningful only when the loop oscillates, so the peak of cross-correlation
+should be checked too (the uncorrelated signals gives the small peak).
+
+The [`detect`] function evaluates the recorded windows of signals on host side or on target,
+the [`Detector`] records the windows itself and emits the result at the end of each window.
+The evaluation uses double precision floating-point values regardless of types of values.
+
+See also [Stiction](https://en.wikipedia.org/wiki/Stiction).
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Sqrt, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+use typenum::Unsigned;
+
+/// The result of stiction detection
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Stiction {
+    /// The confidence score of stiction (0..1)
+    pub confidence: f64,
+    /// The peak of normalized cross-correlation (-1..1)
+    pub peak: f64,
+    /// The lag of peak in steps
+    pub lag: i32,
+}
+
+/// The mean and standard deviation of values
+fn moments<T>(values: &[T]) -> (f64, f64)
+where
+    T: Copy,
+    f64: Cast<T>,
+{
+    let count = values.len() as f64;
+    let mean = values.iter().map(|x| f64::cast(*x)).sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|x| f64::cast(*x) - mean)
+        .map(|x| x * x)
+        .sum::<f64>()
+        / count;
+    (mean, Sqrt::sqrt(variance))
+}
+
+/**
+Detect stiction by recorded window
+
+- `output`: The control output values
+- `measurement`: The measurement values (the same length as output)
+- `lags`: The maximum lag _L_ in steps (less than length)
+ */
+pub fn detect<T>(output: &[T], measurement: &[T], lags: usize) -> Stiction
+where
+    T: Copy,
+    f64: Cast<T>,
+{
+    let length = output.len();
+    assert!(measurement.len() == length && lags < length);
+
+    let (u_mean, u_std) = moments(output);
+    let (y_mean, y_std) = moments(measurement);
+    let norm = length as f64 * u_std * y_std;
+
+    if norm <= 0.0 {
+        // the constant signal doesn't oscillate
+        return Stiction::default();
+    }
+
+    let correlation = |lag: i32| {
+        let (u, y) = if lag < 0 {
+            (&output[(-lag) as usize..], measurement)
+        } else {
+            (output, &measurement[lag as usize..])
+        };
+        u.iter()
+            .zip(y.iter())
+            .map(|(u, y)| (f64::cast(*u) - u_mean) * (f64::cast(*y) - y_mean))
+            .sum::<f64>()
+            / norm
+    };
+
+    let mut result = Stiction {
+        peak: correlation(0),
+        ..Stiction::default()
+    };
+    let mut odd = 0.0;
+    let mut even = 0.0;
+
+    for lag in 1..=lags as i32 {
+        let positive = correlation(lag);
+        let negative = correlation(-lag);
+
+        odd += (positive - negative) * (positive - negative);
+        even += (positive + negative) * (positive + negative);
+
+        for (value, lag) in [(positive, lag), (negative, -lag)] {
+            if value.abs() > result.peak.abs() {
+                result.peak = value;
+                result.lag = lag;
+            }
+        }
+    }
+
+    // the zero lag belongs to even part only
+    even += 4.0 * correlation(0) * correlation(0);
+
+    if odd + even > 0.0 {
+        result.confidence = odd / (odd + even);
+    }
+    result
+}
+
+/**
+Stiction detector parameters
+
+- `N` - window length
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<N> {
+    /// The maximum lag in steps
+    lags: usize,
+    _phantom: PhantomData<N>,
+}
+
+impl<N> Param<N>
+where
+    N: Unsigned,
+{
+    /**
+    Init stiction detector parameters
+
+    - `lags`: The maximum lag _L_ in steps (about half of period of oscillation, less than window length)
+     */
+    pub fn new(lags: usize) -> Self {
+        assert!(lags < N::USIZE);
+
+        Self {
+            lags,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/**
+Stiction detector state
+
+- `T` - values type
+- `N` - window length
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The recorded control output
+    output: GenericArray<T, N>,
+    /// The recorded measurement
+    measurement: GenericArray<T, N>,
+    /// The number of recorded values
+    count: usize,
+}
+
+impl<T, N> ResetState for State<T, N>
+where
+    N: ArrayLength<T>,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T, N> where [N: ArrayLength<T>] {
+        output: GenericArray<T, N>,
+        measurement: GenericArray<T, N>,
+        count: usize,
+    }
+}
+
+/**
+Stiction detector
+
+- `T` - values type
+- `N` - window length
+
+The input is a tuple of control output and measurement.
+The output is a result of detection at the end of each window.
+*/
+#[derive(Debug)]
+pub struct Detector<T, N>(PhantomData<(T, N)>);
+
+impl<T, N> Transducer for Detector<T, N>
+where
+    T: Copy,
+    f64: Cast<T>,
+    N: ArrayLength<T>,
+{
+    type Input = (T, T);
+    type Output = Option<Stiction>;
+    type Param = Param<N>;
+    type State = State<T, N>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (output, measurement): Self::Input,
+    ) -> Self::Output {
+        state.output[state.count] = output;
+        state.measurement[state.count] = measurement;
+        state.count += 1;
+
+        if state.count < N::USIZE {
+            return None;
+        }
+
+        state.count = 0;
+        Some(detect(&state.output, &state.measurement, param.lags))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SinCos;
+    use typenum::{N16, P32, U200, U4};
+    use ufix::bin::Fix;
+
+    /// The loop with sticking valve and PI regulator
+    fn sticky_loop(steps: usize, mut sample: impl FnMut(f64, f64)) {
+        let (mut u, mut valve, mut y) = (0.0, 0.0, 0.0);
+        for _ in 0..steps {
+            // the valve moves only when the friction is overcome
+            if (u - valve) > 1.0 || (valve - u) > 1.0 {
+                valve = u;
+            }
+            y += 0.5 * (valve - y);
+            u += 0.05 * (0.3 - y);
+            sample(u, y);
+        }
+    }
+
+    #[test]
+    fn stiction_detected() {
+        let mut output = [0.0; 400];
+        let mut measurement = [0.0; 400];
+        let mut index = 0;
+        sticky_loop(1000, |u, y| {
+            if index >= 600 {
+                output[index - 600] = u;
+                measurement[index - 600] = y;
+            }
+            index += 1;
+        });
+
+        let result = detect(&output, &measurement, 20);
+        assert!(result.confidence > 0.8, "{:?}", result);
+        assert!(result.peak.abs() > 0.5);
+        assert!(result.lag != 0);
+    }
+
+    #[test]
+    fn stiction_not_detected() {
+        // the oscillation of aggressive loop (antiphase)
+        let mut output = [0.0; 400];
+        let mut measurement = [0.0; 400];
+        for k in 0..400 {
+            let (sin, _) = SinCos::sin_cos(k as f64 * 0.15);
+            output[k] = -2.0 * sin;
+            measurement[k] = sin;
+        }
+
+        let result = detect(&output, &measurement, 20);
+        assert!(result.confidence < 0.1, "{:?}", result);
+        assert!((result.peak + 1.0).abs() < 0.01);
+        assert_eq!(result.lag, 0);
+
+        // the constant signals
+        assert_eq!(detect(&[1.0; 10], &[2.0; 10], 3), Stiction::default());
+    }
+
+    #[test]
+    fn stiction_detector_fix() {
+        type T = Fix<P32, N16>;
+        type D = Detector<T, U200>;
+        let param = Param::new(20);
+        let mut state = State::default();
+
+        let mut results = 0;
+        let mut last = None;
+        sticky_loop(1000, |u, y| {
+            if let Some(result) = D::apply(&param, &mut state, (T::cast(u), T::cast(y))) {
+                results += 1;
+                last = Some(result);
+            }
+        });
+
+        assert_eq!(results, 5);
+        assert!(last.unwrap().confidence > 0.8, "{:?}", last);
+    }
+
+    #[test]
+    #[should_panic]
+    fn stiction_lags_exceed_window() {
+        Param::<U4>::new(4);
+    }
+}
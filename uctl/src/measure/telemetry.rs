@@ -0,0 +1,261 @@
+/*!
+
+## Telemetry decimator
+
+This module implements the reducer which downsamples the signal from the control rate
+to the logging (reporting) rate.
+
+The plain decimation loses the spikes between the reported samples, so the decimator
+reports the compact [`Summary`] of each interval which includes the last sample
+together with the **min**, **max** and **mean** values of interval.
+
+The sum for mean value is collected by [`Accumulator`], so the accumulation strategy
+can be selected according to the values type and the length of interval
+(like [`WideAccumulator`](crate::WideAccumulator) for fixed-point values or
+[`CompensatedAccumulator`](crate::CompensatedAccumulator) for floating-point values).
+
+See also [statistics summary](crate::stats) which additionally evaluates the RMS value.
+
+*/
+
+use crate::{snapshot_fields, Accumulator, Cast, ResetState, Transducer};
+use core::marker::PhantomData;
+
+/// Telemetry decimator parameters
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// The number of samples in interval
+    interval: u32,
+}
+
+impl Param {
+    /**
+    Init telemetry decimator parameters
+
+    - `interval`: The reporting interval (s)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<V>(interval: V, period: V) -> Self
+    where
+        f64: Cast<V>,
+    {
+        Self::from_ratio((f64::cast(interval) / f64::cast(period) + 0.5) as u32)
+    }
+
+    /**
+    Init telemetry decimator parameters using decimation ratio
+
+    - `ratio`: The number of samples in reporting interval (1..)
+     */
+    pub fn from_ratio(ratio: u32) -> Self {
+        assert!(ratio > 0);
+        Self { interval: ratio }
+    }
+
+    /// The number of samples in reporting interval
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+}
+
+/**
+Telemetry summary of interval
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary<T> {
+    /// The last sample
+    pub sample: T,
+    /// Minimum value
+    pub min: T,
+    /// Maximum value
+    pub max: T,
+    /// Mean value
+    pub mean: T,
+}
+
+/**
+Telemetry decimator state
+
+- `T` - values type
+- `A` - accumulator type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T, A> {
+    /// Minimum value
+    min: T,
+    /// Maximum value
+    max: T,
+    /// The accumulator of values
+    accumulator: A,
+}
+
+impl<T, A> State<T, A> {
+    /**
+    Initialize telemetry decimator state
+
+    - `accumulator`: The accumulator of values
+     */
+    pub fn new(accumulator: A) -> Self
+    where
+        T: Default,
+    {
+        Self {
+            min: T::default(),
+            max: T::default(),
+            accumulator,
+        }
+    }
+
+    /// The number of values collected in current interval
+    pub fn count(&self) -> u32
+    where
+        A: Accumulator<T>,
+    {
+        self.accumulator.count()
+    }
+}
+
+/// The preset of telemetry decimator is same as reset
+impl<T, A> ResetState for State<T, A>
+where
+    A: Accumulator<T>,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.accumulator.clear();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T, A> {
+        min: T,
+        max: T,
+        accumulator: A,
+    }
+}
+
+/**
+Telemetry decimator
+
+- `T` - values type
+- `A` - accumulator type
+
+The input is a value at control rate.
+The output is a summary at the end of each reporting interval.
+*/
+#[derive(Debug)]
+pub struct Decimator<T, A>(PhantomData<(T, A)>);
+
+impl<T, A> Transducer for Decimator<T, A>
+where
+    T: Copy + PartialOrd,
+    A: Accumulator<T>,
+{
+    type Input = T;
+    type Output = Option<Summary<T>>;
+    type Param = Param;
+    type State = State<T, A>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        if state.accumulator.count() == 0 {
+            state.min = value;
+            state.max = value;
+        } else if value < state.min {
+            state.min = value;
+        } else if value > state.max {
+            state.max = value;
+        }
+
+        state.accumulator.push(value);
+
+        if state.accumulator.count() < param.interval {
+            return None;
+        }
+
+        let summary = Summary {
+            sample: value,
+            min: state.min,
+            max: state.max,
+            mean: state.accumulator.mean(),
+        };
+
+        state.accumulator.clear();
+
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CompensatedAccumulator, Overflow, WideAccumulator};
+    use typenum::{N16, P32, P48};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn telemetry_spike() {
+        type D = Decimator<f32, CompensatedAccumulator<f32>>;
+        let param = Param::new(0.1, 0.01);
+        let mut state = State::new(CompensatedAccumulator::new());
+
+        assert_eq!(param.interval(), 10);
+
+        let mut reports = 0;
+        for step in 0..100 {
+            let value = if step == 42 { 5.0 } else { 1.0 };
+            if let Some(summary) = D::apply(&param, &mut state, value) {
+                reports += 1;
+                // the spike is reported although the sample misses it
+                if step == 49 {
+                    assert_eq!(
+                        summary,
+                        Summary {
+                            sample: 1.0,
+                            min: 1.0,
+                            max: 5.0,
+                            mean: 1.4,
+                        }
+                    );
+                } else {
+                    assert_eq!(summary.max, 1.0);
+                }
+            }
+        }
+        assert_eq!(reports, 10);
+        assert_eq!(state.count(), 0);
+    }
+
+    #[test]
+    fn telemetry_fix() {
+        type T = Fix<P32, N16>;
+        type W = Fix<P48, N16>;
+        type D = Decimator<T, WideAccumulator<T, W>>;
+
+        // one report per 100000 samples
+        let param = Param::from_ratio(100_000);
+        let mut state = State::new(WideAccumulator::new(1e9, Overflow::Saturate));
+
+        let mut report = None;
+        for step in 0..100_000 {
+            let value = T::cast(if step % 2 == 0 { 1000.0 } else { -999.0 });
+            report = D::apply(&param, &mut state, value);
+        }
+
+        assert_eq!(
+            report,
+            Some(Summary {
+                sample: T::cast(-999.0),
+                min: T::cast(-999.0),
+                max: T::cast(1000.0),
+                mean: T::cast(0.5),
+            })
+        );
+    }
+}
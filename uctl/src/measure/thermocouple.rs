@@ -0,0 +1,218 @@
+/*!
+
+## Thermocouple tables
+
+This module provides the coefficients for linearization of thermocouples
+according to NIST ITS-90 reference tables.
+
+The thermocouple measures the difference of temperatures of hot and cold (reference) junctions,
+so the conversion is done in two steps:
+
+1. The voltage of cold junction is found from its temperature (measured by separate sensor)
+   using [`k_type_cold_junction`] table and added to the measured voltage.
+2. The temperature of hot junction is found from compensated voltage using [`k_type`] polynomial
+   or [`k_type_table`] lookup table.
+
+The voltages is in millivolts, the temperatures is in degrees of Celsius.
+
+The polynomials is intended for floating-point types, because the high order coefficients
+is too small to be represented by fixed-point types with reasonable number of digits.
+The lookup tables is intended for fixed-point types.
+
+*/
+
+use crate::{lut, poly, Cast};
+use typenum::{U10, U11, U59};
+
+/// Type K inverse polynomial coefficients for range -200..0 °C (-5.891..0 mV, error ±0.04 °C)
+pub const K_INVERSE_NEGATIVE: [f64; 9] = [
+    0.0,
+    2.5173462e1,
+    -1.1662878,
+    -1.0833638,
+    -8.9773540e-1,
+    -3.7342377e-1,
+    -8.6632643e-2,
+    -1.0450598e-2,
+    -5.1920577e-4,
+];
+
+/// Type K inverse polynomial coefficients for range 0..500 °C (0..20.644 mV, error ±0.05 °C)
+pub const K_INVERSE: [f64; 10] = [
+    0.0,
+    2.508355e1,
+    7.860106e-2,
+    -2.503131e-1,
+    8.315270e-2,
+    -1.228034e-2,
+    9.804036e-4,
+    -4.413030e-5,
+    1.057734e-6,
+    -1.052755e-8,
+];
+
+/// Type K inverse polynomial coefficients for range 500..1372 °C (20.644..54.886 mV, error ±0.06 °C)
+pub const K_INVERSE_HIGH: [f64; 7] = [
+    -1.318058e2,
+    4.830222e1,
+    -1.646031,
+    5.464731e-2,
+    -9.650715e-4,
+    8.802193e-6,
+    -3.110810e-8,
+];
+
+/// Type K reference table (mV, °C) for range -100..1350 °C with step 25 °C (error ±0.3 °C)
+pub const K_TABLE: [(f64, f64); 59] = [
+    (-3.554, -100.0),
+    (-2.755, -75.0),
+    (-1.889, -50.0),
+    (-0.968, -25.0),
+    (0.0, 0.0),
+    (1.0, 25.0),
+    (2.023, 50.0),
+    (3.059, 75.0),
+    (4.096, 100.0),
+    (5.124, 125.0),
+    (6.138, 150.0),
+    (7.14, 175.0),
+    (8.138, 200.0),
+    (9.141, 225.0),
+    (10.153, 250.0),
+    (11.176, 275.0),
+    (12.209, 300.0),
+    (13.248, 325.0),
+    (14.293, 350.0),
+    (15.343, 375.0),
+    (16.397, 400.0),
+    (17.455, 425.0),
+    (18.516, 450.0),
+    (19.579, 475.0),
+    (20.644, 500.0),
+    (21.71, 525.0),
+    (22.776, 550.0),
+    (23.842, 575.0),
+    (24.905, 600.0),
+    (25.967, 625.0),
+    (27.025, 650.0),
+    (28.079, 675.0),
+    (29.129, 700.0),
+    (30.174, 725.0),
+    (31.213, 750.0),
+    (32.247, 775.0),
+    (33.275, 800.0),
+    (34.297, 825.0),
+    (35.313, 850.0),
+    (36.323, 875.0),
+    (37.326, 900.0),
+    (38.323, 925.0),
+    (39.314, 950.0),
+    (40.298, 975.0),
+    (41.276, 1000.0),
+    (42.247, 1025.0),
+    (43.211, 1050.0),
+    (44.169, 1075.0),
+    (45.119, 1100.0),
+    (46.061, 1125.0),
+    (46.995, 1150.0),
+    (47.921, 1175.0),
+    (48.838, 1200.0),
+    (49.746, 1225.0),
+    (50.644, 1250.0),
+    (51.532, 1275.0),
+    (52.41, 1300.0),
+    (53.279, 1325.0),
+    (54.138, 1350.0),
+];
+
+/// Type K reference table (°C, mV) for cold junction compensation in range -20..80 °C
+pub const K_COLD_JUNCTION: [(f64, f64); 11] = [
+    (-20.0, -0.778),
+    (-10.0, -0.392),
+    (0.0, 0.0),
+    (10.0, 0.397),
+    (20.0, 0.798),
+    (30.0, 1.203),
+    (40.0, 1.612),
+    (50.0, 2.023),
+    (60.0, 2.436),
+    (70.0, 2.851),
+    (80.0, 3.267),
+];
+
+/// Type K thermocouple voltage (mV) to temperature (°C) conversion for range 0..500 °C
+pub fn k_type<A>() -> poly::Param<A, U10>
+where
+    A: Cast<f64>,
+{
+    poly::Param::new(&K_INVERSE)
+}
+
+/// Type K thermocouple voltage (mV) to temperature (°C) conversion for range -100..1350 °C
+///
+/// The lookup table is preferred for fixed-point types.
+pub fn k_type_table<X, Y, K>() -> lut::Param<X, Y, K, U59>
+where
+    X: Cast<f64>,
+    Y: Cast<f64>,
+    K: Cast<f64>,
+{
+    lut::Param::new(&K_TABLE)
+}
+
+/// Type K cold junction temperature (°C) to voltage (mV) conversion for range -20..80 °C
+pub fn k_type_cold_junction<X, Y, K>() -> lut::Param<X, Y, K, U11>
+where
+    X: Cast<f64>,
+    Y: Cast<f64>,
+    K: Cast<f64>,
+{
+    lut::Param::new(&K_COLD_JUNCTION)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Transducer;
+    use typenum::{N16, N24, N8, N9, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn k_type_f64() {
+        type P = poly::Polynomial<f64, f64, U10>;
+        type L = lut::Table<f64, f64, f64, U11>;
+
+        let param = k_type::<f64>();
+        let cold = k_type_cold_junction::<f64, f64, f64>();
+
+        assert!((P::apply(&param, &mut (), 4.096) - 100.0).abs() < 0.05);
+        assert!((P::apply(&param, &mut (), 20.644) - 500.0).abs() < 0.05);
+
+        // the hot junction at 100 °C, the cold junction at 25 °C
+        let voltage = 4.096 - 1.000 + L::apply(&cold, &mut (), 25.0);
+        assert!((P::apply(&param, &mut (), voltage) - 100.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn k_type_fix() {
+        // the products of slopes and input differences should fit in 64 bits
+        type V = Fix<P16, N9>;
+        type T = Fix<P32, N16>;
+        type J = Fix<P16, N8>;
+        type K = Fix<P32, N24>;
+        type L = lut::Table<V, T, T, U59>;
+        type C = lut::Table<J, V, K, U11>;
+
+        let param = k_type_table::<V, T, T>();
+        let cold = k_type_cold_junction::<J, V, K>();
+
+        // 10 mV is 246.2 °C
+        let temperature = f64::cast(L::apply(&param, &mut (), V::cast(10.0)));
+        assert!((temperature - 246.2).abs() < 0.1);
+
+        // the hot junction at 100 °C, the cold junction at 25 °C
+        let voltage = V::cast(V::cast(4.096 - 1.000) + C::apply(&cold, &mut (), J::cast(25.0)));
+        let temperature = f64::cast(L::apply(&param, &mut (), voltage));
+        assert!((temperature - 100.0).abs() < 0.1);
+    }
+}
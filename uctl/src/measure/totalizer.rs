@@ -0,0 +1,257 @@
+/*!
+
+## Totalizer
+
+This module implements the integrating counter (**totalizer**) which integrates
+the rate signal (like flow or power) into the running total (like volume or energy):
+
+_Q = Σ k q P_
+
+where _q_ is a rate, _P_ is a sampling period and _k_ is a units scaling factor
+(for example 1/60000 to count cubic meters of the flow measured in liters per minute).
+
+The total is split into the whole units counter and the fraction of unit.
+Only the fraction is accumulated in the fixed-point type, so the precision
+of accumulation doesn't degrade when the total grows during the long runtime.
+
+The whole units counter can roll over at given limit (like mechanical counters do).
+Also the resettable sub-total (like trip counter) is counted independently of total.
+
+The negative rates is ignored, so the total never decreases.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Totalizer parameters
+
+- `K` - scaling factor type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K> {
+    /// The scaling factor premultiplied by sampling period _k P_
+    scale: K,
+    /// The rollover limit of whole units counter
+    rollover: Option<u64>,
+}
+
+impl<K> Param<K> {
+    /**
+    Init totalizer parameters
+
+    - `scale`: The units scaling factor _k_ (units of total per unit of rate per second)
+    - `period`: The sampling period _P_ (s)
+
+    The total doesn't roll over by default.
+     */
+    pub fn new<V>(scale: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            scale: K::cast(f64::cast(scale) * f64::cast(period)),
+            rollover: None,
+        }
+    }
+
+    /**
+    Set rollover limit of total
+
+    - `limit`: The number of whole units when the total rolls over to zero
+     */
+    pub fn with_rollover(mut self, limit: u64) -> Self {
+        assert!(limit > 0);
+        self.rollover = Some(limit);
+        self
+    }
+}
+
+/**
+Totalizer state
+
+- `A` - fraction accumulator type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<A> {
+    /// The whole units of total
+    total: u64,
+    /// The whole units of sub-total
+    subtotal: u64,
+    /// The fraction of unit
+    fraction: A,
+}
+
+impl<A> State<A> {
+    /// The whole units of total
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The whole units of sub-total
+    pub fn subtotal(&self) -> u64 {
+        self.subtotal
+    }
+
+    /// The accumulated fraction of unit
+    pub fn fraction(&self) -> A
+    where
+        A: Copy,
+    {
+        self.fraction
+    }
+
+    /// Reset the sub-total
+    pub fn reset_subtotal(&mut self) {
+        self.subtotal = 0;
+    }
+}
+
+/// The preset value is a whole units of total (the sub-total is reset)
+impl<A> ResetState for State<A>
+where
+    A: Default,
+{
+    type Value = u64;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, total: Self::Value) {
+        *self = Self::default();
+        self.total = total;
+    }
+}
+
+snapshot_fields! {
+    State<A> {
+        total: u64,
+        subtotal: u64,
+        fraction: A,
+    }
+}
+
+/**
+Totalizer
+
+- `T` - rate type
+- `K` - scaling factor type
+- `A` - fraction accumulator type
+
+The input is a rate. The output is a whole units of total.
+
+The accumulator type should hold the values a bit greater than the maximum increment
+of total per step plus one.
+*/
+#[derive(Debug)]
+pub struct Totalizer<T, K, A>(PhantomData<(T, K, A)>);
+
+impl<T, K, A> Transducer for Totalizer<T, K, A>
+where
+    T: Copy + PartialOrd + Default,
+    K: Copy + Mul<T>,
+    A: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Cast<u32>
+        + Cast<Prod<K, T>>
+        + Add<A>
+        + Sub<A>
+        + Cast<Sum<A, A>>
+        + Cast<Diff<A, A>>,
+    u32: Cast<A>,
+{
+    type Input = T;
+    type Output = u64;
+    type Param = Param<K>;
+    type State = State<A>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, rate: Self::Input) -> Self::Output {
+        if rate > T::default() {
+            state.fraction = A::cast(state.fraction + A::cast(param.scale * rate));
+
+            if state.fraction >= A::cast(1.0) {
+                let whole = u32::cast(state.fraction);
+                state.fraction = A::cast(state.fraction - A::cast(whole));
+
+                state.subtotal = state.subtotal.wrapping_add(whole as u64);
+                state.total = match param.rollover {
+                    Some(limit) => (state.total + whole as u64) % limit,
+                    None => state.total.wrapping_add(whole as u64),
+                };
+            }
+        }
+
+        state.total
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, N28, P24, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn totalizer_f64() {
+        type Q = Totalizer<f64, f64, f64>;
+        // 10 units per second, 0.1 s period
+        let param = Param::<f64>::new(1.0, 0.1);
+        let mut state = State::default();
+
+        let mut total = 0;
+        for _ in 0..1000 {
+            total = Q::apply(&param, &mut state, 10.0);
+        }
+        assert!((999..=1000).contains(&total), "{}", total);
+
+        // the negative rate is ignored
+        Q::apply(&param, &mut state, -100.0);
+        assert_eq!(state.total(), total);
+        assert_eq!(state.subtotal(), total);
+    }
+
+    #[test]
+    fn totalizer_rollover() {
+        type Q = Totalizer<f32, f32, f32>;
+        let param = Param::<f32>::new(1.0, 1.0).with_rollover(100);
+        let mut state = State::default();
+
+        state.preset(95);
+        assert_eq!(Q::apply(&param, &mut state, 3.5), 98);
+        assert_eq!(Q::apply(&param, &mut state, 3.5), 2);
+        assert_eq!(state.fraction(), 0.0);
+        assert_eq!(state.subtotal(), 7);
+
+        state.reset_subtotal();
+        assert_eq!(Q::apply(&param, &mut state, 150.0), 52);
+        assert_eq!(state.subtotal(), 150);
+    }
+
+    #[test]
+    fn totalizer_fix() {
+        type T = Fix<P24, N16>;
+        type K = Fix<P24, N28>;
+        type A = Fix<P32, N24>;
+        type Q = Totalizer<T, K, A>;
+
+        // liters of flow measured in liters per minute with 10 ms period
+        let param = Param::<K>::new(1.0 / 60.0, 0.01);
+        let mut state = State::default();
+
+        // 200 min of 30 L/min
+        let rate = T::cast(30.0);
+        for _ in 0..1_200_000 {
+            Q::apply(&param, &mut state, rate);
+        }
+        let total = state.total() as f64 + f64::cast(state.fraction());
+        assert!((total - 6000.0).abs() < 1.0, "{}", total);
+    }
+}
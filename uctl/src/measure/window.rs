@@ -0,0 +1,121 @@
+/*!
+
+## Window functions
+
+This module implements the generation of common windows for block analysis such as [FFT](crate::fft).
+
+The windows is a sums of cosines:
+
+_w\[n\] = a<sub>0</sub> - a<sub>1</sub> cos(2πn/N) + a<sub>2</sub> cos(4πn/N)_
+
+- Hann: _a<sub>0</sub> = 0.5, a<sub>1</sub> = 0.5_
+- Hamming: _a<sub>0</sub> = 0.54, a<sub>1</sub> = 0.46_
+- Blackman: _a<sub>0</sub> = 0.42, a<sub>1</sub> = 0.5, a<sub>2</sub> = 0.08_
+
+The windows is periodic (DFT-even), which is preferred for spectral analysis.
+
+The coefficients is evaluated in double precision at initialization time,
+so usually the window is generated once and applied to each block using [`apply`].
+
+*/
+
+use crate::{Cast, SinCos};
+use core::{f64::consts::PI, ops::Mul};
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+use typenum::{Prod, Unsigned};
+
+/// Generate cosine-sum window
+fn cosine_sum<W, N>(a0: f64, a1: f64, a2: f64) -> GenericArray<W, N>
+where
+    W: Cast<f64>,
+    N: Unsigned + ArrayLength<W>,
+{
+    let angle = 2.0 * PI / N::USIZE as f64;
+
+    GenericArray::generate(|n| {
+        let (_, cos1) = SinCos::sin_cos(n as f64 * angle);
+        let (_, cos2) = SinCos::sin_cos(2.0 * n as f64 * angle);
+        W::cast(a0 - a1 * cos1 + a2 * cos2)
+    })
+}
+
+/// Generate Hann window
+pub fn hann<W, N>() -> GenericArray<W, N>
+where
+    W: Cast<f64>,
+    N: Unsigned + ArrayLength<W>,
+{
+    cosine_sum(0.5, 0.5, 0.0)
+}
+
+/// Generate Hamming window
+pub fn hamming<W, N>() -> GenericArray<W, N>
+where
+    W: Cast<f64>,
+    N: Unsigned + ArrayLength<W>,
+{
+    cosine_sum(0.54, 0.46, 0.0)
+}
+
+/// Generate Blackman window
+pub fn blackman<W, N>() -> GenericArray<W, N>
+where
+    W: Cast<f64>,
+    N: Unsigned + ArrayLength<W>,
+{
+    cosine_sum(0.42, 0.5, 0.08)
+}
+
+/**
+Apply window to block of values in-place
+
+- `window`: The window coefficients
+- `values`: The block of values
+*/
+pub fn apply<W, T, N>(window: &GenericArray<W, N>, values: &mut GenericArray<T, N>)
+where
+    W: Copy + Mul<T>,
+    T: Copy + Cast<Prod<W, T>>,
+    N: ArrayLength<W> + ArrayLength<T>,
+{
+    for (value, &coeff) in values.iter_mut().zip(window.iter()) {
+        *value = T::cast(coeff * *value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N30, P32, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn window_f32() {
+        assert_eq!(hann::<f32, U4>(), GenericArray::from([0.0, 0.5, 1.0, 0.5]));
+        assert_eq!(
+            hamming::<f32, U4>(),
+            GenericArray::from([0.08, 0.54, 1.0, 0.54])
+        );
+        assert_eq!(blackman::<f32, U4>()[2], 1.0);
+        assert!(blackman::<f32, U4>()[0].abs() < 1e-7);
+
+        let mut values = GenericArray::from([2.0, 2.0, 2.0, 2.0]);
+        apply(&hann::<f32, U4>(), &mut values);
+        assert_eq!(values, GenericArray::from([0.0, 1.0, 2.0, 1.0]));
+    }
+
+    #[test]
+    fn window_fix() {
+        type W = Fix<P32, N30>;
+        type T = Fix<P32, N16>;
+
+        let window = hann::<W, U4>();
+        let mut values = GenericArray::from([T::cast(3.0); 4]);
+
+        apply(&window, &mut values);
+        assert_eq!(
+            values,
+            GenericArray::from([T::cast(0.0), T::cast(1.5), T::cast(3.0), T::cast(1.5)])
+        );
+    }
+}
@@ -0,0 +1,144 @@
+/*!
+
+## Cross-correlation
+
+This module implements the cross-correlation of the stored template with the last
+_N_ samples of signal (also known as **matched filter**).
+
+_r\[n\] = Σ h\[k\] x\[n - N + 1 + k\]_
+
+The template is stored in chronological order (the oldest sample first), so the output
+reaches the peak when the last _N_ samples of signal match the template.
+This can be used for echo ranging, detection of known patterns in sensor streams and etc.
+
+Because the products of template and signal is summed without scaling the accumulator
+type usually should be wider than the type of products to avoid overflow.
+
+*/
+
+use crate::{Cast, Core, CycleCost, DelayLine, OpCost, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Prod, Sum, Unsigned};
+
+/// Cross-correlation parameters
+///
+/// - `B` - template values type
+/// - `N` - template length
+pub type Param<B, N> = GenericArray<B, N>;
+
+/// Cross-correlation state
+///
+/// - `L` - delay line type
+pub type State<L> = L;
+
+/**
+Correlate template with delay line contents
+
+- `template`: The template values (the oldest first)
+- `line`: The delay line with last samples
+*/
+pub fn correlate<A, B, L>(template: &Param<B, L::Length>, line: &L) -> A
+where
+    A: Default + Add<A> + Cast<Sum<A, A>> + Cast<Prod<B, L::Value>>,
+    B: Copy + Mul<L::Value>,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+    L::Length: ArrayLength<B>,
+{
+    // the delay line gives the newest values first
+    template
+        .iter()
+        .rev()
+        .zip(line.iter())
+        .fold(A::default(), |accum, (b, x)| {
+            A::cast(accum + A::cast(*b * x))
+        })
+}
+
+/**
+Matched filter
+
+- `A` - accumulator (output) type
+- `B` - template values type
+- `L` - delay line type
+*/
+#[derive(Debug)]
+pub struct Filter<A, B, L>(PhantomData<(A, B, L)>);
+
+impl<A, B, L> Transducer for Filter<A, B, L>
+where
+    A: Default + Add<A> + Cast<Sum<A, A>> + Cast<Prod<B, L::Value>>,
+    B: Copy + Mul<L::Value>,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+    L::Length: ArrayLength<B>,
+{
+    type Input = L::Value;
+    type Output = A;
+    type Param = Param<B, L::Length>;
+    type State = State<L>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.push(value);
+        correlate(param, state)
+    }
+}
+
+impl<A, B, L> CycleCost for Filter<A, B, L>
+where
+    A: OpCost,
+    B: OpCost,
+    L: DelayLine,
+    for<'a> &'a L: IntoIterator<Item = L::Value>,
+{
+    fn cycles(core: Core) -> u32 {
+        let length = L::Length::to_u32();
+        length * B::mul(core) + length * A::add(core)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pfdl::Store as DL;
+    use typenum::{N16, N8, P16, P32, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn xcorr_i32() {
+        let param = Param::<i32, U3>::from([1, 2, 3]);
+        let mut state = DL::<i32, U3>::from(0);
+
+        type F = Filter<i32, i32, DL<i32, U3>>;
+
+        assert_eq!(F::apply(&param, &mut state, 1), 3);
+        assert_eq!(F::apply(&param, &mut state, 2), 8);
+        // the signal matches template
+        assert_eq!(F::apply(&param, &mut state, 3), 14);
+        assert_eq!(F::apply(&param, &mut state, 0), 8);
+        assert_eq!(F::apply(&param, &mut state, 0), 3);
+        assert_eq!(F::apply(&param, &mut state, 0), 0);
+    }
+
+    #[test]
+    fn xcorr_fix() {
+        type I = Fix<P16, N8>;
+        type A = Fix<P32, N16>;
+
+        let param = Param::<I, U3>::from([I::cast(1.0), I::cast(-1.0), I::cast(1.0)]);
+        let mut state = DL::<I, U3>::from(I::cast(0.0));
+
+        type F = Filter<A, I, DL<I, U3>>;
+
+        // the sum exceeds the range of input type
+        F::apply(&param, &mut state, I::cast(100.0));
+        F::apply(&param, &mut state, I::cast(-100.0));
+        assert_eq!(F::apply(&param, &mut state, I::cast(100.0)), A::cast(300.0));
+
+        assert_eq!(correlate::<A, _, _>(&param, &state), A::cast(300.0));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod alarm;
+pub mod derate;
+pub mod dual;
+pub mod i2t;
+pub mod retry;
+pub mod selftest;
+pub mod thermal;
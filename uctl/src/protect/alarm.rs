@@ -0,0 +1,287 @@
+/*!
+
+## Alarm
+
+This module implements the process alarm which watches the value against
+the high and low limits.
+
+To avoid chattering of alarm on noisy values:
+
+- the alarm is raised only when the limit is exceeded continuously during the on-delay
+- the alarm is cleared only when the value returns inside the limits by the deadband
+  and stays there continuously during the off-delay
+
+The limits is optional, so the high-only and low-only alarms is possible too.
+
+*/
+
+use crate::{snapshot_enum, snapshot_fields, steps, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+use typenum::{Diff, Sum};
+
+/// The alarm condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Condition {
+    /// The value is inside the limits
+    #[default]
+    Normal,
+    /// The value is above the high limit
+    High,
+    /// The value is below the low limit
+    Low,
+}
+
+snapshot_enum!(Condition { Normal, High, Low });
+
+/**
+Alarm parameters
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The high limit
+    high: Option<T>,
+    /// The low limit
+    low: Option<T>,
+    /// The deadband
+    deadband: T,
+    /// The on-delay in steps
+    on_delay: u32,
+    /// The off-delay in steps
+    off_delay: u32,
+}
+
+impl<T> Param<T> {
+    /**
+    Init alarm parameters
+
+    - `deadband`: The deadband to clear the alarm
+    - `on_delay`: The delay to raise the alarm (s)
+    - `off_delay`: The delay to clear the alarm (s)
+    - `period`: The sampling period (s)
+
+    The limits isn't set by default.
+     */
+    pub fn new<V>(deadband: V, on_delay: V, off_delay: V, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let period = f64::cast(period);
+
+        Self {
+            high: None,
+            low: None,
+            deadband: T::cast(f64::cast(deadband)),
+            on_delay: steps(f64::cast(on_delay), period),
+            off_delay: steps(f64::cast(off_delay), period),
+        }
+    }
+
+    /**
+    Set high limit
+
+    - `limit`: The high limit
+     */
+    pub fn with_high<V>(mut self, limit: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        self.high = Some(T::cast(f64::cast(limit)));
+        self
+    }
+
+    /**
+    Set low limit
+
+    - `limit`: The low limit
+     */
+    pub fn with_low<V>(mut self, limit: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        self.low = Some(T::cast(f64::cast(limit)));
+        self
+    }
+}
+
+/**
+Alarm state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The actual condition
+    alarm: Condition,
+    /// The pending condition
+    pending: Condition,
+    /// The number of steps while the pending condition persists
+    elapsed: u32,
+}
+
+impl State {
+    /// The actual condition
+    pub fn alarm(&self) -> Condition {
+        self.alarm
+    }
+}
+
+/// The preset value is an alarm condition
+impl ResetState for State {
+    type Value = Condition;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, alarm: Self::Value) {
+        self.alarm = alarm;
+        self.pending = alarm;
+        self.elapsed = 0;
+    }
+}
+
+snapshot_fields! {
+    State {
+        alarm: Condition,
+        pending: Condition,
+        elapsed: u32,
+    }
+}
+
+/**
+Alarm
+
+- `T` - values type
+
+The input is a watched value. The output is an alarm condition.
+*/
+#[derive(Debug)]
+pub struct Alarm<T>(PhantomData<T>);
+
+impl<T> Transducer for Alarm<T>
+where
+    T: Copy + PartialOrd + Add<T> + Sub<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+{
+    type Input = T;
+    type Output = Condition;
+    type Param = Param<T>;
+    type State = State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let condition = match (param.high, param.low) {
+            (Some(high), _) if value > high => Condition::High,
+            (_, Some(low)) if value < low => Condition::Low,
+            // keep the alarm inside the deadband
+            (Some(high), _)
+                if state.alarm == Condition::High && value > T::cast(high - param.deadband) =>
+            {
+                Condition::High
+            }
+            (_, Some(low))
+                if state.alarm == Condition::Low && value < T::cast(low + param.deadband) =>
+            {
+                Condition::Low
+            }
+            _ => Condition::Normal,
+        };
+
+        if condition == state.alarm {
+            state.pending = condition;
+            state.elapsed = 0;
+        } else {
+            if condition != state.pending {
+                state.pending = condition;
+                state.elapsed = 0;
+            }
+
+            state.elapsed = state.elapsed.saturating_add(1);
+
+            let delay = if condition == Condition::Normal {
+                param.off_delay
+            } else {
+                param.on_delay
+            };
+
+            if state.elapsed > delay {
+                state.alarm = condition;
+                state.elapsed = 0;
+            }
+        }
+
+        state.alarm
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn alarm_limits() {
+        type A = Alarm<f32>;
+        let param = Param::<f32>::new(1.0, 0.0, 0.0, 1.0)
+            .with_high(80.0)
+            .with_low(10.0);
+        let mut state = State::default();
+
+        assert_eq!(A::apply(&param, &mut state, 50.0), Condition::Normal);
+        assert_eq!(A::apply(&param, &mut state, 80.5), Condition::High);
+        // deadband
+        assert_eq!(A::apply(&param, &mut state, 79.5), Condition::High);
+        assert_eq!(A::apply(&param, &mut state, 78.5), Condition::Normal);
+        assert_eq!(A::apply(&param, &mut state, 9.0), Condition::Low);
+        assert_eq!(A::apply(&param, &mut state, 10.5), Condition::Low);
+        assert_eq!(A::apply(&param, &mut state, 11.5), Condition::Normal);
+        assert_eq!(A::apply(&param, &mut state, 81.0), Condition::High);
+        assert_eq!(A::apply(&param, &mut state, 5.0), Condition::Low);
+        assert_eq!(state.alarm(), Condition::Low);
+    }
+
+    #[test]
+    fn alarm_delays() {
+        type A = Alarm<f32>;
+        // 2 s on-delay, 3 s off-delay with 1 s period
+        let param = Param::<f32>::new(0.0, 2.0, 3.0, 1.0).with_high(100.0);
+        let mut state = State::default();
+
+        let values = [
+            101.0, 101.0, 99.0, 101.0, 101.0, 101.0, 99.0, 99.0, 101.0, 99.0, 99.0, 99.0, 99.0,
+        ];
+        let mut alarms = [Condition::Normal; 13];
+        for (alarm, value) in alarms.iter_mut().zip(values.iter()) {
+            *alarm = A::apply(&param, &mut state, *value);
+        }
+
+        use Condition::*;
+        assert_eq!(
+            alarms,
+            [
+                Normal, Normal, Normal, Normal, Normal, High, High, High, High, High, High, High,
+                Normal
+            ]
+        );
+    }
+
+    #[test]
+    fn alarm_fix() {
+        type T = Fix<P32, N16>;
+        type A = Alarm<T>;
+        let param = Param::<T>::new(0.5, 0.0, 0.0, 0.01).with_low(3.0);
+        let mut state = State::default();
+
+        assert_eq!(A::apply(&param, &mut state, T::cast(2.9)), Condition::Low);
+        assert_eq!(A::apply(&param, &mut state, T::cast(3.4)), Condition::Low);
+        assert_eq!(
+            A::apply(&param, &mut state, T::cast(3.6)),
+            Condition::Normal
+        );
+    }
+}
@@ -0,0 +1,177 @@
+/*!
+
+## Derating
+
+This module implements derating block which scales the command limit
+depending on the operating conditions such as temperature or supply voltage.
+
+The derating factor is evaluated through the piecewise-linear curve which
+is defined by the sequence of points `(x, factor)` sorted by ascending of `x`.
+The factor is interpolated linearly between points and holds the value of
+first or last point outside of curve range.
+
+_y = L * (k0 + (x - x0) / (x1 - x0) * (k1 - k0))_
+
+where _L_ is a nominal limit.
+
+The output limit usually feeds the bounds of [`Clamper`](crate::clamper::Clamper)
+through the runtime-adjustable [`ClampParam`](crate::clamper::ClampParam).
+
+*/
+
+use crate::{Cast, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Sub},
+};
+use typenum::{Diff, Prod, Quot, Sum};
+
+/**
+Derating parameters
+
+- `X` - input value type
+- `F` - derating factor type
+- `L` - limit type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<'a, X, F, L> {
+    /// The points of derating curve
+    curve: &'a [(X, F)],
+    /// The nominal limit
+    limit: L,
+}
+
+impl<'a, X, F, L> Param<'a, X, F, L> {
+    /**
+    Init derating parameters
+
+    - `curve`: The points of derating curve `(x, factor)` sorted by `x`
+    - `limit`: The nominal limit
+     */
+    pub fn new(curve: &'a [(X, F)], limit: L) -> Self {
+        Self { curve, limit }
+    }
+
+    /// The points of derating curve
+    pub fn curve(&self) -> &'a [(X, F)] {
+        self.curve
+    }
+
+    /// Set the nominal limit
+    pub fn set_limit(&mut self, limit: L) {
+        self.limit = limit;
+    }
+}
+
+/**
+Derating block
+
+- `X` - input value type
+- `F` - derating factor type
+- `L` - limit type
+- `K` - interpolation factor type
+
+The input is a value of operating condition (temperature, voltage and etc.).
+The output is a derated limit.
+*/
+#[derive(Debug)]
+pub struct Derate<'a, X, F, L, K>(PhantomData<(&'a (), X, F, L, K)>);
+
+impl<'a, X, F, L, K> Transducer for Derate<'a, X, F, L, K>
+where
+    X: Copy + PartialOrd + Sub<X> + 'a,
+    F: Copy
+        + Cast<f64>
+        + Sub<F>
+        + Add<F>
+        + Mul<L>
+        + Cast<Prod<K, Diff<F, F>>>
+        + Cast<Sum<F, F>>
+        + 'a,
+    L: Copy + Cast<Prod<F, L>>,
+    K: Mul<Diff<X, X>> + Mul<Diff<F, F>> + Cast<Quot<Prod<K, Diff<X, X>>, Diff<X, X>>>,
+    Prod<K, Diff<X, X>>: Cast<Diff<X, X>> + Div<Diff<X, X>>,
+{
+    type Input = X;
+    type Output = L;
+    type Param = Param<'a, X, F, L>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let curve = param.curve;
+
+        let factor = match curve.iter().position(|(x, _)| value < *x) {
+            // empty curve
+            None if curve.is_empty() => F::cast(1.0),
+            // above the last point
+            None => curve[curve.len() - 1].1,
+            // below the first point
+            Some(0) => curve[0].1,
+            Some(index) => {
+                let (x0, k0) = curve[index - 1];
+                let (x1, k1) = curve[index];
+
+                let k = K::cast(Prod::<K, Diff<X, X>>::cast(value - x0) / (x1 - x0));
+
+                F::cast(k0 + F::cast(k * (k1 - k0)))
+            }
+        };
+
+        L::cast(factor * param.limit)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clamper::{ClampParam, Clamper};
+    use typenum::{N14, N4, N8, P16};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn derate_f32() {
+        let curve = [(80.0, 1.0), (100.0, 0.5), (120.0, 0.0)];
+        let param = Param::new(&curve, 10.0);
+        type D<'a> = Derate<'a, f32, f32, f32, f32>;
+
+        assert_eq!(D::apply(&param, &mut (), 50.0), 10.0);
+        assert_eq!(D::apply(&param, &mut (), 80.0), 10.0);
+        assert_eq!(D::apply(&param, &mut (), 90.0), 7.5);
+        assert_eq!(D::apply(&param, &mut (), 100.0), 5.0);
+        assert_eq!(D::apply(&param, &mut (), 110.0), 2.5);
+        assert_eq!(D::apply(&param, &mut (), 130.0), 0.0);
+
+        let param = Param::new(&[], 10.0);
+        assert_eq!(D::apply(&param, &mut (), 130.0), 10.0);
+    }
+
+    #[test]
+    fn derate_clamp_fix() {
+        type X = Fix<P16, N4>;
+        type F = Fix<P16, N14>;
+        type L = Fix<P16, N8>;
+        type K = Fix<P16, N14>;
+
+        let curve = [
+            (X::cast(80.0), F::cast(1.0)),
+            (X::cast(100.0), F::cast(0.5)),
+            (X::cast(120.0), F::cast(0.0)),
+        ];
+        let param = Param::new(&curve, L::cast(10.0));
+        type D<'a> = Derate<'a, X, F, L, K>;
+
+        let mut range = ClampParam::new(L::cast(-10.0), L::cast(10.0));
+        type C = Clamper<ClampParam<L>, L>;
+
+        assert_eq!(C::apply(&range, &mut (), L::cast(8.0)), L::cast(8.0));
+
+        let limit = D::apply(&param, &mut (), X::cast(90.0));
+        assert_eq!(limit, L::cast(7.5));
+
+        range.max = Some(limit);
+        assert_eq!(C::apply(&range, &mut (), L::cast(8.0)), L::cast(7.5));
+
+        let limit = D::apply(&param, &mut (), X::cast(117.5));
+        assert_eq!(limit, L::cast(0.625));
+    }
+}
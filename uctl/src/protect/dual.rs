@@ -0,0 +1,240 @@
+/*!
+
+## Dual-channel computation
+
+This module implements the redundant computation for dual-channel safety architectures.
+
+The [`Dual`] wrapper runs two instances of chain (the primary and the secondary channels)
+with the same input and compares the outputs. When the difference of outputs exceeds
+the tolerance continuously during the given number of steps the divergence is flagged.
+The flag is latched until the reset of state.
+
+The channels may use different types of values. For example, the primary channel
+may use fixed-point values while the secondary channel uses floating-point values,
+so the overflows and the loss of precision of fixed-point chain is caught in field.
+The input of secondary channel and the output of primary channel is converted using [`Cast`].
+
+The outputs is compared in the type of output of secondary channel,
+so the secondary channel should have the wider range of values (like floating-point),
+otherwise the overflowed output of primary channel may be converted to the same wrong value.
+The output of primary channel is used as output of wrapper.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{marker::PhantomData, ops::Sub};
+use typenum::Diff;
+
+/**
+Dual-channel parameters
+
+- `A` - primary channel parameters type
+- `B` - secondary channel parameters type
+- `O` - output values type of secondary channel
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<A, B, O> {
+    /// The parameters of primary channel
+    pub primary: A,
+    /// The parameters of secondary channel
+    pub secondary: B,
+    /// The tolerance of difference of outputs
+    tolerance: O,
+    /// The number of steps to confirm divergence
+    persist: u32,
+}
+
+impl<A, B, O> Param<A, B, O> {
+    /**
+    Init dual-channel parameters
+
+    - `primary`: The parameters of primary channel
+    - `secondary`: The parameters of secondary channel
+    - `tolerance`: The tolerance of difference of outputs
+    - `persist`: The number of steps with difference out of tolerance to flag divergence (at least 1)
+     */
+    pub fn new<V>(primary: A, secondary: B, tolerance: V, persist: u32) -> Self
+    where
+        O: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(persist > 0);
+
+        Self {
+            primary,
+            secondary,
+            tolerance: O::cast(f64::cast(tolerance)),
+            persist,
+        }
+    }
+}
+
+/**
+Dual-channel state
+
+- `A` - primary channel state type
+- `B` - secondary channel state type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<A, B> {
+    /// The state of primary channel
+    pub primary: A,
+    /// The state of secondary channel
+    pub secondary: B,
+    /// The number of steps with difference out of tolerance
+    count: u32,
+    /// The divergence is flagged
+    diverged: bool,
+}
+
+impl<A, B> State<A, B> {
+    /// The divergence of channels is flagged
+    pub fn is_diverged(&self) -> bool {
+        self.diverged
+    }
+}
+
+/// The states of both channels is reset too
+impl<A, B> ResetState for State<A, B>
+where
+    A: ResetState,
+    B: ResetState,
+{
+    type Value = (A::Value, B::Value);
+
+    fn reset(&mut self) {
+        self.primary.reset();
+        self.secondary.reset();
+        self.count = 0;
+        self.diverged = false;
+    }
+
+    fn preset(&mut self, (primary, secondary): Self::Value) {
+        self.primary.preset(primary);
+        self.secondary.preset(secondary);
+        self.count = 0;
+        self.diverged = false;
+    }
+}
+
+snapshot_fields! {
+    State<A, B> {
+        primary: A,
+        secondary: B,
+        count: u32,
+        diverged: bool,
+    }
+}
+
+/**
+Dual-channel computation
+
+- `A` - primary channel (transducer or chain)
+- `B` - secondary channel (transducer or chain)
+
+The input is an input of primary channel.
+The output is a tuple of output of primary channel and divergence flag.
+*/
+#[derive(Debug)]
+pub struct Dual<A, B>(PhantomData<(A, B)>);
+
+impl<A, B> Transducer for Dual<A, B>
+where
+    A: Transducer,
+    B: Transducer,
+    A::Input: Copy,
+    B::Input: Cast<A::Input>,
+    A::Output: Copy,
+    B::Output: Copy + PartialOrd + Sub<B::Output> + Cast<Diff<B::Output, B::Output>>,
+    B::Output: Cast<A::Output>,
+{
+    type Input = A::Input;
+    type Output = (A::Output, bool);
+    type Param = Param<A::Param, B::Param, B::Output>;
+    type State = State<A::State, B::State>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let output = A::apply(&param.primary, &mut state.primary, value);
+        let primary = B::Output::cast(output);
+        let secondary = B::apply(
+            &param.secondary,
+            &mut state.secondary,
+            B::Input::cast(value),
+        );
+
+        let difference = if primary > secondary {
+            B::Output::cast(primary - secondary)
+        } else {
+            B::Output::cast(secondary - primary)
+        };
+
+        if difference > param.tolerance {
+            state.count = state.count.saturating_add(1);
+            if state.count >= param.persist {
+                state.diverged = true;
+            }
+        } else {
+            state.count = 0;
+        }
+
+        (output, state.diverged)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, scaler};
+    use typenum::{N12, N2, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn dual_agree() {
+        type T = Fix<P32, N12>;
+        type D = Dual<ema::Filter<T, T, T>, ema::Filter<f64, f64, f64>>;
+
+        let param = Param::new(
+            ema::Param::from_alpha(T::cast(0.25)),
+            ema::Param::from_alpha(0.25),
+            0.01,
+            3,
+        );
+        let mut state = State::default();
+
+        for _ in 0..50 {
+            let (_, diverged) = D::apply(&param, &mut state, T::cast(100.0));
+            assert!(!diverged);
+        }
+        let (y, _) = D::apply(&param, &mut state, T::cast(100.0));
+        assert!((f64::cast(y) - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn dual_precision() {
+        // the factor 0.3 is rounded to 0.25 in coarse fixed-point type
+        type T = Fix<P16, N2>;
+        type D = Dual<scaler::Scaler<T, T, T>, scaler::Scaler<f64, f64, f64>>;
+
+        let param = Param::new(
+            scaler::Param::from_factor(T::cast(0.3), T::cast(0.0)),
+            scaler::Param::from_factor(0.3, 0.0),
+            0.1,
+            2,
+        );
+        let mut state = State::default();
+
+        assert!(!D::apply(&param, &mut state, T::cast(0.0)).1);
+        assert!(!D::apply(&param, &mut state, T::cast(10.0)).1);
+        // the single glitch isn't flagged
+        assert!(!D::apply(&param, &mut state, T::cast(0.0)).1);
+        assert!(!D::apply(&param, &mut state, T::cast(10.0)).1);
+        assert!(D::apply(&param, &mut state, T::cast(10.0)).1);
+        // the flag is latched
+        assert!(D::apply(&param, &mut state, T::cast(0.0)).1);
+        assert!(state.is_diverged());
+
+        state.reset();
+        assert!(!state.is_diverged());
+        assert!(!D::apply(&param, &mut state, T::cast(0.0)).1);
+    }
+}
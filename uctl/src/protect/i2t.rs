@@ -0,0 +1,278 @@
+/*!
+
+## I²t overload protection
+
+This module implements the protective function which trips when the current
+exceeds the time-weighted threshold curve (so called I²t characteristic).
+
+The excess of squared current over the squared nominal current is integrated over time:
+
+_W\[n\] = min(C, max(0, W\[n-1\] + (I\[n\]² - In²) * P))_
+
+where _In_ is a nominal (continuously allowed) current, _P_ is a sampling period
+and _C_ is a trip capacity.
+When the current is less than nominal the accumulator decreases, which models the cooling.
+The accumulator is saturated at the trip capacity, so the cooling time after trip
+doesn't depend on how long the overload lasts.
+
+The trip signal is raised when the accumulated value reaches the trip capacity
+and stays latched until it will be released explicitly by [`State::release`].
+
+The trip capacity is usually evaluated from the pair of trip current and trip time:
+
+_C = (It² - In²) * t_
+
+so the protection trips after the time _t_ when the current _It_ flows continuously,
+and faster when the current is greater.
+
+The input value may be a current as well as a regulator error (for stall detection).
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+I²t protection parameters
+
+- `A` - accumulator type (also used for squared currents)
+- `D` - sampling period type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<A, D> {
+    /// The squared nominal current
+    nominal: A,
+    /// The trip capacity
+    capacity: A,
+    /// The sampling period
+    period: D,
+}
+
+impl<A, D> Param<A, D> {
+    /**
+    Init I²t protection parameters
+
+    - `nominal`: The nominal current which can flow continuously
+    - `current`: The trip current
+    - `time`: The trip time for given trip current
+    - `period`: The sampling period
+     */
+    pub fn new<T>(nominal: T, current: T, time: T, period: T) -> Self
+    where
+        A: Cast<f64>,
+        D: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let nominal = f64::cast(nominal);
+        let nominal = nominal * nominal;
+        let current = f64::cast(current);
+
+        Self {
+            nominal: A::cast(nominal),
+            capacity: A::cast((current * current - nominal) * f64::cast(time)),
+            period: D::cast(f64::cast(period)),
+        }
+    }
+}
+
+/**
+I²t protection state
+
+- `A` - accumulator type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<A> {
+    /// The accumulated value
+    value: A,
+    /// The latched trip flag
+    trip: bool,
+}
+
+impl<A> State<A> {
+    /// Initialize I²t protection state
+    pub fn new(value: A) -> Self {
+        Self { value, trip: false }
+    }
+
+    /// The accumulated value
+    pub fn value(&self) -> A
+    where
+        A: Copy,
+    {
+        self.value
+    }
+
+    /// Check when protection is tripped
+    pub fn is_tripped(&self) -> bool {
+        self.trip
+    }
+
+    /**
+    Release trip latch
+
+    The accumulated value is kept as is, so the protection trips again
+    at next step when the overload is not cooled down yet.
+     */
+    pub fn release(&mut self) {
+        self.trip = false;
+    }
+}
+
+/// The preset value is an accumulated value
+impl<A> ResetState for State<A>
+where
+    A: Default,
+{
+    type Value = A;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        *self = Self::new(value);
+    }
+}
+
+snapshot_fields! {
+    State<A> {
+        value: A,
+        trip: bool,
+    }
+}
+
+/**
+I²t overload protection
+
+- `I` - current type
+- `A` - accumulator type
+- `D` - sampling period type
+
+The output is a latched trip signal.
+*/
+#[derive(Debug)]
+pub struct Overload<I, A, D>(PhantomData<(I, A, D)>);
+
+impl<I, A, D> Transducer for Overload<I, A, D>
+where
+    I: Copy + Mul<I>,
+    A: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Cast<Prod<I, I>>
+        + Add<A>
+        + Sub<A>
+        + Cast<Sum<A, A>>
+        + Cast<Diff<A, A>>
+        + Mul<D>
+        + Cast<Prod<A, D>>,
+    D: Copy,
+{
+    type Input = I;
+    type Output = bool;
+    type Param = Param<A, D>;
+    type State = State<A>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let square = A::cast(value * value);
+
+        state.value = if square > param.nominal {
+            // heating
+            let delta = A::cast(A::cast(square - param.nominal) * param.period);
+            if delta < A::cast(param.capacity - state.value) {
+                A::cast(state.value + delta)
+            } else {
+                param.capacity
+            }
+        } else {
+            // cooling
+            let delta = A::cast(A::cast(param.nominal - square) * param.period);
+            if state.value > delta {
+                A::cast(state.value - delta)
+            } else {
+                A::cast(0.0)
+            }
+        };
+
+        if state.value >= param.capacity {
+            state.trip = true;
+        }
+
+        state.trip
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn i2t_f32() {
+        // 2 A nominal, trips after 1 s at 4 A
+        let param = Param::<f32, f32>::new(2.0, 4.0, 1.0, 0.125);
+        let mut state = State::default();
+        type P = Overload<f32, f32, f32>;
+
+        // nominal current never trips
+        for _ in 0..100 {
+            assert!(!P::apply(&param, &mut state, 2.0));
+        }
+        assert_eq!(state.value(), 0.0);
+
+        for _ in 0..7 {
+            assert!(!P::apply(&param, &mut state, 4.0));
+        }
+        assert_eq!(state.value(), 10.5);
+        assert!(P::apply(&param, &mut state, 4.0));
+
+        // latched
+        assert!(P::apply(&param, &mut state, 0.0));
+        assert_eq!(state.value(), 11.5);
+
+        // trips again when not cooled enough
+        state.release();
+        assert!(!P::apply(&param, &mut state, 0.0));
+        assert!(!P::apply(&param, &mut state, 0.0));
+        assert!(P::apply(&param, &mut state, 4.0));
+    }
+
+    #[test]
+    fn i2t_fix() {
+        type I = Fix<P16, N8>;
+        type A = Fix<P32, N16>;
+        type D = Fix<P16, N16>;
+
+        let param = Param::<A, D>::new(2.0, 4.0, 1.0, 0.125);
+        let mut state = State::default();
+        type P = Overload<I, A, D>;
+
+        // double overcurrent trips faster
+        assert!(!P::apply(&param, &mut state, I::cast(8.0)));
+        assert_eq!(state.value(), A::cast(7.5));
+        assert!(P::apply(&param, &mut state, I::cast(8.0)));
+        assert!(state.is_tripped());
+        assert_eq!(state.value(), A::cast(12.0));
+
+        // long overload doesn't overflow the accumulator
+        for _ in 0..10000 {
+            assert!(P::apply(&param, &mut state, I::cast(8.0)));
+        }
+        assert_eq!(state.value(), A::cast(12.0));
+
+        // cool down from trip capacity
+        for _ in 0..23 {
+            P::apply(&param, &mut state, I::cast(0.0));
+        }
+        assert_eq!(state.value(), A::cast(0.5));
+        P::apply(&param, &mut state, I::cast(0.0));
+        assert_eq!(state.value(), A::cast(0.0));
+        state.release();
+        assert!(!P::apply(&param, &mut state, I::cast(1.0)));
+    }
+}
@@ -0,0 +1,233 @@
+/*!
+
+## Fault recovery with exponential backoff
+
+This module implements supervisory block which handles faults of power stage or actuator.
+
+When the fault is reported the output is disabled for hold-off interval
+and then enabling is retried. Each next retry doubles the hold-off interval
+(up to the maximum interval), so the recurrent faults don't stress the hardware.
+When the number of retries exceeds the maximum the block locks out
+until it will be reset explicitly.
+
+The counter of retries is cleared when no faults occurs during recovery interval after enabling.
+
+All intervals is measured in steps (ticks of control loop).
+
+*/
+
+use crate::{snapshot_enum, snapshot_fields, ResetState, Transducer};
+
+/**
+Fault recovery parameters
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// The initial hold-off interval
+    holdoff: u32,
+    /// The maximum hold-off interval
+    max_holdoff: u32,
+    /// The maximum number of retries
+    retries: u32,
+    /// The interval of fault-free operation to clear retries
+    recovery: u32,
+}
+
+impl Param {
+    /**
+    Init fault recovery parameters
+
+    - `holdoff`: The initial hold-off interval (steps)
+    - `max_holdoff`: The maximum hold-off interval (steps)
+    - `retries`: The maximum number of retries before lock out
+    - `recovery`: The fault-free interval to clear retries counter (steps)
+     */
+    pub const fn new(holdoff: u32, max_holdoff: u32, retries: u32, recovery: u32) -> Self {
+        Self {
+            holdoff,
+            max_holdoff,
+            retries,
+            recovery,
+        }
+    }
+}
+
+/// The status of supervised output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Status {
+    /// The output is enabled
+    #[default]
+    Enabled,
+    /// The output is disabled until hold-off interval expires
+    Holdoff,
+    /// The output is disabled until reset because of too many faults
+    Locked,
+}
+
+snapshot_enum!(Status {
+    Enabled,
+    Holdoff,
+    Locked
+});
+
+impl Status {
+    /// Check when output is enabled
+    pub fn is_enabled(&self) -> bool {
+        *self == Status::Enabled
+    }
+}
+
+/// Fault recovery state
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// Actual status
+    status: Status,
+    /// The number of retries
+    retries: u32,
+    /// The steps counter
+    timer: u32,
+}
+
+impl State {
+    /// Actual status
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// The number of retries done
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+}
+
+/// The preset of fault recovery is same as reset
+impl ResetState for State {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State {
+        status: Status,
+        retries: u32,
+        timer: u32,
+    }
+}
+
+/**
+Fault recovery supervisor
+
+The input is a fault flag. The output is a status.
+*/
+#[derive(Debug)]
+pub struct Retry;
+
+impl Transducer for Retry {
+    type Input = bool;
+    type Output = Status;
+    type Param = Param;
+    type State = State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, fault: Self::Input) -> Self::Output {
+        match state.status {
+            Status::Enabled => {
+                if fault {
+                    if state.retries >= param.retries {
+                        state.status = Status::Locked;
+                    } else {
+                        // hold-off * 2^retries
+                        let holdoff = param
+                            .holdoff
+                            .checked_shl(state.retries)
+                            .filter(|holdoff| *holdoff >> state.retries == param.holdoff)
+                            .unwrap_or(param.max_holdoff);
+
+                        state.retries += 1;
+                        state.timer = holdoff.min(param.max_holdoff);
+                        state.status = Status::Holdoff;
+                    }
+                } else if state.retries > 0 {
+                    state.timer += 1;
+                    if state.timer >= param.recovery {
+                        state.retries = 0;
+                        state.timer = 0;
+                    }
+                }
+            }
+            Status::Holdoff => {
+                state.timer = state.timer.saturating_sub(1);
+                if state.timer == 0 {
+                    state.status = Status::Enabled;
+                }
+            }
+            Status::Locked => {}
+        }
+
+        state.status
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(param: &Param, state: &mut State, fault: bool, steps: u32) -> Status {
+        let mut status = state.status();
+        for _ in 0..steps {
+            status = Retry::apply(param, state, fault);
+        }
+        status
+    }
+
+    #[test]
+    fn retry_backoff() {
+        let param = Param::new(2, 6, 4, 10);
+        let mut state = State::default();
+
+        assert_eq!(run(&param, &mut state, false, 5), Status::Enabled);
+
+        // first fault: 2 steps hold-off
+        assert_eq!(Retry::apply(&param, &mut state, true), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, false, 1), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, false, 1), Status::Enabled);
+
+        // second fault: 4 steps hold-off
+        assert_eq!(Retry::apply(&param, &mut state, true), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, true, 3), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, true, 1), Status::Enabled);
+
+        // third fault: 6 steps hold-off (limited)
+        assert_eq!(Retry::apply(&param, &mut state, true), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, false, 5), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, false, 1), Status::Enabled);
+        assert_eq!(state.retries(), 3);
+
+        // recovered
+        assert_eq!(run(&param, &mut state, false, 10), Status::Enabled);
+        assert_eq!(state.retries(), 0);
+    }
+
+    #[test]
+    fn retry_lockout() {
+        let param = Param::new(1, 100, 2, 10);
+        let mut state = State::default();
+
+        assert_eq!(Retry::apply(&param, &mut state, true), Status::Holdoff);
+        assert_eq!(Retry::apply(&param, &mut state, false), Status::Enabled);
+        assert_eq!(Retry::apply(&param, &mut state, true), Status::Holdoff);
+        assert_eq!(run(&param, &mut state, false, 2), Status::Enabled);
+        assert_eq!(Retry::apply(&param, &mut state, true), Status::Locked);
+        assert_eq!(run(&param, &mut state, false, 100), Status::Locked);
+        assert!(!state.status().is_enabled());
+
+        state.reset();
+        assert!(Retry::apply(&param, &mut state, false).is_enabled());
+    }
+}
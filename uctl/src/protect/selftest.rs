@@ -0,0 +1,366 @@
+/*!
+
+## Self-test
+
+This module implements the startup self-test which injects the known test signal
+into the input of chain (or to the actuator) and validates the measured response.
+
+The test consists of _N_ points. Each point has the level of test signal,
+the settling time and the bounds of expected response.
+The test signal of point is injected during the settling time,
+then the measured response is checked against the bounds:
+
+- when the response is out of bounds the test fails immediately with diagnostic
+  (the index of point, the measured response and the expected bounds)
+- otherwise the test goes to the next point and passes after the last one
+
+When the test is completed (passed or failed) the idle signal is injected.
+The test restarts on reset of state.
+
+*/
+
+use crate::{snapshot_fields, steps, Cast, ResetState, Snapshot, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+
+/**
+Test point
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Point<T> {
+    /// The level of test signal
+    input: T,
+    /// The settling time in steps
+    settle: u32,
+    /// The minimum expected response
+    low: T,
+    /// The maximum expected response
+    high: T,
+}
+
+/**
+The diagnostic of failed test
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Diagnostic<T> {
+    /// The index of failed point
+    pub point: usize,
+    /// The measured response
+    pub response: T,
+    /// The minimum expected response
+    pub low: T,
+    /// The maximum expected response
+    pub high: T,
+}
+
+snapshot_fields! {
+    Diagnostic<T> {
+        point: usize,
+        response: T,
+        low: T,
+        high: T,
+    }
+}
+
+/**
+The status of self-test
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Status<T> {
+    /// The test is in progress
+    #[default]
+    Running,
+    /// The test is passed
+    Passed,
+    /// The test is failed
+    Failed(Diagnostic<T>),
+}
+
+/// The diagnostic is stored for all statuses to keep the size constant
+impl<T> Snapshot for Status<T>
+where
+    T: Snapshot + Default + Copy,
+{
+    const SIZE: usize = u8::SIZE + Diagnostic::<T>::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let (index, diagnostic) = match self {
+            Status::Running => (0u8, Diagnostic::default()),
+            Status::Passed => (1, Diagnostic::default()),
+            Status::Failed(diagnostic) => (2, *diagnostic),
+        };
+        let buffer = index.save(buffer);
+        diagnostic.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let mut index = 0u8;
+        let mut diagnostic = Diagnostic::default();
+        let buffer = index.restore(buffer);
+        let buffer = diagnostic.restore(buffer);
+        match index {
+            0 => *self = Status::Running,
+            1 => *self = Status::Passed,
+            2 => *self = Status::Failed(diagnostic),
+            _ => (),
+        }
+        buffer
+    }
+}
+
+impl<T> Status<T> {
+    /// The test is completed (passed or failed)
+    pub fn is_done(&self) -> bool {
+        !matches!(self, Status::Running)
+    }
+}
+
+/**
+Self-test parameters
+
+- `T` - values type
+- `N` - the number of test points
+*/
+#[derive(Debug, Clone)]
+pub struct Param<T, N>
+where
+    N: ArrayLength<Point<T>>,
+{
+    /// The test points
+    points: GenericArray<Point<T>, N>,
+    /// The idle signal
+    idle: T,
+}
+
+impl<T, N> Param<T, N>
+where
+    N: ArrayLength<Point<T>>,
+{
+    /**
+    Init self-test parameters
+
+    - `idle`: The signal which is injected when the test is completed
+    - `points`: The test points as tuples of signal level, settling time (s),
+      minimum and maximum expected response (the length should be `N`)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<V>(idle: V, points: &[(V, V, V, V)], period: V) -> Self
+    where
+        V: Copy,
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(points.len() == N::USIZE);
+
+        let period = f64::cast(period);
+        let value = |value: V| T::cast(f64::cast(value));
+
+        Self {
+            points: points
+                .iter()
+                .map(|(input, settle, low, high)| {
+                    let settle = steps(f64::cast(*settle), period);
+                    assert!(settle > 0);
+                    Point {
+                        input: value(*input),
+                        settle,
+                        low: value(*low),
+                        high: value(*high),
+                    }
+                })
+                .collect(),
+            idle: value(idle),
+        }
+    }
+}
+
+/**
+Self-test state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The index of actual point
+    point: usize,
+    /// The number of steps while the signal of point is injected
+    elapsed: u32,
+    /// The status of test
+    status: Status<T>,
+}
+
+impl<T> State<T>
+where
+    T: Copy,
+{
+    /// The status of test
+    pub fn status(&self) -> Status<T> {
+        self.status
+    }
+}
+
+/// The test restarts on reset
+impl<T> ResetState for State<T> {
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.point = 0;
+        self.elapsed = 0;
+        self.status = Status::Running;
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        point: usize,
+        elapsed: u32,
+        status: Status<T>,
+    }
+}
+
+/**
+Self-test
+
+- `T` - values type
+- `N` - the number of test points
+
+The input is a measured response.
+The output is a tuple of test signal to inject and status of test.
+*/
+#[derive(Debug)]
+pub struct SelfTest<T, N>(PhantomData<(T, N)>);
+
+impl<T, N> Transducer for SelfTest<T, N>
+where
+    T: Copy + PartialOrd,
+    N: ArrayLength<Point<T>>,
+{
+    type Input = T;
+    type Output = (T, Status<T>);
+    type Param = Param<T, N>;
+    type State = State<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, response: Self::Input) -> Self::Output {
+        if state.status.is_done() || state.point >= N::USIZE {
+            return (param.idle, state.status);
+        }
+
+        let point = &param.points[state.point];
+
+        if state.elapsed >= point.settle {
+            if response < point.low || response > point.high {
+                state.status = Status::Failed(Diagnostic {
+                    point: state.point,
+                    response,
+                    low: point.low,
+                    high: point.high,
+                });
+                return (param.idle, state.status);
+            }
+
+            state.point += 1;
+            state.elapsed = 0;
+
+            if state.point >= N::USIZE {
+                state.status = Status::Passed;
+                return (param.idle, state.status);
+            }
+        }
+
+        state.elapsed += 1;
+        (param.points[state.point].input, state.status)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U2};
+    use ufix::bin::Fix;
+
+    /// Run the test with the plant which responds to the signal on the next step
+    fn run<F>(param: &Param<f32, U2>, state: &mut State<f32>, plant: F) -> [f32; 8]
+    where
+        F: Fn(f32) -> f32,
+    {
+        let mut signals = [0.0; 8];
+        let mut response = 0.0;
+        for signal in signals.iter_mut() {
+            let (output, _) = SelfTest::apply(param, state, response);
+            response = plant(output);
+            *signal = output;
+        }
+        signals
+    }
+
+    #[test]
+    fn selftest_passed() {
+        // 2 steps at 0.5 (expected ~1.0), then 3 steps at 1.0 (expected ~2.0)
+        let param = Param::<f32, U2>::new(0.0, &[(0.5, 2.0, 0.9, 1.1), (1.0, 3.0, 1.9, 2.1)], 1.0);
+        let mut state = State::default();
+
+        assert_eq!(state.status(), Status::Running);
+        let signals = run(&param, &mut state, |x| x * 2.0);
+        assert_eq!(signals, [0.5, 0.5, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(state.status(), Status::Passed);
+
+        // restart
+        state.reset();
+        assert_eq!(state.status(), Status::Running);
+        run(&param, &mut state, |x| x * 2.0);
+        assert_eq!(state.status(), Status::Passed);
+    }
+
+    #[test]
+    fn selftest_failed() {
+        let param = Param::<f32, U2>::new(0.0, &[(0.5, 2.0, 0.9, 1.1), (1.0, 3.0, 1.9, 2.1)], 1.0);
+        let mut state = State::default();
+
+        // the weak actuator
+        let signals = run(&param, &mut state, |x| x * 1.5);
+        assert_eq!(signals, [0.5, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(
+            state.status(),
+            Status::Failed(Diagnostic {
+                point: 0,
+                response: 0.75,
+                low: 0.9,
+                high: 1.1
+            })
+        );
+    }
+
+    #[test]
+    fn selftest_fix() {
+        type T = Fix<P32, N16>;
+        type S = SelfTest<T, U2>;
+        let param = Param::<T, U2>::new(0.0, &[(1.0, 0.1, 0.5, 1.5), (-1.0, 0.1, -1.5, -0.5)], 0.1);
+        let mut state = State::default();
+
+        let (signal, status) = S::apply(&param, &mut state, T::cast(0.0));
+        assert_eq!((signal, status), (T::cast(1.0), Status::Running));
+        let (signal, _) = S::apply(&param, &mut state, T::cast(1.0));
+        assert_eq!(signal, T::cast(-1.0));
+        let (signal, status) = S::apply(&param, &mut state, T::cast(-0.25));
+        assert_eq!(signal, T::cast(0.0));
+        assert_eq!(
+            status,
+            Status::Failed(Diagnostic {
+                point: 1,
+                response: T::cast(-0.25),
+                low: T::cast(-1.5),
+                high: T::cast(-0.5)
+            })
+        );
+    }
+}
@@ -0,0 +1,232 @@
+/*!
+
+## Thermal model
+
+This module implements first-order thermal model which estimates the temperature
+of junction or winding from the dissipated power.
+
+The model is a discrete RC network with thermal resistance _R_ (K/W) and thermal capacity _C_ (J/K):
+
+_Θ\[n\] = Θ\[n-1\] + α * (R * P\[n\] - Θ\[n-1\])_
+
+_T\[n\] = Ta\[n\] + Θ\[n\]_
+
+where _Θ_ is a temperature rise over the ambient temperature _Ta_, _P_ is a dissipated power,
+and the factor _α_ is evaluated from the time constant _τ = R * C_ like for PT1 element:
+
+_α = P / (τ + P)_
+
+where _P_ is a sampling period.
+
+The estimated temperature can be used by derating logic or in conjunction with overload protection.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Thermal model parameters
+
+- `R` - thermal resistance type
+- `A` - model factor type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<R, A> {
+    /// Thermal resistance
+    resistance: R,
+    /// Model factor
+    alpha: A,
+}
+
+impl<R, A> Param<R, A> {
+    /**
+    Init thermal model parameters using thermal resistance and capacity
+
+    - `resistance`: The thermal resistance (K/W)
+    - `capacity`: The thermal capacity (J/K)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<T>(resistance: T, capacity: T, period: T) -> Self
+    where
+        R: Cast<f64>,
+        A: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let resistance = f64::cast(resistance);
+        Self::from_time::<f64>(
+            resistance,
+            resistance * f64::cast(capacity),
+            f64::cast(period),
+        )
+    }
+
+    /**
+    Init thermal model parameters using thermal resistance and time constant
+
+    - `resistance`: The thermal resistance (K/W)
+    - `time`: The thermal time constant (s)
+    - `period`: The sampling period (s)
+     */
+    pub fn from_time<T>(resistance: T, time: T, period: T) -> Self
+    where
+        R: Cast<f64>,
+        A: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let period = f64::cast(period);
+        Self {
+            resistance: R::cast(f64::cast(resistance)),
+            alpha: A::cast(period / (f64::cast(time) + period)),
+        }
+    }
+}
+
+/**
+Thermal model state
+
+- `T` - temperature type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// Temperature rise over ambient
+    rise: T,
+}
+
+impl<T> State<T> {
+    /**
+    Initialize thermal model state
+
+    - `rise`: The initial temperature rise over ambient
+     */
+    pub fn new(rise: T) -> Self {
+        Self { rise }
+    }
+
+    /// The estimated temperature rise over ambient
+    pub fn rise(&self) -> T
+    where
+        T: Copy,
+    {
+        self.rise
+    }
+}
+
+/// The preset value is a temperature rise
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        self.rise = T::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.rise = value;
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        rise: T,
+    }
+}
+
+/**
+Thermal model
+
+- `P` - power type
+- `T` - temperature type
+- `R` - thermal resistance type
+- `A` - model factor type
+
+The input is a tuple of dissipated power and ambient temperature.
+The output is an estimated temperature.
+*/
+#[derive(Debug)]
+pub struct Thermal<P, T, R, A>(PhantomData<(P, T, R, A)>);
+
+impl<P, T, R, A> Transducer for Thermal<P, T, R, A>
+where
+    R: Copy + Mul<P>,
+    A: Copy + Mul<Diff<T, T>>,
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<R, P>> + Cast<Sum<T, T>> + Cast<Prod<A, Diff<T, T>>>,
+{
+    type Input = (P, T);
+    type Output = T;
+    type Param = Param<R, A>;
+    type State = State<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (power, ambient): Self::Input,
+    ) -> Self::Output {
+        // steady state rise
+        let steady = T::cast(param.resistance * power);
+
+        state.rise = T::cast(state.rise + T::cast(param.alpha * (steady - state.rise)));
+
+        T::cast(ambient + state.rise)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn thermal_f32() {
+        // 2 K/W, 1.5 J/K, 1 s period
+        let param = Param::<f32, f32>::new(2.0, 1.5, 1.0);
+        let mut state = State::default();
+        type M = Thermal<f32, f32, f32, f32>;
+
+        assert_eq!(M::apply(&param, &mut state, (10.0, 25.0)), 30.0);
+        assert_eq!(M::apply(&param, &mut state, (10.0, 25.0)), 33.75);
+        assert_eq!(M::apply(&param, &mut state, (10.0, 25.0)), 36.5625);
+        assert_eq!(state.rise(), 11.5625);
+
+        for _ in 0..100 {
+            M::apply(&param, &mut state, (10.0, 25.0));
+        }
+        assert_eq!(M::apply(&param, &mut state, (10.0, 20.0)), 39.999996);
+
+        // cooling
+        assert_eq!(M::apply(&param, &mut state, (0.0, 20.0)), 34.999996);
+    }
+
+    #[test]
+    fn thermal_fix() {
+        type P = Fix<P16, N8>;
+        type T = Fix<P32, N16>;
+        type R = Fix<P16, N8>;
+        type A = Fix<P16, N16>;
+
+        // 2 K/W, 3 s time constant, 1 s period
+        let param = Param::<R, A>::from_time(2.0, 3.0, 1.0);
+        let mut state = State::new(T::cast(0.0));
+        type M = Thermal<P, T, R, A>;
+
+        assert_eq!(
+            M::apply(&param, &mut state, (P::cast(10.0), T::cast(25.0))),
+            T::cast(30.0)
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (P::cast(10.0), T::cast(25.0))),
+            T::cast(33.75)
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (P::cast(10.0), T::cast(25.0))),
+            T::cast(36.5625)
+        );
+    }
+}
@@ -1 +1,13 @@
+pub mod adrc;
+pub mod dcdc;
+pub mod dob;
+pub mod eso;
+pub mod extremum;
+pub mod feedforward;
+pub mod fuzzy;
+pub mod lqr;
 pub mod pid;
+pub mod prefilter;
+pub mod relay;
+pub mod select;
+pub mod zones;
@@ -0,0 +1,416 @@
+/*!
+
+## Active disturbance rejection control
+
+This module implements linear **active disturbance rejection control** (ADRC)
+for first and second order plants.
+
+The regulator uses [extended state observer](crate::eso) to estimate the total disturbance
+of plant and cancels it, so the plant is reduced to the chain of integrators
+which is controlled by simple P or PD law:
+
+- first order: _u = (kp (r - z1) - z2) / b0_, where _kp = ωc_
+- second order: _u = (kp (r - z1) - kd z2 - z3) / b0_, where _kp = ωc², kd = 2 ωc_
+
+So the regulator is tuned by the closed loop bandwidth _ωc_ and the rough estimation
+of plant input gain _b0_ only without detailed modeling of plant.
+The observer bandwidth _ωo_ usually is selected to be 3..10 times higher than _ωc_.
+
+The output is not limited, so the output limits should be applied to the plant model
+(i.e. the observer gets the control which is applied in reality).
+
+See also [Active disturbance rejection control](https://en.wikipedia.org/wiki/Active_disturbance_rejection_control).
+
+*/
+
+use crate::{eso, snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/// The default ratio of observer bandwidth to closed loop bandwidth
+pub const OBSERVER_RATIO: f64 = 4.0;
+
+/**
+ADRC parameters for first order plant
+
+- `K` - coefficients type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param1<K> {
+    /// The observer parameters
+    observer: eso::Param1<K>,
+    /// The proportional gain _kp_
+    kp: K,
+    /// The inverted input gain _1 / b0_
+    inv_gain: K,
+}
+
+impl<K> Param1<K> {
+    /**
+    Init ADRC parameters for first order plant
+
+    - `bandwidth`: The closed loop bandwidth _ωc_ (rad/s)
+    - `gain`: The input gain of plant _b0_
+    - `period`: The sampling period
+
+    The observer bandwidth is [`OBSERVER_RATIO`] times higher than closed loop bandwidth.
+     */
+    pub fn new<T>(bandwidth: T, gain: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let bandwidth = f64::cast(bandwidth);
+        Self::with_observer::<f64>(
+            bandwidth,
+            bandwidth * OBSERVER_RATIO,
+            f64::cast(gain),
+            f64::cast(period),
+        )
+    }
+
+    /**
+    Init ADRC parameters for first order plant with custom observer bandwidth
+
+    - `bandwidth`: The closed loop bandwidth _ωc_ (rad/s)
+    - `observer`: The observer bandwidth _ωo_ (rad/s)
+    - `gain`: The input gain of plant _b0_
+    - `period`: The sampling period
+     */
+    pub fn with_observer<T>(bandwidth: T, observer: T, gain: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let gain = f64::cast(gain);
+
+        Self {
+            observer: eso::Param1::new::<f64>(f64::cast(observer), gain, f64::cast(period)),
+            kp: K::cast(f64::cast(bandwidth)),
+            inv_gain: K::cast(1.0 / gain),
+        }
+    }
+}
+
+/**
+ADRC state for first order plant
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State1<T> {
+    /// The observer state
+    pub observer: eso::State1<T>,
+    /// The last control value
+    control: T,
+}
+
+impl<T> State1<T> {
+    /// Initialize ADRC state by zeros
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+
+    /// The last control value
+    pub fn control(&self) -> T
+    where
+        T: Copy,
+    {
+        self.control
+    }
+}
+
+/// The preset of ADRC sets the estimated output
+impl<T> ResetState for State1<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.observer.preset(value);
+        self.control = T::default();
+    }
+}
+
+snapshot_fields! {
+    State1<T> {
+        observer: eso::State1<T>,
+        control: T,
+    }
+}
+
+/**
+ADRC regulator for first order plant
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct Adrc1<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Adrc1<T, K>
+where
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<K, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = Param1<K>;
+    type State = State1<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let (z1, z2) = eso::Observer1::<T, K>::apply(
+            &param.observer,
+            &mut state.observer,
+            (output, state.control),
+        );
+
+        // u = (kp (r - z1) - z2) / b0
+        let control = T::cast(T::cast(param.kp * T::cast(reference - z1)) - z2);
+        state.control = T::cast(param.inv_gain * control);
+
+        state.control
+    }
+}
+
+/**
+ADRC parameters for second order plant
+
+- `K` - coefficients type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param2<K> {
+    /// The observer parameters
+    observer: eso::Param2<K>,
+    /// The proportional gain _kp_
+    kp: K,
+    /// The derivative gain _kd_
+    kd: K,
+    /// The inverted input gain _1 / b0_
+    inv_gain: K,
+}
+
+impl<K> Param2<K> {
+    /**
+    Init ADRC parameters for second order plant
+
+    - `bandwidth`: The closed loop bandwidth _ωc_ (rad/s)
+    - `gain`: The input gain of plant _b0_
+    - `period`: The sampling period
+
+    The observer bandwidth is [`OBSERVER_RATIO`] times higher than closed loop bandwidth.
+     */
+    pub fn new<T>(bandwidth: T, gain: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let bandwidth = f64::cast(bandwidth);
+        Self::with_observer::<f64>(
+            bandwidth,
+            bandwidth * OBSERVER_RATIO,
+            f64::cast(gain),
+            f64::cast(period),
+        )
+    }
+
+    /**
+    Init ADRC parameters for second order plant with custom observer bandwidth
+
+    - `bandwidth`: The closed loop bandwidth _ωc_ (rad/s)
+    - `observer`: The observer bandwidth _ωo_ (rad/s)
+    - `gain`: The input gain of plant _b0_
+    - `period`: The sampling period
+     */
+    pub fn with_observer<T>(bandwidth: T, observer: T, gain: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let bandwidth = f64::cast(bandwidth);
+        let gain = f64::cast(gain);
+
+        Self {
+            observer: eso::Param2::new::<f64>(f64::cast(observer), gain, f64::cast(period)),
+            kp: K::cast(bandwidth * bandwidth),
+            kd: K::cast(2.0 * bandwidth),
+            inv_gain: K::cast(1.0 / gain),
+        }
+    }
+}
+
+/**
+ADRC state for second order plant
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State2<T> {
+    /// The observer state
+    pub observer: eso::State2<T>,
+    /// The last control value
+    control: T,
+}
+
+impl<T> State2<T> {
+    /// Initialize ADRC state by zeros
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+
+    /// The last control value
+    pub fn control(&self) -> T
+    where
+        T: Copy,
+    {
+        self.control
+    }
+}
+
+/// The preset of ADRC sets the estimated output
+impl<T> ResetState for State2<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.observer.preset(value);
+        self.control = T::default();
+    }
+}
+
+snapshot_fields! {
+    State2<T> {
+        observer: eso::State2<T>,
+        control: T,
+    }
+}
+
+/**
+ADRC regulator for second order plant
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct Adrc2<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Adrc2<T, K>
+where
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<K, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = Param2<K>;
+    type State = State2<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let (z1, z2, z3) = eso::Observer2::<T, K>::apply(
+            &param.observer,
+            &mut state.observer,
+            (output, state.control),
+        );
+
+        // u = (kp (r - z1) - kd z2 - z3) / b0
+        let control = T::cast(
+            T::cast(T::cast(param.kp * T::cast(reference - z1)) - T::cast(param.kd * z2)) - z3,
+        );
+        state.control = T::cast(param.inv_gain * control);
+
+        state.control
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn adrc1_f32() {
+        let param = Param1::<f32>::new(5.0, 2.0, 0.01);
+        let mut state = State1::new();
+        type R = Adrc1<f32, f32>;
+
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.0)), 2.5);
+
+        // y' = -y + 2 u + 0.5
+        state.reset();
+        let mut output = 0.0;
+        for _ in 0..500 {
+            let control = R::apply(&param, &mut state, (1.0, output));
+            output += 0.01 * (-output + 2.0 * control + 0.5);
+        }
+        assert!((output - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn adrc2_f32() {
+        let param = Param2::<f32>::new(5.0, 2.0, 0.01);
+        let mut state = State2::new();
+        type R = Adrc2<f32, f32>;
+
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.0)), 12.5);
+
+        // y'' = -y' + 2 u - 1
+        state.reset();
+        let (mut output, mut derivative) = (0.0, 0.0);
+        for _ in 0..500 {
+            let control = R::apply(&param, &mut state, (1.0, output));
+            output += 0.01 * derivative;
+            derivative += 0.01 * (-derivative + 2.0 * control - 1.0);
+        }
+        assert!((output - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn adrc1_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+
+        let param = Param1::<K>::new(5.0, 2.0, 0.0625);
+        let mut state = State1::new();
+        type R = Adrc1<T, K>;
+
+        assert_eq!(
+            R::apply(&param, &mut state, (T::cast(1.0), T::cast(0.0))),
+            T::cast(2.5)
+        );
+        assert_eq!(state.control(), T::cast(2.5));
+    }
+}
@@ -0,0 +1,446 @@
+/*!
+
+## DC/DC converter controller
+
+This module implements voltage mode controller for buck and boost converters
+which is composed from the following blocks:
+
+- **soft-start** ramp which limits the slew rate of voltage reference to prevent the inrush current,
+- **feed-forward** of duty cycle from the input voltage,
+- **compensator** of voltage loop (usually [type III](crate::tustin::type3)) which corrects the duty cycle,
+- **duty clamp** with conditional integration to prevent the windup of compensator.
+
+The feed-forward is evaluated from the ideal steady-state conversion ratio:
+
+- buck: _D = Vref / Vin_
+- boost: _D = 1 - Vin / Vref_
+
+So the compensator handles the losses and the load transients only,
+which keeps the response consistent over the input voltage range.
+
+The compensator is evaluated by [transfer function](crate::tf) of third order.
+When the duty cycle saturates and the error drives it further into saturation,
+the state of compensator doesn't updated and the soft-start reference follows the output voltage,
+so after saturation the output recovers with limited slew rate again.
+
+*/
+
+use crate::{
+    snapshot_fields,
+    tf::{Param as TfParam, State as TfState, TransferFunction},
+    tustin::Third,
+    Cast, ResetState, Transducer,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Sub},
+};
+use typenum::{Diff, Prod, Quot, Sum, U3};
+
+/// Converter topology
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Step-down converter
+    Buck,
+    /// Step-up converter
+    Boost,
+}
+
+/**
+DC/DC controller parameters
+
+- `V` - voltage type
+- `D` - duty cycle type
+- `B` - compensator coefficients type
+*/
+#[derive(Debug, Clone)]
+pub struct Param<V, D, B> {
+    /// The converter topology
+    topology: Topology,
+    /// The compensator parameters
+    compensator: TfParam<B, U3>,
+    /// The maximum change of reference per step
+    slew: V,
+    /// The minimum duty cycle
+    min: D,
+    /// The maximum duty cycle
+    max: D,
+}
+
+impl<V, D, B> Param<V, D, B> {
+    /**
+    Init DC/DC controller parameters
+
+    - `topology`: The converter topology
+    - `compensator`: The discrete compensator coefficients
+    - `slew`: The maximum slew rate of voltage reference (V/s)
+    - `min`: The minimum duty cycle
+    - `max`: The maximum duty cycle
+    - `period`: The sampling period (s)
+     */
+    pub fn new<T>(
+        topology: Topology,
+        compensator: Third,
+        slew: T,
+        min: T,
+        max: T,
+        period: T,
+    ) -> Self
+    where
+        V: Cast<f64>,
+        D: Cast<f64>,
+        B: Cast<f64>,
+        f64: Cast<T>,
+    {
+        Self {
+            topology,
+            compensator: TfParam::new::<f64>(&compensator.0, &compensator.1),
+            slew: V::cast(f64::cast(slew) * f64::cast(period)),
+            min: D::cast(f64::cast(min)),
+            max: D::cast(f64::cast(max)),
+        }
+    }
+
+    /// The converter topology
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+}
+
+/**
+DC/DC controller state
+
+- `V` - voltage type
+- `S` - compensator state type
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<V, S> {
+    /// The actual (soft-started) voltage reference
+    reference: V,
+    /// The compensator state
+    compensator: TfState<S, U3>,
+}
+
+impl<V, S> State<V, S> {
+    /// The actual voltage reference
+    pub fn reference(&self) -> V
+    where
+        V: Copy,
+    {
+        self.reference
+    }
+}
+
+/// The preset value is an initial voltage reference (for starting into pre-biased output)
+impl<V, S> ResetState for State<V, S>
+where
+    V: Default,
+    S: Default,
+{
+    type Value = V;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.reference = value;
+        self.compensator.reset();
+    }
+}
+
+snapshot_fields! {
+    State<V, S> {
+        reference: V,
+        compensator: TfState<S, U3>,
+    }
+}
+
+/**
+DC/DC converter controller
+
+- `V` - voltage type
+- `D` - duty cycle type
+- `B` - compensator coefficients type
+- `S` - compensator state type
+
+The input is a target output voltage, a measured output voltage and a measured input voltage.
+The output is a duty cycle.
+*/
+#[derive(Debug)]
+pub struct Controller<V, D, B, S>(PhantomData<(V, D, B, S)>);
+
+impl<V, D, B, S> Transducer for Controller<V, D, B, S>
+where
+    V: Copy + PartialOrd + Cast<f64> + Add<V> + Sub<V> + Cast<Sum<V, V>> + Cast<Diff<V, V>>,
+    D: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<D>
+        + Sub<D>
+        + Cast<Sum<D, D>>
+        + Cast<Diff<D, D>>
+        + Cast<S>
+        + Mul<V>
+        + Cast<Quot<Prod<D, V>, V>>,
+    Prod<D, V>: Cast<V> + Div<V>,
+    B: Copy + Mul<V> + Mul<D>,
+    S: Copy
+        + Default
+        + Add<S>
+        + Sub<S>
+        + Cast<Prod<B, V>>
+        + Cast<Prod<B, D>>
+        + Cast<Sum<S, S>>
+        + Cast<Diff<S, S>>,
+{
+    type Input = (V, V, V);
+    type Output = D;
+    type Param = Param<V, D, B>;
+    type State = State<V, S>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (target, output, input): Self::Input,
+    ) -> Self::Output {
+        let zero = V::cast(0.0);
+
+        // soft-start
+        let reference = state.reference;
+        state.reference = if target > reference {
+            let next = V::cast(reference + param.slew);
+            if next < target {
+                next
+            } else {
+                target
+            }
+        } else {
+            let next = V::cast(reference - param.slew);
+            if next > target {
+                next
+            } else {
+                target
+            }
+        };
+        let reference = state.reference;
+
+        // feed-forward
+        let forward = match param.topology {
+            Topology::Buck if input > zero => D::cast(Prod::<D, V>::cast(reference) / input),
+            Topology::Buck => param.max,
+            Topology::Boost if reference > input => {
+                D::cast(D::cast(1.0) - D::cast(Prod::<D, V>::cast(input) / reference))
+            }
+            Topology::Boost => D::cast(0.0),
+        };
+
+        let error = V::cast(reference - output);
+        let last = state.compensator.clone();
+        let correction = TransferFunction::<V, D, B, S, U3>::apply(
+            &param.compensator,
+            &mut state.compensator,
+            error,
+        );
+        let duty = D::cast(forward + correction);
+
+        if duty > param.max {
+            if error > zero {
+                state.compensator = last;
+                state.reference = output;
+            }
+            param.max
+        } else if duty < param.min {
+            if error < zero {
+                state.compensator = last;
+                state.reference = output;
+            }
+            param.min
+        } else {
+            duty
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tustin::type3;
+    use core::f64::consts::PI;
+    use typenum::{N16, N24, N28, P32, P48};
+    use ufix::bin::Fix;
+
+    /// Averaged model of converter with LC output filter and resistive load
+    struct Plant {
+        topology: Topology,
+        inductance: f64,
+        resistance: f64,
+        capacitance: f64,
+        load: f64,
+        current: f64,
+        voltage: f64,
+    }
+
+    impl Plant {
+        fn new(topology: Topology) -> Self {
+            Self {
+                topology,
+                inductance: 22e-6,
+                resistance: 0.1,
+                capacitance: 100e-6,
+                load: 2.5,
+                current: 0.0,
+                voltage: 0.0,
+            }
+        }
+
+        fn step(&mut self, duty: f64, input: f64, period: f64) {
+            let steps = 20;
+            let dt = period / steps as f64;
+            for _ in 0..steps {
+                let (drive, share) = match self.topology {
+                    Topology::Buck => (duty * input - self.voltage, 1.0),
+                    Topology::Boost => (input - (1.0 - duty) * self.voltage, 1.0 - duty),
+                };
+                let drive = drive - self.resistance * self.current;
+                self.current = (self.current + dt * drive / self.inductance).max(0.0);
+                self.voltage +=
+                    dt * (share * self.current - self.voltage / self.load) / self.capacitance;
+            }
+        }
+    }
+
+    const PERIOD: f64 = 1e-5;
+
+    fn compensator(input: f64) -> Third {
+        // the zeros below LC resonance, the poles well above crossover (2 kHz)
+        let resonance = 1.0 / (22e-6f64 * 100e-6).sqrt();
+        let crossover = 2.0 * PI * 2e3;
+        type3(
+            crossover / input,
+            [0.5 * resonance, 0.5 * resonance],
+            [2.0 * PI * 40e3, 2.0 * PI * 40e3],
+            PERIOD,
+        )
+    }
+
+    fn run<V, D, B, S>(
+        param: &Param<V, D, B>,
+        state: &mut State<V, S>,
+        plant: &mut Plant,
+        (target, input): (f64, f64),
+        steps: usize,
+    ) -> f64
+    where
+        Controller<V, D, B, S>:
+            Transducer<Input = (V, V, V), Output = D, Param = Param<V, D, B>, State = State<V, S>>,
+        V: Cast<f64>,
+        f64: Cast<D>,
+    {
+        let mut max = 0.0f64;
+        for _ in 0..steps {
+            let duty = Controller::<V, D, B, S>::apply(
+                param,
+                state,
+                (V::cast(target), V::cast(plant.voltage), V::cast(input)),
+            );
+            plant.step(f64::cast(duty), input, PERIOD);
+            max = max.max(plant.voltage);
+        }
+        max
+    }
+
+    #[test]
+    fn dcdc_buck_f64() {
+        // 12 V to 5 V, 5 V/ms soft-start
+        let param =
+            Param::<f64, f64, f64>::new(Topology::Buck, compensator(12.0), 5e3, 0.0, 0.95, PERIOD);
+        let mut state = State::<f64, f64>::default();
+        let mut plant = Plant::new(Topology::Buck);
+
+        // soft-start
+        run(&param, &mut state, &mut plant, (5.0, 12.0), 50);
+        assert!((state.reference() - 2.5).abs() < 1e-9);
+        assert!(plant.voltage < 2.6);
+
+        let peak = run(&param, &mut state, &mut plant, (5.0, 12.0), 950);
+        assert!(peak < 5.15);
+        assert!((plant.voltage - 5.0).abs() < 0.02);
+
+        // input voltage step
+        run(&param, &mut state, &mut plant, (5.0, 9.0), 300);
+        assert!((plant.voltage - 5.0).abs() < 0.02);
+
+        // load step
+        plant.load = 1.0;
+        run(&param, &mut state, &mut plant, (5.0, 9.0), 500);
+        assert!((plant.voltage - 5.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn dcdc_buck_saturation() {
+        let param =
+            Param::<f64, f64, f64>::new(Topology::Buck, compensator(12.0), 5e3, 0.0, 0.95, PERIOD);
+        let limited =
+            Param::<f64, f64, f64>::new(Topology::Buck, compensator(12.0), 5e3, 0.0, 0.3, PERIOD);
+        let mut state = State::<f64, f64>::default();
+        let mut plant = Plant::new(Topology::Buck);
+
+        run(&param, &mut state, &mut plant, (5.0, 12.0), 1000);
+
+        // the duty cycle is not enough
+        run(&limited, &mut state, &mut plant, (5.0, 12.0), 2000);
+        assert!(plant.voltage < 3.6);
+
+        // the recovery without overshoot due to windup
+        let peak = run(&param, &mut state, &mut plant, (5.0, 12.0), 1000);
+        assert!(peak < 5.25);
+        assert!((plant.voltage - 5.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn dcdc_boost_f64() {
+        // 5 V to 12 V, the right half-plane zero requires lower crossover
+        let resonance = 1.0 / (22e-6f64 * 100e-6).sqrt() * 5.0 / 12.0;
+        let param = Param::<f64, f64, f64>::new(
+            Topology::Boost,
+            type3(
+                2.0 * PI * 400.0 / 12.0,
+                [0.5 * resonance, 0.5 * resonance],
+                [2.0 * PI * 5e3, 2.0 * PI * 40e3],
+                PERIOD,
+            ),
+            2e3,
+            0.0,
+            0.8,
+            PERIOD,
+        );
+        let mut state = State::<f64, f64>::default();
+        let mut plant = Plant::new(Topology::Boost);
+        plant.load = 10.0;
+
+        // pre-biased start from input voltage
+        state.preset(5.0);
+        plant.voltage = 5.0;
+
+        let peak = run(&param, &mut state, &mut plant, (12.0, 5.0), 3000);
+        assert!(peak < 12.5);
+        assert!((plant.voltage - 12.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn dcdc_buck_fix() {
+        type V = Fix<P32, N16>;
+        type D = Fix<P32, N24>;
+        type B = Fix<P32, N28>;
+        type S = Fix<P48, N28>;
+
+        let param =
+            Param::<V, D, B>::new(Topology::Buck, compensator(12.0), 5e3, 0.0, 0.95, PERIOD);
+        let mut state = State::<V, S>::default();
+        let mut plant = Plant::new(Topology::Buck);
+
+        run(&param, &mut state, &mut plant, (5.0, 12.0), 1000);
+        assert!((plant.voltage - 5.0).abs() < 0.02);
+    }
+}
@@ -0,0 +1,295 @@
+/*!
+
+## Disturbance observer
+
+This module implements **disturbance observer** (DOB) which wraps existing regulator
+to estimate and cancel the disturbances applied to the plant input.
+
+The disturbance is estimated as the difference between the control which is
+required to get the measured output using nominal plant model and the actual control:
+
+_d = Q(Pn^-1(y)) - Q(u\[-1\])_
+
+_u = R(r) - d_
+
+where _R_ is a regulator, _Pn^-1_ is an inverse of nominal plant model
+and _Q_ is a low-pass filter with unity gain.
+
+The inverse of plant model usually is not realizable itself, so the observer uses
+the single transducer chain for _Q Pn^-1_ which should be realizable
+(the relative order of _Q_ should be not less than the relative order of plant).
+Both filter chains can be built from the filter primitives of this crate
+(for example [transfer function](crate::tf) or [EMA](crate::ema)).
+
+The bandwidth of _Q_ filter defines the bandwidth of disturbance rejection.
+The control of previous step is used in estimation, so the measurement delay of plant
+should be included into the nominal model.
+
+See also [Disturbance observer](https://en.wikipedia.org/wiki/Disturbance_observer).
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{marker::PhantomData, ops::Sub};
+use typenum::Diff;
+
+/**
+Disturbance observer parameters
+
+- `R` - regulator parameters type
+- `N` - _Q Pn^-1_ chain parameters type
+- `Q` - _Q_ chain parameters type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<R, N, Q> {
+    /// Regulator parameters
+    pub regulator: R,
+    /// The parameters of filtered plant model inverse
+    pub inverse: N,
+    /// The parameters of _Q_ filter
+    pub filter: Q,
+}
+
+/**
+Disturbance observer state
+
+- `R` - regulator state type
+- `N` - _Q Pn^-1_ chain state type
+- `Q` - _Q_ chain state type
+- `U` - control value type
+- `D` - disturbance value type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<R, N, Q, U, D> {
+    /// Regulator state
+    pub regulator: R,
+    /// The state of filtered plant model inverse
+    pub inverse: N,
+    /// The state of _Q_ filter
+    pub filter: Q,
+    /// The last control value
+    control: U,
+    /// The estimated disturbance
+    disturbance: D,
+}
+
+impl<R, N, Q, U, D> State<R, N, Q, U, D> {
+    /// Initialize disturbance observer state
+    pub fn new(regulator: R, inverse: N, filter: Q) -> Self
+    where
+        U: Default,
+        D: Default,
+    {
+        Self {
+            regulator,
+            inverse,
+            filter,
+            control: U::default(),
+            disturbance: D::default(),
+        }
+    }
+
+    /// The last control value
+    pub fn control(&self) -> U
+    where
+        U: Copy,
+    {
+        self.control
+    }
+
+    /// The estimated disturbance
+    pub fn disturbance(&self) -> D
+    where
+        D: Copy,
+    {
+        self.disturbance
+    }
+}
+
+/// The preset of disturbance observer is same as reset
+impl<R, N, Q, U, D> ResetState for State<R, N, Q, U, D>
+where
+    R: ResetState,
+    N: ResetState,
+    Q: ResetState,
+    U: Default,
+    D: Default,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.regulator.reset();
+        self.inverse.reset();
+        self.filter.reset();
+        self.control = U::default();
+        self.disturbance = D::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<R, N, Q, U, D> {
+        regulator: R,
+        inverse: N,
+        filter: Q,
+        control: U,
+        disturbance: D,
+    }
+}
+
+/**
+Disturbance observer
+
+- `R` - regulator
+- `N` - filtered inverse of nominal plant model _Q Pn^-1_
+- `Q` - _Q_ filter
+
+The input is a tuple of regulator input and measured plant output.
+The output is a compensated control value.
+*/
+#[derive(Debug)]
+pub struct Observer<R, N, Q>(PhantomData<(R, N, Q)>);
+
+impl<R, N, Q> Transducer for Observer<R, N, Q>
+where
+    R: Transducer,
+    N: Transducer,
+    Q: Transducer<Input = R::Output, Output = N::Output>,
+    R::Output: Copy + Sub<N::Output> + Cast<Diff<R::Output, N::Output>>,
+    N::Output: Copy + Sub<N::Output> + Cast<Diff<N::Output, N::Output>>,
+{
+    type Input = (R::Input, N::Input);
+    type Output = R::Output;
+    type Param = Param<R::Param, N::Param, Q::Param>;
+    type State = State<R::State, N::State, Q::State, R::Output, N::Output>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (value, measure): Self::Input,
+    ) -> Self::Output {
+        // d = Q Pn^-1 y - Q u[-1]
+        let required = N::apply(&param.inverse, &mut state.inverse, measure);
+        let actual = Q::apply(&param.filter, &mut state.filter, state.control);
+        state.disturbance = N::Output::cast(required - actual);
+
+        // u = R r - d
+        let control = R::apply(&param.regulator, &mut state.regulator, value);
+        state.control = R::Output::cast(control - state.disturbance);
+
+        state.control
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, FnTransducer};
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    type Z = FnTransducer<f32, f32>;
+    type E = ema::Filter<f32, f32, f32>;
+
+    fn same(value: f32) -> f32 {
+        value
+    }
+
+    fn half(value: f32) -> f32 {
+        value * 0.5
+    }
+
+    /// The plant with static gain 2 and input disturbance
+    fn plant(control: f32, disturbance: f32) -> f32 {
+        2.0 * (control + disturbance)
+    }
+
+    #[test]
+    fn dob_static() {
+        type O = Observer<Z, Z, Z>;
+
+        let param = Param {
+            regulator: same as fn(f32) -> f32,
+            inverse: half as fn(f32) -> f32,
+            filter: same as fn(f32) -> f32,
+        };
+        let mut state = State::new((), (), ());
+
+        let mut output = 0.0;
+        let control = O::apply(&param, &mut state, (1.0, output));
+        assert_eq!(control, 1.0);
+
+        output = plant(control, 0.5);
+        let control = O::apply(&param, &mut state, (1.0, output));
+        assert_eq!(control, 0.5);
+        assert_eq!(state.disturbance(), 0.5);
+
+        output = plant(control, 0.5);
+        assert_eq!(output, 2.0);
+    }
+
+    #[test]
+    fn dob_filtered() {
+        type O = Observer<Z, (Z, E), E>;
+
+        let param = Param {
+            regulator: same as fn(f32) -> f32,
+            inverse: (half as fn(f32) -> f32, ema::Param::from_alpha(0.5)),
+            filter: ema::Param::from_alpha(0.5),
+        };
+        let mut state = State::new((), ((), ema::State::new(0.0)), ema::State::new(0.0));
+
+        let mut output = 0.0;
+        let mut control = 0.0;
+        for expected in &[1.0, 0.75, 0.625] {
+            control = O::apply(&param, &mut state, (1.0, output));
+            assert_eq!(control, *expected);
+            output = plant(control, 0.5);
+        }
+
+        for _ in 0..40 {
+            control = O::apply(&param, &mut state, (1.0, output));
+            output = plant(control, 0.5);
+        }
+        assert_eq!(control, 0.5);
+        assert_eq!(output, 2.0);
+
+        state.reset();
+        assert_eq!(state.control(), 0.0);
+    }
+
+    #[test]
+    fn dob_fix() {
+        type T = Fix<P32, N16>;
+        type Z = FnTransducer<T, T>;
+        type O = Observer<Z, Z, Z>;
+
+        fn same(value: T) -> T {
+            value
+        }
+
+        fn half(value: T) -> T {
+            T::cast(value * T::cast(0.5))
+        }
+
+        let param = Param {
+            regulator: same as fn(T) -> T,
+            inverse: half as fn(T) -> T,
+            filter: same as fn(T) -> T,
+        };
+        let mut state = State::new((), (), ());
+
+        assert_eq!(
+            O::apply(&param, &mut state, (T::cast(1.0), T::cast(0.0))),
+            T::cast(1.0)
+        );
+        assert_eq!(
+            O::apply(&param, &mut state, (T::cast(1.0), T::cast(3.0))),
+            T::cast(0.5)
+        );
+        assert_eq!(state.disturbance(), T::cast(0.5));
+    }
+}
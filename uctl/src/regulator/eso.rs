@@ -0,0 +1,407 @@
+/*!
+
+## Extended state observer
+
+This module implements linear **extended state observer** (ESO) for first and second order plants.
+
+The observer treats everything except the known input gain _b0_ as _total disturbance_
+_f_ (including unknown dynamics and external disturbances) and estimates it as extra state.
+
+The first order plant _y' = f + b0 u_ is observed by second order ESO:
+
+_e = y - z1_
+
+_z1 += P (z2 + b0 u + l1 e)_
+
+_z2 += P l2 e_
+
+The second order plant _y'' = f + b0 u_ is observed by third order ESO:
+
+_z1 += P (z2 + l1 e)_
+
+_z2 += P (z3 + b0 u + l2 e)_
+
+_z3 += P l3 e_
+
+The observer gains is tuned by single bandwidth _ωo_ by placing all poles to _-ωo_:
+
+- second order: _l1 = 2 ωo, l2 = ωo²_
+- third order: _l1 = 3 ωo, l2 = 3 ωo², l3 = ωo³_
+
+The gains premultiplied by sampling period _P_ is stored in parameters.
+
+See also [Active disturbance rejection control](https://en.wikipedia.org/wiki/Active_disturbance_rejection_control).
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Observer parameters for first order plant
+
+- `K` - coefficients type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param1<K> {
+    /// The sampling period _P_
+    period: K,
+    /// The input gain _b0 P_
+    gain: K,
+    /// The observer gains _l1 P, l2 P_
+    l: [K; 2],
+}
+
+impl<K> Param1<K> {
+    /**
+    Init observer parameters for first order plant
+
+    - `bandwidth`: The observer bandwidth _ωo_ (rad/s)
+    - `gain`: The input gain of plant _b0_
+    - `period`: The sampling period
+     */
+    pub fn new<T>(bandwidth: T, gain: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let omega = f64::cast(bandwidth);
+        let period = f64::cast(period);
+
+        Self {
+            period: K::cast(period),
+            gain: K::cast(f64::cast(gain) * period),
+            l: [
+                K::cast(2.0 * omega * period),
+                K::cast(omega * omega * period),
+            ],
+        }
+    }
+}
+
+/**
+Observer state for first order plant
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State1<T> {
+    /// The estimated states _z1, z2_
+    z: [T; 2],
+}
+
+impl<T> State1<T> {
+    /// Initialize observer state by zeros
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+
+    /// The estimated output _z1_
+    pub fn output(&self) -> T
+    where
+        T: Copy,
+    {
+        self.z[0]
+    }
+
+    /// The estimated total disturbance _z2_
+    pub fn disturbance(&self) -> T
+    where
+        T: Copy,
+    {
+        self.z[1]
+    }
+}
+
+/// The preset of observer sets the estimated output
+impl<T> ResetState for State1<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.z = [value, T::default()];
+    }
+}
+
+snapshot_fields! {
+    State1<T> {
+        z: [T; 2],
+    }
+}
+
+/**
+Extended state observer for first order plant
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of measured output and control.
+The output is a tuple of estimated output and total disturbance.
+*/
+#[derive(Debug)]
+pub struct Observer1<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Observer1<T, K>
+where
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<K, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = (T, T);
+    type Param = Param1<K>;
+    type State = State1<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (output, control): Self::Input,
+    ) -> Self::Output {
+        let [z1, z2] = state.z;
+        let [l1, l2] = param.l;
+        let error = T::cast(output - z1);
+
+        state.z = [
+            // z1 + P z2 + b0 P u + l1 P e
+            T::cast(
+                T::cast(T::cast(z1 + T::cast(param.period * z2)) + T::cast(param.gain * control))
+                    + T::cast(l1 * error),
+            ),
+            // z2 + l2 P e
+            T::cast(z2 + T::cast(l2 * error)),
+        ];
+
+        (state.z[0], state.z[1])
+    }
+}
+
+/**
+Observer parameters for second order plant
+
+- `K` - coefficients type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param2<K> {
+    /// The sampling period _P_
+    period: K,
+    /// The input gain _b0 P_
+    gain: K,
+    /// The observer gains _l1 P, l2 P, l3 P_
+    l: [K; 3],
+}
+
+impl<K> Param2<K> {
+    /**
+    Init observer parameters for second order plant
+
+    - `bandwidth`: The observer bandwidth _ωo_ (rad/s)
+    - `gain`: The input gain of plant _b0_
+    - `period`: The sampling period
+     */
+    pub fn new<T>(bandwidth: T, gain: T, period: T) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let omega = f64::cast(bandwidth);
+        let period = f64::cast(period);
+
+        Self {
+            period: K::cast(period),
+            gain: K::cast(f64::cast(gain) * period),
+            l: [
+                K::cast(3.0 * omega * period),
+                K::cast(3.0 * omega * omega * period),
+                K::cast(omega * omega * omega * period),
+            ],
+        }
+    }
+}
+
+/**
+Observer state for second order plant
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State2<T> {
+    /// The estimated states _z1, z2, z3_
+    z: [T; 3],
+}
+
+impl<T> State2<T> {
+    /// Initialize observer state by zeros
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+
+    /// The estimated output _z1_
+    pub fn output(&self) -> T
+    where
+        T: Copy,
+    {
+        self.z[0]
+    }
+
+    /// The estimated derivative of output _z2_
+    pub fn derivative(&self) -> T
+    where
+        T: Copy,
+    {
+        self.z[1]
+    }
+
+    /// The estimated total disturbance _z3_
+    pub fn disturbance(&self) -> T
+    where
+        T: Copy,
+    {
+        self.z[2]
+    }
+}
+
+/// The preset of observer sets the estimated output
+impl<T> ResetState for State2<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.z = [value, T::default(), T::default()];
+    }
+}
+
+snapshot_fields! {
+    State2<T> {
+        z: [T; 3],
+    }
+}
+
+/**
+Extended state observer for second order plant
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of measured output and control.
+The output is a tuple of estimated output, derivative of output and total disturbance.
+*/
+#[derive(Debug)]
+pub struct Observer2<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Observer2<T, K>
+where
+    T: Copy + Add<T> + Sub<T> + Cast<Prod<K, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = (T, T, T);
+    type Param = Param2<K>;
+    type State = State2<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (output, control): Self::Input,
+    ) -> Self::Output {
+        let [z1, z2, z3] = state.z;
+        let [l1, l2, l3] = param.l;
+        let error = T::cast(output - z1);
+
+        state.z = [
+            // z1 + P z2 + l1 P e
+            T::cast(T::cast(z1 + T::cast(param.period * z2)) + T::cast(l1 * error)),
+            // z2 + P z3 + b0 P u + l2 P e
+            T::cast(
+                T::cast(T::cast(z2 + T::cast(param.period * z3)) + T::cast(param.gain * control))
+                    + T::cast(l2 * error),
+            ),
+            // z3 + l3 P e
+            T::cast(z3 + T::cast(l3 * error)),
+        ];
+
+        (state.z[0], state.z[1], state.z[2])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn eso1_f32() {
+        let param = Param1::<f32>::new(10.0, 2.0, 0.01);
+        let mut state = State1::new();
+        type O = Observer1<f32, f32>;
+
+        assert_eq!(O::apply(&param, &mut state, (1.0, 0.0)), (0.2, 1.0));
+
+        // y' = 3 + 2 u
+        state.reset();
+        let mut output = 0.0;
+        for _ in 0..200 {
+            O::apply(&param, &mut state, (output, 1.0));
+            output += 0.01 * (3.0 + 2.0 * 1.0);
+        }
+        assert!((state.disturbance() - 3.0).abs() < 1e-3);
+        assert!((state.output() - output).abs() < 1e-3);
+    }
+
+    #[test]
+    fn eso2_f32() {
+        let param = Param2::<f32>::new(10.0, 2.0, 0.01);
+        let mut state = State2::new();
+        type O = Observer2<f32, f32>;
+
+        assert_eq!(O::apply(&param, &mut state, (1.0, 0.0)), (0.3, 3.0, 10.0));
+
+        // y'' = -1 + 2 u
+        state.reset();
+        let (mut output, mut derivative) = (0.0, 0.0);
+        for _ in 0..300 {
+            O::apply(&param, &mut state, (output, 1.0));
+            output += 0.01 * derivative;
+            derivative += 0.01 * (-1.0 + 2.0 * 1.0);
+        }
+        assert!((state.disturbance() + 1.0).abs() < 1e-2);
+        assert!((state.derivative() - derivative).abs() < 1e-2);
+    }
+
+    #[test]
+    fn eso1_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+
+        let param = Param1::<K>::new(10.0, 2.0, 0.0625);
+        let mut state = State1::new();
+        type O = Observer1<T, K>;
+
+        assert_eq!(
+            O::apply(&param, &mut state, (T::cast(1.0), T::cast(0.0))),
+            (T::cast(1.25), T::cast(6.25))
+        );
+        assert_eq!(state.output(), T::cast(1.25));
+    }
+}
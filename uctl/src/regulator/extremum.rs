@@ -0,0 +1,320 @@
+/*!
+
+## Extremum seeking regulator
+
+This module implements gradient-free online optimizer which drives the parameter of plant
+to the extremum of measurable cost, like the maximum power point of solar panel (MPPT)
+or the air-fuel ratio of burner with maximum efficiency.
+
+The regulator adds the small sinusoidal dither to the parameter _θ_:
+
+_u = θ + a sin ωt_
+
+The cost _J(u)_ of plant is measured and its DC component is removed by high-pass filter.
+The remaining oscillation is demodulated by the same sine and averaged by low-pass filter,
+so the estimation of gradient is obtained:
+
+_g = LPF(2 / a HPF(J) sin ωt) ≈ dJ / dθ_
+
+The parameter is driven by the gradient using integrator:
+
+_θ = Ki ∫g dt_
+
+The positive integral gain seeks the maximum and the negative gain seeks the minimum of cost.
+
+The dither is a table of _N_ samples of one period of sine which is evaluated
+at initialization (see [`SinCos`](crate::SinCos)), so the dither frequency is _1 / (N P)_.
+The filters is an [EMA](crate::ema) filters and the integrator is an [integral](crate::pid::Integral)
+regulator, so the parameter can be limited without windup.
+Note that the dither is added to the limited parameter.
+
+The dither period should be longer than the settling time of plant
+and the filters time constants should be longer than the dither period.
+The cost measured at each step is treated as a response to the dither applied at previous step.
+
+See also [Extremum seeking](https://en.wikipedia.org/wiki/Extremum_seeking).
+
+*/
+
+use crate::{
+    ema,
+    pid::{IParam, Integral, PiState},
+    snapshot_fields, Cast, ResetState, SinCos, Transducer,
+};
+use core::{
+    f64::consts::PI,
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Sum};
+
+/// The α factor of EMA filter with time constant _T_ for sampling period _P_
+fn ema_param<K>(time: f64, period: f64) -> ema::Param<K>
+where
+    K: Copy + Cast<f64> + Sub<K> + Cast<Diff<K, K>>,
+{
+    ema::Param::from_alpha(K::cast(period / (time + period)))
+}
+
+/**
+Extremum seeking regulator parameters
+
+- `X` - values type
+- `K` - coefficients type
+- `N` - the number of samples per dither period
+*/
+#[derive(Debug, Clone)]
+pub struct Param<X, K, N>
+where
+    N: ArrayLength<X> + ArrayLength<K>,
+{
+    /// The samples of dither
+    dither: GenericArray<X, N>,
+    /// The samples of demodulating sine premultiplied by _2 / a_
+    demod: GenericArray<K, N>,
+    /// The high-pass filter
+    highpass: ema::Param<K>,
+    /// The low-pass filter
+    lowpass: ema::Param<K>,
+    /// The integrator
+    integral: IParam<K, X>,
+    /// Seek the minimum instead of maximum
+    minimum: bool,
+}
+
+impl<X, K, N> Param<X, K, N>
+where
+    N: ArrayLength<X> + ArrayLength<K>,
+{
+    /**
+    Init extremum seeking regulator parameters
+
+    - `amplitude`: The amplitude of dither _a_
+    - `gain`: The integral gain _Ki_ (1/s, positive to seek maximum and negative to seek minimum)
+    - `highpass`: The time constant of high-pass filter (s)
+    - `lowpass`: The time constant of low-pass filter (s)
+    - `period`: The sampling period _P_ (s)
+
+    The parameter isn't limited by default.
+     */
+    pub fn new<V>(amplitude: V, gain: V, highpass: V, lowpass: V, period: V) -> Self
+    where
+        X: Cast<f64>,
+        K: Copy + Cast<f64> + Sub<K> + Cast<Diff<K, K>>,
+        f64: Cast<V>,
+    {
+        let amplitude = f64::cast(amplitude);
+        let period = f64::cast(period);
+        let gain = f64::cast(gain);
+
+        assert!(amplitude > 0.0 && N::USIZE >= 2);
+
+        let sine = |k: usize| SinCos::sin_cos(2.0 * PI * k as f64 / N::USIZE as f64).0;
+
+        Self {
+            dither: (0..N::USIZE)
+                .map(|k| X::cast(amplitude * sine(k)))
+                .collect(),
+            demod: GenericArray::generate(|k| K::cast(2.0 / amplitude * sine(k))),
+            highpass: ema_param(f64::cast(highpass), period),
+            lowpass: ema_param(f64::cast(lowpass), period),
+            integral: IParam::new::<f64>(gain.abs(), period),
+            minimum: gain < 0.0,
+        }
+    }
+
+    /**
+    Set parameter limits
+
+    - `min`: The minimum parameter
+    - `max`: The maximum parameter
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        X: Cast<f64>,
+        f64: Cast<V>,
+    {
+        self.integral = self.integral.with_limits(min, max);
+        self
+    }
+}
+
+/**
+Extremum seeking regulator state
+
+- `X` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<X> {
+    /// The index of applied dither sample
+    step: usize,
+    /// The high-pass filter state (the DC component of cost)
+    highpass: ema::State<X>,
+    /// The low-pass filter state (the gradient)
+    lowpass: ema::State<X>,
+    /// The integrator state (the parameter)
+    integral: PiState<X>,
+}
+
+impl<X> State<X> {
+    /// The parameter without dither
+    pub fn parameter(&self) -> X
+    where
+        X: Copy,
+    {
+        self.integral.integral()
+    }
+
+    /// The estimated gradient of cost
+    pub fn gradient(&self) -> X
+    where
+        X: Copy,
+    {
+        self.lowpass.value()
+    }
+}
+
+/// The preset value is a parameter
+impl<X> ResetState for State<X>
+where
+    X: Default,
+{
+    type Value = X;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.integral.preset(value);
+    }
+}
+
+snapshot_fields! {
+    State<X> {
+        step: usize,
+        highpass: ema::State<X>,
+        lowpass: ema::State<X>,
+        integral: PiState<X>,
+    }
+}
+
+/**
+Extremum seeking regulator
+
+- `X` - values type
+- `K` - coefficients type
+- `N` - the number of samples per dither period
+
+The input is a measured cost.
+The output is a parameter with dither.
+*/
+#[derive(Debug)]
+pub struct Seeker<X, K, N>(PhantomData<(X, K, N)>);
+
+impl<X, K, N> Transducer for Seeker<X, K, N>
+where
+    X: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<X>
+        + Sub<X>
+        + Cast<Prod<K, X>>
+        + Cast<Sum<X, X>>
+        + Cast<Diff<X, X>>,
+    K: Copy + Mul<X>,
+    N: ArrayLength<X> + ArrayLength<K>,
+{
+    type Input = X;
+    type Output = X;
+    type Param = Param<X, K, N>;
+    type State = State<X>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, cost: Self::Input) -> Self::Output {
+        let mean = ema::Filter::<K, X, X>::apply(&param.highpass, &mut state.highpass, cost);
+        let cost = X::cast(cost - mean);
+
+        let gradient = X::cast(param.demod[state.step] * cost);
+        let gradient = ema::Filter::<K, X, X>::apply(&param.lowpass, &mut state.lowpass, gradient);
+
+        // the integrator input is an error, so the gradient is negated to seek minimum
+        let zero = X::cast(0.0);
+        let input = if param.minimum {
+            (zero, gradient)
+        } else {
+            (gradient, zero)
+        };
+        let parameter = Integral::<X, K>::apply(&param.integral, &mut state.integral, input);
+
+        state.step = (state.step + 1) % N::USIZE;
+        X::cast(parameter + param.dither[state.step])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U20};
+    use ufix::bin::Fix;
+
+    /// The plant with maximum at 3
+    fn plant(u: f64) -> f64 {
+        10.0 - (u - 3.0) * (u - 3.0)
+    }
+
+    #[test]
+    fn seek_maximum() {
+        type S = Seeker<f64, f64, U20>;
+        let param = Param::<f64, f64, U20>::new(0.1, 1.0, 0.5, 0.5, 0.01);
+        let mut state = State::default();
+
+        let mut u = 0.0;
+        for _ in 0..3000 {
+            u = S::apply(&param, &mut state, plant(u));
+        }
+
+        assert!(
+            (state.parameter() - 3.0).abs() < 0.05,
+            "{}",
+            state.parameter()
+        );
+        assert!(state.gradient().abs() < 0.1);
+        assert!((u - 3.0).abs() <= 0.15);
+    }
+
+    #[test]
+    fn seek_minimum_limited() {
+        type S = Seeker<f64, f64, U20>;
+        // minimum of -J is at 3 but the parameter is limited by 2
+        let param = Param::<f64, f64, U20>::new(0.1, -1.0, 0.5, 0.5, 0.01).with_limits(-2.0, 2.0);
+        let mut state = State::default();
+
+        let mut u = 0.0;
+        for _ in 0..3000 {
+            u = S::apply(&param, &mut state, -plant(u));
+        }
+
+        assert!((state.parameter() - 2.0).abs() < 0.01);
+        assert!(u <= 2.0 + 0.1);
+
+        state.preset(2.5);
+        assert_eq!(state.parameter(), 2.5);
+    }
+
+    #[test]
+    fn seek_fix() {
+        type X = Fix<P32, N16>;
+        type S = Seeker<X, X, U20>;
+        let param = Param::<X, X, U20>::new(0.1, 1.0, 0.5, 0.5, 0.01);
+        let mut state = State::default();
+        state.preset(X::cast(5.0));
+
+        let mut u = 5.0;
+        for _ in 0..3000 {
+            u = f64::cast(S::apply(&param, &mut state, X::cast(plant(u))));
+        }
+
+        assert!((f64::cast(state.parameter()) - 3.0).abs() < 0.05);
+    }
+}
@@ -0,0 +1,268 @@
+/*!
+
+## Disturbance feedforward
+
+This module implements the compensation of measured disturbance which is added
+to the control value of regulator:
+
+_u = u<sub>c</sub> + K (T1 s + 1) / (T2 s + 1) d_
+
+where _u<sub>c</sub>_ is a control value of regulator and _d_ is a measured disturbance.
+
+The static gain _K_ compensates the steady state effect of disturbance and
+the lead-lag element compensates the difference of dynamics of disturbance
+and control paths: use _T1 > T2_ when the control path is slower than
+the disturbance path and _T1 < T2_ otherwise.
+
+The lead-lag element is discretized using [bilinear transform](crate::tustin::lead_lag):
+
+_f\[n\] = b0 d\[n\] + b1 d\[n-1\] - a1 f\[n-1\]_
+
+The sum of control and feedforward can be limited.
+
+*/
+
+use crate::{snapshot_fields, tustin, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Feedforward parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K, T> {
+    /// The numerator coefficient _b0_
+    b0: K,
+    /// The numerator coefficient _b1_
+    b1: K,
+    /// The denominator coefficient _a1_
+    a1: K,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<K, T> Param<K, T> {
+    /**
+    Init feedforward parameters
+
+    - `gain`: The static gain _K_
+    - `lead`: The lead time constant _T1_ (s)
+    - `lag`: The lag time constant _T2_ (s)
+    - `period`: The sampling period (s)
+
+    Use equal lead and lag time constants to get static compensation.
+    The output isn't limited by default.
+     */
+    pub fn new<V>(gain: V, lead: V, lag: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let ([b0, b1], [_, a1]) = tustin::lead_lag(
+            f64::cast(gain),
+            f64::cast(lead),
+            f64::cast(lag),
+            f64::cast(period),
+        );
+
+        Self {
+            b0: K::cast(b0),
+            b1: K::cast(b1),
+            a1: K::cast(a1),
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let min = f64::cast(min);
+        let max = f64::cast(max);
+
+        assert!(min < max);
+
+        self.min = Some(T::cast(min));
+        self.max = Some(T::cast(max));
+        self
+    }
+}
+
+/**
+Feedforward state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The last disturbance _d\[n-1\]_
+    disturbance: T,
+    /// The last feedforward _f\[n-1\]_
+    feedforward: T,
+}
+
+impl<T> State<T> {
+    /// The last feedforward value
+    pub fn feedforward(&self) -> T
+    where
+        T: Copy,
+    {
+        self.feedforward
+    }
+}
+
+/**
+The preset value is a disturbance and a feedforward
+
+Use the steady state values (_f = K d_) to avoid transient after preset.
+*/
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = (T, T);
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, (disturbance, feedforward): Self::Value) {
+        self.disturbance = disturbance;
+        self.feedforward = feedforward;
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        disturbance: T,
+        feedforward: T,
+    }
+}
+
+/**
+Disturbance feedforward
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of control value of regulator and measured disturbance.
+The output is a compensated control value.
+*/
+#[derive(Debug)]
+pub struct Feedforward<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Feedforward<T, K>
+where
+    T: Copy + PartialOrd + Add<T> + Sub<T> + Cast<Prod<K, T>> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = Param<K, T>;
+    type State = State<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (control, disturbance): Self::Input,
+    ) -> Self::Output {
+        // f[n] = b0 d[n] + b1 d[n-1] - a1 f[n-1]
+        let feedforward = T::cast(
+            T::cast(T::cast(param.b0 * disturbance) + T::cast(param.b1 * state.disturbance))
+                - T::cast(param.a1 * state.feedforward),
+        );
+
+        state.disturbance = disturbance;
+        state.feedforward = feedforward;
+
+        let output = T::cast(control + feedforward);
+
+        match (param.min, param.max) {
+            (_, Some(max)) if output > max => max,
+            (Some(min), _) if output < min => min,
+            _ => output,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn feedforward_static() {
+        type F = Feedforward<f64, f64>;
+        let param = Param::<f64, f64>::new(-0.5, 1.0, 1.0, 0.1).with_limits(-1.0, 1.0);
+        let mut state = State::default();
+
+        assert_eq!(F::apply(&param, &mut state, (0.5, 0.0)), 0.5);
+        assert!((F::apply(&param, &mut state, (0.5, 1.0)) - 0.0).abs() < 1e-12);
+        assert!((F::apply(&param, &mut state, (0.5, 2.0)) + 0.5).abs() < 1e-12);
+        assert_eq!(F::apply(&param, &mut state, (0.5, 4.0)), -1.0);
+        assert!((state.feedforward() + 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn feedforward_lead_lag() {
+        type F = Feedforward<f64, f64>;
+        // K = 2, T1 = 3 s, T2 = 1 s
+        let param = Param::<f64, f64>::new(2.0, 3.0, 1.0, 0.01);
+        let mut state = State::default();
+
+        // the step response begins from K T1 / T2
+        let first = F::apply(&param, &mut state, (0.0, 1.0));
+        assert!((first - 6.0).abs() < 0.02, "{}", first);
+
+        // and settles to K with time constant T2
+        let mut output = first;
+        for _ in 0..100 {
+            output = F::apply(&param, &mut state, (0.0, 1.0));
+        }
+        // 2 + (6 - 2) e^-1
+        assert!((output - 3.4715).abs() < 0.01, "{}", output);
+        for _ in 0..1000 {
+            output = F::apply(&param, &mut state, (0.0, 1.0));
+        }
+        assert!((output - 2.0).abs() < 1e-3, "{}", output);
+
+        // no transient after preset to steady state
+        state.preset((1.0, 2.0));
+        assert!((F::apply(&param, &mut state, (0.5, 1.0)) - 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn feedforward_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+        type F = Feedforward<T, K>;
+        let param = Param::<K, T>::new(0.5, 2.0, 2.0, 0.1).with_limits(0.0, 10.0);
+        let mut state = State::default();
+
+        assert_eq!(
+            F::apply(&param, &mut state, (T::cast(1.0), T::cast(2.0))),
+            T::cast(2.0)
+        );
+        assert_eq!(
+            F::apply(&param, &mut state, (T::cast(1.0), T::cast(-8.0))),
+            T::cast(0.0)
+        );
+    }
+}
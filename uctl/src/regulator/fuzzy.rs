@@ -0,0 +1,312 @@
+/*!
+
+## Fuzzy regulator
+
+This module implements small table-driven fuzzy inference regulator,
+which is useful for poorly modeled plants where the control strategy
+is known as a set of rules of thumb (like _"if the error is big and it is growing, then heat strongly"_).
+
+The regulator has two inputs: the error _e = r - y_ and the change of error _Δe = e - e<sub>-1</sub>_.
+Each input is fuzzified using _N_ triangular membership functions given by its centers.
+The neighboring functions overlaps so that the sum of memberships is always equal to one
+(the first and the last functions is extended to infinity).
+
+The rule table contains the output value for each pair of fuzzy sets of inputs
+(the singleton consequents): _if e is E<sub>i</sub> and Δe is DE<sub>j</sub> then u = u<sub>ij</sub>_.
+
+The product is used for inference and the centroid method is used for defuzzification:
+
+_u = Σ μ<sub>i</sub>(e) μ<sub>j</sub>(Δe) u<sub>ij</sub> / Σ μ<sub>i</sub>(e) μ<sub>j</sub>(Δe)_
+
+Because the sum of memberships is equal to one the divisor is always equal to one too,
+so the defuzzification doesn't require division. Only two neighboring functions
+of each input is active at once, so the evaluation requires only four rules.
+
+The fuzzy regulator complements the [PID](crate::pid) rather than replacing it.
+The output can be treated as a control value (PD-like regulator)
+or as a change of control value which should be integrated (PI-like regulator).
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Fuzzy regulator input parameters
+
+- `X` - input values type
+- `W` - membership values type
+- `N` - the number of membership functions (at least 2)
+*/
+#[derive(Debug, Clone)]
+pub struct InputParam<X, W, N>
+where
+    N: ArrayLength<X> + ArrayLength<W>,
+{
+    /// The centers of membership functions
+    centers: GenericArray<X, N>,
+    /// The slopes of membership functions
+    slopes: GenericArray<W, N>,
+}
+
+impl<X, W, N> InputParam<X, W, N>
+where
+    N: ArrayLength<X> + ArrayLength<W>,
+{
+    /**
+    Init fuzzy regulator input parameters
+
+    - `centers`: The centers of membership functions in ascending order (the length should be `N`)
+     */
+    pub fn new<V>(centers: &[V]) -> Self
+    where
+        V: Copy,
+        X: Cast<f64>,
+        W: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(centers.len() == N::USIZE && N::USIZE >= 2);
+
+        let center = |i: usize| f64::cast(centers[i]);
+
+        Self {
+            centers: (0..N::USIZE).map(|i| X::cast(center(i))).collect(),
+            slopes: (0..N::USIZE)
+                .map(|i| {
+                    W::cast(if i + 1 < N::USIZE {
+                        1.0 / (center(i + 1) - center(i))
+                    } else {
+                        0.0
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the index of active lower function and the membership of upper function
+    fn fuzzify(&self, value: X) -> (usize, W)
+    where
+        X: Copy + PartialOrd + Sub<X> + Cast<Diff<X, X>>,
+        W: Copy + Cast<f64> + Mul<X> + Cast<Prod<W, X>>,
+    {
+        let last = N::USIZE - 1;
+
+        if value <= self.centers[0] {
+            return (0, W::cast(0.0));
+        }
+        if value >= self.centers[last] {
+            return (last - 1, W::cast(1.0));
+        }
+
+        let i = self.centers[1..last]
+            .iter()
+            .take_while(|x| **x <= value)
+            .count();
+
+        (
+            i,
+            W::cast(self.slopes[i] * X::cast(value - self.centers[i])),
+        )
+    }
+}
+
+/**
+Fuzzy regulator parameters
+
+- `X` - input values type
+- `Y` - output values type
+- `W` - membership values type
+- `N` - the number of membership functions of each input
+*/
+#[derive(Debug, Clone)]
+pub struct Param<X, Y, W, N>
+where
+    N: ArrayLength<X> + ArrayLength<W> + ArrayLength<Y> + ArrayLength<GenericArray<Y, N>>,
+{
+    /// The membership functions of error
+    error: InputParam<X, W, N>,
+    /// The membership functions of change of error
+    delta: InputParam<X, W, N>,
+    /// The rule table
+    rules: GenericArray<GenericArray<Y, N>, N>,
+}
+
+impl<X, Y, W, N> Param<X, Y, W, N>
+where
+    N: ArrayLength<X> + ArrayLength<W> + ArrayLength<Y> + ArrayLength<GenericArray<Y, N>>,
+{
+    /**
+    Init fuzzy regulator parameters
+
+    - `error`: The centers of membership functions of error
+    - `delta`: The centers of membership functions of change of error
+    - `rules`: The outputs of rules (the rows corresponds to error and the columns corresponds to change of error)
+
+    The rules table should contain `N` × `N` values in row-major order.
+     */
+    pub fn new<V>(error: &[V], delta: &[V], rules: &[V]) -> Self
+    where
+        V: Copy,
+        X: Cast<f64>,
+        Y: Cast<f64>,
+        W: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(rules.len() == N::USIZE * N::USIZE);
+
+        Self {
+            error: InputParam::new(error),
+            delta: InputParam::new(delta),
+            rules: rules
+                .chunks(N::USIZE)
+                .map(|row| row.iter().map(|u| Y::cast(f64::cast(*u))).collect())
+                .collect(),
+        }
+    }
+}
+
+/**
+Fuzzy regulator state
+
+- `X` - input values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<X> {
+    /// The last error
+    last: Option<X>,
+}
+
+/// The preset value is the last error
+impl<X> ResetState for State<X> {
+    type Value = X;
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.last = Some(value);
+    }
+}
+
+snapshot_fields! {
+    State<X> {
+        last: Option<X>,
+    }
+}
+
+/**
+Fuzzy regulator
+
+- `X` - input values type
+- `Y` - output values type
+- `W` - membership values type
+
+The input is a pair of reference and actual value. The change of error is zero on the first step.
+*/
+#[derive(Debug)]
+pub struct Fuzzy<X, Y, W, N>(PhantomData<(X, Y, W, N)>);
+
+impl<X, Y, W, N> Transducer for Fuzzy<X, Y, W, N>
+where
+    X: Copy + PartialOrd + Sub<X> + Cast<Diff<X, X>>,
+    Y: Copy + Mul<W> + Add<Y> + Cast<Prod<Y, W>> + Cast<Sum<Y, Y>>,
+    W: Copy
+        + Cast<f64>
+        + Sub<W>
+        + Mul<W>
+        + Mul<X>
+        + Cast<Diff<W, W>>
+        + Cast<Prod<W, W>>
+        + Cast<Prod<W, X>>,
+    N: ArrayLength<X> + ArrayLength<W> + ArrayLength<Y> + ArrayLength<GenericArray<Y, N>>,
+{
+    type Input = (X, X);
+    type Output = Y;
+    type Param = Param<X, Y, W, N>;
+    type State = State<X>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, (r, y): Self::Input) -> Self::Output {
+        let error = X::cast(r - y);
+        let delta = X::cast(error - state.last.unwrap_or(error));
+        state.last = Some(error);
+
+        let (i, a) = param.error.fuzzify(error);
+        let (j, b) = param.delta.fuzzify(delta);
+        let one = W::cast(1.0);
+        let (a0, b0) = (W::cast(one - a), W::cast(one - b));
+
+        let rule = |i: usize, j: usize, a: W, b: W| Y::cast(param.rules[i][j] * W::cast(a * b));
+
+        Y::cast(
+            Y::cast(rule(i, j, a0, b0) + rule(i, j + 1, a0, b))
+                + Y::cast(rule(i + 1, j, a, b0) + rule(i + 1, j + 1, a, b)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U3, U5};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn fuzzy_linear() {
+        // the rules of linear PD regulator u = e + 0.5 Δe
+        type F = Fuzzy<f32, f32, f32, U3>;
+        let param = Param::<f32, f32, f32, U3>::new(
+            &[-1.0, 0.0, 1.0],
+            &[-1.0, 0.0, 1.0],
+            &[-1.5, -1.0, -0.5, -0.5, 0.0, 0.5, 0.5, 1.0, 1.5],
+        );
+        let mut state = State::default();
+
+        assert_eq!(F::apply(&param, &mut state, (0.5, 0.0)), 0.5);
+        assert_eq!(F::apply(&param, &mut state, (0.75, 0.0)), 0.875);
+        assert_eq!(F::apply(&param, &mut state, (0.0, 0.25)), -0.75);
+        // the inputs is saturated
+        assert_eq!(F::apply(&param, &mut state, (5.0, 0.0)), 1.5);
+        assert_eq!(F::apply(&param, &mut state, (5.0, 0.0)), 1.0);
+    }
+
+    #[test]
+    fn fuzzy_fix() {
+        type X = Fix<P32, N16>;
+        type F = Fuzzy<X, X, X, U5>;
+
+        // NB NS ZE PS PB
+        let sets = [-10.0, -2.0, 0.0, 2.0, 10.0];
+        #[rustfmt::skip]
+        let rules = [
+            -100.0, -100.0, -60.0, -30.0,   0.0,
+            -100.0,  -60.0, -20.0,   0.0,  30.0,
+             -60.0,  -20.0,   0.0,  20.0,  60.0,
+             -30.0,    0.0,  20.0,  60.0, 100.0,
+               0.0,   30.0,  60.0, 100.0, 100.0,
+        ];
+        let param = Param::<X, X, X, U5>::new(&sets, &sets, &rules);
+        let mut state = State::default();
+
+        let u = |state: &mut State<X>, r: f64, y: f64| {
+            f64::cast(F::apply(&param, state, (X::cast(r), X::cast(y))))
+        };
+
+        assert_eq!(u(&mut state, 0.0, 0.0), 0.0);
+        // e = 1, Δe = 1
+        assert!((u(&mut state, 1.0, 0.0) - 25.0).abs() < 1e-3);
+        // e = 6, Δe = 5
+        assert!((u(&mut state, 6.0, 0.0) - 87.5).abs() < 1e-3);
+        // e = 6, Δe = 0
+        assert!((u(&mut state, 6.0, 0.0) - 40.0).abs() < 1e-3);
+
+        state.preset(X::cast(-20.0));
+        // e = -20, Δe = 0
+        assert!((u(&mut state, -20.0, 0.0) + 60.0).abs() < 1e-3);
+    }
+}
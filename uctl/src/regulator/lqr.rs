@@ -0,0 +1,396 @@
+/*!
+
+## State feedback
+
+This module implements **state feedback** regulator:
+
+_u = -K x_
+
+where _x_ is a state vector of size _N_, _u_ is a control vector of size _M_
+and _K_ is a gain matrix of size _M×N_.
+
+The state vector usually is provided by observer or measured directly.
+
+The gain matrix can be found using **linear-quadratic regulator** (LQR) synthesis
+which minimizes the cost function:
+
+_J = Σ (x' Q x + u' R u)_
+
+for discrete-time plant model:
+
+_x\[k+1\] = A x\[k\] + B u\[k\]_
+
+With `std` feature the `lqr` function solves the discrete-time algebraic Riccati equation
+on host side to find the gain matrix.
+
+See also [Linear-quadratic regulator](https://en.wikipedia.org/wiki/Linear%E2%80%93quadratic_regulator).
+
+*/
+
+use crate::{Cast, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod};
+
+/// Static matrix of `R` rows and `C` columns
+pub type Matrix<T, R, C> = GenericArray<GenericArray<T, C>, R>;
+
+/**
+State feedback parameters
+
+- `K` - gains type
+- `N` - the size of state vector
+- `M` - the size of control vector
+*/
+#[derive(Debug, Clone)]
+pub struct Param<K, N, M>
+where
+    N: ArrayLength<K>,
+    M: ArrayLength<GenericArray<K, N>>,
+{
+    /// Gain matrix _K_
+    gain: Matrix<K, M, N>,
+}
+
+impl<K, N, M> Param<K, N, M>
+where
+    N: ArrayLength<K>,
+    M: ArrayLength<GenericArray<K, N>>,
+{
+    /**
+    Init state feedback parameters from matrix
+
+    - `gain`: The gain matrix _K_
+     */
+    pub fn from_matrix(gain: Matrix<K, M, N>) -> Self {
+        Self { gain }
+    }
+
+    /**
+    Init state feedback parameters from row-major gains
+
+    - `gain`: The elements of gain matrix _K_ row by row
+
+    The missing elements is filled by zeros.
+     */
+    pub fn new<T>(gain: &[T]) -> Self
+    where
+        T: Copy,
+        K: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let cols = N::to_usize();
+
+        Self {
+            gain: (0..M::to_usize())
+                .map(|row| {
+                    (0..cols)
+                        .map(|col| {
+                            K::cast(
+                                gain.get(row * cols + col)
+                                    .map(|value| f64::cast(*value))
+                                    .unwrap_or(0.0),
+                            )
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// The gain matrix
+    pub fn gain(&self) -> &Matrix<K, M, N> {
+        &self.gain
+    }
+}
+
+/**
+State feedback regulator
+
+- `X` - state values type
+- `U` - control values type
+- `K` - gains type
+- `N` - the size of state vector
+- `M` - the size of control vector
+
+The input is a state vector. The output is a control vector.
+*/
+#[derive(Debug)]
+pub struct StateFeedback<X, U, K, N, M>(PhantomData<(X, U, K, N, M)>);
+
+impl<X, U, K, N, M> Transducer for StateFeedback<X, U, K, N, M>
+where
+    X: Copy,
+    K: Copy + Mul<X>,
+    U: Default + Sub<U> + Cast<Prod<K, X>> + Cast<Diff<U, U>>,
+    N: ArrayLength<X> + ArrayLength<K>,
+    M: ArrayLength<U> + ArrayLength<GenericArray<K, N>>,
+{
+    type Input = GenericArray<X, N>;
+    type Output = GenericArray<U, M>;
+    type Param = Param<K, N, M>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, value: Self::Input) -> Self::Output {
+        // u[i] = -Σ K[i][j] x[j]
+        param
+            .gain
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(value.iter())
+                    .fold(U::default(), |accum, (k, x)| {
+                        U::cast(accum - U::cast(*k * *x))
+                    })
+            })
+            .collect()
+    }
+}
+
+/**
+Find optimal gain matrix of linear-quadratic regulator
+
+- `a`: The state matrix _A_ of discrete-time plant model (_N×N_)
+- `b`: The input matrix _B_ of discrete-time plant model (_N×M_)
+- `q`: The state cost matrix _Q_ (_N×N_)
+- `r`: The control cost matrix _R_ (_M×M_)
+
+The discrete-time algebraic Riccati equation is solved by iterations:
+
+_P = Q + A' P A - A' P B (R + B' P B)^-1 B' P A_
+
+_K = (R + B' P B)^-1 B' P A_
+
+Returns `None` when solution does not converge or the matrix _R + B' P B_ is singular.
+ */
+#[cfg(feature = "std")]
+pub fn lqr<N, M>(
+    a: &Matrix<f64, N, N>,
+    b: &Matrix<f64, N, M>,
+    q: &Matrix<f64, N, N>,
+    r: &Matrix<f64, M, M>,
+) -> Option<Matrix<f64, M, N>>
+where
+    N: ArrayLength<f64> + ArrayLength<GenericArray<f64, N>> + ArrayLength<GenericArray<f64, M>>,
+    M: ArrayLength<f64> + ArrayLength<GenericArray<f64, M>> + ArrayLength<GenericArray<f64, N>>,
+{
+    use dense::*;
+
+    const MAX_ITERATIONS: usize = 100_000;
+    const TOLERANCE: f64 = 1e-12;
+
+    let a = from_matrix(a);
+    let b = from_matrix(b);
+    let q = from_matrix(q);
+    let r = from_matrix(r);
+
+    let at = transpose(&a);
+    let bt = transpose(&b);
+
+    let mut p = q.clone();
+
+    for _ in 0..MAX_ITERATIONS {
+        // K = (R + B' P B)^-1 B' P A
+        let btp = mul(&bt, &p);
+        let s = add(&r, &mul(&btp, &b));
+        let k = mul(&inverse(s)?, &mul(&btp, &a));
+
+        // P = Q + A' P (A - B K)
+        let next = add(&q, &mul(&mul(&at, &p), &sub(&a, &mul(&b, &k))));
+
+        let delta = max_abs(&sub(&next, &p));
+        let scale = 1.0 + max_abs(&next);
+        p = next;
+
+        if !scale.is_finite() {
+            return None;
+        }
+        if delta <= TOLERANCE * scale {
+            return Some(k.iter().map(|row| row.iter().copied().collect()).collect());
+        }
+    }
+
+    None
+}
+
+#[cfg(feature = "std")]
+mod dense {
+    //! Dense matrix operations for host-side synthesis
+
+    use super::Matrix;
+    use generic_array::ArrayLength;
+    use std::vec::Vec;
+
+    pub type Dense = Vec<Vec<f64>>;
+
+    pub fn from_matrix<R, C>(matrix: &Matrix<f64, R, C>) -> Dense
+    where
+        C: ArrayLength<f64>,
+        R: ArrayLength<generic_array::GenericArray<f64, C>>,
+    {
+        matrix.iter().map(|row| row.to_vec()).collect()
+    }
+
+    pub fn transpose(a: &Dense) -> Dense {
+        let cols = a.first().map(|row| row.len()).unwrap_or(0);
+        (0..cols)
+            .map(|col| a.iter().map(|row| row[col]).collect())
+            .collect()
+    }
+
+    pub fn mul(a: &Dense, b: &Dense) -> Dense {
+        let cols = b.first().map(|row| row.len()).unwrap_or(0);
+        a.iter()
+            .map(|row| {
+                (0..cols)
+                    .map(|col| row.iter().zip(b.iter()).map(|(x, b)| x * b[col]).sum())
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn add(a: &Dense, b: &Dense) -> Dense {
+        zip_with(a, b, |a, b| a + b)
+    }
+
+    pub fn sub(a: &Dense, b: &Dense) -> Dense {
+        zip_with(a, b, |a, b| a - b)
+    }
+
+    fn zip_with(a: &Dense, b: &Dense, op: impl Fn(f64, f64) -> f64) -> Dense {
+        a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| a.iter().zip(b.iter()).map(|(a, b)| op(*a, *b)).collect())
+            .collect()
+    }
+
+    pub fn max_abs(a: &Dense) -> f64 {
+        a.iter()
+            .flat_map(|row| row.iter())
+            .fold(0.0, |max, value| max.max(value.abs()))
+    }
+
+    /// Gauss-Jordan elimination with partial pivoting
+    pub fn inverse(mut a: Dense) -> Option<Dense> {
+        let n = a.len();
+        let mut inv: Dense = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+
+        for col in 0..n {
+            let pivot = (col..n).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+            if a[pivot][col].abs() < f64::EPSILON {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let div = a[col][col];
+            for j in 0..n {
+                a[col][j] /= div;
+                inv[col][j] /= div;
+            }
+
+            for row in 0..n {
+                if row != col {
+                    let factor = a[row][col];
+                    for j in 0..n {
+                        a[row][j] -= factor * a[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+
+        Some(inv)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use generic_array::arr;
+    use typenum::{N16, P32, U1, U2};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn state_feedback_f32() {
+        let param = Param::<f32, U2, U1>::new(&[2.0, 0.5]);
+        type R = StateFeedback<f32, f32, f32, U2, U1>;
+
+        assert_eq!(
+            R::apply(&param, &mut (), arr![f32; 1.0, 2.0]),
+            arr![f32; -3.0]
+        );
+        assert_eq!(
+            R::apply(&param, &mut (), arr![f32; -1.0, 0.0]),
+            arr![f32; 2.0]
+        );
+    }
+
+    #[test]
+    fn state_feedback_fix() {
+        type X = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+
+        let param = Param::<K, U2, U2>::new(&[1.0, 0.0, 0.5, -0.25]);
+        type R = StateFeedback<X, X, K, U2, U2>;
+
+        assert_eq!(
+            R::apply(&param, &mut (), arr![X; X::cast(2.0), X::cast(4.0)]),
+            arr![X; X::cast(-2.0), X::cast(0.0)]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lqr_scalar() {
+        type M = Matrix<f64, U1, U1>;
+        let one: M = arr![GenericArray<f64, U1>; arr![f64; 1.0]];
+
+        // p = 1 + p - p² / (1 + p) => p = φ, k = p / (1 + p)
+        let k = lqr::<U1, U1>(&one, &one, &one, &one).unwrap();
+
+        assert!((k[0][0] - 0.618_033_988_749_895).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lqr_double_integrator() {
+        type V = GenericArray<f64, U2>;
+        type S = GenericArray<f64, U1>;
+
+        let period = 0.1;
+        let a: Matrix<f64, U2, U2> = arr![V; arr![f64; 1.0, period], arr![f64; 0.0, 1.0]];
+        let b: Matrix<f64, U2, U1> = arr![S; arr![f64; 0.5 * period * period], arr![f64; period]];
+        let q: Matrix<f64, U2, U2> = arr![V; arr![f64; 1.0, 0.0], arr![f64; 0.0, 1.0]];
+        let r: Matrix<f64, U1, U1> = arr![S; arr![f64; 0.1]];
+
+        let k = lqr::<U2, U1>(&a, &b, &q, &r).unwrap();
+        assert!(k[0][0] > 0.0 && k[0][1] > 0.0);
+
+        let param = Param::<f64, U2, U1>::from_matrix(k);
+        type R = StateFeedback<f64, f64, f64, U2, U1>;
+
+        // the closed loop is stable
+        let mut x = [1.0, 0.0];
+        for _ in 0..200 {
+            let u = R::apply(&param, &mut (), arr![f64; x[0], x[1]])[0];
+            x = [
+                a[0][0] * x[0] + a[0][1] * x[1] + b[0][0] * u,
+                a[1][0] * x[0] + a[1][1] * x[1] + b[1][0] * u,
+            ];
+        }
+        assert!(x[0].abs() < 1e-3 && x[1].abs() < 1e-3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lqr_singular() {
+        let zero: Matrix<f64, U1, U1> = arr![GenericArray<f64, U1>; arr![f64; 0.0]];
+        assert_eq!(lqr::<U1, U1>(&zero, &zero, &zero, &zero), None);
+    }
+}
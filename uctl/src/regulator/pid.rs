@@ -4,6 +4,1440 @@
 
 This module implements Proportional Integral Derivative regulator.
 
+The regulator is implemented in parallel form with filtered derivative:
+
+_u = Kp e + Ki ∫e dt + d_, where _e = r - y_
+
+The derivative term is evaluated from the measured output (not from the error)
+to avoid the kicks on steps of reference, and filtered by first order low-pass filter
+with time constant _Tf = Td / N_, where _Td = Kd / Kp_:
+
+_d = -Kd s / (1 + Tf s) y_
+
+The filter is discretized using backward Euler method:
+
+_d\[n\] = a d\[n-1\] - b (y\[n\] - y\[n-1\])_, where _a = Tf / (Tf + P)_, _b = Kd / (Tf + P)_
+
+The usual values of _N_ is in range 5..20. The higher _N_ gives the less filtered derivative.
+
+The derivative term is zero at the first sample after reset, which only initializes the last
+measured output, so the regulator doesn't kick when it starts from nonzero output.
+The [preset](ResetState::preset) sets the last measured output explicitly.
+
+The output can be limited. To prevent windup the integral isn't updated while the output
+is saturated and the error drives it further into saturation (conditional integration).
+Since the filtered derivative is a part of the output which is checked for saturation
+the derivative peaks holds the integral instead of winding it.
+
+The reduced regulators [`Integral`], [`Pi`] and [`Pd`] is intended for tiny targets,
+which shouldn't pay for unused terms. It uses the same tuning and anti-windup as [`Pid`]
+but stores only required coefficients and state.
+
+The variable-period regulators [`VarPid`] and [`VarIntegral`] takes the sampling period
+together with each input instead of baking it into parameters, which is useful for the
+event-driven or sporadic sampling (like radio-reported sensors) or for the loops with
+measured period (see [loop period meter](crate::period)).
+The derivative filter coefficients is evaluated at each step, so it costs two divisions.
+
 See also [PID](https://en.wikipedia.org/wiki/PID_controller) article.
 
  */
+
+use crate::{
+    Bounds, Cast, Core, CycleCost, OpCost, RangeOverflow, ResetState, Snapshot, Transducer,
+    ValueBounds, Wire, WorstCase,
+};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Sub},
+};
+use typenum::{Diff, Prod, Quot, Sum};
+
+/// The integral gain premultiplied by sampling period _Ki P_
+fn integral_gain(ki: f64, period: f64) -> f64 {
+    ki * period
+}
+
+/// The derivative filter coefficients _a_ and _b_
+fn derivative_filter(kp: f64, kd: f64, filter: f64, period: f64) -> (f64, f64) {
+    if kd > 0.0 {
+        assert!(kp > 0.0);
+        // Tf = Kd / (Kp N)
+        let time = kd / (kp * filter);
+        (time / (time + period), kd / (time + period))
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// The output limits
+fn limits<T, V>(min: V, max: V) -> (Option<T>, Option<T>)
+where
+    T: Cast<f64>,
+    f64: Cast<V>,
+{
+    let min = f64::cast(min);
+    let max = f64::cast(max);
+
+    assert!(min < max);
+
+    (Some(T::cast(min)), Some(T::cast(max)))
+}
+
+/**
+Limit the control value
+
+Returns the limited control value and the flag which indicates that the integral should be updated.
+*/
+fn saturate<T>(min: Option<T>, max: Option<T>, control: T, error: T, zero: T) -> (T, bool)
+where
+    T: Copy + PartialOrd,
+{
+    match (min, max) {
+        (_, Some(max)) if control > max => (max, error < zero),
+        (Some(min), _) if control < min => (min, error > zero),
+        _ => (control, true),
+    }
+}
+
+/**
+Worst-case output range of regulators
+
+- `input`: The range of reference and measured values
+- `min`: The minimum output (`f64::NEG_INFINITY` when unlimited)
+- `max`: The maximum output (`f64::INFINITY` when unlimited)
+
+The integral term isn't bounded by the input range, so the output range is given by limits only
+and the regulator without limits can overflow any type.
+*/
+pub const fn bounds(_input: Bounds, min: f64, max: f64) -> Bounds {
+    Bounds::new(min, max)
+}
+
+/**
+Verify the worst-case ranges of regulator
+
+- `gain`: The gain of error (_Kp_ or _Ki P_ for integral regulator)
+- `min`: The minimum output
+- `max`: The maximum output
+- `input`: The range of reference and measured values
+
+The error and its product by gain is verified too.
+*/
+fn worst_case<K, T>(
+    gain: K,
+    min: Option<T>,
+    max: Option<T>,
+    input: Bounds,
+) -> Result<Bounds, RangeOverflow>
+where
+    T: Copy + ValueBounds,
+    f64: Cast<K> + Cast<T>,
+{
+    let error = input.sub(input).check::<T>()?;
+    error.scale(f64::cast(gain)).check::<T>()?;
+    let min = min.map(f64::cast).unwrap_or(f64::NEG_INFINITY);
+    let max = max.map(f64::cast).unwrap_or(f64::INFINITY);
+    bounds(input, min, max).check::<T>()
+}
+
+/**
+Evaluate the filtered derivative term and store the measured output
+
+The first measured output only initializes the last one, so the derivative doesn't kick.
+*/
+fn derivative<T, K>(ka: K, kb: K, derivative: &mut T, last: &mut Option<T>, output: T) -> T
+where
+    T: Copy + Sub<T> + Cast<Prod<K, T>> + Cast<Diff<T, T>>,
+    K: Mul<T>,
+{
+    if let Some(last) = last.replace(output) {
+        // d[n] = a d[n-1] - b (y[n] - y[n-1])
+        *derivative = T::cast(T::cast(ka * *derivative) - T::cast(kb * T::cast(output - last)));
+    }
+    *derivative
+}
+
+/**
+PID parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K, T> {
+    /// The proportional gain _Kp_
+    kp: K,
+    /// The integral gain premultiplied by sampling period _Ki P_
+    ki: K,
+    /// The derivative filter pole _a_
+    ka: K,
+    /// The derivative filter gain _b_
+    kb: K,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<K, T> Param<K, T> {
+    /**
+    Init PID parameters
+
+    - `kp`: The proportional gain _Kp_
+    - `ki`: The integral gain _Ki_ (1/s)
+    - `kd`: The derivative gain _Kd_ (s)
+    - `filter`: The derivative filter coefficient _N_
+    - `period`: The sampling period _P_ (s)
+
+    The output isn't limited by default.
+     */
+    pub fn new<V>(kp: V, ki: V, kd: V, filter: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let kp = f64::cast(kp);
+        let period = f64::cast(period);
+        let (ka, kb) = derivative_filter(kp, f64::cast(kd), f64::cast(filter), period);
+
+        Self {
+            kp: K::cast(kp),
+            ki: K::cast(integral_gain(f64::cast(ki), period)),
+            ka: K::cast(ka),
+            kb: K::cast(kb),
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let (min, max) = limits(min, max);
+        self.min = min;
+        self.max = max;
+        self
+    }
+}
+
+impl<K, T> Wire for Param<K, T>
+where
+    K: Copy + Wire,
+    T: Copy + Wire,
+{
+    const SIZE: usize = <(K, K, K, K, Option<T>, Option<T>)>::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        (self.kp, self.ki, self.ka, self.kb, self.min, self.max).write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let (kp, ki, ka, kb, min, max) = Wire::read_from(buf);
+        Self {
+            kp,
+            ki,
+            ka,
+            kb,
+            min,
+            max,
+        }
+    }
+}
+
+/**
+PID state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The integral term
+    integral: T,
+    /// The filtered derivative term
+    derivative: T,
+    /// The last measured output (unknown until the first sample)
+    last: Option<T>,
+}
+
+impl<T> State<T> {
+    /// The integral term
+    pub fn integral(&self) -> T
+    where
+        T: Copy,
+    {
+        self.integral
+    }
+
+    /// The filtered derivative term
+    pub fn derivative(&self) -> T
+    where
+        T: Copy,
+    {
+        self.derivative
+    }
+
+    /// Override the integral term (used by coordinators to prevent windup)
+    pub(crate) fn set_integral(&mut self, integral: T) {
+        self.integral = integral;
+    }
+
+    /**
+    Track the actually applied control (external reset feedback)
+
+    - `control`: The control value which was evaluated by regulator
+    - `applied`: The control value which was actually applied
+
+    The integral term is shifted by difference, so the regulator continues
+    from the applied control value without bump when it takes over.
+     */
+    pub fn track(&mut self, control: T, applied: T)
+    where
+        T: Copy + Add<T> + Sub<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    {
+        self.integral = T::cast(self.integral + T::cast(applied - control));
+    }
+}
+
+/**
+The preset value is a control and a measured output
+
+The integral term is set to the control value, so the regulator continues smoothly
+from given operating point (bumpless transfer).
+*/
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = (T, T);
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, (control, output): Self::Value) {
+        self.integral = control;
+        self.derivative = T::default();
+        self.last = Some(output);
+    }
+}
+
+impl<T> Snapshot for State<T>
+where
+    T: Snapshot + Default,
+{
+    const SIZE: usize = 2 * T::SIZE + Option::<T>::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.integral.save(buffer);
+        let buffer = self.derivative.save(buffer);
+        self.last.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let buffer = self.integral.restore(buffer);
+        let buffer = self.derivative.restore(buffer);
+        self.last.restore(buffer)
+    }
+}
+
+/**
+PID regulator
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct Pid<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Pid<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = Param<K, T>;
+    type State = State<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let zero = T::cast(0.0);
+
+        let error = T::cast(reference - output);
+        let proportional = T::cast(param.kp * error);
+
+        let derivative = derivative(
+            param.ka,
+            param.kb,
+            &mut state.derivative,
+            &mut state.last,
+            output,
+        );
+
+        let integral = T::cast(state.integral + T::cast(param.ki * error));
+        let control = T::cast(T::cast(proportional + integral) + derivative);
+
+        let (control, update) = saturate(param.min, param.max, control, error, zero);
+        if update {
+            state.integral = integral;
+        }
+        control
+    }
+}
+
+impl<T, K> CycleCost for Pid<T, K>
+where
+    T: OpCost,
+    K: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        // 5 mul, 6 add/sub and 3 comparisons of saturation
+        5 * K::mul(core) + 9 * T::add(core)
+    }
+}
+
+impl<T, K> WorstCase for Pid<T, K>
+where
+    Self: Transducer<Param = Param<K, T>>,
+    T: Copy + ValueBounds,
+    K: Copy,
+    f64: Cast<K> + Cast<T>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        worst_case(param.kp, param.min, param.max, input)
+    }
+}
+
+/**
+Integral regulator parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct IParam<K, T> {
+    /// The integral gain premultiplied by sampling period _Ki P_
+    ki: K,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<K, T> IParam<K, T> {
+    /**
+    Init integral regulator parameters
+
+    - `ki`: The integral gain _Ki_ (1/s)
+    - `period`: The sampling period _P_ (s)
+
+    The output isn't limited by default.
+     */
+    pub fn new<V>(ki: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            ki: K::cast(integral_gain(f64::cast(ki), f64::cast(period))),
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let (min, max) = limits(min, max);
+        self.min = min;
+        self.max = max;
+        self
+    }
+}
+
+impl<K, T> Wire for IParam<K, T>
+where
+    K: Copy + Wire,
+    T: Copy + Wire,
+{
+    const SIZE: usize = <(K, Option<T>, Option<T>)>::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        (self.ki, self.min, self.max).write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let (ki, min, max) = Wire::read_from(buf);
+        Self { ki, min, max }
+    }
+}
+
+/**
+PI regulator parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct PiParam<K, T> {
+    /// The proportional gain _Kp_
+    kp: K,
+    /// The integral gain premultiplied by sampling period _Ki P_
+    ki: K,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<K, T> PiParam<K, T> {
+    /**
+    Init PI regulator parameters
+
+    - `kp`: The proportional gain _Kp_
+    - `ki`: The integral gain _Ki_ (1/s)
+    - `period`: The sampling period _P_ (s)
+
+    The output isn't limited by default.
+     */
+    pub fn new<V>(kp: V, ki: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            kp: K::cast(f64::cast(kp)),
+            ki: K::cast(integral_gain(f64::cast(ki), f64::cast(period))),
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let (min, max) = limits(min, max);
+        self.min = min;
+        self.max = max;
+        self
+    }
+}
+
+impl<K, T> Wire for PiParam<K, T>
+where
+    K: Copy + Wire,
+    T: Copy + Wire,
+{
+    const SIZE: usize = <(K, K, Option<T>, Option<T>)>::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        (self.kp, self.ki, self.min, self.max).write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let (kp, ki, min, max) = Wire::read_from(buf);
+        Self { kp, ki, min, max }
+    }
+}
+
+/**
+Integral and PI regulators state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PiState<T> {
+    /// The integral term
+    integral: T,
+}
+
+impl<T> PiState<T> {
+    /// The integral term
+    pub fn integral(&self) -> T
+    where
+        T: Copy,
+    {
+        self.integral
+    }
+}
+
+/// The preset value is a control (bumpless transfer)
+impl<T> ResetState for PiState<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, control: Self::Value) {
+        self.integral = control;
+    }
+}
+
+impl<T> Snapshot for PiState<T>
+where
+    T: Snapshot,
+{
+    const SIZE: usize = T::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        self.integral.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        self.integral.restore(buffer)
+    }
+}
+
+/**
+Integral regulator
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct Integral<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Integral<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = IParam<K, T>;
+    type State = PiState<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let error = T::cast(reference - output);
+        let integral = T::cast(state.integral + T::cast(param.ki * error));
+
+        let (control, update) = saturate(param.min, param.max, integral, error, T::cast(0.0));
+        if update {
+            state.integral = integral;
+        }
+        control
+    }
+}
+
+impl<T, K> CycleCost for Integral<T, K>
+where
+    T: OpCost,
+    K: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        // mul, 2 add/sub and 3 comparisons of saturation
+        K::mul(core) + 5 * T::add(core)
+    }
+}
+
+impl<T, K> WorstCase for Integral<T, K>
+where
+    Self: Transducer<Param = IParam<K, T>>,
+    T: Copy + ValueBounds,
+    K: Copy,
+    f64: Cast<K> + Cast<T>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        worst_case(param.ki, param.min, param.max, input)
+    }
+}
+
+/**
+PI regulator
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct Pi<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Pi<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = PiParam<K, T>;
+    type State = PiState<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let error = T::cast(reference - output);
+        let integral = T::cast(state.integral + T::cast(param.ki * error));
+        let control = T::cast(T::cast(param.kp * error) + integral);
+
+        let (control, update) = saturate(param.min, param.max, control, error, T::cast(0.0));
+        if update {
+            state.integral = integral;
+        }
+        control
+    }
+}
+
+impl<T, K> CycleCost for Pi<T, K>
+where
+    T: OpCost,
+    K: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        // 2 mul, 3 add/sub and 3 comparisons of saturation
+        2 * K::mul(core) + 6 * T::add(core)
+    }
+}
+
+impl<T, K> WorstCase for Pi<T, K>
+where
+    Self: Transducer<Param = PiParam<K, T>>,
+    T: Copy + ValueBounds,
+    K: Copy,
+    f64: Cast<K> + Cast<T>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        worst_case(param.kp, param.min, param.max, input)
+    }
+}
+
+/**
+PD regulator parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct PdParam<K, T> {
+    /// The proportional gain _Kp_
+    kp: K,
+    /// The derivative filter pole _a_
+    ka: K,
+    /// The derivative filter gain _b_
+    kb: K,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<K, T> PdParam<K, T> {
+    /**
+    Init PD regulator parameters
+
+    - `kp`: The proportional gain _Kp_
+    - `kd`: The derivative gain _Kd_ (s)
+    - `filter`: The derivative filter coefficient _N_
+    - `period`: The sampling period _P_ (s)
+
+    The output isn't limited by default.
+     */
+    pub fn new<V>(kp: V, kd: V, filter: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let kp = f64::cast(kp);
+        let (ka, kb) = derivative_filter(kp, f64::cast(kd), f64::cast(filter), f64::cast(period));
+
+        Self {
+            kp: K::cast(kp),
+            ka: K::cast(ka),
+            kb: K::cast(kb),
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let (min, max) = limits(min, max);
+        self.min = min;
+        self.max = max;
+        self
+    }
+}
+
+impl<K, T> Wire for PdParam<K, T>
+where
+    K: Copy + Wire,
+    T: Copy + Wire,
+{
+    const SIZE: usize = <(K, K, K, Option<T>, Option<T>)>::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        (self.kp, self.ka, self.kb, self.min, self.max).write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let (kp, ka, kb, min, max) = Wire::read_from(buf);
+        Self {
+            kp,
+            ka,
+            kb,
+            min,
+            max,
+        }
+    }
+}
+
+/**
+PD regulator state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PdState<T> {
+    /// The filtered derivative term
+    derivative: T,
+    /// The last measured output (unknown until the first sample)
+    last: Option<T>,
+}
+
+impl<T> PdState<T> {
+    /// The filtered derivative term
+    pub fn derivative(&self) -> T
+    where
+        T: Copy,
+    {
+        self.derivative
+    }
+}
+
+/// The preset value is a measured output
+impl<T> ResetState for PdState<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, output: Self::Value) {
+        self.derivative = T::default();
+        self.last = Some(output);
+    }
+}
+
+impl<T> Snapshot for PdState<T>
+where
+    T: Snapshot + Default,
+{
+    const SIZE: usize = T::SIZE + Option::<T>::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.derivative.save(buffer);
+        self.last.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let buffer = self.derivative.restore(buffer);
+        self.last.restore(buffer)
+    }
+}
+
+/**
+PD regulator
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct Pd<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Pd<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = (T, T);
+    type Output = T;
+    type Param = PdParam<K, T>;
+    type State = PdState<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let error = T::cast(reference - output);
+        let derivative = derivative(
+            param.ka,
+            param.kb,
+            &mut state.derivative,
+            &mut state.last,
+            output,
+        );
+        let control = T::cast(T::cast(param.kp * error) + derivative);
+
+        saturate(param.min, param.max, control, error, T::cast(0.0)).0
+    }
+}
+
+impl<T, K> CycleCost for Pd<T, K>
+where
+    T: OpCost,
+    K: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        // 3 mul, 4 add/sub and 2 comparisons of saturation
+        3 * K::mul(core) + 6 * T::add(core)
+    }
+}
+
+impl<T, K> WorstCase for Pd<T, K>
+where
+    Self: Transducer<Param = PdParam<K, T>>,
+    T: Copy + ValueBounds,
+    K: Copy,
+    f64: Cast<K> + Cast<T>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        worst_case(param.kp, param.min, param.max, input)
+    }
+}
+
+/**
+Variable-period PID regulator parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct VarParam<K, T> {
+    /// The proportional gain _Kp_
+    kp: K,
+    /// The integral gain _Ki_
+    ki: K,
+    /// The derivative gain _Kd_ and the derivative filter time constant _Tf_
+    kd: Option<(K, K)>,
+    /// The minimum output
+    min: Option<T>,
+    /// The maximum output
+    max: Option<T>,
+}
+
+impl<K, T> VarParam<K, T> {
+    /**
+    Init variable-period PID parameters
+
+    - `kp`: The proportional gain _Kp_
+    - `ki`: The integral gain _Ki_ (1/s)
+    - `kd`: The derivative gain _Kd_ (s)
+    - `filter`: The derivative filter coefficient _N_
+
+    The output isn't limited by default.
+     */
+    pub fn new<V>(kp: V, ki: V, kd: V, filter: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let kp = f64::cast(kp);
+        let kd = f64::cast(kd);
+
+        Self {
+            kp: K::cast(kp),
+            ki: K::cast(f64::cast(ki)),
+            kd: if kd > 0.0 {
+                assert!(kp > 0.0);
+                // Tf = Kd / (Kp N)
+                Some((K::cast(kd), K::cast(kd / (kp * f64::cast(filter)))))
+            } else {
+                None
+            },
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Init variable-period integral regulator parameters
+
+    - `ki`: The integral gain _Ki_ (1/s)
+     */
+    pub fn integral<V>(ki: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            kp: K::cast(0.0),
+            ki: K::cast(f64::cast(ki)),
+            kd: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    /**
+    Set output limits
+
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn with_limits<V>(mut self, min: V, max: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let (min, max) = limits(min, max);
+        self.min = min;
+        self.max = max;
+        self
+    }
+}
+
+/**
+Variable-period PID regulator
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference with measured output and the period since previous step.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct VarPid<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for VarPid<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy
+        + Add<K>
+        + Mul<T>
+        + Mul<K>
+        + Cast<Sum<K, K>>
+        + Cast<Prod<K, K>>
+        + Cast<Quot<Prod<K, K>, K>>,
+    Prod<K, K>: Cast<K> + Div<K>,
+{
+    type Input = ((T, T), K);
+    type Output = T;
+    type Param = VarParam<K, T>;
+    type State = State<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        ((reference, output), period): Self::Input,
+    ) -> Self::Output {
+        let zero = T::cast(0.0);
+
+        let error = T::cast(reference - output);
+        let proportional = T::cast(param.kp * error);
+
+        let derivative = match param.kd {
+            Some((kd, time)) => {
+                // a = Tf / (Tf + P), b = Kd / (Tf + P)
+                let sum = K::cast(time + period);
+                let ka = K::cast(Prod::<K, K>::cast(time) / sum);
+                let kb = K::cast(Prod::<K, K>::cast(kd) / sum);
+
+                derivative(ka, kb, &mut state.derivative, &mut state.last, output)
+            }
+            None => {
+                state.last = Some(output);
+                zero
+            }
+        };
+
+        let ki = K::cast(param.ki * period);
+        let integral = T::cast(state.integral + T::cast(ki * error));
+        let control = T::cast(T::cast(proportional + integral) + derivative);
+
+        let (control, update) = saturate(param.min, param.max, control, error, zero);
+        if update {
+            state.integral = integral;
+        }
+        control
+    }
+}
+
+/**
+Variable-period integral regulator
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference with measured output and the period since previous step.
+The output is a control value.
+*/
+#[derive(Debug)]
+pub struct VarIntegral<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for VarIntegral<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy + Mul<T> + Mul<K> + Cast<Prod<K, K>>,
+{
+    type Input = ((T, T), K);
+    type Output = T;
+    type Param = VarParam<K, T>;
+    type State = PiState<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        ((reference, output), period): Self::Input,
+    ) -> Self::Output {
+        let error = T::cast(reference - output);
+        let ki = K::cast(param.ki * period);
+        let integral = T::cast(state.integral + T::cast(ki * error));
+
+        let (control, update) = saturate(param.min, param.max, integral, error, T::cast(0.0));
+        if update {
+            state.integral = integral;
+        }
+        control
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32};
+    use ufix::bin::Fix;
+
+    /// The first order plant y' = (u - y) / τ with τ = 0.5 s
+    fn run<T, K>(
+        param: &Param<K, T>,
+        state: &mut State<T>,
+        output: &mut f64,
+        reference: f64,
+        steps: usize,
+    ) -> f64
+    where
+        Pid<T, K>: Transducer<Input = (T, T), Output = T, Param = Param<K, T>, State = State<T>>,
+        T: Cast<f64>,
+        f64: Cast<T>,
+    {
+        let mut peak = *output;
+        for _ in 0..steps {
+            let control = f64::cast(Pid::<T, K>::apply(
+                param,
+                state,
+                (T::cast(reference), T::cast(*output)),
+            ));
+            *output += 0.01 * (control - *output) / 0.5;
+            peak = peak.max(*output);
+        }
+        peak
+    }
+
+    #[test]
+    fn pid_proportional() {
+        type R = Pid<f32, f32>;
+        let param = Param::<f32, f32>::new(2.0, 0.0, 0.0, 10.0, 0.01);
+        let mut state = State::default();
+
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.0)), 2.0);
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.5)), 1.0);
+        assert_eq!(state.integral(), 0.0);
+        assert_eq!(state.derivative(), 0.0);
+    }
+
+    #[test]
+    fn pid_integral() {
+        type R = Pid<f32, f32>;
+        let param = Param::<f32, f32>::new(0.0, 10.0, 0.0, 10.0, 0.125);
+        let mut state = State::default();
+
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.0)), 1.25);
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.0)), 2.5);
+        assert_eq!(R::apply(&param, &mut state, (0.0, 0.5)), 1.875);
+    }
+
+    #[test]
+    fn pid_derivative_filter() {
+        type R = Pid<f64, f64>;
+        // Kd = 0.1, N = 10, Tf = 0.01 = P, a = 0.5, b = 5
+        let param = Param::<f64, f64>::new(1.0, 0.0, 0.1, 10.0, 0.01);
+        let mut state = State::default();
+
+        // the reference step doesn't kick the derivative
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.0)), 1.0);
+
+        // the output step is filtered
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.5)), 0.5 - 2.5);
+        assert_eq!(state.derivative(), -2.5);
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.5)), 0.5 - 1.25);
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.5)), 0.5 - 0.625);
+    }
+
+    #[test]
+    fn pid_derivative_first_sample() {
+        type R = Pid<f64, f64>;
+        let param = Param::<f64, f64>::new(1.0, 0.0, 0.1, 10.0, 0.01);
+        let mut state = State::default();
+
+        // the nonzero output at start doesn't kick the derivative
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.5)), 0.5);
+        assert_eq!(state.derivative(), 0.0);
+        assert_eq!(R::apply(&param, &mut state, (1.0, 0.5)), 0.5);
+
+        // the state is restored with the last output
+        let mut buffer = [0u8; State::<f64>::SIZE];
+        state.save(&mut buffer);
+        let mut other = State::default();
+        other.restore(&buffer);
+        assert_eq!(R::apply(&param, &mut other, (1.0, 0.75)), 0.25 - 1.25);
+    }
+
+    #[test]
+    fn pid_closed_loop() {
+        let param = Param::<f64, f64>::new(2.0, 4.0, 0.05, 10.0, 0.01);
+        let mut state = State::default();
+        let mut output = 0.0;
+
+        run(&param, &mut state, &mut output, 1.0, 500);
+        assert!((output - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn pid_anti_windup() {
+        let param = Param::<f64, f64>::new(2.0, 4.0, 0.05, 10.0, 0.01).with_limits(-1.5, 1.5);
+        let mut state = State::default();
+        let mut output = 0.0;
+
+        // the reference is unreachable
+        run(&param, &mut state, &mut output, 2.0, 500);
+        assert!((output - 1.5).abs() < 1e-3);
+        // the integral doesn't wind up
+        assert!(state.integral() < 1.5);
+
+        // recovery without excessive overshoot
+        let peak = run(&param, &mut state, &mut output, 1.0, 500);
+        assert!(peak < 1.5 + 1e-3);
+        assert!((output - 1.0).abs() < 1e-3);
+
+        // the lower limit
+        let peak = run(&param, &mut state, &mut output, -2.0, 500);
+        assert!(peak <= 1.0 + 1e-3);
+        assert!((output + 1.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn pid_preset() {
+        type R = Pid<f32, f32>;
+        let param = Param::<f32, f32>::new(2.0, 1.0, 0.1, 10.0, 0.01);
+        let mut state = State::default();
+
+        // bumpless transfer
+        state.preset((0.75, 1.0));
+        assert_eq!(R::apply(&param, &mut state, (1.0, 1.0)), 0.75);
+    }
+
+    #[test]
+    fn pid_reduced() {
+        let mut state = PiState::default();
+
+        // I
+        let param = IParam::<f32, f32>::new(10.0, 0.125).with_limits(-2.0, 2.0);
+        type I = Integral<f32, f32>;
+        assert_eq!(I::apply(&param, &mut state, (1.0, 0.0)), 1.25);
+        assert_eq!(I::apply(&param, &mut state, (1.0, 0.0)), 2.0);
+        assert_eq!(state.integral(), 1.25);
+        assert_eq!(I::apply(&param, &mut state, (0.0, 0.5)), 0.625);
+
+        // PI is the same as PID without derivative
+        let pi = PiParam::<f64, f64>::new(2.0, 4.0, 0.01).with_limits(-1.5, 1.5);
+        let pid = Param::<f64, f64>::new(2.0, 4.0, 0.0, 10.0, 0.01).with_limits(-1.5, 1.5);
+        let mut pi_state = PiState::default();
+        let mut pid_state = State::default();
+        for step in 0..300 {
+            let input = (if step < 150 { 2.0 } else { 0.5 }, step as f64 * 0.005);
+            assert_eq!(
+                Pi::<f64, f64>::apply(&pi, &mut pi_state, input),
+                Pid::<f64, f64>::apply(&pid, &mut pid_state, input)
+            );
+        }
+
+        // PD is the same as PID without integral
+        let pd = PdParam::<f64, f64>::new(1.0, 0.1, 10.0, 0.01);
+        let pid = Param::<f64, f64>::new(1.0, 0.0, 0.1, 10.0, 0.01);
+        let mut pd_state = PdState::default();
+        let mut pid_state = State::default();
+        for step in 0..100 {
+            let input = (1.0, (step as f64 * 0.1).sin());
+            assert_eq!(
+                Pd::<f64, f64>::apply(&pd, &mut pd_state, input),
+                Pid::<f64, f64>::apply(&pid, &mut pid_state, input)
+            );
+        }
+        assert_eq!(pd_state.derivative(), pid_state.derivative());
+    }
+
+    #[test]
+    fn pid_variable_period() {
+        // the same as PID with constant period
+        let var = VarParam::<f64, f64>::new(2.0, 4.0, 0.05, 10.0).with_limits(-1.5, 1.5);
+        let pid = Param::<f64, f64>::new(2.0, 4.0, 0.05, 10.0, 0.01).with_limits(-1.5, 1.5);
+        let mut var_state = State::default();
+        let mut pid_state = State::default();
+        for step in 0..300 {
+            let input = (
+                if step < 150 { 2.0 } else { 0.5 },
+                (step as f64 * 0.1).sin(),
+            );
+            let control = VarPid::<f64, f64>::apply(&var, &mut var_state, (input, 0.01));
+            let expected = Pid::<f64, f64>::apply(&pid, &mut pid_state, input);
+            assert!(
+                (control - expected).abs() < 1e-12,
+                "{} {}",
+                control,
+                expected
+            );
+        }
+
+        // the sporadic samples
+        type I = VarIntegral<f32, f32>;
+        let param = VarParam::<f32, f32>::integral(2.0);
+        let mut state = PiState::default();
+        assert_eq!(I::apply(&param, &mut state, ((1.0, 0.0), 0.25)), 0.5);
+        assert_eq!(I::apply(&param, &mut state, ((1.0, 0.0), 1.5)), 3.5);
+        assert_eq!(I::apply(&param, &mut state, ((0.0, 1.0), 0.5)), 2.5);
+    }
+
+    #[test]
+    fn pid_variable_period_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+        type R = VarPid<T, K>;
+
+        let param = VarParam::<K, T>::new(2.0, 4.0, 0.05, 10.0).with_limits(-1.5, 1.5);
+        let mut state = State::default();
+        let mut output = 0.0;
+
+        // the period is jittered around 10 ms
+        for step in 0..500 {
+            let period = 0.01 + 0.002 * [0.0, 1.0, -1.0, 0.5][step % 4];
+            let control = f64::cast(R::apply(
+                &param,
+                &mut state,
+                ((T::cast(1.0), T::cast(output)), K::cast(period)),
+            ));
+            output += period * (control - output) / 0.5;
+        }
+        assert!((output - 1.0).abs() < 1e-2, "{}", output);
+    }
+
+    #[test]
+    fn pid_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+
+        let param = Param::<K, T>::new(2.0, 4.0, 0.05, 10.0, 0.01).with_limits(-1.5, 1.5);
+        let mut state = State::default();
+        let mut output = 0.0;
+
+        run(&param, &mut state, &mut output, 1.0, 500);
+        assert!((output - 1.0).abs() < 1e-2);
+
+        assert_eq!(
+            Pid::<T, K>::apply(&param, &mut state, (T::cast(10.0), T::cast(0.0))),
+            T::cast(1.5)
+        );
+    }
+}
@@ -0,0 +1,316 @@
+/*!
+
+## Reference model
+
+This module implements **reference model** prefilter which shapes the raw setpoint
+into the smooth reference which the plant can actually follow.
+
+The prefilter is a first or second order model with limited rate and acceleration:
+
+- first order: _v' = (r - y) / T - v_
+- second order: _y'' = ω² (r - y) - 2 ζ ω y'_
+
+The change of rate is limited by acceleration limit
+and the rate itself is limited by rate limit on each step:
+
+_v = limit(v + limit(Δv, a P), vmax)_
+
+_y = y + v P_
+
+The output is a tuple of reference and its derivative (rate) which can be used as feed-forward.
+So the regulator doesn't need to handle the step changes of setpoint
+and the overshoot can be avoided without detuning of regulator.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Reference model parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K, T> {
+    /// Error gain
+    k1: K,
+    /// Rate gain
+    k2: K,
+    /// Sampling period
+    period: K,
+    /// Rate limit
+    rate: Option<T>,
+    /// Acceleration limit multiplied by period
+    accel: Option<T>,
+}
+
+impl<K, T> Param<K, T> {
+    /**
+    Init first order reference model
+
+    - `time`: The time constant _T_
+    - `period`: The sampling period
+
+    The rate follows the first order response immediately when acceleration isn't limited.
+     */
+    pub fn first_order<V>(time: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            k1: K::cast(1.0 / f64::cast(time)),
+            k2: K::cast(1.0),
+            period: K::cast(f64::cast(period)),
+            rate: None,
+            accel: None,
+        }
+    }
+
+    /**
+    Init second order reference model
+
+    - `omega`: The natural frequency _ω_ (rad/s)
+    - `zeta`: The damping ratio _ζ_ (usually 1 to avoid overshoot)
+    - `period`: The sampling period
+     */
+    pub fn second_order<V>(omega: V, zeta: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let omega = f64::cast(omega);
+        let period = f64::cast(period);
+
+        Self {
+            k1: K::cast(omega * omega * period),
+            k2: K::cast(2.0 * f64::cast(zeta) * omega * period),
+            period: K::cast(period),
+            rate: None,
+            accel: None,
+        }
+    }
+
+    /**
+    Set rate limit
+
+    - `rate`: The maximum absolute value of rate
+     */
+    pub fn with_rate<V>(mut self, rate: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        self.rate = Some(T::cast(f64::cast(rate)));
+        self
+    }
+
+    /**
+    Set acceleration limit
+
+    - `accel`: The maximum absolute value of acceleration
+     */
+    pub fn with_accel<V>(mut self, accel: V) -> Self
+    where
+        K: Copy,
+        T: Cast<f64>,
+        f64: Cast<V> + Cast<K>,
+    {
+        self.accel = Some(T::cast(f64::cast(accel) * f64::cast(self.period)));
+        self
+    }
+}
+
+/**
+Reference model state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// Actual reference
+    value: T,
+    /// Actual rate of reference
+    rate: T,
+}
+
+impl<T> State<T> {
+    /**
+    Initialize reference model state
+
+    - `value`: The initial reference
+     */
+    pub fn new(value: T) -> Self
+    where
+        T: Default,
+    {
+        Self {
+            value,
+            rate: T::default(),
+        }
+    }
+
+    /// Actual reference
+    pub fn value(&self) -> T
+    where
+        T: Copy,
+    {
+        self.value
+    }
+
+    /// Actual rate of reference
+    pub fn rate(&self) -> T
+    where
+        T: Copy,
+    {
+        self.rate
+    }
+}
+
+/// The preset of reference model sets the reference at rest
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        *self = Self::new(value);
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        value: T,
+        rate: T,
+    }
+}
+
+/**
+Reference model prefilter
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a raw setpoint.
+The output is a tuple of reference and rate of reference.
+*/
+#[derive(Debug)]
+pub struct Prefilter<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Prefilter<T, K>
+where
+    T: Copy
+        + PartialOrd
+        + Neg<Output = T>
+        + Add<T>
+        + Sub<T>
+        + Cast<Prod<K, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    K: Copy + Mul<T>,
+{
+    type Input = T;
+    type Output = (T, T);
+    type Param = Param<K, T>;
+    type State = State<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, target: Self::Input) -> Self::Output {
+        // Δv = k1 (r - y) - k2 v
+        let delta = T::cast(
+            T::cast(param.k1 * T::cast(target - state.value)) - T::cast(param.k2 * state.rate),
+        );
+
+        state.rate = limit(T::cast(state.rate + limit(delta, param.accel)), param.rate);
+        state.value = T::cast(state.value + T::cast(param.period * state.rate));
+
+        (state.value, state.rate)
+    }
+}
+
+fn limit<T>(value: T, limit: Option<T>) -> T
+where
+    T: Copy + PartialOrd + Neg<Output = T>,
+{
+    match limit {
+        Some(limit) if value > limit => limit,
+        Some(limit) if value < -limit => -limit,
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    type P = Prefilter<f32, f32>;
+
+    #[test]
+    fn prefilter_first_order() {
+        let param = Param::<f32, f32>::first_order(1.0, 0.5);
+        let mut state = State::new(0.0);
+
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.5, 1.0));
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.75, 0.5));
+
+        let param = param.with_rate(0.25);
+        state.reset();
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.125, 0.25));
+        assert_eq!(P::apply(&param, &mut state, -1.0), (0.0, -0.25));
+
+        let param = Param::<f32, f32>::first_order(1.0, 0.5).with_accel(1.0);
+        state.reset();
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.25, 0.5));
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.625, 0.75));
+    }
+
+    #[test]
+    fn prefilter_second_order() {
+        let param = Param::<f32, f32>::second_order(1.0, 1.0, 0.5);
+        let mut state = State::new(0.0);
+
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.25, 0.5));
+        assert_eq!(P::apply(&param, &mut state, 1.0), (0.4375, 0.375));
+
+        // critically damped response doesn't overshoot
+        for _ in 0..100 {
+            let (value, _) = P::apply(&param, &mut state, 1.0);
+            assert!(value <= 1.0);
+        }
+        assert!(state.value() > 0.999);
+
+        state.preset(2.0);
+        assert_eq!(P::apply(&param, &mut state, 2.0), (2.0, 0.0));
+    }
+
+    #[test]
+    fn prefilter_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N16>;
+
+        let param = Param::<K, T>::first_order(1.0, 0.5).with_accel(1.0);
+        let mut state = State::new(T::cast(0.0));
+        type P = Prefilter<T, K>;
+
+        assert_eq!(
+            P::apply(&param, &mut state, T::cast(1.0)),
+            (T::cast(0.25), T::cast(0.5))
+        );
+        assert_eq!(
+            P::apply(&param, &mut state, T::cast(1.0)),
+            (T::cast(0.625), T::cast(0.75))
+        );
+    }
+}
@@ -0,0 +1,314 @@
+/*!
+
+## Relay regulator
+
+This module implements on/off (**bang-bang**) regulator with hysteresis,
+which is usually used for thermal systems (heaters, compressors and etc.).
+
+The output is switched on when the error exceeds the half of hysteresis _h_
+and switched off when the error falls below the negative half of hysteresis:
+
+_on_ when _r - y > h / 2_, _off_ when _r - y < -h / 2_
+
+In reversed mode (for cooling) the error is _y - r_.
+
+The actuators usually require the minimum on and off times to prevent excessive wear
+(like compressors) or to allow the relay contacts to settle. The switching is delayed
+until the minimum time since the last switch is elapsed.
+After reset the minimum off time is enforced, so the restart delay is respected too.
+
+Optionally the regulator estimates the duty cycle of output using
+[exponential moving average](crate::ema) with given time constant.
+
+*/
+
+use crate::{snapshot_fields, steps, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Relay regulator parameters
+
+- `T` - values type
+- `K` - duty cycle type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T, K> {
+    /// The upper threshold of error _h / 2_
+    high: T,
+    /// The lower threshold of error _-h / 2_
+    low: T,
+    /// The minimum on time in steps
+    min_on: u32,
+    /// The minimum off time in steps
+    min_off: u32,
+    /// The reversed mode
+    reverse: bool,
+    /// The duty cycle averaging factor
+    duty: Option<K>,
+}
+
+impl<T, K> Param<T, K> {
+    /**
+    Init relay regulator parameters
+
+    - `hysteresis`: The full width of hysteresis _h_
+    - `min_on`: The minimum on time (s)
+    - `min_off`: The minimum off time (s)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<V>(hysteresis: V, min_on: V, min_off: V, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let period = f64::cast(period);
+        let half = 0.5 * f64::cast(hysteresis);
+
+        Self {
+            high: T::cast(half),
+            low: T::cast(-half),
+            min_on: steps(f64::cast(min_on), period),
+            min_off: steps(f64::cast(min_off), period),
+            reverse: false,
+            duty: None,
+        }
+    }
+
+    /// Switch on when the output is above the reference (for cooling)
+    pub fn reversed(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /**
+    Enable duty cycle estimation
+
+    - `time`: The averaging time constant (s)
+    - `period`: The sampling period (s)
+     */
+    pub fn with_duty<V>(mut self, time: V, period: V) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let period = f64::cast(period);
+        self.duty = Some(K::cast(period / (f64::cast(time) + period)));
+        self
+    }
+}
+
+/**
+Relay regulator state
+
+- `K` - duty cycle type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<K> {
+    /// The output state
+    on: bool,
+    /// The number of steps since last switch
+    elapsed: u32,
+    /// The estimated duty cycle
+    duty: K,
+}
+
+impl<K> State<K> {
+    /// The output state
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// The estimated duty cycle (zero when estimation is disabled)
+    pub fn duty(&self) -> K
+    where
+        K: Copy,
+    {
+        self.duty
+    }
+}
+
+/// The preset value is an output state (the minimum time is enforced after preset)
+impl<K> ResetState for State<K>
+where
+    K: Default,
+{
+    type Value = bool;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, on: Self::Value) {
+        self.on = on;
+        self.elapsed = 0;
+    }
+}
+
+snapshot_fields! {
+    State<K> {
+        on: bool,
+        elapsed: u32,
+        duty: K,
+    }
+}
+
+/**
+Relay regulator
+
+- `T` - values type
+- `K` - duty cycle type
+
+The input is a tuple of reference and measured output.
+The output is a state of actuator.
+*/
+#[derive(Debug)]
+pub struct Relay<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Relay<T, K>
+where
+    T: Copy + PartialOrd + Sub<T> + Cast<Diff<T, T>>,
+    K: Copy
+        + Cast<f64>
+        + Add<K>
+        + Sub<K>
+        + Mul<K>
+        + Cast<Prod<K, K>>
+        + Cast<Sum<K, K>>
+        + Cast<Diff<K, K>>,
+{
+    type Input = (T, T);
+    type Output = bool;
+    type Param = Param<T, K>;
+    type State = State<K>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (reference, output): Self::Input,
+    ) -> Self::Output {
+        let error = T::cast(if param.reverse {
+            output - reference
+        } else {
+            reference - output
+        });
+
+        let (demand, min_time) = if state.on {
+            (error > param.low, param.min_on)
+        } else {
+            (error > param.high, param.min_off)
+        };
+
+        state.elapsed = state.elapsed.saturating_add(1);
+
+        if demand != state.on && state.elapsed >= min_time {
+            state.on = demand;
+            state.elapsed = 0;
+        }
+
+        if let Some(factor) = param.duty {
+            let value = K::cast(if state.on { 1.0 } else { 0.0 });
+            state.duty = K::cast(state.duty + K::cast(factor * K::cast(value - state.duty)));
+        }
+
+        state.on
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn relay_hysteresis() {
+        type R = Relay<f32, f32>;
+        let param = Param::<f32, f32>::new(1.0, 0.0, 0.0, 1.0);
+        let mut state = State::default();
+
+        assert!(R::apply(&param, &mut state, (20.0, 19.0)));
+        assert!(R::apply(&param, &mut state, (20.0, 20.4)));
+        assert!(!R::apply(&param, &mut state, (20.0, 20.6)));
+        assert!(!R::apply(&param, &mut state, (20.0, 19.6)));
+        assert!(R::apply(&param, &mut state, (20.0, 19.4)));
+
+        let param = param.reversed();
+        assert!(!R::apply(&param, &mut state, (20.0, 19.4)));
+        assert!(R::apply(&param, &mut state, (20.0, 20.6)));
+    }
+
+    #[test]
+    fn relay_min_times() {
+        type R = Relay<f32, f32>;
+        // 3 s minimum on, 5 s minimum off, 1 s period
+        let param = Param::<f32, f32>::new(1.0, 3.0, 5.0, 1.0);
+        let mut state = State::default();
+
+        // restart delay after reset
+        let mut outputs = [false; 8];
+        for output in outputs.iter_mut() {
+            *output = R::apply(&param, &mut state, (20.0, 10.0));
+        }
+        assert_eq!(
+            outputs,
+            [false, false, false, false, true, true, true, true]
+        );
+
+        // minimum on time
+        state.preset(true);
+        let mut outputs = [false; 5];
+        for output in outputs.iter_mut() {
+            *output = R::apply(&param, &mut state, (20.0, 30.0));
+        }
+        assert_eq!(outputs, [true, true, false, false, false]);
+        assert!(!state.is_on());
+    }
+
+    #[test]
+    fn relay_thermal() {
+        type R = Relay<f64, f64>;
+        let param = Param::<f64, f64>::new(1.0, 10.0, 10.0, 1.0).with_duty(600.0, 1.0);
+        let mut state = State::default();
+
+        // heater of 40 °C rise above ambient of 10 °C with time constant 600 s
+        let mut temperature = 10.0;
+        let mut range = (f64::MAX, f64::MIN);
+        for step in 0..20000 {
+            let on = R::apply(&param, &mut state, (20.0, temperature));
+            temperature += ((if on { 40.0 } else { 0.0 }) - (temperature - 10.0)) / 600.0;
+            if step > 5000 {
+                range = (range.0.min(temperature), range.1.max(temperature));
+            }
+        }
+
+        assert!(range.0 > 19.0 && range.1 < 21.0, "{:?}", range);
+        // the steady state duty is 10 / 40
+        assert!((state.duty() - 0.25).abs() < 0.05, "{}", state.duty());
+    }
+
+    #[test]
+    fn relay_fix() {
+        type T = Fix<P32, N16>;
+        type R = Relay<T, T>;
+        let param = Param::<T, T>::new(1.0, 0.0, 0.0, 0.5).with_duty(0.5, 0.5);
+        let mut state = State::default();
+
+        assert!(R::apply(&param, &mut state, (T::cast(20.0), T::cast(19.0))));
+        assert_eq!(state.duty(), T::cast(0.5));
+        assert!(R::apply(
+            &param,
+            &mut state,
+            (T::cast(20.0), T::cast(20.25))
+        ));
+        assert_eq!(state.duty(), T::cast(0.75));
+        assert!(!R::apply(
+            &param,
+            &mut state,
+            (T::cast(20.0), T::cast(20.75))
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+/*!
+
+## Selector control
+
+This module implements the **override control** structure which combines the outputs
+of two [PID](crate::pid) regulators by low or high selector:
+
+- [`Selection::Low`]: _u = min(u<sub>1</sub>, u<sub>2</sub>)_
+- [`Selection::High`]: _u = max(u<sub>1</sub>, u<sub>2</sub>)_
+
+For example the flow regulator can be limited by the pressure regulator using low selector,
+so the pressure regulator takes over when the pressure approaches its limit.
+
+The integral term of non-selected regulator tracks the selected control value
+(external reset feedback), so it doesn't wind up and takes over without bump
+as soon as its own control value becomes selected.
+
+*/
+
+use crate::{pid, snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+use typenum::{Diff, Sum};
+
+/// The selection mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Select the lowest control value
+    Low,
+    /// Select the highest control value
+    High,
+}
+
+/**
+Selector control parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<K, T> {
+    /// The first regulator parameters
+    first: pid::Param<K, T>,
+    /// The second regulator parameters
+    second: pid::Param<K, T>,
+    /// The selection mode
+    selection: Selection,
+}
+
+impl<K, T> Param<K, T> {
+    /**
+    Init selector control parameters
+
+    - `first`: The first (main) regulator parameters
+    - `second`: The second (override) regulator parameters
+    - `selection`: The selection mode
+     */
+    pub fn new(first: pid::Param<K, T>, second: pid::Param<K, T>, selection: Selection) -> Self {
+        Self {
+            first,
+            second,
+            selection,
+        }
+    }
+}
+
+/**
+Selector control state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The first regulator state
+    first: pid::State<T>,
+    /// The second regulator state
+    second: pid::State<T>,
+    /// The second regulator is selected
+    overridden: bool,
+}
+
+impl<T> State<T> {
+    /// The first regulator state
+    pub fn first(&self) -> &pid::State<T> {
+        &self.first
+    }
+
+    /// The second regulator state
+    pub fn second(&self) -> &pid::State<T> {
+        &self.second
+    }
+
+    /// The second regulator is selected
+    pub fn is_overridden(&self) -> bool {
+        self.overridden
+    }
+}
+
+/**
+The preset value is a control and measured outputs of regulators
+
+Both regulators continues from given control value.
+*/
+impl<T> ResetState for State<T>
+where
+    T: Default + Copy,
+{
+    type Value = (T, T, T);
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, (control, first, second): Self::Value) {
+        self.first.preset((control, first));
+        self.second.preset((control, second));
+        self.overridden = false;
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        first: pid::State<T>,
+        second: pid::State<T>,
+        overridden: bool,
+    }
+}
+
+/**
+Selector control
+
+- `T` - values type
+- `K` - coefficients type
+
+The input is a tuple of reference and measured output pairs of first and second regulators.
+The output is a selected control value.
+*/
+#[derive(Debug)]
+pub struct Selector<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Selector<T, K>
+where
+    pid::Pid<T, K>:
+        Transducer<Input = (T, T), Output = T, Param = pid::Param<K, T>, State = pid::State<T>>,
+    T: Copy + PartialOrd + Add<T> + Sub<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+{
+    type Input = ((T, T), (T, T));
+    type Output = T;
+    type Param = Param<K, T>;
+    type State = State<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (first, second): Self::Input,
+    ) -> Self::Output {
+        let first = pid::Pid::<T, K>::apply(&param.first, &mut state.first, first);
+        let second = pid::Pid::<T, K>::apply(&param.second, &mut state.second, second);
+
+        state.overridden = match param.selection {
+            Selection::Low => second < first,
+            Selection::High => second > first,
+        };
+
+        if state.overridden {
+            state.first.track(first, second);
+            second
+        } else {
+            state.second.track(second, first);
+            first
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn select_low() {
+        type S = Selector<f64, f64>;
+        let first = pid::Param::new(0.0, 1.0, 0.0, 10.0, 1.0);
+        let second = pid::Param::new(0.0, 2.0, 0.0, 10.0, 1.0);
+        let param = Param::<f64, f64>::new(first, second, Selection::Low);
+        let mut state = State::default();
+
+        assert_eq!(S::apply(&param, &mut state, ((1.0, 0.0), (1.0, 0.0))), 1.0);
+        assert!(!state.is_overridden());
+        assert_eq!(state.second().integral(), 1.0);
+
+        assert_eq!(S::apply(&param, &mut state, ((1.0, 0.0), (0.0, 1.0))), -1.0);
+        assert!(state.is_overridden());
+        assert_eq!(state.first().integral(), -1.0);
+    }
+
+    #[test]
+    fn select_high() {
+        type S = Selector<f32, f32>;
+        let first = pid::Param::new(1.0, 0.0, 0.0, 10.0, 1.0);
+        let second = pid::Param::new(2.0, 0.0, 0.0, 10.0, 1.0);
+        let param = Param::<f32, f32>::new(first, second, Selection::High);
+        let mut state = State::default();
+
+        assert_eq!(S::apply(&param, &mut state, ((1.0, 0.0), (1.0, 0.0))), 2.0);
+        assert!(state.is_overridden());
+        // the first regulator continues from selected control value
+        assert_eq!(S::apply(&param, &mut state, ((1.0, 0.0), (-1.0, 0.0))), 2.0);
+        assert!(!state.is_overridden());
+    }
+
+    #[test]
+    fn select_override() {
+        type S = Selector<f64, f64>;
+        // flow and pressure regulators of valve opening
+        let flow = pid::Param::new(0.5, 0.5, 0.0, 10.0, 0.1).with_limits(0.0, 1.0);
+        let pressure = pid::Param::new(0.2, 0.2, 0.0, 10.0, 0.1).with_limits(0.0, 1.0);
+        let param = Param::<f64, f64>::new(flow, pressure, Selection::Low);
+        let mut state = State::default();
+
+        // the flow follows opening and pressure is 2 bar per opening with time constant 1 s
+        let (mut flow, mut pressure) = (0.0, 0.0);
+        let step = |state: &mut State<f64>, reference: f64, flow: &mut f64, pressure: &mut f64| {
+            let opening = S::apply(&param, state, ((reference, *flow), (1.5, *pressure)));
+            *flow += (opening - *flow) * 0.1;
+            *pressure += (2.0 * opening - *pressure) * 0.1;
+        };
+
+        // the flow reference can't be reached without exceeding of pressure limit
+        for _ in 0..1000 {
+            step(&mut state, 1.0, &mut flow, &mut pressure);
+        }
+        assert!(state.is_overridden());
+        assert!((pressure - 1.5).abs() < 1e-3, "{}", pressure);
+        assert!((flow - 0.75).abs() < 1e-3, "{}", flow);
+
+        // the flow regulator takes over immediately without windup
+        step(&mut state, 0.5, &mut flow, &mut pressure);
+        assert!(!state.is_overridden());
+        for _ in 0..200 {
+            step(&mut state, 0.5, &mut flow, &mut pressure);
+        }
+        assert!((flow - 0.5).abs() < 1e-2, "{}", flow);
+        assert!((pressure - 1.0).abs() < 2e-2, "{}", pressure);
+    }
+
+    #[test]
+    fn select_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+        type S = Selector<T, K>;
+        let first = pid::Param::new(1.0, 0.5, 0.0, 10.0, 1.0);
+        let second = pid::Param::new(1.0, 0.5, 0.0, 10.0, 1.0);
+        let param = Param::<K, T>::new(first, second, Selection::Low);
+        let mut state = State::default();
+
+        let value = S::apply(
+            &param,
+            &mut state,
+            ((T::cast(2.0), T::cast(0.0)), (T::cast(1.0), T::cast(0.0))),
+        );
+        assert_eq!(value, T::cast(1.5));
+        assert!(state.is_overridden());
+        // 1 + (1.5 - 3)
+        assert_eq!(state.first().integral(), T::cast(-0.5));
+    }
+}
@@ -0,0 +1,432 @@
+/*!
+
+## Multi-zone thermal regulator
+
+This module implements the container of _N_ thermal loops (zones) which share the common
+power budget (for example the heaters of extruder, bed and chamber powered from single supply).
+
+Each zone has own [PID](crate::pid) regulator which output is a relative power command
+in range _0..1_ (like for [time-proportioned output](crate::tpo)),
+the rated power of heater _P<sub>i</sub>_ and priority.
+
+The coordinator evaluates the total demand _D = Σ u<sub>i</sub> P<sub>i</sub>_ and when
+it exceeds the power budget _B_ the commands is reduced by one of the strategies:
+
+- [`Sharing::Proportional`]: all commands is scaled by _B / D_.
+- [`Sharing::Priority`]: the zones get the demanded power in order of priority
+  (from highest to lowest), the zone which exceeds the remaining budget gets the rest of it,
+  the zones with lower priority gets nothing.
+
+When the command of zone was reduced by coordinator the integral term of its regulator
+isn't increased to prevent windup.
+
+*/
+
+use crate::{pid, snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Quot, Sum};
+
+/// The power sharing strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sharing {
+    /// Scale all commands proportionally
+    Proportional,
+    /// Supply the zones in order of priority
+    Priority,
+}
+
+/**
+Zone parameters
+
+- `K` - coefficients type
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Zone<K, T> {
+    /// The regulator parameters
+    regulator: pid::Param<K, T>,
+    /// The rated power _P_
+    power: T,
+    /// The priority (greater is higher)
+    priority: u8,
+}
+
+impl<K, T> Zone<K, T> {
+    /**
+    Init zone parameters
+
+    - `regulator`: The regulator parameters (the output is limited to range _0..1_)
+    - `power`: The rated power of heater _P_
+    - `priority`: The priority of zone (greater is higher)
+     */
+    pub fn new<V>(regulator: pid::Param<K, T>, power: V, priority: u8) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            regulator: regulator.with_limits::<f64>(0.0, 1.0),
+            power: T::cast(f64::cast(power)),
+            priority,
+        }
+    }
+}
+
+/**
+Multi-zone regulator parameters
+
+- `K` - coefficients type
+- `T` - values type
+- `N` - the number of zones
+*/
+#[derive(Debug, Clone)]
+pub struct Param<K, T, N>
+where
+    N: ArrayLength<Zone<K, T>> + ArrayLength<usize>,
+{
+    /// The zones
+    zones: GenericArray<Zone<K, T>, N>,
+    /// The indexes of zones in order of priority
+    order: GenericArray<usize, N>,
+    /// The power budget _B_
+    budget: T,
+    /// The sharing strategy
+    sharing: Sharing,
+}
+
+impl<K, T, N> Param<K, T, N>
+where
+    N: ArrayLength<Zone<K, T>> + ArrayLength<usize>,
+{
+    /**
+    Init multi-zone regulator parameters
+
+    - `zones`: The parameters of zones
+    - `budget`: The power budget _B_
+    - `sharing`: The power sharing strategy
+     */
+    pub fn new<V>(zones: GenericArray<Zone<K, T>, N>, budget: V, sharing: Sharing) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let mut order: GenericArray<usize, N> = (0..N::USIZE).collect();
+        // stable sort by descending of priority
+        for i in 1..N::USIZE {
+            let mut j = i;
+            while j > 0 && zones[order[j - 1]].priority < zones[order[j]].priority {
+                order.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+
+        Self {
+            zones,
+            order,
+            budget: T::cast(f64::cast(budget)),
+            sharing,
+        }
+    }
+
+    /// Set the power budget
+    pub fn set_budget(&mut self, budget: T) {
+        self.budget = budget;
+    }
+}
+
+/**
+Multi-zone regulator state
+
+- `T` - values type
+- `N` - the number of zones
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<T, N>
+where
+    N: ArrayLength<pid::State<T>>,
+{
+    /// The states of regulators
+    zones: GenericArray<pid::State<T>, N>,
+}
+
+impl<T, N> State<T, N>
+where
+    N: ArrayLength<pid::State<T>>,
+{
+    /// The states of regulators
+    pub fn zones(&self) -> &GenericArray<pid::State<T>, N> {
+        &self.zones
+    }
+}
+
+/// The preset of multi-zone regulator presets all zones
+impl<T, N> ResetState for State<T, N>
+where
+    T: Default + Clone,
+    N: ArrayLength<pid::State<T>>,
+{
+    type Value = (T, T);
+
+    fn reset(&mut self) {
+        self.zones.reset();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.zones.preset(value);
+    }
+}
+
+snapshot_fields! {
+    State<T, N> where [N: ArrayLength<pid::State<T>>] {
+        zones: GenericArray<pid::State<T>, N>,
+    }
+}
+
+/**
+Multi-zone regulator
+
+- `T` - values type
+- `K` - coefficients type
+- `N` - the number of zones
+
+The input is an array of references and measured temperatures of zones.
+The output is an array of power commands of zones in range _0..1_.
+*/
+#[derive(Debug)]
+pub struct MultiZone<T, K, N>(PhantomData<(T, K, N)>);
+
+impl<T, K, N> Transducer for MultiZone<T, K, N>
+where
+    pid::Pid<T, K>:
+        Transducer<Input = (T, T), Output = T, Param = pid::Param<K, T>, State = pid::State<T>>,
+    T: Copy
+        + Default
+        + PartialOrd
+        + Add<T>
+        + Sub<T>
+        + Mul<T>
+        + Cast<Prod<T, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>
+        + Cast<Quot<Prod<T, T>, T>>,
+    Prod<T, T>: Cast<T> + Div<T>,
+    N: ArrayLength<Zone<K, T>>
+        + ArrayLength<usize>
+        + ArrayLength<pid::State<T>>
+        + ArrayLength<(T, T)>
+        + ArrayLength<T>,
+{
+    type Input = GenericArray<(T, T), N>;
+    type Output = GenericArray<T, N>;
+    type Param = Param<K, T, N>;
+    type State = State<T, N>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let zero = T::default();
+
+        let integrals: GenericArray<T, N> =
+            state.zones.iter().map(|zone| zone.integral()).collect();
+
+        let commands: GenericArray<T, N> = param
+            .zones
+            .iter()
+            .zip(state.zones.iter_mut())
+            .zip(value)
+            .map(|((zone, state), value)| pid::Pid::<T, K>::apply(&zone.regulator, state, value))
+            .collect();
+
+        // D = Σ u P
+        let demands: GenericArray<T, N> = param
+            .zones
+            .iter()
+            .zip(commands.iter())
+            .map(|(zone, command)| T::cast(*command * zone.power))
+            .collect();
+        let total = demands
+            .iter()
+            .fold(zero, |total, demand| T::cast(total + *demand));
+
+        if total <= param.budget {
+            return commands;
+        }
+
+        let mut outputs = commands.clone();
+
+        match param.sharing {
+            Sharing::Proportional => {
+                // B / D
+                let ratio = T::cast(Prod::<T, T>::cast(param.budget) / total);
+                for output in outputs.iter_mut() {
+                    *output = T::cast(*output * ratio);
+                }
+            }
+            Sharing::Priority => {
+                let mut remains = param.budget;
+                for index in param.order.iter().copied() {
+                    let demand = demands[index];
+                    if demand <= remains {
+                        remains = T::cast(remains - demand);
+                    } else {
+                        outputs[index] = if remains > zero {
+                            T::cast(Prod::<T, T>::cast(remains) / param.zones[index].power)
+                        } else {
+                            zero
+                        };
+                        remains = zero;
+                    }
+                }
+            }
+        }
+
+        // hold the integrals of reduced zones
+        for (((state, integral), command), output) in state
+            .zones
+            .iter_mut()
+            .zip(integrals)
+            .zip(commands)
+            .zip(outputs.iter())
+        {
+            if *output < command && state.integral() > integral {
+                state.set_integral(integral);
+            }
+        }
+
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use generic_array::arr;
+    use typenum::{N16, N24, P32, U3};
+    use ufix::bin::Fix;
+
+    fn zones<K, T>() -> GenericArray<Zone<K, T>, U3>
+    where
+        K: Cast<f64> + Copy,
+        T: Cast<f64> + Copy,
+    {
+        let regulator = pid::Param::new(2.0, 0.05, 0.0, 10.0, 1.0);
+        GenericArray::from([
+            Zone::new(regulator, 1000.0, 1),
+            Zone::new(regulator, 1000.0, 3),
+            Zone::new(regulator, 500.0, 2),
+        ])
+    }
+
+    fn total(outputs: &GenericArray<f64, U3>) -> f64 {
+        outputs[0] * 1000.0 + outputs[1] * 1000.0 + outputs[2] * 500.0
+    }
+
+    #[test]
+    fn zones_within_budget() {
+        type Z = MultiZone<f64, f64, U3>;
+        let param = Param::<f64, f64, U3>::new(zones(), 3000.0, Sharing::Proportional);
+        let mut state = State::default();
+
+        let outputs = Z::apply(
+            &param,
+            &mut state,
+            arr![(f64, f64); (100.0, 99.8), (100.0, 99.9), (100.0, 100.0)],
+        );
+        assert!((outputs[0] - 0.41).abs() < 1e-9);
+        assert!((outputs[1] - 0.205).abs() < 1e-9);
+        assert_eq!(outputs[2], 0.0);
+    }
+
+    #[test]
+    fn zones_proportional() {
+        type Z = MultiZone<f64, f64, U3>;
+        let param = Param::<f64, f64, U3>::new(zones(), 1250.0, Sharing::Proportional);
+        let mut state = State::default();
+
+        let outputs = Z::apply(
+            &param,
+            &mut state,
+            arr![(f64, f64); (100.0, 20.0), (100.0, 20.0), (100.0, 20.0)],
+        );
+        assert!((total(&outputs) - 1250.0).abs() < 1e-9);
+        assert!((outputs[0] - 0.5).abs() < 1e-9);
+        assert!((outputs[1] - 0.5).abs() < 1e-9);
+        assert!((outputs[2] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zones_priority() {
+        type Z = MultiZone<f64, f64, U3>;
+        let param = Param::<f64, f64, U3>::new(zones(), 1250.0, Sharing::Priority);
+        let mut state = State::default();
+
+        let outputs = Z::apply(
+            &param,
+            &mut state,
+            arr![(f64, f64); (100.0, 20.0), (100.0, 20.0), (100.0, 20.0)],
+        );
+        assert_eq!(outputs[1], 1.0);
+        assert_eq!(outputs[2], 0.5);
+        assert_eq!(outputs[0], 0.0);
+
+        // the integral of reduced zone doesn't wind up
+        for _ in 0..100 {
+            Z::apply(
+                &param,
+                &mut state,
+                arr![(f64, f64); (100.0, 20.0), (100.0, 20.0), (100.0, 20.0)],
+            );
+        }
+        assert_eq!(state.zones()[0].integral(), 0.0);
+        assert_eq!(state.zones()[2].integral(), 0.0);
+    }
+
+    #[test]
+    fn zones_thermal() {
+        type Z = MultiZone<f64, f64, U3>;
+        let param = Param::<f64, f64, U3>::new(zones(), 1500.0, Sharing::Proportional);
+        let mut state = State::default();
+
+        // each zone heats by 0.2 °C/s per 1 kW above ambient 20 °C with time constant 500 s
+        let mut temperatures = [20.0; 3];
+        let mut peak = 0.0f64;
+        for _ in 0..5000 {
+            let outputs = Z::apply(
+                &param,
+                &mut state,
+                arr![(f64, f64); (60.0, temperatures[0]), (60.0, temperatures[1]), (60.0, temperatures[2])],
+            );
+            peak = peak.max(total(&outputs));
+            let powers = [outputs[0] * 1000.0, outputs[1] * 1000.0, outputs[2] * 500.0];
+            for (temperature, power) in temperatures.iter_mut().zip(powers.iter()) {
+                *temperature += power * 2e-4 - (*temperature - 20.0) / 500.0;
+            }
+        }
+
+        assert!(peak <= 1500.0 + 1e-9);
+        for temperature in temperatures.iter() {
+            assert!((temperature - 60.0).abs() < 0.1, "{:?}", temperatures);
+        }
+    }
+
+    #[test]
+    fn zones_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P32, N24>;
+        type Z = MultiZone<T, K, U3>;
+
+        let param = Param::<K, T, U3>::new(zones(), 1250.0, Sharing::Proportional);
+        let mut state = State::default();
+
+        let outputs = Z::apply(
+            &param,
+            &mut state,
+            arr![(T, T); (T::cast(100.0), T::cast(20.0)), (T::cast(100.0), T::cast(20.0)), (T::cast(100.0), T::cast(20.0))],
+        );
+        for output in outputs.iter() {
+            assert!((f64::cast(*output) - 0.5).abs() < 1e-3);
+        }
+    }
+}
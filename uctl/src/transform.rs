@@ -1,5 +1,9 @@
 pub mod ab;
+pub mod burst;
 pub mod dqz;
+pub mod phase;
 pub mod psc;
+pub mod pulse;
 pub mod svm;
 pub mod swm;
+pub mod tpo;
@@ -2,6 +2,245 @@
 
 ## αβ (Clarke) transformation
 
+This module implements the value types of three-phase quantities in natural frame [`Abc`]
+and in stationary orthogonal frame [`AlphaBeta`], so the motor-control code can't mix frames accidentally.
+
+The Clarke transformation is expressed as a conversions between them.
+The amplitude-invariant form is used (the amplitude of vector is the same as the phase amplitude):
+
+_α = (2 a - b - c) / 3_
+
+_β = (b - c) / √3_
+
+The inverse transformation drops the zero-sequence component:
+
+_a = α_
+
+_b = -α / 2 + √3 β / 2_
+
+_c = -α / 2 - √3 β / 2_
+
+The types supports component-wise addition and subtraction, the scaling by factor
+and the casting between value types, so it can be used as the values of filters and regulators.
+
 See also [αβ transformation](https://en.wikipedia.org/wiki/Alpha-beta_transformation).
 
  */
+
+use crate::Cast;
+use core::ops::{Add, Mul, Sub};
+use typenum::{Diff, Prod, Sum};
+
+/// √3
+const SQRT_3: f64 = 1.7320508075688772;
+
+/**
+Three-phase values in natural frame
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Abc<T> {
+    /// Phase A
+    pub a: T,
+    /// Phase B
+    pub b: T,
+    /// Phase C
+    pub c: T,
+}
+
+impl<T> Abc<T> {
+    /// Create three-phase values
+    pub fn new(a: T, b: T, c: T) -> Self {
+        Self { a, b, c }
+    }
+}
+
+/**
+Two-phase values in stationary orthogonal frame
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AlphaBeta<T> {
+    /// Alpha component (along the phase A)
+    pub alpha: T,
+    /// Beta component
+    pub beta: T,
+}
+
+impl<T> AlphaBeta<T> {
+    /// Create two-phase values
+    pub fn new(alpha: T, beta: T) -> Self {
+        Self { alpha, beta }
+    }
+}
+
+/// Clarke transformation
+impl<T> From<Abc<T>> for AlphaBeta<T>
+where
+    T: Copy + Cast<f64> + Add<T> + Sub<T> + Mul<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    T: Cast<Prod<T, T>>,
+{
+    fn from(Abc { a, b, c }: Abc<T>) -> Self {
+        // α = (2 a - (b + c)) / 3
+        let alpha = T::cast(T::cast(a + a) - T::cast(b + c));
+        // β = (b - c) / √3
+        let beta = T::cast(b - c);
+
+        Self {
+            alpha: T::cast(alpha * T::cast(1.0 / 3.0)),
+            beta: T::cast(beta * T::cast(1.0 / SQRT_3)),
+        }
+    }
+}
+
+/// Inverse Clarke transformation
+impl<T> From<AlphaBeta<T>> for Abc<T>
+where
+    T: Copy + Cast<f64> + Add<T> + Sub<T> + Mul<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+    T: Cast<Prod<T, T>>,
+{
+    fn from(AlphaBeta { alpha, beta }: AlphaBeta<T>) -> Self {
+        let half_alpha = T::cast(alpha * T::cast(0.5));
+        let beta = T::cast(beta * T::cast(0.5 * SQRT_3));
+
+        Self {
+            a: alpha,
+            b: T::cast(beta - half_alpha),
+            c: T::cast(T::cast(0.0) - T::cast(half_alpha + beta)),
+        }
+    }
+}
+
+macro_rules! vector_ops {
+    ($type:ident { $($field:ident),+ }) => {
+        impl<T> Add for $type<T>
+        where
+            T: Add<T>,
+        {
+            type Output = $type<Sum<T, T>>;
+
+            fn add(self, other: Self) -> Self::Output {
+                $type {
+                    $($field: self.$field + other.$field,)+
+                }
+            }
+        }
+
+        impl<T> Sub for $type<T>
+        where
+            T: Sub<T>,
+        {
+            type Output = $type<Diff<T, T>>;
+
+            fn sub(self, other: Self) -> Self::Output {
+                $type {
+                    $($field: self.$field - other.$field,)+
+                }
+            }
+        }
+
+        /// Scaling by factor
+        impl<T, K> Mul<K> for $type<T>
+        where
+            T: Mul<K>,
+            K: Copy,
+        {
+            type Output = $type<Prod<T, K>>;
+
+            fn mul(self, factor: K) -> Self::Output {
+                $type {
+                    $($field: self.$field * factor,)+
+                }
+            }
+        }
+
+        impl<T, U> Cast<$type<U>> for $type<T>
+        where
+            T: Cast<U>,
+        {
+            fn cast(value: $type<U>) -> Self {
+                $type {
+                    $($field: T::cast(value.$field),)+
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use vector_ops;
+
+vector_ops!(Abc { a, b, c });
+vector_ops!(AlphaBeta { alpha, beta });
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    /// The balanced three-phase values with given amplitude and angle
+    fn balanced(amplitude: f64, angle: f64) -> Abc<f64> {
+        let phase = 2.0 * core::f64::consts::PI / 3.0;
+        Abc::new(
+            amplitude * angle.cos(),
+            amplitude * (angle - phase).cos(),
+            amplitude * (angle + phase).cos(),
+        )
+    }
+
+    #[test]
+    fn clarke_f64() {
+        for step in 0..12 {
+            let angle = step as f64 * 0.5;
+            let ab = AlphaBeta::from(balanced(2.0, angle));
+
+            assert!((ab.alpha - 2.0 * angle.cos()).abs() < 1e-12);
+            assert!((ab.beta - 2.0 * angle.sin()).abs() < 1e-12);
+
+            let abc = Abc::from(ab);
+            let expected = balanced(2.0, angle);
+            assert!((abc.a - expected.a).abs() < 1e-12);
+            assert!((abc.b - expected.b).abs() < 1e-12);
+            assert!((abc.c - expected.c).abs() < 1e-12);
+        }
+
+        // the zero-sequence component is dropped
+        let ab = AlphaBeta::from(Abc::new(1.0, 1.0, 1.0));
+        assert_eq!(ab, AlphaBeta::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn clarke_ops() {
+        let x = AlphaBeta::new(1.0f32, 2.0);
+        let y = AlphaBeta::new(0.5f32, -1.0);
+
+        assert_eq!(x + y, AlphaBeta::new(1.5, 1.0));
+        assert_eq!(x - y, AlphaBeta::new(0.5, 3.0));
+        assert_eq!(x * 2.0, AlphaBeta::new(2.0, 4.0));
+        assert_eq!(
+            Abc::new(1.0f32, 2.0, 3.0) + Abc::new(1.0, 1.0, 1.0),
+            Abc::new(2.0, 3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn clarke_fix() {
+        type T = Fix<P32, N16>;
+
+        let abc = balanced(10.0, 0.3);
+        let ab = AlphaBeta::<T>::from(Abc::<T>::new(
+            T::cast(abc.a),
+            T::cast(abc.b),
+            T::cast(abc.c),
+        ));
+
+        assert!((f64::cast(ab.alpha) - 10.0 * 0.3f64.cos()).abs() < 1e-3);
+        assert!((f64::cast(ab.beta) - 10.0 * 0.3f64.sin()).abs() < 1e-3);
+
+        // the sum has wider type
+        let sum = AlphaBeta::<T>::cast(ab + ab);
+        assert_eq!(sum.alpha, T::cast(ab.alpha + ab.alpha));
+    }
+}
@@ -0,0 +1,236 @@
+/*!
+
+## Burst-fire modulation
+
+This module implements **burst-fire** (zero-cross cycle skipping) modulation
+for heaters driven by solid state relays, when the phase-angle control isn't possible.
+
+The power command _u_ in range _0..1_ is quantized to the number of full mains cycles
+_k = round(u N)_ in the window of _N_ cycles. The _k_ on-cycles is distributed uniformly
+over the window using Bresenham algorithm:
+
+_a = a + k_, the cycle is on when _a ≥ N_ then _a = a - N_
+
+The command is latched at the beginning of each window, so exactly _k_ cycles is on per window.
+
+The mean power of resistive load is proportional to the command _k / N_,
+the RMS voltage is _√(k / N)_ of mains RMS voltage.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+use typenum::{Prod, Sum};
+
+/**
+Burst-fire modulator parameters
+
+- `T` - command type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The number of cycles in window _N_
+    cycles: u32,
+    /// The number of cycles as value
+    scale: T,
+    /// The rounding offset
+    half: T,
+}
+
+impl<T> Param<T> {
+    /**
+    Init burst-fire modulator parameters
+
+    - `cycles`: The number of mains cycles in window _N_
+     */
+    pub fn new(cycles: u32) -> Self
+    where
+        T: Cast<f64>,
+    {
+        assert!(cycles > 0);
+
+        Self {
+            cycles,
+            scale: T::cast(cycles as f64),
+            half: T::cast(0.5),
+        }
+    }
+
+    /// The number of cycles in window
+    pub fn cycles(&self) -> u32 {
+        self.cycles
+    }
+}
+
+/**
+Burst-fire modulator state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The actual cycle in window
+    index: u32,
+    /// The number of on-cycles in actual window
+    count: u32,
+    /// The Bresenham accumulator
+    accum: u32,
+}
+
+impl State {
+    /// The number of on-cycles in actual window
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl ResetState for State {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State {
+        index: u32,
+        count: u32,
+        accum: u32,
+    }
+}
+
+/**
+Burst-fire modulator
+
+- `T` - command type
+
+The input is a power command in range _0..1_ (out of range values is clamped).
+The output is a state of switch for the next mains cycle.
+
+The modulator should be applied once per mains cycle (usually at zero crossing).
+*/
+#[derive(Debug)]
+pub struct BurstFire<T>(PhantomData<T>);
+
+impl<T> Transducer for BurstFire<T>
+where
+    T: Copy + PartialOrd + Cast<f64> + Mul<T> + Cast<Prod<T, T>> + Add<T> + Cast<Sum<T, T>>,
+    u32: Cast<T>,
+{
+    type Input = T;
+    type Output = bool;
+    type Param = Param<T>;
+    type State = State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, command: Self::Input) -> Self::Output {
+        if state.index == 0 {
+            // k = round(u N)
+            state.count = if command <= T::cast(0.0) {
+                0
+            } else if command >= T::cast(1.0) {
+                param.cycles
+            } else {
+                u32::cast(T::cast(T::cast(command * param.scale) + param.half)).min(param.cycles)
+            };
+        }
+
+        state.index += 1;
+        if state.index >= param.cycles {
+            state.index = 0;
+        }
+
+        state.accum += state.count;
+        if state.accum >= param.cycles {
+            state.accum -= param.cycles;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    fn pattern<T>(param: &Param<T>, state: &mut State, command: T) -> [bool; 10]
+    where
+        BurstFire<T>: Transducer<Input = T, Output = bool, Param = Param<T>, State = State>,
+        T: Copy,
+    {
+        let mut result = [false; 10];
+        for value in result.iter_mut() {
+            *value = BurstFire::<T>::apply(param, state, command);
+        }
+        result
+    }
+
+    #[test]
+    fn burst_f32() {
+        let param = Param::<f32>::new(10);
+        let mut state = State::default();
+
+        assert_eq!(pattern(&param, &mut state, 0.0), [false; 10]);
+        assert_eq!(pattern(&param, &mut state, 1.0), [true; 10]);
+        assert_eq!(pattern(&param, &mut state, 2.0), [true; 10]);
+        assert_eq!(pattern(&param, &mut state, -1.0), [false; 10]);
+
+        state.reset();
+        let on = pattern(&param, &mut state, 0.5);
+        assert_eq!(
+            on,
+            [false, true, false, true, false, true, false, true, false, true]
+        );
+
+        state.reset();
+        let on = pattern(&param, &mut state, 0.3);
+        assert_eq!(on.iter().filter(|on| **on).count(), 3);
+        assert_eq!(state.count(), 3);
+        // uniformly distributed
+        assert_eq!(
+            on,
+            [false, false, false, true, false, false, true, false, false, true]
+        );
+    }
+
+    #[test]
+    fn burst_window() {
+        let param = Param::<f64>::new(8);
+        let mut state = State::default();
+
+        // the command is latched per window
+        assert!(!BurstFire::<f64>::apply(&param, &mut state, 0.25));
+        let count = (1..8)
+            .map(|_| BurstFire::<f64>::apply(&param, &mut state, 1.0))
+            .filter(|on| *on)
+            .count();
+        assert_eq!(count, 2);
+
+        // the mean power over many windows
+        let count = (0..800)
+            .map(|_| BurstFire::<f64>::apply(&param, &mut state, 0.6))
+            .filter(|on| *on)
+            .count();
+        // round(0.6 * 8) = 5
+        assert_eq!(count, 500);
+    }
+
+    #[test]
+    fn burst_fix() {
+        type T = Fix<P32, N16>;
+        let param = Param::<T>::new(10);
+        let mut state = State::default();
+
+        let on = pattern(&param, &mut state, T::cast(0.74));
+        assert_eq!(on.iter().filter(|on| **on).count(), 7);
+        assert_eq!(state.count(), 7);
+    }
+}
@@ -4,6 +4,137 @@
 
 This module implements Direct-Quadrature-Zero (DQZ) transformation.
 
+The [`Dq`] value type represents the two-phase quantities in the rotating frame,
+which is aligned with the rotor flux (or voltage) vector.
+The Park transformation is expressed as a conversion between [`AlphaBeta`] and [`Dq`]
+which takes the sine and cosine of the angle of rotating frame
+(which can be evaluated using [`SinCos`](crate::SinCos) or lookup table):
+
+_d = α cos θ + β sin θ_
+
+_q = -α sin θ + β cos θ_
+
+The inverse transformation:
+
+_α = d cos θ - q sin θ_
+
+_β = d sin θ + q cos θ_
+
+The zero-sequence component is dropped by Clarke transformation,
+so the three-phase values can be converted using [`AlphaBeta`] as intermediate frame.
+
 See also [DQZ](https://en.wikipedia.org/wiki/Direct-quadrature-zero_transformation).
 
  */
+
+use super::ab::{vector_ops, AlphaBeta};
+use crate::Cast;
+use core::ops::{Add, Mul, Sub};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Two-phase values in rotating frame
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dq<T> {
+    /// Direct component
+    pub d: T,
+    /// Quadrature component
+    pub q: T,
+}
+
+impl<T> Dq<T> {
+    /// Create rotating frame values
+    pub fn new(d: T, q: T) -> Self {
+        Self { d, q }
+    }
+}
+
+impl<T> Dq<T>
+where
+    T: Copy + Add<T> + Sub<T> + Mul<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>> + Cast<Prod<T, T>>,
+{
+    /**
+    Inverse Park transformation
+
+    - `angle`: The sine and cosine of angle of rotating frame
+     */
+    pub fn to_alpha_beta(self, (sin, cos): (T, T)) -> AlphaBeta<T> {
+        AlphaBeta {
+            alpha: T::cast(T::cast(self.d * cos) - T::cast(self.q * sin)),
+            beta: T::cast(T::cast(self.d * sin) + T::cast(self.q * cos)),
+        }
+    }
+}
+
+impl<T> AlphaBeta<T>
+where
+    T: Copy + Add<T> + Sub<T> + Mul<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>> + Cast<Prod<T, T>>,
+{
+    /**
+    Park transformation
+
+    - `angle`: The sine and cosine of angle of rotating frame
+     */
+    pub fn to_dq(self, (sin, cos): (T, T)) -> Dq<T> {
+        Dq {
+            d: T::cast(T::cast(self.alpha * cos) + T::cast(self.beta * sin)),
+            q: T::cast(T::cast(self.beta * cos) - T::cast(self.alpha * sin)),
+        }
+    }
+}
+
+vector_ops!(Dq { d, q });
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{transform::ab::Abc, SinCos};
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn park_f32() {
+        // the rotating vector is constant in the frame which rotates synchronously
+        for step in 0..12 {
+            let angle = step as f32 * 0.5;
+            let phase = 2.0 * core::f32::consts::PI / 3.0;
+            let abc = Abc::new(
+                1.5 * (angle + 0.2).cos(),
+                1.5 * (angle + 0.2 - phase).cos(),
+                1.5 * (angle + 0.2 + phase).cos(),
+            );
+
+            let angle = SinCos::sin_cos(angle);
+            let dq = AlphaBeta::from(abc).to_dq(angle);
+            assert!((dq.d - 1.5 * 0.2f32.cos()).abs() < 1e-5, "{:?}", dq);
+            assert!((dq.q - 1.5 * 0.2f32.sin()).abs() < 1e-5, "{:?}", dq);
+
+            let abc2 = Abc::from(dq.to_alpha_beta(angle));
+            assert!((abc2.a - abc.a).abs() < 1e-5);
+            assert!((abc2.b - abc.b).abs() < 1e-5);
+            assert!((abc2.c - abc.c).abs() < 1e-5);
+        }
+
+        assert_eq!(Dq::new(1.0, 2.0) - Dq::new(0.5, 0.5), Dq::new(0.5, 1.5));
+    }
+
+    #[test]
+    fn park_fix() {
+        type T = Fix<P32, N16>;
+
+        let (sin, cos) = SinCos::sin_cos(0.5f64);
+        let angle = (T::cast(sin), T::cast(cos));
+        let ab = AlphaBeta::new(T::cast(2.0 * cos), T::cast(2.0 * sin));
+
+        let dq = ab.to_dq(angle);
+        assert!((f64::cast(dq.d) - 2.0).abs() < 1e-3);
+        assert!(f64::cast(dq.q).abs() < 1e-3);
+
+        let ab2 = dq.to_alpha_beta(angle);
+        assert!((f64::cast(ab2.alpha) - f64::cast(ab.alpha)).abs() < 1e-3);
+        assert!((f64::cast(ab2.beta) - f64::cast(ab.beta)).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,205 @@
+/*!
+
+## Phase-angle control
+
+This module implements the conversion of power command to the **firing delay** of TRIAC
+(or pair of thyristors) for phase-angle control of dimmers and heaters.
+
+The TRIAC is fired after delay _t_ since zero crossing of mains voltage and conducts
+until the end of half-period _H_. The power of resistive load at firing angle _α = π t / H_
+is proportional to the integral of _sin²_:
+
+_u = 1 - α / π + sin(2 α) / (2 π)_
+
+The naive linear mapping of command to delay gives significant error of power
+(the curve is flat near zero and full power and steep near the half of power).
+So the inverse of power curve is sampled at initialization into _N_ points uniformly distributed
+over the range of power and evaluated using [lookup table](crate::lut) which requires
+only single multiplication.
+
+The output delay is in the units of half-period, so the half-period can be given
+in seconds or directly in timer ticks.
+
+When the harmonics and interference of phase-angle control is undesired
+the [burst-fire modulation](crate::burst) can be used instead.
+
+*/
+
+use crate::{
+    lut::{Param as LutParam, Table},
+    Cast, SinCos, Transducer,
+};
+use core::{
+    f64::consts::PI,
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Sum};
+
+/// The relative power at firing angle
+fn power(angle: f64) -> f64 {
+    1.0 - angle / PI + SinCos::sin_cos(2.0 * angle).0 / (2.0 * PI)
+}
+
+/// The firing angle for relative power (the power curve is inverted using bisection)
+fn angle(power: f64) -> f64 {
+    let mut range = (0.0, PI);
+
+    for _ in 0..48 {
+        let middle = 0.5 * (range.0 + range.1);
+        if self::power(middle) > power {
+            range.0 = middle;
+        } else {
+            range.1 = middle;
+        }
+    }
+
+    0.5 * (range.0 + range.1)
+}
+
+/**
+Phase-angle control parameters
+
+- `T` - power command type
+- `D` - delay type
+- `K` - slopes type
+- `N` - the number of points of linearization table
+*/
+#[derive(Debug, Clone)]
+pub struct Param<T, D, K, N>
+where
+    N: ArrayLength<T> + ArrayLength<D> + ArrayLength<K>,
+{
+    /// The linearization table
+    table: LutParam<T, D, K, N>,
+}
+
+impl<T, D, K, N> Param<T, D, K, N>
+where
+    N: ArrayLength<T> + ArrayLength<D> + ArrayLength<K> + ArrayLength<(f64, f64)>,
+{
+    /**
+    Init phase-angle control parameters
+
+    - `half_period`: The half-period of mains voltage _H_ (seconds or timer ticks)
+     */
+    pub fn new<V>(half_period: V) -> Self
+    where
+        T: Cast<f64>,
+        D: Cast<f64>,
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let half_period = f64::cast(half_period);
+        let last = (N::USIZE - 1) as f64;
+
+        let points = GenericArray::<(f64, f64), N>::generate(|i| {
+            let power = i as f64 / last;
+            let delay = if i == 0 {
+                half_period
+            } else if i == N::USIZE - 1 {
+                0.0
+            } else {
+                angle(power) / PI * half_period
+            };
+            (power, delay)
+        });
+
+        Self {
+            table: LutParam::new::<f64>(points.as_slice()),
+        }
+    }
+}
+
+/**
+Firing delay evaluator
+
+- `T` - power command type
+- `D` - delay type
+- `K` - slopes type
+- `N` - the number of points of linearization table
+
+The input is a power command in range _0..1_ (out of range values is clamped).
+The output is a firing delay since zero crossing.
+The zero command gives the delay of full half-period, which means that TRIAC shouldn't be fired.
+*/
+#[derive(Debug)]
+pub struct FiringDelay<T, D, K, N>(PhantomData<(T, D, K, N)>);
+
+impl<T, D, K, N> Transducer for FiringDelay<T, D, K, N>
+where
+    T: Copy + PartialOrd + Sub<T>,
+    D: Copy + Add<D> + Cast<Prod<K, Diff<T, T>>> + Cast<Sum<D, D>>,
+    K: Copy + Mul<Diff<T, T>>,
+    N: ArrayLength<T> + ArrayLength<D> + ArrayLength<K>,
+{
+    type Input = T;
+    type Output = D;
+    type Param = Param<T, D, K, N>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, command: Self::Input) -> Self::Output {
+        Table::<T, D, K, N>::apply(&param.table, &mut (), command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P24, P32, U33};
+    use ufix::bin::Fix;
+
+    /// The relative power at delay
+    fn actual(delay: f64, half_period: f64) -> f64 {
+        let angle = delay / half_period * PI;
+        1.0 - angle / PI + (2.0 * angle).sin() / (2.0 * PI)
+    }
+
+    #[test]
+    fn phase_power_curve() {
+        assert_eq!(power(0.0), 1.0);
+        assert!(power(PI).abs() < 1e-12);
+        assert!((power(0.5 * PI) - 0.5).abs() < 1e-12);
+        assert!((angle(0.5) - 0.5 * PI).abs() < 1e-12);
+        assert!((power(angle(0.2)) - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn phase_f32() {
+        type F = FiringDelay<f32, f32, f32, U33>;
+        // 50 Hz mains
+        let param = Param::<f32, f32, f32, U33>::new(0.01);
+
+        assert_eq!(F::apply(&param, &mut (), 0.0), 0.01);
+        assert_eq!(F::apply(&param, &mut (), -0.5), 0.01);
+        assert_eq!(F::apply(&param, &mut (), 1.0), 0.0);
+        assert_eq!(F::apply(&param, &mut (), 1.5), 0.0);
+        assert!((F::apply(&param, &mut (), 0.5) - 0.005).abs() < 1e-6);
+
+        for step in 1..20 {
+            let command = step as f64 * 0.05;
+            let delay = F::apply(&param, &mut (), command as f32) as f64;
+            let error = actual(delay, 0.01) - command;
+            assert!(error.abs() < 0.01, "command: {} error: {}", command, error);
+        }
+    }
+
+    #[test]
+    fn phase_fix() {
+        type T = Fix<P24, N16>;
+        type D = Fix<P32, N8>;
+        type K = Fix<P32, N8>;
+        type F = FiringDelay<T, D, K, U33>;
+        // 60 Hz mains, 1 MHz timer
+        let half_period = 1e6 / 120.0;
+        let param = Param::<T, D, K, U33>::new(half_period);
+
+        for step in 1..20 {
+            let command = step as f64 * 0.05;
+            let delay = f64::cast(F::apply(&param, &mut (), T::cast(command)));
+            let error = actual(delay, half_period) - command;
+            assert!(error.abs() < 0.01, "command: {} error: {}", command, error);
+        }
+    }
+}
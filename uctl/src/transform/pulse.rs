@@ -0,0 +1,283 @@
+/*!
+
+## Pulse shaper
+
+This module implements the shaper which converts continuous command in range _0..1_ to the pulses
+of on/off actuator (like valves or compressors) which requires the minimum on and off durations
+and limits the switching frequency.
+
+The shaper tracks the accumulated demand (the integral of difference between command and output)
+as first order sigma-delta modulator:
+
+_a = a + u - y_
+
+The output is switched on when the accumulated demand is positive and switched off
+when the demand was satisfied, but the switching is delayed until the minimum on
+or off time since the last switch is elapsed and the minimum cycle time since
+the last switching on is elapsed. Since the demand is accumulated while switching is delayed,
+the mean value of output follows the command.
+
+After reset the minimum off time is enforced.
+
+*/
+
+use crate::{snapshot_fields, steps, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+use typenum::{Diff, Sum};
+
+/**
+Pulse shaper parameters
+
+- `T` - command type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The minimum on time in steps
+    min_on: u32,
+    /// The minimum off time in steps
+    min_off: u32,
+    /// The minimum cycle time in steps
+    min_cycle: u32,
+    /// The full command value
+    one: T,
+}
+
+impl<T> Param<T> {
+    /**
+    Init pulse shaper parameters
+
+    - `min_on`: The minimum on time (s)
+    - `min_off`: The minimum off time (s)
+    - `max_frequency`: The maximum switching frequency (Hz)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<V>(min_on: V, min_off: V, max_frequency: V, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let period = f64::cast(period);
+
+        Self {
+            min_on: steps(f64::cast(min_on), period),
+            min_off: steps(f64::cast(min_off), period),
+            min_cycle: steps(1.0 / f64::cast(max_frequency), period),
+            one: T::cast(1.0),
+        }
+    }
+}
+
+/**
+Pulse shaper state
+
+- `T` - command type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The output state
+    on: bool,
+    /// The number of steps since last switch
+    elapsed: u32,
+    /// The number of steps since last switching on
+    cycle: u32,
+    /// The accumulated demand
+    demand: T,
+}
+
+impl<T> State<T> {
+    /// The output state
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// The accumulated demand (in steps of full command)
+    pub fn demand(&self) -> T
+    where
+        T: Copy,
+    {
+        self.demand
+    }
+}
+
+/// The preset of pulse shaper sets the output state (the minimum time is enforced after preset)
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = bool;
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, on: Self::Value) {
+        *self = Self::default();
+        self.on = on;
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        on: bool,
+        elapsed: u32,
+        cycle: u32,
+        demand: T,
+    }
+}
+
+/**
+Pulse shaper
+
+- `T` - command type
+
+The input is a command in range _0..1_ (out of range values is clamped).
+The output is a state of actuator.
+*/
+#[derive(Debug)]
+pub struct PulseShaper<T>(PhantomData<T>);
+
+impl<T> Transducer for PulseShaper<T>
+where
+    T: Copy + PartialOrd + Default + Add<T> + Sub<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+{
+    type Input = T;
+    type Output = bool;
+    type Param = Param<T>;
+    type State = State<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, command: Self::Input) -> Self::Output {
+        let zero = T::default();
+        let command = if command < zero {
+            zero
+        } else if command > param.one {
+            param.one
+        } else {
+            command
+        };
+
+        state.elapsed = state.elapsed.saturating_add(1);
+        state.cycle = state.cycle.saturating_add(1);
+        state.demand = T::cast(state.demand + command);
+
+        if state.on {
+            if state.demand <= zero && state.elapsed >= param.min_on {
+                state.on = false;
+                state.elapsed = 0;
+            }
+        } else if state.demand > zero
+            && state.elapsed >= param.min_off
+            && state.cycle >= param.min_cycle
+        {
+            state.on = true;
+            state.elapsed = 0;
+            state.cycle = 0;
+        }
+
+        if state.on {
+            state.demand = T::cast(state.demand - param.one);
+        }
+
+        state.on
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    /// Run shaper and get the number of on steps and the number of switchings on
+    fn run<T>(param: &Param<T>, state: &mut State<T>, command: T, steps: usize) -> (usize, usize)
+    where
+        PulseShaper<T>: Transducer<Input = T, Output = bool, Param = Param<T>, State = State<T>>,
+        T: Copy,
+    {
+        let (mut on, mut pulses) = (0, 0);
+        let mut last = state.is_on();
+        for _ in 0..steps {
+            let output = PulseShaper::<T>::apply(param, state, command);
+            if output {
+                on += 1;
+            }
+            if output && !last {
+                pulses += 1;
+            }
+            last = output;
+        }
+        (on, pulses)
+    }
+
+    #[test]
+    fn pulse_mean() {
+        // 0.1 s period, 1 s minimum on, 2 s minimum off, no frequency limit
+        let param = Param::<f64>::new(1.0, 2.0, 10.0, 0.1);
+        let mut state = State::default();
+
+        run(&param, &mut state, 0.3, 100);
+        let (on, _) = run(&param, &mut state, 0.3, 1000);
+        assert!((on as f64 / 1000.0 - 0.3).abs() < 0.02);
+
+        let (on, _) = run(&param, &mut state, 0.05, 2000);
+        assert!((on as f64 / 2000.0 - 0.05).abs() < 0.01);
+
+        // full command
+        let (on, _) = run(&param, &mut state, 1.5, 100);
+        assert!(on >= 99);
+
+        let (on, _) = run(&param, &mut state, -1.0, 100);
+        assert!(on <= 1);
+    }
+
+    #[test]
+    fn pulse_min_times() {
+        let param = Param::<f32>::new(3.0, 5.0, 1.0, 1.0);
+        let mut state = State::default();
+
+        // the first interval after reset is skipped
+        let mut last = (false, 0usize);
+        let mut lengths = (usize::MAX, usize::MAX);
+        for step in 0..1000 {
+            let output = PulseShaper::<f32>::apply(&param, &mut state, 0.5);
+            if output != last.0 {
+                if last.1 < step {
+                    if last.0 {
+                        lengths.0 = lengths.0.min(last.1);
+                    } else {
+                        lengths.1 = lengths.1.min(last.1);
+                    }
+                }
+                last = (output, 1);
+            } else {
+                last.1 += 1;
+            }
+        }
+        // the minimum on and off durations is respected
+        assert!(lengths.0 >= 3 && lengths.1 >= 5, "{:?}", lengths);
+    }
+
+    #[test]
+    fn pulse_frequency() {
+        // 0.1 s minimum on and off, but at most 0.5 Hz
+        let param = Param::<f64>::new(0.1, 0.1, 0.5, 0.1);
+        let mut state = State::default();
+
+        let (on, pulses) = run(&param, &mut state, 0.5, 1000);
+        // 100 s, at most 50 pulses
+        assert!(pulses <= 50);
+        assert!((on as f64 / 1000.0 - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn pulse_fix() {
+        type T = Fix<P32, N16>;
+        let param = Param::<T>::new(1.0, 1.0, 10.0, 0.1);
+        let mut state = State::default();
+
+        let (on, _) = run(&param, &mut state, T::cast(0.25), 1000);
+        assert!((on as f64 / 1000.0 - 0.25).abs() < 0.02);
+    }
+}
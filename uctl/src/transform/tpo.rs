@@ -0,0 +1,286 @@
+/*!
+
+## Time-proportioned output
+
+This module implements **time-proportioning** output (TPO) which is the usual interface
+between regulators and relay-driven actuators (like heaters).
+
+The command _u_ in range _0..1_ is latched at the beginning of each cycle of _C_ steps and
+the output is on during first _k_ steps of cycle:
+
+_k = ⌊u C + r⌋_, _r = u C + r - k_
+
+The remainder of quantization _r_ is carried to the next cycle (dithering),
+so the mean value of output follows the command precisely even with short cycles.
+
+The cycle can be shifted by phase to interleave the several outputs which share
+the same power supply.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Time-proportioned output parameters
+
+- `T` - command type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The number of steps in cycle _C_
+    cycle: u32,
+    /// The number of steps in cycle as value
+    scale: T,
+    /// The shift of cycle in steps
+    shift: u32,
+}
+
+impl<T> Param<T> {
+    /**
+    Init time-proportioned output parameters
+
+    - `cycle`: The cycle time (s)
+    - `period`: The sampling period (s)
+     */
+    pub fn new<V>(cycle: V, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let cycle = (f64::cast(cycle) / f64::cast(period) + 0.5) as u32;
+
+        assert!(cycle > 0);
+
+        Self {
+            cycle,
+            scale: T::cast(cycle as f64),
+            shift: 0,
+        }
+    }
+
+    /**
+    Set the phase of cycle
+
+    - `phase`: The phase as a fraction of cycle (0..1)
+     */
+    pub fn with_phase<V>(mut self, phase: V) -> Self
+    where
+        f64: Cast<V>,
+    {
+        let phase = (f64::cast(phase) * self.cycle as f64 + 0.5) as u32 % self.cycle;
+        self.shift = (self.cycle - phase) % self.cycle;
+        self
+    }
+
+    /// The number of steps in cycle
+    pub fn cycle(&self) -> u32 {
+        self.cycle
+    }
+}
+
+/**
+Time-proportioned output state
+
+- `T` - command type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The actual step of unshifted cycle
+    index: u32,
+    /// The number of on steps in actual cycle
+    count: u32,
+    /// The remainder of quantization
+    residual: T,
+}
+
+impl<T> State<T> {
+    /// The number of on steps in actual cycle
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// The preset of time-proportioned output is same as reset
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        index: u32,
+        count: u32,
+        residual: T,
+    }
+}
+
+/**
+Time-proportioned output
+
+- `T` - command type
+
+The input is a command in range _0..1_ (out of range values is clamped).
+The output is a state of actuator.
+*/
+#[derive(Debug)]
+pub struct Tpo<T>(PhantomData<T>);
+
+impl<T> Transducer for Tpo<T>
+where
+    T: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Cast<u32>
+        + Add<T>
+        + Sub<T>
+        + Mul<T>
+        + Cast<Prod<T, T>>
+        + Cast<Sum<T, T>>
+        + Cast<Diff<T, T>>,
+    u32: Cast<T>,
+{
+    type Input = T;
+    type Output = bool;
+    type Param = Param<T>;
+    type State = State<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, command: Self::Input) -> Self::Output {
+        let position = (state.index + param.shift) % param.cycle;
+
+        if position == 0 {
+            let zero = T::cast(0.0);
+            let one = T::cast(1.0);
+            let command = if command < zero {
+                zero
+            } else if command > one {
+                one
+            } else {
+                command
+            };
+
+            // k = ⌊u C + r⌋
+            let demand = T::cast(T::cast(command * param.scale) + state.residual);
+            let count = if demand > zero {
+                u32::cast(demand).min(param.cycle)
+            } else {
+                0
+            };
+
+            state.residual = T::cast(demand - T::cast(count));
+            state.count = count;
+        }
+
+        state.index = (state.index + 1) % param.cycle;
+
+        position < state.count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    fn pattern<T>(param: &Param<T>, state: &mut State<T>, command: T) -> [bool; 10]
+    where
+        Tpo<T>: Transducer<Input = T, Output = bool, Param = Param<T>, State = State<T>>,
+        T: Copy,
+    {
+        let mut result = [false; 10];
+        for value in result.iter_mut() {
+            *value = Tpo::<T>::apply(param, state, command);
+        }
+        result
+    }
+
+    #[test]
+    fn tpo_f32() {
+        // 1 s cycle, 0.1 s period
+        let param = Param::<f32>::new(1.0, 0.1);
+        let mut state = State::default();
+
+        assert_eq!(param.cycle(), 10);
+        assert_eq!(pattern(&param, &mut state, 0.0), [false; 10]);
+        assert_eq!(pattern(&param, &mut state, 1.0), [true; 10]);
+        assert_eq!(pattern(&param, &mut state, 2.0), [true; 10]);
+        assert_eq!(pattern(&param, &mut state, -1.0), [false; 10]);
+
+        state.reset();
+        let on = pattern(&param, &mut state, 0.3);
+        assert_eq!(
+            on,
+            [true, true, true, false, false, false, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn tpo_dithering() {
+        let param = Param::<f64>::new(1.0, 0.1);
+        let mut state = State::default();
+
+        let counts: [u32; 4] = {
+            let mut counts = [0; 4];
+            for count in counts.iter_mut() {
+                pattern(&param, &mut state, 0.35);
+                *count = state.count();
+            }
+            counts
+        };
+        assert_eq!(counts, [3, 4, 3, 4]);
+
+        // the mean over many cycles
+        let mut on = 0;
+        for _ in 0..100 {
+            on += pattern(&param, &mut state, 0.123)
+                .iter()
+                .filter(|on| **on)
+                .count();
+        }
+        assert!((on as i32 - 123).abs() <= 1);
+    }
+
+    #[test]
+    fn tpo_phase() {
+        let param = Param::<f32>::new(1.0, 0.1).with_phase(0.5);
+        let mut state = State::default();
+
+        // the cycles begins at the half of cycle of unshifted output
+        let on = [
+            false, false, false, false, false, true, true, true, false, false,
+        ];
+        assert_eq!(pattern(&param, &mut state, 0.3), on);
+        assert_eq!(pattern(&param, &mut state, 0.3), on);
+    }
+
+    #[test]
+    fn tpo_fix() {
+        type T = Fix<P32, N16>;
+        let param = Param::<T>::new(1.0, 0.1);
+        let mut state = State::default();
+
+        let mut on = 0;
+        for _ in 0..10 {
+            on += pattern(&param, &mut state, T::cast(0.25))
+                .iter()
+                .filter(|on| **on)
+                .count();
+        }
+        assert_eq!(on, 25);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod registry;
+pub mod report;
+pub mod rules;
@@ -0,0 +1,381 @@
+/*!
+
+## Parameter registry
+
+This module implements registry of tunable parameters (gains, limits and etc.)
+which can be accessed at runtime generically by numeric IDs.
+
+The registry is a static table of [`Entry`] descriptors which refer to the fields of user-defined
+settings structure through the accessor functions. The values is accessed as scaled integers
+(see [`Scaled`]), so tuning tools doesn't need to know the actual types of parameters.
+
+When the value was changed the registry calls notification function which should re-derive
+the dependent transducer parameters (like filter coefficients) from the changed values.
+
+The registry doesn't require allocations, so it is suitable for bare-metal targets.
+It also can apply the frames received using [parameter protocol](crate::frame).
+
+*/
+
+use crate::{frame::Frame, Cast};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/// Registry access errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Unknown parameter ID
+    Unknown,
+    /// Value out of range
+    Range,
+    /// Invalid value encoding
+    Format,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Error::Unknown => "Unknown parameter",
+            Error::Range => "Value out of range",
+            Error::Format => "Invalid value format",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/**
+The value which can be represented as scaled integer
+
+The integers is represented as is, the fixed-point values is represented by mantissa.
+The values should fit into 32 bits.
+*/
+pub trait Scaled {
+    /// Convert value into scaled integer
+    fn to_scaled(&self) -> i32;
+
+    /// Convert scaled integer into value
+    fn from_scaled(raw: i32) -> Self;
+}
+
+macro_rules! scaled_impl {
+    ($($type: ty),*) => {
+        $(
+            impl Scaled for $type {
+                fn to_scaled(&self) -> i32 {
+                    *self as i32
+                }
+
+                fn from_scaled(raw: i32) -> Self {
+                    raw as Self
+                }
+            }
+        )*
+    };
+}
+
+scaled_impl!(u8, u16, u32, i8, i16, i32);
+
+impl Scaled for bool {
+    fn to_scaled(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_scaled(raw: i32) -> Self {
+        raw != 0
+    }
+}
+
+impl<R, B, E> Scaled for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Copy + Cast<i32>,
+    i32: Cast<Mantissa<R, B>>,
+{
+    fn to_scaled(&self) -> i32 {
+        i32::cast(self.bits)
+    }
+
+    fn from_scaled(raw: i32) -> Self {
+        Self::new(Mantissa::<R, B>::cast(raw))
+    }
+}
+
+/**
+Parameter descriptor
+
+- `S` - settings type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<S> {
+    /// Parameter ID
+    id: u16,
+    /// Value getter
+    get: fn(&S) -> i32,
+    /// Value setter
+    set: fn(&mut S, i32),
+    /// The minimum allowed scaled value
+    min: i32,
+    /// The maximum allowed scaled value
+    max: i32,
+}
+
+impl<S> Entry<S> {
+    /**
+    Create parameter descriptor
+
+    - `id`: The unique ID of parameter
+    - `get`: The function which gets scaled value from settings
+    - `set`: The function which sets scaled value to settings
+    - `min`: The minimum allowed scaled value
+    - `max`: The maximum allowed scaled value
+
+    The range should not exceed the range of actual type of parameter,
+    because the setter usually narrows the scaled value.
+     */
+    pub const fn new(
+        id: u16,
+        get: fn(&S) -> i32,
+        set: fn(&mut S, i32),
+        min: i32,
+        max: i32,
+    ) -> Self {
+        Self {
+            id,
+            get,
+            set,
+            min,
+            max,
+        }
+    }
+
+    /// Parameter ID
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+}
+
+/**
+Parameter registry
+
+- `S` - settings type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Registry<'a, S> {
+    /// Parameter descriptors
+    entries: &'a [Entry<S>],
+    /// Change notification
+    notify: fn(&mut S, u16),
+}
+
+impl<'a, S> Registry<'a, S> {
+    /**
+    Create parameter registry
+
+    - `entries`: The table of parameter descriptors
+    - `notify`: The function which is called with ID of parameter after it was changed
+     */
+    pub const fn new(entries: &'a [Entry<S>], notify: fn(&mut S, u16)) -> Self {
+        Self { entries, notify }
+    }
+
+    /// Parameter descriptors
+    pub fn entries(&self) -> &'a [Entry<S>] {
+        self.entries
+    }
+
+    fn entry(&self, id: u16) -> Result<&'a Entry<S>, Error> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .ok_or(Error::Unknown)
+    }
+
+    /// Get scaled value of parameter
+    pub fn get(&self, settings: &S, id: u16) -> Result<i32, Error> {
+        self.entry(id).map(|entry| (entry.get)(settings))
+    }
+
+    /**
+    Set scaled value of parameter
+
+    The change notification will be called when the value was changed actually.
+     */
+    pub fn set(&self, settings: &mut S, id: u16, value: i32) -> Result<(), Error> {
+        let entry = self.entry(id)?;
+
+        if value < entry.min || value > entry.max {
+            return Err(Error::Range);
+        }
+
+        if (entry.get)(settings) != value {
+            (entry.set)(settings, value);
+            (self.notify)(settings, id);
+        }
+
+        Ok(())
+    }
+
+    /**
+    Apply received parameter frame
+
+    The payload of frame should be a scaled value.
+     */
+    pub fn apply(&self, settings: &mut S, frame: &Frame) -> Result<(), Error> {
+        let value = frame.read::<i32>().map_err(|_| Error::Format)?;
+        self.set(settings, frame.id, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, frame, Transducer};
+    use typenum::{N8, P16, U16};
+    use ufix::bin::Fix;
+
+    type G = Fix<P16, N8>;
+
+    struct Settings {
+        gain: G,
+        period: u16,
+        enable: bool,
+        level: u8,
+        filter: ema::Param<f32>,
+        derived: u8,
+    }
+
+    const GAIN: u16 = 1;
+    const PERIOD: u16 = 2;
+    const ENABLE: u16 = 3;
+    const LEVEL: u16 = 4;
+
+    static ENTRIES: [Entry<Settings>; 4] = [
+        Entry::new(
+            GAIN,
+            |s: &Settings| s.gain.to_scaled(),
+            |s: &mut Settings, v| s.gain = G::from_scaled(v),
+            0,
+            10 << 8,
+        ),
+        Entry::new(
+            PERIOD,
+            |s: &Settings| s.period.to_scaled(),
+            |s: &mut Settings, v| s.period = u16::from_scaled(v),
+            1,
+            1000,
+        ),
+        Entry::new(
+            ENABLE,
+            |s: &Settings| s.enable.to_scaled(),
+            |s: &mut Settings, v| s.enable = bool::from_scaled(v),
+            0,
+            1,
+        ),
+        Entry::new(
+            LEVEL,
+            |s: &Settings| s.level.to_scaled(),
+            |s: &mut Settings, v| s.level = u8::from_scaled(v),
+            u8::MIN as i32,
+            u8::MAX as i32,
+        ),
+    ];
+
+    fn notify(settings: &mut Settings, id: u16) {
+        if id == PERIOD {
+            settings.filter = ema::Param::from_steps(settings.period as f32);
+        }
+        settings.derived += 1;
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            gain: G::cast(1.0),
+            period: 3,
+            enable: false,
+            level: 0,
+            filter: ema::Param::from_steps(3.0),
+            derived: 0,
+        }
+    }
+
+    #[test]
+    fn registry_get_set() {
+        let registry = Registry::new(&ENTRIES, notify);
+        let mut settings = settings();
+
+        assert_eq!(registry.get(&settings, GAIN), Ok(256));
+        assert_eq!(registry.get(&settings, PERIOD), Ok(3));
+        assert_eq!(registry.get(&settings, 5), Err(Error::Unknown));
+
+        assert_eq!(registry.set(&mut settings, GAIN, 384), Ok(()));
+        assert_eq!(settings.gain, G::cast(1.5));
+        assert_eq!(settings.derived, 1);
+
+        // Unchanged value doesn't notify
+        assert_eq!(registry.set(&mut settings, GAIN, 384), Ok(()));
+        assert_eq!(settings.derived, 1);
+
+        assert_eq!(registry.set(&mut settings, GAIN, -1), Err(Error::Range));
+        assert_eq!(registry.set(&mut settings, PERIOD, 0), Err(Error::Range));
+        assert_eq!(registry.set(&mut settings, 5, 0), Err(Error::Unknown));
+        assert_eq!(settings.derived, 1);
+
+        assert_eq!(registry.set(&mut settings, ENABLE, 1), Ok(()));
+        assert!(settings.enable);
+        assert_eq!(settings.derived, 2);
+    }
+
+    #[test]
+    fn registry_narrow_range() {
+        let registry = Registry::new(&ENTRIES, notify);
+        let mut settings = settings();
+
+        assert_eq!(registry.set(&mut settings, LEVEL, 255), Ok(()));
+        assert_eq!(settings.level, 255);
+
+        // The value which doesn't fit into the type isn't truncated
+        assert_eq!(registry.set(&mut settings, LEVEL, 256), Err(Error::Range));
+        assert_eq!(registry.set(&mut settings, LEVEL, -1), Err(Error::Range));
+        assert_eq!(registry.set(&mut settings, ENABLE, 2), Err(Error::Range));
+        assert_eq!(settings.level, 255);
+        assert!(!settings.enable);
+        assert_eq!(settings.derived, 1);
+    }
+
+    #[test]
+    fn registry_frame() {
+        type E = ema::Filter<f32, f32, f32>;
+
+        let registry = Registry::new(&ENTRIES, notify);
+        let mut settings = settings();
+
+        let mut buf = [0u8; 16];
+        let size = frame::write(PERIOD, &1i32, &mut buf).unwrap();
+
+        let mut parser = frame::Parser::<U16>::new();
+        for byte in &buf[..size] {
+            if let Some(frame) = parser.push(*byte).unwrap() {
+                assert_eq!(registry.apply(&mut settings, &frame), Ok(()));
+            }
+        }
+
+        assert_eq!(settings.period, 1);
+        assert_eq!(settings.derived, 1);
+
+        // The filter with period of 1 sample passes the input as is
+        let mut state = ema::State::new(0.0);
+        assert_eq!(E::apply(&settings.filter, &mut state, 2.0), 2.0);
+
+        let size = frame::write(PERIOD, &1u8, &mut buf).unwrap();
+        for byte in &buf[..size] {
+            if let Some(frame) = parser.push(*byte).unwrap() {
+                assert_eq!(registry.apply(&mut settings, &frame), Err(Error::Format));
+            }
+        }
+    }
+}
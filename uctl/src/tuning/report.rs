@@ -0,0 +1,484 @@
+/*!
+
+## Tuning report
+
+This module implements the [`TuningReport`] which collects the results of tuning in one record
+for logging and traceability: the identified characteristics of [`Plant`], the chosen [`Gains`],
+the expected stability [`Margins`] and the [`Quantization`] errors of coefficients.
+
+The margins is evaluated by frequency response of open loop with [FOPDT](Fopdt) model of process
+and ideal (unfiltered) regulator in parallel form:
+
+_L(jω) = (Kp + Ki / jω + Kd jω) K e<sup>-jωL</sup> / (1 + jωT)_
+
+- The gain margin is an inverse of loop gain at phase crossover frequency (where the phase is -180°)
+- The phase margin is a difference between the phase and -180° at gain crossover frequency (where the gain is 1)
+
+The missing crossover gives the infinite margin.
+
+The quantization errors is a relative errors of coefficients which is used by discrete regulators
+(_Kp_, _Ki P_ and _Kd / P_) when it represented by the coefficients type.
+
+The report can be stored in raw bytes (see [`Snapshot`]) or formatted as a JSON object
+(see [`Display`]) which is useful for logging.
+
+*/
+
+use crate::{
+    rules::{Fopdt, Gains, Ultimate},
+    Atan, Cast, Snapshot, Sqrt,
+};
+use core::{
+    f64::consts::PI,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+/// The identified characteristics of process
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Plant {
+    /// The process isn't identified
+    #[default]
+    Unknown,
+    /// The ultimate characteristics
+    Ultimate(Ultimate),
+    /// The first order plus dead time model
+    Fopdt(Fopdt),
+}
+
+/// The tag is stored before the values (the unused values is zeroed)
+impl Snapshot for Plant {
+    const SIZE: usize = 1 + 3 * f64::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let (tag, values) = match self {
+            Plant::Unknown => (0u8, [0.0; 3]),
+            Plant::Ultimate(ultimate) => (1, [ultimate.gain, ultimate.period, 0.0]),
+            Plant::Fopdt(fopdt) => (2, [fopdt.gain, fopdt.time, fopdt.delay]),
+        };
+        values
+            .iter()
+            .fold(tag.save(buffer), |buffer, value| value.save(buffer))
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let mut tag = 0u8;
+        let mut values = [0.0f64; 3];
+        let buffer = values
+            .iter_mut()
+            .fold(tag.restore(buffer), |buffer, value| value.restore(buffer));
+        *self = match tag {
+            1 => Plant::Ultimate(Ultimate {
+                gain: values[0],
+                period: values[1],
+            }),
+            2 => Plant::Fopdt(Fopdt {
+                gain: values[0],
+                time: values[1],
+                delay: values[2],
+            }),
+            _ => Plant::Unknown,
+        };
+        buffer
+    }
+}
+
+/// The number formatted as JSON value (non-finite values is formatted as `null`)
+struct Number(f64);
+
+impl Display for Number {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.0.is_finite() {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "null")
+        }
+    }
+}
+
+impl Display for Plant {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Plant::Unknown => write!(f, "null"),
+            Plant::Ultimate(Ultimate { gain, period }) => {
+                write!(f, r#"{{"ku":{},"pu":{}}}"#, Number(*gain), Number(*period))
+            }
+            Plant::Fopdt(Fopdt { gain, time, delay }) => {
+                write!(
+                    f,
+                    r#"{{"k":{},"t":{},"l":{}}}"#,
+                    Number(*gain),
+                    Number(*time),
+                    Number(*delay)
+                )
+            }
+        }
+    }
+}
+
+/// The stability margins of loop
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Margins {
+    /// The gain margin (ratio)
+    pub gain: f64,
+    /// The phase margin (degrees)
+    pub phase: f64,
+    /// The gain crossover frequency (rad/s)
+    pub crossover: f64,
+}
+
+impl Margins {
+    /**
+    Evaluate the margins of loop with FOPDT process
+
+    - `plant`: The model of process
+    - `gains`: The gains of regulator
+     */
+    pub fn fopdt(plant: &Fopdt, gains: &Gains) -> Self {
+        // the loop gain and unwrapped phase
+        let response = |w: f64| {
+            let (re, im) = (gains.kp, gains.kd * w - gains.ki / w);
+            let wt = w * plant.time;
+            (
+                Sqrt::sqrt(re * re + im * im) * plant.gain.abs() / Sqrt::sqrt(1.0 + wt * wt),
+                Atan::atan2(im, re) - Atan::atan(wt) - w * plant.delay,
+            )
+        };
+
+        // the frequencies is scanned logarithmically around the characteristic frequency
+        let base = 1e-3 / (plant.time + plant.delay);
+        let find = |f: &dyn Fn(f64) -> f64| {
+            let mut lo = base;
+            while lo < 1e6 * base {
+                let hi = lo * 1.05;
+                if f(lo) > 0.0 && f(hi) <= 0.0 {
+                    // refine the crossover by bisection
+                    let (mut lo, mut hi) = (lo, hi);
+                    for _ in 0..50 {
+                        let mid = Sqrt::sqrt(lo * hi);
+                        if f(mid) > 0.0 {
+                            lo = mid;
+                        } else {
+                            hi = mid;
+                        }
+                    }
+                    return Some(hi);
+                }
+                lo = hi;
+            }
+            None
+        };
+
+        let crossover = find(&|w| response(w).0 - 1.0);
+        let phase_crossover = find(&|w| response(w).1 + PI);
+
+        Self {
+            gain: phase_crossover
+                .map(|w| 1.0 / response(w).0)
+                .unwrap_or(f64::INFINITY),
+            phase: crossover
+                .map(|w| (response(w).1 + PI) * 180.0 / PI)
+                .unwrap_or(f64::INFINITY),
+            crossover: crossover.unwrap_or(0.0),
+        }
+    }
+}
+
+impl Snapshot for Margins {
+    const SIZE: usize = 3 * f64::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.gain.save(buffer);
+        let buffer = self.phase.save(buffer);
+        self.crossover.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let buffer = self.gain.restore(buffer);
+        let buffer = self.phase.restore(buffer);
+        self.crossover.restore(buffer)
+    }
+}
+
+/// The relative quantization errors of coefficients
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Quantization {
+    /// The error of proportional coefficient _Kp_
+    pub kp: f64,
+    /// The error of integral coefficient _Ki P_
+    pub ki: f64,
+    /// The error of derivative coefficient _Kd / P_
+    pub kd: f64,
+}
+
+impl Quantization {
+    /**
+    Evaluate the quantization errors
+
+    - `K` - coefficients type
+    - `gains`: The gains of regulator
+    - `period`: The sampling period _P_ (s)
+     */
+    pub fn new<K>(gains: &Gains, period: f64) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<K>,
+    {
+        let error = |value: f64| {
+            if value == 0.0 {
+                0.0
+            } else {
+                ((f64::cast(K::cast(value)) - value) / value).abs()
+            }
+        };
+
+        Self {
+            kp: error(gains.kp),
+            ki: error(gains.ki * period),
+            kd: error(gains.kd / period),
+        }
+    }
+}
+
+impl Snapshot for Quantization {
+    const SIZE: usize = 3 * f64::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.kp.save(buffer);
+        let buffer = self.ki.save(buffer);
+        self.kd.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let buffer = self.kp.restore(buffer);
+        let buffer = self.ki.restore(buffer);
+        self.kd.restore(buffer)
+    }
+}
+
+/**
+Tuning report
+
+The report is created from chosen gains and extended by available information.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TuningReport {
+    /// The identified characteristics of process
+    pub plant: Plant,
+    /// The gains of regulator
+    pub gains: Gains,
+    /// The sampling period (s)
+    pub period: f64,
+    /// The expected stability margins
+    pub margins: Option<Margins>,
+    /// The quantization errors of coefficients
+    pub quantization: Option<Quantization>,
+}
+
+impl TuningReport {
+    /**
+    Create report
+
+    - `gains`: The gains of regulator
+    - `period`: The sampling period _P_ (s)
+     */
+    pub fn new(gains: Gains, period: f64) -> Self {
+        Self {
+            gains,
+            period,
+            ..Self::default()
+        }
+    }
+
+    /**
+    Set the identified characteristics of process
+
+    The margins is evaluated for FOPDT model.
+     */
+    pub fn with_plant(mut self, plant: Plant) -> Self {
+        self.plant = plant;
+        if let Plant::Fopdt(fopdt) = &plant {
+            self.margins = Some(Margins::fopdt(fopdt, &self.gains));
+        }
+        self
+    }
+
+    /// Set the margins (like measured by experiment)
+    pub fn with_margins(mut self, margins: Margins) -> Self {
+        self.margins = Some(margins);
+        self
+    }
+
+    /**
+    Evaluate the quantization errors of coefficients
+
+    - `K` - coefficients type
+     */
+    pub fn with_quantization<K>(mut self) -> Self
+    where
+        K: Cast<f64>,
+        f64: Cast<K>,
+    {
+        self.quantization = Some(Quantization::new::<K>(&self.gains, self.period));
+        self
+    }
+}
+
+impl Snapshot for TuningReport {
+    const SIZE: usize =
+        Plant::SIZE + 4 * f64::SIZE + <Option<Margins>>::SIZE + <Option<Quantization>>::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.plant.save(buffer);
+        let buffer = self.gains.kp.save(buffer);
+        let buffer = self.gains.ki.save(buffer);
+        let buffer = self.gains.kd.save(buffer);
+        let buffer = self.period.save(buffer);
+        let buffer = self.margins.save(buffer);
+        self.quantization.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let buffer = self.plant.restore(buffer);
+        let buffer = self.gains.kp.restore(buffer);
+        let buffer = self.gains.ki.restore(buffer);
+        let buffer = self.gains.kd.restore(buffer);
+        let buffer = self.period.restore(buffer);
+        let buffer = self.margins.restore(buffer);
+        self.quantization.restore(buffer)
+    }
+}
+
+/// The non-finite values (like infinite margins) is formatted as `null`
+impl Display for TuningReport {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let Gains { kp, ki, kd } = self.gains;
+        write!(
+            f,
+            r#"{{"plant":{},"gains":{{"kp":{},"ki":{},"kd":{}}},"period":{}"#,
+            self.plant,
+            Number(kp),
+            Number(ki),
+            Number(kd),
+            Number(self.period)
+        )?;
+        if let Some(Margins {
+            gain,
+            phase,
+            crossover,
+        }) = self.margins
+        {
+            write!(
+                f,
+                r#","margins":{{"gain":{},"phase":{},"crossover":{}}}"#,
+                Number(gain),
+                Number(phase),
+                Number(crossover)
+            )?;
+        }
+        if let Some(Quantization { kp, ki, kd }) = self.quantization {
+            write!(
+                f,
+                r#","quantization":{{"kp":{},"ki":{},"kd":{}}}"#,
+                Number(kp),
+                Number(ki),
+                Number(kd)
+            )?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N8, P16};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn margins_delay() {
+        // the proportional regulator of pure delay
+        let plant = Fopdt {
+            gain: 1.0,
+            time: 0.0,
+            delay: 1.0,
+        };
+        let margins = Margins::fopdt(&plant, &Gains::from_times(0.5, 0.0, 0.0));
+
+        assert!((margins.gain - 2.0).abs() < 1e-9);
+        assert_eq!(margins.phase, f64::INFINITY);
+    }
+
+    #[test]
+    fn margins_lambda() {
+        let plant = Fopdt {
+            gain: 1.0,
+            time: 10.0,
+            delay: 1.0,
+        };
+        // the loop is an integrator with delay: e^-sL / ((λ + L) s)
+        let margins = Margins::fopdt(&plant, &plant.lambda_pi(1.0));
+
+        assert!((margins.crossover - 0.5).abs() < 1e-9);
+        assert!((margins.phase - (90.0 - 0.5 * 180.0 / PI)).abs() < 1e-6);
+        assert!((margins.gain - PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn report() {
+        extern crate std;
+        use std::format;
+
+        let plant = Fopdt {
+            gain: 2.0,
+            time: 10.0,
+            delay: 1.0,
+        };
+        let report = TuningReport::new(plant.lambda_pi(1.0), 0.1)
+            .with_plant(Plant::Fopdt(plant))
+            .with_quantization::<Fix<P16, N8>>();
+
+        let margins = report.margins.unwrap();
+        assert!(margins.gain > 2.0 && margins.phase > 45.0);
+
+        // kp = 2.5, ki = 0.25 => ki P = 0.025
+        let quantization = report.quantization.unwrap();
+        assert_eq!(quantization.kp, 0.0);
+        assert!((quantization.ki - 0.0625).abs() < 1e-9);
+
+        let mut buffer = [0u8; TuningReport::SIZE];
+        report.save(&mut buffer);
+        let mut restored = TuningReport::default();
+        restored.restore(&buffer);
+        assert_eq!(restored, report);
+
+        assert_eq!(
+            format!(
+                "{}",
+                TuningReport::new(Gains::from_times(0.5, 2.0, 0.0), 0.5)
+            ),
+            r#"{"plant":null,"gains":{"kp":0.5,"ki":0.25,"kd":0},"period":0.5}"#
+        );
+
+        // the non-finite values isn't valid JSON numbers
+        let plant = Plant::Ultimate(Ultimate {
+            gain: f64::INFINITY,
+            period: f64::NAN,
+        });
+        let mut report = TuningReport::new(Gains::from_times(0.5, 0.0, 0.0), 0.5)
+            .with_plant(plant)
+            .with_margins(Margins {
+                gain: f64::INFINITY,
+                phase: 60.0,
+                crossover: 1.0,
+            });
+        report.quantization = Some(Quantization {
+            kp: 0.0,
+            ki: f64::NAN,
+            kd: f64::INFINITY,
+        });
+        assert_eq!(
+            format!("{}", report),
+            r#"{"plant":{"ku":null,"pu":null},"gains":{"kp":0.5,"ki":0,"kd":0},"period":0.5,"margins":{"gain":null,"phase":60,"crossover":1},"quantization":{"kp":0,"ki":null,"kd":null}}"#
+        );
+    }
+}
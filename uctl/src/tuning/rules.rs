@@ -0,0 +1,306 @@
+/*!
+
+## PID tuning rules
+
+This module implements the classic rules which map the identified characteristics of process
+to the gains of PID regulator.
+
+The characteristics can be given in two forms:
+
+- [`Ultimate`] gain _Ku_ and period _Pu_ of oscillations, which usually found
+  by relay feedback experiment or by increasing of proportional gain until
+  the loop begins oscillate.
+- [`Fopdt`] (first order plus dead time) model of process with static gain _K_,
+  time constant _T_ and delay _L_, which usually found from step response.
+
+The rules by ultimate characteristics:
+
+| Rule                 | _Kp_       | _Ti_      | _Td_      |
+|----------------------|------------|-----------|-----------|
+| Ziegler-Nichols P    | 0.5 _Ku_   |           |           |
+| Ziegler-Nichols PI   | 0.45 _Ku_  | _Pu_ / 1.2 |          |
+| Ziegler-Nichols PID  | 0.6 _Ku_   | 0.5 _Pu_  | 0.125 _Pu_ |
+| Tyreus-Luyben PI     | _Ku_ / 3.2 | 2.2 _Pu_  |           |
+| Tyreus-Luyben PID    | _Ku_ / 2.2 | 2.2 _Pu_  | _Pu_ / 6.3 |
+
+The rules by FOPDT model (with closed loop time constant _λ_ and sampling period _P_):
+
+| Rule                 | _Kp_                          | _Ti_         | _Td_               |
+|----------------------|-------------------------------|--------------|--------------------|
+| Ziegler-Nichols PI   | 0.9 _T_ / (_K L_)             | 3.33 _L_     |                    |
+| Ziegler-Nichols PID  | 1.2 _T_ / (_K L_)             | 2 _L_        | 0.5 _L_            |
+| Lambda PI            | _T_ / (_K_ (_λ_ + _L_))       | _T_          |                    |
+| Lambda PID           | (_T_ + _L_/2) / (_K_ (_λ_ + _L_/2)) | _T_ + _L_/2 | _T L_ / (2 _T_ + _L_) |
+
+The Takahashi rules is a modification of Ziegler-Nichols rules for discrete-time regulators
+which takes into account the sampling period:
+
+- PI: _Kp = 0.9 T / (K (L + P/2)) - 0.135 T P / (K (L + P/2)²)_, _Ki = 0.27 T / (K (L + P/2)²)_
+- PID: _Kp = 1.2 T / (K (L + P)) - 0.3 T P / (K (L + P/2)²)_, _Ki = 0.6 T / (K (L + P/2)²)_, _Kd = 0.5 T / K_
+
+The rules gives the gains in parallel form (_Ki = Kp / Ti_, _Kd = Kp Td_) as [`Gains`]
+which can be converted to the parameters of [PID](crate::pid) regulators.
+
+The functions doesn't require standard library, so the tuning can be done on-device at runtime.
+
+*/
+
+use crate::{
+    pid::{Param as PidParam, PiParam},
+    Cast,
+};
+
+/// The gains of PID regulator in parallel form
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Gains {
+    /// The proportional gain _Kp_
+    pub kp: f64,
+    /// The integral gain _Ki_ (1/s)
+    pub ki: f64,
+    /// The derivative gain _Kd_ (s)
+    pub kd: f64,
+}
+
+impl Gains {
+    /// Create gains from proportional gain, integral time _Ti_ and derivative time _Td_
+    pub fn from_times(kp: f64, integral: f64, derivative: f64) -> Self {
+        Self {
+            kp,
+            ki: if integral > 0.0 { kp / integral } else { 0.0 },
+            kd: kp * derivative,
+        }
+    }
+
+    /**
+    Init PID regulator parameters
+
+    - `filter`: The derivative filter coefficient _N_
+    - `period`: The sampling period _P_ (s)
+     */
+    pub fn pid<K, T>(&self, filter: f64, period: f64) -> PidParam<K, T>
+    where
+        K: Cast<f64>,
+    {
+        PidParam::new(self.kp, self.ki, self.kd, filter, period)
+    }
+
+    /**
+    Init PI regulator parameters (the derivative gain is ignored)
+
+    - `period`: The sampling period _P_ (s)
+     */
+    pub fn pi<K, T>(&self, period: f64) -> PiParam<K, T>
+    where
+        K: Cast<f64>,
+    {
+        PiParam::new(self.kp, self.ki, period)
+    }
+}
+
+/// The ultimate characteristics of process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ultimate {
+    /// The ultimate gain _Ku_
+    pub gain: f64,
+    /// The ultimate period _Pu_ (s)
+    pub period: f64,
+}
+
+impl Ultimate {
+    /// Ziegler-Nichols P regulator
+    pub fn ziegler_nichols_p(&self) -> Gains {
+        Gains::from_times(0.5 * self.gain, 0.0, 0.0)
+    }
+
+    /// Ziegler-Nichols PI regulator
+    pub fn ziegler_nichols_pi(&self) -> Gains {
+        Gains::from_times(0.45 * self.gain, self.period / 1.2, 0.0)
+    }
+
+    /// Ziegler-Nichols PID regulator
+    pub fn ziegler_nichols_pid(&self) -> Gains {
+        Gains::from_times(0.6 * self.gain, 0.5 * self.period, 0.125 * self.period)
+    }
+
+    /// Tyreus-Luyben PI regulator
+    pub fn tyreus_luyben_pi(&self) -> Gains {
+        Gains::from_times(self.gain / 3.2, 2.2 * self.period, 0.0)
+    }
+
+    /// Tyreus-Luyben PID regulator
+    pub fn tyreus_luyben_pid(&self) -> Gains {
+        Gains::from_times(self.gain / 2.2, 2.2 * self.period, self.period / 6.3)
+    }
+}
+
+/// The first order plus dead time model of process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fopdt {
+    /// The static gain _K_
+    pub gain: f64,
+    /// The time constant _T_ (s)
+    pub time: f64,
+    /// The dead time _L_ (s)
+    pub delay: f64,
+}
+
+impl Fopdt {
+    /// Ziegler-Nichols PI regulator
+    pub fn ziegler_nichols_pi(&self) -> Gains {
+        Gains::from_times(
+            0.9 * self.time / (self.gain * self.delay),
+            self.delay / 0.3,
+            0.0,
+        )
+    }
+
+    /// Ziegler-Nichols PID regulator
+    pub fn ziegler_nichols_pid(&self) -> Gains {
+        Gains::from_times(
+            1.2 * self.time / (self.gain * self.delay),
+            2.0 * self.delay,
+            0.5 * self.delay,
+        )
+    }
+
+    /**
+    Lambda PI regulator
+
+    - `lambda`: The desired closed loop time constant _λ_ (s)
+     */
+    pub fn lambda_pi(&self, lambda: f64) -> Gains {
+        Gains::from_times(
+            self.time / (self.gain * (lambda + self.delay)),
+            self.time,
+            0.0,
+        )
+    }
+
+    /**
+    Lambda PID regulator
+
+    - `lambda`: The desired closed loop time constant _λ_ (s)
+     */
+    pub fn lambda_pid(&self, lambda: f64) -> Gains {
+        let half = 0.5 * self.delay;
+        Gains::from_times(
+            (self.time + half) / (self.gain * (lambda + half)),
+            self.time + half,
+            self.time * self.delay / (2.0 * self.time + self.delay),
+        )
+    }
+
+    /**
+    Takahashi PI regulator
+
+    - `period`: The sampling period _P_ (s)
+     */
+    pub fn takahashi_pi(&self, period: f64) -> Gains {
+        let delay = self.delay + 0.5 * period;
+        Gains {
+            kp: 0.9 * self.time / (self.gain * delay)
+                - 0.135 * self.time * period / (self.gain * delay * delay),
+            ki: 0.27 * self.time / (self.gain * delay * delay),
+            kd: 0.0,
+        }
+    }
+
+    /**
+    Takahashi PID regulator
+
+    - `period`: The sampling period _P_ (s)
+     */
+    pub fn takahashi_pid(&self, period: f64) -> Gains {
+        let delay = self.delay + 0.5 * period;
+        Gains {
+            kp: 1.2 * self.time / (self.gain * (self.delay + period))
+                - 0.3 * self.time * period / (self.gain * delay * delay),
+            ki: 0.6 * self.time / (self.gain * delay * delay),
+            kd: 0.5 * self.time / self.gain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pid::Pid, Transducer};
+
+    fn assert_gains(gains: Gains, kp: f64, ki: f64, kd: f64) {
+        assert!((gains.kp - kp).abs() < 1e-9, "kp: {} != {}", gains.kp, kp);
+        assert!((gains.ki - ki).abs() < 1e-9, "ki: {} != {}", gains.ki, ki);
+        assert!((gains.kd - kd).abs() < 1e-9, "kd: {} != {}", gains.kd, kd);
+    }
+
+    #[test]
+    fn rules_ultimate() {
+        let process = Ultimate {
+            gain: 10.0,
+            period: 2.0,
+        };
+
+        assert_gains(process.ziegler_nichols_p(), 5.0, 0.0, 0.0);
+        assert_gains(process.ziegler_nichols_pi(), 4.5, 2.7, 0.0);
+        assert_gains(process.ziegler_nichols_pid(), 6.0, 6.0, 1.5);
+        assert_gains(process.tyreus_luyben_pi(), 3.125, 3.125 / 4.4, 0.0);
+        assert_gains(
+            process.tyreus_luyben_pid(),
+            10.0 / 2.2,
+            10.0 / 2.2 / 4.4,
+            10.0 / 2.2 * 2.0 / 6.3,
+        );
+    }
+
+    #[test]
+    fn rules_fopdt() {
+        let process = Fopdt {
+            gain: 2.0,
+            time: 10.0,
+            delay: 1.0,
+        };
+
+        assert_gains(process.ziegler_nichols_pi(), 4.5, 1.35, 0.0);
+        assert_gains(process.ziegler_nichols_pid(), 6.0, 3.0, 3.0);
+        assert_gains(process.lambda_pi(4.0), 1.0, 0.1, 0.0);
+        assert_gains(process.lambda_pid(4.5), 1.05, 0.1, 10.0 / 21.0 * 1.05);
+
+        // the Takahashi rules converges to Ziegler-Nichols rules for small periods
+        let gains = process.takahashi_pi(1e-9);
+        assert!((gains.kp - 4.5).abs() < 1e-6);
+        assert!((gains.ki - 1.35).abs() < 1e-6);
+        let gains = process.takahashi_pid(0.1);
+        assert!(gains.kp < 6.0 && gains.ki < 3.0);
+    }
+
+    #[test]
+    fn rules_closed_loop() {
+        // y' = (2 u(t - 1) - y) / 10
+        let process = Fopdt {
+            gain: 2.0,
+            time: 10.0,
+            delay: 1.0,
+        };
+        let period = 0.01;
+
+        for gains in &[
+            process.ziegler_nichols_pid(),
+            process.lambda_pi(5.0),
+            process.lambda_pid(5.0),
+            process.takahashi_pid(period),
+        ] {
+            let param = gains.pid::<f64, f64>(10.0, period);
+            let mut state = Default::default();
+            let mut delay = [0.0; 100];
+            let mut output = 0.0;
+
+            for step in 0..6000 {
+                let control = Pid::<f64, f64>::apply(&param, &mut state, (1.0, output));
+                let delayed = delay[step % 100];
+                delay[step % 100] = control;
+                output += period * (process.gain * delayed - output) / process.time;
+            }
+
+            assert!((output - 1.0).abs() < 1e-2, "{:?} {}", gains, output);
+        }
+    }
+}
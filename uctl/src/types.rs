@@ -1,6 +1,34 @@
+mod accum;
+mod array;
+mod checked;
+mod cost;
 mod delayline;
+mod interval;
+mod mailbox;
+mod noise;
+mod nth;
+mod range;
+mod rate;
+mod reset;
+mod smooth;
+mod snapshot;
 mod transducer;
+mod warm;
 
+pub use accum::*;
+pub use array::*;
+pub use checked::*;
+pub use cost::*;
 pub use delayline::*;
+pub use interval::*;
+pub use mailbox::*;
+pub use noise::*;
+pub use nth::*;
+pub use range::*;
+pub use rate::*;
+pub use reset::*;
+pub use smooth::*;
+pub use snapshot::*;
 pub use transducer::*;
 pub use ufix::Cast;
+pub use warm::*;
@@ -0,0 +1,445 @@
+/*!
+
+## Accumulators
+
+When the many small values is summed in the same type as the values
+(like the increments of integrators or the samples of statistics)
+the small values is silently lost once the sum grows big enough.
+
+The [`WideAccumulator`] sums the values into the wider type, which is usually the fixed-point
+type with more integer bits and the same or more fractional bits than the input type.
+Because the fixed-point sum never loses precision, it only needs the range check,
+which is done against the explicit limit using given [`Overflow`] policy.
+
+The floating-point sum loses the low bits of small values instead, so the
+[`CompensatedAccumulator`] tracks the lost low-order part of sum separately
+and adds it back (Kahan-Babuška-Neumaier compensated summation).
+
+The [`Accumulator`] trait provides common interface of accumulators,
+so the blocks can be written generically over the accumulation strategy.
+
+*/
+
+use crate::{snapshot_fields, Cast};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/**
+Common interface of accumulators
+
+- `T` - values type
+*/
+pub trait Accumulator<T> {
+    /// The sum type
+    type Sum;
+
+    /// Add value to sum
+    fn push(&mut self, value: T);
+
+    /// The actual sum
+    fn sum(&self) -> Self::Sum;
+
+    /// The number of added values
+    fn count(&self) -> u32;
+
+    /// The mean value (zero when no values was added)
+    fn mean(&self) -> T;
+
+    /// Clear the sum and the number of values
+    fn clear(&mut self);
+}
+
+/// Division by the number of values
+pub trait DivCount {
+    /// Divide value by the number of values
+    fn div_count(self, count: u32) -> Self;
+}
+
+impl DivCount for f32 {
+    fn div_count(self, count: u32) -> Self {
+        self / count as f32
+    }
+}
+
+impl DivCount for f64 {
+    fn div_count(self, count: u32) -> Self {
+        self / count as f64
+    }
+}
+
+/// The mantissa is divided, so the result is truncated
+impl<R, B, E> DivCount for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Cast<u32> + Div<Output = Mantissa<R, B>>,
+{
+    fn div_count(self, count: u32) -> Self {
+        Self::new(self.bits / Mantissa::<R, B>::cast(count))
+    }
+}
+
+/// The overflow policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Clamp the sum to the limit
+    Saturate,
+    /// Discard the values which exceeds the limit
+    Hold,
+}
+
+/**
+Wide accumulator
+
+- `T` - values type
+- `W` - sum type
+
+The sum is kept in range _\[-L, L\]_. When the value exceeds the limit it is handled
+according to overflow policy and the overflow flag is set until the accumulator is cleared.
+The number of values is counted even when the value is discarded.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct WideAccumulator<T, W> {
+    /// The sum
+    sum: W,
+    /// The number of values
+    count: u32,
+    /// The minimum sum
+    min: W,
+    /// The maximum sum
+    max: W,
+    /// The overflow policy
+    policy: Overflow,
+    /// The overflow flag
+    overflow: bool,
+    /// The values type
+    value: PhantomData<T>,
+}
+
+snapshot_fields! {
+    WideAccumulator<T, W> {
+        sum: W,
+        count: u32,
+        overflow: bool,
+    }
+}
+
+impl<T, W> WideAccumulator<T, W> {
+    /**
+    Create accumulator
+
+    - `limit`: The maximum absolute value of sum _L_
+    - `policy`: The overflow policy
+     */
+    pub fn new<V>(limit: V, policy: Overflow) -> Self
+    where
+        W: Cast<f64> + Default,
+        f64: Cast<V>,
+    {
+        let limit = f64::cast(limit);
+
+        assert!(limit > 0.0);
+
+        Self {
+            sum: W::default(),
+            count: 0,
+            min: W::cast(-limit),
+            max: W::cast(limit),
+            policy,
+            overflow: false,
+            value: PhantomData,
+        }
+    }
+
+    /// The overflow was occurred since last clear
+    pub fn is_overflow(&self) -> bool {
+        self.overflow
+    }
+
+    /**
+    The scaled sum
+
+    - `factor`: The scaling factor
+
+    For example the factor _P_ gives the integral of values sampled with period _P_.
+     */
+    pub fn scaled<K>(&self, factor: K) -> T
+    where
+        W: Copy,
+        K: Mul<W>,
+        T: Cast<Prod<K, W>>,
+    {
+        T::cast(factor * self.sum)
+    }
+}
+
+impl<T, W> Accumulator<T> for WideAccumulator<T, W>
+where
+    W: Copy
+        + Default
+        + PartialOrd
+        + Cast<T>
+        + Cast<Sum<W, W>>
+        + Cast<Diff<W, W>>
+        + Add<W>
+        + Sub<W>
+        + DivCount,
+    T: Cast<W>,
+{
+    type Sum = W;
+
+    fn push(&mut self, value: T) {
+        let value = W::cast(value);
+        let zero = W::default();
+
+        self.count = self.count.saturating_add(1);
+
+        let exceeds = if value > zero {
+            value > W::cast(self.max - self.sum)
+        } else {
+            value < W::cast(self.min - self.sum)
+        };
+
+        if exceeds {
+            self.overflow = true;
+            if self.policy == Overflow::Saturate {
+                self.sum = if value > zero { self.max } else { self.min };
+            }
+        } else {
+            self.sum = W::cast(self.sum + value);
+        }
+    }
+
+    fn sum(&self) -> Self::Sum {
+        self.sum
+    }
+
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn mean(&self) -> T {
+        T::cast(if self.count > 0 {
+            self.sum.div_count(self.count)
+        } else {
+            W::default()
+        })
+    }
+
+    fn clear(&mut self) {
+        self.sum = W::default();
+        self.count = 0;
+        self.overflow = false;
+    }
+}
+
+/**
+Compensated accumulator
+
+- `T` - values type (`f32` or `f64`)
+
+The error of summation doesn't grow with the number of values
+(unlike the naive summation where it grows linearly).
+
+See also [Kahan summation algorithm](https://en.wikipedia.org/wiki/Kahan_summation_algorithm).
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompensatedAccumulator<T> {
+    /// The sum
+    sum: T,
+    /// The compensation of lost low-order bits
+    compensation: T,
+    /// The number of values
+    count: u32,
+}
+
+snapshot_fields! {
+    CompensatedAccumulator<T> {
+        sum: T,
+        compensation: T,
+        count: u32,
+    }
+}
+
+impl<T> CompensatedAccumulator<T> {
+    /// Create accumulator
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::default()
+    }
+}
+
+macro_rules! compensated_accumulator {
+    ($($type:ty),*) => {
+        $(
+            impl CompensatedAccumulator<$type> {
+                /**
+                The scaled sum
+
+                - `factor`: The scaling factor
+                 */
+                pub fn scaled(&self, factor: $type) -> $type {
+                    factor * self.sum()
+                }
+            }
+
+            impl Accumulator<$type> for CompensatedAccumulator<$type> {
+                type Sum = $type;
+
+                fn push(&mut self, value: $type) {
+                    let sum = self.sum + value;
+
+                    // the low-order bits of smaller operand is lost
+                    self.compensation += if self.sum.abs() >= value.abs() {
+                        (self.sum - sum) + value
+                    } else {
+                        (value - sum) + self.sum
+                    };
+
+                    self.sum = sum;
+                    self.count = self.count.saturating_add(1);
+                }
+
+                fn sum(&self) -> Self::Sum {
+                    self.sum + self.compensation
+                }
+
+                fn count(&self) -> u32 {
+                    self.count
+                }
+
+                fn mean(&self) -> $type {
+                    if self.count > 0 {
+                        self.sum().div_count(self.count)
+                    } else {
+                        0.0
+                    }
+                }
+
+                fn clear(&mut self) {
+                    *self = Self::default();
+                }
+            }
+        )*
+    };
+}
+
+compensated_accumulator!(f32, f64);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N24, N8, P16, P32, P48};
+    use ufix::bin;
+
+    #[test]
+    fn accumulator_fix() {
+        type T = bin::Fix<P32, N24>;
+        type W = bin::Fix<P48, N24>;
+        type K = bin::Fix<P16, N8>;
+
+        let mut narrow = T::cast(0.0);
+        let mut wide = WideAccumulator::<T, W>::new(1e6, Overflow::Saturate);
+
+        let value = T::cast(0.001);
+        for _ in 0..200_000 {
+            narrow = T::cast(narrow + value);
+            wide.push(value);
+        }
+
+        // the narrow sum is wrapped
+        assert!(f64::cast(narrow) < 0.0);
+        assert_eq!(wide.sum(), W::cast(f64::cast(value) * 200_000.0));
+        assert_eq!(wide.count(), 200_000);
+        assert_eq!(wide.mean(), value);
+        assert_eq!(
+            wide.scaled(K::cast(0.5)),
+            T::cast(f64::cast(wide.sum()) * 0.5)
+        );
+        assert!(!wide.is_overflow());
+    }
+
+    #[test]
+    fn accumulator_overflow() {
+        let mut acc = WideAccumulator::<f32, f32>::new(10.0, Overflow::Saturate);
+        acc.push(6.0);
+        acc.push(6.0);
+        assert_eq!(acc.sum(), 10.0);
+        assert!(acc.is_overflow());
+        acc.push(-25.0);
+        assert_eq!(acc.sum(), -10.0);
+        assert_eq!(acc.mean(), -10.0 / 3.0);
+
+        let mut acc = WideAccumulator::<f32, f32>::new(10.0, Overflow::Hold);
+        acc.push(6.0);
+        acc.push(6.0);
+        assert_eq!(acc.sum(), 6.0);
+        assert!(acc.is_overflow());
+        acc.push(-4.0);
+        assert_eq!(acc.sum(), 2.0);
+
+        acc.clear();
+        assert!(!acc.is_overflow());
+        assert_eq!(acc.count(), 0);
+        assert_eq!(acc.mean(), 0.0);
+    }
+
+    #[test]
+    fn accumulator_compensated() {
+        let mut naive = 0.0f32;
+        let mut acc = CompensatedAccumulator::<f32>::new();
+
+        acc.push(1e4);
+        naive += 1e4;
+        for _ in 0..100_000 {
+            acc.push(1e-3);
+            naive += 1e-3;
+        }
+
+        assert!((naive - 10100.0).abs() > 1.0, "{}", naive);
+        assert!((acc.sum() - 10100.0).abs() < 1e-3, "{}", acc.sum());
+        assert_eq!(acc.count(), 100_001);
+        assert!((acc.scaled(0.5) - 5050.0).abs() < 1e-3);
+
+        // the large values which cancels each other
+        let mut acc = CompensatedAccumulator::<f64>::new();
+        for value in [1.0, 1e100, 1.0, -1e100].iter() {
+            acc.push(*value);
+        }
+        assert_eq!(acc.sum(), 2.0);
+        assert_eq!(acc.mean(), 0.5);
+    }
+
+    /// The generic block over accumulation strategy
+    fn mean<T, A>(acc: &mut A, values: &[T]) -> T
+    where
+        T: Copy,
+        A: Accumulator<T>,
+    {
+        acc.clear();
+        for value in values {
+            acc.push(*value);
+        }
+        acc.mean()
+    }
+
+    #[test]
+    fn accumulator_generic() {
+        let values = [1.0, 2.0, 3.0, 6.0];
+        assert_eq!(mean(&mut CompensatedAccumulator::new(), &values), 3.0);
+        assert_eq!(
+            mean(
+                &mut WideAccumulator::<f64, f64>::new(100.0, Overflow::Hold),
+                &values
+            ),
+            3.0
+        );
+    }
+}
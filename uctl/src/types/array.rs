@@ -0,0 +1,150 @@
+/*!
+
+## Multi-channel container
+
+The [`Array`] combinator applies the same transducer to the several independent channels
+(for example the heaters, LED drivers or axes of drive).
+
+Each channel has own copy of parameters and state, so the channels can be tuned separately.
+The input and output values is the arrays of values per channel.
+
+*/
+
+use crate::{Core, CycleCost, ResetState, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+use typenum::Unsigned;
+
+/**
+Multi-channel transducer
+
+- `T` - transducer of single channel
+- `N` - the number of channels
+*/
+#[derive(Debug)]
+pub struct Array<T, N>(PhantomData<(T, N)>);
+
+impl<T, N> Transducer for Array<T, N>
+where
+    T: Transducer,
+    N: ArrayLength<T::Input>
+        + ArrayLength<T::Output>
+        + ArrayLength<T::Param>
+        + ArrayLength<T::State>,
+{
+    type Input = GenericArray<T::Input, N>;
+    type Output = GenericArray<T::Output, N>;
+    type Param = GenericArray<T::Param, N>;
+    type State = GenericArray<T::State, N>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        param
+            .iter()
+            .zip(state.iter_mut())
+            .zip(value)
+            .map(|((param, state), value)| T::apply(param, state, value))
+            .collect()
+    }
+}
+
+/// The preset of channels sets the same value for all channels
+impl<S, N> ResetState for GenericArray<S, N>
+where
+    S: ResetState,
+    S::Value: Clone,
+    N: ArrayLength<S>,
+{
+    type Value = S::Value;
+
+    fn reset(&mut self) {
+        for state in self.iter_mut() {
+            state.reset();
+        }
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        for state in self.iter_mut() {
+            state.preset(value.clone());
+        }
+    }
+}
+
+impl<T, N> CycleCost for Array<T, N>
+where
+    T: CycleCost,
+    N: Unsigned,
+{
+    fn cycles(core: Core) -> u32 {
+        N::to_u32() * T::cycles(core)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, Cast};
+    use generic_array::arr;
+    use typenum::{N16, P32, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn array_ema_f32() {
+        type E = ema::Filter<f32, f32, f32>;
+        type A = Array<E, U3>;
+
+        let param = GenericArray::from([
+            ema::Param::from_alpha(1.0),
+            ema::Param::from_alpha(0.5),
+            ema::Param::from_alpha(0.25),
+        ]);
+        let mut state = GenericArray::from([
+            ema::State::new(0.0),
+            ema::State::new(0.0),
+            ema::State::new(1.0),
+        ]);
+
+        assert_eq!(
+            A::apply(&param, &mut state, arr![f32; 1.0, 2.0, 3.0]),
+            arr![f32; 1.0, 1.0, 1.5]
+        );
+        assert_eq!(
+            A::apply(&param, &mut state, arr![f32; 1.0, 2.0, 3.0]),
+            arr![f32; 1.0, 1.5, 1.875]
+        );
+
+        state.preset(2.0);
+        assert_eq!(
+            A::apply(&param, &mut state, arr![f32; 2.0, 2.0, 2.0]),
+            arr![f32; 2.0, 2.0, 2.0]
+        );
+
+        state.reset();
+        assert_eq!(
+            A::apply(&param, &mut state, arr![f32; 0.0, 0.0, 0.0]),
+            arr![f32; 0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn array_ema_fix() {
+        type T = Fix<P32, N16>;
+        type E = ema::Filter<T, T, T>;
+        type A = Array<E, U3>;
+
+        let param = GenericArray::from([
+            ema::Param::from_alpha(T::cast(1.0)),
+            ema::Param::from_alpha(T::cast(0.5)),
+            ema::Param::from_alpha(T::cast(0.25)),
+        ]);
+        let mut state = GenericArray::from([ema::State::new(T::cast(0.0)); 3]);
+
+        assert_eq!(
+            A::apply(
+                &param,
+                &mut state,
+                arr![T; T::cast(4.0), T::cast(4.0), T::cast(4.0)]
+            ),
+            arr![T; T::cast(4.0), T::cast(2.0), T::cast(1.0)]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+/*!
+
+## Output range checking
+
+The numeric issues (like overflows or wrong scaling) usually doesn't stop the chain,
+so the wrong values silently propagates to the outputs.
+
+The [`Checked`] wrapper asserts that the output of transducer stays within declared
+physical bounds (like the range of sensor or actuator) and records the extreme values seen,
+so the issues is detected during development.
+
+The checking is enabled by `checked` feature. When the feature is disabled the wrapper
+applies the transducer as is and the bounds and the extreme values isn't stored,
+so it may be kept in chains without runtime and memory cost
+(the output type isn't required to be comparable or convertible in this case).
+
+See also [`WorstCase`](crate::WorstCase) for static analysis of ranges.
+
+*/
+
+use crate::{snapshot_fields, Interval, Transducer};
+use core::marker::PhantomData;
+
+#[cfg(feature = "checked")]
+use crate::Cast;
+
+/**
+Checked output parameters
+
+- `P` - wrapped transducer parameters type
+- `O` - output values type
+
+The bounds is stored with `checked` feature only.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct CheckedParam<P, O> {
+    /// Wrapped transducer parameters
+    pub param: P,
+    /// The declared bounds of output
+    #[cfg(feature = "checked")]
+    bounds: Interval<O>,
+    #[cfg(not(feature = "checked"))]
+    bounds: PhantomData<O>,
+}
+
+#[cfg(feature = "checked")]
+impl<P, O> CheckedParam<P, O> {
+    /**
+    Init checked output parameters
+
+    - `param`: The wrapped transducer parameters
+    - `min`: The minimum output
+    - `max`: The maximum output
+     */
+    pub fn new<V>(param: P, min: V, max: V) -> Self
+    where
+        O: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self {
+            param,
+            bounds: Interval {
+                min: O::cast(f64::cast(min)),
+                max: O::cast(f64::cast(max)),
+            },
+        }
+    }
+
+    /// The declared bounds of output
+    pub fn bounds(&self) -> &Interval<O> {
+        &self.bounds
+    }
+}
+
+#[cfg(not(feature = "checked"))]
+impl<P, O> CheckedParam<P, O> {
+    /**
+    Init checked output parameters
+
+    - `param`: The wrapped transducer parameters
+    - `min`: The minimum output (ignored)
+    - `max`: The maximum output (ignored)
+     */
+    pub fn new<V>(param: P, _min: V, _max: V) -> Self {
+        Self {
+            param,
+            bounds: PhantomData,
+        }
+    }
+}
+
+/**
+Checked output state
+
+- `S` - wrapped transducer state type
+- `O` - output values type
+
+The extreme values is stored with `checked` feature only.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckedState<S, O> {
+    /// Wrapped transducer state
+    pub state: S,
+    /// The extreme values seen
+    #[cfg(feature = "checked")]
+    seen: Option<Interval<O>>,
+    #[cfg(not(feature = "checked"))]
+    seen: PhantomData<O>,
+}
+
+#[cfg(feature = "checked")]
+snapshot_fields! {
+    CheckedState<S, O> {
+        state: S,
+        seen: Option<Interval<O>>,
+    }
+}
+
+#[cfg(not(feature = "checked"))]
+snapshot_fields! {
+    CheckedState<S, O> {
+        state: S,
+    }
+}
+
+impl<S, O> CheckedState<S, O> {
+    /**
+    The extreme values of output seen
+
+    Always `None` when `checked` feature is disabled.
+     */
+    pub fn seen(&self) -> Option<&Interval<O>> {
+        #[cfg(feature = "checked")]
+        {
+            self.seen.as_ref()
+        }
+        #[cfg(not(feature = "checked"))]
+        {
+            None
+        }
+    }
+
+    /// Forget the extreme values seen
+    pub fn clear(&mut self) {
+        #[cfg(feature = "checked")]
+        {
+            self.seen = None;
+        }
+    }
+}
+
+/**
+Checked output wrapper
+
+- `T` - wrapped transducer
+
+Panics when the output is out of declared bounds (with `checked` feature only).
+*/
+#[derive(Debug)]
+pub struct Checked<T>(PhantomData<T>);
+
+#[cfg(feature = "checked")]
+impl<T> Transducer for Checked<T>
+where
+    T: Transducer,
+    T::Output: Copy + PartialOrd,
+    f64: Cast<T::Output>,
+{
+    type Input = T::Input;
+    type Output = T::Output;
+    type Param = CheckedParam<T::Param, T::Output>;
+    type State = CheckedState<T::State, T::Output>;
+
+    #[inline]
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let output = T::apply(&param.param, &mut state.state, value);
+
+        match &mut state.seen {
+            Some(seen) => seen.extend(output),
+            seen => {
+                *seen = Some(Interval {
+                    min: output,
+                    max: output,
+                })
+            }
+        }
+
+        assert!(
+            param.bounds.includes(output),
+            "The output {} is out of bounds [{}, {}]",
+            f64::cast(output),
+            f64::cast(param.bounds.min),
+            f64::cast(param.bounds.max),
+        );
+
+        output
+    }
+}
+
+#[cfg(not(feature = "checked"))]
+impl<T> Transducer for Checked<T>
+where
+    T: Transducer,
+{
+    type Input = T::Input;
+    type Output = T::Output;
+    type Param = CheckedParam<T::Param, T::Output>;
+    type State = CheckedState<T::State, T::Output>;
+
+    #[inline]
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        T::apply(&param.param, &mut state.state, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{scaler, Cast};
+    use typenum::{N8, P16};
+    use ufix::bin::Fix;
+
+    type T = Fix<P16, N8>;
+    type C = Checked<scaler::Scaler<T, T, T>>;
+
+    fn param() -> CheckedParam<scaler::Param<T, T>, T> {
+        CheckedParam::new(
+            scaler::Param::from_factor(T::cast(2.0), T::cast(1.0)),
+            -5.0,
+            5.0,
+        )
+    }
+
+    #[test]
+    fn checked_within() {
+        let param = param();
+        let mut state = CheckedState::default();
+
+        assert_eq!(C::apply(&param, &mut state, T::cast(1.0)), T::cast(3.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(-2.0)), T::cast(-3.0));
+        assert_eq!(C::apply(&param, &mut state, T::cast(0.0)), T::cast(1.0));
+
+        #[cfg(feature = "checked")]
+        assert_eq!(
+            state.seen(),
+            Some(&Interval {
+                min: T::cast(-3.0),
+                max: T::cast(3.0)
+            })
+        );
+        #[cfg(not(feature = "checked"))]
+        assert_eq!(state.seen(), None);
+
+        state.clear();
+        assert_eq!(state.seen(), None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "checked", should_panic(expected = "out of bounds"))]
+    fn checked_out_of_bounds() {
+        let param = param();
+        let mut state = CheckedState::default();
+
+        assert_eq!(C::apply(&param, &mut state, T::cast(3.0)), T::cast(7.0));
+    }
+
+    #[cfg(not(feature = "checked"))]
+    #[test]
+    fn checked_disabled_size() {
+        use core::mem::size_of;
+
+        assert_eq!(
+            size_of::<CheckedParam<scaler::Param<T, T>, T>>(),
+            size_of::<scaler::Param<T, T>>()
+        );
+        assert_eq!(size_of::<CheckedState<(), T>>(), 0);
+    }
+}
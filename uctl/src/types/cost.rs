@@ -0,0 +1,295 @@
+/*!
+
+## Execution cost
+
+The [`CycleCost`] trait provides the worst-case estimation of CPU cycles
+which is required to apply the transducer once on the given processor [`Core`].
+
+The estimation is made by counting the arithmetic operations of transducer and
+multiplying it by the worst-case timings of operations for used numeric types ([`OpCost`]).
+
+The timings of hardware operations is taken from the technical reference manuals of cores:
+
+- Cortex-M0 (Technical Reference Manual, ARM DDI 0432): `ADDS` takes 1 cycle,
+  `MULS` takes 1 cycle (fast multiplier) or 32 cycles (small multiplier, the worst case is used),
+  there is no divide instructions
+- Cortex-M3 (Technical Reference Manual, ARM DDI 0337): `MUL` takes 1 cycle,
+  `UMULL`/`SMULL` takes 3..5 cycles, `MLA` takes 2 cycles and `SDIV`/`UDIV` takes 2..12 cycles
+- Cortex-M4F (Technical Reference Manual, ARM DDI 0439): `MUL`, `MLA`, `UMULL`/`SMULL`
+  takes 1 cycle, `SDIV`/`UDIV` takes 2..12 cycles, `VADD.F32` and `VMUL.F32` takes 1 cycle
+  and `VDIV.F32` takes 14 cycles
+
+The wide integer operations on Cortex-M3 and Cortex-M4F is composed from the instructions above
+(like `ADDS` + `ADCS` for 64-bit addition or `UMULL` + 2 `MLA` for 64-bit multiplication).
+
+The timings of software emulated operations (the division and the wide multiplication on Cortex-M0,
+the 64-bit division, the floating point on FPU-less cores and the double precision on Cortex-M4F)
+is the rough upper bounds of the run-time library routines (`__aeabi_*` from compiler-rt or libgcc),
+which depends on the library version and the operands, so it isn't sourced from the manuals.
+When the budget is tight, the actual cost of these operations should be measured
+on the target (like using DWT cycle counter).
+
+The cost of memory access and calls isn't counted, so the real cost is a bit higher.
+Anyway the estimation allows to compare the alternative designs
+and to check statically whether the composed chain fits the budget of interrupt.
+
+The cost of chains (tuples of transducers) is a sum of costs of stages.
+
+*/
+
+use core::mem::size_of;
+use ufix::{Fix, Radix};
+
+/// Processor core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Core {
+    /// ARM Cortex-M0/M0+ (no hardware divider, the multiplier may be iterative)
+    M0,
+    /// ARM Cortex-M3 (hardware divider, no FPU)
+    M3,
+    /// ARM Cortex-M4F (single-precision FPU)
+    M4F,
+}
+
+/// The worst-case cost of basic operations with values of numeric type
+pub trait OpCost {
+    /// The cycles of addition or subtraction
+    fn add(core: Core) -> u32;
+
+    /// The cycles of multiplication
+    fn mul(core: Core) -> u32;
+
+    /// The cycles of division
+    fn div(core: Core) -> u32;
+}
+
+/// The cycles of integer operations (add, mul, div) by size in bytes
+fn int_cost(core: Core, size: usize) -> (u32, u32, u32) {
+    match (core, size) {
+        // MULS, software division
+        (Core::M0, 0..=4) => (1, 32, 100),
+        // software multiplication and division
+        (Core::M0, 5..=8) => (2, 100, 1000),
+        (Core::M0, _) => (4, 400, 2000),
+        // MUL, SDIV
+        (_, 0..=4) => (1, 1, 12),
+        // UMULL + 2 MLA, software division
+        (Core::M3, 5..=8) => (2, 9, 250),
+        (Core::M4F, 5..=8) => (2, 3, 250),
+        // software multiplication and division
+        (_, _) => (4, 30, 1000),
+    }
+}
+
+/// The cycles of widening multiplication by size of operands in bytes
+fn wide_mul_cost(core: Core, size: usize) -> u32 {
+    match (core, size) {
+        // the product fits into register (MUL or MULS)
+        (_, 0..=2) => int_cost(core, 4).1,
+        // SMULL
+        (Core::M3, 3..=4) => 5,
+        (Core::M4F, 3..=4) => 1,
+        // the truncated product of twice wider operands (software on Cortex-M0)
+        _ => int_cost(core, 2 * size).1,
+    }
+}
+
+macro_rules! int_op_cost {
+    ($($type:ty),+) => {
+        $(
+            impl OpCost for $type {
+                fn add(core: Core) -> u32 {
+                    int_cost(core, size_of::<$type>()).0
+                }
+
+                fn mul(core: Core) -> u32 {
+                    int_cost(core, size_of::<$type>()).1
+                }
+
+                fn div(core: Core) -> u32 {
+                    int_cost(core, size_of::<$type>()).2
+                }
+            }
+        )+
+    };
+}
+
+int_op_cost!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+#[cfg(feature = "i128")]
+int_op_cost!(i128, u128);
+
+impl OpCost for f32 {
+    fn add(core: Core) -> u32 {
+        match core {
+            Core::M0 => 70,
+            Core::M3 => 50,
+            Core::M4F => 1,
+        }
+    }
+
+    fn mul(core: Core) -> u32 {
+        match core {
+            Core::M0 => 60,
+            Core::M3 => 45,
+            Core::M4F => 1,
+        }
+    }
+
+    fn div(core: Core) -> u32 {
+        match core {
+            Core::M0 => 200,
+            Core::M3 => 150,
+            Core::M4F => 14,
+        }
+    }
+}
+
+impl OpCost for f64 {
+    fn add(core: Core) -> u32 {
+        match core {
+            Core::M0 => 110,
+            _ => 70,
+        }
+    }
+
+    fn mul(core: Core) -> u32 {
+        match core {
+            Core::M0 => 160,
+            _ => 90,
+        }
+    }
+
+    fn div(core: Core) -> u32 {
+        match core {
+            Core::M0 => 600,
+            _ => 400,
+        }
+    }
+}
+
+/**
+The operations with fixed-point values requires extra shift to adjust the exponent
+
+The multiplication evaluates the product of twice wider type before shift.
+*/
+impl<R, B, E> OpCost for Fix<R, B, E>
+where
+    R: Radix<B>,
+{
+    fn add(core: Core) -> u32 {
+        int_cost(core, size_of::<R::Type>()).0 + 1
+    }
+
+    fn mul(core: Core) -> u32 {
+        wide_mul_cost(core, size_of::<R::Type>()) + 1
+    }
+
+    fn div(core: Core) -> u32 {
+        int_cost(core, size_of::<R::Type>()).2 + 1
+    }
+}
+
+/// The worst-case cost of transducer step
+pub trait CycleCost {
+    /// The cycles per step
+    fn cycles(core: Core) -> u32;
+
+    /// Check when the step fits to the budget of cycles
+    fn fits(core: Core, budget: u32) -> bool {
+        Self::cycles(core) <= budget
+    }
+}
+
+macro_rules! cost_tuple {
+    ($($type:ident),+) => {
+        impl<$($type),+> CycleCost for ($($type,)+)
+        where
+            $($type: CycleCost),+
+        {
+            fn cycles(core: Core) -> u32 {
+                0 $(+ $type::cycles(core))+
+            }
+        }
+    };
+}
+
+cost_tuple!(A);
+cost_tuple!(A, B);
+cost_tuple!(A, B, C);
+cost_tuple!(A, B, C, D);
+cost_tuple!(A, B, C, D, E);
+cost_tuple!(A, B, C, D, E, F);
+cost_tuple!(A, B, C, D, E, F, G);
+cost_tuple!(A, B, C, D, E, F, G, H);
+cost_tuple!(A, B, C, D, E, F, G, H, I);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+cost_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{clamper::Clamper, ema, fir, lqe, pfdl::Store as DL, pid, scaler, Array};
+    use core::ops::RangeInclusive;
+    use typenum::{N16, N32, P32, P64, U3, U4};
+    use ufix::bin::Fix;
+
+    type E = ema::Filter<f32, f32, f32>;
+
+    #[test]
+    fn op_cost() {
+        assert_eq!(f32::mul(Core::M4F), 1);
+        assert_eq!(i32::mul(Core::M0), 32);
+        // SMULL + shift
+        assert_eq!(Fix::<P32, N16>::mul(Core::M3), 6);
+        assert_eq!(Fix::<P32, N16>::mul(Core::M4F), 2);
+        assert_eq!(Fix::<P64, N32>::mul(Core::M3), 31);
+    }
+
+    #[test]
+    fn chain_cost() {
+        // 2 mul + add
+        assert_eq!(E::cycles(Core::M4F), 3);
+        assert_eq!(E::cycles(Core::M3), 140);
+
+        type C = (E, E);
+        assert_eq!(C::cycles(Core::M4F), 6);
+        assert!(C::fits(Core::M4F, 10));
+        assert!(!C::fits(Core::M3, 10));
+
+        type A = Array<E, U4>;
+        assert_eq!(A::cycles(Core::M4F), 12);
+
+        type T = Fix<P32, N16>;
+        type F = fir::Filter<T, T, DL<T, U3>>;
+        // 4 mul + 3 add
+        assert_eq!(F::cycles(Core::M3), 30);
+    }
+
+    #[test]
+    fn regulator_cost() {
+        type T = Fix<P32, N16>;
+        type S = scaler::Scaler<T, T, T>;
+        type R = pid::Pi<T, T>;
+        type C = Clamper<RangeInclusive<T>, T>;
+
+        // SMULL + shift and add + shift
+        assert_eq!(S::cycles(Core::M4F), 4);
+        // 2 mul + 6 add
+        assert_eq!(R::cycles(Core::M4F), 16);
+        assert_eq!(<(S, R, C)>::cycles(Core::M4F), 24);
+        assert_eq!(<(S, R, C)>::cycles(Core::M3), 36);
+
+        type P = pid::Pid<f32, f32>;
+        assert_eq!(P::cycles(Core::M4F), 14);
+
+        type K = lqe::Filter<f32, f32, f32, f32, f32, f32, f32>;
+        // 7 mul + 5 add + div
+        assert_eq!(K::cycles(Core::M4F), 26);
+    }
+}
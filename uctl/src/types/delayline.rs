@@ -1,4 +1,5 @@
 pub mod pfdl;
+pub mod slice;
 
 use typenum::{NonZero, Unsigned};
 
@@ -37,6 +38,13 @@ where
         self.len() == Self::max_len()
     }
 
+    /// Get the oldest value in line
+    ///
+    /// Returns `None` when line is empty
+    fn oldest(&self) -> Option<Self::Value> {
+        self.iter().last()
+    }
+
     /// Get iterator over a stored values
     fn iter(&self) -> <&Self as IntoIterator>::IntoIter {
         self.into_iter()
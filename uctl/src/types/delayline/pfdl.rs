@@ -7,6 +7,7 @@ This module implements delay line which pre-initialized with some value.
 */
 
 use super::DelayLine;
+use crate::{snapshot_fields, ResetState};
 use core::{
     iter::{repeat, FromIterator, IntoIterator},
     usize,
@@ -15,7 +16,7 @@ use generic_array::{ArrayLength, GenericArray};
 use typenum::NonZero;
 
 /// Simple pre-filled delay line
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Store<T, N>
 where
     T: Copy,
@@ -59,6 +60,33 @@ where
     fn len(&self) -> usize {
         Self::max_len()
     }
+
+    fn oldest(&self) -> Option<Self::Value> {
+        Some(self.data[self.tail])
+    }
+}
+
+impl<T, N> ResetState for Store<T, N>
+where
+    T: Copy + Default,
+    N: ArrayLength<T> + NonZero,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        self.preset(T::default());
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        *self = Self::from(value);
+    }
+}
+
+snapshot_fields! {
+    Store<T, N> where [T: Copy, N: ArrayLength<T> + NonZero] {
+        data: GenericArray<T, N>,
+        tail: usize,
+    }
 }
 
 impl<'a, T, N> IntoIterator for &'a Store<T, N>
@@ -301,6 +329,21 @@ mod test {
         }
     }
 
+    #[test]
+    fn oldest() {
+        let mut dl = Store::<i8, U3>::default();
+
+        assert_eq!(dl.oldest(), Some(0));
+        dl.push(1);
+        dl.push(2);
+        assert_eq!(dl.oldest(), Some(0));
+        dl.push(3);
+        assert_eq!(dl.oldest(), Some(1));
+        dl.push(4);
+        assert_eq!(dl.oldest(), Some(2));
+        assert_eq!(dl.oldest(), dl.iter().last());
+    }
+
     #[test]
     fn from_value() {
         let dl = Store::<i8, U3>::from(11);
@@ -0,0 +1,215 @@
+/*!
+
+## Slice-based delay line
+
+This module implements pre-filled delay line over the memory provided by caller.
+
+The storage of [`pfdl::Store`](super::pfdl::Store) is placed inside the state of transducer,
+so the large delay lines occupies the same memory as other states.
+The [`SliceDelayLine`] borrows the storage instead, so it can be placed in the specific memory region
+(like core-coupled or external RAM) using linker sections:
+
+```
+use uctl::{fir, slice::SliceDelayLine, Transducer};
+use typenum::U255;
+
+type Filter<'a> = fir::Filter<f32, f32, SliceDelayLine<'a, f32, U255>>;
+
+// the buffer can be placed in the specific section using `#[link_section]` attribute
+let mut buffer = [0.0; 255];
+
+let param: fir::Param<f32, U255> = core::iter::repeat(1.0 / 256.0).take(256).collect();
+let mut state = SliceDelayLine::new(&mut buffer, 0.0);
+
+assert_eq!(Filter::apply(&param, &mut state, 1.0), 1.0 / 256.0);
+```
+
+The length of line still is a type-level unsigned integer because the parameters of filters depends on it.
+The length of slice should be equal to it.
+
+*/
+
+use super::DelayLine;
+use crate::{ResetState, Snapshot};
+use core::{
+    iter::{Chain, Copied, Rev},
+    marker::PhantomData,
+    slice::Iter,
+};
+use typenum::{NonZero, Unsigned};
+
+/**
+Pre-filled delay line over borrowed slice
+
+- `T` - values type
+- `N` - line length
+*/
+#[derive(Debug)]
+pub struct SliceDelayLine<'a, T, N> {
+    /// Externally allocated storage for all available values
+    data: &'a mut [T],
+    /// The position after of the last pushed value
+    tail: usize,
+    length: PhantomData<N>,
+}
+
+impl<'a, T, N> SliceDelayLine<'a, T, N>
+where
+    T: Copy,
+    N: Unsigned + NonZero,
+{
+    /**
+    Create delay line over slice
+
+    - `data`: The storage of values (the length should be `N`)
+    - `value`: The initial value of line
+
+    The storage is filled by initial value.
+     */
+    pub fn new(data: &'a mut [T], value: T) -> Self {
+        assert_eq!(data.len(), N::USIZE);
+
+        for item in data.iter_mut() {
+            *item = value;
+        }
+
+        Self {
+            data,
+            tail: 0,
+            length: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, N> DelayLine for SliceDelayLine<'a, T, N>
+where
+    T: Copy,
+    N: Unsigned + NonZero,
+{
+    type Value = T;
+    type Length = N;
+
+    fn push(&mut self, value: Self::Value) {
+        self.data[self.tail] = value;
+        self.tail += 1;
+        if self.tail == Self::max_len() {
+            self.tail = 0;
+        }
+    }
+
+    fn len(&self) -> usize {
+        Self::max_len()
+    }
+
+    fn oldest(&self) -> Option<Self::Value> {
+        Some(self.data[self.tail])
+    }
+}
+
+impl<'a, T, N> ResetState for SliceDelayLine<'a, T, N>
+where
+    T: Copy + Default,
+    N: Unsigned + NonZero,
+{
+    type Value = T;
+
+    fn reset(&mut self) {
+        self.preset(T::default());
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        for item in self.data.iter_mut() {
+            *item = value;
+        }
+        self.tail = 0;
+    }
+}
+
+/// Iterator over stored values (from newest to oldest)
+pub type SliceIter<'a, T> = Copied<Chain<Rev<Iter<'a, T>>, Rev<Iter<'a, T>>>>;
+
+impl<'a, T, N> Snapshot for SliceDelayLine<'a, T, N>
+where
+    T: Snapshot,
+    N: Unsigned,
+{
+    const SIZE: usize = T::SIZE * N::USIZE + usize::SIZE;
+
+    fn save<'b>(&self, buffer: &'b mut [u8]) -> &'b mut [u8] {
+        let buffer = self
+            .data
+            .iter()
+            .fold(buffer, |buffer, value| value.save(buffer));
+        self.tail.save(buffer)
+    }
+
+    fn restore<'b>(&mut self, buffer: &'b [u8]) -> &'b [u8] {
+        let buffer = self
+            .data
+            .iter_mut()
+            .fold(buffer, |buffer, value| value.restore(buffer));
+        self.tail.restore(buffer)
+    }
+}
+
+impl<'a, 'b, T, N> IntoIterator for &'a SliceDelayLine<'b, T, N>
+where
+    T: Copy,
+{
+    type Item = T;
+    type IntoIter = SliceIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let (older, newer) = self.data.split_at(self.tail);
+        older.iter().rev().chain(newer.iter().rev()).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{fir, pfdl::Store, Transducer};
+    use typenum::U3;
+
+    #[test]
+    fn push_iter() {
+        let mut data = [9i8; 3];
+        {
+            let mut dl = SliceDelayLine::<i8, U3>::new(&mut data, 0);
+
+            assert_eq!(dl.len(), 3);
+            assert!(dl.iter().eq([0, 0, 0]));
+            dl.push(1);
+            dl.push(2);
+            assert!(dl.iter().eq([2, 1, 0]));
+            assert_eq!(dl.oldest(), Some(0));
+            dl.push(3);
+            dl.push(4);
+            assert!(dl.iter().eq([4, 3, 2]));
+            assert_eq!(dl.oldest(), Some(2));
+            assert_eq!(dl.oldest(), dl.iter().last());
+
+            dl.preset(5);
+            assert!(dl.iter().eq([5, 5, 5]));
+        }
+        assert_eq!(data, [5, 5, 5]);
+    }
+
+    #[test]
+    fn same_as_store() {
+        type S<'a> = fir::Filter<f32, f32, SliceDelayLine<'a, f32, U3>>;
+        type A = fir::Filter<f32, f32, Store<f32, U3>>;
+
+        let param = fir::Param::<f32, U3>::from([0.4, 0.3, 0.2, 0.1]);
+        let mut data = [0.0; 3];
+        let mut slice = SliceDelayLine::new(&mut data, 0.0);
+        let mut store = Store::default();
+
+        for x in [1.0, -2.0, 3.0, 0.5, 0.0, 0.0, 0.0] {
+            assert_eq!(
+                S::apply(&param, &mut slice, x),
+                A::apply(&param, &mut store, x)
+            );
+        }
+    }
+}
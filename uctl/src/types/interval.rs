@@ -0,0 +1,415 @@
+/*!
+
+## Interval arithmetic
+
+The [`Interval`] represents the closed range of values which contains the unknown exact value.
+The arithmetic operations on intervals gives the interval which contains all possible results,
+so the ranges can be propagated through the computations.
+
+The floating-point results is rounded outward (the lower bound down and the upper bound up)
+when the result isn't exact, so the interval always contains the exact result.
+The rounding error is evaluated using error-free transformations (TwoSum and TwoProduct),
+so the exact results isn't widened.
+The single-precision intervals is evaluated with double precision and rounded outward.
+The operations on fixed-point intervals is exact because the types of results is widened.
+
+The operations on double-precision intervals is `const`, so the ranges can be propagated
+in constant context (see [`Bounds`](crate::Bounds)).
+Also the interval can be used at runtime to monitor the actual range of signal.
+
+See also [Interval arithmetic](https://en.wikipedia.org/wiki/Interval_arithmetic).
+
+*/
+
+use crate::snapshot_fields;
+use core::ops::{Add, Mul, Neg, RangeInclusive, Sub};
+use typenum::{Diff, Prod, Sum};
+use ufix::{Fix, Radix};
+
+/**
+Closed interval of values
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Interval<T> {
+    /// The lower bound
+    pub min: T,
+    /// The upper bound
+    pub max: T,
+}
+
+snapshot_fields! {
+    Interval<T> {
+        min: T,
+        max: T,
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for Interval<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let (min, max) = range.into_inner();
+        Self { min, max }
+    }
+}
+
+impl<T> Interval<T>
+where
+    T: Copy + PartialOrd,
+{
+    /// Check when the value is within interval
+    pub fn includes(&self, value: T) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    /// Extend the interval to include value (useful to monitor the actual range of signal)
+    pub fn extend(&mut self, value: T) {
+        if value < self.min {
+            self.min = value;
+        }
+        if value > self.max {
+            self.max = value;
+        }
+    }
+}
+
+const fn min(a: f64, b: f64) -> f64 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+const fn max(a: f64, b: f64) -> f64 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The sum and its rounding error (TwoSum)
+const fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    let e = (a - (s - bb)) + (b - bb);
+    (s, if e.is_finite() { e } else { 0.0 })
+}
+
+/// The splitting of value into high and low halves (Veltkamp)
+const fn split(a: f64) -> (f64, f64) {
+    let c = 134217729.0 * a; // 2^27 + 1
+    let h = c - (c - a);
+    (h, a - h)
+}
+
+/// The product and its rounding error (TwoProduct)
+const fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    let (ah, al) = split(a);
+    let (bh, bl) = split(b);
+    let e = ((ah * bh - p) + ah * bl + al * bh) + al * bl;
+    (p, if e.is_finite() { e } else { 0.0 })
+}
+
+/// Round the inexact result down
+const fn down((value, error): (f64, f64)) -> f64 {
+    if error < 0.0 {
+        value.next_down()
+    } else {
+        value
+    }
+}
+
+/// Round the inexact result up
+const fn up((value, error): (f64, f64)) -> f64 {
+    if error > 0.0 {
+        value.next_up()
+    } else {
+        value
+    }
+}
+
+impl Interval<f64> {
+    /// Create interval
+    pub const fn new(min: f64, max: f64) -> Self {
+        assert!(min <= max);
+        Self { min, max }
+    }
+
+    /// Create symmetric interval `-limit..=limit`
+    pub const fn symmetric(limit: f64) -> Self {
+        Self::new(-limit, limit)
+    }
+
+    /// Create interval which contains single value
+    pub const fn point(value: f64) -> Self {
+        Self::new(value, value)
+    }
+
+    /// The interval of sum
+    pub const fn add(self, other: Self) -> Self {
+        Self::new(
+            down(two_sum(self.min, other.min)),
+            up(two_sum(self.max, other.max)),
+        )
+    }
+
+    /// The interval of difference
+    pub const fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    /// The interval of negated values
+    pub const fn neg(self) -> Self {
+        Self::new(-self.max, -self.min)
+    }
+
+    /// The interval of product
+    pub const fn mul(self, other: Self) -> Self {
+        let a = two_prod(self.min, other.min);
+        let b = two_prod(self.min, other.max);
+        let c = two_prod(self.max, other.min);
+        let d = two_prod(self.max, other.max);
+        Self::new(
+            min(min(down(a), down(b)), min(down(c), down(d))),
+            max(max(up(a), up(b)), max(up(c), up(d))),
+        )
+    }
+
+    /// The interval of values multiplied by factor
+    pub const fn scale(self, factor: f64) -> Self {
+        self.mul(Self::point(factor))
+    }
+
+    /// The interval of values with added offset
+    pub const fn offset(self, offset: f64) -> Self {
+        self.add(Self::point(offset))
+    }
+
+    /// The smallest interval which contains both intervals
+    pub const fn hull(self, other: Self) -> Self {
+        Self::new(min(self.min, other.min), max(self.max, other.max))
+    }
+
+    /// The interval limited by lower and upper bounds
+    pub const fn clamp(self, lower: f64, upper: f64) -> Self {
+        Self::new(
+            min(max(self.min, lower), upper),
+            max(min(self.max, upper), lower),
+        )
+    }
+
+    /// Check when the interval contains other interval
+    pub const fn contains(self, other: Self) -> bool {
+        self.min <= other.min && other.max <= self.max
+    }
+}
+
+impl Add for Interval<f64> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Interval::add(self, other)
+    }
+}
+
+impl Sub for Interval<f64> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Interval::sub(self, other)
+    }
+}
+
+impl Mul for Interval<f64> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Interval::mul(self, other)
+    }
+}
+
+impl Neg for Interval<f64> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Interval::neg(self)
+    }
+}
+
+impl From<Interval<f32>> for Interval<f64> {
+    fn from(Interval { min, max }: Interval<f32>) -> Self {
+        Self::new(min as f64, max as f64)
+    }
+}
+
+impl From<Interval<f64>> for Interval<f32> {
+    /// The bounds is rounded outward
+    fn from(Interval { min, max }: Interval<f64>) -> Self {
+        let lower = min as f32;
+        let upper = max as f32;
+        Self {
+            min: if lower as f64 > min {
+                lower.next_down()
+            } else {
+                lower
+            },
+            max: if (upper as f64) < max {
+                upper.next_up()
+            } else {
+                upper
+            },
+        }
+    }
+}
+
+macro_rules! interval_f32_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Interval<f32> {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                Interval::<f64>::from(self)
+                    .$method(Interval::<f64>::from(other))
+                    .into()
+            }
+        }
+    };
+}
+
+interval_f32_op!(Add, add);
+interval_f32_op!(Sub, sub);
+interval_f32_op!(Mul, mul);
+
+impl Neg for Interval<f32> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            min: -self.max,
+            max: -self.min,
+        }
+    }
+}
+
+impl<R, B, E, Rr, Br, Er> Add<Interval<Fix<Rr, Br, Er>>> for Interval<Fix<R, B, E>>
+where
+    R: Radix<B>,
+    Rr: Radix<Br>,
+    Fix<R, B, E>: Add<Fix<Rr, Br, Er>>,
+{
+    type Output = Interval<Sum<Fix<R, B, E>, Fix<Rr, Br, Er>>>;
+
+    fn add(self, other: Interval<Fix<Rr, Br, Er>>) -> Self::Output {
+        Interval {
+            min: self.min + other.min,
+            max: self.max + other.max,
+        }
+    }
+}
+
+impl<R, B, E, Rr, Br, Er> Sub<Interval<Fix<Rr, Br, Er>>> for Interval<Fix<R, B, E>>
+where
+    R: Radix<B>,
+    Rr: Radix<Br>,
+    Fix<R, B, E>: Sub<Fix<Rr, Br, Er>>,
+{
+    type Output = Interval<Diff<Fix<R, B, E>, Fix<Rr, Br, Er>>>;
+
+    fn sub(self, other: Interval<Fix<Rr, Br, Er>>) -> Self::Output {
+        Interval {
+            min: self.min - other.max,
+            max: self.max - other.min,
+        }
+    }
+}
+
+impl<R, B, E, Rr, Br, Er> Mul<Interval<Fix<Rr, Br, Er>>> for Interval<Fix<R, B, E>>
+where
+    R: Radix<B>,
+    Rr: Radix<Br>,
+    Fix<R, B, E>: Copy + Mul<Fix<Rr, Br, Er>>,
+    Fix<Rr, Br, Er>: Copy,
+    Prod<Fix<R, B, E>, Fix<Rr, Br, Er>>: Copy + PartialOrd,
+{
+    type Output = Interval<Prod<Fix<R, B, E>, Fix<Rr, Br, Er>>>;
+
+    fn mul(self, other: Interval<Fix<Rr, Br, Er>>) -> Self::Output {
+        let mut result = Interval {
+            min: self.min * other.min,
+            max: self.min * other.min,
+        };
+        result.extend(self.min * other.max);
+        result.extend(self.max * other.min);
+        result.extend(self.max * other.max);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Cast;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin;
+
+    #[test]
+    fn interval_exact() {
+        const A: Interval<f64> = Interval::new(-1.0, 2.0);
+        const B: Interval<f64> = Interval::new(0.5, 4.0);
+        const C: Interval<f64> = A.mul(B).add(B).sub(A.neg());
+
+        assert_eq!(A + B, Interval::new(-0.5, 6.0));
+        assert_eq!(A - B, Interval::new(-5.0, 1.5));
+        assert_eq!(A * B, Interval::new(-4.0, 8.0));
+        assert_eq!(C, Interval::new(-4.5, 14.0));
+    }
+
+    #[test]
+    fn interval_outward() {
+        let sum = Interval::point(0.1) + Interval::point(0.2);
+        assert_eq!(sum.max, sum.min.next_up());
+        assert!(sum.includes(0.1 + 0.2));
+
+        let prod = Interval::point(0.1) * Interval::point(3.0);
+        assert_eq!(prod.max, prod.min.next_up());
+        assert!(prod.includes(0.1 * 3.0));
+
+        // the sum of tiny and huge values
+        let sum = Interval::point(1.0) + Interval::point(1e-30);
+        assert_eq!(sum, Interval::new(1.0, 1.0f64.next_up()));
+
+        let sum = Interval::from(0.1f32..=0.1) + Interval::from(0.2f32..=0.2);
+        assert!(sum.min < sum.max);
+        assert!(sum.includes(0.1f32 + 0.2f32));
+    }
+
+    #[test]
+    fn interval_fix() {
+        type T = bin::Fix<P16, N8>;
+        type P = bin::Fix<P32, N16>;
+
+        let a = Interval::from(T::cast(-1.5)..=T::cast(2.0));
+        let b = Interval::from(T::cast(-0.5)..=T::cast(0.25));
+
+        let p: Interval<P> = a * b;
+        assert_eq!(p, Interval::from(P::cast(-1.0)..=P::cast(0.75)));
+
+        let s = a - b;
+        assert_eq!(f64::cast(s.min), -1.75);
+        assert_eq!(f64::cast(s.max), 2.5);
+    }
+
+    #[test]
+    fn interval_monitor() {
+        let mut range = Interval::from(0..=0);
+        for value in [3, -2, 1, 5, 0].iter() {
+            range.extend(*value);
+        }
+        assert_eq!(range, Interval::from(-2..=5));
+        assert!(range.includes(4));
+        assert!(!range.includes(6));
+    }
+}
@@ -0,0 +1,38 @@
+/*!
+
+## Parameters mailbox
+
+The [`ParamMailbox`] allows to publish the new parameters of transducers from the background task
+to the control loop which runs in interrupt handler without disabling of interrupts.
+
+The mailbox is implemented in the `usync` crate, which contains all `unsafe` code of library.
+
+*/
+
+pub use usync::mailbox::{Consumer, ParamMailbox, Producer};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pid, Transducer};
+
+    #[test]
+    fn mailbox_regulator() {
+        type R = pid::Pi<f32, f32>;
+        let mut mailbox = ParamMailbox::new(pid::PiParam::<f32, f32>::new(1.0, 0.0, 0.01));
+        let (mut producer, mut consumer) = mailbox.split();
+        let mut state = pid::PiState::default();
+
+        assert_eq!(
+            consumer.read(|param| R::apply(param, &mut state, (1.0, 0.0))),
+            1.0
+        );
+
+        producer.publish(pid::PiParam::new(2.0, 0.0, 0.01)).unwrap();
+
+        assert_eq!(
+            consumer.read(|param| R::apply(param, &mut state, (1.0, 0.0))),
+            2.0
+        );
+    }
+}
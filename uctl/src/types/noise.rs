@@ -0,0 +1,204 @@
+/*!
+
+## Quantization noise budget
+
+Each rounding of value to the fixed-point type adds the error which is usually modeled
+as a white noise uniformly distributed over the least significant bit,
+so the variance of the noise is _LSB² / 12_ (the truncation gives the same variance with bias).
+
+The noise of stage is propagated through the next stages together with input noise,
+so the noise floor at the output of chain depends on the exponents of all stages.
+
+The [`NoiseBudget`] trait gives the variance of output noise of transducer
+for given variance of input noise, including the quantization noise of transducer itself.
+The noise of chains (tuples of transducers) is evaluated stage-by-stage,
+so the exponents of stages can be chosen systematically instead of by trial:
+the stage which noise dominates at the output should get more fractional bits.
+
+The [`ValueResolution`] trait gives the least significant bit of numeric type.
+The rounding errors of floating-point types is treated as negligible.
+
+The analysis is intended for host side (like tests) but doesn't require standard library.
+
+*/
+
+use super::range::pow;
+use crate::{Sqrt, Transducer};
+use typenum::{Bit, NonZero, PInt, UInt, Unsigned};
+use ufix::{Digits, Exponent, Fix, Radix};
+
+/// The least significant bit of type
+pub trait ValueResolution {
+    /// The value of least significant bit
+    const LSB: f64;
+
+    /// The variance of quantization noise _LSB² / 12_
+    const NOISE: f64 = Self::LSB * Self::LSB / 12.0;
+}
+
+macro_rules! value_resolution {
+    ($lsb:expr => $($type:ty),+) => {
+        $(
+            impl ValueResolution for $type {
+                const LSB: f64 = $lsb;
+            }
+        )+
+    };
+}
+
+value_resolution!(1.0 => i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+value_resolution!(0.0 => f32, f64);
+
+#[cfg(feature = "i128")]
+value_resolution!(1.0 => i128, u128);
+
+impl<U, B, E> ValueResolution for Fix<PInt<U>, B, E>
+where
+    U: Unsigned + NonZero,
+    PInt<U>: Radix<B>,
+    B: Digits,
+    E: Exponent,
+{
+    const LSB: f64 = pow(U::U64, E::I32);
+}
+
+impl<U, T, B, E> ValueResolution for Fix<UInt<U, T>, B, E>
+where
+    U: Unsigned,
+    T: Bit,
+    UInt<U, T>: Radix<B>,
+    B: Digits,
+    E: Exponent,
+{
+    const LSB: f64 = pow(<UInt<U, T> as Unsigned>::U64, E::I32);
+}
+
+/**
+Quantization noise propagation through transducer
+
+The stationary (steady-state) noise is evaluated.
+*/
+pub trait NoiseBudget: Transducer {
+    /**
+    Evaluate the variance of output noise
+
+    - `param`: The parameters of transducer
+    - `input`: The variance of input noise
+     */
+    fn output_noise(param: &Self::Param, input: f64) -> f64;
+
+    /**
+    Evaluate the noise floor (RMS of output noise)
+
+    - `param`: The parameters of transducer
+    - `input`: The variance of input noise
+     */
+    fn noise_floor(param: &Self::Param, input: f64) -> f64 {
+        Sqrt::sqrt(Self::output_noise(param, input))
+    }
+}
+
+macro_rules! noise_budget_tuple {
+    ( $type0:tt => $field0:tt, $( $typeN:tt : $ptypeN:tt => $fieldN:tt ),+) => {
+        impl<$type0, $($typeN),+> NoiseBudget for ($type0, $($typeN),+)
+        where
+            $type0: NoiseBudget,
+            $($typeN: NoiseBudget<Input = $ptypeN::Output>),+
+        {
+            fn output_noise(param: &Self::Param, input: f64) -> f64 {
+                let noise = $type0::output_noise(&param.$field0, input);
+                $(
+                    let noise = $typeN::output_noise(&param.$fieldN, noise);
+                )+
+                noise
+            }
+        }
+    }
+}
+
+noise_budget_tuple!(A => 0, B: A => 1);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12, N: M => 13);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12, N: M => 13, O: N => 14);
+noise_budget_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12, N: M => 13, O: N => 14, P: O => 15);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{clamper::Clamper, ema, fir, pfdl::Store as DL, scaler, Cast};
+    use typenum::{N12, N4, N8, P16, P32, U3};
+    use ufix::bin;
+
+    #[test]
+    fn value_resolution() {
+        assert_eq!(i16::LSB, 1.0);
+        assert_eq!(f32::NOISE, 0.0);
+        assert_eq!(bin::Fix::<P16, N8>::LSB, 1.0 / 256.0);
+        assert_eq!(bin::UFix::<P16, N4>::NOISE, 1.0 / 16.0 / 16.0 / 12.0);
+    }
+
+    #[test]
+    fn chain_noise() {
+        type T = bin::Fix<P16, N8>;
+        type S = scaler::Scaler<T, T, T>;
+        type E = ema::Filter<T, T, T>;
+        type F = fir::Filter<T, T, DL<T, U3>>;
+        type C = Clamper<core::ops::RangeInclusive<T>, T>;
+
+        let q = T::NOISE;
+
+        // scaling by 2
+        let scale =
+            scaler::Param::<T, T>::new(T::cast(0.0)..=T::cast(1.0), T::cast(0.0)..=T::cast(2.0));
+        assert_eq!(S::output_noise(&scale, q), 4.0 * q + q);
+
+        // the moving average of 4 values
+        let fir = fir::Param::<T, U3>::from([T::cast(0.25); 4]);
+        assert_eq!(F::output_noise(&fir, 4.0 * q), q + 4.0 * q);
+
+        // α = 0.5
+        let ema = ema::Param::from_alpha(T::cast(0.5));
+        assert!((E::output_noise(&ema, 3.0 * q) - (q + 8.0 / 3.0 * q)).abs() < 1e-15);
+
+        let clamp = T::cast(-1.0)..=T::cast(1.0);
+        assert_eq!(C::output_noise(&clamp, q), q);
+
+        type Chain = (S, F, C);
+        let param = (scale, fir, clamp);
+        assert_eq!(Chain::output_noise(&param, 0.0), 0.25 * q + 4.0 * q);
+        assert!((Chain::noise_floor(&param, 0.0) - Sqrt::sqrt(4.25 * q)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn choose_exponent() {
+        type Coarse = bin::Fix<P32, N8>;
+        type Fine = bin::Fix<P32, N12>;
+
+        let ema = |alpha: f64| ema::Param::from_alpha(alpha);
+
+        // the filter with more fractional bits has lower noise floor
+        let coarse = ema::Filter::<Coarse, Coarse, Coarse>::noise_floor(
+            &ema::Param::from_alpha(Coarse::cast(0.125)),
+            0.0,
+        );
+        let fine = ema::Filter::<Fine, Fine, Fine>::noise_floor(
+            &ema::Param::from_alpha(Fine::cast(0.125)),
+            0.0,
+        );
+        assert!((coarse / fine - 16.0).abs() < 1e-9);
+
+        // the floating-point filter doesn't add noise
+        let noise = ema::Filter::<f64, f64, f64>::output_noise(&ema(0.1), 1.0);
+        assert!((noise - 0.1 / 1.9).abs() < 1e-12);
+    }
+}
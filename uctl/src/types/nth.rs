@@ -0,0 +1,150 @@
+/*!
+
+## Access to stages of chains
+
+The composed transducers (tuples) has tuples of parameters and states,
+so the deeply nested stages is hard to reach in generic code.
+
+The [`NthParam`] and [`NthState`] traits provides access to parameters and state
+of individual stage of chain by type-level index:
+
+```
+use uctl::{NthParam, NthState, Transducer, FnTransducer, ema};
+use typenum::U0;
+
+type Chain = (ema::Filter<f32, f32, f32>, FnTransducer<f32, f32>);
+
+let mut param = (ema::Param::from_alpha(0.5), (|x| x * 2.0) as fn(f32) -> f32);
+let mut state = (ema::State::new(0.0), ());
+
+assert_eq!(Chain::apply(&param, &mut state, 1.0), 1.0);
+
+// retune first stage
+*<Chain as NthParam<U0>>::nth_param_mut(&mut param) = ema::Param::from_alpha(1.0);
+// reset first stage
+*<Chain as NthState<U0>>::nth_state_mut(&mut state) = ema::State::new(0.0);
+
+assert_eq!(Chain::apply(&param, &mut state, 1.0), 2.0);
+```
+
+*/
+
+use crate::Transducer;
+use typenum::consts::*;
+
+/**
+Access to parameters of stage of chain
+
+- `N` - type-level index of stage
+*/
+pub trait NthParam<N>: Transducer {
+    /// The stage parameters type
+    type Nth;
+
+    /// Get reference to stage parameters
+    fn nth_param(param: &Self::Param) -> &Self::Nth;
+
+    /// Get mutable reference to stage parameters
+    fn nth_param_mut(param: &mut Self::Param) -> &mut Self::Nth;
+}
+
+/**
+Access to state of stage of chain
+
+- `N` - type-level index of stage
+*/
+pub trait NthState<N>: Transducer {
+    /// The stage state type
+    type Nth;
+
+    /// Get reference to stage state
+    fn nth_state(state: &Self::State) -> &Self::Nth;
+
+    /// Get mutable reference to stage state
+    fn nth_state_mut(state: &mut Self::State) -> &mut Self::Nth;
+}
+
+macro_rules! nth_stage {
+    ([$($type:ident),+], $index:ty, $stage:ident, $field:tt) => {
+        impl<$($type),+> NthParam<$index> for ($($type),+)
+        where
+            $($type: Transducer,)+
+            Self: Transducer<Param = ($($type::Param),+), State = ($($type::State),+)>,
+        {
+            type Nth = $stage::Param;
+
+            fn nth_param(param: &Self::Param) -> &Self::Nth {
+                &param.$field
+            }
+
+            fn nth_param_mut(param: &mut Self::Param) -> &mut Self::Nth {
+                &mut param.$field
+            }
+        }
+
+        impl<$($type),+> NthState<$index> for ($($type),+)
+        where
+            $($type: Transducer,)+
+            Self: Transducer<Param = ($($type::Param),+), State = ($($type::State),+)>,
+        {
+            type Nth = $stage::State;
+
+            fn nth_state(state: &Self::State) -> &Self::Nth {
+                &state.$field
+            }
+
+            fn nth_state_mut(state: &mut Self::State) -> &mut Self::Nth {
+                &mut state.$field
+            }
+        }
+    };
+}
+
+macro_rules! nth_tuple {
+    ($types:tt; $($index:ty => $stage:ident => $field:tt),+) => {
+        $(nth_stage!($types, $index, $stage, $field);)+
+    };
+}
+
+nth_tuple!([A, B]; U0 => A => 0, U1 => B => 1);
+nth_tuple!([A, B, C]; U0 => A => 0, U1 => B => 1, U2 => C => 2);
+nth_tuple!([A, B, C, D]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3);
+nth_tuple!([A, B, C, D, E]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4);
+nth_tuple!([A, B, C, D, E, F]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5);
+nth_tuple!([A, B, C, D, E, F, G]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6);
+nth_tuple!([A, B, C, D, E, F, G, H]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7);
+nth_tuple!([A, B, C, D, E, F, G, H, I]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J, K]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9, U10 => K => 10);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J, K, L]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9, U10 => K => 10, U11 => L => 11);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J, K, L, M]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9, U10 => K => 10, U11 => L => 11, U12 => M => 12);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J, K, L, M, N]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9, U10 => K => 10, U11 => L => 11, U12 => M => 12, U13 => N => 13);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J, K, L, M, N, O]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9, U10 => K => 10, U11 => L => 11, U12 => M => 12, U13 => N => 13, U14 => O => 14);
+nth_tuple!([A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P]; U0 => A => 0, U1 => B => 1, U2 => C => 2, U3 => D => 3, U4 => E => 4, U5 => F => 5, U6 => G => 6, U7 => H => 7, U8 => I => 8, U9 => J => 9, U10 => K => 10, U11 => L => 11, U12 => M => 12, U13 => N => 13, U14 => O => 14, U15 => P => 15);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, FnTransducer};
+
+    type E = ema::Filter<f32, f32, f32>;
+    type C = (E, FnTransducer<f32, f32>, E);
+
+    #[test]
+    fn nth_stage() {
+        let mut param = (
+            ema::Param::from_alpha(0.5),
+            (|x| x * 2.0) as fn(f32) -> f32,
+            ema::Param::from_alpha(0.5),
+        );
+        let mut state = (ema::State::new(0.0), (), ema::State::new(0.0));
+
+        assert_eq!(C::apply(&param, &mut state, 1.0), 0.5);
+
+        *<C as NthState<U2>>::nth_state_mut(&mut state) = ema::State::new(4.0);
+        *<C as NthParam<U1>>::nth_param_mut(&mut param) = |x| x;
+        assert_eq!(C::apply(&param, &mut state, 1.0), 2.375);
+
+        assert_eq!(<C as NthParam<U1>>::nth_param(&param)(3.0), 3.0);
+    }
+}
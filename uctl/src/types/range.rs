@@ -0,0 +1,412 @@
+/*!
+
+## Worst-case ranges
+
+The fixed-point types has limited range, so the values can overflow
+when the chain is fed by unexpected input or the parameters is too big.
+The overflow usually isn't detected at runtime and gives the totally wrong results.
+
+The [`Bounds`] represents the worst-case range of values.
+The operations on bounds is `const` [interval arithmetic](Interval),
+so the ranges can be propagated in constant context.
+
+The [`ValueBounds`] trait gives the range of values which can be represented by numeric type.
+The [`assert_bounds!`](crate::assert_bounds) macro verifies at compile-time
+that the range fits to the type, so the overflow becomes a compile error.
+
+The [`WorstCase`] trait gives the worst-case output range of transducer for given input range
+and verifies that the output range fits to the output type.
+The worst-case of chains (tuples of transducers) is evaluated stage-by-stage,
+so the stage which can overflow is detected.
+
+The [`WorstCase`] evaluation isn't `const`, because the parameters of transducers usually
+is initialized at runtime. So each transducer which implements it also provides the `const fn bounds`
+in its module (like [`scaler::bounds`](crate::scaler::bounds) or [`fir::bounds`](crate::fir::bounds))
+which takes the input range and the parameters as `f64` values.
+The [`assert_bounds!`](crate::assert_bounds) macro evaluates the chain of such functions in constant
+context and verifies the output range of each stage, so the whole chain is verified at compile-time.
+
+The bounds is evaluated using double precision floating-point values
+regardless of types of values (the results is rounded outward).
+
+*/
+
+use crate::{Interval, Transducer};
+use typenum::{Bit, NonZero, PInt, UInt, Unsigned};
+use ufix::{Digits, Exponent, Fix, Radix};
+
+/**
+Worst-case range of values
+
+The range is closed (both bounds is included).
+*/
+pub type Bounds = Interval<f64>;
+
+impl Interval<f64> {
+    /// Check when the range fits to the type
+    pub const fn fits<T: ValueBounds>(self) -> bool {
+        T::BOUNDS.contains(self)
+    }
+
+    /// Verify when the range fits to the type
+    pub fn check<T: ValueBounds>(self) -> Result<Self, RangeOverflow> {
+        if self.fits::<T>() {
+            Ok(self)
+        } else {
+            Err(RangeOverflow {
+                bounds: self,
+                limits: T::BOUNDS,
+            })
+        }
+    }
+}
+
+/// The range which cannot be represented by type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeOverflow {
+    /// The worst-case range of values
+    pub bounds: Bounds,
+    /// The range which can be represented
+    pub limits: Bounds,
+}
+
+/// The range of values which can be represented by type
+pub trait ValueBounds {
+    /// The range of values
+    const BOUNDS: Bounds;
+}
+
+macro_rules! value_bounds {
+    ($($type:ty),+) => {
+        $(
+            impl ValueBounds for $type {
+                const BOUNDS: Bounds = Bounds::new(<$type>::MIN as f64, <$type>::MAX as f64);
+            }
+        )+
+    };
+}
+
+/// The maximum is rounded toward zero to the nearest double precision value
+macro_rules! value_bounds_int {
+    ($($type:ty),+) => {
+        $(
+            impl ValueBounds for $type {
+                const BOUNDS: Bounds = {
+                    let digits = <$type>::BITS - <$type>::MAX.leading_zeros();
+                    let shift = digits.saturating_sub(f64::MANTISSA_DIGITS);
+                    Bounds::new(<$type>::MIN as f64, (<$type>::MAX >> shift << shift) as f64)
+                };
+            }
+        )+
+    };
+}
+
+value_bounds_int!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+value_bounds!(f32, f64);
+
+#[cfg(feature = "i128")]
+value_bounds_int!(i128, u128);
+
+/// The power of radix
+pub(crate) const fn pow(radix: u64, exp: i32) -> f64 {
+    let mut value = 1.0;
+    let mut n = 0;
+    while n < exp.abs() {
+        value *= radix as f64;
+        n += 1;
+    }
+    if exp < 0 {
+        1.0 / value
+    } else {
+        value
+    }
+}
+
+/// The range of mantissa of signed types (the sign bit is counted for binary types)
+impl<U, B, E> ValueBounds for Fix<PInt<U>, B, E>
+where
+    U: Unsigned + NonZero,
+    PInt<U>: Radix<B>,
+    B: Digits,
+    E: Exponent,
+{
+    const BOUNDS: Bounds = {
+        let digits = if U::U64 == 2 { B::I32 - 1 } else { B::I32 };
+        let limit = pow(U::U64, digits);
+        let lsb = pow(U::U64, E::I32);
+        let max = limit - 1.0;
+        let min = if U::U64 == 2 { -limit } else { -max };
+        Bounds::new(min * lsb, max * lsb)
+    };
+}
+
+/// The range of mantissa of unsigned types
+impl<U, T, B, E> ValueBounds for Fix<UInt<U, T>, B, E>
+where
+    U: Unsigned,
+    T: Bit,
+    UInt<U, T>: Radix<B>,
+    B: Digits,
+    E: Exponent,
+{
+    const BOUNDS: Bounds = {
+        let radix = <UInt<U, T> as Unsigned>::U64;
+        Bounds::new(0.0, (pow(radix, B::I32) - 1.0) * pow(radix, E::I32))
+    };
+}
+
+/**
+Worst-case output range of transducer
+
+The implementation should verify that the output range fits to the output type.
+*/
+pub trait WorstCase: Transducer {
+    /**
+    Evaluate the output range
+
+    - `param`: The parameters of transducer
+    - `input`: The range of input values
+
+    The initial (default) state is taken into account.
+     */
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow>;
+}
+
+macro_rules! worst_case_tuple {
+    ( $type0:tt => $field0:tt, $( $typeN:tt : $ptypeN:tt => $fieldN:tt ),+) => {
+        impl<$type0, $($typeN),+> WorstCase for ($type0, $($typeN),+)
+        where
+            $type0: WorstCase,
+            $($typeN: WorstCase<Input = $ptypeN::Output>),+
+        {
+            fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+                let bounds = $type0::output_bounds(&param.$field0, input)?;
+                $(
+                    let bounds = $typeN::output_bounds(&param.$fieldN, bounds)?;
+                )+
+                Ok(bounds)
+            }
+        }
+    }
+}
+
+worst_case_tuple!(A => 0, B: A => 1);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12, N: M => 13);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12, N: M => 13, O: N => 14);
+worst_case_tuple!(A => 0, B: A => 1, C: B => 2, D: C => 3, E: D => 4, F: E => 5, G: F => 6, H: G => 7, I: H => 8, J: I => 9, K: J => 10, L: K => 11, M: L => 12, N: M => 13, O: N => 14, P: O => 15);
+
+/**
+Verify at compile-time that the range fits to the type
+
+```
+use uctl::{assert_bounds, Bounds};
+use ufix::bin::Fix;
+use typenum::{N8, P16};
+
+type T = Fix<P16, N8>;
+
+/// The input range
+const INPUT: Bounds = Bounds::symmetric(10.0);
+
+/// The gain of stage
+const GAIN: f64 = 5.0;
+
+assert_bounds!(T, INPUT.scale(GAIN));
+```
+
+The overflow gives compile error:
+
+```compile_fail
+# use uctl::{assert_bounds, Bounds};
+# use ufix::bin::Fix;
+# use typenum::{N8, P16};
+#
+# type T = Fix<P16, N8>;
+#
+assert_bounds!(T, Bounds::symmetric(10.0).scale(20.0));
+```
+
+The chain of transducers is verified stage-by-stage using the `bounds` functions of stages.
+The input range is passed as first argument of each function, the remaining arguments
+is the parameters of stage:
+
+```
+use uctl::{assert_bounds, Bounds};
+use ufix::bin::Fix;
+use typenum::{N8, P16};
+
+type T = Fix<P16, N8>;
+
+assert_bounds!(Bounds::symmetric(2.0)
+    => T: uctl::scaler::bounds(10.0, 5.0)
+    => T: uctl::ema::bounds()
+    => T: uctl::fir::bounds(&[0.5, -1.0, 0.5]));
+```
+
+The stage which can overflow gives compile error:
+
+```compile_fail
+# use uctl::{assert_bounds, Bounds};
+# use ufix::bin::Fix;
+# use typenum::{N8, P16};
+#
+# type T = Fix<P16, N8>;
+#
+assert_bounds!(Bounds::symmetric(20.0)
+    => T: uctl::scaler::bounds(10.0, 5.0)
+    => T: uctl::clamper::bounds(-1.0, 1.0));
+```
+*/
+#[macro_export]
+macro_rules! assert_bounds {
+    ($input:expr $(=> $type:ty : $($stage:ident)::+ ($($arg:expr),* $(,)?))+) => {
+        const _: () = {
+            let bounds: $crate::Bounds = $input;
+            $(
+                let bounds = $($stage)::+(bounds, $($arg),*);
+                assert!(
+                    $crate::Bounds::fits::<$type>(bounds),
+                    "The values range doesn't fit to the type"
+                );
+            )+
+        };
+    };
+    ($type:ty, $bounds:expr) => {
+        const _: () = assert!(
+            $crate::Bounds::fits::<$type>($bounds),
+            "The values range doesn't fit to the type"
+        );
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        clamper::Clamper, ema, fir, lqe, pfdl::Store as DL, pid, scaler, tf, Cast, Transducer,
+    };
+    use typenum::{N15, N8, P16, P8, U1, U2};
+    use ufix::{bin, dec};
+
+    #[test]
+    fn value_bounds() {
+        assert_eq!(i8::BOUNDS, Bounds::new(-128.0, 127.0));
+        assert_eq!(u16::BOUNDS, Bounds::new(0.0, 65535.0));
+
+        assert_eq!(
+            bin::Fix::<P16, N15>::BOUNDS,
+            Bounds::new(-1.0, 1.0 - 1.0 / 32768.0)
+        );
+        assert_eq!(bin::UFix::<P8, N8>::BOUNDS, Bounds::new(0.0, 255.0 / 256.0));
+        assert!((dec::Fix::<P8, N8>::BOUNDS.max - 0.99999999).abs() < 1e-15);
+
+        // the maximum isn't rounded up to the power of two
+        assert_eq!(i64::BOUNDS.max, (i64::MAX - 1023) as f64);
+        assert_eq!(u64::BOUNDS.max, (u64::MAX - 2047) as f64);
+        assert_eq!(u32::BOUNDS, Bounds::new(0.0, 4294967295.0));
+    }
+
+    #[test]
+    fn bounds_ops() {
+        let a = Bounds::new(-1.0, 2.0);
+        let b = Bounds::new(3.0, 4.0);
+
+        assert_eq!(a.add(b), Bounds::new(2.0, 6.0));
+        assert_eq!(a.sub(b), Bounds::new(-5.0, -1.0));
+        assert_eq!(a.mul(b), Bounds::new(-4.0, 8.0));
+        assert_eq!(a.scale(-2.0), Bounds::new(-4.0, 2.0));
+        assert_eq!(a.hull(b), Bounds::new(-1.0, 4.0));
+        assert_eq!(b.clamp(0.0, 3.5), Bounds::new(3.0, 3.5));
+        assert!(b.hull(a).contains(a));
+        assert!(!a.contains(b));
+    }
+
+    #[test]
+    fn chain_bounds() {
+        type T = bin::Fix<P16, N8>;
+        type S = scaler::Scaler<T, T, T>;
+        type C = Clamper<core::ops::RangeInclusive<T>, T>;
+        type F = fir::Filter<T, T, DL<T, U2>>;
+        type E = ema::Filter<T, T, T>;
+
+        let fir = fir::Param::<T, U2>::from([T::cast(0.5), T::cast(-1.0), T::cast(0.5)]);
+        let clamp = T::cast(-1.0)..=T::cast(1.0);
+
+        // scaling by 10 with offset 5
+        let scale =
+            scaler::Param::<T, T>::new(T::cast(0.0)..=T::cast(1.0), T::cast(5.0)..=T::cast(15.0));
+
+        type C1 = (S, E, F);
+        let input = Bounds::new(-2.0, 2.0);
+        assert_eq!(
+            C1::output_bounds(&(scale, ema::Param::from_alpha(T::cast(0.5)), fir), input),
+            Ok(Bounds::new(-40.0, 40.0))
+        );
+
+        // the output of scaler can overflow
+        let input = Bounds::new(-20.0, 20.0);
+        let result = C1::output_bounds(&(scale, ema::Param::from_alpha(T::cast(0.5)), fir), input);
+        assert_eq!(result.unwrap_err().bounds, Bounds::new(-195.0, 205.0));
+
+        // the clamper protects next stages
+        type C2 = (C, S, F);
+        assert_eq!(
+            C2::output_bounds(&(clamp, scale, fir), input),
+            Ok(Bounds::new(-20.0, 20.0))
+        );
+    }
+
+    #[test]
+    fn chain_bounds_regulators() {
+        type T = bin::Fix<P16, N8>;
+        type F = tf::TransferFunction<T, T, T, T, U1>;
+        type E = lqe::Filter<T, T, T, T, T, T, T>;
+        type R = pid::Pid<T, T>;
+
+        // y = 0.5 x + 0.5 y[-1]
+        let lowpass = tf::Param::<T, U1>::new(&[0.5], &[1.0, -0.5]);
+        let estimator = lqe::Param::<T, T, T>::new(0.6, 0.5, 0.2, 0.4);
+        let regulator = pid::Param::<T, T>::new(1.0, 2.0, 0.1, 10.0, 0.1);
+
+        let input = Bounds::symmetric(2.0);
+        let bounds = F::output_bounds(&lowpass, input).unwrap();
+        assert!(bounds.max <= 2.0 && bounds.max > 2.0 - 1e-9);
+        assert_eq!(bounds.min, -bounds.max);
+
+        // the extreme inputs stays in range
+        let bounds = E::output_bounds(&estimator, input).unwrap();
+        let mut state = lqe::State::default();
+        for step in 0..100 {
+            let value = T::cast(if step % 3 == 0 { -2.0 } else { 2.0 });
+            assert!(bounds.includes(f64::cast(E::apply(&estimator, &mut state, value))));
+        }
+
+        // the integral of regulator without limits is unbounded
+        assert!(R::output_bounds(&regulator, input).is_err());
+        assert_eq!(
+            R::output_bounds(&regulator.with_limits(-10.0, 10.0), input),
+            Ok(Bounds::symmetric(10.0))
+        );
+
+        // the unstable function is unbounded
+        let unstable = tf::Param::<T, U1>::new(&[1.0], &[1.0, -1.5]);
+        assert!(F::output_bounds(&unstable, input).is_err());
+
+        // the same chain is verified at compile-time
+        assert_bounds!(Bounds::symmetric(2.0)
+            => T: tf::bounds(&[0.5, 0.0], &[-0.5])
+            => T: lqe::bounds(0.6, 0.5, 0.2, 0.4)
+            => T: pid::bounds(-10.0, 10.0));
+    }
+}
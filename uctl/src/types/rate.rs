@@ -0,0 +1,361 @@
+/*!
+
+## Type-level sampling rate
+
+The parameters of many transducers (filters, regulators and etc.) depends on sampling period,
+so the transducer designed for one sampling rate gives wrong results at another one.
+Such mismatch is hard to find on target, because the loop still works, but badly.
+
+The [`Rated`] wraps the transducer and tags its input and output values by sampling rate
+using [`Sampled`] values, so the chain of stages designed for different sampling rates
+doesn't compile (the output of one stage doesn't match the input of next stage):
+
+```compile_fail
+use uctl::{ema, Rated, Sampled, Transducer};
+use typenum::{U100, U1000};
+
+type Fast = Rated<ema::Filter<f32, f32, f32>, U1000>;
+type Slow = Rated<ema::Filter<f32, f32, f32>, U100>;
+
+let param = (
+    Fast::param(|period| ema::Param::from_time(0.01, period)),
+    Slow::param(|period| ema::Param::from_time(0.1, period)),
+);
+let mut state = (ema::State::default(), ema::State::default());
+
+<(Fast, Slow)>::apply(&param, &mut state, Sampled::new(1.0));
+```
+
+The rate change should be done explicitly by [`Resample`] stage.
+The resampler decimates the values, so the output is present only on every _F/R_ step.
+The rate boundary splits the chain: the stages after resampler is called only
+when the value is present:
+
+```
+use uctl::{ema, Rated, Resample, Sampled, Transducer};
+use typenum::{U100, U1000};
+
+type Fast = Rated<ema::Filter<f32, f32, f32>, U1000>;
+type Slow = Rated<ema::Filter<f32, f32, f32>, U100>;
+type Decimate = (Fast, Resample<f32, U1000, U100>);
+
+let fast = (Fast::param(|period| ema::Param::from_time(0.01, period)), ());
+let slow = Slow::param(|period| ema::Param::from_time(0.1, period));
+let mut fast_state = Default::default();
+let mut slow_state = ema::State::default();
+
+for _ in 0..1000 {
+    if let Some(x) = Decimate::apply(&fast, &mut fast_state, Sampled::new(1.0)) {
+        let _y: Sampled<f32, U100> = Slow::apply(&slow, &mut slow_state, x);
+    }
+}
+```
+
+The rate is given by the type which implements [`Rate`]: the type-level unsigned integer
+is a rate in Hz and the [`Ratio`] of type-level unsigned integers is a fractional rate.
+The [`Rated::param`] gives the sampling period of rate to the constructor of parameters
+and tags the parameters by rate using [`RatedParam`], so the parameters created for one rate
+cannot be used with the transducer designed for another rate:
+
+```compile_fail
+use uctl::{ema, Rated, Transducer};
+use typenum::{U100, U1000};
+
+type Fast = Rated<ema::Filter<f32, f32, f32>, U1000>;
+type Slow = Rated<ema::Filter<f32, f32, f32>, U100>;
+
+let param = Slow::param(|period| ema::Param::from_time(0.1, period));
+let mut state = ema::State::default();
+
+Fast::apply(&param, &mut state, uctl::Sampled::new(1.0));
+```
+
+The tags has no runtime cost.
+
+*/
+
+use crate::{snapshot_fields, ResetState, Transducer};
+use core::marker::PhantomData;
+use typenum::{NonZero, Unsigned};
+
+/// Convert time to the number of sampling steps (rounding up)
+pub(crate) fn steps(time: f64, period: f64) -> u32 {
+    let steps = time / period;
+    let whole = steps as u32;
+    if (whole as f64) < steps {
+        whole + 1
+    } else {
+        whole
+    }
+}
+
+/// Sampling rate
+pub trait Rate {
+    /// The sampling rate (Hz)
+    const HZ: f64;
+
+    /// The sampling period (s)
+    const PERIOD: f64 = 1.0 / Self::HZ;
+}
+
+/// The integer rate in Hz
+impl<U> Rate for U
+where
+    U: Unsigned + NonZero,
+{
+    const HZ: f64 = U::U64 as f64;
+}
+
+/**
+Fractional sampling rate
+
+- `N` - numerator (Hz)
+- `D` - denominator
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Ratio<N, D>(PhantomData<(N, D)>);
+
+impl<N, D> Rate for Ratio<N, D>
+where
+    N: Unsigned + NonZero,
+    D: Unsigned + NonZero,
+{
+    const HZ: f64 = N::U64 as f64 / D::U64 as f64;
+}
+
+/**
+The value tagged by sampling rate
+
+- `T` - value type
+- `R` - sampling rate
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sampled<T, R> {
+    /// The value
+    pub value: T,
+    rate: PhantomData<R>,
+}
+
+impl<T, R> Sampled<T, R> {
+    /// Tag value by sampling rate
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            rate: PhantomData,
+        }
+    }
+
+    /// Get untagged value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/**
+The parameters created for given sampling rate
+
+- `P` - parameters type
+- `R` - sampling rate
+
+The parameters can be created only by [`Rated::param`].
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct RatedParam<P, R> {
+    param: P,
+    rate: PhantomData<R>,
+}
+
+impl<P, R> RatedParam<P, R> {
+    /// Get untagged parameters
+    pub fn param(&self) -> &P {
+        &self.param
+    }
+}
+
+/**
+The transducer designed for given sampling rate
+
+- `T` - transducer type
+- `R` - sampling rate
+
+The input and output values and the parameters is tagged by sampling rate.
+*/
+#[derive(Debug)]
+pub struct Rated<T, R>(PhantomData<(T, R)>);
+
+impl<T, R> Rated<T, R>
+where
+    T: Transducer,
+    R: Rate,
+{
+    /**
+    Create parameters of transducer for sampling period of rate
+
+    - `init`: The constructor of parameters which takes sampling period (s)
+     */
+    pub fn param(init: impl FnOnce(f64) -> T::Param) -> RatedParam<T::Param, R> {
+        RatedParam {
+            param: init(R::PERIOD),
+            rate: PhantomData,
+        }
+    }
+}
+
+impl<T, R> Transducer for Rated<T, R>
+where
+    T: Transducer,
+    R: Rate,
+{
+    type Input = Sampled<T::Input, R>;
+    type Output = Sampled<T::Output, R>;
+    type Param = RatedParam<T::Param, R>;
+    type State = T::State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        Sampled::new(T::apply(&param.param, state, value.value))
+    }
+}
+
+/**
+Explicit change of sampling rate
+
+- `T` - values type
+- `F` - source sampling rate
+- `R` - target sampling rate
+
+The resampler passes each _F/R_-th value to the output, so the stages after it
+run at target rate. The source rate should be an integer multiple of target rate,
+otherwise the use of resampler gives compile error:
+
+```compile_fail
+use uctl::{Resample, Sampled, Transducer};
+use typenum::{U100, U1000};
+
+Resample::<f32, U100, U1000>::apply(&(), &mut Default::default(), Sampled::new(1.0));
+```
+
+The decimation filter should be placed before it when needed.
+*/
+#[derive(Debug)]
+pub struct Resample<T, F, R>(PhantomData<(T, F, R)>);
+
+impl<T, F, R> Resample<T, F, R>
+where
+    F: Rate,
+    R: Rate,
+{
+    /// The decimation ratio
+    pub const RATIO: u32 = {
+        let ratio = F::HZ / R::HZ;
+        let whole = (ratio + 0.5) as u32;
+        assert!(
+            whole >= 1 && (ratio - whole as f64).abs() <= 1e-9 * ratio,
+            "The source rate should be an integer multiple of target rate"
+        );
+        whole
+    };
+}
+
+/**
+Resampler state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResampleState {
+    /// The number of input values since last output
+    phase: u32,
+}
+
+impl ResetState for ResampleState {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    ResampleState {
+        phase: u32,
+    }
+}
+
+impl<T, F, R> Transducer for Resample<T, F, R>
+where
+    F: Rate,
+    R: Rate,
+{
+    type Input = Sampled<T, F>;
+    type Output = Option<Sampled<T, R>>;
+    type Param = ();
+    type State = ResampleState;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.phase += 1;
+        if state.phase < Self::RATIO {
+            return None;
+        }
+        state.phase = 0;
+
+        Some(Sampled::new(value.value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, pid};
+    use typenum::{U1, U10, U100, U3, U30};
+
+    #[test]
+    fn rates() {
+        assert_eq!(<U100 as Rate>::HZ, 100.0);
+        assert_eq!(<U100 as Rate>::PERIOD, 0.01);
+        assert_eq!(<Ratio<U1, U10> as Rate>::PERIOD, 10.0);
+        assert!((<Ratio<U10, U3> as Rate>::HZ - 10.0 / 3.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn rated_chain() {
+        type R = U10;
+        type F = Rated<ema::Filter<f64, f64, f64>, R>;
+        type I = Rated<pid::Integral<f64, f64>, R>;
+        type S = Rated<crate::FnTransducer<f64, (f64, f64)>, R>;
+
+        let param = (
+            F::param(|period| ema::Param::from_alpha(period / (0.1 + period))),
+            S::param(|_| (|x| (x, 0.0)) as fn(f64) -> (f64, f64)),
+            I::param(|period| pid::IParam::new::<f64>(2.0, period)),
+        );
+        let mut state = Default::default();
+
+        type C = (F, S, I);
+        let y = C::apply(&param, &mut state, Sampled::new(1.0));
+        // α = 0.5, Ki P = 0.2
+        assert!((y.into_inner() - 0.1).abs() < 1e-12);
+
+        // the untagged parameters can be used with unwrapped transducer
+        let y = ema::Filter::<f64, f64, f64>::apply(param.0.param(), &mut Default::default(), 1.0);
+        assert!((y - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn resample_decimates() {
+        type D = Resample<u8, U30, Ratio<U10, U3>>;
+        assert_eq!(D::RATIO, 9);
+
+        let mut state = ResampleState::default();
+        let mut output = (0..20)
+            .filter_map(|value| D::apply(&(), &mut state, Sampled::new(value)))
+            .map(Sampled::into_inner);
+        assert_eq!(output.next(), Some(8));
+        assert_eq!(output.next(), Some(17));
+        assert_eq!(output.next(), None);
+
+        state.reset();
+        assert!(D::apply(&(), &mut state, Sampled::new(0)).is_none());
+    }
+}
@@ -0,0 +1,93 @@
+/*!
+
+## Reset and initialization of states
+
+The [`ResetState`] trait provides common protocol for re-initialization of states
+of filters and regulators, which is required on mode changes or after faults.
+
+The state can be reset to the initial (default) value or preset to the steady state
+which corresponds to the given value.
+
+The trait is implemented for tuples of states too, so the whole chain can be reset in one call.
+
+*/
+
+/// Resettable state
+pub trait ResetState {
+    /// The value for presetting state
+    type Value;
+
+    /// Reset state to the initial value
+    fn reset(&mut self);
+
+    /// Preset state to the steady state for given value
+    fn preset(&mut self, value: Self::Value);
+}
+
+impl ResetState for () {
+    type Value = ();
+
+    fn reset(&mut self) {}
+
+    fn preset(&mut self, _value: Self::Value) {}
+}
+
+macro_rules! reset_tuple {
+    ($($type:ident => $field:tt),+) => {
+        impl<$($type),+> ResetState for ($($type,)+)
+        where
+            $($type: ResetState),+
+        {
+            type Value = ($($type::Value,)+);
+
+            fn reset(&mut self) {
+                $(self.$field.reset();)+
+            }
+
+            fn preset(&mut self, value: Self::Value) {
+                $(self.$field.preset(value.$field);)+
+            }
+        }
+    };
+}
+
+reset_tuple!(A => 0);
+reset_tuple!(A => 0, B => 1);
+reset_tuple!(A => 0, B => 1, C => 2);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12, N => 13);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12, N => 13, O => 14);
+reset_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12, N => 13, O => 14, P => 15);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, Transducer};
+
+    type E = ema::Filter<f32, f32, f32>;
+    type C = (E, E);
+
+    #[test]
+    fn reset_chain() {
+        let param = (ema::Param::from_alpha(0.5), ema::Param::from_alpha(0.5));
+        let mut state = (ema::State::new(0.0), ema::State::new(0.0));
+
+        assert_eq!(C::apply(&param, &mut state, 4.0), 1.0);
+        assert_eq!(C::apply(&param, &mut state, 4.0), 2.0);
+
+        state.reset();
+        assert_eq!(C::apply(&param, &mut state, 4.0), 1.0);
+
+        state.preset((4.0, 4.0));
+        assert_eq!(C::apply(&param, &mut state, 4.0), 4.0);
+    }
+}
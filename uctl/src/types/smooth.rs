@@ -0,0 +1,331 @@
+/*!
+
+## Parameter smoothing
+
+When the parameters of filters or regulators is changed at runtime (live retuning)
+the output may jump because the new parameters does not correspond to the actual state.
+
+The [`Smoothed`] wrapper interpolates the parameters from actual to target values
+over several steps, so the output changes smoothly.
+
+The parameters types should implement [`Interpolate`] trait,
+which is implemented for scalar values, tuples (the parameters of chains)
+and some parameters of filters.
+
+Because the parameters of transducers is immutable during applying,
+the actual interpolated parameters is stored in the state.
+The retuning is detected using version counter of [`SmoothedParam`].
+
+*/
+
+use crate::{snapshot_fields, Cast, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+use ufix::{Digits, Exponent, Fix, Radix};
+
+/**
+Linear interpolation between two values
+
+- `K` - interpolation factor type
+*/
+pub trait Interpolate<K> {
+    /**
+    Interpolate value
+
+    - `to`: The target value
+    - `factor`: The interpolation factor in range [0, 1]
+
+    _y = x + (to - x) * factor_
+     */
+    fn interpolate(&self, to: &Self, factor: K) -> Self;
+}
+
+macro_rules! interpolate_float {
+    ($($type:ty),*) => {
+        $(
+            impl Interpolate<$type> for $type {
+                fn interpolate(&self, to: &Self, factor: $type) -> Self {
+                    self + (to - self) * factor
+                }
+            }
+        )*
+    };
+}
+
+interpolate_float!(f32, f64);
+
+impl<R, B, E, K> Interpolate<K> for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Self: Copy + Sub<Self> + Add<Self> + Cast<Prod<K, Diff<Self, Self>>> + Cast<Sum<Self, Self>>,
+    K: Mul<Diff<Self, Self>>,
+{
+    fn interpolate(&self, to: &Self, factor: K) -> Self {
+        Self::cast(*self + Self::cast(factor * (*to - *self)))
+    }
+}
+
+impl<K> Interpolate<K> for () {
+    fn interpolate(&self, _to: &Self, _factor: K) -> Self {}
+}
+
+/// The functions cannot be interpolated, so the target is used
+impl<I, O, K> Interpolate<K> for fn(I) -> O {
+    fn interpolate(&self, to: &Self, _factor: K) -> Self {
+        *to
+    }
+}
+
+macro_rules! interpolate_tuple {
+    ($($type:ident => $field:tt),+) => {
+        impl<K, $($type),+> Interpolate<K> for ($($type,)+)
+        where
+            K: Copy,
+            $($type: Interpolate<K>),+
+        {
+            fn interpolate(&self, to: &Self, factor: K) -> Self {
+                ($(self.$field.interpolate(&to.$field, factor),)+)
+            }
+        }
+    };
+}
+
+interpolate_tuple!(A => 0);
+interpolate_tuple!(A => 0, B => 1);
+interpolate_tuple!(A => 0, B => 1, C => 2);
+interpolate_tuple!(A => 0, B => 1, C => 2, D => 3);
+interpolate_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+interpolate_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+interpolate_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+interpolate_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+/**
+Smoothed parameters
+
+- `P` - wrapped transducer parameters type
+- `K` - interpolation factor type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct SmoothedParam<P, K> {
+    /// Target parameters
+    target: P,
+    /// Interpolation factor change per step
+    step: K,
+    /// Version of target parameters
+    version: u32,
+}
+
+impl<P, K> SmoothedParam<P, K> {
+    /**
+    Init smoothed parameters
+
+    - `param`: The initial parameters
+    - `steps`: The number of steps of interpolation
+     */
+    pub fn new(param: P, steps: u32) -> Self
+    where
+        K: Cast<f64>,
+    {
+        Self {
+            target: param,
+            step: K::cast(1.0 / steps.max(1) as f64),
+            version: 0,
+        }
+    }
+
+    /// The target parameters
+    pub fn target(&self) -> &P {
+        &self.target
+    }
+
+    /// Set new target parameters
+    pub fn retune(&mut self, param: P) {
+        self.target = param;
+        self.version = self.version.wrapping_add(1);
+    }
+}
+
+/**
+Smoothed parameters state
+
+- `P` - wrapped transducer parameters type
+- `K` - interpolation factor type
+- `S` - wrapped transducer state type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct SmoothedState<P, K, S> {
+    /// Parameters at the start of interpolation
+    start: P,
+    /// Actual parameters
+    current: P,
+    /// Actual interpolation factor
+    factor: K,
+    /// Version of target parameters
+    version: u32,
+    /// Wrapped transducer state
+    state: S,
+}
+
+snapshot_fields! {
+    SmoothedState<P, K, S> {
+        start: P,
+        current: P,
+        factor: K,
+        version: u32,
+        state: S,
+    }
+}
+
+impl<P, K, S> SmoothedState<P, K, S> {
+    /**
+    Initialize smoothed parameters state
+
+    - `param`: The smoothed parameters
+    - `state`: The wrapped transducer state
+     */
+    pub fn new(param: &SmoothedParam<P, K>, state: S) -> Self
+    where
+        P: Clone,
+        K: Cast<f64>,
+    {
+        Self {
+            start: param.target.clone(),
+            current: param.target.clone(),
+            factor: K::cast(1.0),
+            version: param.version,
+            state,
+        }
+    }
+
+    /// The actual parameters
+    pub fn current(&self) -> &P {
+        &self.current
+    }
+
+    /// Wrapped transducer state
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Mutable wrapped transducer state
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+}
+
+/**
+Smoothed parameters wrapper
+
+- `T` - wrapped transducer
+- `K` - interpolation factor type
+*/
+#[derive(Debug)]
+pub struct Smoothed<T, K>(PhantomData<(T, K)>);
+
+impl<T, K> Transducer for Smoothed<T, K>
+where
+    T: Transducer,
+    T::Param: Clone + Interpolate<K>,
+    K: Copy + PartialOrd + Cast<f64> + Add<K> + Cast<Sum<K, K>>,
+{
+    type Input = T::Input;
+    type Output = T::Output;
+    type Param = SmoothedParam<T::Param, K>;
+    type State = SmoothedState<T::Param, K, T::State>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        if state.version != param.version {
+            // retuned: start interpolation from actual parameters
+            state.version = param.version;
+            state.start = state.current.clone();
+            state.factor = K::cast(0.0);
+        }
+
+        let one = K::cast(1.0);
+        if state.factor < one {
+            let factor = K::cast(state.factor + param.step);
+            if factor < one {
+                state.factor = factor;
+                state.current = state.start.interpolate(&param.target, factor);
+            } else {
+                state.factor = one;
+                state.current = param.target.clone();
+            }
+        }
+
+        T::apply(&state.current, &mut state.state, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FnTransducer;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin::Fix;
+
+    #[derive(Debug)]
+    struct Gain<T>(PhantomData<T>);
+
+    impl<T> Transducer for Gain<T>
+    where
+        T: Copy + Mul<T>,
+        T: Cast<Prod<T, T>>,
+    {
+        type Input = T;
+        type Output = T;
+        type Param = T;
+        type State = ();
+
+        fn apply(
+            param: &Self::Param,
+            _state: &mut Self::State,
+            value: Self::Input,
+        ) -> Self::Output {
+            T::cast(*param * value)
+        }
+    }
+
+    #[test]
+    fn smoothed_f32() {
+        let mut param = SmoothedParam::<f32, f32>::new(1.0, 4);
+        let mut state = SmoothedState::new(&param, ());
+        type G = Smoothed<Gain<f32>, f32>;
+
+        assert_eq!(G::apply(&param, &mut state, 2.0), 2.0);
+
+        param.retune(3.0);
+        assert_eq!(G::apply(&param, &mut state, 2.0), 3.0);
+        assert_eq!(G::apply(&param, &mut state, 2.0), 4.0);
+
+        // retune during interpolation
+        param.retune(2.0);
+        assert_eq!(G::apply(&param, &mut state, 2.0), 4.0);
+        assert_eq!(*state.current(), 2.0);
+        assert_eq!(G::apply(&param, &mut state, 2.0), 4.0);
+    }
+
+    #[test]
+    fn smoothed_chain_fix() {
+        type T = Fix<P32, N16>;
+        type K = Fix<P16, N8>;
+
+        fn neg(x: T) -> T {
+            T::cast(T::cast(0.0) - x)
+        }
+
+        let mut param =
+            SmoothedParam::<(T, fn(T) -> T), K>::new((T::cast(1.0), neg as fn(T) -> T), 2);
+        let mut state = SmoothedState::new(&param, ((), ()));
+        type G = Smoothed<(Gain<T>, FnTransducer<T, T>), K>;
+
+        param.retune((T::cast(5.0), neg));
+        assert_eq!(G::apply(&param, &mut state, T::cast(1.0)), T::cast(-3.0));
+        assert_eq!(G::apply(&param, &mut state, T::cast(1.0)), T::cast(-5.0));
+    }
+}
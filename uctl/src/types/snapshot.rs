@@ -0,0 +1,404 @@
+/*!
+
+## Snapshot and restore of states
+
+The control loop sometimes should be checkpointed and restored later,
+for example to continue after soft reset without transients
+or to migrate the loop to the redundant controller.
+
+The [`Snapshot`] trait provides the raw-bytes serialization of states.
+The size of snapshot is known at compile time, so the buffer can be allocated statically.
+The values is stored in little-endian byte order, so the snapshots is portable between targets.
+
+The trait is implemented for primitive and fixed-point values, options, arrays and tuples,
+delay lines and states of transducers, so the whole state tree of composed chain
+can be saved and restored in one call.
+
+The snapshot doesn't contain any type information or integrity check,
+so the snapshot should be restored into the state of the same chain.
+The checksum can be appended using [CRC](crate::Crc16) when the snapshot is stored
+in non-volatile memory or transferred.
+
+*/
+
+use generic_array::{ArrayLength, GenericArray};
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/**
+Raw-bytes snapshot of state
+
+The methods takes the buffer and returns the rest of it after snapshot,
+so the snapshots of composed states can be chained.
+*/
+pub trait Snapshot {
+    /// The size of snapshot in bytes
+    const SIZE: usize;
+
+    /**
+    Save state into buffer
+
+    - `buffer`: The buffer (at least `SIZE` bytes)
+     */
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8];
+
+    /**
+    Restore state from buffer
+
+    - `buffer`: The buffer (at least `SIZE` bytes)
+     */
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8];
+}
+
+macro_rules! snapshot_number {
+    ($($type:ty),*) => {
+        $(
+            impl Snapshot for $type {
+                const SIZE: usize = core::mem::size_of::<$type>();
+
+                fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+                    let (head, tail) = buffer.split_at_mut(Self::SIZE);
+                    head.copy_from_slice(&self.to_le_bytes());
+                    tail
+                }
+
+                fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+                    let (head, tail) = buffer.split_at(Self::SIZE);
+                    let mut bytes = [0u8; core::mem::size_of::<$type>()];
+                    bytes.copy_from_slice(head);
+                    *self = <$type>::from_le_bytes(bytes);
+                    tail
+                }
+            }
+        )*
+    };
+}
+
+snapshot_number!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+#[cfg(feature = "i128")]
+snapshot_number!(u128, i128);
+
+/// The value is stored as 64-bit to be portable
+impl Snapshot for usize {
+    const SIZE: usize = u64::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        (*self as u64).save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let mut value = 0u64;
+        let buffer = value.restore(buffer);
+        *self = value as usize;
+        buffer
+    }
+}
+
+impl Snapshot for bool {
+    const SIZE: usize = 1;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        (*self as u8).save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let mut value = 0u8;
+        let buffer = value.restore(buffer);
+        *self = value != 0;
+        buffer
+    }
+}
+
+/// The mantissa is stored
+impl<R, B, E> Snapshot for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Snapshot,
+{
+    const SIZE: usize = Mantissa::<R, B>::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        self.bits.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        self.bits.restore(buffer)
+    }
+}
+
+/// The flag of presence is stored before value (the absent value is zeroed)
+impl<T> Snapshot for Option<T>
+where
+    T: Snapshot + Default,
+{
+    const SIZE: usize = 1 + T::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.is_some().save(buffer);
+        match self {
+            Some(value) => value.save(buffer),
+            None => {
+                let (head, tail) = buffer.split_at_mut(T::SIZE);
+                head.iter_mut().for_each(|byte| *byte = 0);
+                tail
+            }
+        }
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let mut present = false;
+        let buffer = present.restore(buffer);
+        if present {
+            self.get_or_insert_with(T::default).restore(buffer)
+        } else {
+            *self = None;
+            &buffer[T::SIZE..]
+        }
+    }
+}
+
+impl<T, N> Snapshot for GenericArray<T, N>
+where
+    T: Snapshot,
+    N: ArrayLength<T>,
+{
+    const SIZE: usize = T::SIZE * N::USIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        self.iter().fold(buffer, |buffer, value| value.save(buffer))
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        self.iter_mut()
+            .fold(buffer, |buffer, value| value.restore(buffer))
+    }
+}
+
+impl<T, const N: usize> Snapshot for [T; N]
+where
+    T: Snapshot,
+{
+    const SIZE: usize = T::SIZE * N;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        self.iter().fold(buffer, |buffer, value| value.save(buffer))
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        self.iter_mut()
+            .fold(buffer, |buffer, value| value.restore(buffer))
+    }
+}
+
+impl Snapshot for () {
+    const SIZE: usize = 0;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        buffer
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        buffer
+    }
+}
+
+macro_rules! snapshot_tuple {
+    ($($type:ident => $field:tt),+) => {
+        impl<$($type),+> Snapshot for ($($type,)+)
+        where
+            $($type: Snapshot),+
+        {
+            const SIZE: usize = 0 $(+ $type::SIZE)+;
+
+            fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+                $(let buffer = self.$field.save(buffer);)+
+                buffer
+            }
+
+            fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+                $(let buffer = self.$field.restore(buffer);)+
+                buffer
+            }
+        }
+    };
+}
+
+snapshot_tuple!(A => 0);
+snapshot_tuple!(A => 0, B => 1);
+snapshot_tuple!(A => 0, B => 1, C => 2);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12, N => 13);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12, N => 13, O => 14);
+snapshot_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11, M => 12, N => 13, O => 14, P => 15);
+
+/**
+Implement snapshot of structure which stores the given fields in order
+
+The additional bounds of structure can be given in square brackets after `where`.
+*/
+macro_rules! snapshot_fields {
+    ($type:ident $(<$($param:ident),+>)? $(where [$($bound:tt)*])? {
+        $($field:ident: $ftype:ty),+ $(,)?
+    }) => {
+        impl$(<$($param),+>)? $crate::Snapshot for $type$(<$($param),+>)?
+        where
+            $($ftype: $crate::Snapshot,)+
+            $($($bound)*)?
+        {
+            const SIZE: usize = 0 $(+ <$ftype as $crate::Snapshot>::SIZE)+;
+
+            fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+                $(let buffer = self.$field.save(buffer);)+
+                buffer
+            }
+
+            fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+                $(let buffer = self.$field.restore(buffer);)+
+                buffer
+            }
+        }
+    };
+}
+
+/**
+Implement snapshot of enumeration with unit variants which stores the index of variant
+
+The unknown index is ignored on restore.
+*/
+macro_rules! snapshot_enum {
+    ($type:ident { $($variant:ident),+ $(,)? }) => {
+        impl $crate::Snapshot for $type {
+            const SIZE: usize = 1;
+
+            fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+                (*self as u8).save(buffer)
+            }
+
+            fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+                let mut index = 0u8;
+                let buffer = index.restore(buffer);
+                if let Some(value) = [$($type::$variant),+].get(index as usize) {
+                    *self = *value;
+                }
+                buffer
+            }
+        }
+    };
+}
+
+pub(crate) use {snapshot_enum, snapshot_fields};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        alarm, ema, pfdl::Store, pid, rms, slice::SliceDelayLine, Cast, DelayLine, Transducer, Warm,
+    };
+    use typenum::{N16, P32, U3, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn snapshot_values() {
+        type T = Fix<P32, N16>;
+        let mut buffer = [0u8; 32];
+
+        let value = (1u8, -2i32, Some(T::cast(1.5)), None::<f32>, true, 3usize);
+        assert_eq!(<(u8, i32, Option<T>, Option<f32>, bool, usize)>::SIZE, 24);
+
+        let rest = value.save(&mut buffer).len();
+        assert_eq!(rest, 8);
+
+        let mut restored = (0, 0, None, Some(1.0), false, 0);
+        let rest = restored.restore(&buffer).len();
+        assert_eq!(rest, 8);
+        assert_eq!(restored, value);
+
+        let array = GenericArray::<u16, U3>::from([1, 2, 0x0304]);
+        array.save(&mut buffer);
+        assert_eq!(&buffer[..6], &[1, 0, 2, 0, 4, 3]);
+    }
+
+    #[test]
+    fn snapshot_chain() {
+        type F = (Warm<ema::Filter<f32, f32, f32>>, ema::Filter<f32, f32, f32>);
+        type R = pid::Pid<f32, f32>;
+        type S = (<F as Transducer>::State, <R as Transducer>::State);
+
+        let filter = (ema::Param::from_alpha(0.5), ema::Param::from_alpha(0.25));
+        let regulator = pid::Param::<f32, f32>::new(1.0, 2.0, 0.1, 10.0, 0.1);
+
+        let step = |state: &mut S, value| {
+            let output = F::apply(&filter, &mut state.0, value);
+            R::apply(&regulator, &mut state.1, (1.0, output))
+        };
+
+        let mut state = S::default();
+        for value in [1.0, 0.5, 0.25, 0.125].iter() {
+            step(&mut state, *value);
+        }
+
+        let mut buffer = [0u8; S::SIZE];
+        state.save(&mut buffer);
+
+        // the restored chain continues exactly as original
+        let mut restored = S::default();
+        restored.restore(&buffer);
+        for value in [2.0, 1.0, 0.0].iter() {
+            assert_eq!(step(&mut restored, *value), step(&mut state, *value));
+        }
+    }
+
+    #[test]
+    fn snapshot_delay_line() {
+        type F = rms::Filter<f32, f32, f32, f32, Store<f32, U4>>;
+        type A = alarm::Alarm<f32>;
+        type S = (rms::State<f32, Store<f32, U4>>, alarm::State);
+
+        let filter = rms::Param::new();
+        let alarm = alarm::Param::new(0.0, 1.0, 0.0, 1.0).with_high(1.5);
+
+        let step = |state: &mut S, value| {
+            let output = F::apply(&filter, &mut state.0, value);
+            (output, A::apply(&alarm, &mut state.1, output))
+        };
+
+        let init = || (rms::State::new(Store::from(0.0)), alarm::State::default());
+
+        let mut state: S = init();
+        for value in [2.0, -2.0, 1.0].iter() {
+            step(&mut state, *value);
+        }
+
+        let mut buffer = [0u8; S::SIZE];
+        state.save(&mut buffer);
+
+        let mut restored = init();
+        restored.restore(&buffer);
+        for value in [2.0, -1.0, 0.0, 0.5, 3.0].iter() {
+            assert_eq!(step(&mut restored, *value), step(&mut state, *value));
+        }
+
+        // the borrowed line has the same layout as the owned one
+        let mut line = Store::<u8, U3>::from(0);
+        for value in 1..=4 {
+            line.push(value);
+        }
+        let mut buffer = [0u8; <Store<u8, U3>>::SIZE];
+        line.save(&mut buffer);
+
+        let mut data = [0u8; 3];
+        let mut slice = SliceDelayLine::<u8, U3>::new(&mut data, 0);
+        slice.restore(&buffer);
+        assert!(slice.iter().eq(line.iter()));
+    }
+}
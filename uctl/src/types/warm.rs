@@ -0,0 +1,172 @@
+/*!
+
+## Warm start
+
+The filters which has memory produces the transient after boot,
+because the state is initialized by zeros (or another default value)
+instead of the actual value of signal.
+
+The [`WarmStart`] trait provides the hook which initializes the state
+of filter from the first input value (like the signal was constant forever before).
+For example the EMA filter sets the last value to the input and the FIR filter pre-fills the delay line.
+
+The [`Warm`] wrapper invokes the hook automatically on the first apply.
+
+*/
+
+use crate::{ResetState, Snapshot, Transducer};
+use core::marker::PhantomData;
+
+/// Initialization of state from the first input value
+pub trait WarmStart {
+    /// The input value type
+    type Input;
+    /// The state type
+    type State;
+
+    /// Initialize state to the steady state for given input value
+    fn warm_start(state: &mut Self::State, value: &Self::Input);
+}
+
+/**
+Warm start wrapper state
+
+- `S` - wrapped transducer state type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarmState<S> {
+    /// Wrapped transducer state
+    state: S,
+    /// The state is initialized
+    started: bool,
+}
+
+impl<S> WarmState<S> {
+    /**
+    Initialize warm start wrapper state
+
+    - `state`: The state of wrapped transducer
+     */
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            started: false,
+        }
+    }
+
+    /// Wrapped transducer state
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Mutable wrapped transducer state
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Check when the state is initialized
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// Initialize state again on the next apply
+    pub fn restart(&mut self) {
+        self.started = false;
+    }
+}
+
+impl<S> ResetState for WarmState<S>
+where
+    S: ResetState,
+{
+    type Value = S::Value;
+
+    fn reset(&mut self) {
+        self.state.reset();
+        self.started = false;
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.state.preset(value);
+        self.started = true;
+    }
+}
+
+impl<S> Snapshot for WarmState<S>
+where
+    S: Snapshot,
+{
+    const SIZE: usize = S::SIZE + bool::SIZE;
+
+    fn save<'a>(&self, buffer: &'a mut [u8]) -> &'a mut [u8] {
+        let buffer = self.state.save(buffer);
+        self.started.save(buffer)
+    }
+
+    fn restore<'a>(&mut self, buffer: &'a [u8]) -> &'a [u8] {
+        let buffer = self.state.restore(buffer);
+        self.started.restore(buffer)
+    }
+}
+
+/**
+Warm start wrapper
+
+- `T` - wrapped transducer
+
+The state of wrapped transducer will be initialized from the first input value.
+*/
+#[derive(Debug)]
+pub struct Warm<T>(PhantomData<T>);
+
+impl<T> Transducer for Warm<T>
+where
+    T: Transducer + WarmStart<Input = <T as Transducer>::Input, State = <T as Transducer>::State>,
+{
+    type Input = <T as Transducer>::Input;
+    type Output = T::Output;
+    type Param = T::Param;
+    type State = WarmState<<T as Transducer>::State>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        if !state.started {
+            T::warm_start(&mut state.state, &value);
+            state.started = true;
+        }
+        T::apply(param, &mut state.state, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, fir, pfdl::Store as DL};
+    use typenum::U2;
+
+    #[test]
+    fn warm_ema() {
+        let param = ema::Param::from_alpha(0.5);
+        let mut state = WarmState::new(ema::State::new(0.0));
+        type F = Warm<ema::Filter<f32, f32, f32>>;
+
+        assert_eq!(F::apply(&param, &mut state, 4.0), 4.0);
+        assert_eq!(F::apply(&param, &mut state, 2.0), 3.0);
+        assert!(state.is_started());
+
+        state.restart();
+        assert_eq!(F::apply(&param, &mut state, 8.0), 8.0);
+
+        state.reset();
+        assert_eq!(F::apply(&param, &mut state, 2.0), 2.0);
+    }
+
+    #[test]
+    fn warm_fir() {
+        let param = fir::Param::<f32, U2>::from([0.25, 0.5, 0.25]);
+        let mut state = WarmState::new(DL::<f32, U2>::from(0.0));
+        type F = Warm<fir::Filter<f32, f32, DL<f32, U2>>>;
+
+        assert_eq!(F::apply(&param, &mut state, 4.0), 4.0);
+        assert_eq!(F::apply(&param, &mut state, 0.0), 3.0);
+    }
+}
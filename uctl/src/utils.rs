@@ -1,2 +1,19 @@
+pub mod arbiter;
+pub mod calibration;
 pub mod clamper;
+pub mod crossfade;
+pub mod events;
+pub mod lut;
+pub mod mode;
+pub mod normalize;
+pub mod poly;
+pub mod profile;
 pub mod scaler;
+pub mod scheduler;
+pub mod sequencer;
+pub mod stream;
+
+#[cfg(feature = "std")]
+pub mod recorder;
+#[cfg(feature = "std")]
+pub mod trace;
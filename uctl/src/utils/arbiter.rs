@@ -0,0 +1,305 @@
+/*!
+
+## Command arbiter
+
+This module implements the supervisory block which selects the actuator command
+among several sources with fixed priorities:
+
+- _Safety_ - the safety override (highest priority)
+- _Manual_ - the manual (operator) command
+- _Automatic_ - the command of regulator (lowest priority)
+
+The safety and manual sources is optional and override the automatic command when present.
+
+When the selected source changes the output moves to the new command with limited rate
+(slew-limited), so the actuator doesn't get abrupt bumps. After the output reaches
+the command of new source it follows the source directly.
+
+The transitions to sources with high enough priority can be configured as immediate,
+which is usually required for safety overrides.
+
+*/
+
+use crate::{snapshot_enum, snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+use typenum::{Diff, Sum};
+
+/// Command source in order of priority (from lowest to highest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Source {
+    /// Automatic command
+    #[default]
+    Automatic,
+    /// Manual command
+    Manual,
+    /// Safety override
+    Safety,
+}
+
+snapshot_enum!(Source {
+    Automatic,
+    Manual,
+    Safety
+});
+
+/**
+Command arbiter parameters
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<T> {
+    /// The maximum change of output per step
+    step: T,
+    /// The lowest priority source to switch immediately
+    immediate: Option<Source>,
+}
+
+impl<T> Param<T> {
+    /**
+    Init command arbiter parameters using the maximum change per step
+
+    - `step`: The maximum change of output per step
+     */
+    pub fn new(step: T) -> Self {
+        Self {
+            step,
+            immediate: None,
+        }
+    }
+
+    /**
+    Init command arbiter parameters using the maximum rate
+
+    - `rate`: The maximum rate of change of output (units per second)
+    - `period`: The sampling period (s)
+
+    _step = rate * period_
+     */
+    pub fn from_rate<V>(rate: V, period: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        Self::new(T::cast(f64::cast(rate) * f64::cast(period)))
+    }
+
+    /**
+    Switch immediately to sources with priority not lower than given
+
+    - `source`: The lowest priority source which doesn't require smooth transition
+     */
+    pub fn with_immediate(mut self, source: Source) -> Self {
+        self.immediate = Some(source);
+        self
+    }
+}
+
+/**
+Command arbiter state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The selected source
+    source: Source,
+    /// The last output
+    output: T,
+    /// The transition is in progress
+    transition: bool,
+}
+
+impl<T> State<T> {
+    /// The selected source
+    pub fn source(&self) -> Source {
+        self.source
+    }
+
+    /// The transition between sources is in progress
+    pub fn in_transition(&self) -> bool {
+        self.transition
+    }
+}
+
+/// The preset value is a selected source and an output
+impl<T> ResetState for State<T>
+where
+    T: Default,
+{
+    type Value = (Source, T);
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, (source, output): Self::Value) {
+        self.source = source;
+        self.output = output;
+        self.transition = false;
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        source: Source,
+        output: T,
+        transition: bool,
+    }
+}
+
+/**
+Command arbiter
+
+- `T` - values type
+
+The input is a tuple of optional safety command, optional manual command and automatic command.
+The output is a tuple of selected source and command.
+*/
+#[derive(Debug)]
+pub struct Arbiter<T>(PhantomData<T>);
+
+impl<T> Transducer for Arbiter<T>
+where
+    T: Copy + PartialOrd + Add<T> + Sub<T> + Cast<Sum<T, T>> + Cast<Diff<T, T>>,
+{
+    type Input = (Option<T>, Option<T>, T);
+    type Output = (Source, T);
+    type Param = Param<T>;
+    type State = State<T>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (safety, manual, automatic): Self::Input,
+    ) -> Self::Output {
+        let (source, target) = if let Some(value) = safety {
+            (Source::Safety, value)
+        } else if let Some(value) = manual {
+            (Source::Manual, value)
+        } else {
+            (Source::Automatic, automatic)
+        };
+
+        if source != state.source {
+            state.source = source;
+            state.transition = match param.immediate {
+                Some(immediate) => source < immediate,
+                None => true,
+            };
+        }
+
+        state.output = if state.transition {
+            let output = state.output;
+            if target > output {
+                let next = T::cast(output + param.step);
+                if next < target {
+                    next
+                } else {
+                    state.transition = false;
+                    target
+                }
+            } else {
+                let next = T::cast(output - param.step);
+                if next > target {
+                    next
+                } else {
+                    state.transition = false;
+                    target
+                }
+            }
+        } else {
+            target
+        };
+
+        (source, state.output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn arbiter_priority() {
+        type A = Arbiter<f32>;
+        let param = Param::<f32>::new(0.25);
+        let mut state = State::default();
+
+        assert_eq!(
+            A::apply(&param, &mut state, (None, None, 0.0)),
+            (Source::Automatic, 0.0)
+        );
+        assert_eq!(
+            A::apply(&param, &mut state, (None, Some(1.0), 0.0)),
+            (Source::Manual, 0.25)
+        );
+        assert_eq!(
+            A::apply(&param, &mut state, (None, Some(1.0), 0.0)),
+            (Source::Manual, 0.5)
+        );
+        // the safety overrides manual
+        assert_eq!(
+            A::apply(&param, &mut state, (Some(0.0), Some(1.0), 0.0)),
+            (Source::Safety, 0.25)
+        );
+        assert_eq!(
+            A::apply(&param, &mut state, (Some(0.0), Some(1.0), 0.0)),
+            (Source::Safety, 0.0)
+        );
+        assert!(!state.in_transition());
+
+        // the source is followed directly after transition
+        assert_eq!(
+            A::apply(&param, &mut state, (Some(0.75), Some(1.0), 0.0)),
+            (Source::Safety, 0.75)
+        );
+        assert_eq!(
+            A::apply(&param, &mut state, (None, None, 0.625)),
+            (Source::Automatic, 0.625)
+        );
+        assert_eq!(state.source(), Source::Automatic);
+    }
+
+    #[test]
+    fn arbiter_immediate() {
+        type A = Arbiter<f32>;
+        let param = Param::<f32>::from_rate(1.0, 0.25).with_immediate(Source::Safety);
+        let mut state = State::default();
+
+        state.preset((Source::Automatic, 1.0));
+        assert_eq!(
+            A::apply(&param, &mut state, (Some(0.0), None, 1.0)),
+            (Source::Safety, 0.0)
+        );
+        assert_eq!(
+            A::apply(&param, &mut state, (None, None, 1.0)),
+            (Source::Automatic, 0.25)
+        );
+        assert!(state.in_transition());
+        assert_eq!(
+            A::apply(&param, &mut state, (None, Some(0.0), 1.0)),
+            (Source::Manual, 0.0)
+        );
+    }
+
+    #[test]
+    fn arbiter_fix() {
+        type T = Fix<P32, N16>;
+        type A = Arbiter<T>;
+        let param = Param::<T>::from_rate(10.0, 0.01);
+        let mut state = State::default();
+
+        let mut output = (Source::Automatic, T::cast(0.0));
+        // 0.1 per step (truncated)
+        for _ in 0..6 {
+            output = A::apply(&param, &mut state, (None, Some(T::cast(0.5)), T::cast(0.0)));
+        }
+        assert_eq!(output, (Source::Manual, T::cast(0.5)));
+    }
+}
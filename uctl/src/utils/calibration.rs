@@ -0,0 +1,380 @@
+/*!
+
+## Gain and offset calibration
+
+This module implements the calibration of linear sensors in field,
+which gives the parameters of [`Scaler`](crate::scaler::Scaler) using the pairs of
+raw values and the values of reference instrument.
+
+The two-point calibration uses two pairs (like zero and span), see [`two_point`].
+
+The N-point calibration fits the line using least squares method:
+
+_factor = Σ (x - x̄) (y - ȳ) / Σ (x - x̄)²_
+
+_offset = ȳ - factor x̄_
+
+The [`Calibration`] accumulates the pairs one-by-one, so the pairs doesn't need to be stored.
+The values is accumulated as deviations from running means in double precision floating-point
+(the Welford's method), so the accumulation doesn't overflow and doesn't lose the precision
+even when the values is fixed-point with large raw values.
+
+### Temperature drift compensation
+
+The gain and offset of sensor front ends usually drifts with temperature.
+The [`DriftParam`] holds the calibration sets fitted at several temperatures
+and the [`Compensated`] scaler interpolates the gain and offset linearly by measured temperature:
+
+_factor = factor<sub>i</sub> + (factor<sub>i+1</sub> - factor<sub>i</sub>) (t - t<sub>i</sub>) / (t<sub>i+1</sub> - t<sub>i</sub>)_
+
+The temperatures out of range of sets is clamped to the first and the last sets.
+The segment of temperatures is found by [lookup table](crate::lut) which maps the temperatures
+to the indexes of sets, so its slopes is the reciprocal spans of temperatures.
+The interpolation uses [`Interpolate`] trait of scaler parameters.
+
+*/
+
+use crate::{lut, scaler, Cast, Interpolate, Sqrt, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod};
+
+/// The line fitted by calibration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    /// The scale factor
+    pub factor: f64,
+    /// The offset
+    pub offset: f64,
+    /// The RMS of residuals of fit
+    pub residual: f64,
+}
+
+impl Fit {
+    /**
+    Convert fit to the scaler parameters
+
+    - `F` - scale factor type
+    - `O` - output value type
+     */
+    pub fn param<F, O>(&self) -> scaler::Param<F, O>
+    where
+        F: Cast<f64>,
+        O: Cast<f64>,
+    {
+        scaler::Param::from_factor(F::cast(self.factor), O::cast(self.offset))
+    }
+}
+
+/**
+Two-point calibration
+
+- `low`: The first pair of raw and reference values
+- `high`: The second pair of raw and reference values
+
+Returns `None` when the raw values is equal.
+ */
+pub fn two_point<I, O>((x0, y0): (I, O), (x1, y1): (I, O)) -> Option<Fit>
+where
+    f64: Cast<I> + Cast<O>,
+{
+    let (x0, x1) = (f64::cast(x0), f64::cast(x1));
+    let (y0, y1) = (f64::cast(y0), f64::cast(y1));
+
+    if x0 == x1 {
+        return None;
+    }
+
+    let factor = (y1 - y0) / (x1 - x0);
+
+    Some(Fit {
+        factor,
+        offset: y0 - factor * x0,
+        residual: 0.0,
+    })
+}
+
+/**
+N-point least squares calibration
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Calibration {
+    /// The number of pairs
+    count: u32,
+    /// The mean of raw values
+    mean_x: f64,
+    /// The mean of reference values
+    mean_y: f64,
+    /// The sum of squared deviations of raw values
+    sxx: f64,
+    /// The sum of squared deviations of reference values
+    syy: f64,
+    /// The sum of products of deviations
+    sxy: f64,
+}
+
+impl Calibration {
+    /// The number of pairs
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /**
+    Add the pair of values
+
+    - `raw`: The raw value of sensor
+    - `reference`: The value of reference instrument
+     */
+    pub fn add<I, O>(&mut self, raw: I, reference: O)
+    where
+        f64: Cast<I> + Cast<O>,
+    {
+        let x = f64::cast(raw);
+        let y = f64::cast(reference);
+
+        self.count += 1;
+        let count = self.count as f64;
+
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / count;
+        self.mean_y += dy / count;
+
+        // the deviations from the old and the new means
+        self.sxx += dx * (x - self.mean_x);
+        self.syy += dy * (y - self.mean_y);
+        self.sxy += dx * (y - self.mean_y);
+    }
+
+    /**
+    Fit the line
+
+    Returns `None` when there is less than two different raw values.
+     */
+    pub fn fit(&self) -> Option<Fit> {
+        if self.count < 2 || self.sxx <= 0.0 {
+            return None;
+        }
+
+        let factor = self.sxy / self.sxx;
+        let offset = self.mean_y - factor * self.mean_x;
+        // the sum of squared residuals Syy - Sxy² / Sxx
+        let residual = (self.syy - factor * self.sxy).max(0.0) / self.count as f64;
+
+        Some(Fit {
+            factor,
+            offset,
+            residual: Sqrt::sqrt(residual),
+        })
+    }
+}
+
+/**
+Temperature drift compensation parameters
+
+- `F` - scale factor type
+- `O` - output value type
+- `T` - temperature type
+- `K` - interpolation factor type
+- `N` - the number of calibration sets (at least 2)
+*/
+#[derive(Debug, Clone)]
+pub struct DriftParam<F, O, T, K, N>
+where
+    N: ArrayLength<T> + ArrayLength<usize> + ArrayLength<K> + ArrayLength<scaler::Param<F, O>>,
+{
+    /// The table of temperatures and indexes of sets
+    table: lut::Param<T, usize, K, N>,
+    /// The calibration sets
+    sets: GenericArray<scaler::Param<F, O>, N>,
+}
+
+impl<F, O, T, K, N> DriftParam<F, O, T, K, N>
+where
+    N: ArrayLength<T> + ArrayLength<usize> + ArrayLength<K> + ArrayLength<scaler::Param<F, O>>,
+{
+    /**
+    Init temperature drift compensation parameters
+
+    - `sets`: The temperatures and calibration fits sorted by temperature (the length should be `N`)
+     */
+    pub fn new<V>(sets: &[(V, Fit)]) -> Self
+    where
+        V: Copy,
+        F: Cast<f64>,
+        O: Cast<f64>,
+        T: Cast<f64>,
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(sets.len() == N::USIZE && N::USIZE >= 2);
+
+        Self {
+            table: lut::Param::from_fn(|i| (f64::cast(sets[i].0), i as f64)),
+            sets: sets.iter().map(|(_, fit)| fit.param()).collect(),
+        }
+    }
+
+    /**
+    Get scaler parameters at given temperature
+
+    - `temperature`: The measured temperature
+     */
+    pub fn param_at(&self, temperature: T) -> scaler::Param<F, O>
+    where
+        T: Copy + PartialOrd + Sub<T>,
+        K: Copy + Mul<Diff<T, T>> + Cast<Prod<K, Diff<T, T>>>,
+        scaler::Param<F, O>: Copy + Interpolate<K>,
+    {
+        match self.table.locate(temperature) {
+            (i, Some(factor)) => self.sets[i].interpolate(&self.sets[i + 1], K::cast(factor)),
+            (i, None) => self.sets[i],
+        }
+    }
+}
+
+/**
+Temperature compensated scaler
+
+- `I` - input value type
+- `O` - output value type
+- `F` - scale factor type
+- `T` - temperature type
+- `K` - interpolation factor type
+- `N` - the number of calibration sets
+
+The input is a tuple of raw value and temperature.
+*/
+#[derive(Debug)]
+pub struct Compensated<I, O, F, T, K, N>(PhantomData<(I, O, F, T, K, N)>);
+
+impl<I, O, F, T, K, N> Transducer for Compensated<I, O, F, T, K, N>
+where
+    scaler::Scaler<I, O, F>:
+        Transducer<Input = I, Output = O, Param = scaler::Param<F, O>, State = ()>,
+    T: Copy + PartialOrd + Sub<T>,
+    K: Copy + Mul<Diff<T, T>> + Cast<Prod<K, Diff<T, T>>>,
+    scaler::Param<F, O>: Copy + Interpolate<K>,
+    N: ArrayLength<T> + ArrayLength<usize> + ArrayLength<K> + ArrayLength<scaler::Param<F, O>>,
+{
+    type Input = (I, T);
+    type Output = O;
+    type Param = DriftParam<F, O, T, K, N>;
+    type State = ();
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (raw, temperature): Self::Input,
+    ) -> Self::Output {
+        scaler::Scaler::<I, O, F>::apply(&param.param_at(temperature), state, raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{scaler::Scaler, Transducer};
+    use typenum::{N12, N14, N16, N8, P16, P32, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn two_point_calibration() {
+        // 4..20 mA => 0..100 %
+        let fit = two_point((4.0, 0.0), (20.0, 100.0)).unwrap();
+        assert_eq!(fit.factor, 6.25);
+        assert_eq!(fit.offset, -25.0);
+
+        let param = fit.param::<f32, f32>();
+        assert_eq!(Scaler::apply(&param, &mut (), 12.0f32), 50.0);
+
+        assert_eq!(two_point((1.0, 0.0), (1.0, 1.0)), None);
+    }
+
+    #[test]
+    fn least_squares_calibration() {
+        let mut cal = Calibration::default();
+        assert_eq!(cal.fit(), None);
+
+        // y = 0.5 x + 3 with residuals ±0.1
+        for (i, x) in [10.0, 20.0, 30.0, 40.0].iter().enumerate() {
+            let noise = if i % 2 == 0 { 0.1 } else { -0.1 };
+            cal.add(*x, 0.5 * x + 3.0 + noise);
+        }
+        assert_eq!(cal.count(), 4);
+
+        let fit = cal.fit().unwrap();
+        assert!((fit.factor - 0.496).abs() < 1e-12, "{:?}", fit);
+        assert!((fit.offset - 3.1).abs() < 1e-12, "{:?}", fit);
+        assert!(fit.residual > 0.0 && fit.residual < 0.1, "{:?}", fit);
+    }
+
+    #[test]
+    fn least_squares_fix() {
+        type T = Fix<P32, N16>;
+
+        // the large raw values of 24-bit ADC
+        let mut cal = Calibration::default();
+        for k in 0..100 {
+            let raw = 8_000_000 + k * 1000;
+            cal.add(raw, T::cast(raw as f64 * 1e-5 - 80.0));
+        }
+
+        let fit = cal.fit().unwrap();
+        assert!((fit.factor - 1e-5).abs() < 1e-10, "{:?}", fit);
+        assert!((fit.offset + 80.0).abs() < 1e-4);
+        assert!(fit.residual < 1e-4);
+
+        let param = fit.param::<f64, f64>();
+        let y = Scaler::apply(&param, &mut (), 8_050_000.0);
+        assert!((y - 0.5).abs() < 1e-4);
+
+        // the single raw value
+        let mut cal = Calibration::default();
+        cal.add(1, 2);
+        cal.add(1, 3);
+        assert_eq!(cal.fit(), None);
+    }
+
+    #[test]
+    fn drift_compensation() {
+        type C = Compensated<f32, f32, f32, f32, f32, U3>;
+
+        let sets = [
+            (0.0, two_point((0.0, 0.0), (1.0, 100.0)).unwrap()),
+            (50.0, two_point((0.0, 1.0), (1.0, 99.0)).unwrap()),
+            (100.0, two_point((0.0, 2.0), (1.0, 98.0)).unwrap()),
+        ];
+        let param = DriftParam::<f32, f32, f32, f32, U3>::new(&sets);
+
+        assert_eq!(C::apply(&param, &mut (), (0.5, 0.0)), 50.0);
+        assert_eq!(C::apply(&param, &mut (), (1.0, 25.0)), 99.5);
+        assert_eq!(C::apply(&param, &mut (), (0.0, 75.0)), 1.5);
+        // out of range
+        assert_eq!(C::apply(&param, &mut (), (0.0, -20.0)), 0.0);
+        assert_eq!(C::apply(&param, &mut (), (0.0, 120.0)), 2.0);
+    }
+
+    #[test]
+    fn drift_compensation_fix() {
+        type T = Fix<P16, N8>;
+        type F = Fix<P16, N12>;
+        type K = Fix<P16, N14>;
+        type C = Compensated<T, T, F, T, K, U3>;
+
+        let sets = [
+            (-20.0, two_point((0.0, -1.0), (10.0, 9.0)).unwrap()),
+            (20.0, two_point((0.0, 0.0), (10.0, 10.0)).unwrap()),
+            (60.0, two_point((0.0, 0.0), (10.0, 12.0)).unwrap()),
+        ];
+        let param = DriftParam::<F, T, T, K, U3>::new(&sets);
+
+        let y = C::apply(&param, &mut (), (T::cast(5.0), T::cast(0.0)));
+        assert!((f64::cast(y) - 4.5).abs() < 1e-2, "{}", f64::cast(y));
+        let y = C::apply(&param, &mut (), (T::cast(5.0), T::cast(40.0)));
+        assert!((f64::cast(y) - 5.5).abs() < 1e-2, "{}", f64::cast(y));
+    }
+}
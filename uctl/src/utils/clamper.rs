@@ -2,14 +2,162 @@
 
 Simple clamper for scalar values
 
+The clamper accepts any range type as parameter.
+The [`ClampParam`] is a range which bounds can be adjusted at runtime,
+so the saturation limits can follow the operating conditions.
+
+The [`Saturation`] wraps the clamper and counts the steps when the value is clamped
+to the lower or the upper limit during the window of observation.
+The prolonged saturation indicates undersized actuators or bad tuning,
+so the counters can be reported as duty diagnostics.
+
 */
 
-use crate::Transducer;
+use crate::{
+    snapshot_fields, Bounds, Cast, Core, CycleCost, NoiseBudget, OpCost, RangeOverflow, ResetState,
+    Transducer, ValueBounds, Wire, WorstCase,
+};
 use core::{
     marker::PhantomData,
-    ops::{Bound::*, RangeBounds},
+    ops::{
+        Bound, Bound::*, Neg, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeToInclusive,
+    },
 };
 
+/**
+Runtime-adjustable clamper range
+
+- `T` - bounds type
+
+The missing bound means unbounded range from corresponding side.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClampParam<T> {
+    /// The lower bound (inclusive)
+    pub min: Option<T>,
+    /// The upper bound (inclusive)
+    pub max: Option<T>,
+}
+
+impl<T> Default for ClampParam<T> {
+    fn default() -> Self {
+        Self::unbounded()
+    }
+}
+
+impl<T> ClampParam<T> {
+    /// Create range with both bounds
+    pub fn new(min: T, max: T) -> Self {
+        Self {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    /// Create unbounded range
+    pub fn unbounded() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Create range bounded from below
+    pub fn from_min(min: T) -> Self {
+        Self {
+            min: Some(min),
+            max: None,
+        }
+    }
+
+    /// Create range bounded from above
+    pub fn from_max(max: T) -> Self {
+        Self {
+            min: None,
+            max: Some(max),
+        }
+    }
+
+    /// Set both bounds
+    pub fn set(&mut self, min: T, max: T) {
+        self.min = Some(min);
+        self.max = Some(max);
+    }
+
+    /// Set symmetric bounds `-limit..=limit`
+    pub fn set_symmetric(&mut self, limit: T)
+    where
+        T: Copy + Neg<Output = T>,
+    {
+        self.set(-limit, limit);
+    }
+
+    /// Check when the value is within range
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialOrd,
+    {
+        RangeBounds::contains(self, value)
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for ClampParam<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let (min, max) = range.into_inner();
+        Self::new(min, max)
+    }
+}
+
+impl<T> From<RangeFrom<T>> for ClampParam<T> {
+    fn from(range: RangeFrom<T>) -> Self {
+        Self::from_min(range.start)
+    }
+}
+
+impl<T> From<RangeToInclusive<T>> for ClampParam<T> {
+    fn from(range: RangeToInclusive<T>) -> Self {
+        Self::from_max(range.end)
+    }
+}
+
+impl<T> From<RangeFull> for ClampParam<T> {
+    fn from(_range: RangeFull) -> Self {
+        Self::unbounded()
+    }
+}
+
+impl<T> RangeBounds<T> for ClampParam<T> {
+    fn start_bound(&self) -> Bound<&T> {
+        match &self.min {
+            Some(min) => Included(min),
+            None => Unbounded,
+        }
+    }
+
+    fn end_bound(&self) -> Bound<&T> {
+        match &self.max {
+            Some(max) => Included(max),
+            None => Unbounded,
+        }
+    }
+}
+
+impl<T> Wire for ClampParam<T>
+where
+    T: Copy + Wire,
+{
+    const SIZE: usize = 2 * Option::<T>::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        (self.min, self.max).write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let (min, max) = Wire::read_from(buf);
+        Self { min, max }
+    }
+}
+
 /**
 The clamper
 
@@ -69,6 +217,248 @@ where
     }
 }
 
+/// The comparison costs as subtraction (the unbounded sides is counted too)
+impl<R, T> CycleCost for Clamper<R, T>
+where
+    T: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        2 * T::add(core)
+    }
+}
+
+/**
+Worst-case output range of clamper
+
+- `input`: The range of input values
+- `lower`: The lower bound (`f64::NEG_INFINITY` when unbounded)
+- `upper`: The upper bound (`f64::INFINITY` when unbounded)
+*/
+pub const fn bounds(input: Bounds, lower: f64, upper: f64) -> Bounds {
+    input.clamp(lower, upper)
+}
+
+impl<R, T> WorstCase for Clamper<R, T>
+where
+    R: RangeBounds<T>,
+    T: Copy + PartialOrd + ValueBounds,
+    f64: Cast<T>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        let lower = match param.start_bound() {
+            Included(from) | Excluded(from) => f64::cast(*from),
+            Unbounded => f64::NEG_INFINITY,
+        };
+        let upper = match param.end_bound() {
+            Included(to) | Excluded(to) => f64::cast(*to),
+            Unbounded => f64::INFINITY,
+        };
+        bounds(input, lower, upper).check::<T>()
+    }
+}
+
+/// The noise passes as is (the clamping of noise isn't taken into account)
+impl<R, T> NoiseBudget for Clamper<R, T>
+where
+    R: RangeBounds<T>,
+    T: Copy + PartialOrd,
+{
+    fn output_noise(_param: &Self::Param, input: f64) -> f64 {
+        input
+    }
+}
+
+/**
+Saturation counter parameters
+
+- `R` - value range type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct SaturationParam<R> {
+    /// The clamper range
+    pub range: R,
+    /// The window of observation in steps
+    window: u32,
+}
+
+impl<R> SaturationParam<R> {
+    /**
+    Init saturation counter parameters
+
+    - `range`: The clamper range
+    - `window`: The number of steps in window (1..)
+     */
+    pub fn new(range: R, window: u32) -> Self {
+        assert!(window > 0);
+        Self { range, window }
+    }
+
+    /// The number of steps in window
+    pub fn window(&self) -> u32 {
+        self.window
+    }
+}
+
+/// The window is stored after the range (the zero window is read as one step)
+impl<R> Wire for SaturationParam<R>
+where
+    R: Wire,
+{
+    const SIZE: usize = R::SIZE + u32::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        self.range.write_to(buf);
+        self.window.write_to(&mut buf[R::SIZE..]);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let window = u32::read_from(&buf[R::SIZE..]).max(1);
+        Self::new(R::read_from(buf), window)
+    }
+}
+
+/// The saturation counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Duty {
+    /// The number of steps when the value is clamped to the lower limit
+    pub low: u32,
+    /// The number of steps when the value is clamped to the upper limit
+    pub high: u32,
+    /// The number of steps
+    pub total: u32,
+}
+
+snapshot_fields! {
+    Duty {
+        low: u32,
+        high: u32,
+        total: u32,
+    }
+}
+
+impl Duty {
+    /// The share of steps when the value is clamped (0..1)
+    pub fn ratio(&self) -> f64 {
+        if self.total > 0 {
+            (self.low + self.high) as f64 / self.total as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/**
+Saturation counter state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SaturationState {
+    /// The counters of current window
+    current: Duty,
+    /// The counters of last complete window
+    last: Duty,
+}
+
+impl SaturationState {
+    /// The counters of current window
+    pub fn current(&self) -> Duty {
+        self.current
+    }
+
+    /// The counters of last complete window
+    pub fn last(&self) -> Duty {
+        self.last
+    }
+}
+
+impl ResetState for SaturationState {
+    type Value = ();
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    SaturationState {
+        current: Duty,
+        last: Duty,
+    }
+}
+
+/**
+The clamper with saturation counter
+
+- `R` - value range type
+- `T` - clamping value type
+*/
+pub struct Saturation<R, T> {
+    val: PhantomData<(R, T)>,
+}
+
+impl<R, T> Transducer for Saturation<R, T>
+where
+    R: RangeBounds<T>,
+    T: Copy + PartialOrd,
+{
+    type Input = T;
+    type Output = T;
+    type Param = SaturationParam<R>;
+    type State = SaturationState;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let clamped = Clamper::<R, T>::apply(&param.range, &mut (), value);
+
+        if value < clamped {
+            state.current.low += 1;
+        } else if value > clamped {
+            state.current.high += 1;
+        }
+
+        state.current.total += 1;
+        if state.current.total >= param.window {
+            state.last = state.current;
+            state.current = Duty::default();
+        }
+
+        clamped
+    }
+}
+
+impl<R, T> CycleCost for Saturation<R, T>
+where
+    T: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        // clamping, 2 comparisons with clamped value and 2 counters
+        4 * T::add(core) + 2 * u32::add(core)
+    }
+}
+
+impl<R, T> WorstCase for Saturation<R, T>
+where
+    R: RangeBounds<T>,
+    T: Copy + PartialOrd + ValueBounds,
+    f64: Cast<T>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        Clamper::<R, T>::output_bounds(&param.range, input)
+    }
+}
+
+impl<R, T> NoiseBudget for Saturation<R, T>
+where
+    R: RangeBounds<T>,
+    T: Copy + PartialOrd,
+{
+    fn output_noise(_param: &Self::Param, input: f64) -> f64 {
+        input
+    }
+}
+
 /*
 impl<T> Transducer for Clamper<RangeFrom<T>, T>
 where
@@ -178,4 +568,56 @@ mod test {
         assert_eq!(Clamper::apply(&range, &mut (), 3.0), 3.0);
         assert_eq!(Clamper::apply(&range, &mut (), 3.1), 3.0);
     }
+
+    #[test]
+    fn clamp_param_float() {
+        let mut range = ClampParam::new(2.0, 3.0);
+
+        assert_eq!(Clamper::apply(&range, &mut (), 1.9), 2.0);
+        assert_eq!(Clamper::apply(&range, &mut (), 3.1), 3.0);
+
+        range.max = Some(2.5);
+        assert_eq!(Clamper::apply(&range, &mut (), 3.1), 2.5);
+
+        range.min = None;
+        assert_eq!(Clamper::apply(&range, &mut (), 1.9), 1.9);
+
+        range.set_symmetric(1.0);
+        assert_eq!(range, ClampParam::from(-1.0..=1.0));
+        assert!(range.contains(&0.5));
+        assert_eq!(Clamper::apply(&range, &mut (), -1.5), -1.0);
+
+        let range = ClampParam::from(..=3.0);
+        assert_eq!(Clamper::apply(&range, &mut (), -1.5), -1.5);
+        assert_eq!(Clamper::apply(&range, &mut (), 3.5), 3.0);
+        assert_eq!(ClampParam::<f32>::from(..), ClampParam::default());
+    }
+
+    #[test]
+    fn saturation_duty() {
+        type S = Saturation<ClampParam<f32>, f32>;
+        let param = SaturationParam::new(ClampParam::new(-1.0, 1.0), 4);
+        let mut state = SaturationState::default();
+
+        assert_eq!(S::apply(&param, &mut state, 2.0), 1.0);
+        assert_eq!(S::apply(&param, &mut state, 0.5), 0.5);
+        assert_eq!(S::apply(&param, &mut state, -3.0), -1.0);
+        // the value at limit isn't clamped
+        assert_eq!(S::apply(&param, &mut state, 1.0), 1.0);
+
+        assert_eq!(state.current(), Duty::default());
+        assert_eq!(
+            state.last(),
+            Duty {
+                low: 1,
+                high: 1,
+                total: 4
+            }
+        );
+        assert_eq!(state.last().ratio(), 0.5);
+
+        S::apply(&param, &mut state, 5.0);
+        assert_eq!(state.current().high, 1);
+        assert_eq!(state.last().total, 4);
+    }
 }
@@ -0,0 +1,227 @@
+/*!
+
+## Crossfade
+
+This module implements the mixer which smoothly blends two input signals:
+
+_y = α * x1 + (1 - α) * x2_
+
+The blending factor _α_ is passed at runtime as a part of input,
+but the actual factor follows it with limited rate (slew-limited),
+so the output changes smoothly even when the target factor switches abruptly.
+
+This block is useful for handing over between regulators without bumps,
+for example from open-loop startup to closed-loop field-oriented control of motor.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use typenum::{Diff, Prod, Sum};
+
+/**
+Crossfade parameters
+
+- `A` - blending factor type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<A> {
+    /// The maximum change of factor per step
+    step: A,
+}
+
+impl<A> Param<A> {
+    /**
+    Init crossfade parameters using the maximum rate
+
+    - `step`: The maximum change of blending factor per step (0..1]
+     */
+    pub fn new(step: A) -> Self {
+        Self { step }
+    }
+
+    /**
+    Init crossfade parameters using fade time
+
+    - `time`: The time of full fade from one input to another
+    - `period`: The sampling time (or control step period)
+
+    _step = period / time_
+     */
+    pub fn from_time<T>(time: T, period: T) -> Self
+    where
+        A: Cast<f64>,
+        f64: Cast<T>,
+    {
+        Self {
+            step: A::cast(f64::cast(period) / f64::cast(time)),
+        }
+    }
+}
+
+/**
+Crossfade state
+
+- `A` - blending factor type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<A> {
+    /// The actual blending factor
+    alpha: A,
+}
+
+impl<A> State<A> {
+    /**
+    Initialize crossfade state
+
+    - `alpha`: The initial blending factor
+     */
+    pub fn new(alpha: A) -> Self {
+        Self { alpha }
+    }
+
+    /// The actual blending factor
+    pub fn alpha(&self) -> A
+    where
+        A: Copy,
+    {
+        self.alpha
+    }
+}
+
+/// The preset value is a blending factor
+impl<A> ResetState for State<A>
+where
+    A: Default,
+{
+    type Value = A;
+
+    fn reset(&mut self) {
+        self.alpha = A::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.alpha = value;
+    }
+}
+
+snapshot_fields! {
+    State<A> {
+        alpha: A,
+    }
+}
+
+/**
+Crossfade mixer
+
+- `A` - blending factor type
+- `T` - values type
+
+The input is a tuple of two values and the target blending factor.
+*/
+#[derive(Debug)]
+pub struct Crossfade<A, T>(PhantomData<(A, T)>);
+
+impl<A, T> Transducer for Crossfade<A, T>
+where
+    A: Copy
+        + PartialOrd
+        + Cast<f64>
+        + Add<A>
+        + Sub<A>
+        + Cast<Sum<A, A>>
+        + Cast<Diff<A, A>>
+        + Mul<T>,
+    T: Add<T> + Cast<Prod<A, T>> + Cast<Sum<T, T>>,
+{
+    type Input = (T, T, A);
+    type Output = T;
+    type Param = Param<A>;
+    type State = State<A>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (x1, x2, target): Self::Input,
+    ) -> Self::Output {
+        let alpha = state.alpha;
+
+        state.alpha = if target > alpha {
+            let next = A::cast(alpha + param.step);
+            if next < target {
+                next
+            } else {
+                target
+            }
+        } else {
+            let next = A::cast(alpha - param.step);
+            if next > target {
+                next
+            } else {
+                target
+            }
+        };
+
+        let alpha = state.alpha;
+        let one_sub_alpha = A::cast(A::cast(1.0) - alpha);
+
+        T::cast(T::cast(alpha * x1) + T::cast(one_sub_alpha * x2))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P24, P32};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn crossfade_f32() {
+        let param = Param::<f32>::new(0.25);
+        let mut state = State::new(0.0);
+        type X = Crossfade<f32, f32>;
+
+        assert_eq!(X::apply(&param, &mut state, (8.0, 4.0, 0.0)), 4.0);
+        assert_eq!(X::apply(&param, &mut state, (8.0, 4.0, 1.0)), 5.0);
+        assert_eq!(X::apply(&param, &mut state, (8.0, 4.0, 1.0)), 6.0);
+        assert_eq!(X::apply(&param, &mut state, (8.0, 4.0, 0.6)), 6.4);
+        assert_eq!(X::apply(&param, &mut state, (8.0, 4.0, 0.6)), 6.4);
+        assert_eq!(X::apply(&param, &mut state, (8.0, 4.0, 0.0)), 5.4);
+        assert_eq!(state.alpha(), 0.35000002);
+    }
+
+    #[test]
+    fn crossfade_fix() {
+        type A = Fix<P24, N16>;
+        type T = Fix<P32, N8>;
+
+        // Full fade during 10 ms with 1 ms period
+        let param = Param::<A>::from_time(0.01, 0.001);
+        let mut state = State::new(A::cast(0.0));
+        type X = Crossfade<A, T>;
+
+        let mut out = T::cast(0.0);
+        for _ in 0..5 {
+            out = X::apply(
+                &param,
+                &mut state,
+                (T::cast(100.0), T::cast(0.0), A::cast(0.9)),
+            );
+        }
+        // The step is truncated to 0.09998
+        assert_eq!(out, T::new(12798));
+
+        for _ in 0..5 {
+            out = X::apply(
+                &param,
+                &mut state,
+                (T::cast(100.0), T::cast(0.0), A::cast(0.9)),
+            );
+        }
+        // The target factor is reached
+        assert_eq!(out, T::new(23039));
+    }
+}
@@ -0,0 +1,557 @@
+/*!
+
+## Events
+
+This module implements the lightweight framework which decouples the signal processing
+from the reaction logic of application.
+
+The detectors is transducers which outputs optional typed events:
+
+- [`Threshold`] detects the crossing of threshold with hysteresis ([`Crossing`] events)
+- [`Edge`] detects the changes of flags like faults ([`Change`] events)
+
+Any other transducer which outputs `Option<E>` can be used as detector too.
+
+The [`Emitter`] wrapper stamps the events of detector with the tick timestamp
+which is supplied by application and puts it into the fixed-capacity [`EventQueue`].
+The application takes the events from the queue later (for example in the main loop).
+When the queue is full the oldest event is dropped and the number of lost events is counted.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+
+/**
+Timestamped event
+
+- `C` - tick counter type
+- `E` - event type
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Event<C, E> {
+    /// The tick when event occurred
+    pub tick: C,
+    /// The event
+    pub event: E,
+}
+
+snapshot_fields! {
+    Event<C, E> {
+        tick: C,
+        event: E,
+    }
+}
+
+/**
+Fixed-capacity event queue
+
+- `C` - tick counter type
+- `E` - event type
+- `N` - the capacity of queue
+*/
+#[derive(Debug, Clone)]
+pub struct EventQueue<C, E, N>
+where
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    /// The storage of events
+    events: GenericArray<Option<Event<C, E>>, N>,
+    /// The index of oldest event
+    head: usize,
+    /// The number of events in queue
+    len: usize,
+    /// The number of lost events
+    lost: u32,
+}
+
+snapshot_fields! {
+    EventQueue<C, E, N> where [N: ArrayLength<Option<Event<C, E>>>] {
+        events: GenericArray<Option<Event<C, E>>, N>,
+        head: usize,
+        len: usize,
+        lost: u32,
+    }
+}
+
+impl<C, E, N> Default for EventQueue<C, E, N>
+where
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    fn default() -> Self {
+        Self {
+            events: GenericArray::default(),
+            head: 0,
+            len: 0,
+            lost: 0,
+        }
+    }
+}
+
+impl<C, E, N> EventQueue<C, E, N>
+where
+    C: Copy,
+    E: Copy,
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    /// The capacity of queue
+    pub fn capacity() -> usize {
+        N::USIZE
+    }
+
+    /// The number of events in queue
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of events which was lost because the queue was full
+    pub fn lost(&self) -> u32 {
+        self.lost
+    }
+
+    /**
+    Put event into queue
+
+    - `tick`: The timestamp of event
+    - `event`: The event
+
+    Returns `false` when the oldest event was dropped to free space.
+     */
+    pub fn push(&mut self, tick: C, event: E) -> bool {
+        let dropped = self.len == N::USIZE;
+
+        if dropped {
+            self.head = (self.head + 1) % N::USIZE;
+            self.len -= 1;
+            self.lost = self.lost.saturating_add(1);
+        }
+
+        self.events[(self.head + self.len) % N::USIZE] = Some(Event { tick, event });
+        self.len += 1;
+
+        !dropped
+    }
+
+    /// Take the oldest event from queue
+    pub fn pop(&mut self) -> Option<Event<C, E>> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let event = self.events[self.head].take();
+        self.head = (self.head + 1) % N::USIZE;
+        self.len -= 1;
+
+        event
+    }
+
+    /// Remove all events and clear the counter of lost events
+    pub fn clear(&mut self) {
+        for event in self.events.iter_mut() {
+            *event = None;
+        }
+        self.head = 0;
+        self.len = 0;
+        self.lost = 0;
+    }
+}
+
+/// Threshold crossing event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossing {
+    /// The value rises above the threshold
+    Rising,
+    /// The value falls below the threshold
+    Falling,
+}
+
+/**
+Threshold detector parameters
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct ThresholdParam<T> {
+    /// The upper threshold
+    high: T,
+    /// The lower threshold
+    low: T,
+}
+
+impl<T> ThresholdParam<T> {
+    /**
+    Init threshold detector parameters
+
+    - `threshold`: The threshold
+    - `hysteresis`: The full width of hysteresis around threshold
+     */
+    pub fn new<V>(threshold: V, hysteresis: V) -> Self
+    where
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        let threshold = f64::cast(threshold);
+        let half = 0.5 * f64::cast(hysteresis);
+
+        Self {
+            high: T::cast(threshold + half),
+            low: T::cast(threshold - half),
+        }
+    }
+}
+
+/**
+Threshold detector state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThresholdState {
+    /// The value is above threshold
+    above: Option<bool>,
+}
+
+/// The preset value is a side of threshold (`true` when above)
+impl ResetState for ThresholdState {
+    type Value = bool;
+
+    fn reset(&mut self) {
+        self.above = None;
+    }
+
+    fn preset(&mut self, above: Self::Value) {
+        self.above = Some(above);
+    }
+}
+
+snapshot_fields! {
+    ThresholdState {
+        above: Option<bool>,
+    }
+}
+
+/**
+Threshold detector
+
+- `T` - values type
+
+The input is a value. The output is a crossing event.
+The side of threshold is initialized by the first value after reset without event.
+*/
+#[derive(Debug)]
+pub struct Threshold<T>(PhantomData<T>);
+
+impl<T> Transducer for Threshold<T>
+where
+    T: PartialOrd,
+{
+    type Input = T;
+    type Output = Option<Crossing>;
+    type Param = ThresholdParam<T>;
+    type State = ThresholdState;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        match state.above {
+            Some(false) if value > param.high => {
+                state.above = Some(true);
+                Some(Crossing::Rising)
+            }
+            Some(true) if value < param.low => {
+                state.above = Some(false);
+                Some(Crossing::Falling)
+            }
+            Some(_) => None,
+            None => {
+                state.above = Some(value > param.high);
+                None
+            }
+        }
+    }
+}
+
+/// Flag change event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// The flag was set
+    Set,
+    /// The flag was cleared
+    Cleared,
+}
+
+/**
+Edge detector state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EdgeState {
+    /// The last flag
+    last: bool,
+}
+
+/// The preset value is a last flag
+impl ResetState for EdgeState {
+    type Value = bool;
+
+    fn reset(&mut self) {
+        self.last = false;
+    }
+
+    fn preset(&mut self, last: Self::Value) {
+        self.last = last;
+    }
+}
+
+snapshot_fields! {
+    EdgeState {
+        last: bool,
+    }
+}
+
+/**
+Edge detector
+
+The input is a flag. The output is a change event.
+*/
+#[derive(Debug)]
+pub struct Edge;
+
+impl Transducer for Edge {
+    type Input = bool;
+    type Output = Option<Change>;
+    type Param = ();
+    type State = EdgeState;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, flag: Self::Input) -> Self::Output {
+        if flag == state.last {
+            return None;
+        }
+
+        state.last = flag;
+
+        Some(if flag { Change::Set } else { Change::Cleared })
+    }
+}
+
+/**
+Event emitter state
+
+- `S` - detector state type
+- `C` - tick counter type
+- `E` - event type
+- `N` - the capacity of queue
+*/
+#[derive(Debug, Clone)]
+pub struct EmitterState<S, C, E, N>
+where
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    /// The detector state
+    pub detector: S,
+    /// The event queue
+    pub queue: EventQueue<C, E, N>,
+}
+
+impl<S, C, E, N> Default for EmitterState<S, C, E, N>
+where
+    S: Default,
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    fn default() -> Self {
+        Self {
+            detector: S::default(),
+            queue: EventQueue::default(),
+        }
+    }
+}
+
+/// The preset value is a preset of detector (the queue is kept)
+impl<S, C, E, N> ResetState for EmitterState<S, C, E, N>
+where
+    S: ResetState,
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    type Value = S::Value;
+
+    fn reset(&mut self) {
+        self.detector.reset();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.detector.preset(value);
+    }
+}
+
+snapshot_fields! {
+    EmitterState<S, C, E, N> where [N: ArrayLength<Option<Event<C, E>>>] {
+        detector: S,
+        queue: EventQueue<C, E, N>,
+    }
+}
+
+/**
+Event emitter
+
+- `D` - detector transducer
+- `C` - tick counter type
+- `N` - the capacity of queue
+
+The input is a tuple of tick and input of detector.
+The output is an output of detector (the event is also put into queue).
+*/
+#[derive(Debug)]
+pub struct Emitter<D, C, N>(PhantomData<(D, C, N)>);
+
+impl<D, C, E, N> Transducer for Emitter<D, C, N>
+where
+    D: Transducer<Output = Option<E>>,
+    C: Copy,
+    E: Copy,
+    N: ArrayLength<Option<Event<C, E>>>,
+{
+    type Input = (C, D::Input);
+    type Output = Option<E>;
+    type Param = D::Param;
+    type State = EmitterState<D::State, C, E, N>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (tick, value): Self::Input,
+    ) -> Self::Output {
+        let event = D::apply(param, &mut state.detector, value);
+
+        if let Some(event) = event {
+            state.queue.push(tick, event);
+        }
+
+        event
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U2, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn events_queue() {
+        let mut queue = EventQueue::<u32, u8, U2>::default();
+
+        assert!(queue.is_empty());
+        assert!(queue.push(1, 10));
+        assert!(queue.push(2, 20));
+        assert!(!queue.push(3, 30));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.lost(), 1);
+
+        assert_eq!(queue.pop(), Some(Event { tick: 2, event: 20 }));
+        assert!(queue.push(4, 40));
+        assert_eq!(queue.pop(), Some(Event { tick: 3, event: 30 }));
+        assert_eq!(queue.pop(), Some(Event { tick: 4, event: 40 }));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn events_threshold() {
+        type D = Emitter<Threshold<f32>, u32, U4>;
+        let param = ThresholdParam::<f32>::new(10.0, 1.0);
+        let mut state = EmitterState::default();
+
+        let values = [12.0, 9.0, 10.2, 10.7, 10.0, 9.4, 9.8, 11.0];
+        let mut events = [None; 8];
+        for (tick, (value, event)) in values.iter().zip(events.iter_mut()).enumerate() {
+            *event = D::apply(&param, &mut state, (tick as u32, *value));
+        }
+
+        use Crossing::*;
+        assert_eq!(
+            events,
+            [
+                None,
+                Some(Falling),
+                None,
+                Some(Rising),
+                None,
+                Some(Falling),
+                None,
+                Some(Rising)
+            ]
+        );
+
+        let queue = &mut state.queue;
+        assert_eq!(queue.len(), 4);
+        assert_eq!(
+            queue.pop(),
+            Some(Event {
+                tick: 1,
+                event: Falling
+            })
+        );
+        assert_eq!(
+            queue.pop(),
+            Some(Event {
+                tick: 3,
+                event: Rising
+            })
+        );
+        assert_eq!(
+            queue.pop(),
+            Some(Event {
+                tick: 5,
+                event: Falling
+            })
+        );
+        assert_eq!(
+            queue.pop(),
+            Some(Event {
+                tick: 7,
+                event: Rising
+            })
+        );
+    }
+
+    #[test]
+    fn events_fault() {
+        type D = Emitter<Edge, u64, U4>;
+        let mut state = EmitterState::default();
+
+        for (tick, fault) in [false, true, true, false, false].iter().enumerate() {
+            D::apply(&(), &mut state, (1000 + tick as u64, *fault));
+        }
+
+        assert_eq!(
+            state.queue.pop(),
+            Some(Event {
+                tick: 1001,
+                event: Change::Set
+            })
+        );
+        assert_eq!(
+            state.queue.pop(),
+            Some(Event {
+                tick: 1003,
+                event: Change::Cleared
+            })
+        );
+        assert!(state.queue.is_empty());
+    }
+
+    #[test]
+    fn events_fix() {
+        type T = Fix<P32, N16>;
+        type D = Threshold<T>;
+        let param = ThresholdParam::<T>::new(1.0, 0.5);
+        let mut state = ThresholdState::default();
+
+        assert_eq!(D::apply(&param, &mut state, T::cast(0.0)), None);
+        assert_eq!(D::apply(&param, &mut state, T::cast(1.2)), None);
+        assert_eq!(
+            D::apply(&param, &mut state, T::cast(1.3)),
+            Some(Crossing::Rising)
+        );
+    }
+}
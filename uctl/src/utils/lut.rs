@@ -0,0 +1,350 @@
+/*!
+
+## Lookup table
+
+This module implements piecewise-linear interpolation over a table of points
+which is usually used for linearization of sensors and actuators.
+
+The points should be sorted by _x_. The slopes of segments is evaluated at initialization,
+so the interpolation requires single multiplication:
+
+_y = y<sub>i</sub> + k<sub>i</sub> (x - x<sub>i</sub>)_, where _x<sub>i</sub> ≤ x < x<sub>i+1</sub>_
+
+The input values out of range of table is clamped to the first and the last points.
+
+The [periodic table](Periodic) is indexed by angle instead. It has _N_ values uniformly distributed
+over one cycle, so the node is found by single multiplication and the last value
+is interpolated with the first one:
+
+_y = y<sub>i</sub> + f (y<sub>i+1</sub> - y<sub>i</sub>)_, where _i + f = θ N_
+
+The values of periodic table can be changed at runtime (for example by learning algorithms).
+
+*/
+
+use crate::{snapshot_fields, Cast, Cyc, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Diff, Prod, Sum};
+
+/// The increment of output from the point of segment
+pub type Increment<X, K> = Prod<K, Diff<X, X>>;
+
+/**
+Lookup table parameters
+
+- `X` - input values type
+- `Y` - output values type
+- `K` - slopes type
+- `N` - the number of points (at least 2)
+*/
+#[derive(Debug, Clone)]
+pub struct Param<X, Y, K, N>
+where
+    N: ArrayLength<X> + ArrayLength<Y> + ArrayLength<K>,
+{
+    /// The inputs of points
+    x: GenericArray<X, N>,
+    /// The outputs of points
+    y: GenericArray<Y, N>,
+    /// The slopes of segments
+    k: GenericArray<K, N>,
+}
+
+impl<X, Y, K, N> Param<X, Y, K, N>
+where
+    N: ArrayLength<X> + ArrayLength<Y> + ArrayLength<K>,
+{
+    /**
+    Init lookup table parameters
+
+    - `points`: The points of table (_x_, _y_) sorted by _x_ (the length should be `N`)
+     */
+    pub fn new<V>(points: &[(V, V)]) -> Self
+    where
+        V: Copy,
+        X: Cast<f64>,
+        Y: Cast<f64>,
+        K: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(points.len() == N::USIZE);
+
+        Self::from_fn(|i| (f64::cast(points[i].0), f64::cast(points[i].1)))
+    }
+
+    /**
+    Init lookup table parameters using function
+
+    - `point`: The function which gives the point (_x_, _y_) by index (the points should be sorted by _x_)
+     */
+    pub fn from_fn(point: impl Fn(usize) -> (f64, f64)) -> Self
+    where
+        X: Cast<f64>,
+        Y: Cast<f64>,
+        K: Cast<f64>,
+    {
+        assert!(N::USIZE >= 2);
+
+        Self {
+            x: (0..N::USIZE).map(|i| X::cast(point(i).0)).collect(),
+            y: (0..N::USIZE).map(|i| Y::cast(point(i).1)).collect(),
+            k: (0..N::USIZE)
+                .map(|i| {
+                    K::cast(if i + 1 < N::USIZE {
+                        let ((x0, y0), (x1, y1)) = (point(i), point(i + 1));
+                        (y1 - y0) / (x1 - x0)
+                    } else {
+                        0.0
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /**
+    Locate the input value in the table
+
+    - `value`: The input value
+
+    Returns the index of the first point of segment and the increment of output from it.
+    The increment is absent when the value is out of range of table,
+    so the index is the first or the last point.
+     */
+    pub fn locate(&self, value: X) -> (usize, Option<Increment<X, K>>)
+    where
+        X: Copy + PartialOrd + Sub<X>,
+        K: Copy + Mul<Diff<X, X>>,
+    {
+        let last = N::USIZE - 1;
+
+        if value <= self.x[0] {
+            return (0, None);
+        }
+        if value >= self.x[last] {
+            return (last, None);
+        }
+
+        let i = self.x[1..last].iter().take_while(|x| **x <= value).count();
+
+        (i, Some(self.k[i] * (value - self.x[i])))
+    }
+}
+
+/**
+Lookup table
+
+- `X` - input values type
+- `Y` - output values type
+- `K` - slopes type
+- `N` - the number of points
+*/
+#[derive(Debug)]
+pub struct Table<X, Y, K, N>(PhantomData<(X, Y, K, N)>);
+
+impl<X, Y, K, N> Transducer for Table<X, Y, K, N>
+where
+    X: Copy + PartialOrd + Sub<X>,
+    Y: Copy + Add<Y> + Cast<Prod<K, Diff<X, X>>> + Cast<Sum<Y, Y>>,
+    K: Copy + Mul<Diff<X, X>>,
+    N: ArrayLength<X> + ArrayLength<Y> + ArrayLength<K>,
+{
+    type Input = X;
+    type Output = Y;
+    type Param = Param<X, Y, K, N>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, value: Self::Input) -> Self::Output {
+        match param.locate(value) {
+            (i, Some(delta)) => Y::cast(param.y[i] + Y::cast(delta)),
+            (i, None) => param.y[i],
+        }
+    }
+}
+
+/**
+Periodic lookup table parameters
+
+- `Y` - output values type
+- `N` - the number of values
+*/
+#[derive(Debug, Clone, Default)]
+pub struct PeriodicParam<Y, N>
+where
+    N: ArrayLength<Y>,
+{
+    /// The values of nodes
+    y: GenericArray<Y, N>,
+}
+
+snapshot_fields! {
+    PeriodicParam<Y, N> where [N: ArrayLength<Y>] {
+        y: GenericArray<Y, N>,
+    }
+}
+
+impl<Y, N> PeriodicParam<Y, N>
+where
+    N: ArrayLength<Y>,
+{
+    /**
+    Init periodic lookup table parameters
+
+    - `values`: The values of nodes starting from zero angle (the length should be `N`)
+     */
+    pub fn new<V>(values: &[V]) -> Self
+    where
+        V: Copy,
+        Y: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(values.len() == N::USIZE && N::USIZE >= 1);
+
+        Self {
+            y: values
+                .iter()
+                .map(|value| Y::cast(f64::cast(*value)))
+                .collect(),
+        }
+    }
+
+    /// The values of nodes
+    pub fn values(&self) -> &[Y] {
+        &self.y
+    }
+
+    /// The mutable values of nodes
+    pub fn values_mut(&mut self) -> &mut [Y] {
+        &mut self.y
+    }
+
+    /**
+    Set the value of node
+
+    - `index`: The index of node
+    - `value`: The new value
+     */
+    pub fn set(&mut self, index: usize, value: Y) {
+        self.y[index] = value;
+    }
+
+    /**
+    Get the indexes of two nodes around the angle and the fraction between them
+
+    - `angle`: The angle in cycles
+     */
+    pub fn locate<A>(angle: A) -> (usize, usize, A)
+    where
+        A: Copy + Cast<u32> + Mul<A> + Sub<A> + Cast<Prod<A, A>> + Cast<Diff<A, A>>,
+        u32: Cast<A>,
+    {
+        let position = A::cast(angle * A::cast(N::U32));
+        let index = u32::cast(position);
+        let fraction = A::cast(position - A::cast(index));
+        let index = index as usize % N::USIZE;
+
+        (index, (index + 1) % N::USIZE, fraction)
+    }
+
+    /**
+    Interpolate the value between two nodes
+
+    - `location`: The location given by [`PeriodicParam::locate`]
+     */
+    pub fn interpolate<A>(&self, (this, next, fraction): (usize, usize, A)) -> Y
+    where
+        A: Mul<Y>,
+        Y: Copy + Add<Y> + Sub<Y> + Cast<Sum<Y, Y>> + Cast<Diff<Y, Y>> + Cast<Prod<A, Y>>,
+    {
+        let (this, next) = (self.y[this], self.y[next]);
+        Y::cast(this + Y::cast(fraction * Y::cast(next - this)))
+    }
+}
+
+impl<Y, N> From<GenericArray<Y, N>> for PeriodicParam<Y, N>
+where
+    N: ArrayLength<Y>,
+{
+    fn from(y: GenericArray<Y, N>) -> Self {
+        Self { y }
+    }
+}
+
+/**
+Periodic lookup table
+
+- `A` - angle type
+- `Y` - output values type
+- `N` - the number of values
+
+The input is an angle.
+*/
+#[derive(Debug)]
+pub struct Periodic<A, Y, N>(PhantomData<(A, Y, N)>);
+
+impl<A, Y, N> Transducer for Periodic<A, Y, N>
+where
+    A: Copy + Cast<u32> + Mul<A> + Sub<A> + Cast<Prod<A, A>> + Cast<Diff<A, A>> + Mul<Y>,
+    u32: Cast<A>,
+    Y: Copy + Add<Y> + Sub<Y> + Cast<Sum<Y, Y>> + Cast<Diff<Y, Y>> + Cast<Prod<A, Y>>,
+    N: ArrayLength<Y>,
+{
+    type Input = Cyc<A>;
+    type Output = Y;
+    type Param = PeriodicParam<Y, N>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, angle: Self::Input) -> Self::Output {
+        param.interpolate(PeriodicParam::<Y, N>::locate(*angle))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N24, N8, P16, P32, U3, U4};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn lut_f32() {
+        type L = Table<f32, f32, f32, U3>;
+        let param = Param::<f32, f32, f32, U3>::new(&[(0.0, 0.0), (1.0, 2.0), (3.0, 3.0)]);
+
+        assert_eq!(L::apply(&param, &mut (), -1.0), 0.0);
+        assert_eq!(L::apply(&param, &mut (), 0.5), 1.0);
+        assert_eq!(L::apply(&param, &mut (), 1.0), 2.0);
+        assert_eq!(L::apply(&param, &mut (), 2.0), 2.5);
+        assert_eq!(L::apply(&param, &mut (), 5.0), 3.0);
+    }
+
+    #[test]
+    fn lut_fix() {
+        type X = Fix<P16, N8>;
+        type T = Fix<P32, N16>;
+        type L = Table<X, T, T, U3>;
+        let param = Param::<X, T, T, U3>::new(&[(-1.0, 10.0), (0.0, 0.0), (4.0, 1.0)]);
+
+        assert_eq!(L::apply(&param, &mut (), X::cast(-0.5)), T::cast(5.0));
+        assert_eq!(L::apply(&param, &mut (), X::cast(2.0)), T::cast(0.5));
+    }
+
+    #[test]
+    fn lut_periodic() {
+        type A = Fix<P32, N24>;
+        type T = Fix<P32, N16>;
+        type L = Periodic<A, T, U4>;
+        let mut param = PeriodicParam::<T, U4>::new(&[0.0, 1.0, 2.0, 1.0]);
+
+        assert_eq!(L::apply(&param, &mut (), Cyc(A::cast(0.125))), T::cast(0.5));
+        assert_eq!(L::apply(&param, &mut (), Cyc(A::cast(0.5))), T::cast(2.0));
+        // the last value is interpolated with the first one
+        assert_eq!(L::apply(&param, &mut (), Cyc(A::cast(0.875))), T::cast(0.5));
+
+        param.set(0, T::cast(-1.0));
+        assert_eq!(param.values()[0], T::cast(-1.0));
+        assert_eq!(L::apply(&param, &mut (), Cyc(A::cast(0.875))), T::cast(0.0));
+    }
+}
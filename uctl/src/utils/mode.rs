@@ -0,0 +1,325 @@
+/*!
+
+## Mode state machine
+
+This module implements the supervisory skeleton of control application
+with typical operating modes:
+
+- _Init_ - initialization (calibration, precharge and etc.)
+- _Standby_ - ready to run, output is in safe state
+- _Run_ - normal operation
+- _Fault_ - output is in safe state until fault will be cleared
+
+The transitions is triggered by commands and checked by guard hooks:
+
+- _Init_ → _Standby_ when initialization is complete
+- _Standby_ → _Run_ by _Start_ command when starting is allowed
+- _Run_ → _Standby_ by _Stop_ command
+- any → _Fault_ by _Fault_ command or when fault is detected
+- _Fault_ → _Init_ by _Reset_ command when fault is gone
+
+Each mode has own transducer chain which is selected at compile time.
+The state of chain is reset when the mode is entered.
+
+*/
+
+use crate::{snapshot_enum, snapshot_fields, ResetState, Transducer};
+use core::marker::PhantomData;
+
+/// Operating mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Initialization
+    #[default]
+    Init,
+    /// Ready to run
+    Standby,
+    /// Normal operation
+    Run,
+    /// Fault
+    Fault,
+}
+
+snapshot_enum!(Mode {
+    Init,
+    Standby,
+    Run,
+    Fault
+});
+
+/// Supervisory command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Start operation
+    Start,
+    /// Stop operation
+    Stop,
+    /// Report fault
+    Fault,
+    /// Reset after fault
+    Reset,
+}
+
+/**
+Guard hooks of transitions
+
+- `I` - input value type
+
+All hooks has default implementation which always allows transitions.
+*/
+pub trait Guards<I> {
+    /// Check when initialization is complete
+    fn initialized(&self, _value: &I) -> bool {
+        true
+    }
+
+    /// Check when starting is allowed
+    fn can_start(&self, _value: &I) -> bool {
+        true
+    }
+
+    /// Check when fault is detected
+    fn is_fault(&self, _value: &I) -> bool {
+        false
+    }
+}
+
+impl<I> Guards<I> for () {}
+
+/**
+Mode state machine parameters
+
+- `G` - guard hooks type
+- `N` - _Init_ chain parameters type
+- `S` - _Standby_ chain parameters type
+- `R` - _Run_ chain parameters type
+- `F` - _Fault_ chain parameters type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Param<G, N, S, R, F> {
+    /// Guard hooks
+    pub guards: G,
+    /// _Init_ chain parameters
+    pub init: N,
+    /// _Standby_ chain parameters
+    pub standby: S,
+    /// _Run_ chain parameters
+    pub run: R,
+    /// _Fault_ chain parameters
+    pub fault: F,
+}
+
+/**
+Mode state machine state
+
+- `N` - _Init_ chain state type
+- `S` - _Standby_ chain state type
+- `R` - _Run_ chain state type
+- `F` - _Fault_ chain state type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<N, S, R, F> {
+    /// Actual mode
+    mode: Mode,
+    /// _Init_ chain state
+    pub init: N,
+    /// _Standby_ chain state
+    pub standby: S,
+    /// _Run_ chain state
+    pub run: R,
+    /// _Fault_ chain state
+    pub fault: F,
+}
+
+impl<N, S, R, F> State<N, S, R, F> {
+    /// Initialize mode state machine state
+    pub fn new(init: N, standby: S, run: R, fault: F) -> Self {
+        Self {
+            mode: Mode::Init,
+            init,
+            standby,
+            run,
+            fault,
+        }
+    }
+
+    /// Actual mode
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+}
+
+impl<N, S, R, F> ResetState for State<N, S, R, F>
+where
+    N: ResetState,
+    S: ResetState,
+    R: ResetState,
+    F: ResetState,
+{
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.mode = Mode::Init;
+        self.init.reset();
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    State<N, S, R, F> {
+        mode: Mode,
+        init: N,
+        standby: S,
+        run: R,
+        fault: F,
+    }
+}
+
+/**
+Mode state machine
+
+- `G` - guard hooks type
+- `N` - _Init_ chain
+- `S` - _Standby_ chain
+- `R` - _Run_ chain
+- `F` - _Fault_ chain
+
+The input is a tuple of optional command and input value of chains.
+The output is a tuple of actual mode and output value of selected chain.
+*/
+#[derive(Debug)]
+pub struct Machine<G, N, S, R, F>(PhantomData<(G, N, S, R, F)>);
+
+impl<G, N, S, R, F> Transducer for Machine<G, N, S, R, F>
+where
+    N: Transducer,
+    S: Transducer<Input = N::Input, Output = N::Output>,
+    R: Transducer<Input = N::Input, Output = N::Output>,
+    F: Transducer<Input = N::Input, Output = N::Output>,
+    N::State: ResetState,
+    S::State: ResetState,
+    R::State: ResetState,
+    F::State: ResetState,
+    G: Guards<N::Input>,
+{
+    type Input = (Option<Command>, N::Input);
+    type Output = (Mode, N::Output);
+    type Param = Param<G, N::Param, S::Param, R::Param, F::Param>;
+    type State = State<N::State, S::State, R::State, F::State>;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (command, value): Self::Input,
+    ) -> Self::Output {
+        let guards = &param.guards;
+
+        let mode = if command == Some(Command::Fault) || guards.is_fault(&value) {
+            Mode::Fault
+        } else {
+            match (state.mode, command) {
+                (Mode::Init, _) if guards.initialized(&value) => Mode::Standby,
+                (Mode::Standby, Some(Command::Start)) if guards.can_start(&value) => Mode::Run,
+                (Mode::Run, Some(Command::Stop)) => Mode::Standby,
+                (Mode::Fault, Some(Command::Reset)) => Mode::Init,
+                (mode, _) => mode,
+            }
+        };
+
+        if mode != state.mode {
+            state.mode = mode;
+            match mode {
+                Mode::Init => state.init.reset(),
+                Mode::Standby => state.standby.reset(),
+                Mode::Run => state.run.reset(),
+                Mode::Fault => state.fault.reset(),
+            }
+        }
+
+        let output = match mode {
+            Mode::Init => N::apply(&param.init, &mut state.init, value),
+            Mode::Standby => S::apply(&param.standby, &mut state.standby, value),
+            Mode::Run => R::apply(&param.run, &mut state.run, value),
+            Mode::Fault => F::apply(&param.fault, &mut state.fault, value),
+        };
+
+        (mode, output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ema, FnTransducer};
+
+    type Z = FnTransducer<f32, f32>;
+    type E = ema::Filter<f32, f32, f32>;
+    type M = Machine<Limits, Z, Z, E, Z>;
+
+    /// Input is a supply voltage
+    struct Limits;
+
+    impl Guards<f32> for Limits {
+        fn initialized(&self, value: &f32) -> bool {
+            *value > 10.0
+        }
+
+        fn is_fault(&self, value: &f32) -> bool {
+            *value > 30.0
+        }
+    }
+
+    fn zero(_: f32) -> f32 {
+        0.0
+    }
+
+    #[test]
+    fn mode_transitions() {
+        let param = Param {
+            guards: Limits,
+            init: zero as fn(f32) -> f32,
+            standby: zero as fn(f32) -> f32,
+            run: ema::Param::from_alpha(0.5),
+            fault: zero as fn(f32) -> f32,
+        };
+        let mut state = State::new((), (), ema::State::new(0.0), ());
+
+        assert_eq!(M::apply(&param, &mut state, (None, 5.0)), (Mode::Init, 0.0));
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Start), 12.0)),
+            (Mode::Standby, 0.0)
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Start), 12.0)),
+            (Mode::Run, 6.0)
+        );
+        assert_eq!(M::apply(&param, &mut state, (None, 12.0)), (Mode::Run, 9.0));
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Stop), 12.0)),
+            (Mode::Standby, 0.0)
+        );
+
+        // the run chain is reset on entering
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Start), 12.0)),
+            (Mode::Run, 6.0)
+        );
+
+        assert_eq!(
+            M::apply(&param, &mut state, (None, 32.0)),
+            (Mode::Fault, 0.0)
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Reset), 31.0)),
+            (Mode::Fault, 0.0)
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Reset), 5.0)),
+            (Mode::Init, 0.0)
+        );
+        assert_eq!(state.mode(), Mode::Init);
+    }
+}
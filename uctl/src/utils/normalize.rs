@@ -0,0 +1,166 @@
+/*!
+
+## Normalization
+
+This module implements deterministic normalization of non-negative fixed-point values
+so that the results sum exactly to the given total (usually one).
+
+The naive scaling of each value loses the fractional parts, so the sum of results
+can be less than required total by several least significant digits.
+The [largest remainder method](https://en.wikipedia.org/wiki/Largest_remainder_method)
+distributes the lost digits to the values which has largest fractional parts,
+so the total is preserved exactly.
+
+The algorithm operates with mantissas only using integer arithmetic and doesn't require allocations.
+The product of input mantissa and output total mantissa should fit into 64 bits.
+
+This utility is useful for blending of several controller outputs or distribution of duty between channels.
+
+*/
+
+use crate::Cast;
+use ufix::{Digits, Exponent, Fix, Mantissa, Radix};
+
+/**
+Normalize values to sum exactly to total
+
+- `values`: The non-negative values (negative values is treated as zeros)
+- `total`: The required sum of results
+- `output`: The normalized values
+
+Only the minimum of lengths of slices will be processed.
+When all values is zero the total is distributed evenly.
+ */
+pub fn normalize<R, B, E, Ro, Bo, Eo>(
+    values: &[Fix<R, B, E>],
+    total: Fix<Ro, Bo, Eo>,
+    output: &mut [Fix<Ro, Bo, Eo>],
+) where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Mantissa<R, B>: Copy,
+    i64: Cast<Mantissa<R, B>>,
+    Ro: Radix<Bo>,
+    Bo: Digits,
+    Eo: Exponent,
+    Mantissa<Ro, Bo>: Copy + Cast<u64>,
+    i64: Cast<Mantissa<Ro, Bo>>,
+{
+    let count = values.len().min(output.len());
+    if count == 0 {
+        return;
+    }
+    let values = &values[..count];
+    let output = &mut output[..count];
+
+    let total = i64::cast(total.bits).max(0) as u64;
+
+    let bits = |value: &Fix<R, B, E>| i64::cast(value.bits).max(0) as u64;
+    let sum: u64 = values.iter().map(bits).sum();
+
+    // The weight of each value and the divisor
+    let even = sum == 0;
+    let weight = |value: &Fix<R, B, E>| if even { 1 } else { bits(value) };
+    let sum = if even { count as u64 } else { sum };
+
+    let mut assigned = 0;
+    for (out, value) in output.iter_mut().zip(values.iter()) {
+        let share = weight(value) * total / sum;
+        *out = Fix::new(Mantissa::<Ro, Bo>::cast(share));
+        assigned += share;
+    }
+
+    // Distribute the rest to the values with largest remainders
+    // (the first value wins when remainders is equal)
+    let remainder = |index: usize| weight(&values[index]) * total % sum;
+    let mut last: Option<(u64, usize)> = None;
+
+    for _ in assigned..total {
+        let mut best: Option<(u64, usize)> = None;
+
+        for index in 0..count {
+            let rem = remainder(index);
+
+            // skip already selected values
+            if let Some((last_rem, last_index)) = last {
+                if rem > last_rem || (rem == last_rem && index <= last_index) {
+                    continue;
+                }
+            }
+
+            if best.map(|(best_rem, _)| rem > best_rem).unwrap_or(true) {
+                best = Some((rem, index));
+            }
+        }
+
+        if let Some((_, index)) = best {
+            output[index] = Fix::new(Mantissa::<Ro, Bo>::cast(
+                i64::cast(output[index].bits) as u64 + 1,
+            ));
+        }
+        last = best;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, N8, P16, P32};
+    use ufix::bin::Fix;
+
+    type I = Fix<P16, N8>;
+    type O = Fix<P32, N16>;
+
+    fn sum(values: &[O]) -> O {
+        values.iter().fold(O::cast(0.0), |sum, x| O::cast(sum + *x))
+    }
+
+    #[test]
+    fn normalize_thirds() {
+        let values = [I::cast(1.0), I::cast(1.0), I::cast(1.0)];
+        let mut output = [O::cast(0.0); 3];
+
+        normalize(&values, O::cast(1.0), &mut output);
+
+        assert_eq!(output, [O::new(21846), O::new(21845), O::new(21845)]);
+        assert_eq!(sum(&output), O::cast(1.0));
+    }
+
+    #[test]
+    fn normalize_remainders() {
+        let values = [I::cast(1.0), I::cast(2.0), I::cast(4.0), I::cast(0.0)];
+        let mut output = [O::cast(0.0); 4];
+
+        normalize(&values, O::cast(1.0), &mut output);
+
+        // 1/7 = 9362.28, 2/7 = 18724.57, 4/7 = 37449.14
+        assert_eq!(
+            output,
+            [O::new(9362), O::new(18725), O::new(37449), O::new(0)]
+        );
+        assert_eq!(sum(&output), O::cast(1.0));
+    }
+
+    #[test]
+    fn normalize_total() {
+        let values = [I::cast(0.5), I::cast(-1.0), I::cast(0.25)];
+        let mut output = [O::cast(0.0); 3];
+
+        normalize(&values, O::cast(100.0), &mut output);
+
+        assert_eq!(sum(&output), O::cast(100.0));
+        assert_eq!(output[1], O::cast(0.0));
+        assert_eq!(output[0], O::new(4369067));
+    }
+
+    #[test]
+    fn normalize_zeros() {
+        let values = [I::cast(0.0); 3];
+        let mut output = [O::cast(0.0); 3];
+
+        normalize(&values, O::new(4), &mut output);
+
+        assert_eq!(output, [O::new(2), O::new(1), O::new(1)]);
+    }
+}
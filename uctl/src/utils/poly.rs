@@ -0,0 +1,128 @@
+/*!
+
+## Polynomial
+
+This module implements evaluation of polynomial which is usually used for linearization
+of sensors (for example thermocouples and RTDs).
+
+_y = c<sub>0</sub> + c<sub>1</sub> x + c<sub>2</sub> x<sup>2</sup> + ... + c<sub>N-1</sub> x<sup>N-1</sup>_
+
+The polynomial is evaluated using Horner's method:
+
+_y = c<sub>0</sub> + x (c<sub>1</sub> + x (c<sub>2</sub> + ...))_
+
+When fixed-point values is used the accumulator type should hold the coefficients
+and the intermediate results with desired precision.
+
+*/
+
+use crate::{Cast, Transducer};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{Prod, Sum};
+
+/**
+Polynomial parameters
+
+- `A` - coefficients type
+- `N` - the number of coefficients
+*/
+#[derive(Debug, Clone)]
+pub struct Param<A, N>
+where
+    N: ArrayLength<A>,
+{
+    /// The coefficients in ascending order of powers
+    coefs: GenericArray<A, N>,
+}
+
+impl<A, N> Param<A, N>
+where
+    N: ArrayLength<A>,
+{
+    /**
+    Init polynomial parameters
+
+    - `coefs`: The coefficients in ascending order of powers (the length should be `N`)
+     */
+    pub fn new<V>(coefs: &[V]) -> Self
+    where
+        V: Copy,
+        A: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert_eq!(coefs.len(), N::USIZE);
+
+        Self {
+            coefs: coefs.iter().map(|coef| A::cast(f64::cast(*coef))).collect(),
+        }
+    }
+
+    /// The coefficients
+    pub fn coefs(&self) -> &[A] {
+        &self.coefs
+    }
+}
+
+/**
+Polynomial
+
+- `T` - input value type
+- `A` - coefficients (and output) type
+- `N` - the number of coefficients
+*/
+#[derive(Debug)]
+pub struct Polynomial<T, A, N>(PhantomData<(T, A, N)>);
+
+impl<T, A, N> Transducer for Polynomial<T, A, N>
+where
+    T: Copy,
+    A: Copy + Default + Mul<T> + Add<A> + Cast<Prod<A, T>> + Cast<Sum<A, A>>,
+    N: ArrayLength<A>,
+{
+    type Input = T;
+    type Output = A;
+    type Param = Param<A, N>;
+    type State = ();
+
+    fn apply(param: &Self::Param, _state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let mut coefs = param.coefs.iter().rev();
+        let first = coefs.next().copied().unwrap_or_default();
+
+        coefs.fold(first, |accum, coef| A::cast(*coef + A::cast(accum * value)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U1, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn poly_f32() {
+        type P = Polynomial<f32, f32, U3>;
+        let param = Param::<f32, U3>::new(&[1.0, -2.0, 0.5]);
+
+        assert_eq!(P::apply(&param, &mut (), 0.0), 1.0);
+        assert_eq!(P::apply(&param, &mut (), 2.0), -1.0);
+        assert_eq!(P::apply(&param, &mut (), -4.0), 17.0);
+
+        type C = Polynomial<f32, f32, U1>;
+        let param = Param::<f32, U1>::new(&[3.0]);
+        assert_eq!(C::apply(&param, &mut (), 5.0), 3.0);
+    }
+
+    #[test]
+    fn poly_fix() {
+        type T = Fix<P32, N16>;
+        type P = Polynomial<T, T, U3>;
+        let param = Param::<T, U3>::new(&[1.0, -2.0, 0.5]);
+
+        assert_eq!(P::apply(&param, &mut (), T::cast(2.0)), T::cast(-1.0));
+        assert_eq!(P::apply(&param, &mut (), T::cast(0.5)), T::cast(0.125));
+    }
+}
@@ -0,0 +1,333 @@
+/*!
+
+## Profile runner
+
+This module implements the setpoint generator which steps through the table of segments
+like the temperature profiles of reflow ovens or kilns (preheat, soak, reflow, cooling).
+
+Each segment has a duration, a target value and an interpolation:
+
+- [`Interpolation::Step`] - the setpoint jumps to target at start of segment and holds it
+- [`Interpolation::Ramp`] - the setpoint changes linearly from the target of previous segment
+  (or from initial value) to the target of segment during the segment
+
+The runner can be paused and resumed by the run flag.
+The end of each segment is reported by the [`Event`], so the application can react on it.
+When the last segment is done the runner holds the last target.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::{marker::PhantomData, ops::Add};
+use generic_array::{ArrayLength, GenericArray};
+use typenum::Sum;
+
+/// Interpolation of segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Jump to target and hold it
+    Step,
+    /// Linear ramp to target
+    Ramp,
+}
+
+/// Profile event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The segment with given index is done
+    End(usize),
+    /// The last segment is done
+    Complete,
+}
+
+/**
+Profile segment
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Segment<T> {
+    /// The duration in steps
+    steps: u32,
+    /// The target value
+    target: T,
+    /// The increment per step (for ramp)
+    delta: Option<T>,
+}
+
+/**
+Profile runner parameters
+
+- `T` - values type
+- `N` - the number of segments
+*/
+#[derive(Debug, Clone)]
+pub struct Param<T, N>
+where
+    N: ArrayLength<Segment<T>>,
+{
+    /// The initial value
+    initial: T,
+    /// The segments
+    segments: GenericArray<Segment<T>, N>,
+}
+
+impl<T, N> Param<T, N>
+where
+    N: ArrayLength<Segment<T>>,
+{
+    /**
+    Init profile runner parameters
+
+    - `initial`: The initial value
+    - `segments`: The segments (duration (s), target, interpolation) (the length should be `N`)
+    - `period`: The sampling period (s)
+
+    The duration of segment is rounded to whole number of periods (at least one).
+     */
+    pub fn new<V>(initial: V, segments: &[(V, V, Interpolation)], period: V) -> Self
+    where
+        V: Copy,
+        T: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(segments.len() == N::USIZE && N::USIZE > 0);
+
+        let period = f64::cast(period);
+        let mut start = f64::cast(initial);
+
+        Self {
+            initial: T::cast(start),
+            segments: segments
+                .iter()
+                .map(|(duration, target, interpolation)| {
+                    let steps = ((f64::cast(*duration) / period + 0.5) as u32).max(1);
+                    let target = f64::cast(*target);
+                    let delta = match interpolation {
+                        Interpolation::Step => None,
+                        Interpolation::Ramp => Some(T::cast((target - start) / steps as f64)),
+                    };
+                    start = target;
+
+                    Segment {
+                        steps,
+                        target: T::cast(target),
+                        delta,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of segments
+    pub fn segments() -> usize {
+        N::USIZE
+    }
+}
+
+/**
+Profile runner state
+
+- `T` - values type
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State<T> {
+    /// The index of actual segment
+    segment: usize,
+    /// The number of steps since start of segment
+    elapsed: u32,
+    /// The actual setpoint (unknown until first step)
+    value: Option<T>,
+}
+
+impl<T> State<T> {
+    /// The index of actual segment (the number of segments when complete)
+    pub fn segment(&self) -> usize {
+        self.segment
+    }
+
+    /// The number of steps since start of actual segment
+    pub fn elapsed(&self) -> u32 {
+        self.elapsed
+    }
+}
+
+/// The preset value is an index of segment to start from
+impl<T> ResetState for State<T> {
+    type Value = usize;
+
+    fn reset(&mut self) {
+        self.preset(0);
+    }
+
+    fn preset(&mut self, segment: Self::Value) {
+        self.segment = segment;
+        self.elapsed = 0;
+        self.value = None;
+    }
+}
+
+snapshot_fields! {
+    State<T> {
+        segment: usize,
+        elapsed: u32,
+        value: Option<T>,
+    }
+}
+
+/**
+Profile runner
+
+- `T` - values type
+- `N` - the number of segments
+
+The input is a run flag (`false` pauses the runner).
+The output is a tuple of setpoint and optional event.
+*/
+#[derive(Debug)]
+pub struct Runner<T, N>(PhantomData<(T, N)>);
+
+impl<T, N> Transducer for Runner<T, N>
+where
+    T: Copy + Add<T> + Cast<Sum<T, T>>,
+    N: ArrayLength<Segment<T>>,
+{
+    type Input = bool;
+    type Output = (T, Option<Event>);
+    type Param = Param<T, N>;
+    type State = State<T>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, run: Self::Input) -> Self::Output {
+        let last = N::USIZE - 1;
+
+        // the start value of actual segment
+        let value = state.value.unwrap_or_else(|| match state.segment {
+            0 => param.initial,
+            index => param.segments[(index - 1).min(last)].target,
+        });
+
+        if !run || state.segment > last {
+            state.value = Some(value);
+            return (value, None);
+        }
+
+        let segment = &param.segments[state.segment];
+
+        state.elapsed += 1;
+
+        let (value, event) = if state.elapsed < segment.steps {
+            let value = match segment.delta {
+                Some(delta) => T::cast(value + delta),
+                None => segment.target,
+            };
+
+            (value, None)
+        } else {
+            let index = state.segment;
+
+            state.segment += 1;
+            state.elapsed = 0;
+
+            let event = if index == last {
+                Event::Complete
+            } else {
+                Event::End(index)
+            };
+
+            // snap to target to avoid accumulation of errors
+            (segment.target, Some(event))
+        };
+
+        state.value = Some(value);
+
+        (value, event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use typenum::{N16, P32, U2, U3};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn profile_f32() {
+        type R = Runner<f32, U3>;
+        use Interpolation::*;
+        // ramp to 10 during 5 s, soak 3 s, jump to 20 for 2 s
+        let param = Param::<f32, U3>::new(
+            0.0,
+            &[(5.0, 10.0, Ramp), (3.0, 10.0, Step), (2.0, 20.0, Step)],
+            1.0,
+        );
+        let mut state = State::default();
+
+        let mut outputs = [(0.0, None); 11];
+        for output in outputs.iter_mut() {
+            *output = R::apply(&param, &mut state, true);
+        }
+
+        assert_eq!(
+            outputs,
+            [
+                (2.0, None),
+                (4.0, None),
+                (6.0, None),
+                (8.0, None),
+                (10.0, Some(Event::End(0))),
+                (10.0, None),
+                (10.0, None),
+                (10.0, Some(Event::End(1))),
+                (20.0, None),
+                (20.0, Some(Event::Complete)),
+                (20.0, None),
+            ]
+        );
+        assert_eq!(state.segment(), 3);
+    }
+
+    #[test]
+    fn profile_pause() {
+        type R = Runner<f32, U2>;
+        use Interpolation::*;
+        let param = Param::<f32, U2>::new(10.0, &[(4.0, 30.0, Ramp), (4.0, 10.0, Ramp)], 1.0);
+        let mut state = State::default();
+
+        // paused before start
+        assert_eq!(R::apply(&param, &mut state, false), (10.0, None));
+        assert_eq!(R::apply(&param, &mut state, true), (15.0, None));
+        assert_eq!(R::apply(&param, &mut state, true), (20.0, None));
+        assert_eq!(R::apply(&param, &mut state, false), (20.0, None));
+        assert_eq!(R::apply(&param, &mut state, false), (20.0, None));
+        assert_eq!(state.elapsed(), 2);
+        assert_eq!(R::apply(&param, &mut state, true), (25.0, None));
+
+        // restart from the second segment
+        state.preset(1);
+        assert_eq!(R::apply(&param, &mut state, true), (25.0, None));
+        assert_eq!(state.segment(), 1);
+    }
+
+    #[test]
+    fn profile_fix() {
+        type T = Fix<P32, N16>;
+        type R = Runner<T, U2>;
+        use Interpolation::*;
+        // reflow: ramp 25 → 150 °C during 90 s, then ramp to 217 °C during 60 s
+        let param = Param::<T, U2>::new(25.0, &[(90.0, 150.0, Ramp), (60.0, 217.0, Ramp)], 0.1);
+        let mut state = State::default();
+
+        let mut output = (T::cast(0.0), None);
+        for step in 0..1500 {
+            output = R::apply(&param, &mut state, true);
+            if step == 899 {
+                assert_eq!(output, (T::cast(150.0), Some(Event::End(0))));
+            }
+            if step == 449 {
+                let value = f64::cast(output.0);
+                assert!((value - 87.5).abs() < 0.01, "{}", value);
+            }
+        }
+        assert_eq!(output, (T::cast(217.0), Some(Event::Complete)));
+    }
+}
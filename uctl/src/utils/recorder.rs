@@ -0,0 +1,286 @@
+/*!
+
+## Signal recorder
+
+This module implements the tools for host-side analysis of signals (requires `std` feature).
+
+The [`Recorder`] transducer passes the values as is and stores them into the vector,
+so it can be inserted at any point of transducers chain.
+
+The recorded signals can be exported in CSV format using [`write_csv`]
+for analysis and plotting in external tools (spreadsheets, gnuplot, pandas and etc.).
+
+The [`Reference`] transducer runs the reference model (usually the same transducers with `f64` types)
+side by side with the transducer under test (usually the fixed-point one)
+and records the deviations of outputs, so the precision of implementation can be verified.
+
+*/
+
+use crate::{Cast, Transducer};
+use std::{
+    io::{Result, Write},
+    marker::PhantomData,
+    vec::Vec,
+};
+use ufix::{Digits, Exponent, Fix, Radix};
+
+/**
+Signal recorder
+
+- `T` - values type
+
+The state is a vector of recorded values.
+*/
+#[derive(Debug)]
+pub struct Recorder<T>(PhantomData<T>);
+
+impl<T> Transducer for Recorder<T>
+where
+    T: Clone,
+{
+    type Input = T;
+    type Output = T;
+    type Param = ();
+    type State = Vec<T>;
+
+    fn apply(_param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        state.push(value.clone());
+        value
+    }
+}
+
+/**
+Reference model comparator
+
+- `X` - the transducer under test
+- `M` - the reference model (usually `f64`-based)
+
+The input is passed to both transducers, the output of transducer under test is passed through
+and the deviation from the output of reference model is recorded.
+
+The parameter is a tuple of parameters of the transducer under test and the reference model.
+*/
+#[derive(Debug)]
+pub struct Reference<X, M>(PhantomData<(X, M)>);
+
+/**
+Reference model comparator state
+
+- `S` - the state of transducer under test
+- `R` - the state of reference model
+*/
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceState<S, R> {
+    /// The state of transducer under test
+    pub state: S,
+    /// The state of reference model
+    pub model: R,
+    /// The recorded deviations
+    errors: Vec<f64>,
+}
+
+impl<S, R> ReferenceState<S, R> {
+    /**
+    Initialize reference model comparator state
+
+    - `state`: The state of transducer under test
+    - `model`: The state of reference model
+     */
+    pub fn new(state: S, model: R) -> Self {
+        Self {
+            state,
+            model,
+            errors: Vec::new(),
+        }
+    }
+
+    /// The recorded deviations of outputs from reference model
+    pub fn errors(&self) -> &[f64] {
+        &self.errors
+    }
+
+    /// The maximum absolute deviation
+    pub fn max_error(&self) -> f64 {
+        self.errors
+            .iter()
+            .fold(0.0, |max, error| max.max(error.abs()))
+    }
+
+    /// The root mean square of deviations
+    pub fn rms_error(&self) -> f64 {
+        if self.errors.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self.errors.iter().map(|error| error * error).sum();
+        (sum / self.errors.len() as f64).sqrt()
+    }
+}
+
+impl<X, M> Transducer for Reference<X, M>
+where
+    X: Transducer,
+    M: Transducer,
+    X::Input: Copy,
+    X::Output: Copy,
+    M::Input: Cast<X::Input>,
+    f64: Cast<X::Output> + Cast<M::Output>,
+{
+    type Input = X::Input;
+    type Output = X::Output;
+    type Param = (X::Param, M::Param);
+    type State = ReferenceState<X::State, M::State>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let output = X::apply(&param.0, &mut state.state, value);
+        let reference = M::apply(&param.1, &mut state.model, M::Input::cast(value));
+        state.errors.push(f64::cast(output) - f64::cast(reference));
+        output
+    }
+}
+
+/**
+The value which can be written as CSV row
+
+The scalar values is written as single cell, the tuples is written as several cells.
+*/
+pub trait Row {
+    /// Write value as comma separated cells
+    fn write_cells<W: Write>(&self, writer: &mut W) -> Result<()>;
+}
+
+macro_rules! row_impl {
+    ($($type: ty),*) => {
+        $(
+            impl Row for $type {
+                fn write_cells<W: Write>(&self, writer: &mut W) -> Result<()> {
+                    write!(writer, "{}", self)
+                }
+            }
+        )*
+    };
+}
+
+row_impl!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool);
+
+impl<R, B, E> Row for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Self: Copy,
+    f64: Cast<Self>,
+{
+    fn write_cells<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write!(writer, "{}", f64::cast(*self))
+    }
+}
+
+macro_rules! row_tuple {
+    ($type0: ident => $field0: tt $(, $type: ident => $field: tt)*) => {
+        impl<$type0 $(, $type)*> Row for ($type0, $($type,)*)
+        where
+            $type0: Row,
+            $($type: Row),*
+        {
+            fn write_cells<W: Write>(&self, writer: &mut W) -> Result<()> {
+                self.$field0.write_cells(writer)?;
+                $(
+                    writer.write_all(b",")?;
+                    self.$field.write_cells(writer)?;
+                )*
+                Ok(())
+            }
+        }
+    };
+}
+
+row_tuple!(A => 0);
+row_tuple!(A => 0, B => 1);
+row_tuple!(A => 0, B => 1, C => 2);
+row_tuple!(A => 0, B => 1, C => 2, D => 3);
+row_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+row_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+row_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+row_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+/**
+Write the recorded values in CSV format
+
+- `header`: The names of columns (the header row will be omitted when empty)
+- `rows`: The recorded values
+ */
+pub fn write_csv<W, T>(mut writer: W, header: &[&str], rows: &[T]) -> Result<()>
+where
+    W: Write,
+    T: Row,
+{
+    if !header.is_empty() {
+        writeln!(writer, "{}", header.join(","))?;
+    }
+
+    for row in rows {
+        row.write_cells(&mut writer)?;
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ema;
+    use typenum::{N8, P16};
+    use ufix::bin::Fix;
+
+    #[test]
+    fn recorder_csv() {
+        type T = Fix<P16, N8>;
+        type F = (Recorder<T>, ema::Filter<T, T, T>, Recorder<T>);
+
+        let param = ((), ema::Param::from_alpha(T::cast(0.5)), ());
+        let mut state = (Vec::new(), ema::State::new(T::cast(0.0)), Vec::new());
+
+        for x in &[1.0, 1.0, 0.5] {
+            F::apply(&param, &mut state, T::cast(*x));
+        }
+
+        let rows: Vec<_> = state
+            .0
+            .iter()
+            .cloned()
+            .zip(state.2.iter().cloned())
+            .collect();
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &["x", "y"], &rows).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "x,y\n1,0.5\n1,0.75\n0.5,0.625\n"
+        );
+    }
+
+    #[test]
+    fn reference_model() {
+        type T = Fix<P16, N8>;
+        type F = Reference<ema::Filter<T, T, T>, ema::Filter<f64, f64, f64>>;
+
+        let param = (
+            ema::Param::from_alpha(T::cast(0.3)),
+            ema::Param::from_alpha(0.3),
+        );
+        let mut state = ReferenceState::new(ema::State::new(T::cast(0.0)), ema::State::new(0.0));
+
+        for n in 0..100 {
+            let x = if n % 10 < 5 { 1.0 } else { -1.0 };
+            F::apply(&param, &mut state, T::cast(x));
+        }
+
+        assert_eq!(state.errors().len(), 100);
+        // the error is within few LSB (the alpha is rounded too)
+        assert!(state.max_error() < 8.0 / 256.0);
+        assert!(state.rms_error() <= state.max_error());
+        assert!(state.rms_error() > 0.0);
+    }
+}
@@ -4,7 +4,10 @@ Simple scaler for scalar values
 
 */
 
-use crate::{Cast, Transducer};
+use crate::{
+    Bounds, Cast, Core, CycleCost, Interpolate, NoiseBudget, OpCost, RangeOverflow, Transducer,
+    ValueBounds, ValueResolution, Wire, WorstCase,
+};
 use core::{
     marker::PhantomData,
     ops::{Add, Div, Mul, RangeInclusive, Sub},
@@ -26,6 +29,7 @@ offset = y0 - x0 * factor;
 
 y = x * factor + offset;
 */
+#[derive(Debug, Clone, Copy)]
 pub struct Param<F, O> {
     factor: F,
     offset: O,
@@ -53,6 +57,46 @@ impl<F, O> Param<F, O> {
 
         Self { factor, offset }
     }
+
+    /**
+    Create scaler parameters from factor and offset
+
+    _y = x * factor + offset_
+     */
+    pub fn from_factor(factor: F, offset: O) -> Self {
+        Self { factor, offset }
+    }
+}
+
+impl<F, O> Wire for Param<F, O>
+where
+    F: Copy + Wire,
+    O: Copy + Wire,
+{
+    const SIZE: usize = F::SIZE + O::SIZE;
+
+    fn write_to(&self, buf: &mut [u8]) {
+        (self.factor, self.offset).write_to(buf);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        let (factor, offset) = Wire::read_from(buf);
+        Self { factor, offset }
+    }
+}
+
+impl<F, O, K> Interpolate<K> for Param<F, O>
+where
+    F: Interpolate<K>,
+    O: Interpolate<K>,
+    K: Copy,
+{
+    fn interpolate(&self, to: &Self, factor: K) -> Self {
+        Self {
+            factor: self.factor.interpolate(&to.factor, factor),
+            offset: self.offset.interpolate(&to.offset, factor),
+        }
+    }
 }
 
 /** Scaler state
@@ -83,6 +127,53 @@ where
     }
 }
 
+impl<I, O, F> CycleCost for Scaler<I, O, F>
+where
+    O: OpCost,
+    F: OpCost,
+{
+    fn cycles(core: Core) -> u32 {
+        F::mul(core) + O::add(core)
+    }
+}
+
+/**
+Worst-case output range of scaler
+
+- `input`: The range of input values
+- `factor`: The scaling factor
+- `offset`: The offset of output
+*/
+pub const fn bounds(input: Bounds, factor: f64, offset: f64) -> Bounds {
+    input.scale(factor).offset(offset)
+}
+
+impl<I, O, F> WorstCase for Scaler<I, O, F>
+where
+    Self: Transducer<Param = Param<F, O>, Output = O>,
+    O: Copy + ValueBounds,
+    F: Copy,
+    f64: Cast<F> + Cast<O>,
+{
+    fn output_bounds(param: &Self::Param, input: Bounds) -> Result<Bounds, RangeOverflow> {
+        bounds(input, f64::cast(param.factor), f64::cast(param.offset)).check::<O>()
+    }
+}
+
+/// The output is rounded once
+impl<I, O, F> NoiseBudget for Scaler<I, O, F>
+where
+    Self: Transducer<Param = Param<F, O>, Output = O>,
+    O: ValueResolution,
+    F: Copy,
+    f64: Cast<F>,
+{
+    fn output_noise(param: &Self::Param, input: f64) -> f64 {
+        let factor = f64::cast(param.factor);
+        factor * factor * input + O::NOISE
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,305 @@
+/*!
+
+## Interleaving scheduler
+
+This module implements the scheduler which spreads the execution of several independent
+control loops across the ticks of interrupt in round-robin manner.
+
+The cycle of scheduler consists of _M_ ticks. The loop with index _i_ runs on the tick _i mod M_,
+so each loop runs once per _M_ ticks and the work per tick is reduced about _M_ times.
+
+Because each loop runs only once per cycle the period of loop is _M_ times longer than
+the period of interrupt, so the parameters of loops should be initialized using the
+[loop period](Scheduler::period) instead of period of interrupt.
+
+The [`Interleaved`] transducer applies the scheduler to the array of loops.
+The loops which isn't scheduled on the current tick holds the last output.
+
+*/
+
+use crate::{snapshot_fields, Cast, Core, CycleCost, ResetState, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+use typenum::{NonZero, Unsigned};
+
+/**
+Round-robin scheduler
+
+- `M` - the number of ticks in cycle
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scheduler<M> {
+    /// The actual tick in cycle
+    tick: usize,
+    _phantom: PhantomData<M>,
+}
+
+impl<M> Scheduler<M>
+where
+    M: Unsigned + NonZero,
+{
+    /// Create scheduler
+    pub const fn new() -> Self {
+        Self {
+            tick: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The number of ticks in cycle
+    pub fn ticks() -> usize {
+        M::to_usize()
+    }
+
+    /**
+    The period of scheduled loop
+
+    - `period`: The period of ticks (interrupt)
+
+    _P = M * period_
+     */
+    pub fn period<T>(period: T) -> T
+    where
+        T: Cast<f64>,
+        f64: Cast<T>,
+    {
+        T::cast(f64::cast(period) * M::to_usize() as f64)
+    }
+
+    /// The actual tick in cycle
+    pub fn tick(&self) -> usize {
+        self.tick
+    }
+
+    /// Check when the loop with given index should run on actual tick
+    pub fn is_due(&self, index: usize) -> bool {
+        index % M::to_usize() == self.tick
+    }
+
+    /// Go to the next tick
+    pub fn advance(&mut self) {
+        self.tick += 1;
+        if self.tick == M::to_usize() {
+            self.tick = 0;
+        }
+    }
+}
+
+impl<M> ResetState for Scheduler<M> {
+    type Value = ();
+
+    fn reset(&mut self) {
+        self.tick = 0;
+    }
+
+    fn preset(&mut self, _value: Self::Value) {
+        self.reset();
+    }
+}
+
+snapshot_fields! {
+    Scheduler<M> {
+        tick: usize,
+    }
+}
+
+/**
+Interleaved loops state
+
+- `S` - loop state type
+- `O` - loop output type
+- `N` - the number of loops
+- `M` - the number of ticks in cycle
+*/
+#[derive(Debug, Clone, Default)]
+pub struct State<S, O, N, M>
+where
+    N: ArrayLength<S> + ArrayLength<O>,
+{
+    /// The scheduler
+    scheduler: Scheduler<M>,
+    /// The states of loops
+    states: GenericArray<S, N>,
+    /// The last outputs of loops
+    outputs: GenericArray<O, N>,
+}
+
+impl<S, O, N, M> State<S, O, N, M>
+where
+    N: ArrayLength<S> + ArrayLength<O>,
+    M: Unsigned + NonZero,
+{
+    /**
+    Initialize interleaved loops state
+
+    - `states`: The states of loops
+     */
+    pub fn new(states: GenericArray<S, N>) -> Self
+    where
+        O: Default,
+    {
+        Self {
+            scheduler: Scheduler::new(),
+            states,
+            outputs: GenericArray::default(),
+        }
+    }
+
+    /// The scheduler
+    pub fn scheduler(&self) -> &Scheduler<M> {
+        &self.scheduler
+    }
+
+    /// The states of loops
+    pub fn states(&self) -> &GenericArray<S, N> {
+        &self.states
+    }
+
+    /// Mutable states of loops
+    pub fn states_mut(&mut self) -> &mut GenericArray<S, N> {
+        &mut self.states
+    }
+}
+
+/// The preset of interleaved loops presets all loops
+impl<S, O, N, M> ResetState for State<S, O, N, M>
+where
+    S: ResetState,
+    S::Value: Clone,
+    O: Default,
+    N: ArrayLength<S> + ArrayLength<O>,
+{
+    type Value = S::Value;
+
+    fn reset(&mut self) {
+        self.scheduler.reset();
+        self.states.reset();
+        self.outputs = GenericArray::default();
+    }
+
+    fn preset(&mut self, value: Self::Value) {
+        self.scheduler.reset();
+        self.states.preset(value);
+        self.outputs = GenericArray::default();
+    }
+}
+
+snapshot_fields! {
+    State<S, O, N, M> where [N: ArrayLength<S> + ArrayLength<O>] {
+        scheduler: Scheduler<M>,
+        states: GenericArray<S, N>,
+        outputs: GenericArray<O, N>,
+    }
+}
+
+/**
+Interleaved loops
+
+- `T` - loop transducer
+- `N` - the number of loops
+- `M` - the number of ticks in cycle
+
+The input is an array of loop inputs. The output is an array of loop outputs.
+Only the loops which is due on actual tick consumes the input.
+*/
+#[derive(Debug)]
+pub struct Interleaved<T, N, M>(PhantomData<(T, N, M)>);
+
+impl<T, N, M> Transducer for Interleaved<T, N, M>
+where
+    T: Transducer,
+    T::Output: Clone,
+    N: ArrayLength<T::Input>
+        + ArrayLength<T::Output>
+        + ArrayLength<T::Param>
+        + ArrayLength<T::State>,
+    M: Unsigned + NonZero,
+{
+    type Input = GenericArray<T::Input, N>;
+    type Output = GenericArray<T::Output, N>;
+    type Param = GenericArray<T::Param, N>;
+    type State = State<T::State, T::Output, N, M>;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let scheduler = &state.scheduler;
+
+        for (index, (((param, loop_state), output), value)) in param
+            .iter()
+            .zip(state.states.iter_mut())
+            .zip(state.outputs.iter_mut())
+            .zip(value)
+            .enumerate()
+        {
+            if scheduler.is_due(index) {
+                *output = T::apply(param, loop_state, value);
+            }
+        }
+
+        state.scheduler.advance();
+        state.outputs.clone()
+    }
+}
+
+/// The cost of the tick with maximum number of scheduled loops
+impl<T, N, M> CycleCost for Interleaved<T, N, M>
+where
+    T: CycleCost,
+    N: Unsigned,
+    M: Unsigned + NonZero,
+{
+    fn cycles(core: Core) -> u32 {
+        let loops = N::to_u32().div_ceil(M::to_u32());
+        loops * T::cycles(core)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ema;
+    use typenum::{U2, U3, U4};
+
+    #[test]
+    fn scheduler_round_robin() {
+        let mut scheduler = Scheduler::<U3>::new();
+
+        assert_eq!(Scheduler::<U3>::ticks(), 3);
+        assert_eq!(Scheduler::<U3>::period(0.25), 0.75);
+
+        assert!(scheduler.is_due(0) && scheduler.is_due(3) && !scheduler.is_due(1));
+        scheduler.advance();
+        assert!(scheduler.is_due(1) && scheduler.is_due(4));
+        scheduler.advance();
+        assert!(scheduler.is_due(2));
+        scheduler.advance();
+        assert_eq!(scheduler.tick(), 0);
+    }
+
+    #[test]
+    fn interleaved_ema() {
+        type E = ema::Filter<f32, f32, f32>;
+        type I = Interleaved<E, U4, U2>;
+
+        let param = GenericArray::from([ema::Param::from_alpha(0.5); 4]);
+        let mut state = State::<_, _, U4, U2>::new(GenericArray::from([ema::State::new(0.0); 4]));
+        let input = GenericArray::from([2.0, 4.0, 6.0, 8.0]);
+
+        // loops 0 and 2
+        assert_eq!(
+            I::apply(&param, &mut state, input),
+            GenericArray::from([1.0, 0.0, 3.0, 0.0])
+        );
+        // loops 1 and 3
+        assert_eq!(
+            I::apply(&param, &mut state, input),
+            GenericArray::from([1.0, 2.0, 3.0, 4.0])
+        );
+        assert_eq!(
+            I::apply(&param, &mut state, input),
+            GenericArray::from([1.5, 2.0, 4.5, 4.0])
+        );
+
+        state.reset();
+        assert_eq!(state.scheduler().tick(), 0);
+    }
+}
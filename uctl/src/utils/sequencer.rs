@@ -0,0 +1,487 @@
+/*!
+
+## Batch sequencer
+
+This module implements the simple sequencer for small batch-control applications
+(like filling, heating and draining of tank).
+
+Each step of sequence has:
+
+- an action, which is an output value of sequencer during the step
+  (like the set of valves states or the setpoint of regulator)
+- a completion condition on the input signal (or on the elapsed time)
+- an optional timeout, the sequence is stopped with fault when the step isn't completed in time
+
+The interlock flag permits the running of sequence. When the interlock trips
+the sequencer holds the actual step with the safe output until the interlock is restored.
+
+The sequencer outputs the [`Event`]s on completion, timeout and interlock, which can be put into
+the [event queue](crate::events::EventQueue) or be mapped to the commands of
+[mode state machine](crate::mode), for example the sequencer can be used as the _Run_ chain
+which is restarted each time the machine is started.
+
+*/
+
+use crate::{snapshot_fields, Cast, ResetState, Transducer};
+use core::marker::PhantomData;
+use generic_array::{ArrayLength, GenericArray};
+
+/**
+Completion condition of step
+
+- `X` - signal type
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completion<X> {
+    /// The signal rises above the value
+    Above(X),
+    /// The signal falls below the value
+    Below(X),
+    /// The timeout is elapsed (timeout is a duration of step)
+    Elapsed,
+}
+
+/// Sequencer event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The step with given index is completed
+    Done(usize),
+    /// The step with given index is timed out (the sequence is stopped)
+    Timeout(usize),
+    /// The interlock is tripped in step with given index
+    Interlock(usize),
+    /// The last step is completed
+    Complete,
+}
+
+/**
+Sequence step
+
+- `T` - output type
+- `X` - signal type
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Step<T, X> {
+    /// The action
+    output: T,
+    /// The completion condition
+    completion: Completion<X>,
+    /// The timeout in steps
+    timeout: Option<u32>,
+}
+
+/**
+Batch sequencer parameters
+
+- `T` - output type
+- `X` - signal type
+- `N` - the number of steps
+*/
+#[derive(Debug, Clone)]
+pub struct Param<T, X, N>
+where
+    N: ArrayLength<Step<T, X>>,
+{
+    /// The safe output
+    safe: T,
+    /// The steps
+    steps: GenericArray<Step<T, X>, N>,
+}
+
+impl<T, X, N> Param<T, X, N>
+where
+    N: ArrayLength<Step<T, X>>,
+{
+    /**
+    Init batch sequencer parameters
+
+    - `safe`: The output when sequence isn't running
+    - `steps`: The steps (action, completion, timeout (s)) (the length should be `N`)
+    - `period`: The sampling period (s)
+
+    The step with [`Completion::Elapsed`] condition requires timeout.
+     */
+    pub fn new<V>(safe: T, steps: &[(T, Completion<V>, Option<V>)], period: V) -> Self
+    where
+        T: Copy,
+        V: Copy,
+        X: Cast<f64>,
+        f64: Cast<V>,
+    {
+        assert!(steps.len() == N::USIZE && N::USIZE > 0);
+
+        let period = f64::cast(period);
+
+        Self {
+            safe,
+            steps: steps
+                .iter()
+                .map(|(output, completion, timeout)| {
+                    let timeout = timeout.map(|timeout| (f64::cast(timeout) / period + 0.5) as u32);
+
+                    let completion = match completion {
+                        Completion::Above(value) => Completion::Above(X::cast(f64::cast(*value))),
+                        Completion::Below(value) => Completion::Below(X::cast(f64::cast(*value))),
+                        Completion::Elapsed => {
+                            assert!(timeout.is_some());
+                            Completion::Elapsed
+                        }
+                    };
+
+                    Step {
+                        output: *output,
+                        completion,
+                        timeout,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of steps
+    pub fn steps() -> usize {
+        N::USIZE
+    }
+}
+
+/**
+Batch sequencer state
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    /// The index of actual step
+    step: usize,
+    /// The number of steps since start of step
+    elapsed: u32,
+    /// The sequence is held by interlock
+    held: bool,
+    /// The sequence is stopped by timeout
+    fault: bool,
+}
+
+impl State {
+    /// The index of actual step (the number of steps when complete)
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// The number of steps since start of actual step
+    pub fn elapsed(&self) -> u32 {
+        self.elapsed
+    }
+
+    /// The sequence is held by interlock
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    /// The sequence is stopped by timeout
+    pub fn is_fault(&self) -> bool {
+        self.fault
+    }
+}
+
+/// The preset value is an index of step to start from (the fault is cleared)
+impl ResetState for State {
+    type Value = usize;
+
+    fn reset(&mut self) {
+        self.preset(0);
+    }
+
+    fn preset(&mut self, step: Self::Value) {
+        *self = Self {
+            step,
+            ..Self::default()
+        };
+    }
+}
+
+snapshot_fields! {
+    State {
+        step: usize,
+        elapsed: u32,
+        held: bool,
+        fault: bool,
+    }
+}
+
+/**
+Batch sequencer
+
+- `T` - output type
+- `X` - signal type
+- `N` - the number of steps
+
+The input is a tuple of interlock flag (`true` permits running) and signal.
+The output is a tuple of action of actual step (or safe output) and optional event.
+*/
+#[derive(Debug)]
+pub struct Sequencer<T, X, N>(PhantomData<(T, X, N)>);
+
+impl<T, X, N> Transducer for Sequencer<T, X, N>
+where
+    T: Copy,
+    X: Copy + PartialOrd,
+    N: ArrayLength<Step<T, X>>,
+{
+    type Input = (bool, X);
+    type Output = (T, Option<Event>);
+    type Param = Param<T, X, N>;
+    type State = State;
+
+    fn apply(
+        param: &Self::Param,
+        state: &mut Self::State,
+        (permit, signal): Self::Input,
+    ) -> Self::Output {
+        let index = state.step;
+
+        if index >= N::USIZE || state.fault {
+            return (param.safe, None);
+        }
+
+        if !permit {
+            let event = if state.held {
+                None
+            } else {
+                state.held = true;
+                Some(Event::Interlock(index))
+            };
+            return (param.safe, event);
+        }
+
+        state.held = false;
+
+        let step = &param.steps[index];
+
+        state.elapsed = state.elapsed.saturating_add(1);
+
+        let timeout = step.timeout.is_some_and(|timeout| state.elapsed >= timeout);
+
+        let done = match step.completion {
+            Completion::Above(value) => signal > value,
+            Completion::Below(value) => signal < value,
+            Completion::Elapsed => timeout,
+        };
+
+        if done {
+            state.step += 1;
+            state.elapsed = 0;
+
+            return if state.step < N::USIZE {
+                (param.steps[state.step].output, Some(Event::Done(index)))
+            } else {
+                (param.safe, Some(Event::Complete))
+            };
+        }
+
+        if timeout {
+            state.fault = true;
+            return (param.safe, Some(Event::Timeout(index)));
+        }
+
+        (step.output, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        events::EventQueue,
+        mode::{Command, Machine, Mode},
+        FnTransducer,
+    };
+    use typenum::{N16, P32, U3, U8};
+    use ufix::bin::Fix;
+
+    /// Valves: inlet, heater, outlet
+    type Valves = (bool, bool, bool);
+
+    const IDLE: Valves = (false, false, false);
+    const FILL: Valves = (true, false, false);
+    const HEAT: Valves = (false, true, false);
+    const DRAIN: Valves = (false, false, true);
+
+    type S = Sequencer<Valves, f32, U3>;
+
+    /// Tank: fill to level 0.8 in 10 s, heat during 3 s, drain to level 0.1 in 10 s
+    fn param() -> Param<Valves, f32, U3> {
+        Param::new(
+            IDLE,
+            &[
+                (FILL, Completion::Above(0.8), Some(10.0)),
+                (HEAT, Completion::Elapsed, Some(3.0)),
+                (DRAIN, Completion::Below(0.1), Some(10.0)),
+            ],
+            1.0,
+        )
+    }
+
+    #[test]
+    fn sequencer_batch() {
+        let param = param();
+        let mut state = State::default();
+        let mut queue = EventQueue::<u32, Event, U8>::default();
+
+        let mut level = 0.0;
+        let mut outputs = [IDLE; 12];
+        for (tick, output) in outputs.iter_mut().enumerate() {
+            let (valves, event) = S::apply(&param, &mut state, (true, level));
+            if let Some(event) = event {
+                queue.push(tick as u32, event);
+            }
+            // the tank model
+            if valves.0 {
+                level += 0.3;
+            }
+            if valves.2 {
+                level -= 0.3;
+            }
+            *output = valves;
+        }
+
+        assert_eq!(
+            outputs,
+            [FILL, FILL, FILL, HEAT, HEAT, HEAT, DRAIN, DRAIN, DRAIN, IDLE, IDLE, IDLE]
+        );
+
+        let mut events = [None; 5];
+        for event in events.iter_mut() {
+            *event = queue.pop().map(|event| (event.tick, event.event));
+        }
+        assert_eq!(
+            events,
+            [
+                Some((3, Event::Done(0))),
+                Some((6, Event::Done(1))),
+                Some((9, Event::Complete)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn sequencer_timeout() {
+        let param = param();
+        let mut state = State::default();
+
+        // the inlet is blocked
+        for _ in 0..9 {
+            assert_eq!(S::apply(&param, &mut state, (true, 0.0)), (FILL, None));
+        }
+        assert_eq!(
+            S::apply(&param, &mut state, (true, 0.0)),
+            (IDLE, Some(Event::Timeout(0)))
+        );
+        assert!(state.is_fault());
+        assert_eq!(S::apply(&param, &mut state, (true, 1.0)), (IDLE, None));
+
+        state.reset();
+        assert_eq!(
+            S::apply(&param, &mut state, (true, 1.0)),
+            (HEAT, Some(Event::Done(0)))
+        );
+    }
+
+    #[test]
+    fn sequencer_interlock() {
+        let param = param();
+        let mut state = State::default();
+
+        state.preset(1);
+        assert_eq!(S::apply(&param, &mut state, (true, 0.8)), (HEAT, None));
+        assert_eq!(
+            S::apply(&param, &mut state, (false, 0.8)),
+            (IDLE, Some(Event::Interlock(1)))
+        );
+        assert_eq!(S::apply(&param, &mut state, (false, 0.8)), (IDLE, None));
+        assert!(state.is_held());
+        // the time of step is counted only while running
+        assert_eq!(S::apply(&param, &mut state, (true, 0.8)), (HEAT, None));
+        assert_eq!(
+            S::apply(&param, &mut state, (true, 0.8)),
+            (DRAIN, Some(Event::Done(1)))
+        );
+    }
+
+    fn idle(_: (bool, f32)) -> (Valves, Option<Event>) {
+        (IDLE, None)
+    }
+
+    #[test]
+    fn sequencer_mode() {
+        type Z = FnTransducer<(bool, f32), (Valves, Option<Event>)>;
+        type M = Machine<(), Z, Z, S, Z>;
+
+        let param = crate::mode::Param {
+            guards: (),
+            init: idle as fn(_) -> _,
+            standby: idle as fn(_) -> _,
+            run: param(),
+            fault: idle as fn(_) -> _,
+        };
+        let mut state = crate::mode::State::default();
+
+        assert_eq!(
+            M::apply(&param, &mut state, (None, (true, 0.0))),
+            (Mode::Standby, (IDLE, None))
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Start), (true, 0.0))),
+            (Mode::Run, (FILL, None))
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (None, (true, 0.9))),
+            (Mode::Run, (HEAT, Some(Event::Done(0))))
+        );
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Stop), (true, 0.9))),
+            (Mode::Standby, (IDLE, None))
+        );
+        // the sequence is restarted
+        assert_eq!(
+            M::apply(&param, &mut state, (Some(Command::Start), (true, 0.0))),
+            (Mode::Run, (FILL, None))
+        );
+    }
+
+    #[test]
+    fn sequencer_fix() {
+        type X = Fix<P32, N16>;
+        type S = Sequencer<u8, X, U3>;
+        let param = Param::<u8, X, U3>::new(
+            0,
+            &[
+                (1, Completion::Above(25.5), None),
+                (2, Completion::Below(-3.25), None),
+                (3, Completion::Elapsed, Some(0.02)),
+            ],
+            0.01,
+        );
+        let mut state = State::default();
+
+        assert_eq!(
+            S::apply(&param, &mut state, (true, X::cast(25.5))),
+            (1, None)
+        );
+        assert_eq!(
+            S::apply(&param, &mut state, (true, X::cast(25.75))),
+            (2, Some(Event::Done(0)))
+        );
+        assert_eq!(
+            S::apply(&param, &mut state, (true, X::cast(-3.5))),
+            (3, Some(Event::Done(1)))
+        );
+        assert_eq!(
+            S::apply(&param, &mut state, (true, X::cast(0.0))),
+            (3, None)
+        );
+        assert_eq!(
+            S::apply(&param, &mut state, (true, X::cast(0.0))),
+            (0, Some(Event::Complete))
+        );
+    }
+}
@@ -0,0 +1,75 @@
+/*!
+
+## Double-buffered block processing
+
+The [`BlockProcessor`] streams the blocks of samples between the peripherals (like ADC or DAC)
+and the transducers using DMA with ping-pong buffers.
+
+The processor is implemented in the `usync` crate, which contains all `unsafe` code of library.
+The [`ProcessBlock`] applies the transducer to the completed block in place
+and reports the [`Overrun`] when DMA completes the next transfer while block is processed.
+
+*/
+
+use crate::Transducer;
+use generic_array::ArrayLength;
+
+pub use usync::stream::{Aligned, BlockHandle, BlockProcessor, DmaHandle, Half, Overrun};
+
+/**
+Processing of blocks by transducers
+
+- `T` - values type
+*/
+pub trait ProcessBlock<T> {
+    /**
+    Apply transducer to the completed block in place
+
+    - `param`: The parameters of transducer
+    - `state`: The state of transducer
+
+    Returns `Ok(false)` when there is no completed block and `Ok(true)` when it is processed.
+    Returns [`Overrun`] error when the values may be corrupted by DMA.
+     */
+    fn process<X>(&mut self, param: &X::Param, state: &mut X::State) -> Result<bool, Overrun>
+    where
+        T: Copy,
+        X: Transducer<Input = T, Output = T>;
+}
+
+impl<T, N> ProcessBlock<T> for BlockHandle<'_, T, N>
+where
+    N: ArrayLength<T>,
+{
+    fn process<X>(&mut self, param: &X::Param, state: &mut X::State) -> Result<bool, Overrun>
+    where
+        T: Copy,
+        X: Transducer<Input = T, Output = T>,
+    {
+        self.map_block(|value| X::apply(param, state, value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ema;
+    use typenum::U4;
+
+    #[test]
+    fn process_block() {
+        type F = ema::Filter<f32, f32, f32>;
+        let param = ema::Param::from_alpha(0.5);
+        let mut state = ema::State::default();
+        let mut proc = BlockProcessor::<f32, U4>::default();
+        let (mut dma, mut block) = proc.split();
+
+        assert_eq!(block.process::<F>(&param, &mut state), Ok(false));
+
+        dma.with_buffer(|values| values.fill(1.0)).unwrap();
+        assert!(dma.complete());
+        assert_eq!(block.process::<F>(&param, &mut state), Ok(true));
+        assert!(!block.is_pending());
+        assert_eq!(state.value(), 0.9375);
+    }
+}
@@ -0,0 +1,483 @@
+/*!
+
+## Signal tracing
+
+This module implements tracing of transducers chains for host-side simulations (requires `std` feature).
+
+Each stage of chain which should be inspected is wrapped by [`Traced`] transducer
+which logs the input and output values of stage with its name into the shared [`Tracer`] sink.
+The user advances the tick counter of tracer at each step of simulation.
+
+The collected trace can be exported in two formats:
+
+- CSV using [`Tracer::write_csv`]: the row per tick with columns named like _stage.in_ and _stage.out_
+  (the tuples produces several columns like _stage.in.0_, _stage.in.1_ and so on),
+  the names with special characters is quoted as described in RFC 4180
+- NDJSON using [`Tracer::write_ndjson`]: the object per stage per tick like
+  `{"tick":0,"stage":"ema","input":[1],"output":[0.5]}`
+
+*/
+
+use crate::{Cast, Transducer};
+use std::{
+    cell::RefCell,
+    io::{Result, Write},
+    marker::PhantomData,
+    rc::Rc,
+    vec::Vec,
+};
+use ufix::{Digits, Exponent, Fix, Radix};
+
+/**
+The value which can be traced
+
+The scalar values is traced as single value, the tuples is traced as several values.
+*/
+pub trait Values {
+    /// Append the values to the vector
+    fn values(&self, values: &mut Vec<f64>);
+}
+
+macro_rules! values_impl {
+    ($($type: ty),*) => {
+        $(
+            impl Values for $type {
+                fn values(&self, values: &mut Vec<f64>) {
+                    values.push(*self as f64);
+                }
+            }
+        )*
+    };
+}
+
+values_impl!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+impl Values for bool {
+    fn values(&self, values: &mut Vec<f64>) {
+        values.push(if *self { 1.0 } else { 0.0 });
+    }
+}
+
+impl<R, B, E> Values for Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    Self: Copy,
+    f64: Cast<Self>,
+{
+    fn values(&self, values: &mut Vec<f64>) {
+        values.push(f64::cast(*self));
+    }
+}
+
+impl<T> Values for Option<T>
+where
+    T: Values,
+{
+    fn values(&self, values: &mut Vec<f64>) {
+        match self {
+            Some(value) => value.values(values),
+            None => values.push(f64::NAN),
+        }
+    }
+}
+
+impl Values for () {
+    fn values(&self, _values: &mut Vec<f64>) {}
+}
+
+macro_rules! values_tuple {
+    ($($type: ident => $field: tt),+) => {
+        impl<$($type),+> Values for ($($type,)+)
+        where
+            $($type: Values),+
+        {
+            fn values(&self, values: &mut Vec<f64>) {
+                $(self.$field.values(values);)+
+            }
+        }
+    };
+}
+
+values_tuple!(A => 0);
+values_tuple!(A => 0, B => 1);
+values_tuple!(A => 0, B => 1, C => 2);
+values_tuple!(A => 0, B => 1, C => 2, D => 3);
+values_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4);
+values_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+values_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+values_tuple!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+
+/// Traced values of stage
+#[derive(Debug, Clone)]
+struct Record {
+    tick: usize,
+    stage: &'static str,
+    input: Vec<f64>,
+    output: Vec<f64>,
+}
+
+#[derive(Debug, Default)]
+struct Records {
+    tick: usize,
+    records: Vec<Record>,
+}
+
+/**
+Trace sink
+
+The tracer can be cloned to share single sink between several stages.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Tracer(Rc<RefCell<Records>>);
+
+impl Tracer {
+    /// Create new tracer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance tick counter
+    pub fn tick(&self) {
+        self.0.borrow_mut().tick += 1;
+    }
+
+    /// Clear collected trace and reset tick counter
+    pub fn clear(&self) {
+        let mut records = self.0.borrow_mut();
+        records.tick = 0;
+        records.records.clear();
+    }
+
+    /// The number of traced records
+    pub fn len(&self) -> usize {
+        self.0.borrow().records.len()
+    }
+
+    /// Check when no records traced
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push(&self, stage: &'static str, input: Vec<f64>, output: Vec<f64>) {
+        let mut records = self.0.borrow_mut();
+        let tick = records.tick;
+        records.records.push(Record {
+            tick,
+            stage,
+            input,
+            output,
+        });
+    }
+
+    /**
+    Write trace in CSV format
+
+    The row per tick will be written. The columns is ordered as stages was traced first time.
+     */
+    pub fn write_csv<W>(&self, mut writer: W) -> Result<()>
+    where
+        W: Write,
+    {
+        let records = self.0.borrow();
+
+        // stage name with the number of input and output values
+        let mut columns: Vec<(&'static str, usize, usize)> = Vec::new();
+        for record in &records.records {
+            match columns
+                .iter_mut()
+                .find(|(stage, _, _)| *stage == record.stage)
+            {
+                Some((_, inputs, outputs)) => {
+                    *inputs = (*inputs).max(record.input.len());
+                    *outputs = (*outputs).max(record.output.len());
+                }
+                None => columns.push((record.stage, record.input.len(), record.output.len())),
+            }
+        }
+
+        write!(writer, "tick")?;
+        for (stage, inputs, outputs) in &columns {
+            write_names(&mut writer, stage, "in", *inputs)?;
+            write_names(&mut writer, stage, "out", *outputs)?;
+        }
+        writeln!(writer)?;
+
+        let mut rest = &records.records[..];
+        while let Some(first) = rest.first() {
+            let tick = first.tick;
+            let count = rest
+                .iter()
+                .position(|record| record.tick != tick)
+                .unwrap_or(rest.len());
+            let (current, next) = rest.split_at(count);
+            rest = next;
+
+            write!(writer, "{}", tick)?;
+            for (stage, inputs, outputs) in &columns {
+                let record = current.iter().find(|record| record.stage == *stage);
+                write_cells(&mut writer, record.map(|record| &record.input[..]), *inputs)?;
+                write_cells(
+                    &mut writer,
+                    record.map(|record| &record.output[..]),
+                    *outputs,
+                )?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+    Write trace in NDJSON format
+
+    The line per stage per tick will be written. The NaN values is written as `null`.
+    The stage names is escaped, so any names produces valid JSON.
+     */
+    pub fn write_ndjson<W>(&self, mut writer: W) -> Result<()>
+    where
+        W: Write,
+    {
+        for record in &self.0.borrow().records {
+            write!(writer, "{{\"tick\":{},\"stage\":", record.tick)?;
+            write_string(&mut writer, record.stage)?;
+            write!(writer, ",\"input\":")?;
+            write_array(&mut writer, &record.input)?;
+            write!(writer, ",\"output\":")?;
+            write_array(&mut writer, &record.output)?;
+            writeln!(writer, "}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_names<W: Write>(writer: &mut W, stage: &str, kind: &str, count: usize) -> Result<()> {
+    if count == 1 {
+        write!(writer, ",")?;
+        write_field(writer, &format!("{}.{}", stage, kind))
+    } else {
+        for index in 0..count {
+            write!(writer, ",")?;
+            write_field(writer, &format!("{}.{}.{}", stage, kind, index))?;
+        }
+        Ok(())
+    }
+}
+
+fn write_field<W: Write>(writer: &mut W, value: &str) -> Result<()> {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        // the quotes is doubled inside of quoted field
+        write!(writer, "\"{}\"", value.replace('"', "\"\""))
+    } else {
+        write!(writer, "{}", value)
+    }
+}
+
+fn write_cells<W: Write>(writer: &mut W, values: Option<&[f64]>, count: usize) -> Result<()> {
+    for index in 0..count {
+        match values.and_then(|values| values.get(index)) {
+            Some(value) if !value.is_nan() => write!(writer, ",{}", value)?,
+            _ => write!(writer, ",")?,
+        }
+    }
+    Ok(())
+}
+
+fn write_string<W: Write>(writer: &mut W, value: &str) -> Result<()> {
+    write!(writer, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    write!(writer, "\"")
+}
+
+fn write_array<W: Write>(writer: &mut W, values: &[f64]) -> Result<()> {
+    write!(writer, "[")?;
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(writer, ",")?;
+        }
+        if value.is_finite() {
+            write!(writer, "{}", value)?;
+        } else {
+            write!(writer, "null")?;
+        }
+    }
+    write!(writer, "]")
+}
+
+/**
+Traced stage parameters
+
+- `P` - parameters of wrapped transducer
+*/
+#[derive(Debug, Clone)]
+pub struct Param<P> {
+    /// Stage name
+    name: &'static str,
+    /// Trace sink
+    tracer: Tracer,
+    /// Wrapped transducer parameters
+    param: P,
+}
+
+impl<P> Param<P> {
+    /**
+    Init traced stage parameters
+
+    - `name`: The name of stage
+    - `tracer`: The trace sink
+    - `param`: The parameters of wrapped transducer
+     */
+    pub fn new(name: &'static str, tracer: &Tracer, param: P) -> Self {
+        Self {
+            name,
+            tracer: tracer.clone(),
+            param,
+        }
+    }
+}
+
+/**
+Traced stage
+
+- `T` - wrapped transducer
+
+The state is a state of wrapped transducer.
+*/
+#[derive(Debug)]
+pub struct Traced<T>(PhantomData<T>);
+
+impl<T> Transducer for Traced<T>
+where
+    T: Transducer,
+    T::Input: Values,
+    T::Output: Values,
+{
+    type Input = T::Input;
+    type Output = T::Output;
+    type Param = Param<T::Param>;
+    type State = T::State;
+
+    fn apply(param: &Self::Param, state: &mut Self::State, value: Self::Input) -> Self::Output {
+        let mut input = Vec::new();
+        value.values(&mut input);
+
+        let value = T::apply(&param.param, state, value);
+
+        let mut output = Vec::new();
+        value.values(&mut output);
+
+        param.tracer.push(param.name, input, output);
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{cic, ema};
+    use std::string::String;
+    use typenum::U1;
+
+    type C = Traced<cic::Decimator<i32, U1>>;
+    type E = Traced<ema::Filter<f32, f32, f32>>;
+    type S = (E, Traced<crate::FnTransducer<f32, (f32, bool)>>);
+
+    #[test]
+    fn trace_csv() {
+        let tracer = Tracer::new();
+
+        let param = (
+            Param::new("ema", &tracer, ema::Param::from_alpha(0.5)),
+            Param::new("cmp", &tracer, (|x| (x, x > 0.6)) as fn(f32) -> (f32, bool)),
+        );
+        let mut state = (ema::State::new(0.0), ());
+
+        for x in &[1.0, 1.0] {
+            S::apply(&param, &mut state, *x);
+            tracer.tick();
+        }
+
+        let mut csv = Vec::new();
+        tracer.write_csv(&mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "tick,ema.in,ema.out,cmp.in,cmp.out.0,cmp.out.1\n\
+             0,1,0.5,0.5,0.5,0\n\
+             1,1,0.75,0.75,0.75,1\n"
+        );
+    }
+
+    #[test]
+    fn trace_ndjson() {
+        let tracer = Tracer::new();
+
+        let param = Param::new("cic", &tracer, cic::Param::new(2));
+        let mut state = cic::State::default();
+
+        for x in &[1, 2] {
+            C::apply(&param, &mut state, *x);
+            tracer.tick();
+        }
+        assert_eq!(tracer.len(), 2);
+
+        let mut json = Vec::new();
+        tracer.write_ndjson(&mut json).unwrap();
+
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "{\"tick\":0,\"stage\":\"cic\",\"input\":[1],\"output\":[null]}\n\
+             {\"tick\":1,\"stage\":\"cic\",\"input\":[2],\"output\":[3]}\n"
+        );
+
+        tracer.clear();
+        assert!(tracer.is_empty());
+    }
+
+    #[test]
+    fn trace_ndjson_escape() {
+        let tracer = Tracer::new();
+
+        let param = Param::new("a\"b\\c\nd\u{1}", &tracer, cic::Param::new(1));
+        C::apply(&param, &mut cic::State::default(), 1);
+
+        let mut json = Vec::new();
+        tracer.write_ndjson(&mut json).unwrap();
+
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "{\"tick\":0,\"stage\":\"a\\\"b\\\\c\\nd\\u0001\",\"input\":[1],\"output\":[1]}\n"
+        );
+    }
+
+    #[test]
+    fn trace_csv_escape() {
+        let tracer = Tracer::new();
+
+        let param = Param::new("a,\"b\"\nc", &tracer, cic::Param::new(1));
+        C::apply(&param, &mut cic::State::default(), 1);
+        tracer.tick();
+
+        let mut csv = Vec::new();
+        tracer.write_csv(&mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "tick,\"a,\"\"b\"\"\nc.in\",\"a,\"\"b\"\"\nc.out\"\n0,1,1\n"
+        );
+    }
+}
@@ -190,3 +190,57 @@ pub mod iec {
     /** Unsigned fixed ×2<sup>80</sup> */
     pub type UYobi<B> = UFix<B, P80>;
 }
+
+/// Q-format (Q<sub>m.n</sub>) types
+///
+/// The _m_ is a number of integer bits including the sign bit and the _n_ is a number of fractional bits
+/// (the ARM convention), so the mantissa has _m + n_ bits. The `Q15` is same as `Q1.15` and has 16-bits mantissa.
+pub mod q {
+    use super::bin::Fix;
+    use typenum::{Add1, Diff, Negate, Sum, N15, N31, N63, N7, P1, P16, P32, P64, P8, U};
+
+    /// Signed Q<sub>m.n</sub> fixed-point type with `I` integer (including sign) and `F` fractional bits
+    pub type Q<I, F> = Fix<Sum<I, F>, Negate<F>>;
+
+    /** Signed fixed Q<sub>1.7</sub> (`q7_t` in CMSIS-DSP) */
+    pub type Q7 = Fix<P8, N7>;
+    /** Signed fixed Q<sub>1.15</sub> (`q15_t` in CMSIS-DSP) */
+    pub type Q15 = Fix<P16, N15>;
+    /** Signed fixed Q<sub>1.31</sub> (`q31_t` in CMSIS-DSP) */
+    pub type Q31 = Fix<P32, N31>;
+    /** Signed fixed Q<sub>1.63</sub> (`q63_t` in CMSIS-DSP) */
+    pub type Q63 = Fix<P64, N63>;
+
+    /// The type-level integer from constant (zero included)
+    #[doc(hidden)]
+    pub type __Int<const N: usize> = Diff<typenum::PInt<Add1<U<N>>>, P1>;
+
+    /// Extracts the number of integer (`part` = 0) or fractional (`part` = 1) bits from Q<sub>m.n</sub> notation
+    ///
+    /// The notation without integer bits (like `15`) is treated as Q<sub>1.n</sub>.
+    #[doc(hidden)]
+    pub const fn __bits(format: &str, part: usize) -> usize {
+        let format = format.as_bytes();
+        let mut bits = [0usize; 2];
+        let mut index = 0;
+        let mut dot = false;
+
+        while index < format.len() {
+            match format[index] {
+                digit @ b'0'..=b'9' => {
+                    let part = if dot { 1 } else { 0 };
+                    bits[part] = bits[part] * 10 + (digit - b'0') as usize;
+                }
+                b'.' if !dot => dot = true,
+                _ => panic!("Invalid Q-format notation"),
+            }
+            index += 1;
+        }
+
+        if !dot {
+            bits = [1, bits[0]];
+        }
+
+        bits[part]
+    }
+}
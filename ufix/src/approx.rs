@@ -0,0 +1,148 @@
+use crate::{Cast, Digits, Exponent, Fix, Radix};
+
+impl<R, B, E> Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+{
+    /// The value of least significant bit (digit) _R<sup>E</sup>_
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typenum::{N2, N8, P16, P4};
+    /// use ufix::{bin, dec};
+    ///
+    /// assert_eq!(bin::Fix::<P16, N8>::lsb(), 1.0 / 256.0);
+    /// assert_eq!(dec::Fix::<P4, N2>::lsb(), 0.01);
+    /// ```
+    pub fn lsb() -> f64 {
+        let radix = R::U64 as f64;
+        let mut lsb = 1.0;
+        for _ in 0..E::I32.unsigned_abs() {
+            lsb *= radix;
+        }
+        if E::I32 < 0 {
+            1.0 / lsb
+        } else {
+            lsb
+        }
+    }
+}
+
+/// The difference between values in the least significant bits of the first value type
+///
+/// The second value may be the fixed-point value of any type or the floating-point value.
+pub fn lsb_diff<R, B, E, V>(a: Fix<R, B, E>, b: V) -> f64
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    f64: Cast<Fix<R, B, E>> + Cast<V>,
+{
+    let diff = f64::cast(a) - f64::cast(b);
+    let diff = if diff < 0.0 { -diff } else { diff };
+    diff / Fix::<R, B, E>::lsb()
+}
+
+/// Compare values with tolerance in the least significant bits of the first value type
+///
+/// The second value may be the fixed-point value of any type or the floating-point value.
+///
+/// # Examples
+///
+/// ```
+/// use typenum::{N10, N16, N8, P16, P32};
+/// use ufix::{approx_eq, bin::Fix, Cast};
+///
+/// let a = Fix::<P32, N16>::cast(1.0) / Fix::<P16, N8>::cast(3.0); // Fix<P16, N8>
+///
+/// assert!(approx_eq(a, 1.0 / 3.0, 1));
+/// assert!(approx_eq(a, Fix::<P32, N10>::cast(0.333), 1));
+/// assert!(!approx_eq(a, 0.3, 1));
+/// ```
+pub fn approx_eq<R, B, E, V>(a: Fix<R, B, E>, b: V, tol_lsb: u32) -> bool
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+    f64: Cast<Fix<R, B, E>> + Cast<V>,
+{
+    lsb_diff(a, b) <= tol_lsb as f64
+}
+
+/// Assert that the fixed-point value is approximately equal to another value
+///
+/// The tolerance is given in the least significant bits of the first value type (1 by default).
+/// The second value may be the fixed-point value of any type or the floating-point value.
+///
+/// # Examples
+///
+/// ```
+/// use typenum::{N16, N32, P32, P64};
+/// use ufix::{assert_fix_eq, bin::Fix, Cast};
+///
+/// type T = Fix<P32, N16>;
+///
+/// let a = Fix::<P64, N32>::cast(1.0) / T::cast(3.0); // Fix<P32, N16>
+///
+/// assert_fix_eq!(a, 1.0 / 3.0);
+/// assert_fix_eq!(T::cast(a * T::cast(3.0)), 1.0, 2);
+/// ```
+#[macro_export]
+macro_rules! assert_fix_eq {
+    ($left:expr, $right:expr) => {
+        $crate::assert_fix_eq!($left, $right, 1)
+    };
+    ($left:expr, $right:expr, $tol_lsb:expr) => {
+        match ($left, $right) {
+            (left, right) => {
+                let tol_lsb: u32 = $tol_lsb;
+                if !$crate::approx_eq(left, right, tol_lsb) {
+                    panic!(
+                        "assertion failed: `left ≈ right` (within {} LSB)\n  left: `{}`\n right: `{}`\n  diff: `{}` LSB",
+                        tol_lsb,
+                        <f64 as $crate::Cast<_>>::cast(left),
+                        <f64 as $crate::Cast<_>>::cast(right),
+                        $crate::lsb_diff(left, right),
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{approx_eq, bin, dec, lsb_diff, Cast};
+    use typenum::{N12, N16, N3, N32, N8, P16, P2, P32, P64, P8};
+
+    #[test]
+    fn lsb() {
+        assert_eq!(bin::Fix::<P16, N16>::lsb(), 1.0 / 65536.0);
+        assert_eq!(bin::UFix::<P8, P2>::lsb(), 4.0);
+        assert_eq!(dec::Fix::<P8, N3>::lsb(), 0.001);
+    }
+
+    #[test]
+    fn approx() {
+        type T = bin::Fix<P32, N16>;
+
+        let a = bin::Fix::<P64, N32>::cast(2.0) / T::cast(3.0);
+        assert!(approx_eq(a, 2.0 / 3.0, 1));
+        assert!(approx_eq(a, 2.0f32 / 3.0, 1));
+        assert!(approx_eq(bin::Fix::<P16, N12>::cast(0.6667), a, 1));
+        assert!(!approx_eq(a, 0.667, 8));
+        assert!((lsb_diff(T::cast(1.0), 1.0 + 3.0 / 65536.0) - 3.0).abs() < 1e-9);
+
+        assert_fix_eq!(a, 2.0 / 3.0);
+        assert_fix_eq!(bin::Fix::<P16, N8>::cast(0.1), 0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "within 2 LSB")]
+    fn assert_fails() {
+        assert_fix_eq!(bin::Fix::<P16, N8>::cast(1.0), 1.0 + 3.0 / 256.0, 2);
+    }
+}
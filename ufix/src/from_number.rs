@@ -1,4 +1,4 @@
-use super::{Cast, Digits, Exponent, Fix, Mantissa, Radix};
+use super::{Cast, Digits, Exponent, Fix, FromPositive, Mantissa, Radix, UnsignedPow};
 
 macro_rules! from_num {
     ($TYPE: ty, $KIND: tt) => {
@@ -12,7 +12,7 @@ macro_rules! from_num {
         {
             fn from(value: $TYPE) -> Self {
                 // radix^|exp|
-                let ratio = R::ratio(E::I32.abs() as u32);
+                let ratio = from_num!(@ratio $KIND, R, $TYPE, E::I32.abs() as u32);
                 // TODO: Add rounding
                 Self::new(if 0 < E::I32 {
                     from_num!(@$KIND, /, $TYPE, Mantissa<R, B>, value, ratio)
@@ -23,8 +23,17 @@ macro_rules! from_num {
         }
     };
 
+    (@ratio int, $RADIX: ty, $TYPE: ty, $exp: expr) => {
+        <$RADIX>::ratio($exp)
+    };
+
+    // the ratio is evaluated using float to avoid overflow of mantissa
+    (@ratio float, $RADIX: ty, $TYPE: ty, $exp: expr) => {
+        <$TYPE as Cast<u64>>::cast(u64::from_positive::<$RADIX>()).unsigned_pow($exp)
+    };
+
     (@float, $OP: tt, $TYPE: ty, $BITS: ty, $value: ident, $ratio: ident) => {
-        <$BITS>::cast($value $OP <$TYPE>::cast($ratio))
+        <$BITS>::cast($value $OP $ratio)
     };
 
     (@int, $OP: tt, $TYPE: ty, $BITS: ty, $value: ident, $ratio: ident) => {
@@ -51,7 +60,7 @@ from_num!(f64, float);
 
 #[cfg(test)]
 mod test {
-    use crate::si::Milli;
+    use crate::{bin, si::Milli};
     use typenum::*;
 
     #[test]
@@ -113,4 +122,19 @@ mod test {
         let a = Milli::<P4>::from(-2.5f64);
         assert_eq!(a, -Milli::new(2_500));
     }
+
+    #[test]
+    fn from_f32_full_fraction() {
+        let a = bin::Fix::<P16, N15>::from(-1.0f32);
+        assert_eq!(a, bin::Fix::new(-0x8000));
+
+        let a = bin::Fix::<P32, N31>::from(0.5f32);
+        assert_eq!(a, bin::Fix::new(0x4000_0000));
+    }
+
+    #[test]
+    fn from_f64_full_fraction() {
+        let a = bin::Fix::<P64, N63>::from(-0.25f64);
+        assert_eq!(a, bin::Fix::new(-0x2000_0000_0000_0000));
+    }
 }
@@ -1,4 +1,4 @@
-use super::{Cast, Digits, Exponent, Fix, Mantissa, Radix};
+use super::{Cast, Digits, Exponent, Fix, FromPositive, Mantissa, Radix, UnsignedPow};
 
 macro_rules! into_num {
     ($TYPE: ty, $KIND: tt) => {
@@ -11,7 +11,7 @@ macro_rules! into_num {
         {
             fn from(Fix { bits: value, .. }: Fix<R, B, E>) -> Self {
                 // radix^|exp|
-                let ratio = R::ratio(E::I32.abs() as u32);
+                let ratio = into_num!(@ratio $KIND, R, $TYPE, E::I32.abs() as u32);
 
                 // TODO: Add rounding
 
@@ -29,7 +29,16 @@ macro_rules! into_num {
     };
 
     (@float, $OP: tt, $TYPE: ty, $value: ident, $ratio: ident) => {
-        <$TYPE>::cast($value) $OP <$TYPE>::cast($ratio)
+        <$TYPE>::cast($value) $OP $ratio
+    };
+
+    (@ratio int, $RADIX: ty, $TYPE: ty, $exp: expr) => {
+        <$RADIX>::ratio($exp)
+    };
+
+    // the ratio is evaluated using float to avoid overflow of mantissa
+    (@ratio float, $RADIX: ty, $TYPE: ty, $exp: expr) => {
+        <$TYPE as Cast<u64>>::cast(u64::from_positive::<$RADIX>()).unsigned_pow($exp)
     };
 }
 
@@ -52,7 +61,7 @@ into_num!(f64, float);
 
 #[cfg(test)]
 mod test {
-    use crate::si::Milli;
+    use crate::{bin, si::Milli};
     use typenum::*;
 
     #[test]
@@ -120,4 +129,19 @@ mod test {
         let a = Milli::<P4>::new(-2_500);
         assert_eq!(f64::from(a), -2.5);
     }
+
+    #[test]
+    fn into_f32_full_fraction() {
+        let a = bin::Fix::<P16, N15>::new(-0x8000);
+        assert_eq!(f32::from(a), -1.0);
+
+        let a = bin::Fix::<P32, N31>::new(0x4000_0000);
+        assert_eq!(f32::from(a), 0.5);
+    }
+
+    #[test]
+    fn into_f64_full_fraction() {
+        let a = bin::Fix::<P64, N63>::new(-0x2000_0000_0000_0000);
+        assert_eq!(f64::from(a), -0.25);
+    }
 }
@@ -41,6 +41,9 @@ The `P*` as the radix type parameter means signed type. To create unsigned types
 
 Unlike well known and widely used __Qn.m__ representation the exponent is not constrained by mantissa bits. It can be less to represent more precisive small values. Also it can be greater than zero to represent less precision bigger values.
 
+The Q-format types can be used through the type aliases in [`q`](mod@q) module or [`q!`] macro.
+The integer bits of Q-format includes the sign bit (ARM convention), so the `q!(1.15)` has 16-bits mantissa.
+
 ```
 # use ufix::Fix;
 # use typenum::*;
@@ -138,6 +141,7 @@ The __word8__ and __word16__ features can reduce minimum mantissa size to 8 and
 #![forbid(missing_docs)]
 
 mod aliases;
+mod approx;
 mod arithmetic;
 mod cast;
 mod cast_fixed;
@@ -148,11 +152,13 @@ mod from_number;
 mod hashing;
 mod into_number;
 mod positive;
+mod qformat;
 mod radix;
 mod types;
 mod unsigned_pow;
 
 pub use aliases::*;
+pub use approx::{approx_eq, lsb_diff};
 pub use cast::Cast;
 pub use fixed::Fix;
 pub use positive::{FromPositive, Positive};
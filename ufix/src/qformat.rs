@@ -0,0 +1,119 @@
+/*!
+
+## Q-format notation
+
+The Q<sub>m.n</sub> notation is widely used to describe the binary fixed-point formats,
+especially in DSP libraries (like CMSIS-DSP) and vendor libraries.
+The _m_ is a number of integer bits and the _n_ is a number of fractional bits.
+
+There are two conventions which differs by counting of sign bit.
+This crate follows the ARM convention where the _m_ includes the sign bit,
+so the `q15_t` is Q<sub>1.15</sub> and the Q<sub>n</sub> is a short form of Q<sub>1.n</sub>.
+
+The Q-format types is a subset of binary fixed-point types,
+where the number of mantissa bits is _m + n_ and exponent is _-n_.
+The [`q!`] macro and the type aliases in [`q`](crate::q) module maps the Q-format onto the [`Fix`] types.
+
+The raw Q-format values can be converted using [`Fix::from_raw`] and [`Fix::to_raw`].
+
+ */
+
+use super::{Cast, Digits, Exponent, Fix, Mantissa, Radix};
+
+impl<R, B, E> Fix<R, B, E>
+where
+    R: Radix<B>,
+    B: Digits,
+    E: Exponent,
+{
+    /// Creates a number from raw integer value (like `q15_t` or `q31_t`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ufix::{Cast, q::Q15};
+    ///
+    /// assert_eq!(Q15::from_raw(0x4000i16), Q15::cast(0.5));
+    /// assert_eq!(Q15::from_raw(-0x8000i16), Q15::cast(-1.0));
+    /// ```
+    pub fn from_raw<I>(raw: I) -> Self
+    where
+        Mantissa<R, B>: Cast<I>,
+    {
+        Self::new(Mantissa::<R, B>::cast(raw))
+    }
+
+    /// Converts to raw integer value (like `q15_t` or `q31_t`).
+    ///
+    /// The mantissa is truncated when the integer type is narrower than it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ufix::{Cast, q::Q31};
+    ///
+    /// assert_eq!(Q31::cast(-0.5).to_raw::<i32>(), -0x4000_0000);
+    /// ```
+    pub fn to_raw<I>(self) -> I
+    where
+        I: Cast<Mantissa<R, B>>,
+    {
+        I::cast(self.bits)
+    }
+}
+
+/// Q-format type by Q<sub>m.n</sub> notation
+///
+/// The _m_ includes the sign bit, so the mantissa has _m + n_ bits.
+/// The notation without integer bits (like `q!(15)`) means Q<sub>1.n</sub>.
+///
+/// # Examples
+///
+/// ```
+/// use ufix::{bin::Fix, q, Cast};
+/// use typenum::{N14, N15, N9, P12, P16};
+///
+/// type Q2_14 = q!(2.14);
+///
+/// assert_eq!(Q2_14::cast(1.5), Fix::<P16, N14>::cast(1.5));
+/// assert_eq!(<q!(1.15)>::cast(0.25), Fix::<P16, N15>::cast(0.25));
+/// assert_eq!(<q!(3.9)>::cast(-2.5), Fix::<P12, N9>::cast(-2.5));
+/// ```
+#[macro_export]
+macro_rules! q {
+    ($format:literal) => {
+        $crate::q::Q<
+            $crate::q::__Int<{ $crate::q::__bits(stringify!($format), 0) }>,
+            $crate::q::__Int<{ $crate::q::__bits(stringify!($format), 1) }>,
+        >
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::q::{Q, Q15, Q31, Q7};
+    use typenum::{N9, P12, P2, P30};
+
+    #[test]
+    fn raw_values() {
+        assert_eq!(Q7::from_raw(-64i8), Q7::cast(-0.5));
+        assert_eq!(Q7::cast(0.25).to_raw::<i8>(), 32);
+
+        assert_eq!(Q15::from_raw(0x2000i16), Q15::cast(0.25));
+        assert_eq!(Q15::cast(-0.25).to_raw::<i16>(), -0x2000);
+
+        assert_eq!(Q31::from_raw(i32::MIN), Q31::cast(-1.0));
+        assert_eq!(Q31::cast(0.75).to_raw::<i32>(), 0x6000_0000);
+    }
+
+    #[test]
+    fn notation() {
+        assert_eq!(<q!(1.15)>::from_raw(0x4000i16), Q15::from_raw(0x4000i16));
+        assert_eq!(<q!(31)>::from_raw(i32::MIN), Q31::cast(-1.0));
+        assert_eq!(<q!(2.30)>::cast(-1.5), Q::<P2, P30>::cast(-1.5));
+        assert_eq!(<q!(8.0)>::cast(-3.0).to_raw::<i8>(), -3);
+        assert_eq!(<q!(16.16)>::from_raw(0x18000i32), <q!(16.16)>::cast(1.5));
+        assert_eq!(<q!(3.9)>::cast(2.5), crate::bin::Fix::<P12, N9>::cast(2.5));
+    }
+}
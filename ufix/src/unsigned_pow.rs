@@ -33,3 +33,39 @@ unsigned_pow!(i64);
 #[cfg(feature = "i128")]
 unsigned_pow!(i128);
 unsigned_pow!(isize);
+
+macro_rules! unsigned_pow_float {
+    ($TYPE: ty) => {
+        impl UnsignedPow for $TYPE {
+            fn unsigned_pow(self, mut exp: u32) -> Self {
+                // exponentiation by squaring (the `powi` isn't available in `core`)
+                let mut base = self;
+                let mut value = 1.0;
+                while exp > 0 {
+                    if exp & 1 != 0 {
+                        value *= base;
+                    }
+                    base *= base;
+                    exp >>= 1;
+                }
+                value
+            }
+        }
+    };
+}
+
+unsigned_pow_float!(f32);
+unsigned_pow_float!(f64);
+
+#[cfg(test)]
+mod test {
+    use super::UnsignedPow;
+
+    #[test]
+    fn float_pow() {
+        assert_eq!(2.0f32.unsigned_pow(0), 1.0);
+        assert_eq!(2.0f32.unsigned_pow(15), 32768.0);
+        assert_eq!(10.0f64.unsigned_pow(3), 1000.0);
+        assert_eq!(0.5f64.unsigned_pow(63), 1.0 / 9223372036854775808.0);
+    }
+}
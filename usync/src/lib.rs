@@ -0,0 +1,27 @@
+/*!
+
+# Lock-free primitives for low-end hardware
+
+This crate implements the primitives for sharing of data between the interrupt handlers
+and the tasks on bare-metal targets, which is used by the control library `uctl`.
+
+The primitives requires `unsafe` code, so it is kept in this small crate to be reviewed separately,
+while the control library itself forbids `unsafe` code at all.
+The reasoning about each `unsafe` block is given by `SAFETY` comment near it.
+
+Only atomic loads and stores is used (no read-modify-write operations and no critical sections),
+so the primitives is suitable even for Cortex-M0 cores.
+
+## Overview
+
+- The [parameters mailbox](mailbox) publishes the parameters from background task to interrupt handler
+- The [block processor](stream) streams the blocks of samples between DMA and processing task
+
+ */
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unsafe_op_in_unsafe_fn)]
+#![forbid(missing_docs)]
+
+pub mod mailbox;
+pub mod stream;
@@ -0,0 +1,218 @@
+/*!
+
+## Parameters mailbox
+
+The parameters of control loop which runs in interrupt handler usually is updated
+from the background task (like command interpreter or tuning procedure).
+The parameters is too big to be written atomically, so the interrupt can see
+the partially updated (torn) parameters.
+
+The [`ParamMailbox`] is a lock-free single-producer single-consumer double-buffered cell,
+which allows to publish the new parameters without disabling of interrupts.
+
+The mailbox has two slots: the active slot which is read by consumer
+and the inactive slot which is written by producer. The swap protocol is following:
+
+1. The producer writes new parameters into inactive slot only when no pending update
+   (otherwise the parameters is given back so the producer can retry later)
+2. The producer sets the pending flag
+3. The consumer checks the pending flag before reading, swaps the slots and clears the flag
+
+The consumer never reads the slot which is written and the producer never writes the slot
+which is read, so the parameters is always consistent.
+Only atomic loads and stores is used (no read-modify-write operations),
+so the mailbox is suitable even for Cortex-M0 cores.
+
+The [`Producer`] and [`Consumer`] handles is obtained by [splitting](ParamMailbox::split)
+of mailbox, so the single producer and single consumer is guaranteed.
+
+*/
+
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+/**
+Double-buffered parameters mailbox
+
+- `P` - parameters type
+*/
+#[derive(Debug)]
+pub struct ParamMailbox<P> {
+    /// The slots of parameters
+    slots: [UnsafeCell<P>; 2],
+    /// The index of active slot (which is read by consumer)
+    active: AtomicU8,
+    /// The inactive slot contains pending update
+    pending: AtomicBool,
+}
+
+// SAFETY: the access to slots is coordinated by swap protocol,
+// so the slot never is written and read simultaneously
+unsafe impl<P: Send> Sync for ParamMailbox<P> {}
+
+impl<P> ParamMailbox<P> {
+    /**
+    Create mailbox
+
+    - `param`: The initial parameters
+     */
+    pub fn new(param: P) -> Self
+    where
+        P: Clone,
+    {
+        Self {
+            slots: [UnsafeCell::new(param.clone()), UnsafeCell::new(param)],
+            active: AtomicU8::new(0),
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Split mailbox into producer and consumer handles
+    pub fn split(&mut self) -> (Producer<'_, P>, Consumer<'_, P>) {
+        let mailbox = &*self;
+        (Producer { mailbox }, Consumer { mailbox })
+    }
+
+    /// The actual parameters (requires exclusive access)
+    pub fn get_mut(&mut self) -> &mut P {
+        let active = *self.active.get_mut() as usize;
+        self.slots[active].get_mut()
+    }
+}
+
+/**
+Producer handle of mailbox
+
+- `P` - parameters type
+*/
+#[derive(Debug)]
+pub struct Producer<'a, P> {
+    mailbox: &'a ParamMailbox<P>,
+}
+
+impl<P> Producer<'_, P> {
+    /// The previous update still isn't taken by consumer
+    pub fn is_pending(&self) -> bool {
+        self.mailbox.pending.load(Ordering::Acquire)
+    }
+
+    /**
+    Publish new parameters
+
+    - `param`: The new parameters
+
+    Returns the parameters back when the previous update still isn't taken by consumer.
+     */
+    pub fn publish(&mut self, param: P) -> Result<(), P> {
+        if self.mailbox.pending.load(Ordering::Acquire) {
+            return Err(param);
+        }
+
+        // the active slot is changed by consumer only while pending
+        let inactive = 1 - self.mailbox.active.load(Ordering::Relaxed) as usize;
+
+        // SAFETY: the consumer doesn't read the inactive slot until pending flag is set
+        unsafe {
+            *self.mailbox.slots[inactive].get() = param;
+        }
+
+        self.mailbox.pending.store(true, Ordering::Release);
+
+        Ok(())
+    }
+}
+
+/**
+Consumer handle of mailbox
+
+- `P` - parameters type
+*/
+#[derive(Debug)]
+pub struct Consumer<'a, P> {
+    mailbox: &'a ParamMailbox<P>,
+}
+
+impl<P> Consumer<'_, P> {
+    /**
+    Access the actual parameters
+
+    - `func`: The function which uses parameters
+
+    The pending update is taken before access.
+     */
+    pub fn read<R>(&mut self, func: impl FnOnce(&P) -> R) -> R {
+        let mut active = self.mailbox.active.load(Ordering::Relaxed);
+
+        if self.mailbox.pending.load(Ordering::Acquire) {
+            active = 1 - active;
+            self.mailbox.active.store(active, Ordering::Relaxed);
+            self.mailbox.pending.store(false, Ordering::Release);
+        }
+
+        // SAFETY: the producer doesn't write the active slot
+        // and the exclusive borrow of consumer prevents swapping while reading
+        func(unsafe { &*self.mailbox.slots[active as usize].get() })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mailbox_swap() {
+        let mut mailbox = ParamMailbox::new((1, 1));
+        let (mut producer, mut consumer) = mailbox.split();
+
+        assert_eq!(consumer.read(|param| *param), (1, 1));
+
+        assert_eq!(producer.publish((2, 2)), Ok(()));
+        assert!(producer.is_pending());
+        // the update is given back until the previous one is taken
+        assert_eq!(producer.publish((3, 3)), Err((3, 3)));
+
+        assert_eq!(consumer.read(|param| *param), (2, 2));
+        assert!(!producer.is_pending());
+        assert_eq!(consumer.read(|param| *param), (2, 2));
+
+        assert_eq!(producer.publish((3, 3)), Ok(()));
+        assert_eq!(consumer.read(|param| *param), (3, 3));
+
+        assert_eq!(*mailbox.get_mut(), (3, 3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mailbox_threads() {
+        extern crate std;
+        use std::thread;
+
+        // the parameters is consistent when both fields is equal
+        let mut mailbox = ParamMailbox::new((0u32, 0u32));
+        let (mut producer, mut consumer) = mailbox.split();
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                let mut value = 1;
+                while value < 1000 {
+                    if producer.publish((value, value)).is_ok() {
+                        value += 1;
+                    } else {
+                        thread::yield_now();
+                    }
+                }
+            });
+
+            let mut last = 0;
+            while last < 999 {
+                let (a, b) = consumer.read(|param| *param);
+                assert_eq!(a, b);
+                assert!(a >= last);
+                last = a;
+                thread::yield_now();
+            }
+        });
+    }
+}
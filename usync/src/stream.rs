@@ -0,0 +1,538 @@
+/*!
+
+## Double-buffered block processing
+
+This module implements the ping-pong buffers for streaming of blocks of samples
+between the peripherals (like ADC or DAC) and the transducers using DMA.
+
+The [`BlockProcessor`] owns two buffers of the same length.
+While the DMA fills (or drains) one buffer, the transducer is applied
+to the values of another one, which is completed by DMA before.
+
+The buffers is aligned to 32 bytes, which is enough for the cache lines of Cortex-M7
+and the burst transfers of most DMA controllers.
+
+The addresses of buffers can be obtained using [`BlockProcessor::dma_buffers`] for setup of
+double-buffer (or circular) mode of DMA controller. Obtaining of pointers is safe,
+but the processor should not be moved while DMA accesses the buffers
+(usually the processor is placed in the static memory).
+
+The processor is [split](BlockProcessor::split) into two handles:
+
+- the [`DmaHandle`] is used in the transfer-complete (or half-transfer) interrupt
+  to swap the roles of buffers by [`DmaHandle::complete`]
+- the [`BlockHandle`] is used in the processing task to access the completed block
+  in place by [`BlockHandle::map_block`] or [`BlockHandle::with_block`]
+
+The software producer (like ADC interrupt which reads the samples by CPU) can fill the buffer
+owned by DMA using [`DmaHandle::with_buffer`].
+
+The handles shares the counters of completed and processed blocks,
+which is accessed using atomic loads and stores only (no read-modify-write operations
+and no critical sections), so the interrupt may preempt the processing at any time.
+
+The aliasing invariant is following: the [`DmaHandle`] never creates the references to buffers
+(it only gives the raw pointers to DMA) and the [`BlockHandle`] accesses the completed buffer only,
+which isn't owned by DMA. When the block isn't processed before the next completion
+of DMA transfer it is counted as overrun. In that case DMA already writes the buffer
+which is processed, so the values may be corrupted.
+
+The [`BlockHandle::map_block`] accesses the values by volatile reads and writes without references,
+so the overrun is detected and reported by [`Overrun`] error after processing.
+The [`BlockHandle::with_block`] gives the reference to the whole block, which is faster,
+but it is `unsafe`, because the write of DMA to the referenced buffer is undefined behavior,
+so the caller should guarantee that the processing completes before the next transfer.
+
+*/
+
+use core::{
+    cell::UnsafeCell,
+    slice,
+    sync::atomic::{AtomicU32, Ordering},
+};
+use generic_array::{ArrayLength, GenericArray};
+
+/// The buffer aligned to 32 bytes
+#[derive(Debug, Clone, Default)]
+#[repr(C, align(32))]
+pub struct Aligned<T>(pub T);
+
+/// The DMA completed the next transfer while block is processed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overrun;
+
+/// The buffer of ping-pong pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Half {
+    /// The first buffer
+    #[default]
+    Ping,
+    /// The second buffer
+    Pong,
+}
+
+impl Half {
+    /// The index of buffer
+    pub fn index(self) -> usize {
+        match self {
+            Half::Ping => 0,
+            Half::Pong => 1,
+        }
+    }
+
+    /// The another buffer of pair
+    pub fn other(self) -> Self {
+        match self {
+            Half::Ping => Half::Pong,
+            Half::Pong => Half::Ping,
+        }
+    }
+
+    /// The buffer which is owned by DMA after given number of completed transfers
+    fn after(count: u32) -> Self {
+        if count & 1 == 0 {
+            Half::Ping
+        } else {
+            Half::Pong
+        }
+    }
+}
+
+/**
+Double-buffered block processor
+
+- `T` - values type
+- `N` - block length
+
+Initially the DMA owns the [`Half::Ping`] buffer.
+*/
+#[derive(Debug, Default)]
+pub struct BlockProcessor<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The ping-pong buffers
+    buffers: [UnsafeCell<Aligned<GenericArray<T, N>>>; 2],
+    /// The number of completed blocks (written by DMA handle only)
+    completed: AtomicU32,
+    /// The number of completed blocks at last processing (written by block handle only)
+    processed: AtomicU32,
+    /// The number of blocks which isn't processed in time (written by DMA handle only)
+    overruns: AtomicU32,
+}
+
+// SAFETY: the access to buffers is coordinated by counters of blocks,
+// so the completed buffer is referenced by single handle only
+unsafe impl<T: Send, N: ArrayLength<T>> Sync for BlockProcessor<T, N> {}
+
+impl<T, N> BlockProcessor<T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The block length
+    pub fn len() -> usize {
+        N::USIZE
+    }
+
+    /**
+    The raw pointers and the length of both buffers (ping and pong)
+
+    The pointers is valid while the processor isn't moved.
+     */
+    pub fn dma_buffers(&mut self) -> ([*mut T; 2], usize) {
+        let [ping, pong] = &mut self.buffers;
+        (
+            [ping.get_mut().0.as_mut_ptr(), pong.get_mut().0.as_mut_ptr()],
+            N::USIZE,
+        )
+    }
+
+    /// Split processor into DMA and block handles
+    pub fn split(&mut self) -> (DmaHandle<'_, T, N>, BlockHandle<'_, T, N>) {
+        let processor = &*self;
+        (DmaHandle { processor }, BlockHandle { processor })
+    }
+
+    /// The number of blocks which isn't processed in time
+    pub fn overruns(&self) -> u32 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    fn is_pending(&self) -> bool {
+        self.completed.load(Ordering::Acquire) != self.processed.load(Ordering::Acquire)
+    }
+}
+
+/**
+DMA handle of block processor
+
+- `T` - values type
+- `N` - block length
+
+The handle is intended to be used in the DMA interrupt.
+*/
+#[derive(Debug)]
+pub struct DmaHandle<'a, T, N>
+where
+    N: ArrayLength<T>,
+{
+    processor: &'a BlockProcessor<T, N>,
+}
+
+impl<T, N> DmaHandle<'_, T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The buffer which is owned by DMA
+    pub fn dma_half(&self) -> Half {
+        Half::after(self.processor.completed.load(Ordering::Relaxed))
+    }
+
+    /**
+    The raw pointer and the length of buffer which is owned by DMA
+
+    The pointer is valid while the processor isn't moved.
+     */
+    pub fn dma_buffer(&self) -> (*mut T, usize) {
+        // the aligned buffer and the generic array has the layout of array of values
+        let buffer = self.processor.buffers[self.dma_half().index()].get() as *mut T;
+        (buffer, N::USIZE)
+    }
+
+    /**
+    Access the values of buffer which is owned by DMA from software
+
+    - `func`: The function which fills values
+
+    Returns `None` after overrun until the last completed block is processed,
+    because the block handle may still reference the buffer which is owned by DMA.
+     */
+    pub fn with_buffer<R>(&mut self, func: impl FnOnce(&mut [T]) -> R) -> Option<R> {
+        let processor = self.processor;
+        let completed = processor.completed.load(Ordering::Relaxed);
+
+        // the block handle references the buffer which is completed before the last one
+        // only when the last one isn't processed yet
+        if completed.wrapping_sub(processor.processed.load(Ordering::Acquire)) > 1 {
+            return None;
+        }
+
+        let (buffer, len) = self.dma_buffer();
+
+        // SAFETY: the block handle references another buffer (see above) and the completion
+        // of transfer is prevented by exclusive borrow of this handle
+        Some(func(unsafe { slice::from_raw_parts_mut(buffer, len) }))
+    }
+
+    /**
+    Complete the transfer of DMA buffer
+
+    The completed buffer becomes available for processing and DMA continues with another one.
+    Returns `false` when the previous block isn't processed (overrun).
+     */
+    pub fn complete(&mut self) -> bool {
+        let processor = self.processor;
+        // the counters is changed by this handle only
+        let completed = processor.completed.load(Ordering::Relaxed);
+        let overrun = completed != processor.processed.load(Ordering::Acquire);
+
+        if overrun {
+            let overruns = processor.overruns.load(Ordering::Relaxed);
+            processor
+                .overruns
+                .store(overruns.saturating_add(1), Ordering::Relaxed);
+        }
+
+        processor
+            .completed
+            .store(completed.wrapping_add(1), Ordering::Release);
+
+        !overrun
+    }
+
+    /// The completed block is waiting for processing
+    pub fn is_pending(&self) -> bool {
+        self.processor.is_pending()
+    }
+
+    /// The number of blocks which isn't processed in time
+    pub fn overruns(&self) -> u32 {
+        self.processor.overruns()
+    }
+}
+
+/**
+Block handle of block processor
+
+- `T` - values type
+- `N` - block length
+
+The handle is intended to be used in the processing task.
+*/
+#[derive(Debug)]
+pub struct BlockHandle<'a, T, N>
+where
+    N: ArrayLength<T>,
+{
+    processor: &'a BlockProcessor<T, N>,
+}
+
+impl<T, N> BlockHandle<'_, T, N>
+where
+    N: ArrayLength<T>,
+{
+    /// The completed block is waiting for processing
+    pub fn is_pending(&self) -> bool {
+        self.processor.is_pending()
+    }
+
+    /// The number of blocks which isn't processed in time
+    pub fn overruns(&self) -> u32 {
+        self.processor.overruns()
+    }
+
+    /**
+    Map the values of completed block in place
+
+    - `func`: The function which maps each value
+
+    Returns `Ok(false)` when there is no completed block and `Ok(true)` when it is processed.
+    The block is marked as processed after access.
+
+    Returns [`Overrun`] error when DMA completes the next transfer while block is processed,
+    because DMA writes the same buffer since that, so the values may be corrupted.
+     */
+    pub fn map_block(&mut self, mut func: impl FnMut(T) -> T) -> Result<bool, Overrun>
+    where
+        T: Copy,
+    {
+        self.access(|buffer| {
+            for index in 0..N::USIZE {
+                // SAFETY: the pointer is in bounds of buffer and the values is accessed
+                // by volatile operations without references, so the writes of DMA after overrun
+                // may corrupt the values only
+                unsafe {
+                    let value = buffer.add(index);
+                    value.write_volatile(func(value.read_volatile()));
+                }
+            }
+        })
+        .map(|result| result.is_some())
+    }
+
+    /**
+    Access the values of completed block
+
+    - `func`: The function which uses values
+
+    Returns `Ok(None)` when there is no completed block.
+    The block is marked as processed after access.
+
+    Returns [`Overrun`] error when DMA completes the next transfer while block is processed
+    (the result of function is dropped in that case).
+
+    # Safety
+
+    The processing should complete before the next completion of DMA transfer
+    (like [`DmaHandle::complete`] call in interrupt), because DMA writes the referenced buffer
+    since that, which is undefined behavior. The overrun is detected after processing,
+    so the error doesn't prevent it. Use [`BlockHandle::map_block`] when it cannot be guaranteed.
+     */
+    pub unsafe fn with_block<R>(
+        &mut self,
+        func: impl FnOnce(&mut [T]) -> R,
+    ) -> Result<Option<R>, Overrun> {
+        self.access(|buffer| {
+            // SAFETY: the DMA handle never references the buffers and DMA owns another buffer
+            // until the next completion (guaranteed by caller),
+            // the exclusive borrow of this handle prevents the second reference
+            func(unsafe { slice::from_raw_parts_mut(buffer, N::USIZE) })
+        })
+    }
+
+    /// Access the last completed buffer by pointer and detect overrun after access
+    fn access<R>(&mut self, func: impl FnOnce(*mut T) -> R) -> Result<Option<R>, Overrun> {
+        let processor = self.processor;
+        let completed = processor.completed.load(Ordering::Acquire);
+
+        if completed == processor.processed.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+
+        // the last completed buffer
+        let half = Half::after(completed).other();
+        let result = func(processor.buffers[half.index()].get() as *mut T);
+
+        processor.processed.store(completed, Ordering::Release);
+
+        // the buffer is owned by DMA after the next completion
+        if processor.completed.load(Ordering::Acquire) != completed {
+            return Err(Overrun);
+        }
+
+        Ok(Some(result))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use generic_array::typenum::U4;
+
+    /// Emulate the transfer of DMA
+    fn dma_fill<N>(dma: &DmaHandle<'_, f32, N>, value: f32)
+    where
+        N: ArrayLength<f32>,
+    {
+        let (buffer, len) = dma.dma_buffer();
+        for index in 0..len {
+            unsafe { buffer.add(index).write(value) };
+        }
+    }
+
+    #[test]
+    fn alignment() {
+        let mut proc = BlockProcessor::<i16, U4>::default();
+        let ([ping, pong], len) = proc.dma_buffers();
+
+        assert_eq!(len, 4);
+        assert_eq!(ping as usize % 32, 0);
+        assert_eq!(pong as usize % 32, 0);
+        assert_ne!(ping, pong);
+
+        let (mut dma, _) = proc.split();
+        assert_eq!(dma.dma_buffer(), (ping, 4));
+        dma.complete();
+        assert_eq!(dma.dma_buffer(), (pong, 4));
+    }
+
+    #[test]
+    fn ping_pong() {
+        let mut proc = BlockProcessor::<f32, U4>::default();
+        let (mut dma, mut block) = proc.split();
+
+        assert_eq!(block.map_block(|value| value), Ok(false));
+
+        // the DMA fills the ping buffer
+        assert_eq!(dma.dma_half(), Half::Ping);
+        dma_fill(&dma, 1.0);
+        assert!(dma.complete());
+        assert_eq!(dma.dma_half(), Half::Pong);
+        assert!(block.is_pending());
+
+        // SAFETY: the DMA is emulated in the same thread
+        assert_eq!(
+            unsafe { block.with_block(|values| values[0]) },
+            Ok(Some(1.0))
+        );
+        assert!(!block.is_pending());
+        dma_fill(&dma, 2.0);
+        assert!(dma.complete());
+        let mut sum = 0.0;
+        let mut sum_values = |value| {
+            sum += value;
+            value
+        };
+        assert_eq!(block.map_block(&mut sum_values), Ok(true));
+        assert_eq!(block.map_block(&mut sum_values), Ok(false));
+        assert_eq!(sum, 8.0);
+
+        // the block isn't processed in time
+        assert!(dma.complete());
+        assert!(!dma.complete());
+        assert_eq!(dma.overruns(), 1);
+        assert_eq!(dma.dma_half(), Half::Ping);
+
+        // the last completed block is still pending
+        assert!(block.is_pending());
+        assert_eq!(block.map_block(|value| value), Ok(true));
+        assert!(!block.is_pending());
+        assert_eq!(proc.overruns(), 1);
+    }
+
+    #[test]
+    fn overrun_while_processing() {
+        let mut proc = BlockProcessor::<f32, U4>::default();
+        let (mut dma, mut block) = proc.split();
+
+        dma_fill(&dma, 1.0);
+        assert!(dma.complete());
+
+        // the DMA completes the next transfer and continues with the processed buffer
+        let mut first = true;
+        let result = block.map_block(|value| {
+            if first {
+                first = false;
+                assert!(!dma.complete());
+                dma_fill(&dma, 2.0);
+            }
+            value
+        });
+        assert_eq!(result, Err(Overrun));
+        assert!(block.is_pending());
+        assert_eq!(proc.overruns(), 1);
+    }
+
+    #[test]
+    fn software_fill() {
+        let mut proc = BlockProcessor::<f32, U4>::default();
+        let (mut dma, mut block) = proc.split();
+
+        assert_eq!(dma.with_buffer(|values| values.fill(1.0)), Some(()));
+        assert!(dma.complete());
+        // the next buffer can be filled while the block is pending
+        assert_eq!(dma.with_buffer(|values| values.fill(2.0)), Some(()));
+        assert_eq!(block.map_block(|value| value + 1.0), Ok(true));
+
+        // the buffer which is owned by DMA isn't accessible after overrun
+        assert!(dma.complete());
+        assert!(!dma.complete());
+        assert_eq!(dma.with_buffer(|_| ()), None);
+
+        // SAFETY: the DMA is emulated in the same thread
+        assert_eq!(
+            unsafe { block.with_block(|values| values == [2.0; 4]) },
+            Ok(Some(true))
+        );
+        assert_eq!(dma.with_buffer(|values| values.fill(3.0)), Some(()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn ping_pong_threads() {
+        extern crate std;
+        use std::thread;
+
+        let mut proc = BlockProcessor::<f32, U4>::default();
+        let (mut dma, mut block) = proc.split();
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                for value in 1..=100 {
+                    // the emulated DMA doesn't overrun
+                    while dma.is_pending() {
+                        thread::yield_now();
+                    }
+                    dma_fill(&dma, value as f32);
+                    assert!(dma.complete());
+                }
+            });
+
+            let mut last = 0.0;
+            while last < 100.0 {
+                // SAFETY: the emulated DMA waits for processing of block
+                if let Ok(Some(values)) =
+                    unsafe { block.with_block(|values| [values[0], values[3]]) }
+                {
+                    assert_eq!(values[0], values[1]);
+                    assert_eq!(values[0], last + 1.0);
+                    last = values[0];
+                } else {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        assert_eq!(proc.overruns(), 0);
+    }
+}